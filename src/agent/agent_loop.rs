@@ -1,8 +1,17 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
 use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
-use crate::tools::{ExecutionResult, LLMBackend, ToolContext};
+use super::checkpoint::CheckpointStore;
+use super::diagnosis::RootCauseAnalyzer;
+use super::policy::CommandPolicy;
+use crate::audit::ToolMetrics;
+use crate::kubectl::EnvironmentType;
+use crate::shell::confirm::{self, CommandRisk};
+use crate::tools::{ExecutionResult, LLMBackend, RiskLevel, ToolContext};
+use crate::ui::confirmation::ConfirmationType;
 
 /// Maximum number of iterations before forcing termination
 const MAX_ITERATIONS: usize = 20;
@@ -10,6 +19,18 @@ const MAX_ITERATIONS: usize = 20;
 /// Maximum total execution time (5 minutes)
 const MAX_EXECUTION_TIME: Duration = Duration::from_secs(300);
 
+/// Number of correlated root-cause candidates shown to the LLM
+const MAX_ROOT_CAUSE_CANDIDATES: usize = 3;
+
+/// Stop reason when the run is cancelled
+const INTERRUPTED: &str = "Interrupted by user";
+
+/// Stop reason when the iteration budget is spent
+const MAX_ITERATIONS_REACHED: &str = "Maximum iterations reached";
+
+/// Stop reason when the user ends input instead of answering a question
+const NO_ANSWER: &str = "Question left unanswered";
+
 /// Type of step in the ReAct loop
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum StepType {
@@ -23,6 +44,14 @@ pub enum StepType {
     Reflection,
     /// Final solution or conclusion
     Solution,
+    /// Solution plan step the user chose not to run
+    Skipped,
+    /// Command stopped by the user before it finished
+    Interrupted,
+    /// Something the agent asked the user
+    Question,
+    /// The user's reply to a question
+    Answer,
 }
 
 /// Single step in the ReAct loop
@@ -49,10 +78,27 @@ pub struct AgentStep {
     /// Educational explanation of the command (for explain mode)
     #[serde(default)]
     pub explanation: Option<String>,
+
+    /// Time spent on this step: the command run for Observation steps,
+    /// LLM calls for the others
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+
+    /// Tokens used by this step's LLM call, when the backend reports them
+    #[serde(default)]
+    pub tokens: Option<u32>,
+}
+
+impl AgentStep {
+    /// Whether this step's duration was spent running a command rather
+    /// than waiting on the LLM
+    pub fn is_execution(&self) -> bool {
+        self.step_type == StepType::Observation
+    }
 }
 
 /// Status of agent execution
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AgentStatus {
     /// Agent is actively working
     Running,
@@ -63,6 +109,9 @@ pub enum AgentStatus {
     /// Agent paused, waiting for user confirmation
     AwaitingConfirmation,
 
+    /// Agent paused until the user answers its question
+    AwaitingInput(String),
+
     /// Agent failed with error
     Failed(String),
 
@@ -71,8 +120,11 @@ pub enum AgentStatus {
 }
 
 /// State of the agent during execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentState {
+    /// Short run ID, used to name its checkpoint
+    pub id: String,
+
     /// Original problem/task description
     pub task: String,
 
@@ -94,13 +146,17 @@ pub struct AgentState {
     /// Iteration count
     pub iteration: usize,
 
-    /// Start time
+    /// Start time; a resumed run starts its time budget again
+    #[serde(skip, default = "Instant::now")]
     pub start_time: Instant,
 }
 
 impl AgentState {
     pub fn new(task: String) -> Self {
+        let mut id = uuid::Uuid::new_v4().simple().to_string();
+        id.truncate(8);
         Self {
+            id,
             task,
             status: AgentStatus::Running,
             history: Vec::new(),
@@ -128,10 +184,20 @@ impl AgentState {
             success,
             timestamp: std::time::SystemTime::now(),
             explanation: None,
+            duration_ms: None,
+            tokens: None,
         };
         self.history.push(step);
     }
 
+    /// Record how long the last step took and the tokens it used
+    pub fn set_last_step_telemetry(&mut self, duration: Duration, tokens: Option<u32>) {
+        if let Some(last_step) = self.history.last_mut() {
+            last_step.duration_ms = Some(duration.as_millis() as u64);
+            last_step.tokens = tokens;
+        }
+    }
+
     /// Set explanation on the last step (for explain mode)
     pub fn set_last_step_explanation(&mut self, explanation: String) {
         if let Some(last_step) = self.history.last_mut() {
@@ -159,6 +225,38 @@ impl AgentState {
         }
     }
 
+    /// Whether a stopped run can be picked up again: it was interrupted,
+    /// ran out of time or never finished, rather than completing, failing
+    /// or using up its iterations
+    pub fn is_resumable(&self) -> bool {
+        match &self.status {
+            AgentStatus::Running
+            | AgentStatus::AwaitingConfirmation
+            | AgentStatus::AwaitingInput(_) => true,
+            AgentStatus::Stopped(reason) => reason != MAX_ITERATIONS_REACHED,
+            AgentStatus::Completed | AgentStatus::Failed(_) => false,
+        }
+    }
+
+    /// The question the run is waiting on, if any
+    pub fn pending_question(&self) -> Option<&str> {
+        match &self.status {
+            AgentStatus::AwaitingInput(question) => Some(question),
+            _ => None,
+        }
+    }
+
+    /// Questions asked so far with the user's answers, oldest first
+    pub fn answered_questions(&self) -> Vec<(&str, &str)> {
+        self.history
+            .windows(2)
+            .filter(|pair| {
+                pair[0].step_type == StepType::Question && pair[1].step_type == StepType::Answer
+            })
+            .map(|pair| (pair[0].content.as_str(), pair[1].content.as_str()))
+            .collect()
+    }
+
     /// Get last N steps of specific type
     pub fn get_recent_steps(&self, step_type: StepType, count: usize) -> Vec<&AgentStep> {
         self.history
@@ -179,13 +277,94 @@ impl AgentState {
             .filter(|s| s.step_type == StepType::Action)
             .count();
 
-        format!(
+        let timed = |execution: bool| {
+            let steps: Vec<&AgentStep> = self
+                .history
+                .iter()
+                .filter(|s| s.duration_ms.is_some() && s.is_execution() == execution)
+                .collect();
+            let total_ms: u64 = steps.iter().filter_map(|s| s.duration_ms).sum();
+            (steps.len(), Duration::from_millis(total_ms))
+        };
+        let (llm_calls, llm_time) = timed(false);
+        let (commands, execution_time) = timed(true);
+        let tokens: u32 = self.history.iter().filter_map(|s| s.tokens).sum();
+
+        let mut summary = format!(
             "Task: {}\nStatus: {:?}\nSteps: {} (Actions: {})\nDuration: {:?}",
             self.task, self.status, steps_count, actions_count, duration
-        )
+        );
+        if llm_calls > 0 {
+            summary.push_str(&format!(
+                "\nLLM time: {:.1}s over {llm_calls} calls",
+                llm_time.as_secs_f64()
+            ));
+            if tokens > 0 {
+                summary.push_str(&format!(" ({tokens} tokens)"));
+            }
+        }
+        if commands > 0 {
+            summary.push_str(&format!(
+                "\nExecution time: {:.1}s over {commands} commands",
+                execution_time.as_secs_f64()
+            ));
+        }
+        summary
+    }
+}
+
+/// A solution plan step awaiting the user's decision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanStep {
+    /// Position in the plan (1-indexed)
+    pub number: usize,
+    /// Number of steps in the plan
+    pub total: usize,
+    /// Command to run
+    pub command: String,
+    /// Tool that will run it ("shell" when no tool owns the command)
+    pub tool: &'static str,
+    /// Risk of the command
+    pub risk: RiskLevel,
+    /// Environment of the current kubectl context
+    pub environment: EnvironmentType,
+}
+
+impl PlanStep {
+    /// The step's risk as the shell's confirmation flow sees it
+    pub fn command_risk(&self) -> CommandRisk {
+        CommandRisk {
+            tool: self.tool,
+            level: self.risk,
+            environment: self.environment,
+        }
     }
 }
 
+/// What to do with a plan step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanDecision {
+    /// Run the command as proposed
+    Approve,
+    /// Don't run it, move on to the next step
+    Skip,
+    /// Run this command instead
+    Edit(String),
+    /// Skip this and all remaining steps
+    Abort,
+}
+
+/// Result of running a solution plan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanOutcome {
+    /// Steps that ran and succeeded
+    pub succeeded: usize,
+    /// Steps that ran and failed
+    pub failed: usize,
+    /// Steps the user skipped
+    pub skipped: usize,
+}
+
 /// Main ReAct agent loop
 pub struct AgentLoop {
     /// Current state
@@ -194,26 +373,72 @@ pub struct AgentLoop {
     /// Tool registry for executing commands
     tool_registry: crate::tools::ToolRegistry,
 
+    /// Environment used to classify solution plan risk
+    context: ToolContext,
+
+    /// Correlates collected observations into candidate root causes
+    analyzer: RootCauseAnalyzer,
+
     /// Callback for displaying progress (optional)
     #[expect(clippy::type_complexity)]
     progress_callback: Option<Box<dyn Fn(&AgentStep) + Send>>,
 
     /// Enable explain mode for educational command breakdowns
     explain_mode: bool,
+
+    /// Cancelled to stop the running command (Ctrl+C)
+    cancel: CancellationToken,
+
+    /// Tools and commands the agent may run
+    policy: CommandPolicy,
+
+    /// Where the state is saved after every step, if anywhere
+    checkpoints: Option<CheckpointStore>,
+
+    /// Where tool outcomes and latencies are recorded, if anywhere
+    metrics: Option<ToolMetrics>,
+}
+
+/// What happened to an action handed to `execute_action`
+enum ActionOutcome {
+    /// The command ran to completion
+    Ran(ExecutionResult),
+    /// The user interrupted it
+    Interrupted,
+    /// The policy didn't allow it; holds the reason
+    Blocked(String),
 }
 
 impl AgentLoop {
     /// Create new agent loop for a task
     pub fn new(task: String, context: ToolContext) -> Self {
-        let _ = context; // Reserved for future use - suppress unused warning
         Self {
             state: AgentState::new(task),
             tool_registry: crate::tools::ToolRegistry::new(),
+            context,
+            analyzer: RootCauseAnalyzer::new(),
             progress_callback: None,
             explain_mode: true, // Default ON for learning
+            cancel: CancellationToken::new(),
+            policy: CommandPolicy::default(),
+            checkpoints: None,
+            metrics: None,
         }
     }
 
+    /// Continue a run from a checkpointed state
+    ///
+    /// A run waiting on a question keeps waiting, so the question is asked
+    /// again before it continues.
+    pub fn resume(mut state: AgentState, context: ToolContext) -> Self {
+        if state.pending_question().is_none() {
+            state.status = AgentStatus::Running;
+        }
+        let mut agent = Self::new(state.task.clone(), context);
+        agent.state = state;
+        agent
+    }
+
     /// Enable or disable explain mode
     pub fn with_explain_mode(mut self, enabled: bool) -> Self {
         self.explain_mode = enabled;
@@ -229,6 +454,34 @@ impl AgentLoop {
         self
     }
 
+    /// Save the state to `store` after every step
+    ///
+    /// The checkpoint is removed once the run can't be resumed.
+    pub fn with_checkpoints(mut self, store: CheckpointStore) -> Self {
+        self.checkpoints = Some(store);
+        self
+    }
+
+    /// Record each tool invocation's outcome and latency
+    pub fn with_metrics(mut self, metrics: ToolMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Restrict which tools and commands the agent may run
+    pub fn with_policy(mut self, policy: CommandPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Token that interrupts the agent when cancelled
+    ///
+    /// Cancelling stops the command being executed and ends the run; the
+    /// step is recorded as `Interrupted`.
+    pub fn cancel_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
     /// Get current state
     pub fn state(&self) -> &AgentState {
         &self.state
@@ -242,32 +495,68 @@ impl AgentLoop {
     /// Execute one iteration of the ReAct loop
     /// Returns true if should continue, false if done
     pub async fn step(&mut self, llm: &dyn LLMBackend) -> Result<bool> {
-        // Check if should continue
+        // Check if should continue; a finished or paused run keeps its status
         if !self.state.should_continue() {
+            if self.state.status != AgentStatus::Running {
+                return Ok(false);
+            }
             if self.state.iteration >= MAX_ITERATIONS {
-                self.state.status = AgentStatus::Stopped("Maximum iterations reached".to_string());
+                self.state.status = AgentStatus::Stopped(MAX_ITERATIONS_REACHED.to_string());
             } else if self.state.start_time.elapsed() >= MAX_EXECUTION_TIME {
                 self.state.status =
                     AgentStatus::Stopped("Maximum execution time exceeded".to_string());
             }
             return Ok(false);
         }
+        if self.cancel.is_cancelled() {
+            self.state.status = AgentStatus::Stopped(INTERRUPTED.to_string());
+            return Ok(false);
+        }
 
         self.state.iteration += 1;
 
         // ReAct cycle:
         // 1. Thought - AI decides what to do next
-        let thought = self.generate_thought(llm).await?;
-        self.add_and_notify_step(StepType::Thought, thought.clone(), None, None);
+        let LlmReply {
+            text: thought,
+            duration,
+            tokens,
+        } = self.generate_thought(llm).await?;
+        self.state
+            .add_step(StepType::Thought, thought.clone(), None, None);
+        self.state.set_last_step_telemetry(duration, tokens);
+        self.notify_last_step();
 
         // 2. Check if AI thinks task is complete
         if self.is_completion_thought(&thought) {
+            let plan = parse_solution_plan(&thought);
+            if !plan.is_empty() {
+                self.state.solution_plan = Some(plan);
+            }
+            if self.state.root_cause.is_none() {
+                self.state.root_cause = self
+                    .analyzer
+                    .correlate(&self.state.collected_info)
+                    .first()
+                    .map(|cause| cause.description.clone());
+            }
             self.state.status = AgentStatus::Completed;
             return Ok(false);
         }
 
-        // 3. Action - Extract and validate action
-        let action = self.parse_action_from_thought(&thought)?;
+        // 3. Action - Extract and validate action, unless the AI needs
+        // something from the user first
+        let action = match parse_action(&thought) {
+            Some(action) => action,
+            None => match parse_question(&thought) {
+                Some(question) => {
+                    self.state.status = AgentStatus::AwaitingInput(question.clone());
+                    self.add_and_notify_step(StepType::Question, question, None, None);
+                    return Ok(false);
+                }
+                None => self.repair_action(llm, &thought).await?,
+            },
+        };
         self.add_and_notify_step(
             StepType::Action,
             action.command.clone(),
@@ -277,31 +566,41 @@ impl AgentLoop {
 
         // 3.5. Generate educational explanation if explain mode is enabled
         if self.explain_mode {
+            let start = Instant::now();
             if let Ok(explanation) =
                 crate::ai::CommandExplainer::explain(&action.command, &action.tool_name, llm).await
             {
                 self.state.set_last_step_explanation(explanation);
+                self.state.set_last_step_telemetry(start.elapsed(), None);
                 // Re-notify with updated explanation
-                if let Some(ref callback) = self.progress_callback {
-                    if let Some(last_step) = self.state.history.last() {
-                        callback(last_step);
-                    }
-                }
+                self.notify_last_step();
             }
         }
 
         // 4. Execute action (auto-execute if diagnostic, else may need confirmation)
-        let execution_result = self.execute_action(&action).await?;
-
-        // 5. Observation - Record result
-        let observation = self.format_observation(&execution_result);
-        let success = execution_result.exit_code == 0;
-        self.add_and_notify_step(
-            StepType::Observation,
-            observation.clone(),
-            None,
-            Some(success),
-        );
+        let observation = match self.execute_action(&action).await? {
+            ActionOutcome::Ran(execution_result) => {
+                // 5. Observation - Record result
+                let observation = self.format_observation(&execution_result);
+                let success = execution_result.exit_code == 0;
+                self.state.add_step(
+                    StepType::Observation,
+                    observation.clone(),
+                    None,
+                    Some(success),
+                );
+                self.state
+                    .set_last_step_telemetry(execution_result.duration, None);
+                self.notify_last_step();
+                observation
+            }
+            ActionOutcome::Blocked(reason) => self.record_blocked(&reason),
+            ActionOutcome::Interrupted => {
+                self.record_interrupted(&action);
+                self.state.status = AgentStatus::Stopped(INTERRUPTED.to_string());
+                return Ok(false);
+            }
+        };
 
         // Store collected info
         self.state
@@ -310,7 +609,11 @@ impl AgentLoop {
 
         // 6. Reflection - AI analyzes if making progress
         let reflection = self.generate_reflection(llm).await?;
-        self.add_and_notify_step(StepType::Reflection, reflection.clone(), None, None);
+        self.state
+            .add_step(StepType::Reflection, reflection.text, None, None);
+        self.state
+            .set_last_step_telemetry(reflection.duration, reflection.tokens);
+        self.notify_last_step();
 
         // Continue loop
         Ok(true)
@@ -322,21 +625,181 @@ impl AgentLoop {
             // Continue until step returns false
         }
 
+        // A failed step returns early, leaving the last checkpoint to resume
+        if let Some(store) = &self.checkpoints {
+            let kept = if self.state.is_resumable() {
+                store.save(&self.state)
+            } else {
+                store.remove(&self.state.id)
+            };
+            if let Err(e) = kept {
+                log::warn!("Failed to update agent checkpoint: {e:#}");
+            }
+        }
+
         Ok(self.state.clone())
     }
 
+    /// Answer the pending question and let the run continue
+    ///
+    /// An empty answer tells the agent to find out another way. Does
+    /// nothing when no question is pending.
+    pub fn answer(&mut self, answer: &str) {
+        let Some(question) = self.state.pending_question().map(str::to_string) else {
+            return;
+        };
+        let answer = match answer.trim() {
+            "" => "(no answer, find out another way)",
+            answer => answer,
+        };
+
+        self.state.status = AgentStatus::Running;
+        self.state
+            .collected_info
+            .push((format!("Asked the user: {question}"), answer.to_string()));
+        self.add_and_notify_step(StepType::Answer, answer.to_string(), None, None);
+    }
+
+    /// Ask the pending question on stdin and answer it
+    ///
+    /// Returns false, stopping the run, if input ends instead.
+    pub fn answer_from_stdin(&mut self) -> bool {
+        let Some(question) = self.state.pending_question().map(str::to_string) else {
+            return true;
+        };
+        let stdin = io::stdin();
+        match prompt_question(&question, &mut stdin.lock()) {
+            Some(answer) => {
+                self.answer(&answer);
+                true
+            }
+            None => {
+                self.state.status = AgentStatus::Stopped(NO_ANSWER.to_string());
+                false
+            }
+        }
+    }
+
+    /// Walk the solution plan, asking on stdin before each step
+    ///
+    /// Each command's risk is shown and the user can approve, skip, edit or
+    /// abort. Commands that need typed confirmation in the shell need it
+    /// here too. Skipped steps are recorded in history as `Skipped`.
+    pub async fn execute_plan_interactive(&mut self) -> Result<PlanOutcome> {
+        self.execute_plan_with(|step| {
+            let stdin = io::stdin();
+            prompt_plan_step(step, &mut stdin.lock())
+        })
+        .await
+    }
+
+    /// Walk the solution plan, asking `decide` what to do with each step
+    ///
+    /// An edited command is assessed and put to `decide` again before it
+    /// runs.
+    pub async fn execute_plan_with<F>(&mut self, mut decide: F) -> Result<PlanOutcome>
+    where
+        F: FnMut(&PlanStep) -> PlanDecision,
+    {
+        let plan = self.state.solution_plan.clone().unwrap_or_default();
+        let total = plan.len();
+        let mut outcome = PlanOutcome::default();
+        let mut aborted = false;
+
+        for (i, command) in plan.into_iter().enumerate() {
+            let mut step = self.plan_step(i + 1, total, command);
+            let mut decision = if aborted {
+                PlanDecision::Skip
+            } else {
+                decide(&step)
+            };
+            while let PlanDecision::Edit(edited) = decision {
+                step = self.plan_step(step.number, total, edited);
+                decision = decide(&step);
+            }
+
+            if decision != PlanDecision::Approve {
+                aborted |= decision == PlanDecision::Abort;
+                self.add_and_notify_step(
+                    StepType::Skipped,
+                    step.command,
+                    Some(step.tool.to_string()),
+                    None,
+                );
+                outcome.skipped += 1;
+                continue;
+            }
+
+            let action = ActionCommand {
+                tool_name: step.tool.to_string(),
+                command: step.command,
+            };
+            self.add_and_notify_step(
+                StepType::Action,
+                action.command.clone(),
+                Some(action.tool_name.clone()),
+                None,
+            );
+
+            let result = match self.execute_action(&action).await? {
+                ActionOutcome::Ran(result) => result,
+                ActionOutcome::Blocked(reason) => {
+                    self.record_blocked(&reason);
+                    outcome.failed += 1;
+                    continue;
+                }
+                ActionOutcome::Interrupted => {
+                    self.record_interrupted(&action);
+                    outcome.failed += 1;
+                    aborted = true;
+                    continue;
+                }
+            };
+            let success = result.exit_code == 0;
+            if success {
+                outcome.succeeded += 1;
+            } else {
+                outcome.failed += 1;
+            }
+            let observation = self.format_observation(&result);
+            self.state
+                .add_step(StepType::Observation, observation, None, Some(success));
+            self.state.set_last_step_telemetry(result.duration, None);
+            self.notify_last_step();
+        }
+
+        Ok(outcome)
+    }
+
+    /// Describe a plan command with the tool that will run it and its risk
+    ///
+    /// Classified the way `execute_action` routes the command, so the user
+    /// confirms what actually runs.
+    fn plan_step(&self, number: usize, total: usize, command: String) -> PlanStep {
+        let (tool, risk) = self.tool_registry.classify(&command, &self.context);
+        PlanStep {
+            number,
+            total,
+            tool,
+            risk,
+            environment: self
+                .context
+                .kubectl_context
+                .as_ref()
+                .map(|ctx| ctx.environment_type)
+                .unwrap_or(EnvironmentType::Unknown),
+            command,
+        }
+    }
+
     /// Generate thought using LLM
-    async fn generate_thought(&self, llm: &dyn LLMBackend) -> Result<String> {
-        let prompt = self.build_thought_prompt();
-        let response = llm.infer(&prompt).await?;
-        Ok(response.reasoning)
+    async fn generate_thought(&self, llm: &dyn LLMBackend) -> Result<LlmReply> {
+        LlmReply::infer(llm, &self.build_thought_prompt()).await
     }
 
     /// Generate reflection using LLM
-    async fn generate_reflection(&self, llm: &dyn LLMBackend) -> Result<String> {
-        let prompt = self.build_reflection_prompt();
-        let response = llm.infer(&prompt).await?;
-        Ok(response.reasoning)
+    async fn generate_reflection(&self, llm: &dyn LLMBackend) -> Result<LlmReply> {
+        LlmReply::infer(llm, &self.build_reflection_prompt()).await
     }
 
     /// Build prompt for thought generation
@@ -364,6 +827,32 @@ impl AgentLoop {
             prompt.push('\n');
         }
 
+        // Answers stay in the prompt even once their steps scroll out
+        let answers = self.state.answered_questions();
+        if !answers.is_empty() {
+            prompt.push_str("The user told you:\n");
+            for (question, answer) in answers {
+                prompt.push_str(&format!("- Q: {question}\n  A: {answer}\n"));
+            }
+            prompt.push('\n');
+        }
+
+        // Ground the final answer in evidence gathered across all observations
+        let candidates = self.analyzer.correlate(&self.state.collected_info);
+        if !candidates.is_empty() {
+            prompt.push_str("Candidate root causes from the evidence so far:\n");
+            for cause in candidates.iter().take(MAX_ROOT_CAUSE_CANDIDATES) {
+                prompt.push_str(&format!(
+                    "- {} (confidence {}%)\n",
+                    cause.description, cause.confidence
+                ));
+                for evidence in &cause.evidence {
+                    prompt.push_str(&format!("    evidence: {evidence}\n"));
+                }
+            }
+            prompt.push_str("If you propose a SOLUTION, base it on this evidence and cite it.\n\n");
+        }
+
         prompt.push_str(
             "Think about what to do next:\n\
             \n\
@@ -373,6 +862,11 @@ impl AgentLoop {
             Example: ACTION: network netstat -tuln\n\
             Example: ACTION: apache2 apache2ctl -S\n\
             \n\
+            If you need something only the user knows and no command can find out,\n\
+            ask one short question instead:\n\
+            ASK: [question]\n\
+            Example: ASK: Which namespace is the app deployed in?\n\
+            \n\
             When you've identified the root cause, respond with:\n\
             SOLUTION: [explanation and fix]\n\
             \n\
@@ -411,68 +905,106 @@ impl AgentLoop {
             || thought.to_lowercase().contains("problem solved")
     }
 
-    /// Parse action from thought
-    fn parse_action_from_thought(&self, thought: &str) -> Result<ActionCommand> {
-        // Look for ACTION: [tool] [command] pattern
-        if let Some(action_line) = thought
-            .lines()
-            .find(|l| l.trim().to_lowercase().starts_with("action:"))
-        {
-            let action_content = action_line.trim()[7..].trim(); // Remove "ACTION:"
-
-            // Parse tool and command
-            let parts: Vec<&str> = action_content.splitn(2, ' ').collect();
-            if parts.len() == 2 {
-                return Ok(ActionCommand {
-                    tool_name: parts[0].to_string(),
-                    command: parts[1].to_string(),
-                });
+    /// Ask the model once more when its thought had no usable ACTION line
+    ///
+    /// Only if the corrected reply can't be parsed either is the original
+    /// thought run as a shell command.
+    async fn repair_action(
+        &mut self,
+        llm: &dyn LLMBackend,
+        thought: &str,
+    ) -> Result<ActionCommand> {
+        let reply = LlmReply::infer(llm, &build_repair_prompt(thought)).await?;
+        let repaired = parse_action(&reply.text);
+
+        let note = match &repaired {
+            Some(action) => format!(
+                "Reparsed: reply had no ACTION line, corrected to ACTION: {} {}",
+                action.tool_name, action.command
+            ),
+            None => {
+                "Reparse failed: reply still had no ACTION line, running thought as shell command"
+                    .to_string()
             }
-        }
+        };
+        self.state.add_step(StepType::Thought, note, None, None);
+        self.state
+            .set_last_step_telemetry(reply.duration, reply.tokens);
+        self.notify_last_step();
 
-        // Fallback: treat whole thought as command
-        Ok(ActionCommand {
+        Ok(repaired.unwrap_or_else(|| ActionCommand {
             tool_name: "shell".to_string(),
             command: thought.to_string(),
-        })
+        }))
     }
 
-    /// Execute action using proper tool
-    async fn execute_action(&self, action: &ActionCommand) -> Result<ExecutionResult> {
-        let start = std::time::Instant::now();
-
-        // Get tool from registry
-        if let Some(tool) = self.tool_registry.get_tool(&action.tool_name) {
+    /// Execute action using proper tool, unless the policy blocks it
+    async fn execute_action(&self, action: &ActionCommand) -> Result<ActionOutcome> {
+        // The model's tool name is only a hint; what runs decides the tool
+        let tool = self.tool_registry.tool_for_command(&action.command);
+        let tool_name = tool.map_or("shell", |tool| tool.name());
+        if tool_name != action.tool_name {
             log::info!(
-                "Using tool '{}' to execute: {}",
+                "Action declared tool '{}' but '{}' runs as {tool_name}",
                 action.tool_name,
                 action.command
             );
-            let result = tool.execute(&action.command).await?;
-            Ok(result)
+        }
+        if let Some(reason) = self.policy.check(tool_name, &action.command) {
+            log::warn!("Agent policy blocked '{}': {reason}", action.command);
+            return Ok(ActionOutcome::Blocked(reason));
+        }
+
+        if let Some(tool) = tool {
+            log::info!("Using tool '{tool_name}' to execute: {}", action.command);
+            let started = Instant::now();
+            let result = tool
+                .execute_cancellable(&action.command, &self.cancel)
+                .await;
+            if let Some(metrics) = &self.metrics {
+                match &result {
+                    // An interruption says nothing about the tool
+                    Ok(None) => {}
+                    Ok(Some(r)) => metrics.record(tool.name(), r.exit_code == 0, started.elapsed()),
+                    Err(_) => metrics.record(tool.name(), false, started.elapsed()),
+                }
+            }
+            Ok(result?.map_or(ActionOutcome::Interrupted, ActionOutcome::Ran))
         } else {
-            // Fallback to shell execution for unknown tools
-            log::warn!(
-                "Tool '{}' not found, falling back to shell",
-                action.tool_name
-            );
-            use tokio::process::Command;
-
-            let output = Command::new("sh")
-                .arg("-c")
-                .arg(&action.command)
-                .output()
-                .await?;
-
-            Ok(ExecutionResult {
-                exit_code: output.status.code().unwrap_or(-1),
-                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-                duration: start.elapsed(),
-            })
+            // No tool owns the command; run it through the shell
+            log::info!("Running '{}' through the shell", action.command);
+            let mut shell = tokio::process::Command::new("sh");
+            shell.arg("-c").arg(&action.command);
+            tokio::select! {
+                biased;
+                () = self.cancel.cancelled() => Ok(ActionOutcome::Interrupted),
+                result = ExecutionResult::capture(&mut shell) => Ok(ActionOutcome::Ran(result?)),
+            }
         }
     }
 
+    /// Record a policy block as a failed observation; returns its text
+    fn record_blocked(&mut self, reason: &str) -> String {
+        let observation = format!("Blocked by agent policy, not run: {reason}");
+        self.add_and_notify_step(
+            StepType::Observation,
+            observation.clone(),
+            None,
+            Some(false),
+        );
+        observation
+    }
+
+    /// Record that the user stopped `action` before it finished
+    fn record_interrupted(&mut self, action: &ActionCommand) {
+        self.add_and_notify_step(
+            StepType::Interrupted,
+            action.command.clone(),
+            Some(action.tool_name.clone()),
+            Some(false),
+        );
+    }
+
     /// Format execution result as observation
     fn format_observation(&self, result: &ExecutionResult) -> String {
         if result.exit_code == 0 {
@@ -503,12 +1035,86 @@ impl AgentLoop {
         success: Option<bool>,
     ) {
         self.state.add_step(step_type, content, tool_used, success);
+        self.notify_last_step();
+    }
 
+    /// Pass the latest step to the progress callback and checkpoint it
+    fn notify_last_step(&self) {
         if let Some(ref callback) = self.progress_callback {
             if let Some(last_step) = self.state.history.last() {
                 callback(last_step);
             }
         }
+        if let Some(store) = &self.checkpoints {
+            if let Err(e) = store.save(&self.state) {
+                log::warn!("Failed to checkpoint agent run {}: {e:#}", self.state.id);
+            }
+        }
+    }
+}
+
+/// Parse `ACTION: [tool] [command]` out of a thought
+fn parse_action(thought: &str) -> Option<ActionCommand> {
+    let action_line = thought
+        .lines()
+        .find(|l| l.trim().to_lowercase().starts_with("action:"))?;
+    let action_content = action_line.trim()[7..].trim(); // Remove "ACTION:"
+
+    // Parse tool and command
+    let (tool_name, command) = action_content.split_once(' ')?;
+    let command = command.trim();
+    if command.is_empty() {
+        return None;
+    }
+    Some(ActionCommand {
+        tool_name: tool_name.to_string(),
+        command: command.to_string(),
+    })
+}
+
+/// Parse `ASK: [question]` out of a thought
+fn parse_question(thought: &str) -> Option<String> {
+    thought.lines().find_map(|line| {
+        let line = line.trim();
+        line.get(..4)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("ask:"))
+            .map(|_| line[4..].trim().to_string())
+            .filter(|question| !question.is_empty())
+    })
+}
+
+/// Correction prompt for a thought that didn't follow the ACTION format
+fn build_repair_prompt(thought: &str) -> String {
+    format!(
+        "Your previous reply could not be parsed:\n\
+        ---\n{}\n---\n\n\
+        Respond ONLY with a single line in this format, nothing else:\n\
+        ACTION: [tool_name] [command]\n\
+        Example: ACTION: network netstat -tuln",
+        thought.chars().take(500).collect::<String>()
+    )
+}
+
+/// LLM output with what it cost
+struct LlmReply {
+    text: String,
+    duration: Duration,
+    tokens: Option<u32>,
+}
+
+impl LlmReply {
+    async fn infer(llm: &dyn LLMBackend, prompt: &str) -> Result<Self> {
+        let start = Instant::now();
+        let response = llm.infer(prompt).await?;
+        let tokens = match (response.prompt_tokens, response.completion_tokens) {
+            (None, None) => None,
+            (prompt, completion) => Some(prompt.unwrap_or(0) + completion.unwrap_or(0)),
+        };
+        Ok(Self {
+            text: response.reasoning,
+            duration: start.elapsed(),
+            tokens,
+        })
     }
 }
 
@@ -519,6 +1125,101 @@ struct ActionCommand {
     command: String,
 }
 
+/// Extract the fix commands from a `SOLUTION:` thought
+///
+/// Commands are taken from `backticks` or `$ `-prefixed lines after the
+/// SOLUTION marker, in order.
+fn parse_solution_plan(thought: &str) -> Vec<String> {
+    let Some(start) = thought.to_lowercase().find("solution:") else {
+        return Vec::new();
+    };
+
+    let mut plan = Vec::new();
+    for line in thought[start + "solution:".len()..].lines() {
+        let line = line.trim();
+        if let Some(command) = line.strip_prefix("$ ") {
+            plan.push(command.trim().to_string());
+            continue;
+        }
+
+        // Odd-numbered segments of a backtick split are inside backticks
+        plan.extend(
+            line.split('`')
+                .skip(1)
+                .step_by(2)
+                .map(str::trim)
+                .filter(|command| !command.is_empty())
+                .map(str::to_string),
+        );
+    }
+
+    plan.dedup();
+    plan
+}
+
+/// Show a plan step and read the user's decision
+///
+/// Approving a step whose risk needs typed confirmation asks for it the way
+/// the shell does; a wrong answer skips the step.
+fn prompt_plan_step(step: &PlanStep, input: &mut impl BufRead) -> PlanDecision {
+    println!(
+        "\n\x1b[38;5;147m◆ step {}/{}\x1b[0m [{}] {} risk",
+        step.number, step.total, step.tool, step.risk
+    );
+    println!("  \x1b[1m{}\x1b[0m", step.command);
+    print!("  run? [y]es / [s]kip / [e]dit / [q]uit: ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer).unwrap_or(0) == 0 {
+        return PlanDecision::Abort;
+    }
+
+    match answer.trim().to_lowercase().as_str() {
+        "y" | "yes" => {
+            let risk = step.command_risk();
+            let confirmation = if risk.level.requires_typed_confirmation(risk.is_production()) {
+                ConfirmationType::Typed
+            } else {
+                ConfirmationType::None
+            };
+            if confirm::prompt_with(&step.command, &risk, confirmation, input) {
+                PlanDecision::Approve
+            } else {
+                PlanDecision::Skip
+            }
+        }
+        "e" | "edit" => {
+            print!("  command: ");
+            let _ = io::stdout().flush();
+            let mut edited = String::new();
+            match input.read_line(&mut edited) {
+                Ok(n) if n > 0 && !edited.trim().is_empty() => {
+                    PlanDecision::Edit(edited.trim().to_string())
+                }
+                _ => PlanDecision::Skip,
+            }
+        }
+        "q" | "quit" => PlanDecision::Abort,
+        _ => PlanDecision::Skip,
+    }
+}
+
+/// Show the agent's question and read the user's answer
+///
+/// None when input ends (Ctrl+D).
+fn prompt_question(question: &str, input: &mut impl BufRead) -> Option<String> {
+    println!("\n\x1b[38;5;147m? {question}\x1b[0m");
+    print!("  answer \x1b[2m(Enter to skip, Ctrl+D to stop)\x1b[0m: ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    match input.read_line(&mut answer) {
+        Ok(n) if n > 0 => Some(answer.trim().to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -541,6 +1242,25 @@ mod tests {
         assert_eq!(state.history[0].content, "Thinking...");
     }
 
+    #[test]
+    fn test_summary_splits_llm_and_execution_time() {
+        let mut state = AgentState::new("Site returns 502".to_string());
+        state.add_step(StepType::Thought, "check nginx".to_string(), None, None);
+        state.set_last_step_telemetry(Duration::from_millis(4200), Some(900));
+        state.add_step(StepType::Action, "nginx -t".to_string(), None, None);
+        state.add_step(StepType::Observation, "ok".to_string(), None, Some(true));
+        state.set_last_step_telemetry(Duration::from_millis(300), None);
+        state.add_step(StepType::Reflection, "fine".to_string(), None, None);
+        state.set_last_step_telemetry(Duration::from_millis(1800), Some(400));
+
+        assert_eq!(state.history[0].duration_ms, Some(4200));
+        assert_eq!(state.history[1].duration_ms, None);
+
+        let summary = state.summary();
+        assert!(summary.contains("LLM time: 6.0s over 2 calls (1300 tokens)"));
+        assert!(summary.contains("Execution time: 0.3s over 1 commands"));
+    }
+
     #[test]
     fn test_should_continue() {
         let mut state = AgentState::new("Test".to_string());
@@ -553,4 +1273,399 @@ mod tests {
         state.status = AgentStatus::Completed;
         assert!(!state.should_continue());
     }
+
+    #[test]
+    fn test_thought_prompt_includes_correlated_root_causes() {
+        let mut agent = AgentLoop::new("Site returns 502".to_string(), ToolContext::default());
+        assert!(!agent
+            .build_thought_prompt()
+            .contains("Candidate root causes"));
+
+        agent.state_mut().collected_info = vec![
+            (
+                "curl -I http://localhost".to_string(),
+                "HTTP/1.1 502 Bad Gateway".to_string(),
+            ),
+            (
+                "tail /var/log/nginx/error.log".to_string(),
+                "connect() failed (111: Connection refused) while connecting to upstream"
+                    .to_string(),
+            ),
+        ];
+
+        let prompt = agent.build_thought_prompt();
+        assert!(prompt.contains("Candidate root causes"));
+        assert!(prompt.contains("Upstream service is down"));
+        assert!(prompt.contains("evidence: `curl -I http://localhost`: HTTP/1.1 502 Bad Gateway"));
+    }
+
+    #[test]
+    fn test_parse_solution_plan() {
+        let thought = "The app crashed so nginx has no upstream.\n\
+            SOLUTION: restart the app, then reload nginx:\n\
+            1. `systemctl restart app`\n\
+            2. Validate with `nginx -t` and reload with `nginx -s reload`\n\
+            $ curl -I http://localhost";
+
+        assert_eq!(
+            parse_solution_plan(thought),
+            vec![
+                "systemctl restart app",
+                "nginx -t",
+                "nginx -s reload",
+                "curl -I http://localhost",
+            ]
+        );
+        assert!(parse_solution_plan("SOLUTION: it was DNS").is_empty());
+        assert!(parse_solution_plan("ACTION: nginx `nginx -t`").is_empty());
+    }
+
+    #[test]
+    fn test_prompt_plan_step_answers() {
+        let step = PlanStep {
+            number: 1,
+            total: 2,
+            command: "nginx -s reload".to_string(),
+            tool: "nginx",
+            risk: RiskLevel::Medium,
+            environment: EnvironmentType::Unknown,
+        };
+        let ask = |answer: &str| prompt_plan_step(&step, &mut answer.as_bytes());
+
+        assert_eq!(ask("y\n"), PlanDecision::Approve);
+        assert_eq!(ask("\n"), PlanDecision::Skip);
+        assert_eq!(ask("q\n"), PlanDecision::Abort);
+        assert_eq!(ask(""), PlanDecision::Abort);
+        assert_eq!(
+            ask("e\nnginx -t\n"),
+            PlanDecision::Edit("nginx -t".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prompt_plan_step_types_to_confirm_critical() {
+        let step = PlanStep {
+            number: 1,
+            total: 1,
+            command: "rm -rf /var/lib/app".to_string(),
+            tool: "shell",
+            risk: RiskLevel::Critical,
+            environment: EnvironmentType::Unknown,
+        };
+        let ask = |answer: &str| prompt_plan_step(&step, &mut answer.as_bytes());
+
+        assert_eq!(ask("y\n\n"), PlanDecision::Skip);
+        assert_eq!(ask("y\ny\n"), PlanDecision::Skip);
+        assert_eq!(ask("y\nyes\n"), PlanDecision::Approve);
+    }
+
+    struct FixedLLM(&'static str);
+
+    #[async_trait::async_trait]
+    impl LLMBackend for FixedLLM {
+        async fn infer(&self, _prompt: &str) -> Result<crate::tools::LLMResponse> {
+            Ok(crate::tools::LLMResponse {
+                command: String::new(),
+                confidence: 90,
+                reasoning: self.0.to_string(),
+                prompt_tokens: None,
+                completion_tokens: None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_parse_action() {
+        let action = parse_action("Check listeners first.\nACTION: network ss -tlnp").unwrap();
+        assert_eq!(action.tool_name, "network");
+        assert_eq!(action.command, "ss -tlnp");
+
+        assert!(parse_action("I should look at the nginx config next").is_none());
+        assert!(parse_action("ACTION: nginx").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repair_action_reprompts_once() {
+        let mut agent = AgentLoop::new("Fix it".to_string(), ToolContext::default());
+        let thought = "The port is probably taken, let me check what listens on it";
+
+        let action = agent
+            .repair_action(&FixedLLM("ACTION: network ss -tlnp"), thought)
+            .await
+            .unwrap();
+        assert_eq!(action.command, "ss -tlnp");
+        let note = agent.state().history.last().unwrap();
+        assert_eq!(note.step_type, StepType::Thought);
+        assert!(note.content.starts_with("Reparsed"));
+
+        let action = agent
+            .repair_action(&FixedLLM("still rambling"), thought)
+            .await
+            .unwrap();
+        assert_eq!(action.tool_name, "shell");
+        assert_eq!(action.command, thought);
+        assert!(agent
+            .state()
+            .history
+            .last()
+            .unwrap()
+            .content
+            .starts_with("Reparse failed"));
+    }
+
+    #[test]
+    fn test_parse_question() {
+        assert_eq!(
+            parse_question("I can't tell where it runs.\nASK: Which namespace is the app in?"),
+            Some("Which namespace is the app in?".to_string())
+        );
+        assert_eq!(parse_question("ask:   "), None);
+        assert_eq!(parse_question("ACTION: network ss -tlnp"), None);
+
+        assert_eq!(
+            prompt_question("Which namespace?", &mut "shop\n".as_bytes()),
+            Some("shop".to_string())
+        );
+        assert_eq!(
+            prompt_question("Which namespace?", &mut "\n".as_bytes()),
+            Some(String::new())
+        );
+        assert_eq!(
+            prompt_question("Which namespace?", &mut "".as_bytes()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_question_pauses_until_answered() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = CheckpointStore::with_dir(dir.path());
+        let question = "Which namespace is the app in?";
+
+        let mut agent = AgentLoop::new("App returns 502".to_string(), ToolContext::default())
+            .with_checkpoints(store.clone());
+        let state = agent
+            .run_until_complete(&FixedLLM("ASK: Which namespace is the app in?"))
+            .await
+            .unwrap();
+        assert_eq!(state.pending_question(), Some(question));
+        assert_eq!(state.history.last().unwrap().step_type, StepType::Question);
+        assert!(state.is_resumable());
+
+        // A resumed run asks again before doing anything
+        let saved = store.load(&state.id).unwrap();
+        let mut agent = AgentLoop::resume(saved, ToolContext::default());
+        assert!(!agent.step(&FixedLLM("SOLUTION: done")).await.unwrap());
+        assert_eq!(agent.state().pending_question(), Some(question));
+
+        agent.answer("shop");
+        assert_eq!(agent.state().status, AgentStatus::Running);
+        assert_eq!(agent.state().answered_questions(), vec![(question, "shop")]);
+        let prompt = agent.build_thought_prompt();
+        assert!(prompt.contains("- Q: Which namespace is the app in?\n  A: shop"));
+
+        // Answering twice does nothing
+        agent.answer("billing");
+        assert_eq!(agent.state().history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_kept_until_resumed_run_completes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = CheckpointStore::with_dir(dir.path());
+
+        let mut agent = AgentLoop::new("Site returns 502".to_string(), ToolContext::default())
+            .with_checkpoints(store.clone());
+        agent.cancel_token().cancel();
+        let state = agent
+            .run_until_complete(&FixedLLM("SOLUTION: restart nginx"))
+            .await
+            .unwrap();
+        assert_eq!(state.status, AgentStatus::Stopped(INTERRUPTED.to_string()));
+        assert!(state.is_resumable());
+
+        let saved = store.load(&state.id).unwrap();
+        let mut resumed =
+            AgentLoop::resume(saved, ToolContext::default()).with_checkpoints(store.clone());
+        assert_eq!(resumed.state().status, AgentStatus::Running);
+        let state = resumed
+            .run_until_complete(&FixedLLM("SOLUTION: restart nginx"))
+            .await
+            .unwrap();
+        assert_eq!(state.status, AgentStatus::Completed);
+        assert_eq!(state.history.len(), 1);
+        assert!(store.list().unwrap().is_empty());
+
+        let mut spent = AgentState::new("Test".to_string());
+        spent.status = AgentStatus::Stopped(MAX_ITERATIONS_REACHED.to_string());
+        assert!(!spent.is_resumable());
+    }
+
+    #[tokio::test]
+    async fn test_execute_plan_records_skipped_steps() {
+        let mut agent = AgentLoop::new("Fix it".to_string(), ToolContext::default());
+        agent.state_mut().solution_plan = Some(vec![
+            "rm -rf /tmp/kaido-never-run".to_string(),
+            "echo fixed".to_string(),
+            "echo later".to_string(),
+        ]);
+
+        let mut seen = Vec::new();
+        let outcome = agent
+            .execute_plan_with(|step| {
+                seen.push((step.number, step.risk));
+                match (step.number, step.command.as_str()) {
+                    (1, _) => PlanDecision::Skip,
+                    (2, "echo edited") => PlanDecision::Approve,
+                    (2, _) => PlanDecision::Edit("echo edited".to_string()),
+                    _ => PlanDecision::Abort,
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            PlanOutcome {
+                succeeded: 1,
+                failed: 0,
+                skipped: 2
+            }
+        );
+        assert_eq!(seen.len(), 4);
+        assert_eq!(seen[0].1, RiskLevel::High);
+        assert_eq!(seen[2].0, 2);
+
+        let types: Vec<_> = agent
+            .state()
+            .history
+            .iter()
+            .map(|s| s.step_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                StepType::Skipped,
+                StepType::Action,
+                StepType::Observation,
+                StepType::Skipped
+            ]
+        );
+        assert_eq!(agent.state().history[1].content, "echo edited");
+        assert!(agent.state().history[2].content.contains("edited"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_step_shows_the_tool_that_runs_it() {
+        let mut agent = AgentLoop::new("Fix it".to_string(), ToolContext::default());
+        agent.state_mut().solution_plan = Some(vec![
+            "rm -rf /var/lib/docker/volumes/cache".to_string(),
+            "docker rm -f web".to_string(),
+        ]);
+
+        let mut seen = Vec::new();
+        agent
+            .execute_plan_with(|step| {
+                seen.push((step.tool, step.risk));
+                PlanDecision::Skip
+            })
+            .await
+            .unwrap();
+
+        // Mentioning docker doesn't make docker run it
+        assert_eq!(seen[0], ("shell", RiskLevel::High));
+        assert_eq!(seen[1].0, "docker");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_interrupts_plan() {
+        let mut agent = AgentLoop::new("Fix it".to_string(), ToolContext::default());
+        agent.state_mut().solution_plan =
+            Some(vec!["sleep 30".to_string(), "echo later".to_string()]);
+        let cancel = agent.cancel_token();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        });
+
+        let start = Instant::now();
+        let outcome = agent
+            .execute_plan_with(|_| PlanDecision::Approve)
+            .await
+            .unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(
+            outcome,
+            PlanOutcome {
+                succeeded: 0,
+                failed: 1,
+                skipped: 1
+            }
+        );
+        let types: Vec<_> = agent
+            .state()
+            .history
+            .iter()
+            .map(|s| s.step_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![StepType::Action, StepType::Interrupted, StepType::Skipped]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy_ignores_declared_tool() {
+        let policy = CommandPolicy::from_config(&crate::config::AgentConfig {
+            allowed_tools: vec!["docker".to_string()],
+            denied_commands: vec![],
+        })
+        .unwrap();
+        let agent =
+            AgentLoop::new("Fix it".to_string(), ToolContext::default()).with_policy(policy);
+
+        // Claims to be docker, but runs rm through the shell
+        let spoofed = ActionCommand {
+            tool_name: "docker".to_string(),
+            command: "rm -rf /tmp/kaido-none".to_string(),
+        };
+        match agent.execute_action(&spoofed).await.unwrap() {
+            ActionOutcome::Blocked(reason) => assert!(reason.contains("tool 'shell'")),
+            _ => panic!("spoofed tool name was trusted"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_policy_blocks_plan_step() {
+        let policy = CommandPolicy::from_config(&crate::config::AgentConfig {
+            allowed_tools: vec![],
+            denied_commands: vec![r"\brm\b".to_string()],
+        })
+        .unwrap();
+        let mut agent =
+            AgentLoop::new("Fix it".to_string(), ToolContext::default()).with_policy(policy);
+        agent.state_mut().solution_plan = Some(vec![
+            "rm -rf /tmp/kaido-none".to_string(),
+            "echo ok".to_string(),
+        ]);
+
+        let outcome = agent
+            .execute_plan_with(|_| PlanDecision::Approve)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outcome,
+            PlanOutcome {
+                succeeded: 1,
+                failed: 1,
+                skipped: 0
+            }
+        );
+        let blocked = &agent.state().history[1];
+        assert_eq!(blocked.step_type, StepType::Observation);
+        assert_eq!(blocked.success, Some(false));
+        assert!(blocked.content.starts_with("Blocked by agent policy"));
+    }
 }
@@ -0,0 +1,163 @@
+// Agent run checkpoints
+//
+// The agent's state is written to ~/.kaido/agent/<id>.json after every step,
+// so a diagnosis cut short by Ctrl+C, a crash or a failing LLM call can be
+// picked up with `kaido diagnose --resume <id>`. Finished runs are removed.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::agent_loop::AgentState;
+use crate::secrets::write_private;
+
+/// Directory of agent checkpoints, one JSON file per run
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    dir: PathBuf,
+}
+
+impl CheckpointStore {
+    /// Store under ~/.kaido/agent
+    pub fn new() -> Result<Self> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        Ok(Self::with_dir(home.join(".kaido").join("agent")))
+    }
+
+    /// Store in `dir`
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// Write a run's state, replacing its previous checkpoint
+    ///
+    /// Written to a temporary file first, so a crash mid-write leaves the
+    /// previous checkpoint intact. Checkpoints hold command output, so only
+    /// the user can read them.
+    pub fn save(&self, state: &AgentState) -> Result<()> {
+        let path = self.path(&state.id);
+        let partial = path.with_extension("json.partial");
+        write_private(&partial, &serde_json::to_vec_pretty(state)?)?;
+        std::fs::rename(&partial, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// State of a checkpointed run
+    pub fn load(&self, id: &str) -> Result<AgentState> {
+        let path = self.path(id);
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) || !path.exists() {
+            anyhow::bail!(
+                "No checkpoint '{id}'. Run `kaido diagnose --list` to see resumable runs"
+            );
+        }
+        read_state(&path)
+    }
+
+    /// Delete a run's checkpoint; a missing one is fine
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let path = self.path(id);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Checkpointed runs, most recently active first
+    ///
+    /// Unreadable files are skipped with a warning.
+    pub fn list(&self) -> Result<Vec<AgentState>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut states = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {}", self.dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match read_state(&path) {
+                Ok(state) => states.push(state),
+                Err(e) => log::warn!("Skipping checkpoint {}: {e:#}", path.display()),
+            }
+        }
+        states.sort_by_key(|state| {
+            std::cmp::Reverse(state.history.last().map(|step| step.timestamp))
+        });
+        Ok(states)
+    }
+}
+
+fn read_state(path: &Path) -> Result<AgentState> {
+    let contents =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice(&contents)
+        .with_context(|| format!("Invalid checkpoint {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::{AgentStatus, StepType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_load_list_remove() {
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::with_dir(dir.path());
+        assert!(store.list().unwrap().is_empty());
+
+        let mut state = AgentState::new("Site returns 502".to_string());
+        state.add_step(StepType::Thought, "check nginx".to_string(), None, None);
+        state.add_step(
+            StepType::Action,
+            "systemctl status nginx".to_string(),
+            Some("shell".to_string()),
+            None,
+        );
+        state
+            .collected_info
+            .push(("systemctl status nginx".to_string(), "inactive".to_string()));
+        state.iteration = 1;
+        state.status = AgentStatus::Stopped("Interrupted by user".to_string());
+        store.save(&state).unwrap();
+
+        let loaded = store.load(&state.id).unwrap();
+        assert_eq!(loaded.task, "Site returns 502");
+        assert_eq!(loaded.history.len(), 2);
+        assert_eq!(loaded.history[1].tool_used.as_deref(), Some("shell"));
+        assert_eq!(loaded.collected_info, state.collected_info);
+        assert_eq!(loaded.iteration, 1);
+        assert_eq!(loaded.status, state.status);
+
+        std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+        let listed = store.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, state.id);
+
+        store.remove(&state.id).unwrap();
+        store.remove(&state.id).unwrap();
+        assert!(store.load(&state.id).is_err());
+        assert!(store.load("../broken").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_checkpoints_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::with_dir(dir.path().join("agent"));
+        let state = AgentState::new("Site returns 502".to_string());
+        store.save(&state).unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&store.path(&state.id)), 0o600);
+        assert_eq!(mode(&dir.path().join("agent")), 0o700);
+    }
+}
@@ -247,6 +247,22 @@ impl RootCauseAnalyzer {
         // Return root cause with highest confidence
         root_causes.into_iter().max_by_key(|c| c.confidence)
     }
+
+    /// Correlate evidence across several observations (command, output)
+    ///
+    /// Returns candidate root causes ranked by confidence, highest first.
+    /// A candidate is only produced when every required signal of a rule
+    /// shows up somewhere in the observations; supporting signals raise
+    /// the confidence.
+    pub fn correlate(&self, observations: &[(String, String)]) -> Vec<RootCause> {
+        let mut candidates: Vec<RootCause> = CORRELATION_RULES
+            .iter()
+            .filter_map(|rule| rule.evaluate(observations))
+            .collect();
+
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.confidence));
+        candidates
+    }
 }
 
 impl Default for RootCauseAnalyzer {
@@ -255,6 +271,215 @@ impl Default for RootCauseAnalyzer {
     }
 }
 
+// ===== Evidence Correlation =====
+
+/// Confidence added for each supporting signal found
+const SUPPORT_BONUS: u8 = 10;
+
+/// Highest confidence a correlated root cause can reach
+const MAX_CORRELATION_CONFIDENCE: u8 = 95;
+
+/// Longest evidence line kept before truncating
+const MAX_EVIDENCE_LEN: usize = 120;
+
+/// A pattern correlation rule
+///
+/// Each signal is a list of alternative (lowercase) phrases; a signal
+/// matches when any alternative appears as whole words in an observation's
+/// output, so `502` never matches `pid 15024`.
+struct CorrelationRule {
+    category: RootCauseCategory,
+    description: &'static str,
+    /// Signals that must all be observed
+    required: &'static [&'static [&'static str]],
+    /// Signals that corroborate the diagnosis
+    supporting: &'static [&'static [&'static str]],
+    base_confidence: u8,
+}
+
+const CORRELATION_RULES: &[CorrelationRule] = &[
+    CorrelationRule {
+        category: RootCauseCategory::ServiceDown,
+        description: "Upstream service is down: the proxy returns 502 because its backend refuses connections",
+        required: &[
+            &["502", "bad gateway"],
+            &["connection refused", "connect() failed"],
+        ],
+        supporting: &[
+            &["upstream"],
+            &["inactive (dead)", "not running", "exited"],
+        ],
+        base_confidence: 75,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::NetworkIssue,
+        description: "Upstream is slow or unreachable: the proxy times out waiting for it",
+        required: &[&["504", "gateway timeout", "upstream timed out"]],
+        supporting: &[&["timed out", "no route to host"]],
+        base_confidence: 65,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::ServiceDown,
+        description: "Proxy cannot reach its upstream (502 Bad Gateway)",
+        required: &[&["502", "bad gateway"]],
+        supporting: &[],
+        base_confidence: 50,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::ServiceDown,
+        description: "Service is not running",
+        required: &[&["inactive (dead)", "failed (result:", "active: failed"]],
+        supporting: &[&["connection refused"], &["exited", "code=exited"]],
+        base_confidence: 70,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::PortConflict,
+        description: "Port is already in use by another process",
+        required: &[&["address already in use", "eaddrinuse", "bind() to"]],
+        supporting: &[&["listen"]],
+        base_confidence: 80,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::ConfigurationError,
+        description: "Configuration file contains errors",
+        required: &[&[
+            "syntax error",
+            "configuration test failed",
+            "unknown directive",
+            "parse error",
+        ]],
+        supporting: &[&["failed to start", "reload failed"]],
+        base_confidence: 80,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::ResourceExhaustion,
+        description: "Disk is full",
+        required: &[&["no space left on device", "disk quota exceeded"]],
+        supporting: &[&["100%"]],
+        base_confidence: 80,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::ResourceExhaustion,
+        description: "Process was killed for running out of memory",
+        required: &[&["out of memory", "oom-killer", "oomkilled"]],
+        supporting: &[&["killed process", "exit code 137", "killed"]],
+        base_confidence: 75,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::PermissionError,
+        description: "Insufficient permissions to access a required resource",
+        required: &[&["permission denied", "eacces", "operation not permitted"]],
+        supporting: &[&["failed to start", "cannot open"]],
+        base_confidence: 70,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::NetworkIssue,
+        description: "DNS resolution is failing",
+        required: &[&[
+            "could not resolve host",
+            "name or service not known",
+            "temporary failure in name resolution",
+        ]],
+        supporting: &[&["nameserver", "resolv.conf"]],
+        base_confidence: 75,
+    },
+    CorrelationRule {
+        category: RootCauseCategory::AuthenticationFailure,
+        description: "Credentials are missing or rejected",
+        required: &[&[
+            "authentication failed",
+            "access denied for user",
+            "401 unauthorized",
+            "invalid credentials",
+        ]],
+        supporting: &[&["password", "token"]],
+        base_confidence: 75,
+    },
+];
+
+impl CorrelationRule {
+    fn evaluate(&self, observations: &[(String, String)]) -> Option<RootCause> {
+        let mut evidence = Vec::new();
+        let mut components = Vec::new();
+
+        for signal in self.required {
+            let (command, line) = find_signal(signal, observations)?;
+            Self::record(&mut evidence, &mut components, command, line);
+        }
+
+        let mut confidence = self.base_confidence;
+        for signal in self.supporting {
+            if let Some((command, line)) = find_signal(signal, observations) {
+                confidence = confidence.saturating_add(SUPPORT_BONUS);
+                Self::record(&mut evidence, &mut components, command, line);
+            }
+        }
+
+        Some(RootCause {
+            category: self.category,
+            description: self.description.to_string(),
+            evidence,
+            confidence: confidence.min(MAX_CORRELATION_CONFIDENCE),
+            affected_components: components,
+        })
+    }
+
+    fn record(evidence: &mut Vec<String>, components: &mut Vec<String>, command: &str, line: &str) {
+        let line = if line.chars().count() > MAX_EVIDENCE_LEN {
+            format!(
+                "{}...",
+                line.chars().take(MAX_EVIDENCE_LEN).collect::<String>()
+            )
+        } else {
+            line.to_string()
+        };
+        let entry = format!("`{command}`: {line}");
+        if !evidence.contains(&entry) {
+            evidence.push(entry);
+        }
+
+        if let Some(tool) = command.split_whitespace().next() {
+            if !components.iter().any(|c| c == tool) {
+                components.push(tool.to_string());
+            }
+        }
+    }
+}
+
+/// Find the first observation line matching any alternative of a signal
+fn find_signal<'a>(
+    alternatives: &[&str],
+    observations: &'a [(String, String)],
+) -> Option<(&'a str, &'a str)> {
+    observations.iter().find_map(|(command, output)| {
+        output
+            .lines()
+            .find(|line| {
+                let lower = line.to_lowercase();
+                alternatives.iter().any(|alt| contains_words(&lower, alt))
+            })
+            .map(|line| (command.as_str(), line.trim()))
+    })
+}
+
+/// Whether `phrase` occurs in `text` not glued to other letters or digits
+///
+/// Only edges of `phrase` that are alphanumeric need a boundary, so
+/// `connect() failed` and `100%` still match inside punctuation.
+fn contains_words(text: &str, phrase: &str) -> bool {
+    let word_edge = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    let needs_start = word_edge(phrase.chars().next());
+    let needs_end = word_edge(phrase.chars().next_back());
+
+    text.match_indices(phrase).any(|(start, matched)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + matched.len()..].chars().next();
+        let glued_start = needs_start && word_edge(before);
+        let glued_end = needs_end && word_edge(after);
+        !glued_start && !glued_end
+    })
+}
+
 // ===== Diagnosis Strategies =====
 
 /// Strategy for diagnosing port conflicts
@@ -486,4 +711,90 @@ mod tests {
         let commands = analyzer.get_diagnostic_commands(&problem);
         assert!(!commands.is_empty());
     }
+
+    fn observation(command: &str, output: &str) -> (String, String) {
+        (command.to_string(), output.to_string())
+    }
+
+    #[test]
+    fn test_correlate_upstream_down() {
+        let analyzer = RootCauseAnalyzer::new();
+        let observations = vec![
+            observation(
+                "curl -I http://localhost",
+                "HTTP/1.1 502 Bad Gateway\nServer: nginx",
+            ),
+            observation(
+                "tail /var/log/nginx/error.log",
+                "connect() failed (111: Connection refused) while connecting to upstream",
+            ),
+            observation("systemctl status app", "Active: inactive (dead) since Mon"),
+        ];
+
+        let causes = analyzer.correlate(&observations);
+        assert!(causes.len() >= 2);
+
+        let top = &causes[0];
+        assert_eq!(top.category, RootCauseCategory::ServiceDown);
+        assert!(top.description.contains("Upstream service is down"));
+        assert_eq!(top.confidence, 95);
+        assert!(top.evidence.iter().any(|e| e.contains("502 Bad Gateway")));
+        assert!(top
+            .evidence
+            .iter()
+            .any(|e| e.contains("Connection refused")));
+        assert!(top.affected_components.contains(&"curl".to_string()));
+
+        // Ranked highest confidence first
+        assert!(causes
+            .windows(2)
+            .all(|w| w[0].confidence >= w[1].confidence));
+    }
+
+    #[test]
+    fn test_correlate_requires_all_signals() {
+        let analyzer = RootCauseAnalyzer::new();
+        let observations = vec![observation(
+            "curl -I http://localhost",
+            "HTTP/1.1 502 Bad Gateway",
+        )];
+
+        let causes = analyzer.correlate(&observations);
+        assert_eq!(causes.len(), 1);
+        assert_eq!(
+            causes[0].description,
+            "Proxy cannot reach its upstream (502 Bad Gateway)"
+        );
+        assert_eq!(causes[0].confidence, 50);
+    }
+
+    #[test]
+    fn test_correlate_no_evidence() {
+        let analyzer = RootCauseAnalyzer::new();
+        assert!(analyzer.correlate(&[]).is_empty());
+
+        let observations = vec![observation("uptime", "10:00 up 3 days, load average: 0.1")];
+        assert!(analyzer.correlate(&observations).is_empty());
+    }
+
+    #[test]
+    fn test_correlate_ignores_numbers_inside_other_numbers() {
+        let analyzer = RootCauseAnalyzer::new();
+        let observations = vec![
+            observation("ps aux", "www-data  15024  0.0  nginx: worker process"),
+            observation("ls -l", "-rw-r--r-- 1 root root 5021 bytes app.log"),
+            observation("date", "Mon 15:02:1504 UTC"),
+            observation(
+                "nc -z localhost 8080",
+                "connect to localhost port 8080: Connection refused",
+            ),
+        ];
+        assert!(analyzer.correlate(&observations).is_empty());
+
+        assert!(contains_words("http/1.1 502 bad gateway", "502"));
+        assert!(contains_words("\"get / http/1.1\" 502 157", "502"));
+        assert!(contains_words("connect() failed (111)", "connect() failed"));
+        assert!(!contains_words("pid 15024", "502"));
+        assert!(!contains_words("took 1502ms", "502"));
+    }
 }
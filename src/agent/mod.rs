@@ -1,5 +1,11 @@
 pub mod agent_loop;
+pub mod checkpoint;
 pub mod diagnosis;
+pub mod policy;
 
-pub use agent_loop::{AgentLoop, AgentState, AgentStatus, AgentStep, StepType};
-pub use diagnosis::{DiagnosisStrategy, ProblemContext, RootCauseAnalyzer};
+pub use agent_loop::{
+    AgentLoop, AgentState, AgentStatus, AgentStep, PlanDecision, PlanOutcome, PlanStep, StepType,
+};
+pub use checkpoint::CheckpointStore;
+pub use diagnosis::{DiagnosisStrategy, ProblemContext, RootCause, RootCauseAnalyzer};
+pub use policy::CommandPolicy;
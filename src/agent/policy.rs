@@ -0,0 +1,100 @@
+// Guardrails for agent actions
+//
+// The agent picks its own commands, so before it runs against real
+// infrastructure its reach can be narrowed in config.toml: only some tools,
+// and never commands matching a deny pattern. The tool is worked out from
+// the command, not taken from the model's word, and deny patterns are tried
+// on every part of a `;`/`&&`/`||`/`|` chain. A blocked action isn't run;
+// the agent sees a policy observation instead and can try something else.
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::config::AgentConfig;
+use crate::tools::command_line;
+
+/// Which tools and commands the agent may run
+#[derive(Debug, Clone, Default)]
+pub struct CommandPolicy {
+    /// Tools the agent may use; empty allows all ("shell" is the fallback
+    /// for commands no tool handles)
+    allowed_tools: Vec<String>,
+    /// Commands matching any of these are never run
+    denied_commands: Vec<Regex>,
+}
+
+impl CommandPolicy {
+    /// Policy from the `[agent]` section; fails on an invalid pattern
+    pub fn from_config(config: &AgentConfig) -> Result<Self> {
+        let denied_commands = config
+            .denied_commands
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    anyhow::anyhow!("Invalid agent.denied_commands pattern '{pattern}': {e}")
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            allowed_tools: config.allowed_tools.clone(),
+            denied_commands,
+        })
+    }
+
+    /// Why `command` run through `tool` is not allowed, if it isn't
+    ///
+    /// `tool` must be the one that owns the command (see
+    /// `ToolRegistry::tool_for_command`), or "shell".
+    pub fn check(&self, tool: &str, command: &str) -> Option<String> {
+        if !self.allowed_tools.is_empty() && !self.allowed_tools.iter().any(|t| t == tool) {
+            return Some(format!(
+                "tool '{tool}' is not in agent.allowed_tools ({})",
+                self.allowed_tools.join(", ")
+            ));
+        }
+        let segments = command_line::segments(command);
+        self.denied_commands
+            .iter()
+            .find(|re| re.is_match(command) || segments.iter().any(|s| re.is_match(s.text)))
+            .map(|re| format!("command matches agent.denied_commands pattern '{re}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_blocks_tools_and_patterns() {
+        let policy = CommandPolicy::from_config(&AgentConfig {
+            allowed_tools: vec!["kubectl".to_string(), "docker".to_string()],
+            denied_commands: vec![r"\bdelete\b".to_string(), r"^rm\s".to_string()],
+        })
+        .unwrap();
+
+        assert_eq!(policy.check("kubectl", "kubectl get pods"), None);
+        assert!(policy
+            .check("kubectl", "kubectl delete pod api-1")
+            .unwrap()
+            .contains(r"\bdelete\b"));
+        assert!(policy
+            .check("shell", "rm -rf /tmp/cache")
+            .unwrap()
+            .contains("not in agent.allowed_tools"));
+
+        // Anchored patterns see each part of a chain
+        assert!(policy
+            .check("kubectl", "kubectl get pods; rm -rf /")
+            .is_some());
+
+        assert!(CommandPolicy::default()
+            .check("shell", "rm -rf /")
+            .is_none());
+        assert!(CommandPolicy::from_config(&AgentConfig {
+            allowed_tools: vec![],
+            denied_commands: vec!["(".to_string()],
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,191 @@
+// Multi-turn conversations
+//
+// Follow-up questions ("why?", "show me the fix") only make sense if the
+// model sees the previous turn. A Conversation keeps the last few exchanges
+// and drops the oldest ones once it exceeds its message or token cap.
+
+use super::tokens::estimate_tokens;
+use serde::{Deserialize, Serialize};
+
+/// Exchanges (user message plus reply) kept by default
+pub const DEFAULT_MAX_TURNS: usize = 3;
+
+/// Estimated tokens kept by default across the whole history
+pub const DEFAULT_MAX_TOKENS: u32 = 3000;
+
+/// Who sent a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    System,
+    User,
+    Assistant,
+}
+
+impl ChatRole {
+    /// Role name used by OpenAI-compatible and Ollama chat APIs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Assistant => "assistant",
+        }
+    }
+}
+
+/// One message of a conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn new(role: ChatRole, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+        }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::System, content)
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::User, content)
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::Assistant, content)
+    }
+}
+
+/// Rolling conversation history with a size cap
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    messages: Vec<ChatMessage>,
+    max_turns: usize,
+    max_tokens: u32,
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            max_turns: DEFAULT_MAX_TURNS,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        }
+    }
+
+    /// Keep at most this many exchanges
+    pub fn with_max_turns(mut self, max_turns: usize) -> Self {
+        self.max_turns = max_turns.max(1);
+        self
+    }
+
+    /// Keep at most this many estimated tokens
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    pub fn push(&mut self, message: ChatMessage) {
+        self.messages.push(message);
+        self.trim();
+    }
+
+    pub fn push_user(&mut self, content: impl Into<String>) {
+        self.push(ChatMessage::user(content));
+    }
+
+    pub fn push_assistant(&mut self, content: impl Into<String>) {
+        self.push(ChatMessage::assistant(content));
+    }
+
+    /// Drop the last message, e.g. a question whose request failed
+    pub fn pop(&mut self) -> Option<ChatMessage> {
+        self.messages.pop()
+    }
+
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Estimated tokens across all messages
+    pub fn estimated_tokens(&self) -> u32 {
+        self.messages
+            .iter()
+            .map(|m| estimate_tokens(&m.content))
+            .sum()
+    }
+
+    /// Drop the oldest messages until both caps hold
+    ///
+    /// The newest message is always kept, even if it alone exceeds the
+    /// token cap, and history never starts with an assistant reply.
+    fn trim(&mut self) {
+        let max_messages = self.max_turns * 2;
+        while self.messages.len() > 1
+            && (self.messages.len() > max_messages || self.estimated_tokens() > self.max_tokens)
+        {
+            self.messages.remove(0);
+        }
+        while self.messages.len() > 1 && self.messages[0].role == ChatRole::Assistant {
+            self.messages.remove(0);
+        }
+    }
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_last_turns() {
+        let mut conversation = Conversation::new().with_max_turns(2);
+        for i in 0..4 {
+            conversation.push_user(format!("question {i}"));
+            conversation.push_assistant(format!("answer {i}"));
+        }
+
+        let contents: Vec<_> = conversation
+            .messages()
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(
+            contents,
+            ["question 2", "answer 2", "question 3", "answer 3"]
+        );
+    }
+
+    #[test]
+    fn test_token_cap_drops_oldest() {
+        let mut conversation = Conversation::new().with_max_tokens(10);
+        conversation.push_user("x".repeat(32));
+        conversation.push_assistant("y".repeat(8));
+        conversation.push_user("why?");
+
+        // The first question no longer fits, and the reply it belonged to
+        // can't lead the history on its own
+        assert_eq!(conversation.messages(), [ChatMessage::user("why?")]);
+
+        conversation.push_user("z".repeat(400));
+        assert_eq!(conversation.messages().len(), 1);
+    }
+}
@@ -0,0 +1,158 @@
+// Circuit breaker for a flaky backend
+//
+// In auto mode every request tries Gemini first. When Gemini keeps failing
+// (offline-ish laptop, expired key) that costs a timeout per request, so
+// after a run of consecutive failures the backend is skipped for a cooldown
+// window, then a single request probes it again.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Skips a backend after `threshold` consecutive failures, for `cooldown`
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: &'static str,
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Failures in a row since the last success
+    failures: u32,
+    /// Skip the backend until then
+    open_until: Option<Instant>,
+    /// The next result decides whether the backend is back
+    probing: bool,
+}
+
+impl CircuitBreaker {
+    /// Breaker for backend `name`; a `threshold` of 0 never skips it
+    pub fn new(name: &'static str, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name,
+            threshold,
+            cooldown,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Whether to call the backend now; once the cooldown is over the next
+    /// call is let through as a probe
+    pub fn allow(&self) -> bool {
+        self.allow_at(Instant::now())
+    }
+
+    fn allow_at(&self, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match state.open_until {
+            Some(until) if now < until => false,
+            Some(_) => {
+                log::info!("{} cooldown over, probing it again", self.name);
+                state.open_until = None;
+                state.probing = true;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// How long the backend is still skipped for
+    pub fn remaining_cooldown(&self) -> Option<Duration> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .open_until
+            .map(|until| until.saturating_duration_since(Instant::now()))
+    }
+
+    /// Record the outcome of a call that `allow` let through
+    pub fn record(&self, success: bool) {
+        self.record_at(success, Instant::now());
+    }
+
+    fn record_at(&self, success: bool, now: Instant) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if success {
+            if state.probing {
+                log::info!("{} is back, using it again", self.name);
+            }
+            *state = State::default();
+            return;
+        }
+
+        state.failures += 1;
+        let open = if state.probing {
+            log::warn!(
+                "{} still failing, skipping it for another {:?}",
+                self.name,
+                self.cooldown
+            );
+            true
+        } else if self.threshold > 0 && state.failures == self.threshold {
+            log::warn!(
+                "{} failed {} times in a row, skipping it for {:?}",
+                self.name,
+                state.failures,
+                self.cooldown
+            );
+            true
+        } else {
+            false
+        };
+        if open {
+            state.open_until = Some(now + self.cooldown);
+            state.probing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_probes_after_cooldown() {
+        let breaker = CircuitBreaker::new("Gemini", 3, Duration::from_secs(60));
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            assert!(breaker.allow_at(start));
+            breaker.record_at(false, start);
+        }
+        assert!(breaker.allow_at(start));
+        breaker.record_at(false, start);
+        assert!(!breaker.allow_at(start + Duration::from_secs(59)));
+
+        // Probe fails: skipped for another cooldown
+        let probe = start + Duration::from_secs(61);
+        assert!(breaker.allow_at(probe));
+        breaker.record_at(false, probe);
+        assert!(!breaker.allow_at(probe + Duration::from_secs(30)));
+
+        // Probe succeeds: closed again
+        let probe = probe + Duration::from_secs(61);
+        assert!(breaker.allow_at(probe));
+        breaker.record_at(true, probe);
+        assert!(breaker.allow_at(probe));
+        breaker.record_at(false, probe);
+        assert!(breaker.allow_at(probe));
+    }
+
+    #[test]
+    fn test_success_resets_the_count_and_zero_never_opens() {
+        let breaker = CircuitBreaker::new("Gemini", 2, Duration::from_secs(60));
+        let now = Instant::now();
+        breaker.record_at(false, now);
+        breaker.record_at(true, now);
+        breaker.record_at(false, now);
+        assert!(breaker.allow_at(now));
+
+        let disabled = CircuitBreaker::new("Gemini", 0, Duration::from_secs(60));
+        for _ in 0..10 {
+            disabled.record_at(false, now);
+        }
+        assert!(disabled.allow_at(now));
+        assert_eq!(disabled.remaining_cooldown(), None);
+    }
+}
@@ -1,3 +1,5 @@
+use super::chat::ChatMessage;
+use super::ollama::extract_command;
 use crate::config::CopilotConfig;
 use crate::tools::{LLMBackend, LLMResponse};
 use anyhow::{Context, Result};
@@ -53,24 +55,29 @@ impl CopilotBackend {
         !self.get_token().is_empty()
     }
     
-    fn get_token(&self) -> String {
-        if !self.config.token.is_empty() {
-            self.config.token.clone()
-        } else {
-            CopilotConfig::load_token().unwrap_or_default()
-        }
-    }
-}
+    /// Infer with conversation history as chat completion messages
+    pub async fn infer_chat(&self, messages: &[ChatMessage]) -> Result<LLMResponse> {
+        let messages = messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.as_str().to_string(),
+                content: m.content.clone(),
+            })
+            .collect();
 
-impl Default for CopilotBackend {
-    fn default() -> Self {
-        Self::new()
+        let content = self.complete(messages, 1024).await?;
+
+        Ok(LLMResponse {
+            command: extract_command(&content).unwrap_or_default(),
+            confidence: 85,
+            reasoning: content,
+            prompt_tokens: None,
+            completion_tokens: None,
+        })
     }
-}
 
-#[async_trait]
-impl LLMBackend for CopilotBackend {
-    async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
+    /// Call the chat completions API and return the first choice
+    async fn complete(&self, messages: Vec<Message>, max_tokens: u32) -> Result<String> {
         let token = self.get_token();
         
         if token.is_empty() {
@@ -86,18 +93,9 @@ impl LLMBackend for CopilotBackend {
         
         let request = CopilotRequest {
             model,
-            messages: vec![
-                Message {
-                    role: "system".to_string(),
-                    content: "You are a DevOps assistant. Translate natural language to shell commands. Respond with just the command, no explanation.".to_string(),
-                },
-                Message {
-                    role: "user".to_string(),
-                    content: prompt.to_string(),
-                },
-            ],
+            messages,
             temperature: 0.3,
-            max_tokens: 256,
+            max_tokens,
         };
         
         let url = format!("{}/v1/chat/completions", self.config.base_url);
@@ -117,15 +115,49 @@ impl LLMBackend for CopilotBackend {
             .await
             .context("Failed to parse Copilot response")?;
         
-        let content = result.choices
+        Ok(result.choices
             .first()
             .map(|c| c.message.content.clone())
-            .unwrap_or_default();
-        
+            .unwrap_or_default())
+    }
+    
+    fn get_token(&self) -> String {
+        if !self.config.token.is_empty() {
+            self.config.token.clone()
+        } else {
+            CopilotConfig::load_token().unwrap_or_default()
+        }
+    }
+}
+
+impl Default for CopilotBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LLMBackend for CopilotBackend {
+    async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
+        let messages = vec![
+            Message {
+                role: "system".to_string(),
+                content: "You are a DevOps assistant. Translate natural language to shell commands. Respond with just the command, no explanation.".to_string(),
+            },
+            Message {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            },
+        ];
+
+        let content = self.complete(messages, 256).await?;
+
         Ok(LLMResponse {
             command: content,
             confidence: 85,
             reasoning: "Copilot inference".to_string(),
+            prompt_tokens: None,
+            completion_tokens: None,
         })
     }
 }
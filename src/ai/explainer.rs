@@ -5,10 +5,106 @@
 
 use crate::tools::LLMBackend;
 use anyhow::Result;
+use unicode_width::UnicodeWidthStr;
 
 /// Generates educational explanations for commands
 pub struct CommandExplainer;
 
+/// Commands whose first argument picks a subcommand
+const SUBCOMMAND_TOOLS: &[&str] = &[
+    "kubectl",
+    "docker",
+    "git",
+    "systemctl",
+    "helm",
+    "drush",
+    "npm",
+    "cargo",
+    "apt",
+    "brew",
+];
+
+/// Role of a token within its command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenRole {
+    /// The program being run
+    Command,
+    /// Subcommand of tools like kubectl or git
+    Subcommand,
+    /// Option starting with `-`
+    Flag,
+    /// Anything else: names, paths, values
+    Argument,
+}
+
+impl TokenRole {
+    fn label(&self) -> &'static str {
+        match self {
+            TokenRole::Command => "command",
+            TokenRole::Subcommand => "subcommand",
+            TokenRole::Flag => "flag",
+            TokenRole::Argument => "argument",
+        }
+    }
+}
+
+/// One token and what it does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenNote {
+    pub token: String,
+    pub role: TokenRole,
+    /// Empty when nothing is known about the token
+    pub meaning: String,
+}
+
+/// Token-by-token breakdown of a command, one entry per pipeline stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepExplanation {
+    pub stages: Vec<Vec<TokenNote>>,
+}
+
+impl DeepExplanation {
+    /// Render each stage as an aligned token / role / meaning table
+    pub fn render(&self) -> String {
+        let width = |notes: &[TokenNote]| {
+            notes
+                .iter()
+                .map(|note| note.token.width())
+                .max()
+                .unwrap_or(0)
+        };
+
+        let mut out = String::new();
+        for (i, notes) in self.stages.iter().enumerate() {
+            if self.stages.len() > 1 {
+                let stage: Vec<&str> = notes.iter().map(|note| note.token.as_str()).collect();
+                out.push_str(&format!(
+                    "Stage {} of {}: {}\n",
+                    i + 1,
+                    self.stages.len(),
+                    stage.join(" ")
+                ));
+            }
+            let token_width = width(notes);
+            for note in notes {
+                let padding = " ".repeat(token_width - note.token.width());
+                let line = format!(
+                    "  {}{padding}  {:<10}  {}",
+                    note.token,
+                    note.role.label(),
+                    note.meaning
+                );
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+            if i + 1 < self.stages.len() {
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
 impl CommandExplainer {
     /// Generate an educational explanation for a command (async with LLM)
     ///
@@ -61,6 +157,118 @@ impl CommandExplainer {
         explanation
     }
 
+    /// Annotate every token of a command, stage by stage for pipelines
+    ///
+    /// Roles and meanings for well-known commands and flags come from
+    /// built-in knowledge; with an LLM, its per-token annotations replace
+    /// them. If the LLM call fails the built-in breakdown is returned.
+    pub async fn explain_deep(
+        command: &str,
+        llm: Option<&dyn LLMBackend>,
+    ) -> Result<DeepExplanation> {
+        let mut deep = Self::explain_deep_sync(command)?;
+        let Some(llm) = llm else {
+            return Ok(deep);
+        };
+
+        match llm.infer(&Self::build_deep_prompt(command, &deep)).await {
+            Ok(response) => Self::apply_token_meanings(&mut deep, &response.reasoning),
+            Err(e) => log::warn!("Deep explanation fell back to built-in notes: {e}"),
+        }
+        Ok(deep)
+    }
+
+    /// Token breakdown from built-in knowledge only
+    pub fn explain_deep_sync(command: &str) -> Result<DeepExplanation> {
+        let stages = tokenize_command(command)?
+            .into_iter()
+            .map(|tokens| Self::annotate_stage(&tokens))
+            .collect();
+        Ok(DeepExplanation { stages })
+    }
+
+    fn annotate_stage(tokens: &[String]) -> Vec<TokenNote> {
+        let base = tokens.first().map(String::as_str).unwrap_or("");
+        let known_flags = Self::explain_flags(&format!(" {} ", tokens.join(" ")), base);
+        let known = |token: &str| {
+            known_flags
+                .iter()
+                .find(|(flag, _)| flag == token)
+                .map(|(_, meaning)| meaning.to_string())
+                .unwrap_or_default()
+        };
+
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(i, token)| {
+                let (role, meaning) = if i == 0 {
+                    (
+                        TokenRole::Command,
+                        Self::get_base_command_desc(token).to_string(),
+                    )
+                } else if token.starts_with('-') && token.len() > 1 {
+                    let flag = token.split('=').next().unwrap_or(token);
+                    (TokenRole::Flag, known(flag))
+                } else if i == 1 && SUBCOMMAND_TOOLS.contains(&base) {
+                    (TokenRole::Subcommand, known(token))
+                } else {
+                    (TokenRole::Argument, String::new())
+                };
+                TokenNote {
+                    token: token.clone(),
+                    role,
+                    meaning,
+                }
+            })
+            .collect()
+    }
+
+    /// Build the prompt asking for one annotation per token
+    fn build_deep_prompt(command: &str, deep: &DeepExplanation) -> String {
+        let tokens: Vec<&str> = deep
+            .stages
+            .iter()
+            .flatten()
+            .map(|note| note.token.as_str())
+            .collect();
+        format!(
+            r#"You are an expert ops instructor teaching a beginner.
+
+Explain what each token of this shell command does here.
+
+Command: {command}
+
+Tokens, one per line:
+{}
+
+Reply with exactly one line per token, in the same order, formatted as:
+<token> :: <what it does in this command, under 60 characters>
+
+For a flag's value, say what the value is for (e.g. "namespace to look in").
+No other text, no markdown."#,
+            tokens.join("\n")
+        )
+    }
+
+    /// Fill in meanings from `<token> :: <meaning>` lines, matched in order
+    fn apply_token_meanings(deep: &mut DeepExplanation, reply: &str) {
+        let mut notes = deep.stages.iter_mut().flatten();
+        for line in reply.lines() {
+            let Some((token, meaning)) = line.split_once(" :: ") else {
+                continue;
+            };
+            let (token, meaning) = (token.trim().trim_matches('`'), meaning.trim());
+            if meaning.is_empty() {
+                continue;
+            }
+            // Skip notes the model left out until the token matches
+            if let Some(note) = notes.by_ref().find(|note| note.token == token) {
+                note.meaning = meaning.to_string();
+            }
+        }
+    }
+
     fn get_base_command_desc(cmd: &str) -> &'static str {
         match cmd {
             "kubectl" => "Kubernetes command-line tool",
@@ -284,6 +492,72 @@ Focus on teaching the CONCEPT, not just describing syntax."#
     }
 }
 
+/// Split a command line into pipeline stages of tokens
+///
+/// Quotes group words and are removed, as the shell would; `|`, `||`, `&&`
+/// and `;` outside quotes end a stage.
+pub fn tokenize_command(command: &str) -> Result<Vec<Vec<String>>> {
+    let mut stages = Vec::new();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    let mut end_stage = |tokens: &mut Vec<String>| {
+        if !tokens.is_empty() {
+            stages.push(std::mem::take(tokens));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) => {
+                token.extend(chars.next());
+            }
+            (Some(_), c) => token.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_token = true;
+            }
+            (None, '\\') => {
+                token.extend(chars.next());
+                in_token = true;
+            }
+            (None, '|' | '&' | ';') if c != '&' || chars.peek() == Some(&'&') => {
+                if c != ';' && chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                if in_token {
+                    tokens.push(std::mem::take(&mut token));
+                    in_token = false;
+                }
+                end_stage(&mut tokens);
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut token));
+                    in_token = false;
+                }
+            }
+            (None, c) => {
+                token.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if let Some(q) = quote {
+        anyhow::bail!("Unterminated {q} quote in: {command}");
+    }
+    if in_token {
+        tokens.push(token);
+    }
+    end_stage(&mut tokens);
+    Ok(stages)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -305,4 +579,74 @@ mod tests {
         assert!(!formatted.contains("```"));
         assert!(formatted.contains("lsof"));
     }
+
+    #[test]
+    fn test_tokenize_respects_quotes_and_pipelines() {
+        let stages =
+            tokenize_command(r#"grep -E "error|fatal" 'app log.txt' | sort && echo done\ ok"#)
+                .unwrap();
+        assert_eq!(
+            stages,
+            vec![
+                vec!["grep", "-E", "error|fatal", "app log.txt"],
+                vec!["sort"],
+                vec!["echo", "done ok"],
+            ]
+        );
+        assert!(tokenize_command("echo 'oops").is_err());
+        assert_eq!(
+            tokenize_command("sleep 1 &").unwrap(),
+            [["sleep", "1", "&"]]
+        );
+    }
+
+    #[test]
+    fn test_explain_deep_sync_roles() {
+        let deep =
+            CommandExplainer::explain_deep_sync("kubectl get pods -n prod | grep Crash").unwrap();
+        assert_eq!(deep.stages.len(), 2);
+
+        let kubectl = &deep.stages[0];
+        assert_eq!(kubectl[0].role, TokenRole::Command);
+        assert_eq!(kubectl[0].meaning, "Kubernetes command-line tool");
+        assert_eq!(kubectl[1].role, TokenRole::Subcommand);
+        assert_eq!(kubectl[1].meaning, "retrieve resources");
+        assert_eq!(kubectl[3].role, TokenRole::Flag);
+        assert_eq!(kubectl[3].meaning, "specify namespace");
+        assert_eq!(kubectl[4].role, TokenRole::Argument);
+
+        let rendered = deep.render();
+        assert!(rendered.contains("Stage 2 of 2: grep Crash"));
+        assert!(rendered.contains("  kubectl  command     Kubernetes command-line tool\n"));
+        assert!(rendered.contains("  -n       flag        specify namespace\n"));
+    }
+
+    #[test]
+    fn test_apply_token_meanings_in_order() {
+        let mut deep = CommandExplainer::explain_deep_sync("ls -la /tmp | wc -l").unwrap();
+        CommandExplainer::apply_token_meanings(
+            &mut deep,
+            "ls :: list a directory\n\
+             /tmp :: directory to list\n\
+             noise without separator\n\
+             -l :: count lines",
+        );
+
+        let meanings: Vec<&str> = deep
+            .stages
+            .iter()
+            .flatten()
+            .map(|note| note.meaning.as_str())
+            .collect();
+        assert_eq!(
+            meanings,
+            [
+                "list a directory",
+                "",
+                "directory to list",
+                "command-line tool",
+                "count lines"
+            ]
+        );
+    }
 }
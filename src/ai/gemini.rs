@@ -1,4 +1,8 @@
 // Gemini AI Backend
+use super::chat::{ChatMessage, ChatRole};
+use super::rate_limit::RateLimiter;
+use super::tokens::estimate_tokens;
+use crate::config::GeminiConfig;
 use crate::tools::{LLMBackend, LLMResponse};
 use anyhow::Result;
 use async_trait::async_trait;
@@ -10,13 +14,53 @@ const GEMINI_API_URL: &str =
 #[derive(Debug, Serialize)]
 struct GeminiRequest {
     contents: Vec<GeminiContent>,
+    #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
 }
 
 #[derive(Debug, Serialize)]
 struct GeminiContent {
+    /// "user" or "model"; omitted for single-turn requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
     parts: Vec<GeminiPart>,
 }
 
+impl GeminiContent {
+    fn text(role: Option<&'static str>, text: &str) -> Self {
+        Self {
+            role,
+            parts: vec![GeminiPart {
+                text: text.to_string(),
+            }],
+        }
+    }
+}
+
+impl GeminiRequest {
+    /// Multi-turn request; system messages become the system instruction
+    fn from_messages(messages: &[ChatMessage]) -> Self {
+        let system: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == ChatRole::System)
+            .map(|m| m.content.as_str())
+            .collect();
+
+        Self {
+            contents: messages
+                .iter()
+                .filter_map(|m| match m.role {
+                    ChatRole::System => None,
+                    ChatRole::User => Some(GeminiContent::text(Some("user"), &m.content)),
+                    ChatRole::Assistant => Some(GeminiContent::text(Some("model"), &m.content)),
+                })
+                .collect(),
+            system_instruction: (!system.is_empty())
+                .then(|| GeminiContent::text(None, &system.join("\n\n"))),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct GeminiPart {
     text: String,
@@ -25,6 +69,16 @@ struct GeminiPart {
 #[derive(Debug, Deserialize)]
 struct GeminiResponse {
     candidates: Vec<GeminiCandidate>,
+    #[serde(rename = "usageMetadata", default)]
+    usage_metadata: Option<GeminiUsage>,
+}
+
+/// Token usage as reported by the API
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    prompt_token_count: Option<u32>,
+    candidates_token_count: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +99,10 @@ struct GeminiPartResponse {
 pub struct GeminiBackend {
     api_key: String,
     client: reqwest::Client,
+    /// Client-side requests-per-minute limit
+    limiter: RateLimiter,
+    /// Wait for the limiter instead of failing fast
+    wait_when_limited: bool,
 }
 
 impl GeminiBackend {
@@ -56,14 +114,21 @@ impl GeminiBackend {
     ///
     /// Get your API key from: https://aistudio.google.com/app/apikey
     pub fn new() -> Self {
-        let api_key = Self::load_api_key();
+        Self::with_api_key(Self::load_api_key())
+    }
 
-        Self {
-            api_key,
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .expect("Failed to build reqwest client"),
+    /// Apply the requests-per-minute limit from config
+    pub fn with_rate_limit(mut self, config: &GeminiConfig) -> Self {
+        self.limiter = RateLimiter::per_minute(config.requests_per_minute);
+        self.wait_when_limited = config.wait_when_limited;
+        self
+    }
+
+    /// Requests left this minute and the per-minute limit, if limited
+    pub fn quota(&self) -> Option<(u32, u32)> {
+        match self.limiter.limit() {
+            0 => None,
+            limit => Some((self.limiter.remaining(), limit)),
         }
     }
 
@@ -92,21 +157,20 @@ impl GeminiBackend {
         String::new()
     }
 
-    /// Create Gemini backend with explicit API key
-    pub fn with_api_key(api_key: String) -> Self {
-        Self {
-            api_key,
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .expect("Failed to build reqwest client"),
-        }
+    /// Whether an API key is configured
+    pub fn has_api_key(&self) -> bool {
+        !self.api_key.is_empty()
     }
-}
 
-#[async_trait]
-impl LLMBackend for GeminiBackend {
-    async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
+    /// Infer with conversation history as a multi-turn request
+    pub async fn infer_chat(&self, messages: &[ChatMessage]) -> Result<LLMResponse> {
+        let prompt: String = messages.iter().map(|m| m.content.as_str()).collect();
+        self.generate(&GeminiRequest::from_messages(messages), &prompt)
+            .await
+    }
+
+    /// Send a request; `prompt` is only used to estimate token usage
+    async fn generate(&self, request: &GeminiRequest, prompt: &str) -> Result<LLMResponse> {
         // Check if API key is configured
         if self.api_key.is_empty() {
             return Err(anyhow::anyhow!(
@@ -119,23 +183,34 @@ impl LLMBackend for GeminiBackend {
             ));
         }
 
-        log::info!("[AI] Calling Gemini API...");
+        if let Err(wait) = self.limiter.try_acquire() {
+            if !self.wait_when_limited {
+                return Err(anyhow::anyhow!(
+                    "Gemini quota: 0 of {} requests left this minute, next one in {}s.\n\
+                    Raise gemini.requests_per_minute if your plan allows more.",
+                    self.limiter.limit(),
+                    wait.as_secs().max(1)
+                ));
+            }
+            log::info!("[AI] Gemini rate limit reached, waiting {wait:?}");
+            self.limiter.acquire().await;
+        }
 
-        let request = GeminiRequest {
-            contents: vec![GeminiContent {
-                parts: vec![GeminiPart {
-                    text: prompt.to_string(),
-                }],
-            }],
-        };
+        log::info!("[AI] Calling Gemini API...");
 
         let url = format!("{}?key={}", GEMINI_API_URL, self.api_key);
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        let response = self.client.post(&url).json(request).send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(anyhow::anyhow!(
+                    "Gemini quota exceeded on the server ({status}). Wait a minute, or lower \
+                    gemini.requests_per_minute to match your plan.\n{error_text}"
+                ));
+            }
             return Err(anyhow::anyhow!("Gemini API error ({status}): {error_text}"));
         }
 
@@ -150,13 +225,56 @@ impl LLMBackend for GeminiBackend {
 
         log::info!("[OK] Gemini response successful");
 
+        let (prompt_tokens, completion_tokens) =
+            token_counts(gemini_response.usage_metadata.as_ref(), prompt, &text);
+        log::debug!("[AI] Gemini tokens: prompt={prompt_tokens}, completion={completion_tokens}");
+
         // 返回 LLMResponse
         Ok(LLMResponse {
             command: extract_command(&text).unwrap_or_default(),
             confidence: 85,
             reasoning: text,
+            prompt_tokens: Some(prompt_tokens),
+            completion_tokens: Some(completion_tokens),
         })
     }
+
+    /// Create Gemini backend with explicit API key
+    pub fn with_api_key(api_key: String) -> Self {
+        let limits = GeminiConfig::default();
+        Self {
+            api_key,
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .expect("Failed to build reqwest client"),
+            limiter: RateLimiter::per_minute(limits.requests_per_minute),
+            wait_when_limited: limits.wait_when_limited,
+        }
+    }
+}
+
+#[async_trait]
+impl LLMBackend for GeminiBackend {
+    async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
+        let request = GeminiRequest {
+            contents: vec![GeminiContent::text(None, prompt)],
+            system_instruction: None,
+        };
+        self.generate(&request, prompt).await
+    }
+}
+
+/// Token counts for a request, preferring the API's reported usage
+/// and falling back to an estimate for anything it leaves out
+fn token_counts(usage: Option<&GeminiUsage>, prompt: &str, completion: &str) -> (u32, u32) {
+    let prompt_tokens = usage
+        .and_then(|u| u.prompt_token_count)
+        .unwrap_or_else(|| estimate_tokens(prompt));
+    let completion_tokens = usage
+        .and_then(|u| u.candidates_token_count)
+        .unwrap_or_else(|| estimate_tokens(completion));
+    (prompt_tokens, completion_tokens)
 }
 
 /// 從 AI 回應中提取命令
@@ -184,3 +302,68 @@ impl Default for GeminiBackend {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_usage_metadata() {
+        let json = r#"{
+            "candidates": [{"content": {"parts": [{"text": "ok"}]}}],
+            "usageMetadata": {"promptTokenCount": 42, "candidatesTokenCount": 7, "totalTokenCount": 49}
+        }"#;
+        let response: GeminiResponse = serde_json::from_str(json).unwrap();
+
+        let counts = token_counts(response.usage_metadata.as_ref(), "prompt", "ok");
+        assert_eq!(counts, (42, 7));
+    }
+
+    #[test]
+    fn test_multi_turn_request() {
+        let request = GeminiRequest::from_messages(&[
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Explain the error"),
+            ChatMessage::assistant("The port is taken."),
+            ChatMessage::user("why?"),
+        ]);
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "contents": [
+                    {"role": "user", "parts": [{"text": "Explain the error"}]},
+                    {"role": "model", "parts": [{"text": "The port is taken."}]},
+                    {"role": "user", "parts": [{"text": "why?"}]},
+                ],
+                "systemInstruction": {"parts": [{"text": "Be brief."}]},
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fails_fast_when_limited() {
+        let gemini =
+            GeminiBackend::with_api_key("test-key".to_string()).with_rate_limit(&GeminiConfig {
+                requests_per_minute: 1,
+                wait_when_limited: false,
+                ..GeminiConfig::default()
+            });
+        // Use up the only request without touching the network
+        gemini.limiter.try_acquire().unwrap();
+        assert_eq!(gemini.quota(), Some((0, 1)));
+
+        let err = gemini.infer("hi").await.unwrap_err().to_string();
+        assert!(err.contains("0 of 1 requests left this minute"), "{err}");
+    }
+
+    #[test]
+    fn test_token_counts_fall_back_to_estimate() {
+        let json = r#"{"candidates": [{"content": {"parts": [{"text": "ok"}]}}]}"#;
+        let response: GeminiResponse = serde_json::from_str(json).unwrap();
+        assert!(response.usage_metadata.is_none());
+
+        let counts = token_counts(None, "kubectl get pods", "ok");
+        assert_eq!(counts, (4, 1));
+    }
+}
@@ -0,0 +1,187 @@
+// Tolerant JSON extraction from LLM responses
+//
+// Models asked for "JSON only" still wrap it in ```json fences, add a
+// sentence before or after, or leave trailing commas. These helpers dig the
+// first balanced object out of such a response and clean it up so serde can
+// parse it.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Parse a model response as JSON, tolerating fences, prose and trailing commas
+///
+/// Tries the raw text first, then the extracted object.
+pub fn parse_json<T: DeserializeOwned>(response: &str) -> Result<T> {
+    if let Ok(parsed) = serde_json::from_str(response.trim()) {
+        return Ok(parsed);
+    }
+
+    let json = extract_json(response).context("No JSON object found in AI response")?;
+    serde_json::from_str(&json).context("AI returned invalid JSON")
+}
+
+/// Extract the first balanced `{...}` object from a model response
+///
+/// Code fences are stripped and trailing commas before `}` or `]` are
+/// removed. Braces inside strings are ignored. Returns None when no
+/// complete object is present.
+pub fn extract_json(response: &str) -> Option<String> {
+    let object = first_balanced_object(strip_code_fence(response))
+        .or_else(|| first_balanced_object(response))?;
+    Some(remove_trailing_commas(object))
+}
+
+/// Return the contents of the first ``` fenced block, or the whole text
+fn strip_code_fence(text: &str) -> &str {
+    let Some(start) = text.find("```") else {
+        return text;
+    };
+
+    // Skip the language tag (```json) up to the end of the line
+    let after_fence = &text[start + 3..];
+    let body_start = after_fence.find('\n').map(|i| i + 1).unwrap_or(0);
+    let body = &after_fence[body_start..];
+
+    match body.find("```") {
+        Some(end) => &body[..end],
+        None => body,
+    }
+}
+
+/// Find the first `{` and its matching `}`, skipping braces inside strings
+fn first_balanced_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text[start..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[start..start + i + 1]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Drop commas that directly precede `}` or `]` (outside strings)
+fn remove_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut output = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            output.push(c);
+            continue;
+        }
+
+        if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        output.push(c);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Reply {
+        command: String,
+        confidence: u8,
+    }
+
+    fn reply(command: &str, confidence: u8) -> Reply {
+        Reply {
+            command: command.to_string(),
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_parse_plain_json() {
+        let parsed: Reply =
+            parse_json(r#"{"command": "kubectl get pods", "confidence": 90}"#).unwrap();
+        assert_eq!(parsed, reply("kubectl get pods", 90));
+    }
+
+    #[test]
+    fn test_parse_fenced_json() {
+        let response = "```json\n{\"command\": \"kubectl get pods\", \"confidence\": 85}\n```";
+        let parsed: Reply = parse_json(response).unwrap();
+        assert_eq!(parsed, reply("kubectl get pods", 85));
+    }
+
+    #[test]
+    fn test_parse_json_with_prose() {
+        let response = "Sure! Here is the command you asked for:\n\
+            {\"command\": \"kubectl logs api\", \"confidence\": 80}\n\
+            Let me know if you need anything else {or more help}.";
+        let parsed: Reply = parse_json(response).unwrap();
+        assert_eq!(parsed, reply("kubectl logs api", 80));
+    }
+
+    #[test]
+    fn test_parse_json_with_trailing_commas() {
+        let response = r#"```
+{
+  "command": "kubectl get pods -l 'app in (a,b)'",
+  "confidence": 75,
+}
+```"#;
+        let parsed: Reply = parse_json(response).unwrap();
+        assert_eq!(parsed, reply("kubectl get pods -l 'app in (a,b)'", 75));
+
+        let json = extract_json(r#"{"items": [1, 2, ], "note": "a, }",}"#).unwrap();
+        assert_eq!(json, r#"{"items": [1, 2 ], "note": "a, }"}"#);
+    }
+
+    #[test]
+    fn test_braces_inside_strings() {
+        let response = r#"Result: {"command": "echo \"}{\"", "confidence": 60} trailing }"#;
+        let parsed: Reply = parse_json(response).unwrap();
+        assert_eq!(parsed, reply(r#"echo "}{""#, 60));
+    }
+
+    #[test]
+    fn test_no_json_is_an_error() {
+        assert!(extract_json("I could not translate that.").is_none());
+        assert!(extract_json("{\"command\": \"unterminated\"").is_none());
+        assert!(parse_json::<Reply>("no json here").is_err());
+    }
+}
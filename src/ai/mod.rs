@@ -1,18 +1,55 @@
+pub mod chat;
+pub mod circuit;
 pub mod copilot;
 pub mod explainer;
 pub mod gemini;
+pub mod json;
 pub mod ollama;
+pub mod rate_limit;
+pub mod redact;
+pub mod tokens;
+pub mod truncate;
 
+pub use chat::{ChatMessage, ChatRole, Conversation};
+pub use circuit::CircuitBreaker;
 pub use copilot::CopilotBackend;
-pub use explainer::CommandExplainer;
+pub use explainer::{CommandExplainer, DeepExplanation, TokenNote, TokenRole};
 pub use gemini::GeminiBackend;
-pub use ollama::{ModelRecommendation, OllamaBackend, OllamaStatus};
+pub use json::{extract_json, parse_json};
+pub use ollama::{ModelRecommendation, OllamaBackend, OllamaHealth, OllamaStatus};
+pub use redact::redact_secrets;
+pub use tokens::estimate_tokens;
+pub use truncate::OutputBudget;
 
 use crate::config::{AIProvider, Config};
 use crate::kubectl::{KubectlContext, TranslationResult};
-use crate::tools::{LLMBackend, LLMResponse};
+use crate::tools::{LLMBackend, LLMResponse, Tool, ToolContext, ToolRegistry, Translation};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::time::Duration;
+
+/// Environment variable that turns on offline mode (`kaido --no-ai` sets it)
+pub const OFFLINE_ENV: &str = "KAIDO_OFFLINE";
+
+/// Whether AI is disabled for this process
+///
+/// Offline mode guarantees no model calls and no availability probes, for
+/// air-gapped machines. Mentor guidance falls back to built-in patterns.
+pub fn offline_mode() -> bool {
+    std::env::var(OFFLINE_ENV).is_ok_and(|value| {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+/// A single prompt or a conversation, sent to whichever backend serves it
+#[derive(Clone, Copy)]
+enum Request<'a> {
+    Prompt(&'a str),
+    Chat(&'a [ChatMessage]),
+}
 
 /// AI Manager - Handles inference with multiple backends
 /// Supports: Gemini API, Ollama (local), GitHub Copilot
@@ -21,16 +58,127 @@ pub struct AIManager {
     ollama: OllamaBackend,
     copilot: CopilotBackend,
     provider: AIProvider,
+    /// Refuse every request without touching the network
+    offline: bool,
+    /// Skips Gemini in auto mode while it keeps failing
+    gemini_breaker: CircuitBreaker,
 }
 
 impl AIManager {
     /// Create a new AI manager with config
+    ///
+    /// Starts offline when [`offline_mode`] is on.
     pub fn new(config: Config) -> Self {
         Self {
-            gemini: GeminiBackend::new(),
+            gemini: GeminiBackend::new().with_rate_limit(&config.gemini),
             ollama: OllamaBackend::with_config(config.ollama.clone()),
             copilot: CopilotBackend::with_config(config.copilot.clone()),
             provider: config.provider.clone(),
+            offline: offline_mode(),
+            gemini_breaker: CircuitBreaker::new(
+                "Gemini",
+                config.gemini.skip_after_failures,
+                Duration::from_secs(config.gemini.skip_cooldown_seconds),
+            ),
+        }
+    }
+
+    /// Force offline mode on or off
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Whether every request is refused (`--no-ai` / `KAIDO_OFFLINE=1`)
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Check that the configured provider can actually serve requests
+    ///
+    /// Returns an error describing what is missing (API key, unreachable
+    /// Ollama, missing model) so callers can warn and fall back.
+    pub async fn check_provider(&self) -> Result<()> {
+        if self.offline {
+            return Err(offline_error());
+        }
+        match &self.provider {
+            AIProvider::Gemini => {
+                if self.gemini.has_api_key() {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Gemini API key not configured (set GEMINI_API_KEY)"
+                    ))
+                }
+            }
+            AIProvider::Ollama => {
+                let models = self.ollama.list_models().await.map_err(|_| {
+                    anyhow::anyhow!("Ollama is not reachable (is `ollama serve` running?)")
+                })?;
+                let model = self.ollama.model_name();
+                if models
+                    .iter()
+                    .any(|m| m == model || m.split(':').next() == Some(model))
+                {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Ollama model '{model}' is not pulled (run `ollama pull {model}`)"
+                    ))
+                }
+            }
+            AIProvider::Copilot => {
+                if self.copilot.is_available() {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "Copilot token not found (set GITHUB_COPILOT_TOKEN)"
+                    ))
+                }
+            }
+            AIProvider::Auto => {
+                if self.gemini.has_api_key()
+                    || self.copilot.is_available()
+                    || self.ollama.is_available().await
+                {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!(
+                        "No AI backend available (no Gemini key, Ollama unreachable, no Copilot token)"
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Whether prompts may leave the machine (any provider but Ollama)
+    pub fn may_use_cloud(&self) -> bool {
+        !self.offline && !matches!(self.provider, AIProvider::Ollama)
+    }
+
+    /// Load state of the Ollama model, when Ollama may serve requests
+    ///
+    /// Returns None for providers that never use Ollama.
+    pub async fn ollama_health(&self) -> Option<OllamaHealth> {
+        if self.offline {
+            return None;
+        }
+        match &self.provider {
+            AIProvider::Ollama | AIProvider::Auto => Some(self.ollama.health().await),
+            AIProvider::Gemini | AIProvider::Copilot => None,
+        }
+    }
+
+    /// Gemini requests left this minute and the limit, when Gemini may
+    /// serve requests
+    pub fn gemini_quota(&self) -> Option<(u32, u32)> {
+        if self.offline || !self.gemini.has_api_key() {
+            return None;
+        }
+        match &self.provider {
+            AIProvider::Gemini | AIProvider::Auto => self.gemini.quota(),
+            AIProvider::Ollama | AIProvider::Copilot => None,
         }
     }
 
@@ -63,10 +211,7 @@ impl AIManager {
         let response_text = self
             .infer(&prompt)
             .await
-            .map_err(|e| crate::utils::KaidoError::ModelError {
-                message: e.to_string(),
-                model_name: "ai".to_string(),
-            })?
+            .map_err(|e| crate::utils::KaidoError::model("ai", "inference failed", e))?
             .reasoning;
 
         // Parse JSON response
@@ -77,40 +222,142 @@ impl AIManager {
             reasoning: String,
         }
 
-        match serde_json::from_str::<KubectlResponse>(&response_text) {
+        match json::parse_json::<KubectlResponse>(&response_text) {
             Ok(parsed) => {
                 log::info!("Kubectl translation successful: {}", parsed.command);
-                Ok(TranslationResult {
-                    kubectl_command: parsed.command,
-                    confidence_score: parsed.confidence,
-                    reasoning: parsed.reasoning,
-                })
+                TranslationResult::new(parsed.command, parsed.confidence, parsed.reasoning)
+                    .with_output_format(input)
+                    .map_err(|e| {
+                        crate::utils::KaidoError::model("ai", "invalid kubectl translation", e)
+                    })
             }
             Err(e) => {
-                log::warn!("Failed to parse AI output as JSON: {e}");
-                Err(crate::utils::KaidoError::ModelError {
-                    message: format!("AI returned invalid JSON: {e}"),
-                    model_name: "ai".to_string(),
-                })
+                log::warn!("Failed to parse AI output as JSON: {e:#}");
+                Err(crate::utils::KaidoError::model(
+                    "ai",
+                    "reply is not the expected JSON",
+                    e,
+                ))
             }
         }
     }
 
+    /// Translate natural language into a command for `tool`
+    ///
+    /// The tool builds its own prompt (nginx knows `nginx -t`, docker knows
+    /// `docker ps`), so this works for every registered tool, not just
+    /// kubectl. Rate the result with `tool.classify_risk` before running it.
+    pub async fn translate(
+        &self,
+        input: &str,
+        tool: &dyn Tool,
+        context: &ToolContext,
+    ) -> Result<Translation> {
+        if self.offline {
+            return Err(offline_error());
+        }
+        log::info!("Attempting {} translation", tool.name());
+
+        let mut translation = tool.translate(input, context, self).await?;
+        // Models like to wrap the command in backticks
+        translation.command = translation
+            .command
+            .trim()
+            .trim_matches('`')
+            .trim()
+            .to_string();
+        if translation.command.is_empty() {
+            anyhow::bail!("The {} translation came back empty", tool.name());
+        }
+        if translation.tool_name.is_empty() {
+            translation.tool_name = tool.name().to_string();
+        }
+        Ok(translation)
+    }
+
+    /// Translate natural language, picking the tool from `registry`
+    ///
+    /// Errors without asking the model when no tool matches the request.
+    pub async fn translate_input(
+        &self,
+        input: &str,
+        registry: &ToolRegistry,
+        context: &ToolContext,
+    ) -> Result<Translation> {
+        let tool = registry.detect_tool(input).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Couldn't tell which tool this is for; try naming it (kubectl, docker, nginx...)"
+            )
+        })?;
+        self.translate(input, tool, context).await
+    }
+
+    /// Infer with the prior turns of a conversation
+    ///
+    /// The last message is normally the new user question. Each backend
+    /// receives the history as native multi-turn messages.
+    pub async fn infer_with_context(&self, messages: &[ChatMessage]) -> Result<LLMResponse> {
+        self.infer_with_provider(Request::Chat(messages)).await
+    }
+
+    async fn call_gemini(&self, request: Request<'_>) -> Result<LLMResponse> {
+        match request {
+            Request::Prompt(prompt) => self.gemini.infer(prompt).await,
+            Request::Chat(messages) => self.gemini.infer_chat(messages).await,
+        }
+    }
+
+    /// Gemini for auto mode, skipped while the circuit breaker is open
+    ///
+    /// Only failures with an API key configured count; without one Gemini
+    /// fails instantly and there's no timeout to save.
+    async fn call_gemini_guarded(&self, request: Request<'_>) -> Result<LLMResponse> {
+        if !self.gemini_breaker.allow() {
+            let wait = self.gemini_breaker.remaining_cooldown().unwrap_or_default();
+            return Err(anyhow::anyhow!(
+                "skipped after repeated failures (next try in {}s)",
+                wait.as_secs()
+            ));
+        }
+        let result = self.call_gemini(request).await;
+        if self.gemini.has_api_key() {
+            self.gemini_breaker.record(result.is_ok());
+        }
+        result
+    }
+
+    async fn call_ollama(&self, request: Request<'_>) -> Result<LLMResponse> {
+        match request {
+            Request::Prompt(prompt) => self.ollama.infer(prompt).await,
+            Request::Chat(messages) => self.ollama.infer_chat(messages).await,
+        }
+    }
+
+    async fn call_copilot(&self, request: Request<'_>) -> Result<LLMResponse> {
+        match request {
+            Request::Prompt(prompt) => self.copilot.infer(prompt).await,
+            Request::Chat(messages) => self.copilot.infer_chat(messages).await,
+        }
+    }
+
     /// Infer using the configured provider strategy
-    async fn infer_with_provider(&self, prompt: &str) -> Result<LLMResponse> {
+    async fn infer_with_provider(&self, request: Request<'_>) -> Result<LLMResponse> {
+        if self.offline {
+            return Err(offline_error());
+        }
         match &self.provider {
             AIProvider::Gemini => {
                 log::info!("Using Gemini API (configured)");
-                self.gemini.infer(prompt).await
+                self.call_gemini(request).await
             }
             AIProvider::Ollama => {
                 log::info!("Using Ollama (configured)");
-                self.ollama.infer(prompt).await
+                self.call_ollama(request).await
             }
             AIProvider::Copilot => {
                 log::info!("Using GitHub Copilot (configured)");
                 if self.copilot.is_available() {
-                    self.copilot.infer(prompt).await
+                    self.call_copilot(request).await
                 } else {
                     Err(anyhow::anyhow!(
                         "Copilot not available. Set GITHUB_COPILOT_TOKEN environment variable."
@@ -120,7 +367,7 @@ impl AIManager {
             AIProvider::Auto => {
                 // Auto: Try Gemini first, then Ollama, then Copilot
                 log::info!("Auto mode: trying Gemini API first");
-                match self.gemini.infer(prompt).await {
+                match self.call_gemini_guarded(request).await {
                     Ok(response) => {
                         log::info!("[OK] Gemini API successful");
                         Ok(response)
@@ -128,16 +375,16 @@ impl AIManager {
                     Err(gemini_err) => {
                         log::warn!("Gemini failed: {gemini_err}, trying Ollama");
 
-                        match self.ollama.infer(prompt).await {
+                        match self.call_ollama(request).await {
                             Ok(response) => {
                                 log::info!("[OK] Ollama successful");
                                 Ok(response)
                             }
                             Err(ollama_err) => {
                                 log::warn!("Ollama failed: {ollama_err}, trying Copilot");
-                                
+
                                 if self.copilot.is_available() {
-                                    match self.copilot.infer(prompt).await {
+                                    match self.call_copilot(request).await {
                                         Ok(response) => {
                                             log::info!("[OK] Copilot successful");
                                             Ok(response)
@@ -172,10 +419,95 @@ impl AIManager {
     }
 }
 
+fn offline_error() -> anyhow::Error {
+    anyhow::anyhow!("AI is disabled in offline mode (--no-ai or {OFFLINE_ENV}=1)")
+}
+
 // Implement LLMBackend trait for AIManager
 #[async_trait]
 impl LLMBackend for AIManager {
     async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
-        self.infer_with_provider(prompt).await
+        self.infer_with_provider(Request::Prompt(prompt)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_offline_refuses_requests() {
+        let manager = AIManager::new(Config::default()).with_offline(true);
+        assert!(manager.is_offline());
+        assert!(!manager.may_use_cloud());
+        assert!(manager.ollama_health().await.is_none());
+
+        let err = manager.infer("explain ls").await.unwrap_err();
+        assert!(err.to_string().contains("offline mode"));
+        assert!(manager.check_provider().await.is_err());
+        let tool = crate::tools::NginxTool::new();
+        assert!(manager
+            .translate("restart nginx", &tool, &ToolContext::default())
+            .await
+            .is_err());
+    }
+
+    /// Answers without asking the model, like a tool with a canned reply
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn detect_intent(&self, _input: &str) -> f32 {
+            1.0
+        }
+
+        async fn translate(
+            &self,
+            input: &str,
+            _context: &ToolContext,
+            _llm: &dyn LLMBackend,
+        ) -> Result<Translation> {
+            Ok(Translation {
+                command: format!(" `echo {input}` "),
+                confidence: 90,
+                reasoning: "prints it".to_string(),
+                tool_name: String::new(),
+                requires_files: Vec::new(),
+            })
+        }
+
+        fn classify_risk(&self, _command: &str, _context: &ToolContext) -> crate::tools::RiskLevel {
+            crate::tools::RiskLevel::Low
+        }
+
+        async fn execute(&self, _command: &str) -> Result<crate::tools::ExecutionResult> {
+            unreachable!("not run in tests")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_translate_normalizes_command() {
+        let manager = AIManager::new(Config::default()).with_offline(false);
+        let translation = manager
+            .translate("hi", &EchoTool, &ToolContext::default())
+            .await
+            .unwrap();
+        assert_eq!(translation.command, "echo hi");
+        assert_eq!(translation.tool_name, "echo");
+        assert_eq!(translation.confidence, 90);
+
+        let err = manager
+            .translate_input(
+                "make me a sandwich",
+                &ToolRegistry::new(),
+                &ToolContext::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("which tool"));
     }
 }
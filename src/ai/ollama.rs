@@ -1,4 +1,5 @@
 // Ollama AI Backend - Local LLM inference via Ollama REST API
+use super::chat::ChatMessage;
 use crate::config::OllamaConfig;
 use crate::tools::{LLMBackend, LLMResponse};
 use anyhow::Result;
@@ -19,6 +20,20 @@ struct OllamaResponse {
     response: String,
 }
 
+/// Ollama chat API request structure
+#[derive(Debug, Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+}
+
+/// Ollama chat API response structure
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    message: ChatMessage,
+}
+
 /// Ollama API error response
 #[derive(Debug, Deserialize)]
 struct OllamaError {
@@ -47,6 +62,89 @@ impl OllamaBackend {
         Self { config, client }
     }
 
+    /// Infer with conversation history via the chat endpoint
+    pub async fn infer_chat(&self, messages: &[ChatMessage]) -> Result<LLMResponse> {
+        log::info!(
+            "[AI] Calling Ollama chat API (model: {}, {} messages)...",
+            self.config.model,
+            messages.len()
+        );
+
+        let request = OllamaChatRequest {
+            model: &self.config.model,
+            messages,
+            stream: false,
+        };
+
+        let response = self.post("/api/chat", &request).await?;
+        let chat_response: OllamaChatResponse = response.json().await?;
+
+        log::info!("[OK] Ollama response successful");
+
+        Ok(Self::to_llm_response(chat_response.message.content.trim()))
+    }
+
+    /// POST to the Ollama API, turning failures into actionable errors
+    async fn post(&self, path: &str, body: &impl Serialize) -> Result<reqwest::Response> {
+        let url = format!("{}{path}", self.config.base_url);
+
+        let response = self.client
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    anyhow::anyhow!(
+                        "Cannot connect to Ollama at {}. Is Ollama running?\n\
+                        Start with: ollama serve",
+                        self.config.base_url
+                    )
+                } else if e.is_timeout() {
+                    anyhow::anyhow!(
+                        "Ollama request timed out after {}s. Try a smaller model or increase timeout.",
+                        self.config.timeout_seconds
+                    )
+                } else {
+                    anyhow::anyhow!("Ollama request failed: {e}")
+                }
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+
+            // Try to parse error message
+            if let Ok(err) = serde_json::from_str::<OllamaError>(&error_text) {
+                if err.error.contains("not found") {
+                    return Err(anyhow::anyhow!(
+                        "Model '{}' not found. Install with: ollama pull {}",
+                        self.config.model,
+                        self.config.model
+                    ));
+                }
+                return Err(anyhow::anyhow!("Ollama error: {}", err.error));
+            }
+
+            return Err(anyhow::anyhow!("Ollama API error ({status}): {error_text}"));
+        }
+
+        Ok(response)
+    }
+
+    fn to_llm_response(text: &str) -> LLMResponse {
+        // Extract command from response
+        let command = extract_command(text).unwrap_or_default();
+
+        LLMResponse {
+            command,
+            confidence: 80,
+            reasoning: text.to_string(),
+            prompt_tokens: None,
+            completion_tokens: None,
+        }
+    }
+
     /// Check if Ollama is running and accessible
     pub async fn is_available(&self) -> bool {
         let url = format!("{}/api/tags", self.config.base_url);
@@ -110,6 +208,44 @@ impl OllamaBackend {
         })
     }
 
+    /// Check whether the configured model is loaded and how big it is
+    ///
+    /// Uses `/api/ps`. Ollama versions that predate it report
+    /// `model_loaded: None` instead of failing.
+    pub async fn health(&self) -> OllamaHealth {
+        let mut health = OllamaHealth {
+            reachable: false,
+            model: self.config.model.clone(),
+            model_loaded: None,
+            size_bytes: None,
+            size_vram_bytes: None,
+            context_length: None,
+        };
+
+        let url = format!("{}/api/ps", self.config.base_url);
+        let response = match self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(HEALTH_TIMEOUT_SECS))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(_) => return health,
+        };
+
+        // Servers without /api/ps answer 404: up, but load state unknown
+        health.reachable = true;
+        if !response.status().is_success() {
+            return health;
+        }
+
+        if let Ok(ps) = response.json::<PsResponse>().await {
+            health.apply(&ps);
+        }
+        health
+    }
+
     /// Recommend a model based on available models
     /// Prioritizes: codestral > qwen2.5 > llama3.2 > mistral > others
     pub fn recommend_model(available_models: &[String]) -> Option<String> {
@@ -180,6 +316,107 @@ pub struct OllamaStatus {
     pub recommended_model: Option<String>,
 }
 
+/// How long `health` waits for `/api/ps`
+const HEALTH_TIMEOUT_SECS: u64 = 3;
+
+/// `/api/ps` response: models currently loaded in memory
+#[derive(Debug, Deserialize)]
+struct PsResponse {
+    #[serde(default)]
+    models: Vec<RunningModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunningModel {
+    name: String,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    size_vram: Option<u64>,
+    #[serde(default)]
+    context_length: Option<u32>,
+}
+
+/// Load state of the configured Ollama model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OllamaHealth {
+    /// Whether the Ollama server answered
+    pub reachable: bool,
+    /// Configured model name
+    pub model: String,
+    /// Whether the model is loaded (None when the server lacks `/api/ps`)
+    pub model_loaded: Option<bool>,
+    /// Memory used by the loaded model
+    pub size_bytes: Option<u64>,
+    /// Part of the model held in VRAM
+    pub size_vram_bytes: Option<u64>,
+    /// Context window the model was loaded with
+    pub context_length: Option<u32>,
+}
+
+impl OllamaHealth {
+    /// Fill in load state from an `/api/ps` response
+    fn apply(&mut self, ps: &PsResponse) {
+        let running = ps.models.iter().find(|m| same_model(&m.name, &self.model));
+
+        self.model_loaded = Some(running.is_some());
+        if let Some(running) = running {
+            self.size_bytes = running.size;
+            self.size_vram_bytes = running.size_vram;
+            self.context_length = running.context_length;
+        }
+    }
+
+    /// One-line description for `ai status`
+    pub fn summary(&self) -> String {
+        if !self.reachable {
+            return "Ollama not reachable (is `ollama serve` running?)".to_string();
+        }
+
+        match self.model_loaded {
+            None => format!(
+                "{}: load state unknown (Ollama too old for /api/ps)",
+                self.model
+            ),
+            Some(false) => format!("{}: not yet loaded, first request will be slow", self.model),
+            Some(true) => {
+                let mut summary = format!("{}: loaded", self.model);
+                if let Some(size) = self.size_bytes {
+                    summary.push_str(&format!(", {}", format_gb(size)));
+                    if let Some(percent) = self
+                        .size_vram_bytes
+                        .and_then(|vram| (vram * 100).checked_div(size))
+                    {
+                        summary.push_str(&format!(" ({percent}% VRAM)"));
+                    }
+                }
+                if let Some(context) = self.context_length {
+                    summary.push_str(&format!(", {context} token context"));
+                }
+                summary
+            }
+        }
+    }
+}
+
+/// Whether two Ollama model names refer to the same model
+///
+/// An untagged name means `:latest`.
+pub(crate) fn same_model(a: &str, b: &str) -> bool {
+    fn with_tag(name: &str) -> String {
+        if name.contains(':') {
+            name.to_string()
+        } else {
+            format!("{name}:latest")
+        }
+    }
+    with_tag(a) == with_tag(b)
+}
+
+fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+}
+
 /// Model recommendation with system requirements
 #[derive(Debug, Clone)]
 pub struct ModelRecommendation {
@@ -196,8 +433,6 @@ pub struct ModelRecommendation {
 #[async_trait]
 impl LLMBackend for OllamaBackend {
     async fn infer(&self, prompt: &str) -> Result<LLMResponse> {
-        let url = format!("{}/api/generate", self.config.base_url);
-
         log::info!("[AI] Calling Ollama API (model: {})...", self.config.model);
 
         let request = OllamaRequest {
@@ -206,65 +441,17 @@ impl LLMBackend for OllamaBackend {
             stream: false,
         };
 
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_connect() {
-                    anyhow::anyhow!(
-                        "Cannot connect to Ollama at {}. Is Ollama running?\n\
-                        Start with: ollama serve",
-                        self.config.base_url
-                    )
-                } else if e.is_timeout() {
-                    anyhow::anyhow!(
-                        "Ollama request timed out after {}s. Try a smaller model or increase timeout.",
-                        self.config.timeout_seconds
-                    )
-                } else {
-                    anyhow::anyhow!("Ollama request failed: {e}")
-                }
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-
-            // Try to parse error message
-            if let Ok(err) = serde_json::from_str::<OllamaError>(&error_text) {
-                if err.error.contains("not found") {
-                    return Err(anyhow::anyhow!(
-                        "Model '{}' not found. Install with: ollama pull {}",
-                        self.config.model,
-                        self.config.model
-                    ));
-                }
-                return Err(anyhow::anyhow!("Ollama error: {}", err.error));
-            }
-
-            return Err(anyhow::anyhow!("Ollama API error ({status}): {error_text}"));
-        }
-
+        let response = self.post("/api/generate", &request).await?;
         let ollama_response: OllamaResponse = response.json().await?;
 
         log::info!("[OK] Ollama response successful");
 
-        // Extract command from response
-        let text = ollama_response.response.trim();
-        let command = extract_command(text).unwrap_or_default();
-
-        Ok(LLMResponse {
-            command,
-            confidence: 80,
-            reasoning: text.to_string(),
-        })
+        Ok(Self::to_llm_response(ollama_response.response.trim()))
     }
 }
 
 /// Extract command from AI response (looks for code blocks)
-fn extract_command(text: &str) -> Option<String> {
+pub(super) fn extract_command(text: &str) -> Option<String> {
     // Look for code block
     if let Some(start) = text.find("```") {
         if let Some(end) = text[start + 3..].find("```") {
@@ -363,4 +550,59 @@ mod tests {
             assert!(recommendations[i].size_gb >= recommendations[i - 1].size_gb);
         }
     }
+
+    fn health(model: &str) -> OllamaHealth {
+        OllamaHealth {
+            reachable: true,
+            model: model.to_string(),
+            model_loaded: None,
+            size_bytes: None,
+            size_vram_bytes: None,
+            context_length: None,
+        }
+    }
+
+    #[test]
+    fn test_health_from_ps() {
+        let ps: PsResponse = serde_json::from_str(
+            r#"{"models": [{
+                "name": "llama3.2:latest",
+                "model": "llama3.2:latest",
+                "size": 4000000000,
+                "size_vram": 3000000000,
+                "expires_at": "2024-06-04T14:38:31.83753-07:00",
+                "context_length": 8192
+            }]}"#,
+        )
+        .unwrap();
+
+        let mut loaded = health("llama3.2");
+        loaded.apply(&ps);
+        assert_eq!(loaded.model_loaded, Some(true));
+        assert_eq!(loaded.context_length, Some(8192));
+        assert_eq!(
+            loaded.summary(),
+            "llama3.2: loaded, 4.0 GB (75% VRAM), 8192 token context"
+        );
+
+        let mut other = health("qwen2.5:7b");
+        other.apply(&ps);
+        assert_eq!(other.model_loaded, Some(false));
+        assert!(other.summary().contains("first request will be slow"));
+    }
+
+    #[test]
+    fn test_health_without_ps() {
+        assert!(health("llama3.2").summary().contains("load state unknown"));
+
+        let mut down = health("llama3.2");
+        down.reachable = false;
+        assert!(down.summary().contains("not reachable"));
+
+        // Older servers may omit fields
+        let ps: PsResponse = serde_json::from_str(r#"{"models": [{"name": "llama3.2"}]}"#).unwrap();
+        let mut loaded = health("llama3.2:latest");
+        loaded.apply(&ps);
+        assert_eq!(loaded.summary(), "llama3.2:latest: loaded");
+    }
 }
@@ -0,0 +1,123 @@
+// Client-side request rate limiting
+//
+// Gemini's free tier rejects bursts with an opaque 429. A token bucket sized
+// to the plan's requests-per-minute lets us wait for capacity, or fail with
+// a message that says what the quota is, before the server says no.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket allowing `limit` requests per minute, refilled continuously
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `limit` requests per minute; 0 means unlimited
+    pub fn per_minute(limit: u32) -> Self {
+        Self {
+            limit,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(limit),
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Requests allowed per minute (0 = unlimited)
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Requests that can be made right now
+    pub fn remaining(&self) -> u32 {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> u32 {
+        if self.limit == 0 {
+            return u32::MAX;
+        }
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        self.refill(&mut bucket, now);
+        bucket.tokens.floor() as u32
+    }
+
+    /// Use up one request, or return how long until one is available
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        self.refill(&mut bucket, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = 1.0 - bucket.tokens;
+        Err(Duration::from_secs_f64(
+            missing * 60.0 / f64::from(self.limit),
+        ))
+    }
+
+    /// Wait until a request is available and use it up
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        let refill = elapsed.as_secs_f64() * f64::from(self.limit) / 60.0;
+        bucket.tokens = (bucket.tokens + refill).min(f64::from(self.limit));
+        bucket.refilled_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_empties_and_refills() {
+        let limiter = RateLimiter::per_minute(60);
+        let start = Instant::now();
+
+        for _ in 0..60 {
+            assert!(limiter.try_acquire_at(start).is_ok());
+        }
+        assert_eq!(limiter.remaining_at(start), 0);
+        let wait = limiter.try_acquire_at(start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // One request per second comes back
+        let later = start + Duration::from_secs(5);
+        assert_eq!(limiter.remaining_at(later), 5);
+        assert!(limiter.try_acquire_at(later).is_ok());
+        assert_eq!(limiter.remaining_at(later), 4);
+
+        // Never more than a minute's worth
+        assert_eq!(limiter.remaining_at(start + Duration::from_secs(600)), 60);
+    }
+
+    #[test]
+    fn test_zero_limit_is_unlimited() {
+        let limiter = RateLimiter::per_minute(0);
+        for _ in 0..1000 {
+            assert!(limiter.try_acquire().is_ok());
+        }
+    }
+}
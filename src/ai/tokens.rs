@@ -0,0 +1,33 @@
+// Token estimation
+//
+// Rough token counts for prompts and responses when a backend does not
+// report usage. Roughly four characters per token holds well enough for
+// English text and shell output to keep an eye on quota.
+
+/// Approximate characters per token
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Estimate the number of tokens in a piece of text
+pub fn estimate_tokens(text: &str) -> u32 {
+    let chars = text.chars().count();
+    chars.div_ceil(CHARS_PER_TOKEN) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("ls"), 1);
+        assert_eq!(estimate_tokens("kubectl get pods"), 4);
+        assert_eq!(estimate_tokens("kubectl get pods -A"), 5);
+    }
+
+    #[test]
+    fn test_estimate_tokens_counts_chars_not_bytes() {
+        // 4 CJK characters are 12 bytes but should count as one token
+        assert_eq!(estimate_tokens("連線失敗"), 1);
+    }
+}
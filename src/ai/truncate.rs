@@ -0,0 +1,102 @@
+// Command output truncation for prompts
+//
+// Errors are usually at the end of command output (stack traces, the final
+// `error:` line), so long output keeps its head and its tail and drops the
+// middle instead of cutting everything after the first N bytes.
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// How many bytes of command output to keep from each end
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputBudget {
+    /// Bytes kept from the start
+    pub head: usize,
+    /// Bytes kept from the end
+    pub tail: usize,
+}
+
+impl OutputBudget {
+    /// Budget for AI error explanation prompts
+    pub const PROMPT: Self = Self::new(200, 300);
+
+    /// Budget for each diagnostic command in MCP `kaido_diagnose`
+    pub const MCP: Self = Self::new(800, 1200);
+
+    pub const fn new(head: usize, tail: usize) -> Self {
+        Self { head, tail }
+    }
+
+    /// Keep the head and tail of `text`, marking what was cut
+    ///
+    /// Cuts land on char boundaries, so a budget may keep a few bytes less
+    /// than asked for.
+    pub fn truncate<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if text.len() <= self.head + self.tail {
+            return Cow::Borrowed(text);
+        }
+
+        let head_end = floor_char_boundary(text, self.head);
+        let tail_start = ceil_char_boundary(text, text.len() - self.tail);
+        let omitted = tail_start - head_end;
+
+        Cow::Owned(format!(
+            "{}\n…truncated {omitted} bytes…\n{}",
+            &text[..head_end],
+            &text[tail_start..]
+        ))
+    }
+}
+
+impl Default for OutputBudget {
+    fn default() -> Self {
+        Self::PROMPT
+    }
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_output_untouched() {
+        let budget = OutputBudget::new(5, 5);
+        assert!(matches!(budget.truncate("0123456789"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_keeps_head_and_error_at_end() {
+        let output = format!(
+            "Compiling app\n{}error: linker failed\n",
+            "noise\n".repeat(200)
+        );
+        let truncated = OutputBudget::new(14, 21).truncate(&output);
+
+        assert!(truncated.starts_with("Compiling app\n"));
+        assert!(truncated.ends_with("error: linker failed\n"));
+        assert!(truncated.contains("…truncated 1200 bytes…"));
+    }
+
+    #[test]
+    fn test_cuts_on_char_boundaries() {
+        let output = "é".repeat(100);
+        let truncated = OutputBudget::new(3, 3).truncate(&output);
+
+        assert!(truncated.starts_with("é\n"));
+        assert!(truncated.ends_with("\né"));
+    }
+}
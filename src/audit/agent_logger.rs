@@ -4,32 +4,39 @@ use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::agent::{AgentState, AgentStep, StepType};
+use crate::utils::sqlite::{self, WriteFailures};
 
 /// Agent audit logger for recording complete diagnosis sessions
 #[derive(Clone)]
 pub struct AgentAuditLogger {
     conn: Arc<Mutex<Connection>>,
+    write_failures: Arc<WriteFailures>,
 }
 
 impl AgentAuditLogger {
     /// Create new agent audit logger
     pub fn new(database_path: &str) -> Result<Self> {
-        let conn = Connection::open(database_path)?;
-
-        conn.execute_batch(
-            "PRAGMA journal_mode=WAL;
-             PRAGMA synchronous=NORMAL;
-             PRAGMA foreign_keys=ON;
-             PRAGMA temp_store=MEMORY;",
-        )?;
-
-        Self::initialize_schema(&conn)?;
+        let conn = sqlite::open(database_path, |conn| {
+            conn.execute_batch(
+                "PRAGMA foreign_keys=ON;
+                 PRAGMA temp_store=MEMORY;",
+            )?;
+            Self::initialize_schema(conn)
+        })?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            write_failures: Arc::default(),
         })
     }
 
+    /// Run a write in one transaction, retried while the database is
+    /// locked; after a failure (logged once) the store isn't written again
+    fn write(&self, op: impl Fn(&Connection) -> Result<()>) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        self.write_failures.write("Agent audit log", &mut conn, op)
+    }
+
     /// Initialize database schema
     fn initialize_schema(conn: &Connection) -> Result<()> {
         // Agent sessions table
@@ -85,22 +92,21 @@ impl AgentAuditLogger {
 
     /// Log agent session start
     pub fn log_session_start(&self, session_id: &str, task: &str) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
-        conn.execute(
-            "INSERT INTO agent_sessions 
-             (session_id, task_description, start_time, status) 
-             VALUES (?1, ?2, ?3, ?4)",
-            params![session_id, task, timestamp, "RUNNING"],
-        )?;
-
-        Ok(())
+        self.write(|conn| {
+            conn.execute(
+                "INSERT INTO agent_sessions 
+                 (session_id, task_description, start_time, status) 
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![session_id, task, timestamp, "RUNNING"],
+            )?;
+            Ok(())
+        })
     }
 
     /// Log agent step
     pub fn log_step(&self, session_id: &str, step: &AgentStep) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
         let timestamp = step.timestamp.duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
         let step_type = match step.step_type {
@@ -109,31 +115,35 @@ impl AgentAuditLogger {
             StepType::Observation => "OBSERVATION",
             StepType::Reflection => "REFLECTION",
             StepType::Solution => "SOLUTION",
+            StepType::Skipped => "SKIPPED",
+            StepType::Interrupted => "INTERRUPTED",
+            StepType::Question => "QUESTION",
+            StepType::Answer => "ANSWER",
         };
 
         let success_int = step.success.map(|b| if b { 1 } else { 0 });
 
-        conn.execute(
-            "INSERT INTO agent_steps 
-             (session_id, step_number, step_type, content, tool_used, success, timestamp) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![
-                session_id,
-                step.step_number as i64,
-                step_type,
-                step.content,
-                step.tool_used,
-                success_int,
-                timestamp
-            ],
-        )?;
-
-        Ok(())
+        self.write(|conn| {
+            conn.execute(
+                "INSERT INTO agent_steps 
+                 (session_id, step_number, step_type, content, tool_used, success, timestamp) 
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    session_id,
+                    step.step_number as i64,
+                    step_type,
+                    step.content,
+                    step.tool_used,
+                    success_int,
+                    timestamp
+                ],
+            )?;
+            Ok(())
+        })
     }
 
     /// Log agent session completion
     pub fn log_session_end(&self, session_id: &str, final_state: &AgentState) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
         let end_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
         let duration_ms = final_state.start_time.elapsed().as_millis() as i64;
@@ -152,24 +162,25 @@ impl AgentAuditLogger {
             None
         };
 
-        conn.execute(
-            "UPDATE agent_sessions 
-             SET end_time = ?1, status = ?2, total_steps = ?3, total_actions = ?4, 
-                 duration_ms = ?5, root_cause = ?6, solution_plan = ?7
-             WHERE session_id = ?8",
-            params![
-                end_time,
-                status,
-                total_steps,
-                total_actions,
-                duration_ms,
-                final_state.root_cause,
-                solution_plan_json,
-                session_id
-            ],
-        )?;
-
-        Ok(())
+        self.write(|conn| {
+            conn.execute(
+                "UPDATE agent_sessions 
+                 SET end_time = ?1, status = ?2, total_steps = ?3, total_actions = ?4, 
+                     duration_ms = ?5, root_cause = ?6, solution_plan = ?7
+                 WHERE session_id = ?8",
+                params![
+                    end_time,
+                    status,
+                    total_steps,
+                    total_actions,
+                    duration_ms,
+                    final_state.root_cause,
+                    solution_plan_json,
+                    session_id
+                ],
+            )?;
+            Ok(())
+        })
     }
 
     /// Get recent agent sessions
@@ -5,6 +5,7 @@ use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::kubectl::{ExecutionResult, RiskLevel};
+use crate::utils::sqlite::{self, WriteFailures};
 
 /// Maximum length for stdout/stderr (10KB)
 const MAX_OUTPUT_LENGTH: usize = 10 * 1024;
@@ -18,6 +19,12 @@ pub enum UserAction {
     Cancelled,
     /// Command was edited before execution
     Edited,
+    /// Command was executed by an MCP client via kaido_execute
+    McpExecuted,
+    /// Command was refused for an MCP client (critical risk)
+    McpBlocked,
+    /// Command risk was checked by an MCP client via kaido_check_risk
+    McpRiskChecked,
 }
 
 impl UserAction {
@@ -26,6 +33,9 @@ impl UserAction {
             UserAction::Executed => "EXECUTED",
             UserAction::Cancelled => "CANCELLED",
             UserAction::Edited => "EDITED",
+            UserAction::McpExecuted => "MCP_EXECUTED",
+            UserAction::McpBlocked => "MCP_BLOCKED",
+            UserAction::McpRiskChecked => "MCP_RISK_CHECKED",
         }
     }
 }
@@ -63,12 +73,15 @@ pub struct AuditLogEntry {
     pub execution_duration_ms: Option<i64>,
     /// User action
     pub user_action: UserAction,
+    /// MCP client that requested the command (None outside MCP)
+    pub mcp_client: Option<String>,
 }
 
 /// Audit logger for recording kubectl commands
 #[derive(Clone)]
 pub struct AuditLogger {
     conn: Arc<Mutex<Connection>>,
+    write_failures: Arc<WriteFailures>,
 }
 
 impl AuditLogger {
@@ -76,25 +89,24 @@ impl AuditLogger {
     ///
     /// Initializes database connection, applies schema, and runs retention policy
     pub fn new(database_path: &str) -> Result<Self> {
-        // Open connection
-        let conn = Connection::open(database_path)?;
-
-        // Set PRAGMA for better performance
-        conn.execute_batch(
-            "PRAGMA journal_mode=WAL;
-             PRAGMA synchronous=NORMAL;
-             PRAGMA foreign_keys=ON;
-             PRAGMA temp_store=MEMORY;",
-        )?;
+        // Open connection (WAL, busy timeout, retried while locked)
+        let conn = sqlite::open(database_path, |conn| {
+            conn.execute_batch(
+                "PRAGMA foreign_keys=ON;
+                 PRAGMA temp_store=MEMORY;",
+            )?;
 
-        // Initialize schema (from schema.rs)
-        crate::audit::schema::initialize_schema(&conn)?;
+            // Initialize schema (from schema.rs)
+            crate::audit::schema::initialize_schema(conn)?;
 
-        // Clean old entries (retention policy: 90 days)
-        Self::clean_old_entries_internal(&conn, 90)?;
+            // Clean old entries (retention policy: 90 days)
+            Self::clean_old_entries_internal(conn, 90)?;
+            Ok(())
+        })?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            write_failures: Arc::default(),
         })
     }
 
@@ -106,10 +118,11 @@ impl AuditLogger {
         let stdout = entry.stdout.as_ref().map(|s| truncate_output(s));
         let stderr = entry.stderr.as_ref().map(|s| truncate_output(s));
 
-        // Insert into database
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT INTO audit_log (
+        // Insert into database; retried while locked, stops after a failure
+        let mut conn = self.conn.lock().unwrap();
+        self.write_failures.write("Audit log", &mut conn, |conn| {
+            conn.execute(
+                "INSERT INTO audit_log (
                 timestamp,
                 user_id,
                 natural_language_input,
@@ -124,28 +137,30 @@ impl AuditLogger {
                 stdout,
                 stderr,
                 execution_duration_ms,
-                user_action
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                entry.timestamp,
-                entry.user_id,
-                entry.natural_language_input,
-                entry.kubectl_command,
-                entry.original_command,
-                entry.confidence_score,
-                entry.risk_level.as_str(),
-                entry.environment,
-                entry.cluster,
-                entry.namespace,
-                entry.exit_code,
-                stdout,
-                stderr,
-                entry.execution_duration_ms,
-                entry.user_action.as_str(),
-            ],
-        )?;
-
-        Ok(conn.last_insert_rowid())
+                user_action,
+                mcp_client
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    entry.timestamp,
+                    entry.user_id,
+                    entry.natural_language_input,
+                    entry.kubectl_command,
+                    entry.original_command,
+                    entry.confidence_score,
+                    entry.risk_level.as_str(),
+                    entry.environment,
+                    entry.cluster,
+                    entry.namespace,
+                    entry.exit_code,
+                    stdout,
+                    stderr,
+                    entry.execution_duration_ms,
+                    entry.user_action.as_str(),
+                    entry.mcp_client,
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
     }
 
     /// Clean entries older than specified days
@@ -241,6 +256,7 @@ pub fn audit_entry_from_execution(
         },
         execution_duration_ms: Some(result.execution_duration_ms),
         user_action,
+        mcp_client: None,
     }
 }
 
@@ -270,6 +286,7 @@ pub fn audit_entry_cancelled(
         stderr: None,
         execution_duration_ms: None,
         user_action: UserAction::Cancelled,
+        mcp_client: None,
     }
 }
 
@@ -320,6 +337,7 @@ mod tests {
             stderr: None,
             execution_duration_ms: Some(123),
             user_action: UserAction::Executed,
+            mcp_client: None,
         };
 
         let result = logger.log_execution(entry);
@@ -350,6 +368,7 @@ mod tests {
             stderr: None,
             execution_duration_ms: Some(100),
             user_action: UserAction::Executed,
+            mcp_client: None,
         };
 
         logger.log_execution(entry).unwrap();
@@ -364,5 +383,6 @@ mod tests {
         assert_eq!(UserAction::Executed.as_str(), "EXECUTED");
         assert_eq!(UserAction::Cancelled.as_str(), "CANCELLED");
         assert_eq!(UserAction::Edited.as_str(), "EDITED");
+        assert_eq!(UserAction::McpExecuted.as_str(), "MCP_EXECUTED");
     }
 }
@@ -0,0 +1,216 @@
+// Tool execution metrics
+//
+// Every tool invocation made through `ToolExecutor` or the agent is stored
+// as one row (tool, outcome, duration) in the audit database, so
+// `kaido tools metrics` can show which tools are slow or keep failing.
+
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::sqlite::{self, WriteFailures};
+
+/// Per-tool numbers over the selected period
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolStats {
+    pub tool: String,
+    pub invocations: u64,
+    pub successes: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+impl ToolStats {
+    /// Share of invocations that succeeded, 0.0 - 1.0
+    pub fn success_rate(&self) -> f64 {
+        if self.invocations == 0 {
+            return 0.0;
+        }
+        self.successes as f64 / self.invocations as f64
+    }
+}
+
+/// Store of tool invocation outcomes
+#[derive(Clone)]
+pub struct ToolMetrics {
+    conn: Arc<Mutex<Connection>>,
+    write_failures: Arc<WriteFailures>,
+}
+
+impl ToolMetrics {
+    /// Open (and create if needed) the metrics table in `database_path`
+    pub fn new(database_path: &str) -> Result<Self> {
+        let conn = sqlite::open(database_path, |conn| {
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS tool_metrics (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    timestamp INTEGER NOT NULL,
+                    tool_name TEXT NOT NULL,
+                    success INTEGER NOT NULL,
+                    duration_ms INTEGER NOT NULL
+                )",
+                [],
+            )?;
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_tool_metrics_tool
+                 ON tool_metrics(tool_name, timestamp)",
+                [],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            write_failures: Arc::default(),
+        })
+    }
+
+    /// Record one invocation; failures are logged once, not returned
+    pub fn record(&self, tool: &str, success: bool, duration: Duration) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let duration_ms = duration.as_millis() as i64;
+
+        let Ok(mut conn) = self.conn.lock() else {
+            return;
+        };
+        let result = self
+            .write_failures
+            .write("Tool metrics", &mut conn, |conn| {
+                conn.execute(
+                    "INSERT INTO tool_metrics (timestamp, tool_name, success, duration_ms)
+                 VALUES (?, ?, ?, ?)",
+                    params![now, tool, success, duration_ms],
+                )?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            log::debug!("Tool metrics not recorded: {e}");
+        }
+    }
+
+    /// Stats per tool for invocations since `since` (Unix time), by name
+    pub fn summary(&self, since: Option<i64>) -> Result<Vec<ToolStats>> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        let mut stmt = conn.prepare(
+            "SELECT tool_name, success, duration_ms FROM tool_metrics
+             WHERE timestamp >= ? ORDER BY tool_name",
+        )?;
+        let rows = stmt.query_map(params![since.unwrap_or(0)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, bool>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+
+        let mut by_tool: BTreeMap<String, (u64, Vec<u64>)> = BTreeMap::new();
+        for row in rows {
+            let (tool, success, duration_ms) = row?;
+            let (successes, durations) = by_tool.entry(tool).or_default();
+            *successes += u64::from(success);
+            durations.push(duration_ms.max(0) as u64);
+        }
+
+        Ok(by_tool
+            .into_iter()
+            .map(|(tool, (successes, mut durations))| {
+                durations.sort_unstable();
+                ToolStats {
+                    tool,
+                    invocations: durations.len() as u64,
+                    successes,
+                    p50_ms: percentile(&durations, 50),
+                    p95_ms: percentile(&durations, 95),
+                    max_ms: durations.last().copied().unwrap_or(0),
+                }
+            })
+            .collect())
+    }
+
+    /// Format stats as an aligned table
+    pub fn format_table(stats: &[ToolStats]) -> String {
+        let mut output = format!(
+            "{:<12} {:>6} {:>8} {:>9} {:>9} {:>9}\n",
+            "Tool", "Calls", "Success", "p50", "p95", "Max"
+        );
+        output.push_str(&format!("{}\n", "-".repeat(58)));
+
+        for stat in stats {
+            output.push_str(&format!(
+                "{:<12} {:>6} {:>7.0}% {:>9} {:>9} {:>9}\n",
+                stat.tool,
+                stat.invocations,
+                stat.success_rate() * 100.0,
+                format_ms(stat.p50_ms),
+                format_ms(stat.p95_ms),
+                format_ms(stat.max_ms)
+            ));
+        }
+        output
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn format_ms(ms: u64) -> String {
+    if ms >= 1000 {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    } else {
+        format!("{ms}ms")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let values: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&values, 50), 10);
+        assert_eq!(percentile(&values, 95), 19);
+        assert_eq!(percentile(&[7], 95), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_summary_per_tool() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("audit.db");
+        let metrics = ToolMetrics::new(&path.to_string_lossy()).unwrap();
+
+        for (success, ms) in [(true, 100), (false, 30_000), (true, 200), (false, 30_000)] {
+            metrics.record("terraform", success, Duration::from_millis(ms));
+        }
+        metrics.record("kubectl", true, Duration::from_millis(50));
+
+        let stats = metrics.summary(None).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].tool, "kubectl");
+        let terraform = &stats[1];
+        assert_eq!(terraform.invocations, 4);
+        assert_eq!(terraform.success_rate(), 0.5);
+        assert_eq!(terraform.p50_ms, 200);
+        assert_eq!(terraform.max_ms, 30_000);
+
+        let table = ToolMetrics::format_table(&stats);
+        assert!(table.contains("terraform"));
+        assert!(table.contains("30.0s"));
+
+        // Nothing recorded in the future
+        assert!(metrics.summary(Some(i64::MAX)).unwrap().is_empty());
+    }
+}
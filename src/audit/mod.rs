@@ -3,10 +3,12 @@
 // This module provides:
 // - schema.rs: SQLite schema initialization
 // - logger.rs: Write audit log entries
-// - query.rs: Query audit log (today, last week, production)
+// - query.rs: Query audit log (today, last week, production, filtered)
+// - metrics.rs: Per-tool invocation counts, success rates and latency
 
 pub mod agent_logger;
 pub mod logger;
+pub mod metrics;
 pub mod query;
 pub mod schema;
 
@@ -14,3 +16,4 @@ pub use agent_logger::{AgentAuditLogger, AgentSessionDetail, AgentSessionSummary
 pub use logger::{
     audit_entry_cancelled, audit_entry_from_execution, AuditContext, AuditLogger, UserAction,
 };
+pub use metrics::{ToolMetrics, ToolStats};
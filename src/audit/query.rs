@@ -40,6 +40,30 @@ impl QueryResult {
     }
 }
 
+/// Filters for `kaido audit show`; unset filters match everything
+#[derive(Debug, Clone)]
+pub struct AuditFilter {
+    /// Only commands run at or after this Unix timestamp
+    pub since: Option<i64>,
+    /// Only environments containing this, case-insensitively; `production`
+    /// matches anything with "prod" like the production view
+    pub environment: Option<String>,
+    /// Only commands at this risk level or above
+    pub min_risk: Option<crate::kubectl::RiskLevel>,
+    pub limit: usize,
+}
+
+impl Default for AuditFilter {
+    fn default() -> Self {
+        Self {
+            since: None,
+            environment: None,
+            min_risk: None,
+            limit: 50,
+        }
+    }
+}
+
 /// Audit query interface for history retrieval
 ///
 /// TODO: Future Analytics for Model Fine-Tuning
@@ -131,6 +155,58 @@ impl AuditQuery {
         self.execute_query(&sql, params![])
     }
 
+    /// Query the most recent commands
+    ///
+    /// Returns up to `limit` commands, newest first, optionally restricted to
+    /// those executed at or after the `since` Unix timestamp
+    pub fn query_recent(&self, limit: usize, since: Option<i64>) -> Result<Vec<QueryResult>> {
+        self.query(&AuditFilter {
+            since,
+            limit,
+            ..AuditFilter::default()
+        })
+    }
+
+    /// Query commands matching all of `filter`, newest first
+    pub fn query(&self, filter: &AuditFilter) -> Result<Vec<QueryResult>> {
+        use crate::kubectl::RiskLevel;
+
+        let environment =
+            filter
+                .environment
+                .as_deref()
+                .map(|env| match env.to_lowercase().as_str() {
+                    "production" | "prod" => "%prod%".to_string(),
+                    env => format!("%{}%", env.replace(['%', '_'], "")),
+                });
+        let risks: &[&str] = match filter.min_risk {
+            None | Some(RiskLevel::Low) => &["LOW", "MEDIUM", "HIGH"],
+            Some(RiskLevel::Medium) => &["MEDIUM", "HIGH"],
+            Some(RiskLevel::High) => &["HIGH"],
+        };
+        let sql = format!(
+            "SELECT id, datetime(timestamp, 'unixepoch') as executed_at, user_id,
+                    natural_language_input, kubectl_command, risk_level, environment,
+                    user_action, exit_code
+             FROM audit_log
+             WHERE timestamp >= ?1
+               AND (?2 IS NULL OR environment LIKE ?2)
+               AND risk_level IN ({})
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?3",
+            risks
+                .iter()
+                .map(|risk| format!("'{risk}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        self.execute_query(
+            &sql,
+            params![filter.since.unwrap_or(0), environment, filter.limit as i64],
+        )
+    }
+
     /// Execute query and return results
     fn execute_query(
         &self,
@@ -163,7 +239,7 @@ impl AuditQuery {
     /// Format query results as table for TUI display
     ///
     /// Returns formatted string with aligned columns:
-    /// ID | Time | Command | Environment | Action | Exit Code
+    /// ID | Time | Command | Environment | Risk | Action | Exit Code
     pub fn format_table(results: &[QueryResult], max_rows: usize) -> String {
         if results.is_empty() {
             return "No commands found.".to_string();
@@ -179,10 +255,10 @@ impl AuditQuery {
 
         // Header
         output.push_str(&format!(
-            "{:<6} {:<20} {:<40} {:<15} {:<10} {:<8}\n",
-            "ID", "Time", "Command", "Environment", "Action", "Exit"
+            "{:<6} {:<20} {:<40} {:<15} {:<7} {:<10} {:<8}\n",
+            "ID", "Time", "Command", "Environment", "Risk", "Action", "Exit"
         ));
-        output.push_str(&format!("{}\n", "-".repeat(100)));
+        output.push_str(&format!("{}\n", "-".repeat(108)));
 
         // Rows
         for result in results_to_show {
@@ -210,8 +286,14 @@ impl AuditQuery {
                 .unwrap_or_else(|| "-".to_string());
 
             output.push_str(&format!(
-                "{:<6} {:<20} {:<40} {:<15} {:<10} {:<8}\n",
-                result.id, time, command, environment, result.user_action, exit_code
+                "{:<6} {:<20} {:<40} {:<15} {:<7} {:<10} {:<8}\n",
+                result.id,
+                time,
+                command,
+                environment,
+                result.risk_level,
+                result.user_action,
+                exit_code
             ));
         }
 
@@ -230,6 +312,41 @@ impl AuditQuery {
     }
 }
 
+/// Parse a `since` filter into a Unix timestamp
+///
+/// Accepts a relative duration (`30m`, `2h`, `7d`), an RFC 3339 timestamp,
+/// or a plain date (`2025-10-25`, interpreted as UTC midnight)
+pub fn parse_since(input: &str, now: i64) -> Option<i64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    let last_char = input.char_indices().last().map_or(0, |(i, _)| i);
+    let (amount, unit) = input.split_at(last_char);
+    if !amount.is_empty() && amount.chars().all(|c| c.is_ascii_digit()) {
+        let amount: i64 = amount.parse().ok()?;
+        let seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        return Some(now - amount * seconds);
+    }
+
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(input) {
+        return Some(dt.timestamp());
+    }
+
+    chrono::NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +383,7 @@ mod tests {
             stderr: None,
             execution_duration_ms: Some(100),
             user_action: UserAction::Executed,
+            mcp_client: None,
         }
     }
 
@@ -340,6 +458,136 @@ mod tests {
         assert_eq!(results.len(), 3);
     }
 
+    #[test]
+    fn test_query_recent() {
+        let (temp_db, logger) = create_test_db();
+
+        let mut old = create_test_entry("old", "kubectl get nodes", RiskLevel::Low, "dev");
+        old.timestamp -= 3600;
+        logger.log_execution(old).unwrap();
+        for i in 0..3 {
+            logger
+                .log_execution(create_test_entry(
+                    &format!("command {i}"),
+                    &format!("kubectl cmd {i}"),
+                    RiskLevel::Low,
+                    "dev",
+                ))
+                .unwrap();
+        }
+
+        let query = AuditQuery::new(temp_db.path().to_str().unwrap()).unwrap();
+
+        let results = query.query_recent(2, None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].kubectl_command, "kubectl cmd 2");
+
+        let since = AuditLogger::current_timestamp() - 60;
+        let results = query.query_recent(10, Some(since)).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.natural_language_input != "old"));
+    }
+
+    #[test]
+    fn test_query_filters_combine() {
+        let (temp_db, logger) = create_test_db();
+
+        let mut yesterday = create_test_entry(
+            "drop ns",
+            "kubectl delete ns shop",
+            RiskLevel::High,
+            "prod-eu",
+        );
+        yesterday.timestamp -= 20 * 3600;
+        logger.log_execution(yesterday).unwrap();
+        let mut last_week = create_test_entry(
+            "drop pod",
+            "kubectl delete pod web",
+            RiskLevel::High,
+            "prod-eu",
+        );
+        last_week.timestamp -= 6 * 24 * 3600;
+        logger.log_execution(last_week).unwrap();
+        for (command, risk, env) in [
+            (
+                "kubectl scale deploy web --replicas=3",
+                RiskLevel::Medium,
+                "production",
+            ),
+            ("kubectl delete pod api", RiskLevel::High, "staging"),
+            ("kubectl get pods", RiskLevel::Low, "prod-eu"),
+        ] {
+            logger
+                .log_execution(create_test_entry(command, command, risk, env))
+                .unwrap();
+        }
+
+        let query = AuditQuery::new(temp_db.path().to_str().unwrap()).unwrap();
+        let now = AuditLogger::current_timestamp();
+        let commands = |filter: AuditFilter| -> Vec<String> {
+            query
+                .query(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.kubectl_command)
+                .collect()
+        };
+
+        assert_eq!(
+            commands(AuditFilter {
+                since: parse_since("24h", now),
+                environment: Some("production".to_string()),
+                min_risk: Some(RiskLevel::High),
+                ..AuditFilter::default()
+            }),
+            ["kubectl delete ns shop"]
+        );
+        assert_eq!(
+            commands(AuditFilter {
+                environment: Some("PROD".to_string()),
+                min_risk: Some(RiskLevel::Medium),
+                ..AuditFilter::default()
+            }),
+            [
+                "kubectl scale deploy web --replicas=3",
+                "kubectl delete ns shop",
+                "kubectl delete pod web"
+            ]
+        );
+        assert_eq!(
+            commands(AuditFilter {
+                environment: Some("staging".to_string()),
+                ..AuditFilter::default()
+            }),
+            ["kubectl delete pod api"]
+        );
+        assert_eq!(
+            commands(AuditFilter {
+                limit: 2,
+                ..AuditFilter::default()
+            })
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_since("30m", now), Some(now - 1800));
+        assert_eq!(parse_since("2h", now), Some(now - 7200));
+        assert_eq!(parse_since("1d", now), Some(now - 86400));
+        assert_eq!(
+            parse_since("2023-11-14T22:13:20Z", now),
+            Some(1_700_000_000)
+        );
+        assert_eq!(parse_since("2023-11-14", now), Some(1_699_920_000));
+        assert_eq!(parse_since("yesterday", now), None);
+        assert_eq!(parse_since("5x", now), None);
+        assert_eq!(parse_since("5日", now), None);
+        assert_eq!(parse_since("", now), None);
+    }
+
     #[test]
     fn test_format_table() {
         let results = vec![QueryResult {
@@ -358,6 +606,7 @@ mod tests {
         assert!(formatted.contains("ID"));
         assert!(formatted.contains("Time"));
         assert!(formatted.contains("kubectl get pods"));
+        assert!(formatted.contains(" LOW "));
         assert!(formatted.contains("Total: 1 results"));
     }
 
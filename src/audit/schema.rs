@@ -32,7 +32,10 @@ CREATE TABLE IF NOT EXISTS audit_log (
     execution_duration_ms INTEGER,
     
     -- User action tracking
-    user_action TEXT NOT NULL CHECK(user_action IN ('EXECUTED', 'CANCELLED', 'EDITED')),
+    user_action TEXT NOT NULL CHECK(user_action IN (
+        'EXECUTED', 'CANCELLED', 'EDITED', 'MCP_EXECUTED', 'MCP_BLOCKED', 'MCP_RISK_CHECKED'
+    )),
+    mcp_client TEXT,  -- MCP client name for commands requested over MCP
     
     -- Metadata
     created_at TEXT NOT NULL DEFAULT (datetime('now', 'utc'))
@@ -109,11 +112,19 @@ WHERE environment LIKE '%prod%' OR environment LIKE '%production%'
 ORDER BY timestamp DESC;
 "#;
 
+/// Columns shared by every audit_log schema version
+const AUDIT_LOG_V1_COLUMNS: &str = "id, timestamp, user_id, natural_language_input, \
+     kubectl_command, original_command, confidence_score, risk_level, environment, cluster, \
+     namespace, exit_code, stdout, stderr, execution_duration_ms, user_action, created_at";
+
 /// Initialize database schema
 pub fn initialize_schema(conn: &rusqlite::Connection) -> anyhow::Result<()> {
     // Create audit_log table
     conn.execute(AUDIT_LOG_SCHEMA, [])?;
 
+    // Upgrade databases created before MCP actions were audited
+    migrate_mcp_actions(conn)?;
+
     // Create indexes
     conn.execute_batch(AUDIT_LOG_INDEXES)?;
 
@@ -133,6 +144,38 @@ pub fn initialize_schema(conn: &rusqlite::Connection) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Rebuild audit_log to accept MCP user actions and the mcp_client column
+///
+/// SQLite can't alter a CHECK constraint, so the table is recreated and its
+/// rows copied. Views are dropped first and re-created by the caller.
+fn migrate_mcp_actions(conn: &rusqlite::Connection) -> anyhow::Result<()> {
+    let table_sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type='table' AND name='audit_log'",
+        [],
+        |row| row.get(0),
+    )?;
+    if table_sql.contains("MCP_EXECUTED") {
+        return Ok(());
+    }
+
+    conn.execute_batch(&format!(
+        "BEGIN;
+         DROP VIEW IF EXISTS v_today_commands;
+         DROP VIEW IF EXISTS v_last_week_commands;
+         DROP VIEW IF EXISTS v_production_commands;
+         ALTER TABLE audit_log RENAME TO audit_log_v1;
+         {AUDIT_LOG_SCHEMA};
+         INSERT INTO audit_log ({AUDIT_LOG_V1_COLUMNS})
+             SELECT {AUDIT_LOG_V1_COLUMNS} FROM audit_log_v1;
+         DROP TABLE audit_log_v1;
+         COMMIT;"
+    ))?;
+
+    log::info!("Audit log migrated for MCP actions");
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,5 +205,48 @@ mod tests {
             .unwrap();
         assert_eq!(index_count, 4);
     }
+
+    #[test]
+    fn test_migrates_pre_mcp_table() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let old_schema = AUDIT_LOG_SCHEMA
+            .replace(
+                "'EXECUTED', 'CANCELLED', 'EDITED', 'MCP_EXECUTED', 'MCP_BLOCKED', 'MCP_RISK_CHECKED'",
+                "'EXECUTED', 'CANCELLED', 'EDITED'",
+            )
+            .replace("mcp_client TEXT,", "");
+        conn.execute(&old_schema, []).unwrap();
+        conn.execute_batch(AUDIT_LOG_VIEWS).unwrap();
+        conn.execute(
+            "INSERT INTO audit_log (timestamp, user_id, natural_language_input, kubectl_command,
+                 risk_level, environment, cluster, user_action)
+             VALUES (1, 'me', 'show pods', 'kubectl get pods', 'LOW', 'dev', 'dev', 'EXECUTED')",
+            [],
+        )
+        .unwrap();
+
+        initialize_schema(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO audit_log (timestamp, user_id, natural_language_input, kubectl_command,
+                 risk_level, environment, cluster, user_action, mcp_client)
+             VALUES (2, 'me', '', 'ls', 'LOW', 'unknown', 'unknown', 'MCP_EXECUTED', 'claude')",
+            [],
+        )
+        .unwrap();
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 2);
+
+        let view_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type='view' AND name LIKE 'v_%_commands'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(view_count, 3);
+    }
 }
 // Note: clean_old_entries test removed - function needs to be implemented
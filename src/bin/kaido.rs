@@ -1,8 +1,13 @@
 use clap::{Parser, Subcommand};
+use kaido::agent::CheckpointStore;
 use kaido::ai::{GeminiBackend, OllamaBackend};
-use kaido::config::{AIProvider, Config};
-use kaido::shell::Shell;
-use kaido::tools::LLMBackend;
+use kaido::audit::query::{parse_since, AuditFilter, AuditQuery};
+use kaido::audit::AuditLogger;
+use kaido::config::{mask_secret, AIProvider, Config};
+use kaido::learning::LearningTracker;
+use kaido::mcp::{McpHttpServer, McpServer};
+use kaido::shell::{aliases, KaidoREPL, KaidoShell, Shell, ShellConfig};
+use kaido::tools::{LLMBackend, ToolRegistry, MIN_INTENT_CONFIDENCE};
 use std::io::{self, Write};
 
 // ANSI color codes
@@ -34,6 +39,15 @@ struct Cli {
     #[arg(long, value_name = "user@host", default_value = "")]
     target: String,
 
+    /// Config profile for `kaido shell`, e.g. `[profiles.work]` in config.toml
+    #[arg(long, value_name = "name")]
+    profile: Option<String>,
+
+    /// Offline mode: no AI calls or probes, pattern-based guidance only
+    /// (same as KAIDO_OFFLINE=1)
+    #[arg(long, global = true)]
+    no_ai: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -41,10 +55,15 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Onboard - set up AI backends and configure Kaido
+    #[command(visible_alias = "init")]
     Onboard {
         /// Skip interactive prompts and use defaults
         #[arg(long)]
         non_interactive: bool,
+        /// Copy aliases from ~/.bashrc, ~/.zshrc, ... into the kaido shell
+        /// without asking
+        #[arg(long)]
+        import_aliases: bool,
     },
     /// Check for updates and upgrade to the latest version
     Update {
@@ -52,8 +71,37 @@ enum Commands {
         #[arg(long)]
         check: bool,
     },
+    /// Start the AI-native shell with per-session AI overrides
+    Shell {
+        /// Model to use for this session only (e.g. qwen2.5:14b)
+        #[arg(long)]
+        model: Option<String>,
+        /// AI provider for this session only
+        #[arg(long, value_parser = ["auto", "gemini", "ollama", "copilot"])]
+        provider: Option<String>,
+        /// Record commands, output and mentor guidance to a JSONL transcript
+        #[arg(long, value_name = "file", conflicts_with = "replay")]
+        record: Option<std::path::PathBuf>,
+        /// Play back a recorded transcript instead of starting a shell
+        #[arg(long, value_name = "file")]
+        replay: Option<std::path::PathBuf>,
+        /// With --replay, print everything at once instead of in real time
+        #[arg(long, requires = "replay")]
+        fast: bool,
+        /// Run one command through the error mentor and exit with its code
+        #[arg(short = 'c', value_name = "command", conflicts_with_all = ["record", "replay"])]
+        command: Option<String>,
+        /// Output of `-c`: text, or one JSON object for CI
+        #[arg(long, value_parser = ["text", "json"], default_value = "text", requires = "command")]
+        format: String,
+        /// Start without the banner or the AI mode line
+        #[arg(long, short)]
+        quiet: bool,
+    },
     /// Configure AI API providers
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
         /// Show current configuration
         #[arg(long)]
         show: bool,
@@ -70,6 +118,141 @@ enum Commands {
         #[arg(long)]
         provider: Option<String>,
     },
+    /// Expose Kaido tools to MCP clients
+    Mcp {
+        #[command(subcommand)]
+        action: McpAction,
+    },
+    /// Manage the learning history (errors, concepts, sessions)
+    Learning {
+        #[command(subcommand)]
+        action: LearningAction,
+    },
+    /// Browse the audit log of executed commands
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+    /// Inspect the built-in tools
+    Tools {
+        #[command(subcommand)]
+        action: ToolsAction,
+    },
+    /// Check the setup: config, AI backends, databases, tools, terminal
+    Doctor,
+    /// Let the agent diagnose a problem, or resume an interrupted run
+    Diagnose {
+        /// Problem in plain words, e.g. "nginx returns 502"; omit for a prompt
+        problem: Vec<String>,
+        /// Continue a checkpointed run by ID
+        #[arg(long, value_name = "id", conflicts_with_all = ["problem", "list"])]
+        resume: Option<String>,
+        /// List interrupted runs that can be resumed
+        #[arg(long, conflicts_with = "problem")]
+        list: bool,
+    },
+    /// Write a redacted report (version, config, doctor, logs) to attach to an issue
+    BugReport {
+        /// Report file; defaults to kaido-bug-report-<timestamp>.md
+        #[arg(short, long, value_name = "file")]
+        output: Option<std::path::PathBuf>,
+        /// Session transcript to include the tail of, from `kaido shell --record`
+        #[arg(long, value_name = "file")]
+        transcript: Option<std::path::PathBuf>,
+        /// Lines of log and transcript to include
+        #[arg(long, default_value_t = kaido::bug_report::DEFAULT_LOG_LINES)]
+        log_lines: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum ToolsAction {
+    /// Show which tools are installed on this machine
+    List {
+        /// Also show each binary's path and version
+        #[arg(long)]
+        detailed: bool,
+    },
+    /// Show every tool's intent score for an input, to debug routing
+    Detect {
+        /// Input as typed in the shell, e.g. "show pods in prod"
+        #[arg(required = true, num_args = 1..)]
+        input: Vec<String>,
+    },
+    /// Show per-tool call counts, success rates and latency
+    Metrics {
+        /// Only calls since this time: 30m, 24h, 7d or a date like 2025-10-25
+        #[arg(long)]
+        since: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the configuration file (API keys masked)
+    Show,
+    /// Set a configuration value, e.g. `kaido config set ollama.model qwen2.5`
+    Set {
+        /// Dotted key path (provider, ollama.model, ollama.base_url, ...)
+        key: String,
+        /// New value
+        value: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum McpAction {
+    /// Run the MCP server (stdio by default)
+    Serve {
+        /// Serve over HTTP with the SSE transport instead of stdio
+        #[arg(long)]
+        http: bool,
+        /// Port to listen on with --http
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to bind with --http (tools can run commands, keep it local)
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum LearningAction {
+    /// Delete all learning history
+    Reset {
+        /// Don't ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Write the learning history to a JSON file
+    Export {
+        /// Destination file
+        file: std::path::PathBuf,
+    },
+    /// Merge a JSON export into the learning history
+    Import {
+        /// Export file to merge
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    /// Show recorded commands, newest first
+    Show {
+        /// Only commands since a duration (30m, 24h, 7d) or date (2025-10-25)
+        #[arg(long, value_name = "when")]
+        since: Option<String>,
+        /// Only commands in a matching environment, e.g. production
+        #[arg(long, value_name = "env")]
+        env: Option<String>,
+        /// Only commands at this risk level or above
+        #[arg(long, value_parser = ["low", "medium", "high"])]
+        risk: Option<String>,
+        /// Maximum number of commands to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
 }
 
 #[tokio::main]
@@ -83,17 +266,115 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let cli = Cli::parse();
+    if cli.no_ai {
+        // Every AIManager reads this, including ones built deep in tools
+        std::env::set_var(kaido::ai::OFFLINE_ENV, "1");
+    }
 
     match cli.command {
-        Some(Commands::Onboard { non_interactive }) => {
-            run_init_learning(non_interactive).await?;
+        Some(Commands::Onboard {
+            non_interactive,
+            import_aliases,
+        }) => {
+            run_init_learning(non_interactive, import_aliases).await?;
         }
         Some(Commands::Update { check }) => {
             run_update(check).await?;
         }
-        Some(Commands::Config { show, set_api_key, set_model, set_url, provider }) => {
+        Some(Commands::Shell {
+            replay: Some(transcript),
+            fast,
+            ..
+        }) => {
+            kaido::shell::recording::replay(&transcript, fast).await?;
+        }
+        Some(Commands::Shell {
+            command: Some(command),
+            format,
+            ..
+        }) => {
+            let config = match cli.profile.as_deref() {
+                Some(profile) => Config::load_with_profile(Some(profile))?,
+                None => Config::load_with_profile(None).unwrap_or_default(),
+            };
+            let shell_config = ShellConfig::from_config(&config);
+            let code =
+                kaido::shell::oneshot::run_once(&command, &shell_config, config, format == "json")
+                    .await?;
+            std::process::exit(code);
+        }
+        Some(Commands::Shell {
+            model,
+            provider,
+            record,
+            quiet,
+            ..
+        }) => {
+            run_shell(cli.profile.as_deref(), model, provider, record, quiet).await?;
+        }
+        Some(Commands::Config {
+            action: Some(action),
+            ..
+        }) => {
+            run_config_action(action)?;
+        }
+        Some(Commands::Config {
+            action: None,
+            show,
+            set_api_key,
+            set_model,
+            set_url,
+            provider,
+        }) => {
             run_config(show, set_api_key, set_model, set_url, provider).await?;
         }
+        Some(Commands::Mcp {
+            action: McpAction::Serve { http, port, host },
+        }) => {
+            run_mcp_serve(http, &host, port).await?;
+        }
+        Some(Commands::Learning { action }) => {
+            run_learning_action(action)?;
+        }
+        Some(Commands::Audit { action }) => {
+            run_audit_action(action)?;
+        }
+        Some(Commands::Tools {
+            action: ToolsAction::List { detailed },
+        }) => {
+            let availability = ToolRegistry::new().availability(detailed);
+            print!(
+                "{}",
+                kaido::tools::format_availability(&availability, detailed)
+            );
+        }
+        Some(Commands::Tools {
+            action: ToolsAction::Detect { input },
+        }) => {
+            run_tools_detect(&input.join(" "));
+        }
+        Some(Commands::Tools {
+            action: ToolsAction::Metrics { since },
+        }) => {
+            run_tools_metrics(since)?;
+        }
+        Some(Commands::Doctor) => {
+            run_doctor().await;
+        }
+        Some(Commands::Diagnose {
+            problem,
+            resume,
+            list,
+        }) => {
+            run_diagnose(&problem.join(" "), resume.as_deref(), list).await?;
+        }
+        Some(Commands::BugReport {
+            output,
+            transcript,
+            log_lines,
+        }) => {
+            run_bug_report(output, transcript.as_deref(), log_lines).await?;
+        }
         None => {
             // Check if first run (no config file exists)
             let config_path = Config::get_config_path();
@@ -119,11 +400,14 @@ async fn main() -> anyhow::Result<()> {
 }
 
 /// Learning-focused init experience
-async fn run_init_learning(non_interactive: bool) -> anyhow::Result<()> {
+async fn run_init_learning(non_interactive: bool, import_aliases: bool) -> anyhow::Result<()> {
     // Load existing config or create default
     let mut config = Config::load().unwrap_or_default();
 
     if non_interactive {
+        if import_aliases {
+            import_shell_aliases(false)?;
+        }
         return run_init_non_interactive(&mut config).await;
     }
 
@@ -249,6 +533,10 @@ async fn run_init_learning(non_interactive: bool) -> anyhow::Result<()> {
         }
     }
 
+    // Offer the aliases the user already has in bash/zsh
+    println!();
+    import_shell_aliases(!import_aliases)?;
+
     // ══════════════════════════════════════════════════════════════
     // SAVE & COMPLETE
     // ══════════════════════════════════════════════════════════════
@@ -648,6 +936,67 @@ async fn check_ollama_available() -> bool {
     OllamaBackend::new().is_available().await
 }
 
+/// Copy aliases from the user's bash/zsh rc files into ~/.kaido/aliases
+///
+/// With `ask`, lists what was found and asks first. Imported aliases replace
+/// kaido aliases of the same name.
+fn import_shell_aliases(ask: bool) -> anyhow::Result<()> {
+    let Some(home) = dirs::home_dir() else {
+        return Ok(());
+    };
+    let files = aliases::rc_files(&home);
+    let found = aliases::import_from_rc_files(&files);
+    if found.is_empty() {
+        if !ask {
+            println!("{DIM}No aliases found in your shell rc files.{RESET}");
+        }
+        return Ok(());
+    }
+
+    let sources: Vec<String> = files
+        .iter()
+        .filter_map(|f| f.file_name())
+        .map(|name| format!("~/{}", name.to_string_lossy()))
+        .collect();
+    println!(
+        "Found {BOLD}{}{RESET} aliases in {}",
+        found.len(),
+        sources.join(", ")
+    );
+    if ask {
+        let preview: Vec<&str> = found.keys().take(8).map(String::as_str).collect();
+        let more = if found.len() > preview.len() {
+            ", ..."
+        } else {
+            ""
+        };
+        println!("  {DIM}{}{more}{RESET}", preview.join(", "));
+        print!("Use them in the kaido shell? [Y/n]: ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if matches!(answer.trim().to_lowercase().as_str(), "n" | "no") {
+            println!("{DIM}Skipped. Run 'kaido init --import-aliases' anytime.{RESET}");
+            return Ok(());
+        }
+    }
+
+    let path = aliases::default_aliases_path();
+    let mut merged = if path.exists() {
+        aliases::read_aliases(&path)?
+    } else {
+        Default::default()
+    };
+    merged.extend(found);
+    aliases::save_aliases(&path, &merged)?;
+    println!(
+        "{GREEN}✓{RESET} Saved {} aliases to {}",
+        merged.len(),
+        path.display()
+    );
+    Ok(())
+}
+
 /// Non-interactive init
 async fn run_init_non_interactive(config: &mut Config) -> anyhow::Result<()> {
     println!("Running non-interactive setup...\n");
@@ -1190,7 +1539,355 @@ async fn run_config(
     println!("  kaido config --provider openai        # Set provider");
     println!("  kaido config --set-api-key google=KEY # Set API key");
     println!("  kaido config --set-model openai=gpt-4 # Set model");
-    println!("  kaido config --set-url https://...   # Set custom endpoint\n");
+    println!("  kaido config --set-url https://...   # Set custom endpoint");
+    println!("  kaido config show                     # Print config file (keys masked)");
+    println!("  kaido config set ollama.model qwen2.5 # Set a single value\n");
+
+    Ok(())
+}
+
+/// Start the AI-native shell, applying `--provider` / `--model` overrides
+///
+/// Overrides only live for this session; the config file is not written.
+async fn run_shell(
+    profile: Option<&str>,
+    model: Option<String>,
+    provider: Option<String>,
+    record: Option<std::path::PathBuf>,
+    quiet: bool,
+) -> anyhow::Result<()> {
+    let mut config = match profile {
+        // An explicitly requested profile must exist
+        Some(_) => Config::load_with_profile(profile)?,
+        None => Config::load_with_profile(None).unwrap_or_default(),
+    };
+
+    if let Some(provider) = &provider {
+        config.set_value("provider", provider)?;
+    }
+    if let Some(model) = &model {
+        if let Err(e) = config.override_model(model) {
+            println!("{YELLOW}⚠ {e}; ignoring --model{RESET}");
+        }
+    }
+
+    for issue in config.validate() {
+        println!("{YELLOW}⚠ {issue}{RESET}");
+    }
+
+    // Make sure the requested provider is usable before starting (offline
+    // mode skips this so startup never waits on a probe)
+    if kaido::ai::offline_mode() {
+        if provider.is_some() || model.is_some() {
+            println!("{YELLOW}⚠ Offline mode: ignoring --provider/--model{RESET}");
+        }
+    } else if let Err(e) = kaido::ai::AIManager::new(config.clone())
+        .check_provider()
+        .await
+    {
+        if config.provider == AIProvider::Auto {
+            println!("{YELLOW}⚠ {e}. Falling back to pattern-based guidance.{RESET}");
+        } else {
+            println!(
+                "{YELLOW}⚠ {:?} is not usable: {e}. Falling back to auto provider selection.{RESET}",
+                config.provider
+            );
+            config.provider = AIProvider::Auto;
+        }
+    }
+
+    let shell_config = ShellConfig {
+        quiet,
+        ..ShellConfig::from_config(&config)
+    };
+    let mut shell = KaidoShell::with_ai_config(shell_config, config)?;
+    if let Some(path) = &record {
+        shell.record_to(path)?;
+        println!("{CYAN}● Recording session to {}{RESET}", path.display());
+    }
+    shell.run().await
+}
+
+/// Handle `kaido mcp serve`
+async fn run_mcp_serve(http: bool, host: &str, port: u16) -> anyhow::Result<()> {
+    if http {
+        let mut server = McpHttpServer::new();
+        if let Ok(token) = std::env::var(kaido::mcp::http::TOKEN_ENV) {
+            server = server.with_token(token)?;
+        }
+        return server.serve((host, port)).await;
+    }
+
+    // The stdio server drives its own runtime, so keep it off the async threads
+    tokio::task::spawn_blocking(|| McpServer::new().run().map_err(|e| e.to_string()))
+        .await?
+        .map_err(anyhow::Error::msg)
+}
+
+/// Handle `kaido config show` / `kaido config set`
+fn run_config_action(action: ConfigAction) -> anyhow::Result<()> {
+    let mut config = Config::load()?;
+
+    match action {
+        ConfigAction::Show => {
+            let rendered = toml::to_string_pretty(&config.masked())?;
+            println!("{DIM}# {}{RESET}", Config::get_config_path()?.display());
+            print!("{rendered}");
+
+            let issues = config.validate();
+            if !issues.is_empty() {
+                println!();
+                for issue in issues {
+                    println!("{YELLOW}⚠ {issue}{RESET}");
+                }
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            config.set_value(&key, &value)?;
+            config.save()?;
+            if key == "gemini_api_key" {
+                println!(
+                    "{GREEN}✓{RESET} {key} = {} {DIM}(stored: {}){RESET}",
+                    mask_secret(&value),
+                    config.key_storage.as_str()
+                );
+            } else {
+                println!("{GREEN}✓{RESET} {key} = {value}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle `kaido tools detect <input>`
+fn run_tools_detect(input: &str) {
+    let registry = ToolRegistry::new();
+    let scores = registry.intent_scores(input);
+    let selected = registry.detect_tool(input).map(|tool| tool.name());
+    let width = scores.iter().map(|(name, _)| name.len()).max().unwrap_or(4);
+
+    println!("{DIM}Input:{RESET} {input}");
+    println!();
+    println!("  {BOLD}{:<width$}  SCORE{RESET}", "TOOL");
+    for (name, score) in &scores {
+        if Some(*name) == selected {
+            println!("{GREEN}→ {name:<width$}  {score:.2}  (selected){RESET}");
+        } else if *score >= MIN_INTENT_CONFIDENCE {
+            println!("  {name:<width$}  {score:.2}");
+        } else {
+            println!("  {DIM}{name:<width$}  {score:.2}{RESET}");
+        }
+    }
+    println!();
+    if selected.is_none() {
+        println!(
+            "{YELLOW}No tool reaches {MIN_INTENT_CONFIDENCE:.2}; the input is not routed to a tool.{RESET}"
+        );
+    }
+}
+
+/// Handle `kaido diagnose`: one problem, a resumed run, the list of
+/// resumable runs, or the agent prompt when given nothing
+async fn run_diagnose(problem: &str, resume: Option<&str>, list: bool) -> anyhow::Result<()> {
+    if list {
+        let runs = CheckpointStore::new()?.list()?;
+        if runs.is_empty() {
+            println!("{DIM}No interrupted runs to resume.{RESET}");
+        }
+        for run in runs {
+            let age = run
+                .history
+                .last()
+                .and_then(|step| step.timestamp.elapsed().ok())
+                .map(|elapsed| format!("{}m ago", elapsed.as_secs() / 60))
+                .unwrap_or_default();
+            println!(
+                "{CYAN}{}{RESET}  {}  {DIM}{} steps, {age}{RESET}",
+                run.id,
+                run.task,
+                run.history.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let mut repl = KaidoREPL::new()?;
+    match resume {
+        Some(id) => repl.resume(id).await,
+        None if problem.trim().is_empty() => repl.run().await,
+        None => repl.run_agent(problem).await,
+    }
+}
+
+/// Print the `kaido doctor` checklist; exits non-zero if a check failed
+async fn run_doctor() {
+    use kaido::doctor::CheckStatus;
+
+    println!("{DIM}Checking your Kaido setup...{RESET}");
+    println!();
+    let checks = kaido::doctor::run_checks().await;
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+
+    for check in &checks {
+        let mark = match check.status {
+            CheckStatus::Pass => format!("{GREEN}✓{RESET}"),
+            CheckStatus::Warn => format!("{YELLOW}!{RESET}"),
+            CheckStatus::Fail => "\x1b[31m✗\x1b[0m".to_string(),
+        };
+        println!("  {mark} {:<width$}  {}", check.name, check.detail);
+        if let Some(fix) = &check.fix {
+            println!("    {:<width$}  {DIM}→ {fix}{RESET}", "");
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    let warned = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Warn)
+        .count();
+    println!();
+    if failed > 0 {
+        println!("{BOLD}{failed} problem(s){RESET} and {warned} warning(s) found.");
+        std::process::exit(1);
+    }
+    if warned > 0 {
+        println!("{GREEN}Kaido will work{RESET}, with {warned} warning(s).");
+    } else {
+        println!("{GREEN}Everything looks good.{RESET}");
+    }
+}
+
+/// Write the `kaido bug-report` bundle and say where it went
+async fn run_bug_report(
+    output: Option<std::path::PathBuf>,
+    transcript: Option<&std::path::Path>,
+    log_lines: usize,
+) -> anyhow::Result<()> {
+    use kaido::bug_report::{default_output_path, BugReport};
+
+    println!("{DIM}Collecting config, doctor checks and logs...{RESET}");
+    let report = BugReport::collect(log_lines, transcript).await?;
+    let path = output.unwrap_or_else(default_output_path);
+    std::fs::write(&path, report.render())
+        .map_err(|e| anyhow::anyhow!("Cannot write {}: {e}", path.display()))?;
+
+    println!(
+        "{GREEN}✓{RESET} Bug report written to {BOLD}{}{RESET}",
+        path.display()
+    );
+    println!("{DIM}Secrets are redacted; skim it, then attach it to your GitHub issue.{RESET}");
+    Ok(())
+}
+
+/// Handle `kaido learning reset|export|import`
+fn run_learning_action(action: LearningAction) -> anyhow::Result<()> {
+    let mut tracker = LearningTracker::with_default_path()?;
+
+    match action {
+        LearningAction::Reset { yes } => {
+            if !yes {
+                print!("Delete all learning history? This cannot be undone. [y/N]: ");
+                io::stdout().flush()?;
+
+                let mut response = String::new();
+                io::stdin().read_line(&mut response)?;
+                if !matches!(response.trim().to_lowercase().as_str(), "y" | "yes") {
+                    println!("{DIM}Reset cancelled.{RESET}");
+                    return Ok(());
+                }
+            }
+
+            tracker.reset()?;
+            println!("{GREEN}✓{RESET} Learning history cleared");
+        }
+        LearningAction::Export { file } => {
+            std::fs::write(&file, tracker.export_json()?)?;
+            println!(
+                "{GREEN}✓{RESET} Learning history written to {}",
+                file.display()
+            );
+        }
+        LearningAction::Import { file } => {
+            let json = std::fs::read_to_string(&file)?;
+            let summary = tracker.import_json(&json)?;
+            println!(
+                "{GREEN}✓{RESET} Imported {}: {} added, {} newly resolved, {} already present",
+                file.display(),
+                summary.added,
+                summary.resolved,
+                summary.duplicates
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle `kaido audit show`
+fn run_audit_action(action: AuditAction) -> anyhow::Result<()> {
+    let AuditAction::Show {
+        since,
+        env,
+        risk,
+        limit,
+    } = action;
+
+    let since = since.as_deref().map(parse_since_arg).transpose()?;
+    let min_risk = risk.map(|risk| match risk.as_str() {
+        "high" => kaido::kubectl::RiskLevel::High,
+        "medium" => kaido::kubectl::RiskLevel::Medium,
+        _ => kaido::kubectl::RiskLevel::Low,
+    });
+
+    let path = Config::load().unwrap_or_default().audit.database_path;
+    if !path.exists() {
+        println!("{DIM}No commands recorded yet.{RESET}");
+        return Ok(());
+    }
+    let query = AuditQuery::new(&path.to_string_lossy())?;
+    let results = query.query(&AuditFilter {
+        since,
+        environment: env,
+        min_risk,
+        limit,
+    })?;
+    if results.is_empty() {
+        println!("{DIM}No commands match these filters.{RESET}");
+        return Ok(());
+    }
+    print!("{}", AuditQuery::format_table(&results, limit));
+
+    Ok(())
+}
+
+/// Parse a `--since` value into a Unix timestamp
+fn parse_since_arg(since: &str) -> anyhow::Result<i64> {
+    parse_since(since, AuditLogger::current_timestamp()).ok_or_else(|| {
+        anyhow::anyhow!("Invalid --since '{since}'. Use e.g. 30m, 24h, 7d or 2025-10-25")
+    })
+}
+
+/// Handle `kaido tools metrics`
+fn run_tools_metrics(since: Option<String>) -> anyhow::Result<()> {
+    use kaido::audit::ToolMetrics;
+
+    let since = since.as_deref().map(parse_since_arg).transpose()?;
+
+    let path = Config::load().unwrap_or_default().audit.database_path;
+    if !path.exists() {
+        println!("{DIM}No tool calls recorded yet.{RESET}");
+        return Ok(());
+    }
+    let stats = ToolMetrics::new(&path.to_string_lossy())?.summary(since)?;
+    if stats.is_empty() {
+        println!("{DIM}No tool calls recorded in this period.{RESET}");
+        return Ok(());
+    }
+    print!("{}", ToolMetrics::format_table(&stats));
 
     Ok(())
 }
@@ -307,7 +307,7 @@ fn convert_execution_result_for_audit(result: &ExecutionResult) -> crate::kubect
 }
 
 /// Convert tools::RiskLevel to kubectl::RiskLevel for audit
-fn convert_risk_level(risk: RiskLevel) -> crate::kubectl::RiskLevel {
+pub(crate) fn convert_risk_level(risk: RiskLevel) -> crate::kubectl::RiskLevel {
     match risk {
         RiskLevel::Low => crate::kubectl::RiskLevel::Low,
         RiskLevel::Medium => crate::kubectl::RiskLevel::Medium,
@@ -331,6 +331,8 @@ mod tests {
                 command: "kubectl get pods".to_string(),
                 confidence: 95,
                 reasoning: "Standard pod listing command".to_string(),
+                prompt_tokens: None,
+                completion_tokens: None,
             })
         }
     }
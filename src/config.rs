@@ -1,5 +1,15 @@
+use anyhow::Context;
+use serde::de::{DeserializeOwned, IntoDeserializer};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::mentor::Verbosity;
+use crate::secrets::{KeyStorage, SecretStore};
+use crate::shell::confirm::ConfirmMode;
+use crate::shell::highlight::HighlightRule;
+use crate::shell::pty::{DEFAULT_ENV_SNAPSHOT, DEFAULT_PASSTHROUGH};
 
 /// AI provider selection
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -37,6 +47,31 @@ impl Default for OllamaConfig {
     }
 }
 
+/// Gemini API configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GeminiConfig {
+    /// Requests per minute your plan allows; 0 turns the client-side limit off
+    pub requests_per_minute: u32,
+    /// Wait for capacity when the limit is reached instead of failing fast
+    pub wait_when_limited: bool,
+    /// In auto mode, skip Gemini after this many failures in a row (0 = never)
+    pub skip_after_failures: u32,
+    /// How long Gemini is skipped before it's tried again
+    pub skip_cooldown_seconds: u64,
+}
+
+impl Default for GeminiConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 60,
+            wait_when_limited: true,
+            skip_after_failures: 3,
+            skip_cooldown_seconds: 60,
+        }
+    }
+}
+
 /// OpenAI API configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAIConfig {
@@ -153,6 +188,23 @@ pub struct DisplayConfig {
     pub show_reasoning: bool,
     /// Enable explain mode to show educational command breakdowns
     pub explain_mode: bool,
+    /// Mentor color theme: default, light, high-contrast or colorblind-safe
+    #[serde(default)]
+    pub theme: crate::mentor::ColorTheme,
+    /// Custom mentor colors, overriding single roles of the theme
+    #[serde(default)]
+    pub palette: crate::mentor::Palette,
+    /// Mentor language: en or zh-TW (unset = from $LANG)
+    #[serde(default)]
+    pub language: Option<crate::mentor::Language>,
+    /// Short guidance (up to this many characters, no next steps) is shown
+    /// as a one-liner unless verbose is picked explicitly; 0 turns this off
+    #[serde(default = "default_compact_threshold")]
+    pub compact_threshold: usize,
+}
+
+fn default_compact_threshold() -> usize {
+    crate::mentor::DEFAULT_COMPACT_THRESHOLD
 }
 
 impl Default for DisplayConfig {
@@ -161,6 +213,185 @@ impl Default for DisplayConfig {
             show_confidence_threshold: 70,
             show_reasoning: false,
             explain_mode: true, // Default ON for learning-first experience
+            theme: crate::mentor::ColorTheme::default(),
+            palette: crate::mentor::Palette::default(),
+            language: None,
+            compact_threshold: default_compact_threshold(),
+        }
+    }
+}
+
+/// Environment classification for kubectl contexts
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EnvironmentsConfig {
+    /// Rules checked before the built-in prod/staging/dev ones
+    #[serde(default)]
+    pub rules: Vec<crate::kubectl::EnvironmentRule>,
+}
+
+/// Drush site alias settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DrushConfig {
+    /// Alias names treated as production (`*` wildcards), matched against
+    /// each part of `@site.env`
+    pub production_aliases: Vec<String>,
+}
+
+impl Default for DrushConfig {
+    fn default() -> Self {
+        Self {
+            production_aliases: vec!["prod*".to_string(), "live*".to_string()],
+        }
+    }
+}
+
+/// Guardrails for the troubleshooting agent
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AgentConfig {
+    /// Tools the agent may use (kubectl, docker, nginx, ..., or shell for
+    /// anything else); empty allows all
+    pub allowed_tools: Vec<String>,
+    /// Regexes for commands the agent must never run
+    pub denied_commands: Vec<String>,
+}
+
+/// Database connection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// Host names treated as production (`*` wildcards) when a connection
+    /// comes from `$DATABASE_URL`
+    pub production_hosts: Vec<String>,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            production_hosts: vec![
+                "prod*".to_string(),
+                "*.prod.*".to_string(),
+                "*-prod*".to_string(),
+                "live*".to_string(),
+            ],
+        }
+    }
+}
+
+/// How much command output is kept when it is sent to a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TruncationConfig {
+    /// AI error explanations in the shell
+    pub prompt: crate::ai::OutputBudget,
+    /// Diagnostic command output returned by MCP `kaido_diagnose`
+    pub mcp: crate::ai::OutputBudget,
+    /// Bytes of stdout and of stderr a tool captures per command (the tail
+    /// is kept)
+    pub capture_bytes: usize,
+}
+
+impl Default for TruncationConfig {
+    fn default() -> Self {
+        Self {
+            prompt: crate::ai::OutputBudget::PROMPT,
+            mcp: crate::ai::OutputBudget::MCP,
+            capture_bytes: crate::tools::DEFAULT_MAX_CAPTURE_BYTES,
+        }
+    }
+}
+
+/// Named set of AI settings, e.g. `[profiles.work]` for a Gemini-backed
+/// work cluster and `[profiles.home]` for a local Ollama
+///
+/// Unset fields keep the top-level value.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// AI provider: auto, gemini, ollama, or copilot
+    pub provider: Option<AIProvider>,
+    /// Model for the profile's provider (see [`Config::override_model`])
+    pub model: Option<String>,
+    /// Ollama API base URL
+    pub ollama_url: Option<String>,
+}
+
+/// How the mentor chooses its verbosity
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerbosityModeConfig {
+    /// Adapt to the detected skill level
+    #[default]
+    Auto,
+    /// Always use `mentor_verbosity`
+    Fixed,
+}
+
+/// Interactive shell preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellPreferences {
+    pub use_colors: bool,
+    pub show_git_branch: bool,
+    /// Show the kubectl context and its environment in the prompt
+    pub show_kube_context: bool,
+    /// Shell binary used to run commands (default: $SHELL)
+    pub shell: Option<String>,
+    /// Mentor verbosity when `verbosity_mode` is fixed
+    pub mentor_verbosity: Verbosity,
+    pub verbosity_mode: VerbosityModeConfig,
+    /// AI-powered explanations (false = pattern-based only)
+    pub ai_enabled: bool,
+    pub show_suggestions: bool,
+    /// Capture stdout and stderr separately instead of running under a PTY
+    pub separate_streams: bool,
+    /// Page output taller than the terminal through $PAGER
+    pub page_long_output: bool,
+    /// When to ask before running Medium+ risk commands
+    pub confirm_mode: ConfirmMode,
+    /// Mask secrets in prompts sent to cloud AI providers
+    pub redact_secrets: bool,
+    /// Programs that get the real terminal instead of captured output
+    pub passthrough_commands: Vec<String>,
+    /// One line instead of the full mentor block for an error seen before
+    /// in the session
+    pub collapse_repeated_errors: bool,
+    /// Suggest builtins like `why` and `verbose` to beginners now and then
+    pub feature_hints: bool,
+    /// Show the welcome banner when the shell starts
+    pub show_banner: bool,
+    /// File printed instead of the built-in banner, like /etc/motd
+    pub banner_path: Option<PathBuf>,
+    /// Environment variables recorded with each command for error reports
+    /// and transcripts (secrets masked); empty to record nothing
+    pub env_snapshot: Vec<String>,
+    /// Regex → color rules for lines of command output
+    pub output_highlights: Vec<HighlightRule>,
+}
+
+impl Default for ShellPreferences {
+    fn default() -> Self {
+        Self {
+            use_colors: true,
+            show_git_branch: true,
+            show_kube_context: true,
+            shell: None,
+            mentor_verbosity: Verbosity::Normal,
+            verbosity_mode: VerbosityModeConfig::Auto,
+            ai_enabled: true,
+            show_suggestions: true,
+            separate_streams: false,
+            page_long_output: false,
+            confirm_mode: ConfirmMode::Smart,
+            redact_secrets: true,
+            passthrough_commands: DEFAULT_PASSTHROUGH.iter().map(|p| p.to_string()).collect(),
+            collapse_repeated_errors: false,
+            feature_hints: true,
+            show_banner: true,
+            banner_path: None,
+            env_snapshot: DEFAULT_ENV_SNAPSHOT.iter().map(|v| v.to_string()).collect(),
+            output_highlights: Vec::new(),
         }
     }
 }
@@ -178,12 +409,72 @@ pub struct Config {
     /// GitHub Copilot configuration
     #[serde(default)]
     pub copilot: CopilotConfig,
+    /// Gemini rate limiting
+    #[serde(default)]
+    pub gemini: GeminiConfig,
     pub audit: AuditConfig,
     pub safety: SafetyConfig,
     pub display: DisplayConfig,
+    /// Rules for classifying kubectl contexts as production, staging, etc.
+    #[serde(default)]
+    pub environments: EnvironmentsConfig,
+    /// Drush site aliases that point at production
+    #[serde(default)]
+    pub drush: DrushConfig,
+    /// Database hosts that are production
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    /// Tools and commands the agent may run
+    #[serde(default)]
+    pub agent: AgentConfig,
+    /// Head/tail byte budgets for command output sent to models
+    #[serde(default)]
+    pub truncation: TruncationConfig,
+    /// Interactive shell preferences
+    #[serde(default)]
+    pub shell: ShellPreferences,
+    /// Profile applied on startup unless `--profile` picks another
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Named AI settings, switchable with `--profile` or `profile <name>`
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
 
     /// Gemini API key (optional, can also be set via GEMINI_API_KEY env var)
     pub gemini_api_key: Option<String>,
+    /// Where API keys are kept: plaintext (this file), keychain or encrypted
+    #[serde(default)]
+    pub key_storage: KeyStorage,
+}
+
+/// Name of the Gemini API key in the keychain and secrets directory
+const GEMINI_API_KEY_SECRET: &str = "gemini_api_key";
+
+/// A config problem found by [`Config::validate`], with how to fix it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Dotted key path of the offending setting
+    pub key: String,
+    /// What is wrong
+    pub problem: String,
+    /// What to do about it
+    pub fix: String,
+}
+
+impl ConfigIssue {
+    fn new(key: impl Into<String>, problem: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            problem: problem.into(),
+            fix: fix.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} → {}", self.key, self.problem, self.fix)
+    }
 }
 
 impl Config {
@@ -196,10 +487,289 @@ impl Config {
         }
 
         let contents = std::fs::read_to_string(&config_path)?;
-        let config: Config = toml::from_str(&contents)?;
+        let mut config: Config = toml::from_str(&contents)?;
+        // $GEMINI_API_KEY wins anyway, so the keychain isn't asked
+        let key_in_env = std::env::var("GEMINI_API_KEY").is_ok_and(|key| !key.is_empty());
+        if config.key_storage != KeyStorage::Plaintext && !key_in_env {
+            config.load_stored_secrets()?;
+        }
+        Ok(config)
+    }
+
+    /// Stored API keys, read from the keychain or encrypted files once per
+    /// process
+    ///
+    /// Config is loaded on hot paths (tool context, risk checks), and a
+    /// keychain lookup can be slow or even prompt. Saving a key resets it.
+    fn stored_secrets() -> &'static Mutex<Option<(KeyStorage, Option<String>)>> {
+        static STORED: Mutex<Option<(KeyStorage, Option<String>)>> = Mutex::new(None);
+        &STORED
+    }
+
+    /// Fill in API keys kept in the keychain or encrypted files, from the
+    /// per-process copy
+    ///
+    /// A stored key wins over a plaintext one left in config.toml.
+    fn load_stored_secrets(&mut self) -> anyhow::Result<()> {
+        let mut stored = Self::stored_secrets()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !matches!(&*stored, Some((storage, _)) if *storage == self.key_storage) {
+            let key = self.read_secret(&SecretStore::new()?);
+            *stored = Some((self.key_storage, key));
+        }
+        if let Some((_, Some(key))) = &*stored {
+            self.gemini_api_key = Some(key.clone());
+        }
+        Ok(())
+    }
+
+    /// The Gemini API key in `store`, if one is saved there
+    fn read_secret(&self, store: &SecretStore) -> Option<String> {
+        store
+            .load(GEMINI_API_KEY_SECRET, self.key_storage)
+            .unwrap_or_else(|e| {
+                log::warn!(
+                    "Could not read the Gemini API key ({}): {e}",
+                    self.key_storage.as_str()
+                );
+                None
+            })
+    }
+
+    /// Copy to write to config.toml, with API keys moved into `store`
+    /// unless they are kept in plaintext
+    fn with_secrets_stored(&self, store: &SecretStore) -> anyhow::Result<Self> {
+        let mut stored = self.clone();
+        if self.key_storage != KeyStorage::Plaintext {
+            if let Some(key) = stored.gemini_api_key.take().filter(|key| !key.is_empty()) {
+                store.save(GEMINI_API_KEY_SECRET, &key, self.key_storage)?;
+                *Self::stored_secrets()
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = None;
+            }
+        }
+        Ok(stored)
+    }
+
+    /// Load configuration and apply a profile: `profile` if given, else
+    /// `active_profile`
+    ///
+    /// A requested profile that doesn't exist is an error; a stale
+    /// `active_profile` only logs a warning.
+    pub fn load_with_profile(profile: Option<&str>) -> anyhow::Result<Self> {
+        let mut config = Self::load()?;
+        match (profile, config.active_profile.clone()) {
+            (Some(name), _) => config.apply_profile(name)?,
+            (None, Some(name)) => {
+                if let Err(e) = config.apply_profile(&name) {
+                    log::warn!("Ignoring active_profile: {e}");
+                    config.active_profile = None;
+                }
+            }
+            (None, None) => {}
+        }
         Ok(config)
     }
 
+    /// Profile names, sorted
+    pub fn profile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Overlay a named profile onto this config (session only, not saved)
+    pub fn apply_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            if self.profiles.is_empty() {
+                anyhow::bail!(
+                    "Unknown profile '{name}'. No profiles are defined; add a [profiles.{name}] table to config.toml"
+                );
+            }
+            anyhow::bail!(
+                "Unknown profile '{name}'. Available profiles: {}",
+                self.profile_names().join(", ")
+            );
+        };
+
+        let context = || format!("Invalid profile '{name}'");
+        if let Some(provider) = profile.provider {
+            self.provider = provider;
+        }
+        if let Some(url) = &profile.ollama_url {
+            self.set_value("ollama.base_url", url)
+                .with_context(context)?;
+        }
+        if let Some(model) = &profile.model {
+            self.override_model(model).with_context(context)?;
+        }
+        self.active_profile = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Check settings that would otherwise fail at first use
+    ///
+    /// Only looks at the config and environment; whether Ollama is actually
+    /// up is checked separately by `AIManager::check_provider`.
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        self.validate_with_env(|name| std::env::var(name).ok())
+    }
+
+    /// [`Config::validate`] given an environment lookup
+    pub fn validate_with_env(&self, var: impl Fn(&str) -> Option<String>) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        let set = |key: &str, example: &str| format!("run `kaido config set {key} {example}`");
+
+        if parse_url("ollama.base_url", &self.ollama.base_url).is_err() {
+            issues.push(ConfigIssue::new(
+                "ollama.base_url",
+                format!(
+                    "'{}' is not an http:// or https:// URL",
+                    self.ollama.base_url
+                ),
+                set("ollama.base_url", "http://localhost:11434"),
+            ));
+        }
+        if self.ollama.model.trim().is_empty() {
+            issues.push(ConfigIssue::new(
+                "ollama.model",
+                "no model is set",
+                set("ollama.model", "llama3.2"),
+            ));
+        }
+        if self.ollama.timeout_seconds == 0 {
+            issues.push(ConfigIssue::new(
+                "ollama.timeout_seconds",
+                "a timeout of 0 fails every request",
+                set("ollama.timeout_seconds", "120"),
+            ));
+        }
+
+        match self.provider {
+            AIProvider::Gemini => {
+                let has_key = self
+                    .gemini_api_key
+                    .as_deref()
+                    .or(var("GEMINI_API_KEY").as_deref())
+                    .is_some_and(|key| !key.trim().is_empty());
+                if !has_key {
+                    issues.push(ConfigIssue::new(
+                        "provider",
+                        "provider is gemini but no Gemini API key is configured",
+                        "export GEMINI_API_KEY=<key> or run `kaido init`",
+                    ));
+                }
+            }
+            AIProvider::Copilot => {
+                if self.copilot.token.is_empty() && CopilotConfig::load_token().is_none() {
+                    issues.push(ConfigIssue::new(
+                        "provider",
+                        "provider is copilot but no Copilot token was found",
+                        "set copilot.token in config.toml or log in to Copilot with OpenCode",
+                    ));
+                }
+                if self.copilot.model.trim().is_empty() {
+                    issues.push(ConfigIssue::new(
+                        "copilot.model",
+                        "no model is set",
+                        set("copilot.model", "gpt-4o"),
+                    ));
+                }
+            }
+            AIProvider::Auto | AIProvider::Ollama => {}
+        }
+
+        if let Some(name) = &self.active_profile {
+            if !self.profiles.contains_key(name) {
+                issues.push(ConfigIssue::new(
+                    "active_profile",
+                    format!("profile '{name}' is not defined"),
+                    format!("add a [profiles.{name}] table or remove active_profile"),
+                ));
+            }
+        }
+        for name in self.profile_names() {
+            if let Some(url) = &self.profiles[name].ollama_url {
+                if parse_url("ollama_url", url).is_err() {
+                    issues.push(ConfigIssue::new(
+                        format!("profiles.{name}.ollama_url"),
+                        format!("'{url}' is not an http:// or https:// URL"),
+                        "use a full URL such as http://localhost:11434",
+                    ));
+                }
+            }
+        }
+
+        for role in self.display.palette.invalid_roles() {
+            issues.push(ConfigIssue::new(
+                format!("display.palette.{role}"),
+                "not an SGR color code",
+                "use numbers separated by ';', such as \"1;36\" or \"38;5;208\"",
+            ));
+        }
+
+        for (i, rule) in self.shell.output_highlights.iter().enumerate() {
+            if let Err(e) = regex::Regex::new(&rule.pattern) {
+                issues.push(ConfigIssue::new(
+                    format!("shell.output_highlights[{i}].pattern"),
+                    format!("'{}' is not a valid regex ({e})", rule.pattern),
+                    "fix the pattern; until then the rule is skipped",
+                ));
+            }
+            if !crate::mentor::colors::is_sgr(&rule.color) {
+                issues.push(ConfigIssue::new(
+                    format!("shell.output_highlights[{i}].color"),
+                    "not an SGR color code",
+                    "use numbers separated by ';', such as \"31\" or \"1;33\"",
+                ));
+            }
+        }
+
+        for (i, pattern) in self.agent.denied_commands.iter().enumerate() {
+            if let Err(e) = regex::Regex::new(pattern) {
+                issues.push(ConfigIssue::new(
+                    format!("agent.denied_commands[{i}]"),
+                    format!("'{pattern}' is not a valid regex ({e})"),
+                    "fix the pattern; the agent won't start until then",
+                ));
+            }
+        }
+
+        for (i, rule) in self.environments.rules.iter().enumerate() {
+            if let Err(e) = regex::Regex::new(&rule.pattern) {
+                issues.push(ConfigIssue::new(
+                    format!("environments.rules[{i}].pattern"),
+                    format!("'{}' is not a valid regex ({e})", rule.pattern),
+                    "fix the pattern; until then the rule never matches",
+                ));
+            }
+        }
+
+        if self.display.show_confidence_threshold > 100 {
+            issues.push(ConfigIssue::new(
+                "display.show_confidence_threshold",
+                format!(
+                    "{} is above 100, so confidence is never shown",
+                    self.display.show_confidence_threshold
+                ),
+                "use a percentage between 0 and 100",
+            ));
+        }
+        if self.truncation.capture_bytes == 0 {
+            issues.push(ConfigIssue::new(
+                "truncation.capture_bytes",
+                "0 drops all command output",
+                format!(
+                    "remove it or set it to e.g. {}",
+                    crate::tools::DEFAULT_MAX_CAPTURE_BYTES
+                ),
+            ));
+        }
+
+        issues
+    }
+
     /// Save configuration to TOML file
     pub fn save(&self) -> anyhow::Result<()> {
         let config_path = Self::get_config_path()?;
@@ -209,7 +779,12 @@ impl Config {
             std::fs::create_dir_all(parent)?;
         }
 
-        let contents = toml::to_string_pretty(self)?;
+        let stored = if self.key_storage == KeyStorage::Plaintext {
+            self.clone()
+        } else {
+            self.with_secrets_stored(&SecretStore::new()?)?
+        };
+        let contents = toml::to_string_pretty(&stored)?;
         std::fs::write(&config_path, contents)?;
 
         // Set permissions to 600 (user read/write only) on Unix
@@ -230,6 +805,225 @@ impl Config {
 
         Ok(home.join(".kaido").join("config.toml"))
     }
+
+    /// Keys accepted by [`Config::set_value`]
+    pub const SETTABLE_KEYS: &'static [&'static str] = &[
+        "provider",
+        "ollama.model",
+        "ollama.base_url",
+        "ollama.timeout_seconds",
+        "copilot.model",
+        "gemini.requests_per_minute",
+        "gemini.wait_when_limited",
+        "gemini.skip_after_failures",
+        "gemini.skip_cooldown_seconds",
+        "audit.retention_days",
+        "safety.confirm_destructive",
+        "display.explain_mode",
+        "display.show_reasoning",
+        "display.theme",
+        "display.language",
+        "display.compact_threshold",
+        "shell.use_colors",
+        "shell.show_git_branch",
+        "shell.show_kube_context",
+        "shell.mentor_verbosity",
+        "shell.verbosity_mode",
+        "shell.show_suggestions",
+        "shell.confirm_mode",
+        "shell.redact_secrets",
+        "shell.collapse_repeated_errors",
+        "shell.feature_hints",
+        "shell.show_banner",
+        "shell.banner_path",
+        "active_profile",
+        "gemini_api_key",
+        "key_storage",
+    ];
+
+    /// Set a single value by dotted key path, validating the value type
+    ///
+    /// Unknown keys are rejected with the list of valid keys.
+    pub fn set_value(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "provider" => {
+                self.provider = match value.to_lowercase().as_str() {
+                    "auto" => AIProvider::Auto,
+                    "gemini" => AIProvider::Gemini,
+                    "ollama" => AIProvider::Ollama,
+                    "copilot" => AIProvider::Copilot,
+                    _ => anyhow::bail!(
+                        "Invalid provider '{value}'. Valid values: auto, gemini, ollama, copilot"
+                    ),
+                };
+            }
+            "ollama.model" => self.ollama.model = parse_non_empty(key, value)?,
+            "ollama.base_url" => self.ollama.base_url = parse_url(key, value)?,
+            "ollama.timeout_seconds" => self.ollama.timeout_seconds = parse_number(key, value)?,
+            "copilot.model" => self.copilot.model = parse_non_empty(key, value)?,
+            "gemini.requests_per_minute" => {
+                self.gemini.requests_per_minute = parse_number(key, value)?
+            }
+            "gemini.wait_when_limited" => self.gemini.wait_when_limited = parse_bool(key, value)?,
+            "gemini.skip_after_failures" => {
+                self.gemini.skip_after_failures = parse_number(key, value)?
+            }
+            "gemini.skip_cooldown_seconds" => {
+                self.gemini.skip_cooldown_seconds = parse_number(key, value)?
+            }
+            "audit.retention_days" => self.audit.retention_days = parse_number(key, value)?,
+            "safety.confirm_destructive" => {
+                self.safety.confirm_destructive = parse_bool(key, value)?
+            }
+            "display.explain_mode" => self.display.explain_mode = parse_bool(key, value)?,
+            "display.show_reasoning" => self.display.show_reasoning = parse_bool(key, value)?,
+            "display.theme" => {
+                self.display.theme =
+                    parse_choice(key, value, "default, light, high-contrast, colorblind-safe")?
+            }
+            "display.language" => {
+                self.display.language = match value.trim() {
+                    "auto" => None,
+                    _ => Some(parse_choice(key, value, "auto, en, zh-TW")?),
+                }
+            }
+            "display.compact_threshold" => {
+                self.display.compact_threshold = parse_number(key, value)?
+            }
+            "shell.use_colors" => self.shell.use_colors = parse_bool(key, value)?,
+            "shell.show_git_branch" => self.shell.show_git_branch = parse_bool(key, value)?,
+            "shell.show_kube_context" => self.shell.show_kube_context = parse_bool(key, value)?,
+            // Picking a level only sticks if the mode is fixed, so set both
+            "shell.mentor_verbosity" => {
+                self.shell.mentor_verbosity =
+                    parse_choice(key, value, "verbose, normal, compact, debug")?;
+                self.shell.verbosity_mode = VerbosityModeConfig::Fixed;
+            }
+            "shell.verbosity_mode" => {
+                self.shell.verbosity_mode = parse_choice(key, value, "auto, fixed")?
+            }
+            "shell.show_suggestions" => self.shell.show_suggestions = parse_bool(key, value)?,
+            "shell.confirm_mode" => {
+                self.shell.confirm_mode = parse_choice(key, value, "smart, always, never")?
+            }
+            "shell.redact_secrets" => self.shell.redact_secrets = parse_bool(key, value)?,
+            "shell.collapse_repeated_errors" => {
+                self.shell.collapse_repeated_errors = parse_bool(key, value)?
+            }
+            "shell.feature_hints" => self.shell.feature_hints = parse_bool(key, value)?,
+            "shell.show_banner" => self.shell.show_banner = parse_bool(key, value)?,
+            "shell.banner_path" => {
+                self.shell.banner_path = match value.trim() {
+                    "" | "none" => None,
+                    path => Some(PathBuf::from(path)),
+                }
+            }
+            "active_profile" => {
+                let name = parse_non_empty(key, value)?;
+                if !self.profiles.contains_key(&name) {
+                    anyhow::bail!(
+                        "Unknown profile '{name}'. Available profiles: {}",
+                        self.profile_names().join(", ")
+                    );
+                }
+                self.active_profile = Some(name);
+            }
+            // A key set here is kept out of config.toml; set key_storage to
+            // plaintext afterwards to opt out
+            "gemini_api_key" => {
+                self.gemini_api_key = Some(parse_non_empty(key, value)?);
+                if self.key_storage == KeyStorage::Plaintext {
+                    self.key_storage = KeyStorage::Keychain;
+                }
+            }
+            "key_storage" => {
+                self.key_storage = parse_choice(key, value, "plaintext, keychain, encrypted")?
+            }
+            _ => anyhow::bail!(
+                "Unknown config key '{key}'. Valid keys: {}",
+                Self::SETTABLE_KEYS.join(", ")
+            ),
+        }
+        Ok(())
+    }
+
+    /// Override the model for the selected provider (session only, not saved)
+    ///
+    /// `auto` falls back to Ollama after Gemini, so the model applies to
+    /// Ollama there. Gemini's model is fixed and cannot be overridden.
+    pub fn override_model(&mut self, model: &str) -> anyhow::Result<()> {
+        let key = match self.provider {
+            AIProvider::Auto | AIProvider::Ollama => "ollama.model",
+            AIProvider::Copilot => "copilot.model",
+            AIProvider::Gemini => {
+                anyhow::bail!("The Gemini model is fixed; --model applies to ollama or copilot")
+            }
+        };
+        self.set_value(key, model)
+    }
+
+    /// Copy of this config with API keys and tokens masked, for display
+    pub fn masked(&self) -> Self {
+        let mut masked = self.clone();
+        masked.gemini_api_key = masked.gemini_api_key.as_deref().map(mask_secret);
+        if !masked.ai.api_key.is_empty() {
+            masked.ai.api_key = mask_secret(&masked.ai.api_key);
+        }
+        if !masked.copilot.token.is_empty() {
+            masked.copilot.token = mask_secret(&masked.copilot.token);
+        }
+        masked
+    }
+}
+
+/// Mask a secret, keeping only enough characters to recognise it
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        "****".to_string()
+    } else {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{head}...{tail}")
+    }
+}
+
+fn parse_non_empty(key: &str, value: &str) -> anyhow::Result<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        anyhow::bail!("'{key}' cannot be empty");
+    }
+    Ok(value.to_string())
+}
+
+fn parse_url(key: &str, value: &str) -> anyhow::Result<String> {
+    let value = parse_non_empty(key, value)?;
+    if !value.starts_with("http://") && !value.starts_with("https://") {
+        anyhow::bail!("'{key}' must be an http:// or https:// URL, got '{value}'");
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
+
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> anyhow::Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("'{key}' must be a non-negative integer, got '{value}'"))
+}
+
+fn parse_bool(key: &str, value: &str) -> anyhow::Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => anyhow::bail!("'{key}' must be true or false, got '{value}'"),
+    }
+}
+
+fn parse_choice<T: DeserializeOwned>(key: &str, value: &str, choices: &str) -> anyhow::Result<T> {
+    let normalized = value.trim().to_lowercase();
+    T::deserialize(normalized.into_deserializer()).map_err(|_: serde::de::value::Error| {
+        anyhow::anyhow!("'{key}' must be one of {choices}, got '{value}'")
+    })
 }
 
 /// Logging level enumeration (preserved for backward compatibility)
@@ -257,3 +1051,329 @@ impl std::fmt::Display for LogLevel {
         write!(f, "{}", self.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_value() {
+        let mut config = Config::default();
+
+        config.set_value("provider", "Ollama").unwrap();
+        assert_eq!(config.provider, AIProvider::Ollama);
+
+        config.set_value("ollama.model", "qwen2.5").unwrap();
+        assert_eq!(config.ollama.model, "qwen2.5");
+
+        config
+            .set_value("ollama.base_url", "http://gpu-box:11434/")
+            .unwrap();
+        assert_eq!(config.ollama.base_url, "http://gpu-box:11434");
+
+        config.set_value("ollama.timeout_seconds", "60").unwrap();
+        assert_eq!(config.ollama.timeout_seconds, 60);
+
+        config.set_value("display.explain_mode", "off").unwrap();
+        assert!(!config.display.explain_mode);
+    }
+
+    #[test]
+    fn test_set_value_rejects_bad_values() {
+        let mut config = Config::default();
+
+        assert!(config.set_value("provider", "openai").is_err());
+        assert!(config
+            .set_value("ollama.base_url", "localhost:11434")
+            .is_err());
+        assert!(config.set_value("ollama.timeout_seconds", "-5").is_err());
+        assert!(config
+            .set_value("safety.confirm_destructive", "maybe")
+            .is_err());
+        assert!(config.set_value("ollama.model", "  ").is_err());
+
+        // Nothing was changed by the failed attempts
+        assert_eq!(config.provider, AIProvider::Auto);
+        assert_eq!(config.ollama.base_url, "http://localhost:11434");
+    }
+
+    #[test]
+    fn test_override_model_targets_provider() {
+        let mut config = Config::default();
+        config.override_model("qwen2.5:14b").unwrap();
+        assert_eq!(config.ollama.model, "qwen2.5:14b");
+
+        config.provider = AIProvider::Copilot;
+        config.override_model("gpt-5-mini").unwrap();
+        assert_eq!(config.copilot.model, "gpt-5-mini");
+
+        config.provider = AIProvider::Gemini;
+        assert!(config.override_model("gemini-2.5-pro").is_err());
+        assert_eq!(config.ollama.model, "qwen2.5:14b");
+    }
+
+    #[test]
+    fn test_set_value_unknown_key_lists_valid_keys() {
+        let mut config = Config::default();
+        let err = config.set_value("ollama.modle", "llama3.2").unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("ollama.modle"));
+        assert!(msg.contains("ollama.model"));
+        assert!(msg.contains("provider"));
+    }
+
+    #[test]
+    fn test_masked_hides_secrets() {
+        let config = Config {
+            gemini_api_key: Some("AIzaSyExampleKey1234".to_string()),
+            ..Default::default()
+        };
+
+        let masked = config.masked();
+        assert_eq!(masked.gemini_api_key.as_deref(), Some("AIza...1234"));
+
+        let rendered = toml::to_string_pretty(&masked).unwrap();
+        assert!(!rendered.contains("AIzaSyExampleKey1234"));
+    }
+
+    #[test]
+    fn test_api_key_kept_out_of_config_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = SecretStore::with_dir(dir.path()).without_keychain();
+
+        let mut config = Config::default();
+        config
+            .set_value("gemini_api_key", "AIzaSyExampleKey1234")
+            .unwrap();
+        assert_eq!(config.key_storage, KeyStorage::Keychain);
+
+        let stored = config.with_secrets_stored(&store).unwrap();
+        let rendered = toml::to_string_pretty(&stored).unwrap();
+        assert!(!rendered.contains("AIzaSyExampleKey1234"));
+        assert!(rendered.contains(r#"key_storage = "keychain""#));
+
+        // The stored key is found past a stale plaintext one
+        let mut loaded: Config =
+            toml::from_str(&format!("gemini_api_key = \"old\"\n{rendered}")).unwrap();
+        loaded.gemini_api_key = loaded.read_secret(&store);
+        assert_eq!(
+            loaded.gemini_api_key.as_deref(),
+            Some("AIzaSyExampleKey1234")
+        );
+
+        // Switching back to plaintext writes the key to the file again
+        loaded.set_value("key_storage", "plaintext").unwrap();
+        let rendered =
+            toml::to_string_pretty(&loaded.with_secrets_stored(&store).unwrap()).unwrap();
+        assert!(rendered.contains("AIzaSyExampleKey1234"));
+        assert!(config.set_value("key_storage", "vault").is_err());
+    }
+
+    #[test]
+    fn test_environment_rules_from_toml() {
+        use crate::kubectl::{EnvironmentRule, EnvironmentType};
+
+        let mut config = Config::default();
+        config.environments.rules =
+            vec![EnvironmentRule::new("-live$", EnvironmentType::Production)];
+        let rendered = toml::to_string_pretty(&config).unwrap();
+        assert!(rendered.contains("environment = \"production\""));
+
+        let parsed: Config = toml::from_str(&rendered).unwrap();
+        assert_eq!(
+            EnvironmentType::classify("acme-live", &parsed.environments.rules),
+            EnvironmentType::Production
+        );
+    }
+
+    #[test]
+    fn test_shell_preferences() {
+        let mut config = Config::default();
+        config.shell.mentor_verbosity = Verbosity::Compact;
+        config.shell.verbosity_mode = VerbosityModeConfig::Fixed;
+        config.shell.confirm_mode = ConfirmMode::Always;
+        let rendered = toml::to_string_pretty(&config).unwrap();
+        assert!(rendered.contains("mentor_verbosity = \"compact\""));
+
+        let parsed: Config = toml::from_str(&rendered).unwrap();
+        assert_eq!(parsed.shell.mentor_verbosity, Verbosity::Compact);
+        assert_eq!(parsed.shell.verbosity_mode, VerbosityModeConfig::Fixed);
+        assert_eq!(parsed.shell.confirm_mode, ConfirmMode::Always);
+        assert!(parsed.shell.use_colors);
+        assert!(parsed.shell.redact_secrets);
+
+        let mut config = Config::default();
+        config
+            .set_value("shell.mentor_verbosity", "Verbose")
+            .unwrap();
+        assert_eq!(config.shell.mentor_verbosity, Verbosity::Verbose);
+        assert_eq!(config.shell.verbosity_mode, VerbosityModeConfig::Fixed);
+        config.set_value("shell.verbosity_mode", "auto").unwrap();
+        assert_eq!(config.shell.verbosity_mode, VerbosityModeConfig::Auto);
+        assert!(config.set_value("shell.confirm_mode", "sometimes").is_err());
+        assert_eq!(config.shell.confirm_mode, ConfirmMode::Smart);
+    }
+
+    #[test]
+    fn test_profiles() {
+        let rendered = toml::to_string_pretty(&Config::default()).unwrap();
+        let toml = format!(
+            "{rendered}\n\
+             [profiles.work]\n\
+             provider = \"gemini\"\n\
+             \n\
+             [profiles.home]\n\
+             provider = \"ollama\"\n\
+             model = \"qwen2.5:14b\"\n\
+             ollama_url = \"http://homelab:11434\"\n"
+        );
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.profile_names(), ["home", "work"]);
+
+        let mut home = config.clone();
+        home.apply_profile("home").unwrap();
+        assert_eq!(home.provider, AIProvider::Ollama);
+        assert_eq!(home.ollama.model, "qwen2.5:14b");
+        assert_eq!(home.ollama.base_url, "http://homelab:11434");
+        assert_eq!(home.active_profile.as_deref(), Some("home"));
+
+        let mut work = config.clone();
+        work.apply_profile("work").unwrap();
+        assert_eq!(work.provider, AIProvider::Gemini);
+        assert_eq!(work.ollama.model, "llama3.2");
+
+        let err = config.clone().apply_profile("office").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown profile 'office'. Available profiles: home, work"));
+
+        let mut config = config;
+        assert!(config.set_value("active_profile", "office").is_err());
+        config.set_value("active_profile", "work").unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_validate() {
+        let no_env = |_: &str| None;
+        assert!(Config::default().validate_with_env(no_env).is_empty());
+
+        let config = Config {
+            provider: AIProvider::Gemini,
+            ollama: OllamaConfig {
+                base_url: "localhost:11434".to_string(),
+                model: String::new(),
+                ..Default::default()
+            },
+            active_profile: Some("work".to_string()),
+            environments: EnvironmentsConfig {
+                rules: vec![crate::kubectl::EnvironmentRule::new(
+                    "prod(",
+                    crate::kubectl::EnvironmentType::Production,
+                )],
+            },
+            ..Default::default()
+        };
+
+        let keys: Vec<String> = config
+            .validate_with_env(no_env)
+            .into_iter()
+            .map(|issue| issue.key)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "ollama.base_url",
+                "ollama.model",
+                "provider",
+                "active_profile",
+                "environments.rules[0].pattern"
+            ]
+        );
+
+        // The Gemini key may come from the environment
+        let issues = config.validate_with_env(|name| {
+            (name == "GEMINI_API_KEY").then(|| "AIzaSyExample".to_string())
+        });
+        assert!(issues.iter().all(|issue| issue.key != "provider"));
+        assert!(issues[0].fix.contains("kaido config set ollama.base_url"));
+    }
+
+    #[test]
+    fn test_display_theme_and_palette() {
+        let mut config = Config::default();
+        config
+            .set_value("display.theme", "colorblind-safe")
+            .unwrap();
+        assert_eq!(
+            config.display.theme,
+            crate::mentor::ColorTheme::ColorblindSafe
+        );
+        assert!(config.set_value("display.theme", "neon").is_err());
+
+        config.set_value("display.language", "zh-TW").unwrap();
+        assert_eq!(
+            config.display.language,
+            Some(crate::mentor::Language::TraditionalChinese)
+        );
+        assert!(config.set_value("display.language", "fr").is_err());
+
+        config.display.palette.key_message = Some("1;38;5;208".to_string());
+        let loaded: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(
+            loaded.display.theme,
+            crate::mentor::ColorTheme::ColorblindSafe
+        );
+        assert_eq!(loaded.display.palette, config.display.palette);
+
+        config.display.palette.border = Some("cyan".to_string());
+        let keys: Vec<String> = config
+            .validate_with_env(|_| None)
+            .into_iter()
+            .map(|issue| issue.key)
+            .collect();
+        assert_eq!(keys, ["display.palette.border"]);
+    }
+
+    #[test]
+    fn test_output_highlights() {
+        let shell: ShellPreferences = toml::from_str(
+            r#"
+            [[output_highlights]]
+            pattern = '\bERROR\b'
+            color = "1;31"
+
+            [[output_highlights]]
+            pattern = "WARN("
+            color = "yellow"
+            "#,
+        )
+        .unwrap();
+        let config = Config {
+            shell,
+            ..Config::default()
+        };
+        assert_eq!(
+            config.shell.output_highlights[0],
+            HighlightRule::new(r"\bERROR\b", "1;31")
+        );
+
+        let keys: Vec<String> = config
+            .validate_with_env(|_| None)
+            .into_iter()
+            .map(|issue| issue.key)
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "shell.output_highlights[1].pattern",
+                "shell.output_highlights[1].color"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mask_secret_short() {
+        assert_eq!(mask_secret("abc"), "****");
+    }
+}
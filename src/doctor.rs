@@ -0,0 +1,412 @@
+// Setup self-diagnostics
+//
+// `kaido doctor` walks through everything a working install needs: a config
+// that parses, a reachable AI backend, writable databases, the usual tools
+// on PATH and a terminal that renders the mentor's colors. Each check says
+// how to fix what it found, so setup problems don't need a support thread.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::ai::{offline_mode, GeminiBackend, OllamaBackend};
+use crate::config::{AIProvider, Config};
+use crate::tools::availability::find_in_path;
+use crate::tools::LLMBackend;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something is missing or degraded
+    Warn,
+    /// Kaido can't work as configured
+    Fail,
+}
+
+/// One line of the doctor checklist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// What to do about it, for warnings and failures
+    pub fix: Option<String>,
+}
+
+impl Check {
+    pub fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    pub fn warn(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    pub fn fail(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+/// Tools most guidance assumes are installed, with where to get them
+const COMMON_TOOLS: &[(&str, &str)] = &[
+    ("kubectl", "https://kubernetes.io/docs/tasks/tools/"),
+    ("docker", "https://docs.docker.com/get-docker/"),
+];
+
+/// Run every check, in checklist order
+pub async fn run_checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let config = match Config::get_config_path() {
+        Ok(path) => {
+            let (check, config) = check_config(&path);
+            checks.push(check);
+            config
+        }
+        Err(e) => {
+            checks.push(Check::fail(
+                "config",
+                format!("can't locate the config directory: {e}"),
+                "Make sure $HOME is set",
+            ));
+            Config::default()
+        }
+    };
+    for issue in config.validate() {
+        checks.push(Check::warn(
+            format!("config: {}", issue.key),
+            issue.problem,
+            issue.fix,
+        ));
+    }
+
+    if offline_mode() {
+        checks.push(Check::pass("ai", "skipped, offline mode is on"));
+    } else {
+        checks.push(check_gemini(&config).await);
+        checks.push(check_ollama(&config).await);
+    }
+
+    for (name, path) in [
+        ("history", crate::shell::default_history_path()),
+        (
+            "learning database",
+            crate::learning::default_learning_db_path(),
+        ),
+        ("audit database", config.audit.database_path.clone()),
+    ] {
+        checks.push(check_writable(name, &path));
+    }
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    for (tool, install_url) in COMMON_TOOLS {
+        checks.push(match find_in_path(tool, &path_var) {
+            Some(found) => Check::pass(*tool, found.display().to_string()),
+            None => Check::warn(
+                *tool,
+                "not found on PATH",
+                format!("Install it from {install_url}"),
+            ),
+        });
+    }
+
+    checks.push(check_colors(
+        std::env::var_os("NO_COLOR").is_some(),
+        std::env::var("TERM").ok().as_deref(),
+        std::env::var("COLORTERM").ok().as_deref(),
+        std::io::IsTerminal::is_terminal(&std::io::stdout()),
+    ));
+
+    checks
+}
+
+/// Whether the config file parses; returns the config to check further
+fn check_config(path: &Path) -> (Check, Config) {
+    if !path.exists() {
+        return (
+            Check::warn(
+                "config",
+                format!("{} doesn't exist, using defaults", path.display()),
+                "Run `kaido onboard` to set up an AI backend",
+            ),
+            Config::default(),
+        );
+    }
+
+    let parsed = std::fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| Ok(toml::from_str::<Config>(&contents)?));
+    match parsed {
+        Ok(config) => (Check::pass("config", path.display().to_string()), config),
+        Err(e) => (
+            Check::fail(
+                "config",
+                format!("{} doesn't parse: {e}", path.display()),
+                "Fix the TOML syntax, or move the file away and run `kaido onboard`",
+            ),
+            Config::default(),
+        ),
+    }
+}
+
+/// Problems with the configured provider fail; with a fallback they only warn
+fn backend_check(
+    config: &Config,
+    provider: AIProvider,
+    name: &str,
+    detail: String,
+    fix: &str,
+) -> Check {
+    if config.provider == provider {
+        Check::fail(name, detail, fix)
+    } else {
+        Check::warn(name, detail, fix)
+    }
+}
+
+/// Send the Gemini key a one-word prompt
+async fn check_gemini(config: &Config) -> Check {
+    let key = std::env::var("GEMINI_API_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+        .or_else(|| config.gemini_api_key.clone().filter(|key| !key.is_empty()));
+    let Some(key) = key else {
+        return backend_check(
+            config,
+            AIProvider::Gemini,
+            "gemini",
+            "no API key configured".to_string(),
+            "Set GEMINI_API_KEY or run `kaido onboard`",
+        );
+    };
+
+    match GeminiBackend::with_api_key(key)
+        .infer("Reply with the single word OK.")
+        .await
+    {
+        Ok(_) => Check::pass("gemini", "API key accepted"),
+        Err(e) => backend_check(
+            config,
+            AIProvider::Gemini,
+            "gemini",
+            format!("request failed: {e}"),
+            "Check the key at https://aistudio.google.com/app/apikey",
+        ),
+    }
+}
+
+/// Check the Ollama server answers and has the configured model
+async fn check_ollama(config: &Config) -> Check {
+    let url = &config.ollama.base_url;
+    let model = &config.ollama.model;
+    let ollama = OllamaBackend::with_config(config.ollama.clone());
+    if !ollama.is_available().await {
+        return backend_check(
+            config,
+            AIProvider::Ollama,
+            "ollama",
+            format!("not reachable at {url}"),
+            "Start it with `ollama serve`, or set ollama.base_url",
+        );
+    }
+
+    match ollama.list_models().await {
+        Ok(models)
+            if models
+                .iter()
+                .any(|m| crate::ai::ollama::same_model(m, model)) =>
+        {
+            Check::pass("ollama", format!("{model} available at {url}"))
+        }
+        Ok(_) => backend_check(
+            config,
+            AIProvider::Ollama,
+            "ollama",
+            format!("running, but model {model} isn't pulled"),
+            &format!("Run `ollama pull {model}`"),
+        ),
+        Err(e) => backend_check(
+            config,
+            AIProvider::Ollama,
+            "ollama",
+            format!("couldn't list models: {e}"),
+            "Check the server log (`ollama serve`)",
+        ),
+    }
+}
+
+/// Check a data file (or the directory it will be created in) is writable
+///
+/// Existing files are opened for append, so nothing is modified; for a
+/// missing file a probe file is created and removed next to it.
+fn check_writable(name: &str, path: &Path) -> Check {
+    let fix = || format!("Make {} writable by your user", path.display());
+
+    if path.exists() {
+        return match OpenOptions::new().append(true).open(path) {
+            Ok(_) => Check::pass(name, path.display().to_string()),
+            Err(e) => Check::fail(name, format!("{}: {e}", path.display()), fix()),
+        };
+    }
+
+    let Some(dir) = path.parent() else {
+        return Check::fail(
+            name,
+            format!("{}: no parent directory", path.display()),
+            fix(),
+        );
+    };
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return Check::fail(name, format!("{}: {e}", dir.display()), fix());
+    }
+    let probe = dir.join(format!(".kaido-doctor-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::pass(name, format!("{} (created on first use)", path.display()))
+        }
+        Err(e) => Check::fail(name, format!("{}: {e}", dir.display()), fix()),
+    }
+}
+
+/// Whether mentor output will show its colors
+fn check_colors(no_color: bool, term: Option<&str>, colorterm: Option<&str>, tty: bool) -> Check {
+    const NAME: &str = "colors";
+
+    if no_color {
+        return Check::warn(
+            NAME,
+            "NO_COLOR is set, mentor output is plain text",
+            "Unset NO_COLOR if you want colors",
+        );
+    }
+    if !tty {
+        return Check::warn(
+            NAME,
+            "stdout is not a terminal",
+            "Run `kaido doctor` directly in your terminal to check it",
+        );
+    }
+
+    let term = term.unwrap_or("");
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        return Check::pass(NAME, "true color");
+    }
+    if term.contains("256color") {
+        return Check::pass(NAME, "256 colors");
+    }
+    if term.is_empty() || term == "dumb" {
+        return Check::fail(
+            NAME,
+            format!("TERM={term:?} has no color support"),
+            "export TERM=xterm-256color",
+        );
+    }
+    Check::warn(
+        NAME,
+        format!("TERM={term} may only show 16 colors; the mentor palette uses 256"),
+        "export TERM=xterm-256color",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config_reports_parse_errors() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        assert_eq!(check_config(&path).0.status, CheckStatus::Warn);
+
+        let config = Config {
+            provider: AIProvider::Ollama,
+            ..Config::default()
+        };
+        std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+        let (check, config) = check_config(&path);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(config.provider, AIProvider::Ollama);
+
+        std::fs::write(&path, "provider = [\n").unwrap();
+        let (check, _) = check_config(&path);
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("doesn't parse"));
+    }
+
+    #[test]
+    fn test_check_writable_leaves_no_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("learning.db");
+
+        assert_eq!(check_writable("learning", &path).status, CheckStatus::Pass);
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            0
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_path_needs_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let tool = dir.path().join("kubectl");
+        std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+        let path_var =
+            std::env::join_paths(["/nonexistent", dir.path().to_str().unwrap()]).unwrap();
+
+        assert_eq!(find_in_path("kubectl", &path_var), None);
+        std::fs::set_permissions(&tool, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(find_in_path("kubectl", &path_var), Some(tool));
+    }
+
+    #[test]
+    fn test_check_colors() {
+        let status =
+            |no_color, term, colorterm, tty| check_colors(no_color, term, colorterm, tty).status;
+
+        assert_eq!(
+            status(false, Some("xterm-256color"), None, true),
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            status(false, Some("xterm"), Some("truecolor"), true),
+            CheckStatus::Pass
+        );
+        assert_eq!(status(false, Some("xterm"), None, true), CheckStatus::Warn);
+        assert_eq!(status(false, Some("dumb"), None, true), CheckStatus::Fail);
+        assert_eq!(
+            status(true, Some("xterm-256color"), None, true),
+            CheckStatus::Warn
+        );
+    }
+}
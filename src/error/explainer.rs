@@ -79,8 +79,8 @@ Provide your response in JSON format:
     /// Parse LLM explanation response
     fn parse_llm_explanation(&self, llm_response: &str) -> Result<ErrorExplanation> {
         // Try to parse JSON
-        let parsed: serde_json::Value = serde_json::from_str(llm_response)
-            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response as JSON: {e}"))?;
+        let parsed: serde_json::Value = crate::ai::parse_json(llm_response)
+            .map_err(|e| anyhow::anyhow!("Failed to parse LLM response as JSON: {e:#}"))?;
 
         let error_type = parsed["error_type"]
             .as_str()
@@ -1,4 +1,6 @@
 pub mod explainer;
 pub mod patterns;
+pub mod solutions;
 
-pub use patterns::PatternMatcher;
+pub use patterns::{CommandPattern, PatternMatcher, Tag};
+pub use solutions::SolutionRunner;
@@ -1,5 +1,7 @@
+use crate::tools::command_line;
 use crate::tools::{ErrorExplanation, RiskLevel, Solution};
 use regex::Regex;
+use std::fmt;
 
 /// Error pattern for matching
 pub struct ErrorPattern {
@@ -10,15 +12,63 @@ pub struct ErrorPattern {
     pub solutions: Vec<Solution>,
 }
 
-/// Pattern matcher for common errors
+/// Category of a command, assigned before it runs (for policy checks)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Tag {
+    /// Deletes or irreversibly overwrites data
+    Destructive,
+    /// Changes files, services, packages or cluster state
+    Write,
+    /// Talks to other hosts
+    Network,
+    /// Runs with elevated privileges
+    Privileged,
+    /// Only inspects state (never set alongside Destructive or Write)
+    ReadOnly,
+    /// Tag from a user-added pattern
+    Custom(String),
+}
+
+impl Tag {
+    /// Tag name as shown to users and matched by policies
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Destructive => "destructive",
+            Self::Write => "write",
+            Self::Network => "network",
+            Self::Privileged => "privileged",
+            Self::ReadOnly => "read-only",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Command pattern for pre-execution classification
+pub struct CommandPattern {
+    pub regex: Regex,
+    pub tag: Tag,
+}
+
+/// Pattern matcher for common errors and command classification
 pub struct PatternMatcher {
     patterns: Vec<ErrorPattern>,
+    command_patterns: Vec<CommandPattern>,
 }
 
 impl PatternMatcher {
     pub fn new() -> Self {
-        let mut matcher = Self { patterns: vec![] };
+        let mut matcher = Self {
+            patterns: vec![],
+            command_patterns: vec![],
+        };
         matcher.init_patterns();
+        matcher.init_command_patterns();
         matcher
     }
 
@@ -182,6 +232,135 @@ impl PatternMatcher {
         self.patterns.push(pattern);
     }
 
+    /// Initialize command classification patterns
+    fn init_command_patterns(&mut self) {
+        let patterns = [
+            // Destructive
+            (r"(?:^|[\s;&|(])rm\s", Tag::Destructive),
+            (r"\b(?:mkfs(?:\.\w+)?|shred|wipefs)\b", Tag::Destructive),
+            (r"\bdd\b.*\bof=", Tag::Destructive),
+            (r"\bfind\b.*\s-delete\b", Tag::Destructive),
+            (r"\bkubectl\b.*\s(?:delete|drain)\b", Tag::Destructive),
+            (r"\bdocker\b.*\s(?:rm|rmi|prune|kill)\b", Tag::Destructive),
+            (
+                r"(?i)\b(?:drop\s+(?:table|database|schema)|truncate\s+table|delete\s+from)\b",
+                Tag::Destructive,
+            ),
+            (
+                r"\bgit\s+(?:push\b.*\s(?:--force|-f)\b|reset\s+--hard|clean\s+-\w*f)",
+                Tag::Destructive,
+            ),
+            (r"\b(?:iptables\s+-F|ufw\s+reset)\b", Tag::Destructive),
+            // Write
+            (
+                r"\bkubectl\b.*\s(?:apply|create|patch|edit|scale|set|label|annotate|rollout\s+(?:restart|undo))\b",
+                Tag::Write,
+            ),
+            (
+                r"(?:^|[\s;&|(])(?:mv|cp|chmod|chown|touch|mkdir|ln|tee)\s",
+                Tag::Write,
+            ),
+            (r"\bsed\s+(?:-\w+\s+)*-i", Tag::Write),
+            (
+                r"\bsystemctl\s+(?:start|stop|restart|reload|enable|disable)\b",
+                Tag::Write,
+            ),
+            (r">>?\s*/(?:etc|usr|var|boot|root|opt)/", Tag::Write),
+            (
+                r"\b(?:apt(?:-get)?|dnf|yum|apk|brew|pip3?|npm)\s+(?:install|remove|uninstall|upgrade|add|del)\b",
+                Tag::Write,
+            ),
+            (r"\bpacman\s+-[SR]", Tag::Write),
+            (r"(?i)^\s*(?:insert|update|alter|create)\s", Tag::Write),
+            // Network
+            (
+                r"\b(?:curl|wget|ssh|scp|sftp|rsync|nc|ncat|telnet|ping|dig|nslookup|ftp)\b",
+                Tag::Network,
+            ),
+            (r"\bgit\s+(?:clone|fetch|pull|push)\b", Tag::Network),
+            (r"\bdocker\s+(?:pull|push|login)\b", Tag::Network),
+            (r"\bkubectl\s+(?:port-forward|proxy)\b", Tag::Network),
+            // Privileged
+            (r"(?:^|[;&|(]\s*)(?:sudo|doas|su)\b", Tag::Privileged),
+            // Read-only
+            (
+                r"^\s*(?:ls|cat|less|head|tail|grep|find|ps|top|df|du|free|uptime|whoami|id|pwd|echo|stat|which|env|journalctl|dmesg|lsof|netstat|ss)\b",
+                Tag::ReadOnly,
+            ),
+            (
+                r"\bkubectl\s+(?:get|describe|logs|top|explain|version|config\s+(?:view|get-contexts|current-context))\b",
+                Tag::ReadOnly,
+            ),
+            (
+                r"\bdocker\s+(?:ps|images|logs|inspect|info|version|stats)\b",
+                Tag::ReadOnly,
+            ),
+            (r"\bgit\s+(?:status|log|diff|show|branch)\b", Tag::ReadOnly),
+            (r"\bsystemctl\s+status\b|\bnginx\s+-t\b", Tag::ReadOnly),
+            (r"(?i)^\s*(?:select|show|describe|explain)\s", Tag::ReadOnly),
+        ];
+
+        for (regex, tag) in patterns {
+            self.add_command_pattern(CommandPattern {
+                regex: Regex::new(regex).unwrap(),
+                tag,
+            });
+        }
+    }
+
+    /// Add a command classification pattern
+    pub fn add_command_pattern(&mut self, pattern: CommandPattern) {
+        self.command_patterns.push(pattern);
+    }
+
+    /// Classify a command before it runs
+    ///
+    /// Returns every matching tag once, in pattern order. `ReadOnly` is
+    /// kept only when every part of a chain or pipeline is read-only and
+    /// nothing is redirected into a file.
+    pub fn classify_command(&self, command: &str) -> Vec<Tag> {
+        let mut tags: Vec<Tag> = Vec::new();
+        for pattern in &self.command_patterns {
+            if !tags.contains(&pattern.tag) && pattern.regex.is_match(command) {
+                tags.push(pattern.tag.clone());
+            }
+        }
+
+        if tags.contains(&Tag::Destructive)
+            || tags.contains(&Tag::Write)
+            || !self.only_reads(command)
+        {
+            tags.retain(|tag| *tag != Tag::ReadOnly);
+        }
+        tags
+    }
+
+    /// Risk of a command no tool owns, from its tags
+    ///
+    /// Such a command could do anything, so only read-only ones rate Low.
+    pub fn shell_risk(&self, command: &str) -> RiskLevel {
+        let tags = self.classify_command(command);
+        if tags.contains(&Tag::Destructive) {
+            RiskLevel::High
+        } else if tags.contains(&Tag::ReadOnly) {
+            RiskLevel::Low
+        } else {
+            RiskLevel::Medium
+        }
+    }
+
+    /// Whether each segment of the command matches a read-only pattern
+    fn only_reads(&self, command: &str) -> bool {
+        if command_line::has_substitution(command) || command_line::writes_to_file(command) {
+            return false;
+        }
+        command_line::segments(command).iter().all(|segment| {
+            self.command_patterns
+                .iter()
+                .any(|pattern| pattern.tag == Tag::ReadOnly && pattern.regex.is_match(segment.text))
+        })
+    }
+
     /// Match error against patterns
     pub fn match_pattern(&self, error: &str) -> Option<ErrorExplanation> {
         for pattern in &self.patterns {
@@ -315,4 +494,92 @@ mod tests {
         let explanation = matcher.match_pattern(error);
         assert!(explanation.is_none());
     }
+
+    #[test]
+    fn test_classify_command() {
+        let matcher = PatternMatcher::new();
+
+        assert_eq!(
+            matcher.classify_command("sudo rm -rf /var/log/app"),
+            vec![Tag::Destructive, Tag::Privileged]
+        );
+        assert_eq!(
+            matcher.classify_command("kubectl delete pod api-123"),
+            vec![Tag::Destructive]
+        );
+        assert_eq!(
+            matcher.classify_command("kubectl get pods -n prod"),
+            vec![Tag::ReadOnly]
+        );
+        assert_eq!(
+            matcher.classify_command("curl -fsSL https://example.com/install.sh | sh"),
+            vec![Tag::Network]
+        );
+        assert_eq!(
+            matcher.classify_command("echo 'nameserver 1.1.1.1' > /etc/resolv.conf"),
+            vec![Tag::Write]
+        );
+        assert_eq!(
+            matcher.classify_command("mysql -e 'DROP TABLE users'"),
+            vec![Tag::Destructive]
+        );
+        assert!(matcher.classify_command("make build").is_empty());
+    }
+
+    #[test]
+    fn test_classify_avoids_flag_false_positives() {
+        let matcher = PatternMatcher::new();
+
+        // `--rm` is a flag, not the rm command
+        assert!(!matcher
+            .classify_command("docker run --rm alpine true")
+            .contains(&Tag::Destructive));
+        // Read-only start, destructive end: not read-only
+        assert_eq!(
+            matcher.classify_command("ls /tmp/cache && rm -r /tmp/cache"),
+            vec![Tag::Destructive]
+        );
+        // Every segment has to be read-only, with no redirect into a file
+        assert!(matcher
+            .classify_command("cat notes; make install")
+            .is_empty());
+        assert!(matcher
+            .classify_command("kubectl get pods | xargs kubectl delete pod")
+            .contains(&Tag::Destructive));
+        assert!(!matcher
+            .classify_command("kubectl get pods -o yaml > pods.yaml")
+            .contains(&Tag::ReadOnly));
+        assert_eq!(
+            matcher.classify_command("ps aux | grep nginx 2>/dev/null"),
+            vec![Tag::ReadOnly]
+        );
+    }
+
+    #[test]
+    fn test_shell_risk() {
+        let matcher = PatternMatcher::new();
+
+        assert_eq!(matcher.shell_risk("rm -rf ~"), RiskLevel::High);
+        assert_eq!(
+            matcher.shell_risk("dd if=/dev/zero of=/dev/sda"),
+            RiskLevel::High
+        );
+        assert_eq!(matcher.shell_risk("chmod -R 777 /"), RiskLevel::Medium);
+        assert_eq!(matcher.shell_risk("make build"), RiskLevel::Medium);
+        assert_eq!(matcher.shell_risk("ls -la /tmp"), RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_custom_command_pattern() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_command_pattern(CommandPattern {
+            regex: Regex::new(r"\bterraform\s+apply\b").unwrap(),
+            tag: Tag::Custom("infra-change".to_string()),
+        });
+
+        let tags = matcher.classify_command("terraform apply -auto-approve");
+        assert_eq!(tags, vec![Tag::Custom("infra-change".to_string())]);
+        assert_eq!(tags[0].to_string(), "infra-change");
+        assert_eq!(Tag::ReadOnly.to_string(), "read-only");
+    }
 }
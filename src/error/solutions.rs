@@ -0,0 +1,220 @@
+// Running the fixes an error explanation proposes
+//
+// Explanations from tools and error patterns carry solutions with commands
+// and risk levels. The shell (`apply [n]`) and MCP (`kaido_apply_solution`)
+// pick one by number and run it after the usual risk checks.
+
+use super::PatternMatcher;
+use crate::tools::{ErrorExplanation, RiskLevel, Solution, ToolContext, ToolRegistry};
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Finds and picks runnable solutions for an error
+pub struct SolutionRunner<'a> {
+    registry: &'a ToolRegistry,
+    patterns: PatternMatcher,
+}
+
+impl<'a> SolutionRunner<'a> {
+    pub fn new(registry: &'a ToolRegistry) -> Self {
+        Self {
+            registry,
+            patterns: PatternMatcher::new(),
+        }
+    }
+
+    /// Explain an error without the LLM
+    ///
+    /// The tool that ran `command` gets the first look, then the generic
+    /// error patterns. Returns None when nothing matched or no solution has
+    /// a command to run.
+    pub fn explain(&self, command: &str, error_text: &str) -> Option<ErrorExplanation> {
+        let explanation = self
+            .registry
+            .tool_for_command(command)
+            .and_then(|tool| tool.explain_error(error_text))
+            .or_else(|| self.patterns.match_pattern(error_text))?;
+        explanation
+            .solutions
+            .iter()
+            .any(|s| runnable_command(s).is_some())
+            .then_some(explanation)
+    }
+
+    /// Solution `number` (1-based), or the recommended one if None
+    pub fn choose(explanation: &ErrorExplanation, number: Option<usize>) -> Result<&Solution> {
+        let count = explanation.solutions.len();
+        let index = match number {
+            Some(n) if n == 0 || n > count => {
+                return Err(anyhow!("No solution {n}; pick 1-{count}"));
+            }
+            Some(n) => n - 1,
+            None => explanation
+                .recommended_solution
+                .min(count.saturating_sub(1)),
+        };
+        let solution = explanation
+            .solutions
+            .get(index)
+            .ok_or_else(|| anyhow!("No solutions to apply"))?;
+        if runnable_command(solution).is_none() {
+            return Err(anyhow!(
+                "Solution {} is a manual step: {}",
+                index + 1,
+                solution.description
+            ));
+        }
+        Ok(solution)
+    }
+
+    /// Risk of running a solution: the higher of its own rating and the
+    /// registry's classification of its command
+    pub fn risk(&self, solution: &Solution) -> RiskLevel {
+        let classified = runnable_command(solution)
+            .map(|command| self.registry.classify(command, &ToolContext::detect()).1)
+            .unwrap_or(RiskLevel::Low);
+        solution.risk_level.max(classified)
+    }
+}
+
+/// The command of a solution, unless it's missing, a `# ...` instruction
+/// or a template with placeholders like `<host>` or `{filename}`
+///
+/// Under `sh`, `dig <host>` would even read a file called "host".
+pub fn runnable_command(solution: &Solution) -> Option<&str> {
+    solution
+        .command
+        .as_deref()
+        .map(str::trim)
+        .filter(|command| {
+            !command.is_empty() && !command.starts_with('#') && !has_placeholder(command)
+        })
+}
+
+/// Whether a command still has a `<...>` or `{...}` slot to fill in
+fn has_placeholder(command: &str) -> bool {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER
+        .get_or_init(|| Regex::new(r"<[A-Za-z][\w.:/ -]*>|\{[A-Za-z_][\w.-]*\}").unwrap())
+        .is_match(command)
+}
+
+/// Numbered list of solutions with the recommended one starred
+pub fn render_solutions(explanation: &ErrorExplanation) -> String {
+    let mut output =
+        String::from("\x1b[38;5;150m◆ Fixes\x1b[0m \x1b[2m(`apply <n>` to run one)\x1b[0m\n");
+    for (index, solution) in explanation.solutions.iter().enumerate() {
+        let marker = if index == explanation.recommended_solution {
+            "\x1b[33m★\x1b[0m"
+        } else {
+            " "
+        };
+        output.push_str(&format!(
+            "  {marker} {}. {} \x1b[2m[{}]\x1b[0m\n",
+            index + 1,
+            solution.description,
+            solution.risk_level.as_str()
+        ));
+        match runnable_command(solution) {
+            Some(command) => output.push_str(&format!("       \x1b[1m{command}\x1b[0m\n")),
+            None => {
+                if let Some(step) = solution.command.as_deref() {
+                    output.push_str(&format!("       \x1b[2m{}\x1b[0m\n", step.trim()));
+                }
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PORT_IN_USE: &str =
+        "nginx: [emerg] bind() to 0.0.0.0:80 failed (98: Address already in use)";
+
+    #[test]
+    fn test_explain_prefers_the_tool_then_patterns() {
+        let registry = ToolRegistry::new();
+        let runner = SolutionRunner::new(&registry);
+
+        let nginx = runner.explain("nginx", PORT_IN_USE).unwrap();
+        assert_eq!(nginx.error_type, "Port Conflict");
+
+        let docker = runner
+            .explain(
+                "ls",
+                "Cannot connect to the Docker daemon at unix:///var/run/docker.sock",
+            )
+            .unwrap();
+        assert_eq!(docker.error_type, "Docker Daemon Not Running");
+
+        assert!(runner.explain("ls", "some unknown failure").is_none());
+    }
+
+    #[test]
+    fn test_choose_and_risk() {
+        let registry = ToolRegistry::new();
+        let runner = SolutionRunner::new(&registry);
+        let explanation = runner.explain("nginx", PORT_IN_USE).unwrap();
+
+        let recommended = SolutionRunner::choose(&explanation, None).unwrap();
+        assert_eq!(runnable_command(recommended), Some("lsof -i :80 -P -n"));
+        assert_eq!(runner.risk(recommended), RiskLevel::Low);
+
+        let stop = SolutionRunner::choose(&explanation, Some(2)).unwrap();
+        assert_eq!(runner.risk(stop), RiskLevel::High);
+
+        // A fix that names no tool is still rated by what it runs
+        let wipe = Solution {
+            description: "Clear the cache".to_string(),
+            command: Some("rm -rf /var/cache/app".to_string()),
+            risk_level: RiskLevel::Low,
+        };
+        assert_eq!(runner.risk(&wipe), RiskLevel::High);
+
+        // The third solution is an editing instruction, not a command
+        assert!(SolutionRunner::choose(&explanation, Some(3)).is_err());
+        assert!(SolutionRunner::choose(&explanation, Some(0)).is_err());
+        assert!(SolutionRunner::choose(&explanation, Some(9)).is_err());
+    }
+
+    #[test]
+    fn test_placeholder_templates_are_manual_steps() {
+        let solution = |command: &str| Solution {
+            description: String::new(),
+            command: Some(command.to_string()),
+            risk_level: RiskLevel::Low,
+        };
+        for template in [
+            "dig <host>",
+            "systemctl status <service>",
+            "nc -zv -w 5 <host> <port>",
+            "vendor/bin/drush sql:cli < {filename}",
+        ] {
+            assert_eq!(runnable_command(&solution(template)), None, "{template}");
+        }
+        for command in [
+            "drush sql:cli < dump.sql",
+            "docker ps --format '{{.Names}}'",
+            "kubectl get pods -o jsonpath='{.items[0].metadata.name}'",
+        ] {
+            assert_eq!(runnable_command(&solution(command)), Some(command));
+        }
+    }
+
+    #[test]
+    fn test_render_stars_the_recommended_solution() {
+        let registry = ToolRegistry::new();
+        let explanation = SolutionRunner::new(&registry)
+            .explain("nginx", PORT_IN_USE)
+            .unwrap();
+        let rendered = render_solutions(&explanation);
+        let first = rendered.lines().nth(1).unwrap();
+        assert!(first.contains('★') && first.contains("1."), "{rendered}");
+        assert!(rendered.contains("systemctl stop apache2"));
+        assert!(rendered.contains("[HIGH]"));
+    }
+}
@@ -1,8 +1,10 @@
+use regex::RegexBuilder;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Kubernetes environment type detected from context name
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum EnvironmentType {
     Development,
     Staging,
@@ -10,20 +12,79 @@ pub enum EnvironmentType {
     Unknown,
 }
 
+/// Built-in environment rules, checked after any user rules
+const DEFAULT_ENVIRONMENT_RULES: &[(&str, EnvironmentType)] = &[
+    ("prod", EnvironmentType::Production),
+    ("stag|stg", EnvironmentType::Staging),
+    ("dev", EnvironmentType::Development),
+];
+
+/// Maps context/cluster names matching a regex to an environment
+///
+/// Configured in `config.toml`:
+///
+/// ```toml
+/// [[environments.rules]]
+/// pattern = "-live$"
+/// environment = "production"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentRule {
+    /// Regex matched case-insensitively against the name
+    pub pattern: String,
+    /// Environment assigned on match
+    pub environment: EnvironmentType,
+}
+
+impl EnvironmentRule {
+    /// Create a new rule
+    pub fn new(pattern: impl Into<String>, environment: EnvironmentType) -> Self {
+        Self {
+            pattern: pattern.into(),
+            environment,
+        }
+    }
+
+    /// Built-in rules (prod, staging, dev substrings)
+    pub fn defaults() -> Vec<Self> {
+        DEFAULT_ENVIRONMENT_RULES
+            .iter()
+            .map(|(pattern, environment)| Self::new(*pattern, *environment))
+            .collect()
+    }
+
+    /// Whether the rule matches a name (invalid patterns never match)
+    pub fn matches(&self, name: &str) -> bool {
+        match RegexBuilder::new(&self.pattern)
+            .case_insensitive(true)
+            .build()
+        {
+            Ok(regex) => regex.is_match(name),
+            Err(e) => {
+                log::warn!("Ignoring invalid environment rule '{}': {e}", self.pattern);
+                false
+            }
+        }
+    }
+}
+
 impl EnvironmentType {
-    /// Detect environment type from context name using regex patterns
+    /// Detect environment type from context name using the built-in rules
     pub fn from_context_name(name: &str) -> Self {
-        let name_lower = name.to_lowercase();
+        Self::classify(name, &[])
+    }
 
-        if name_lower.contains("prod") || name_lower.contains("production") {
-            EnvironmentType::Production
-        } else if name_lower.contains("stag") || name_lower.contains("staging") {
-            EnvironmentType::Staging
-        } else if name_lower.contains("dev") || name_lower.contains("development") {
-            EnvironmentType::Development
-        } else {
-            EnvironmentType::Unknown
-        }
+    /// Classify a context or cluster name
+    ///
+    /// User rules are checked first, in order, so they can override the
+    /// built-in ones (e.g. mark `acme-live` as production).
+    pub fn classify(name: &str, rules: &[EnvironmentRule]) -> Self {
+        rules
+            .iter()
+            .chain(EnvironmentRule::defaults().iter())
+            .find(|rule| rule.matches(name))
+            .map(|rule| rule.environment)
+            .unwrap_or(EnvironmentType::Unknown)
     }
 
     /// Convert to string for display
@@ -61,6 +122,17 @@ impl KubectlContext {
         }
     }
 
+    /// Re-classify the environment using user rules
+    ///
+    /// The context name is tried first, then the cluster name.
+    pub fn with_environment_rules(mut self, rules: &[EnvironmentRule]) -> Self {
+        self.environment_type = match EnvironmentType::classify(&self.name, rules) {
+            EnvironmentType::Unknown => EnvironmentType::classify(&self.cluster, rules),
+            env => env,
+        };
+        self
+    }
+
     /// Get effective namespace (default to "default" if not specified)
     pub fn effective_namespace(&self) -> &str {
         self.namespace.as_deref().unwrap_or("default")
@@ -118,7 +190,25 @@ impl KubectlContext {
     }
 
     /// Get current kubectl context from default kubeconfig location
+    ///
+    /// The environment is classified with the rules from `config.toml`.
     pub fn current() -> anyhow::Result<Self> {
+        let rules = crate::config::Config::load()
+            .map(|config| config.environments.rules)
+            .unwrap_or_default();
+
+        Ok(Self::current_unclassified()?.with_environment_rules(&rules))
+    }
+
+    /// Kubeconfig file kubectl reads: $KUBECONFIG, else ~/.kube/config
+    pub fn kubeconfig_path() -> Option<PathBuf> {
+        match std::env::var("KUBECONFIG") {
+            Ok(kubeconfig_path) => Some(PathBuf::from(kubeconfig_path)),
+            Err(_) => dirs::home_dir().map(|home| home.join(".kube").join("config")),
+        }
+    }
+
+    fn current_unclassified() -> anyhow::Result<Self> {
         // Try $KUBECONFIG env var first
         if let Ok(kubeconfig_path) = std::env::var("KUBECONFIG") {
             let path = PathBuf::from(kubeconfig_path);
@@ -126,10 +216,8 @@ impl KubectlContext {
         }
 
         // Fall back to ~/.kube/config
-        let home =
-            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
-
-        let kubeconfig_path = home.join(".kube").join("config");
+        let kubeconfig_path = Self::kubeconfig_path()
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine home directory"))?;
 
         if !kubeconfig_path.exists() {
             return Err(anyhow::anyhow!(
@@ -170,6 +258,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_classify_with_user_rules() {
+        let rules = vec![
+            EnvironmentRule::new(r"-live$", EnvironmentType::Production),
+            EnvironmentRule::new(r"^sandbox", EnvironmentType::Development),
+        ];
+
+        assert_eq!(
+            EnvironmentType::classify("acme-live", &rules),
+            EnvironmentType::Production
+        );
+        assert_eq!(
+            EnvironmentType::classify("ACME-LIVE", &rules),
+            EnvironmentType::Production
+        );
+        // User rules win over the built-in "prod" match
+        assert_eq!(
+            EnvironmentType::classify("sandbox-prod-copy", &rules),
+            EnvironmentType::Development
+        );
+        // Built-in rules still apply when no user rule matches
+        assert_eq!(
+            EnvironmentType::classify("stg-eu", &rules),
+            EnvironmentType::Staging
+        );
+        assert_eq!(
+            EnvironmentType::classify("acme-lively", &rules),
+            EnvironmentType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_classify_ignores_invalid_rule() {
+        let rules = vec![EnvironmentRule::new(
+            "(unclosed",
+            EnvironmentType::Production,
+        )];
+        assert_eq!(
+            EnvironmentType::classify("dev-cluster", &rules),
+            EnvironmentType::Development
+        );
+    }
+
+    #[test]
+    fn test_context_falls_back_to_cluster_name() {
+        let rules = vec![EnvironmentRule::new("live", EnvironmentType::Production)];
+        let ctx = KubectlContext::new(
+            "admin@acme".to_string(),
+            "acme-live".to_string(),
+            None,
+            "admin".to_string(),
+        );
+        assert_eq!(ctx.environment_type, EnvironmentType::Unknown);
+
+        let ctx = ctx.with_environment_rules(&rules);
+        assert_eq!(ctx.environment_type, EnvironmentType::Production);
+    }
+
     #[test]
     fn test_effective_namespace() {
         let ctx = KubectlContext::new(
@@ -5,14 +5,18 @@
 // - translator.rs: Natural language to kubectl via OpenAI
 // - risk_classifier.rs: Risk level classification (LOW/MEDIUM/HIGH)
 // - executor.rs: kubectl command execution
+// - switch.rs: Checked context and namespace switching
+// - preview.rs: Diffs shown before apply/patch/scale are confirmed
 
 pub mod context;
 pub mod executor;
 pub mod openai;
+pub mod preview;
 pub mod risk_classifier;
+pub mod switch;
 pub mod translator;
 
-pub use context::{EnvironmentType, KubectlContext};
+pub use context::{EnvironmentRule, EnvironmentType, KubectlContext};
 pub use executor::{execute_kubectl, format_output, ExecutionResult};
 pub use risk_classifier::RiskLevel;
-pub use translator::TranslationResult;
+pub use translator::{OutputFormat, TranslationResult};
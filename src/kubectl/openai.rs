@@ -172,7 +172,7 @@ pub async fn translate_to_kubectl(
         match make_openai_request(&client, &config.base_url, &config.api_key, &request_body).await {
             Ok(response) => {
                 // Parse response
-                return parse_translation_response(&response);
+                return parse_translation_response(&response)?.with_output_format(input);
             }
             Err(e) => {
                 last_error = Some(e);
@@ -228,8 +228,8 @@ fn parse_translation_response(response: &OpenAIResponse) -> anyhow::Result<Trans
         .message
         .content;
 
-    let translation: TranslationContent = serde_json::from_str(content_json)
-        .map_err(|e| anyhow::anyhow!("Failed to parse OpenAI JSON response: {e}"))?;
+    let translation: TranslationContent = crate::ai::parse_json(content_json)
+        .map_err(|e| anyhow::anyhow!("Failed to parse OpenAI JSON response: {e:#}"))?;
 
     // Validate command starts with "kubectl "
     if !translation.command.trim().starts_with("kubectl ") {
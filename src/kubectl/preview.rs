@@ -0,0 +1,334 @@
+// Previews of what a mutating kubectl command will change
+//
+// Shown by the shell's confirmation before `apply`, `patch` or `scale`
+// runs: `kubectl diff` for manifests, and for patch/scale the live object
+// diffed against a server-side dry run of the change. Previews only read.
+
+use anyhow::{anyhow, Context, Result};
+use similar::TextDiff;
+use std::process::Command;
+
+use crate::ai::explainer::tokenize_command;
+
+/// How long each preview call may wait on the API server
+const REQUEST_TIMEOUT: &str = "--request-timeout=10s";
+
+/// Global kubectl flags that take a value, before the subcommand
+const GLOBAL_VALUE_FLAGS: &[&str] = &[
+    "-n",
+    "--namespace",
+    "--context",
+    "--cluster",
+    "--user",
+    "--kubeconfig",
+    "-s",
+    "--server",
+];
+
+/// Flags a preview must not pass on, and whether they take a value
+const OUTPUT_FLAGS: &[(&str, bool)] = &[
+    ("-o", true),
+    ("--output", true),
+    ("--dry-run", false),
+    ("--wait", false),
+    ("--timeout", true),
+    ("--record", false),
+];
+
+/// Flags that describe the change rather than the object
+const CHANGE_FLAGS: &[(&str, bool)] = &[
+    ("-p", true),
+    ("--patch", true),
+    ("--patch-file", true),
+    ("--type", true),
+    ("--replicas", true),
+    ("--current-replicas", true),
+    ("--resource-version", true),
+];
+
+/// Lines of context around each change in dry-run diffs
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// Read-only commands that show what a kubectl command would change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Preview {
+    /// `kubectl diff` over the same manifests
+    Diff(Vec<String>),
+    /// The live object, and the object after a server-side dry run
+    DryRun {
+        live: Vec<String>,
+        dry_run: Vec<String>,
+    },
+}
+
+impl Preview {
+    /// Preview for `kubectl apply -f/-k`, `patch` or `scale`
+    ///
+    /// None for other commands, pipelines and applies from stdin.
+    pub fn for_command(command: &str) -> Option<Self> {
+        let stages = tokenize_command(command).ok()?;
+        let [tokens] = stages.as_slice() else {
+            return None;
+        };
+        if tokens.first().map(String::as_str) != Some("kubectl") {
+            return None;
+        }
+        let position = subcommand_position(tokens)?;
+
+        match tokens[position].as_str() {
+            "apply" => {
+                let sources: Vec<&str> = tokens
+                    .windows(2)
+                    .filter(|pair| {
+                        matches!(pair[0].as_str(), "-f" | "--filename" | "-k" | "--kustomize")
+                    })
+                    .map(|pair| pair[1].as_str())
+                    .chain(tokens.iter().filter_map(|t| {
+                        t.strip_prefix("--filename=")
+                            .or_else(|| t.strip_prefix("--kustomize="))
+                    }))
+                    .collect();
+                if sources.is_empty() || sources.contains(&"-") {
+                    return None;
+                }
+                let mut diff =
+                    strip_flags(&with_subcommand(tokens, position, "diff"), OUTPUT_FLAGS);
+                diff.push(REQUEST_TIMEOUT.to_string());
+                Some(Self::Diff(diff))
+            }
+            "patch" | "scale" => {
+                let mut live = strip_flags(
+                    &strip_flags(&with_subcommand(tokens, position, "get"), CHANGE_FLAGS),
+                    OUTPUT_FLAGS,
+                );
+                let mut dry_run = strip_flags(tokens, OUTPUT_FLAGS);
+                for args in [&mut live, &mut dry_run] {
+                    args.extend(["-o", "yaml", REQUEST_TIMEOUT].map(String::from));
+                }
+                dry_run.push("--dry-run=server".to_string());
+                Some(Self::DryRun { live, dry_run })
+            }
+            _ => None,
+        }
+    }
+
+    /// The commands the preview runs, for display
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Diff(args) => command_line(args),
+            Self::DryRun { dry_run, .. } => command_line(dry_run),
+        }
+    }
+
+    /// Run the preview and return a unified diff, empty when nothing changes
+    pub fn run(&self) -> Result<String> {
+        match self {
+            // `kubectl diff` exits 1 when there are differences
+            Self::Diff(args) => match run(args, 1)? {
+                (0, _) => Ok(String::new()),
+                (_, stdout) => Ok(stdout),
+            },
+            Self::DryRun { live, dry_run } => {
+                let (_, before) = run(live, 0)?;
+                let (_, after) = run(dry_run, 0)?;
+                Ok(dry_run_diff(&before, &after))
+            }
+        }
+    }
+}
+
+/// Unified diff from the live object to the dry-run result
+fn dry_run_diff(live: &str, after: &str) -> String {
+    if live == after {
+        return String::new();
+    }
+    TextDiff::from_lines(live, after)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .header("live", "after")
+        .to_string()
+}
+
+/// Index of the subcommand, skipping global flags and their values
+fn subcommand_position(tokens: &[String]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(token) = tokens.get(index) {
+        if GLOBAL_VALUE_FLAGS.contains(&token.as_str()) {
+            index += 2;
+        } else if token.starts_with('-') {
+            index += 1;
+        } else {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// `--flag` of `--flag=value`
+fn flag_name(token: &str) -> &str {
+    token.split('=').next().unwrap_or(token)
+}
+
+fn with_subcommand(tokens: &[String], position: usize, subcommand: &str) -> Vec<String> {
+    let mut tokens = tokens.to_vec();
+    tokens[position] = subcommand.to_string();
+    tokens
+}
+
+/// Drop `flags` (and their values) from a command's tokens
+fn strip_flags(tokens: &[String], flags: &[(&str, bool)]) -> Vec<String> {
+    let mut kept = Vec::new();
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        match flags.iter().find(|(flag, _)| *flag == flag_name(token)) {
+            Some((_, takes_value)) => {
+                if *takes_value && !token.contains('=') {
+                    tokens.next();
+                }
+            }
+            None => kept.push(token.clone()),
+        }
+    }
+    kept
+}
+
+/// Tokens as a command line, quoting the ones the shell would split
+fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || "'\"{}$".contains(c)) {
+                format!("'{}'", arg.replace('\'', r"'\''"))
+            } else {
+                arg.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Exit code and stdout of a command
+///
+/// Errors with the last line of stderr on exit codes above `max_ok`, and
+/// when the command can't start or is killed.
+fn run(args: &[String], max_ok: i32) -> Result<(i32, String)> {
+    let (program, rest) = args.split_first().context("Empty preview command")?;
+    let output = Command::new(program)
+        .args(rest)
+        .output()
+        .with_context(|| format!("Failed to run {program}"))?;
+    let code = output
+        .status
+        .code()
+        .with_context(|| format!("{program} was killed"))?;
+    if code > max_ok {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "{}: {}",
+            command_line(args),
+            stderr.trim().lines().last().unwrap_or_default()
+        ));
+    }
+    Ok((code, String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_apply_previews_with_diff() {
+        assert_eq!(
+            Preview::for_command("kubectl -n prod apply -f deploy.yaml --record -o name"),
+            Some(Preview::Diff(args(
+                "kubectl -n prod diff -f deploy.yaml --request-timeout=10s"
+            )))
+        );
+        assert_eq!(
+            Preview::for_command("kubectl apply -k overlays/staging --server-side"),
+            Some(Preview::Diff(args(
+                "kubectl diff -k overlays/staging --server-side --request-timeout=10s"
+            )))
+        );
+        // Nothing to diff against
+        assert_eq!(Preview::for_command("kubectl apply -f -"), None);
+        assert_eq!(
+            Preview::for_command("cat x.yaml | kubectl apply -f x.yaml"),
+            None
+        );
+        assert_eq!(Preview::for_command("kubectl get pods"), None);
+        assert_eq!(Preview::for_command("helm upgrade web ./chart"), None);
+    }
+
+    #[test]
+    fn test_patch_and_scale_preview_with_dry_run() {
+        let preview = Preview::for_command(
+            r#"kubectl patch deployment web -n prod --type merge -p '{"spec": {"paused": true}}'"#,
+        )
+        .unwrap();
+        let Preview::DryRun { live, dry_run } = &preview else {
+            panic!("{preview:?}");
+        };
+        assert_eq!(
+            live,
+            &args("kubectl get deployment web -n prod -o yaml --request-timeout=10s")
+        );
+        assert_eq!(dry_run[9], r#"{"spec": {"paused": true}}"#);
+        assert!(dry_run.ends_with(&args("-o yaml --request-timeout=10s --dry-run=server")));
+        assert!(preview
+            .describe()
+            .contains(r#"-p '{"spec": {"paused": true}}'"#));
+
+        let Some(Preview::DryRun { live, dry_run }) =
+            Preview::for_command("kubectl scale deploy/web --replicas=5 -o name")
+        else {
+            panic!("scale has a preview");
+        };
+        assert_eq!(
+            live,
+            args("kubectl get deploy/web -o yaml --request-timeout=10s")
+        );
+        assert_eq!(
+            dry_run,
+            args("kubectl scale deploy/web --replicas=5 -o yaml --request-timeout=10s --dry-run=server")
+        );
+    }
+
+    #[test]
+    fn test_dry_run_diff() {
+        let live = "kind: Deployment\nspec:\n  replicas: 2\n";
+        let after = "kind: Deployment\nspec:\n  replicas: 5\n";
+        let diff = dry_run_diff(live, after);
+        assert!(diff.starts_with("--- live\n+++ after\n"), "{diff}");
+        assert!(diff.contains("-  replicas: 2\n+  replicas: 5\n"));
+        assert_eq!(dry_run_diff(live, live), "");
+    }
+
+    #[test]
+    fn test_run_reads_diff_exit_codes() {
+        let changed = Preview::Diff(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo '+ replicas: 5'; exit 1".to_string(),
+        ]);
+        assert_eq!(changed.run().unwrap(), "+ replicas: 5\n");
+
+        let same = Preview::Diff(args("true"));
+        assert_eq!(same.run().unwrap(), "");
+
+        let broken = Preview::Diff(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo 'error: the server could not find the requested resource' >&2; exit 2"
+                .to_string(),
+        ]);
+        let error = broken.run().unwrap_err().to_string();
+        assert!(
+            error.contains("could not find the requested resource"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+// Context and namespace switching
+//
+// Backs the shell's `kctx` and `kns` builtins. Both check the target exists
+// before touching kubeconfig, since `kubectl config use-context typo` fails
+// late and `set-context --namespace=typo` doesn't fail at all.
+
+use std::process::{Command, Output};
+
+/// Result of checking a namespace before switching to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamespaceCheck {
+    /// The namespace exists
+    Exists,
+    /// The cluster says it doesn't
+    Missing,
+    /// The check itself failed (RBAC, unreachable cluster); holds kubectl's error
+    Unverified(String),
+}
+
+/// Context names from kubeconfig, in kubeconfig order
+pub fn context_names() -> anyhow::Result<Vec<String>> {
+    let output = kubectl(&["config", "get-contexts", "-o", "name"])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "kubectl config get-contexts failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_names(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Switch kubectl to an existing context
+pub fn use_context(name: &str) -> anyhow::Result<()> {
+    let names = context_names()?;
+    if !names.iter().any(|n| n == name) {
+        anyhow::bail!(unknown_context_message(name, &names));
+    }
+
+    let output = kubectl(&["config", "use-context", name])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "kubectl config use-context failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Ask the cluster whether a namespace exists
+pub fn check_namespace(namespace: &str) -> anyhow::Result<NamespaceCheck> {
+    let output = kubectl(&["get", "namespace", namespace, "-o", "name"])?;
+    Ok(classify_namespace_check(
+        output.status.success(),
+        &String::from_utf8_lossy(&output.stderr),
+    ))
+}
+
+/// Make `namespace` the current context's default namespace
+pub fn set_namespace(namespace: &str) -> anyhow::Result<()> {
+    let output = kubectl(&[
+        "config",
+        "set-context",
+        "--current",
+        &format!("--namespace={namespace}"),
+    ])?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "kubectl config set-context failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn kubectl(args: &[&str]) -> anyhow::Result<Output> {
+    Command::new("kubectl").args(args).output().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            anyhow::anyhow!(
+                "kubectl command not found. Please install kubectl: https://kubernetes.io/docs/tasks/tools/"
+            )
+        } else {
+            anyhow::anyhow!("Failed to execute kubectl: {e}")
+        }
+    })
+}
+
+fn parse_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn unknown_context_message(name: &str, names: &[String]) -> String {
+    if names.is_empty() {
+        return format!("Unknown context '{name}'. No contexts are defined in kubeconfig");
+    }
+    format!(
+        "Unknown context '{name}'. Available contexts: {}",
+        names.join(", ")
+    )
+}
+
+fn classify_namespace_check(success: bool, stderr: &str) -> NamespaceCheck {
+    if success {
+        NamespaceCheck::Exists
+    } else if stderr.contains("(NotFound)") || stderr.contains("not found") {
+        NamespaceCheck::Missing
+    } else {
+        NamespaceCheck::Unverified(stderr.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_context_lists_available() {
+        let names = parse_names("kind-dev\nprod-eu\n\n");
+        assert_eq!(names, ["kind-dev", "prod-eu"]);
+        assert_eq!(
+            unknown_context_message("prod-us", &names),
+            "Unknown context 'prod-us'. Available contexts: kind-dev, prod-eu"
+        );
+    }
+
+    #[test]
+    fn test_classify_namespace_check() {
+        assert_eq!(classify_namespace_check(true, ""), NamespaceCheck::Exists);
+        assert_eq!(
+            classify_namespace_check(
+                false,
+                "Error from server (NotFound): namespaces \"paymnets\" not found\n"
+            ),
+            NamespaceCheck::Missing
+        );
+        assert!(matches!(
+            classify_namespace_check(
+                false,
+                "Error from server (Forbidden): namespaces \"payments\" is forbidden"
+            ),
+            NamespaceCheck::Unverified(_)
+        ));
+    }
+}
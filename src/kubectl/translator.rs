@@ -1,4 +1,89 @@
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Subcommands that accept `-o`/`--output`
+const OUTPUT_VERBS: &[&str] = &[
+    "get", "create", "apply", "run", "expose", "patch", "label", "annotate", "scale", "set",
+    "config", "version", "wait", "rollout", "delete",
+];
+
+/// Global flags whose value is a separate word (`-n prod`)
+const VALUE_FLAGS: &[&str] = &[
+    "-n",
+    "--namespace",
+    "--context",
+    "--cluster",
+    "--kubeconfig",
+    "--user",
+    "-s",
+    "--server",
+];
+
+/// Output format asked for in a request, e.g. "get pods as yaml"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Yaml,
+    Json,
+    Wide,
+    Name,
+}
+
+impl OutputFormat {
+    /// Value for `-o`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yaml => "yaml",
+            Self::Json => "json",
+            Self::Wide => "wide",
+            Self::Name => "name",
+        }
+    }
+
+    /// Format named in a natural language request
+    ///
+    /// None if no format is mentioned, or several are ("yaml or json?").
+    pub fn from_request(input: &str) -> Option<Self> {
+        let lower = input.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let has_word = |word: &str| words.contains(&word);
+        let has_phrase = |phrases: &[&str]| phrases.iter().any(|p| lower.contains(p));
+
+        let mut found = Vec::new();
+        if has_word("yaml") || has_word("yml") {
+            found.push(Self::Yaml);
+        }
+        // "json for jq", "pipe it to jq"
+        if has_word("json") || has_word("jq") {
+            found.push(Self::Json);
+        }
+        if has_word("wide") || has_phrase(&["more columns", "with ip", "with node"]) {
+            found.push(Self::Wide);
+        }
+        if has_phrase(&["names only", "only names", "just names", "just the names"]) {
+            found.push(Self::Name);
+        }
+
+        match found[..] {
+            [format] => Some(format),
+            _ => None,
+        }
+    }
+
+    /// Plain format for an `-o` value; None for templates and custom columns
+    fn from_flag(value: &str) -> Option<Self> {
+        match value {
+            "yaml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            "wide" => Some(Self::Wide),
+            "name" => Some(Self::Name),
+            _ => None,
+        }
+    }
+}
 
 /// Result of AI translation from natural language to kubectl command
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +124,112 @@ impl TranslationResult {
     pub fn needs_clarification(&self) -> bool {
         self.reasoning.contains("NEEDS_CLARIFICATION")
     }
+
+    /// Enforce the output format asked for in `input` on the command
+    /// (see [`enforce_output_format`])
+    pub fn with_output_format(mut self, input: &str) -> anyhow::Result<Self> {
+        self.kubectl_command = enforce_output_format(input, &self.kubectl_command)?;
+        Ok(self)
+    }
+}
+
+/// `-o yaml`, `-oyaml`, `-o=yaml`, `--output yaml`, `--output=yaml`
+fn output_flag_regex() -> &'static Regex {
+    static OUTPUT_FLAG: OnceLock<Regex> = OnceLock::new();
+    OUTPUT_FLAG.get_or_init(|| Regex::new(r"(^|\s)(?:-o|--output)(?:=|\s+)?([^\s\-]\S*)").unwrap())
+}
+
+/// Values of every output flag in a command
+fn output_flags(command: &str) -> Vec<&str> {
+    output_flag_regex()
+        .captures_iter(command)
+        .filter_map(|caps| caps.get(2).map(|m| m.as_str()))
+        .collect()
+}
+
+/// Subcommand of a kubectl command, skipping global flags
+fn kubectl_verb(command: &str) -> Option<&str> {
+    let mut words = command.split_whitespace();
+    if words.next()? != "kubectl" {
+        return None;
+    }
+    while let Some(word) = words.next() {
+        if VALUE_FLAGS.contains(&word) {
+            words.next();
+        } else if !word.starts_with('-') {
+            return Some(word);
+        }
+    }
+    None
+}
+
+/// Make the command's `-o` flag match the format asked for in `input`, then
+/// reject commands whose output flags conflict
+///
+/// Models often drop the format or contradict it ("as yaml" answered with
+/// `-o json`), so the request wins over what the model produced.
+pub fn enforce_output_format(input: &str, command: &str) -> anyhow::Result<String> {
+    let command = match OutputFormat::from_request(input) {
+        Some(format) => apply_output_format(command, format),
+        None => command.to_string(),
+    };
+    validate_output_flags(&command)?;
+    Ok(command)
+}
+
+/// Set `-o <format>` on a command whose subcommand supports it
+///
+/// Templates and custom columns (`-o jsonpath=...`) are kept since they
+/// are more specific than a bare format.
+pub fn apply_output_format(command: &str, format: OutputFormat) -> String {
+    let Some(verb) = kubectl_verb(command) else {
+        return command.to_string();
+    };
+    if !OUTPUT_VERBS.contains(&verb) || (format == OutputFormat::Wide && verb != "get") {
+        return command.to_string();
+    }
+
+    let flags = output_flags(command);
+    if flags.iter().all(|flag| *flag == format.as_str()) && !flags.is_empty() {
+        return command.to_string();
+    }
+    if flags.is_empty() {
+        return format!("{} -o {}", command.trim_end(), format.as_str());
+    }
+    if flags
+        .iter()
+        .all(|flag| OutputFormat::from_flag(flag).is_none())
+    {
+        return command.to_string();
+    }
+    output_flag_regex()
+        .replace_all(command, |caps: &Captures| {
+            format!("{}-o {}", &caps[1], format.as_str())
+        })
+        .into_owned()
+}
+
+/// Reject commands with contradicting or misplaced output flags
+pub fn validate_output_flags(command: &str) -> anyhow::Result<()> {
+    let flags = output_flags(command);
+    let Some(&first) = flags.first() else {
+        return Ok(());
+    };
+
+    if flags.iter().any(|flag| *flag != first) {
+        anyhow::bail!(
+            "Conflicting output flags in `{command}`: -o {}",
+            flags.join(" and -o ")
+        );
+    }
+    let verb = kubectl_verb(command).unwrap_or_default();
+    if !OUTPUT_VERBS.contains(&verb) {
+        anyhow::bail!("`kubectl {verb}` doesn't take an output flag (got -o {first})");
+    }
+    if first == "wide" && verb != "get" {
+        anyhow::bail!("-o wide only works with `kubectl get` (got `kubectl {verb}`)");
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -70,6 +261,81 @@ mod tests {
         assert!(result.needs_clarification());
     }
 
+    #[test]
+    fn test_output_format_from_request() {
+        assert_eq!(
+            OutputFormat::from_request("get pods as yaml"),
+            Some(OutputFormat::Yaml)
+        );
+        assert_eq!(
+            OutputFormat::from_request("list services, json for jq"),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(
+            OutputFormat::from_request("show nodes wide"),
+            Some(OutputFormat::Wide)
+        );
+        assert_eq!(
+            OutputFormat::from_request("deployments, just the names"),
+            Some(OutputFormat::Name)
+        );
+        assert_eq!(OutputFormat::from_request("get pods"), None);
+        assert_eq!(OutputFormat::from_request("yaml or json?"), None);
+        // Words containing a format name don't count
+        assert_eq!(OutputFormat::from_request("get the jsonpath docs"), None);
+    }
+
+    #[test]
+    fn test_enforce_output_format() {
+        let enforce = |input, command| enforce_output_format(input, command).unwrap();
+
+        assert_eq!(
+            enforce("get pods as yaml", "kubectl get pods -n prod"),
+            "kubectl get pods -n prod -o yaml"
+        );
+        // The request wins over what the model picked
+        assert_eq!(
+            enforce("get pods as yaml", "kubectl get pods --output=json"),
+            "kubectl get pods -o yaml"
+        );
+        assert_eq!(
+            enforce("wide pod list", "kubectl -n prod get pods -owide"),
+            "kubectl -n prod get pods -owide"
+        );
+        assert_eq!(
+            enforce(
+                "pod names as json",
+                "kubectl get pods -o jsonpath='{.items[*].metadata.name}'"
+            ),
+            "kubectl get pods -o jsonpath='{.items[*].metadata.name}'"
+        );
+        // describe and logs have no -o, so nothing is added
+        assert_eq!(
+            enforce("describe the api pod as yaml", "kubectl describe pod api"),
+            "kubectl describe pod api"
+        );
+        assert_eq!(
+            enforce("deployment wide", "kubectl rollout status deploy/api"),
+            "kubectl rollout status deploy/api"
+        );
+    }
+
+    #[test]
+    fn test_rejects_conflicting_output_flags() {
+        assert!(validate_output_flags("kubectl get pods -o yaml -o json").is_err());
+        assert!(validate_output_flags("kubectl describe pod api -o yaml").is_err());
+        assert!(validate_output_flags("kubectl apply -f app.yaml -o wide").is_err());
+        assert!(validate_output_flags("kubectl get pods -o wide --output-watch-events").is_ok());
+        assert!(validate_output_flags("kubectl logs api -f").is_ok());
+
+        let result = TranslationResult::new(
+            "kubectl get pods -o json -o yaml".to_string(),
+            90,
+            String::new(),
+        );
+        assert!(result.with_output_format("get pods").is_err());
+    }
+
     #[test]
     fn test_invalid_command() {
         let result = TranslationResult::new("docker ps".to_string(), 50, "Wrong tool".to_string());
@@ -0,0 +1,161 @@
+// Feature hints
+//
+// Beginners rarely read `help`, so they never find `why` or `verbose`.
+// When an error keeps coming back, the shell may mention one builtin that
+// would help. Hints are gated by skill level, spaced out by a cooldown and
+// retired after being shown a couple of times (counts live in the learning
+// database, so this holds across sessions).
+
+use super::skill::SkillLevel;
+
+/// Times a hint is shown before it's retired for good
+pub const MAX_TIMES_SHOWN: u32 = 2;
+
+/// Commands that must run between two hints
+pub const HINT_COOLDOWN_COMMANDS: u32 = 10;
+
+/// A shell builtin worth pointing out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureHint {
+    Why,
+    Verbose,
+    Fix,
+    Progress,
+}
+
+impl FeatureHint {
+    /// In the order they're suggested
+    pub const ALL: [FeatureHint; 4] = [Self::Why, Self::Verbose, Self::Fix, Self::Progress];
+
+    /// Key the shown count is stored under
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Why => "why",
+            Self::Verbose => "verbose",
+            Self::Fix => "fix",
+            Self::Progress => "progress",
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            Self::Why => "type `why` to dig into the root cause",
+            Self::Verbose => "try `verbose` for more detail on every error",
+            Self::Fix => "type `fix` to get a fix proposed and run for you",
+            Self::Progress => "`progress` shows which errors you've mastered",
+        }
+    }
+
+    /// Most advanced skill level that still gets this hint
+    fn max_level(&self) -> SkillLevel {
+        match self {
+            Self::Why | Self::Verbose => SkillLevel::Beginner,
+            Self::Fix | Self::Progress => SkillLevel::Intermediate,
+        }
+    }
+}
+
+/// Decides when a hint is due within one session
+#[derive(Debug, Default)]
+pub struct HintScheduler {
+    /// Command count when the last hint was shown
+    last_shown: Option<u32>,
+    /// Features the user found on their own
+    used: Vec<FeatureHint>,
+}
+
+impl HintScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The user already knows this feature; don't suggest it
+    pub fn note_used(&mut self, hint: FeatureHint) {
+        if !self.used.contains(&hint) {
+            self.used.push(hint);
+        }
+    }
+
+    /// Hint to show after an error seen `occurrence` times this session
+    ///
+    /// Only repeated errors get one, and only when the cooldown since the
+    /// last hint has passed. `times_shown` is the count across sessions.
+    pub fn next_hint(
+        &self,
+        level: SkillLevel,
+        occurrence: u32,
+        commands_run: u32,
+        times_shown: impl Fn(FeatureHint) -> u32,
+    ) -> Option<FeatureHint> {
+        if occurrence < 2 || level == SkillLevel::Advanced {
+            return None;
+        }
+        if let Some(last) = self.last_shown {
+            if commands_run < last + HINT_COOLDOWN_COMMANDS {
+                return None;
+            }
+        }
+
+        FeatureHint::ALL.into_iter().find(|hint| {
+            (level == SkillLevel::Beginner || hint.max_level() == SkillLevel::Intermediate)
+                && !self.used.contains(hint)
+                && times_shown(*hint) < MAX_TIMES_SHOWN
+        })
+    }
+
+    /// Start the cooldown
+    pub fn mark_shown(&mut self, commands_run: u32) {
+        self.last_shown = Some(commands_run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints_gated_by_level_and_repetition() {
+        let hints = HintScheduler::new();
+        let never_shown = |_| 0;
+
+        assert_eq!(
+            hints.next_hint(SkillLevel::Beginner, 1, 5, never_shown),
+            None
+        );
+        assert_eq!(
+            hints.next_hint(SkillLevel::Beginner, 2, 5, never_shown),
+            Some(FeatureHint::Why)
+        );
+        assert_eq!(
+            hints.next_hint(SkillLevel::Intermediate, 2, 5, never_shown),
+            Some(FeatureHint::Fix)
+        );
+        assert_eq!(
+            hints.next_hint(SkillLevel::Advanced, 5, 5, never_shown),
+            None
+        );
+    }
+
+    #[test]
+    fn test_hints_rate_limited_and_retired() {
+        let mut hints = HintScheduler::new();
+        hints.mark_shown(5);
+        assert_eq!(hints.next_hint(SkillLevel::Beginner, 2, 10, |_| 0), None);
+        assert_eq!(
+            hints.next_hint(SkillLevel::Beginner, 2, 15, |_| 0),
+            Some(FeatureHint::Why)
+        );
+
+        // Shown enough times already, or found by the user
+        let why_retired = |hint| if hint == FeatureHint::Why { 2 } else { 0 };
+        assert_eq!(
+            hints.next_hint(SkillLevel::Beginner, 2, 15, why_retired),
+            Some(FeatureHint::Verbose)
+        );
+        hints.note_used(FeatureHint::Verbose);
+        assert_eq!(
+            hints.next_hint(SkillLevel::Beginner, 2, 15, why_retired),
+            Some(FeatureHint::Fix)
+        );
+    }
+}
@@ -7,13 +7,19 @@
 // - Provides learning progress summary
 // - Detects skill level and adapts verbosity
 // - Generates session summaries
+// - Suggests shell features to beginners
 
+pub mod hints;
 pub mod schema;
 pub mod skill;
 pub mod summary;
 pub mod tracker;
 
+pub use hints::{FeatureHint, HintScheduler};
 pub use schema::{default_learning_db_path, ensure_learning_dir};
-pub use skill::{SkillAssessment, SkillDetector, SkillIndicator, SkillLevel, VerbosityMode};
-pub use summary::{SessionStats, SessionSummary, SummaryGenerator};
-pub use tracker::{ErrorEncounter, ErrorSummary, LearningProgress, LearningTracker};
+pub use skill::{SkillAssessment, SkillDetector, SkillIndicator, SkillLevel, Trend, VerbosityMode};
+pub use summary::{RecurringError, SessionStats, SessionSummary, SummaryGenerator};
+pub use tracker::{
+    ErrorEncounter, ErrorSummary, ImportSummary, LearningExport, LearningProgress, LearningTracker,
+    WeakConcept,
+};
@@ -19,11 +19,15 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
             full_output TEXT,
             resolved INTEGER DEFAULT 0,
             resolution_time_ms INTEGER,
+            resolution_command TEXT,
             mentor_shown INTEGER DEFAULT 1
         )",
         [],
     )?;
 
+    // Databases created before resolution tracking lack this column
+    add_column_if_missing(conn, "error_encounters", "resolution_command", "TEXT")?;
+
     // Concepts learned table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS concepts_learned (
@@ -36,6 +40,21 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Spaced-repetition state for concept refreshers
+    add_column_if_missing(conn, "concepts_learned", "last_reviewed", "INTEGER")?;
+    add_column_if_missing(
+        conn,
+        "concepts_learned",
+        "review_count",
+        "INTEGER DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        conn,
+        "concepts_learned",
+        "review_dismissed",
+        "INTEGER DEFAULT 0",
+    )?;
+
     // Session statistics table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS sessions (
@@ -49,6 +68,29 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Progress snapshots taken at the end of each session (for trend detection)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS progress_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            timestamp INTEGER NOT NULL,
+            session_id INTEGER,
+            total_errors INTEGER NOT NULL,
+            resolved_errors INTEGER NOT NULL,
+            resolution_rate REAL NOT NULL
+        )",
+        [],
+    )?;
+
+    // How often each feature hint was shown, so hints retire
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS feature_hints (
+            hint TEXT PRIMARY KEY,
+            shown_count INTEGER NOT NULL DEFAULT 0,
+            last_shown INTEGER
+        )",
+        [],
+    )?;
+
     // Create indexes for efficient queries
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_error_type ON error_encounters(error_type)",
@@ -66,6 +108,24 @@ pub fn init_schema(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Add a column to an existing table unless it is already present
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {table} ADD COLUMN {column} {decl}"),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Get the default learning database path
 pub fn default_learning_db_path() -> std::path::PathBuf {
     dirs::home_dir()
@@ -103,6 +163,40 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn test_migrates_legacy_error_encounters() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE error_encounters (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                error_type TEXT NOT NULL,
+                key_message TEXT NOT NULL,
+                command TEXT NOT NULL,
+                exit_code INTEGER,
+                full_output TEXT,
+                resolved INTEGER DEFAULT 0,
+                resolution_time_ms INTEGER,
+                mentor_shown INTEGER DEFAULT 1
+            )",
+            [],
+        )
+        .unwrap();
+
+        init_schema(&conn).unwrap();
+        // Running twice must not try to add the column again
+        init_schema(&conn).unwrap();
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('error_encounters') WHERE name = 'resolution_command'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[test]
     fn test_default_learning_db_path() {
         let path = default_learning_db_path();
@@ -74,10 +74,42 @@ impl SkillAssessment {
     }
 }
 
+/// Slope (resolution rate change per session) below which progress is flat
+const PLATEAU_SLOPE: f32 = 0.02;
+
+/// Minimum sessions with new errors needed to call a trend
+const MIN_TREND_POINTS: usize = 3;
+
+/// Direction of the resolution rate over recent sessions
+///
+/// `slope` is the change in resolution rate per session (-1.0 to 1.0).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    /// Resolving a growing share of errors
+    Improving { slope: f32 },
+    /// No meaningful change (or too little history)
+    Plateau { slope: f32 },
+    /// Resolving fewer errors than before
+    Declining { slope: f32 },
+}
+
+impl Trend {
+    /// Change in resolution rate per session
+    pub fn slope(&self) -> f32 {
+        match self {
+            Trend::Improving { slope } | Trend::Plateau { slope } | Trend::Declining { slope } => {
+                *slope
+            }
+        }
+    }
+}
+
 /// Skill detector that analyzes learning progress
 pub struct SkillDetector {
     /// Minimum number of errors before confident assessment
     min_errors_for_assessment: u32,
+    /// Number of recent sessions considered for trends
+    trend_window: usize,
 }
 
 impl SkillDetector {
@@ -85,6 +117,70 @@ impl SkillDetector {
     pub fn new() -> Self {
         Self {
             min_errors_for_assessment: 5,
+            trend_window: 10,
+        }
+    }
+
+    /// Number of recent progress snapshots `trend` looks at
+    pub fn trend_window(&self) -> usize {
+        self.trend_window
+    }
+
+    /// Detect whether the resolution rate is improving, flat or declining
+    ///
+    /// `history` holds cumulative progress snapshots, oldest first (one per
+    /// session). Each session's own resolution rate is derived from the
+    /// difference between consecutive snapshots, and the trend is the
+    /// least-squares slope of those rates. Sessions without new errors are
+    /// skipped.
+    pub fn trend(&self, history: &[LearningProgress]) -> Trend {
+        let start = history.len().saturating_sub(self.trend_window + 1);
+        let rates: Vec<f32> = history[start..]
+            .windows(2)
+            .filter_map(|pair| {
+                let new_errors = pair[1].total_errors.checked_sub(pair[0].total_errors)?;
+                if new_errors == 0 {
+                    return None;
+                }
+                let resolved = pair[1]
+                    .resolved_errors
+                    .saturating_sub(pair[0].resolved_errors);
+                Some((resolved as f32 / new_errors as f32).min(1.0))
+            })
+            .collect();
+
+        if rates.len() < MIN_TREND_POINTS {
+            return Trend::Plateau { slope: 0.0 };
+        }
+
+        let slope = Self::slope(&rates);
+        if slope >= PLATEAU_SLOPE {
+            Trend::Improving { slope }
+        } else if slope <= -PLATEAU_SLOPE {
+            Trend::Declining { slope }
+        } else {
+            Trend::Plateau { slope }
+        }
+    }
+
+    /// Least-squares slope of evenly spaced values
+    fn slope(values: &[f32]) -> f32 {
+        let n = values.len() as f32;
+        let mean_x = (n - 1.0) / 2.0;
+        let mean_y = values.iter().sum::<f32>() / n;
+
+        let (num, den) = values
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(num, den), (i, y)| {
+                let dx = i as f32 - mean_x;
+                (num + dx * (y - mean_y), den + dx * dx)
+            });
+
+        if den == 0.0 {
+            0.0
+        } else {
+            num / den
         }
     }
 
@@ -281,6 +377,62 @@ mod tests {
         }
     }
 
+    /// Cumulative snapshots from per-session (errors, resolved) counts
+    fn history_from_sessions(sessions: &[(u32, u32)]) -> Vec<LearningProgress> {
+        let mut history = vec![create_test_progress(0, 0, vec![], vec![])];
+        let (mut total, mut resolved) = (0, 0);
+        for (errors, fixed) in sessions {
+            total += errors;
+            resolved += fixed;
+            history.push(create_test_progress(total, resolved, vec![], vec![]));
+        }
+        history
+    }
+
+    #[test]
+    fn test_trend_improving() {
+        let detector = SkillDetector::new();
+        let history = history_from_sessions(&[(10, 2), (10, 4), (10, 6), (10, 8)]);
+
+        let trend = detector.trend(&history);
+        assert!(matches!(trend, Trend::Improving { .. }));
+        assert!((trend.slope() - 0.2).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_trend_declining() {
+        let detector = SkillDetector::new();
+        let history = history_from_sessions(&[(5, 5), (5, 4), (0, 0), (5, 2), (5, 1)]);
+
+        assert!(matches!(detector.trend(&history), Trend::Declining { .. }));
+    }
+
+    #[test]
+    fn test_trend_plateau() {
+        let detector = SkillDetector::new();
+        let history = history_from_sessions(&[(4, 2), (6, 3), (8, 4), (2, 1)]);
+        assert!(matches!(detector.trend(&history), Trend::Plateau { .. }));
+
+        // Too little history to call a trend
+        let history = history_from_sessions(&[(10, 1), (10, 9)]);
+        assert_eq!(detector.trend(&history), Trend::Plateau { slope: 0.0 });
+    }
+
+    #[test]
+    fn test_trend_uses_recent_window() {
+        let detector = SkillDetector::new();
+        let mut sessions = vec![(20, 0); 10];
+        sessions.extend((0..10).map(|i| (20, 20 - i)));
+        let history = history_from_sessions(&sessions);
+
+        // Over all 20 sessions the rate went up; the last 10 slide from 100% to 55%
+        assert!(matches!(detector.trend(&history), Trend::Declining { .. }));
+        assert!(matches!(
+            detector.trend(&history[..12]),
+            Trend::Improving { .. }
+        ));
+    }
+
     #[test]
     fn test_skill_detector_new_user() {
         let detector = SkillDetector::new();
@@ -5,6 +5,8 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
+use super::skill::Trend;
+
 /// Session statistics for summary generation
 #[derive(Debug, Clone)]
 pub struct SessionStats {
@@ -22,6 +24,20 @@ pub struct SessionStats {
     pub concepts_learned: Vec<String>,
     /// Unique commands used
     pub unique_commands: Vec<String>,
+    /// Prompt tokens sent to AI backends
+    pub prompt_tokens: u64,
+    /// Completion tokens received from AI backends
+    pub completion_tokens: u64,
+    /// Occurrences of each distinct error, by `ErrorInfo::fingerprint`
+    pub recurring: HashMap<u64, RecurringError>,
+}
+
+/// An error seen during the session and how often
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringError {
+    /// How the error was first shown, e.g. "Permission Denied: open /etc/x"
+    pub message: String,
+    pub count: u32,
 }
 
 impl SessionStats {
@@ -35,6 +51,9 @@ impl SessionStats {
             errors_resolved: 0,
             concepts_learned: Vec::new(),
             unique_commands: Vec::new(),
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            recurring: HashMap::new(),
         }
     }
 
@@ -66,11 +85,53 @@ impl SessionStats {
         }
     }
 
+    /// Count an occurrence of the error with this fingerprint; returns how
+    /// many times it has now been seen (1 the first time)
+    pub fn record_occurrence(&mut self, fingerprint: u64, message: &str) -> u32 {
+        let entry = self
+            .recurring
+            .entry(fingerprint)
+            .or_insert_with(|| RecurringError {
+                message: message.to_string(),
+                count: 0,
+            });
+        entry.count += 1;
+        entry.count
+    }
+
+    /// Errors seen more than once, most frequent first
+    pub fn top_recurring(&self, limit: usize) -> Vec<RecurringError> {
+        let mut repeated: Vec<_> = self
+            .recurring
+            .values()
+            .filter(|e| e.count > 1)
+            .cloned()
+            .collect();
+        repeated.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.message.cmp(&b.message))
+        });
+        repeated.truncate(limit);
+        repeated
+    }
+
     /// Record an error resolution
     pub fn record_resolution(&mut self) {
         self.errors_resolved += 1;
     }
 
+    /// Record token usage from an AI response
+    pub fn record_tokens(&mut self, prompt: Option<u32>, completion: Option<u32>) {
+        self.prompt_tokens += u64::from(prompt.unwrap_or(0));
+        self.completion_tokens += u64::from(completion.unwrap_or(0));
+    }
+
+    /// Total tokens used this session
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
     /// Get session duration
     pub fn duration(&self) -> Duration {
         self.start_time.elapsed()
@@ -100,6 +161,14 @@ pub struct SessionSummary {
     pub next_steps: Vec<String>,
     /// Achievement earned (if any)
     pub achievement: Option<Achievement>,
+    /// Prompt tokens sent to AI backends
+    pub prompt_tokens: u64,
+    /// Completion tokens received from AI backends
+    pub completion_tokens: u64,
+    /// Resolution rate trend across recent sessions (if history is available)
+    pub trend: Option<Trend>,
+    /// Errors that kept coming back, most frequent first
+    pub recurring_errors: Vec<RecurringError>,
 }
 
 /// Summary of a concept learned
@@ -163,6 +232,10 @@ impl SummaryGenerator {
             tools_used,
             next_steps,
             achievement,
+            prompt_tokens: stats.prompt_tokens,
+            completion_tokens: stats.completion_tokens,
+            trend: None,
+            recurring_errors: stats.top_recurring(3),
         }
     }
 
@@ -319,6 +392,17 @@ impl SummaryGenerator {
             "\x1b[36m│\x1b[0m  Problems solved: \x1b[1m{:<10}\x1b[0m                          \x1b[36m│\x1b[0m\n",
             summary.problems_solved
         ));
+        if summary.prompt_tokens + summary.completion_tokens > 0 {
+            let tokens = format!(
+                "~{} ({} in / {} out)",
+                summary.prompt_tokens + summary.completion_tokens,
+                summary.prompt_tokens,
+                summary.completion_tokens
+            );
+            output.push_str(&format!(
+                "\x1b[36m│\x1b[0m  AI tokens used: \x1b[1m{tokens:<27}\x1b[0m           \x1b[36m│\x1b[0m\n"
+            ));
+        }
         output.push_str("\x1b[36m│\x1b[0m                                                            \x1b[36m│\x1b[0m\n");
 
         // Concepts learned
@@ -344,6 +428,19 @@ impl SummaryGenerator {
             output.push_str("\x1b[36m│\x1b[0m                                                            \x1b[36m│\x1b[0m\n");
         }
 
+        // Errors that kept coming back
+        if !summary.recurring_errors.is_empty() {
+            output.push_str("\x1b[36m│\x1b[0m  \x1b[1m🔁 Recurring Errors:\x1b[0m                                     \x1b[36m│\x1b[0m\n");
+            for error in &summary.recurring_errors {
+                let message: String = error.message.chars().take(42).collect();
+                let count = format!("×{}", error.count);
+                output.push_str(&format!(
+                    "\x1b[36m│\x1b[0m    • {message:<42} {count:>7} \x1b[36m│\x1b[0m\n"
+                ));
+            }
+            output.push_str("\x1b[36m│\x1b[0m                                                            \x1b[36m│\x1b[0m\n");
+        }
+
         // Next steps
         if !summary.next_steps.is_empty() {
             output.push_str("\x1b[36m│\x1b[0m  \x1b[1m💡 Suggested Next Steps:\x1b[0m                                 \x1b[36m│\x1b[0m\n");
@@ -355,6 +452,22 @@ impl SummaryGenerator {
             output.push_str("\x1b[36m│\x1b[0m                                                            \x1b[36m│\x1b[0m\n");
         }
 
+        // Resolution rate trend
+        let trend_lines: &[&str] = match summary.trend {
+            Some(Trend::Declining { .. }) => &[
+                "📉 Your resolution rate dropped lately. Want a refresher?",
+                "   Type 'progress' to review your most common errors.",
+            ],
+            Some(Trend::Improving { .. }) => &["📈 Your resolution rate is improving. Keep it up!"],
+            _ => &[],
+        };
+        if !trend_lines.is_empty() {
+            for line in trend_lines {
+                output.push_str(&format!("\x1b[36m│\x1b[0m  {line:<55} \x1b[36m│\x1b[0m\n"));
+            }
+            output.push_str("\x1b[36m│\x1b[0m                                                            \x1b[36m│\x1b[0m\n");
+        }
+
         // Achievement
         if let Some(achievement) = &summary.achievement {
             output.push_str(&format!(
@@ -407,6 +520,27 @@ mod tests {
         assert_eq!(stats.concepts_learned.len(), 2); // Deduplicated
     }
 
+    #[test]
+    fn test_top_recurring() {
+        let mut stats = SessionStats::new();
+        assert_eq!(stats.record_occurrence(1, "Timeout: curl"), 1);
+        assert_eq!(stats.record_occurrence(1, "Timeout: curl"), 2);
+        assert_eq!(stats.record_occurrence(1, "Timeout: curl"), 3);
+        stats.record_occurrence(2, "Permission Denied: /etc/x");
+        stats.record_occurrence(2, "Permission Denied: /etc/x");
+        stats.record_occurrence(3, "Once only");
+
+        let top = stats.top_recurring(3);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].message, "Timeout: curl");
+        assert_eq!(top[0].count, 3);
+
+        let rendered = SummaryGenerator::render(&SummaryGenerator::generate(&stats));
+        assert!(rendered.contains("Recurring Errors"));
+        assert!(rendered.contains("×3"));
+        assert!(!rendered.contains("Once only"));
+    }
+
     #[test]
     fn test_generate_summary() {
         let mut stats = SessionStats::new();
@@ -466,5 +600,36 @@ mod tests {
         let output = SummaryGenerator::render(&summary);
         assert!(output.contains("SESSION SUMMARY"));
         assert!(output.contains("Commands executed"));
+        assert!(!output.contains("AI tokens used"));
+        assert!(!output.contains("resolution rate"));
+    }
+
+    #[test]
+    fn test_render_declining_trend() {
+        let mut stats = SessionStats::new();
+        stats.record_command("ls");
+        let mut summary = SummaryGenerator::generate(&stats);
+        summary.trend = Some(Trend::Declining { slope: -0.1 });
+
+        let output = SummaryGenerator::render(&summary);
+        assert!(output.contains("resolution rate dropped"));
+        assert!(output.contains("refresher"));
+    }
+
+    #[test]
+    fn test_record_tokens() {
+        let mut stats = SessionStats::new();
+        stats.record_tokens(Some(120), Some(30));
+        stats.record_tokens(Some(80), None);
+        stats.record_tokens(None, None);
+
+        assert_eq!(stats.prompt_tokens, 200);
+        assert_eq!(stats.completion_tokens, 30);
+        assert_eq!(stats.total_tokens(), 230);
+
+        let summary = SummaryGenerator::generate(&stats);
+        let output = SummaryGenerator::render(&summary);
+        assert!(output.contains("AI tokens used"));
+        assert!(output.contains("~230 (200 in / 30 out)"));
     }
 }
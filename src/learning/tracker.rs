@@ -2,8 +2,9 @@
 //
 // Tracks error encounters and learning progress for users.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Mutex;
@@ -11,6 +12,7 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use super::schema::{ensure_learning_dir, init_schema};
 use crate::mentor::ErrorType;
+use crate::utils::sqlite::{self, WriteFailures};
 
 /// A recorded error encounter
 #[derive(Debug, Clone)]
@@ -23,6 +25,8 @@ pub struct ErrorEncounter {
     pub exit_code: Option<i32>,
     pub resolved: bool,
     pub resolution_time_ms: Option<u64>,
+    /// Command that resolved the error (may differ from `command`)
+    pub resolution_command: Option<String>,
     pub mentor_shown: bool,
 }
 
@@ -66,21 +70,118 @@ impl LearningProgress {
     }
 }
 
+/// A concept that keeps coming back without getting easier
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeakConcept {
+    /// Error type name, as stored in `concepts_learned`
+    pub concept: String,
+    pub encounters: u32,
+    /// Share of the last `REVIEW_WINDOW` encounters that were resolved
+    pub recent_resolution_rate: f32,
+    /// Refreshers already shown for it
+    pub reviews: u32,
+}
+
+/// Encounters before a concept can get a refresher
+const REVIEW_MIN_ENCOUNTERS: u32 = 3;
+
+/// Recent encounters a concept's resolution rate is measured over
+const REVIEW_WINDOW: usize = 5;
+
+/// Concepts resolved at least this often don't need a refresher
+const REVIEW_MAX_RESOLUTION_RATE: f32 = 0.5;
+
+/// At most one refresher per day, across all concepts
+const REVIEW_COOLDOWN_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Doubling of the review interval stops after this many reviews (32 days)
+const REVIEW_MAX_DOUBLINGS: u32 = 5;
+
+/// Format version written by `export_json`
+const EXPORT_VERSION: u32 = 1;
+
+/// Portable copy of the learning database (`kaido learning export`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LearningExport {
+    pub version: u32,
+    pub error_encounters: Vec<ExportedEncounter>,
+    pub concepts_learned: Vec<ExportedConcept>,
+    pub sessions: Vec<ExportedSession>,
+    pub progress_snapshots: Vec<ExportedSnapshot>,
+}
+
+/// `error_encounters` row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedEncounter {
+    pub timestamp: u64,
+    pub error_type: String,
+    pub key_message: String,
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub full_output: Option<String>,
+    pub resolved: bool,
+    pub resolution_time_ms: Option<u64>,
+    pub resolution_command: Option<String>,
+    pub mentor_shown: bool,
+}
+
+/// `concepts_learned` row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedConcept {
+    pub concept: String,
+    pub first_encounter: u64,
+    pub encounter_count: u32,
+    pub last_encounter: Option<u64>,
+}
+
+/// `sessions` row; `id` only links snapshots to their session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub id: i64,
+    pub start_time: u64,
+    pub end_time: Option<u64>,
+    pub commands_executed: u32,
+    pub errors_encountered: u32,
+    pub errors_resolved: u32,
+}
+
+/// `progress_snapshots` row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedSnapshot {
+    pub timestamp: u64,
+    pub session_id: Option<i64>,
+    pub total_errors: u32,
+    pub resolved_errors: u32,
+    pub resolution_rate: f64,
+}
+
+/// What `import_json` changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Error encounters added
+    pub added: usize,
+    /// Existing encounters that gained resolution data
+    pub resolved: usize,
+    /// Encounters already present
+    pub duplicates: usize,
+}
+
 /// Learning tracker for recording error encounters and progress
 pub struct LearningTracker {
     conn: Mutex<Connection>,
     session_id: Option<i64>,
+    write_failures: WriteFailures,
 }
 
 impl LearningTracker {
     /// Create a new learning tracker with the given database path
     pub fn new(db_path: impl AsRef<Path>) -> Result<Self> {
-        let conn = Connection::open(db_path)?;
-        init_schema(&conn)?;
+        let conn = sqlite::open(db_path, init_schema)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
             session_id: None,
+            write_failures: WriteFailures::default(),
         })
     }
 
@@ -96,28 +197,52 @@ impl LearningTracker {
         Self::new(":memory:")
     }
 
+    /// Create the tracker at the default path, or an in-memory one when
+    /// that database can't be opened (progress then isn't saved)
+    pub fn with_default_path_or_memory() -> Result<Self> {
+        Self::with_default_path().or_else(|e| {
+            log::warn!("Learning database unavailable ({e}); progress won't be saved this session");
+            Self::in_memory()
+        })
+    }
+
+    /// Run a write in one transaction, retried while the database is
+    /// locked; after a failure (logged once) the store isn't written again
+    fn write<T>(&self, op: impl Fn(&Connection) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        self.write_failures
+            .write("Learning database", &mut conn, op)
+    }
+
     /// Start a new learning session
     pub fn start_session(&mut self) -> Result<i64> {
         let now = current_timestamp();
-        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
-
-        conn.execute("INSERT INTO sessions (start_time) VALUES (?)", params![now])?;
+        let session_id = self.write(|conn| {
+            conn.execute("INSERT INTO sessions (start_time) VALUES (?)", params![now])?;
+            Ok(conn.last_insert_rowid())
+        })?;
 
-        let session_id = conn.last_insert_rowid();
         self.session_id = Some(session_id);
         Ok(session_id)
     }
 
     /// End the current session
+    ///
+    /// Also stores a progress snapshot so trends can be detected later.
     pub fn end_session(&mut self) -> Result<()> {
+        if self.session_id.is_some() {
+            self.record_progress_snapshot()?;
+        }
+
         if let Some(session_id) = self.session_id.take() {
             let now = current_timestamp();
-            let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
-
-            conn.execute(
-                "UPDATE sessions SET end_time = ? WHERE id = ?",
-                params![now, session_id],
-            )?;
+            self.write(|conn| {
+                conn.execute(
+                    "UPDATE sessions SET end_time = ? WHERE id = ?",
+                    params![now, session_id],
+                )?;
+                Ok(())
+            })?;
         }
         Ok(())
     }
@@ -132,56 +257,78 @@ impl LearningTracker {
         full_output: Option<&str>,
     ) -> Result<i64> {
         let now = current_timestamp();
-        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
-
-        conn.execute(
-            "INSERT INTO error_encounters (timestamp, error_type, key_message, command, exit_code, full_output)
-             VALUES (?, ?, ?, ?, ?, ?)",
-            params![
-                now,
-                error_type.name(),
-                key_message,
-                command,
-                exit_code,
-                full_output
-            ],
-        )?;
-
-        let error_id = conn.last_insert_rowid();
-
-        // Update session stats
-        if let Some(session_id) = self.session_id {
+        self.write(|conn| {
             conn.execute(
-                "UPDATE sessions SET errors_encountered = errors_encountered + 1 WHERE id = ?",
-                params![session_id],
+                "INSERT INTO error_encounters (timestamp, error_type, key_message, command, exit_code, full_output)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+                params![
+                    now,
+                    error_type.name(),
+                    key_message,
+                    command,
+                    exit_code,
+                    full_output
+                ],
             )?;
-        }
 
-        // Record concept if applicable
-        let concept = error_type.name().to_string();
-        self.record_concept_internal(&conn, &concept, now)?;
+            let error_id = conn.last_insert_rowid();
+
+            // Update session stats
+            if let Some(session_id) = self.session_id {
+                conn.execute(
+                    "UPDATE sessions SET errors_encountered = errors_encountered + 1 WHERE id = ?",
+                    params![session_id],
+                )?;
+            }
+
+            // Record concept if applicable
+            self.record_concept_internal(conn, error_type.name(), now)?;
 
-        Ok(error_id)
+            Ok(error_id)
+        })
     }
 
     /// Mark an error as resolved
     pub fn mark_resolved(&self, error_id: i64, resolution_time: Duration) -> Result<()> {
-        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        self.mark_resolved_internal(error_id, resolution_time, None)
+    }
 
-        conn.execute(
-            "UPDATE error_encounters SET resolved = 1, resolution_time_ms = ? WHERE id = ?",
-            params![resolution_time.as_millis() as i64, error_id],
-        )?;
+    /// Mark an error as resolved, recording the command that fixed it
+    pub fn mark_resolved_by(
+        &self,
+        error_id: i64,
+        resolution_time: Duration,
+        resolution_command: &str,
+    ) -> Result<()> {
+        self.mark_resolved_internal(error_id, resolution_time, Some(resolution_command))
+    }
 
-        // Update session stats
-        if let Some(session_id) = self.session_id {
+    fn mark_resolved_internal(
+        &self,
+        error_id: i64,
+        resolution_time: Duration,
+        resolution_command: Option<&str>,
+    ) -> Result<()> {
+        self.write(|conn| {
             conn.execute(
-                "UPDATE sessions SET errors_resolved = errors_resolved + 1 WHERE id = ?",
-                params![session_id],
+                "UPDATE error_encounters SET resolved = 1, resolution_time_ms = ?, resolution_command = ? WHERE id = ?",
+                params![
+                    resolution_time.as_millis() as i64,
+                    resolution_command,
+                    error_id
+                ],
             )?;
-        }
 
-        Ok(())
+            // Update session stats
+            if let Some(session_id) = self.session_id {
+                conn.execute(
+                    "UPDATE sessions SET errors_resolved = errors_resolved + 1 WHERE id = ?",
+                    params![session_id],
+                )?;
+            }
+
+            Ok(())
+        })
     }
 
     /// Record a concept learned
@@ -215,7 +362,7 @@ impl LearningTracker {
 
         let result = conn
             .query_row(
-                "SELECT id, timestamp, error_type, key_message, command, exit_code, resolved, resolution_time_ms, mentor_shown
+                "SELECT id, timestamp, error_type, key_message, command, exit_code, resolved, resolution_time_ms, resolution_command, mentor_shown
                  FROM error_encounters ORDER BY id DESC LIMIT 1",
                 [],
                 |row| {
@@ -228,7 +375,8 @@ impl LearningTracker {
                         exit_code: row.get(5)?,
                         resolved: row.get::<_, i32>(6)? != 0,
                         resolution_time_ms: row.get(7)?,
-                        mentor_shown: row.get::<_, i32>(8)? != 0,
+                        resolution_command: row.get(8)?,
+                        mentor_shown: row.get::<_, i32>(9)? != 0,
                     })
                 },
             )
@@ -310,6 +458,188 @@ impl LearningTracker {
         })
     }
 
+    /// The concept most in need of a refresher, if one is due
+    ///
+    /// A concept qualifies after `REVIEW_MIN_ENCOUNTERS` when fewer than
+    /// half its recent encounters were resolved and that rate isn't better
+    /// than before. Reviews of a concept are spaced 1, 2, 4, ... days apart,
+    /// dismissed concepts never come back, and nothing is due within a day
+    /// of the last refresher.
+    pub fn concept_due_for_review(&self) -> Result<Option<WeakConcept>> {
+        let now = current_timestamp();
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let last_review: Option<u64> = conn.query_row(
+            "SELECT MAX(last_reviewed) FROM concepts_learned",
+            [],
+            |row| row.get(0),
+        )?;
+        if last_review.is_some_and(|at| now < at + REVIEW_COOLDOWN_MS) {
+            return Ok(None);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT concept, encounter_count, last_reviewed, COALESCE(review_count, 0)
+             FROM concepts_learned
+             WHERE COALESCE(review_dismissed, 0) = 0 AND encounter_count >= ?",
+        )?;
+        let candidates = stmt
+            .query_map(params![REVIEW_MIN_ENCOUNTERS], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u32>(1)?,
+                    row.get::<_, Option<u64>>(2)?,
+                    row.get::<_, u32>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut weakest: Option<WeakConcept> = None;
+        for (concept, encounters, last_reviewed, reviews) in candidates {
+            let interval = REVIEW_COOLDOWN_MS << reviews.min(REVIEW_MAX_DOUBLINGS);
+            if last_reviewed.is_some_and(|at| now < at + interval) {
+                continue;
+            }
+
+            // Newest first: the window, then everything before it
+            let mut stmt = conn.prepare(
+                "SELECT resolved FROM error_encounters WHERE error_type = ?
+                 ORDER BY timestamp DESC, id DESC",
+            )?;
+            let resolved = stmt
+                .query_map(params![concept], |row| Ok(row.get::<_, i32>(0)? != 0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let (recent, earlier) = resolved.split_at(resolved.len().min(REVIEW_WINDOW));
+            let rate = |outcomes: &[bool]| {
+                outcomes.iter().filter(|r| **r).count() as f32 / outcomes.len() as f32
+            };
+            if recent.is_empty() {
+                continue;
+            }
+            let recent_rate = rate(recent);
+            let improving = !earlier.is_empty() && recent_rate > rate(earlier);
+            if recent_rate >= REVIEW_MAX_RESOLUTION_RATE || improving {
+                continue;
+            }
+
+            let candidate = WeakConcept {
+                concept,
+                encounters,
+                recent_resolution_rate: recent_rate,
+                reviews,
+            };
+            let weaker = weakest.as_ref().is_none_or(|w| {
+                (
+                    candidate.recent_resolution_rate,
+                    std::cmp::Reverse(candidate.encounters),
+                ) < (w.recent_resolution_rate, std::cmp::Reverse(w.encounters))
+            });
+            if weaker {
+                weakest = Some(candidate);
+            }
+        }
+        Ok(weakest)
+    }
+
+    /// Note that a refresher for `concept` was shown
+    pub fn mark_reviewed(&self, concept: &str) -> Result<()> {
+        let now = current_timestamp();
+        self.write(|conn| {
+            conn.execute(
+                "UPDATE concepts_learned
+                 SET last_reviewed = ?, review_count = COALESCE(review_count, 0) + 1
+                 WHERE concept = ?",
+                params![now, concept],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Stop showing refreshers for `concept`
+    pub fn dismiss_review(&self, concept: &str) -> Result<()> {
+        self.write(|conn| {
+            conn.execute(
+                "UPDATE concepts_learned SET review_dismissed = 1 WHERE concept = ?",
+                params![concept],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// How many times a feature hint has been shown, across sessions
+    pub fn hint_shown_count(&self, hint: &str) -> Result<u32> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        let count = conn
+            .query_row(
+                "SELECT shown_count FROM feature_hints WHERE hint = ?",
+                params![hint],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(count.unwrap_or(0))
+    }
+
+    /// Note that a feature hint was shown
+    pub fn mark_hint_shown(&self, hint: &str) -> Result<()> {
+        let now = current_timestamp();
+        self.write(|conn| {
+            conn.execute(
+                "INSERT INTO feature_hints (hint, shown_count, last_shown) VALUES (?1, 1, ?2)
+                 ON CONFLICT(hint) DO UPDATE SET shown_count = shown_count + 1, last_shown = ?2",
+                params![hint, now],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Store a snapshot of the current overall progress
+    pub fn record_progress_snapshot(&self) -> Result<()> {
+        let progress = self.get_progress()?;
+        let now = current_timestamp();
+        self.write(|conn| {
+            conn.execute(
+                "INSERT INTO progress_snapshots (timestamp, session_id, total_errors, resolved_errors, resolution_rate)
+                 VALUES (?, ?, ?, ?, ?)",
+                params![
+                    now,
+                    self.session_id,
+                    progress.total_errors,
+                    progress.resolved_errors,
+                    progress.resolution_rate
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Get the most recent progress snapshots, oldest first
+    ///
+    /// Snapshots only carry the totals; per-type breakdowns are left empty.
+    pub fn get_progress_history(&self, limit: usize) -> Result<Vec<LearningProgress>> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT total_errors, resolved_errors, resolution_rate FROM progress_snapshots
+             ORDER BY id DESC LIMIT ?",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok(LearningProgress {
+                total_errors: row.get(0)?,
+                resolved_errors: row.get(1)?,
+                resolution_rate: row.get::<_, f64>(2)? as f32,
+                ..LearningProgress::empty()
+            })
+        })?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            history.push(row?);
+        }
+        history.reverse();
+
+        Ok(history)
+    }
+
     /// Get error summary by type
     pub fn get_error_summaries(&self, limit: usize) -> Result<Vec<ErrorSummary>> {
         let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
@@ -342,6 +672,252 @@ impl LearningTracker {
         Ok(summaries)
     }
 
+    /// Delete all recorded errors, concepts, sessions, snapshots and hint counts
+    pub fn reset(&mut self) -> Result<()> {
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        let tx = conn.transaction()?;
+        for table in [
+            "error_encounters",
+            "concepts_learned",
+            "sessions",
+            "progress_snapshots",
+            "feature_hints",
+        ] {
+            tx.execute(&format!("DELETE FROM {table}"), [])?;
+        }
+        tx.commit()?;
+
+        self.session_id = None;
+        Ok(())
+    }
+
+    /// Dump every learning table to pretty-printed JSON
+    pub fn export_json(&self) -> Result<String> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, error_type, key_message, command, exit_code, full_output,
+                    resolved, resolution_time_ms, resolution_command, mentor_shown
+             FROM error_encounters ORDER BY id",
+        )?;
+        let error_encounters = stmt
+            .query_map([], |row| {
+                Ok(ExportedEncounter {
+                    timestamp: row.get(0)?,
+                    error_type: row.get(1)?,
+                    key_message: row.get(2)?,
+                    command: row.get(3)?,
+                    exit_code: row.get(4)?,
+                    full_output: row.get(5)?,
+                    resolved: row.get::<_, i32>(6)? != 0,
+                    resolution_time_ms: row.get(7)?,
+                    resolution_command: row.get(8)?,
+                    mentor_shown: row.get::<_, i32>(9)? != 0,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT concept, first_encounter, encounter_count, last_encounter
+             FROM concepts_learned ORDER BY id",
+        )?;
+        let concepts_learned = stmt
+            .query_map([], |row| {
+                Ok(ExportedConcept {
+                    concept: row.get(0)?,
+                    first_encounter: row.get(1)?,
+                    encounter_count: row.get(2)?,
+                    last_encounter: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, start_time, end_time, commands_executed, errors_encountered, errors_resolved
+             FROM sessions ORDER BY id",
+        )?;
+        let sessions = stmt
+            .query_map([], |row| {
+                Ok(ExportedSession {
+                    id: row.get(0)?,
+                    start_time: row.get(1)?,
+                    end_time: row.get(2)?,
+                    commands_executed: row.get(3)?,
+                    errors_encountered: row.get(4)?,
+                    errors_resolved: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT timestamp, session_id, total_errors, resolved_errors, resolution_rate
+             FROM progress_snapshots ORDER BY id",
+        )?;
+        let progress_snapshots = stmt
+            .query_map([], |row| {
+                Ok(ExportedSnapshot {
+                    timestamp: row.get(0)?,
+                    session_id: row.get(1)?,
+                    total_errors: row.get(2)?,
+                    resolved_errors: row.get(3)?,
+                    resolution_rate: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let export = LearningExport {
+            version: EXPORT_VERSION,
+            error_encounters,
+            concepts_learned,
+            sessions,
+            progress_snapshots,
+        };
+        Ok(serde_json::to_string_pretty(&export)?)
+    }
+
+    /// Merge an `export_json` dump into this database
+    ///
+    /// Encounters are deduplicated by (error_type, command, timestamp); a
+    /// duplicate only updates the existing row if it adds resolution data.
+    /// Concepts keep the highest count, so importing a file twice is harmless.
+    pub fn import_json(&self, json: &str) -> Result<ImportSummary> {
+        let export: LearningExport =
+            serde_json::from_str(json).context("Not a Kaido learning export")?;
+        if export.version > EXPORT_VERSION {
+            anyhow::bail!(
+                "Learning export version {} is newer than this Kaido supports ({EXPORT_VERSION})",
+                export.version
+            );
+        }
+
+        let mut conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        let tx = conn.transaction()?;
+        let mut summary = ImportSummary::default();
+
+        for encounter in &export.error_encounters {
+            let existing: Option<(i64, bool)> = tx
+                .query_row(
+                    "SELECT id, resolved FROM error_encounters
+                     WHERE error_type = ? AND command = ? AND timestamp = ?",
+                    params![encounter.error_type, encounter.command, encounter.timestamp],
+                    |row| Ok((row.get(0)?, row.get::<_, i32>(1)? != 0)),
+                )
+                .optional()?;
+
+            match existing {
+                Some((id, false)) if encounter.resolved => {
+                    tx.execute(
+                        "UPDATE error_encounters
+                         SET resolved = 1, resolution_time_ms = ?, resolution_command = ?
+                         WHERE id = ?",
+                        params![
+                            encounter.resolution_time_ms,
+                            encounter.resolution_command,
+                            id
+                        ],
+                    )?;
+                    summary.resolved += 1;
+                }
+                Some(_) => summary.duplicates += 1,
+                None => {
+                    tx.execute(
+                        "INSERT INTO error_encounters
+                         (timestamp, error_type, key_message, command, exit_code, full_output,
+                          resolved, resolution_time_ms, resolution_command, mentor_shown)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                        params![
+                            encounter.timestamp,
+                            encounter.error_type,
+                            encounter.key_message,
+                            encounter.command,
+                            encounter.exit_code,
+                            encounter.full_output,
+                            encounter.resolved,
+                            encounter.resolution_time_ms,
+                            encounter.resolution_command,
+                            encounter.mentor_shown
+                        ],
+                    )?;
+                    summary.added += 1;
+                }
+            }
+        }
+
+        for concept in &export.concepts_learned {
+            tx.execute(
+                "INSERT INTO concepts_learned (concept, first_encounter, encounter_count, last_encounter)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT(concept) DO UPDATE SET
+                     first_encounter = MIN(first_encounter, ?2),
+                     encounter_count = MAX(encounter_count, ?3),
+                     last_encounter = MAX(COALESCE(last_encounter, 0), COALESCE(?4, 0))",
+                params![
+                    concept.concept,
+                    concept.first_encounter,
+                    concept.encounter_count,
+                    concept.last_encounter
+                ],
+            )?;
+        }
+
+        // Sessions get new ids here, so remap the snapshots that point at them
+        let mut session_ids = HashMap::new();
+        for session in &export.sessions {
+            let existing: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM sessions WHERE start_time = ?",
+                    params![session.start_time],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let id = match existing {
+                Some(id) => id,
+                None => {
+                    tx.execute(
+                        "INSERT INTO sessions
+                         (start_time, end_time, commands_executed, errors_encountered, errors_resolved)
+                         VALUES (?, ?, ?, ?, ?)",
+                        params![
+                            session.start_time,
+                            session.end_time,
+                            session.commands_executed,
+                            session.errors_encountered,
+                            session.errors_resolved
+                        ],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+            session_ids.insert(session.id, id);
+        }
+
+        for snapshot in &export.progress_snapshots {
+            let exists: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM progress_snapshots WHERE timestamp = ?)",
+                params![snapshot.timestamp],
+                |row| row.get(0),
+            )?;
+            if exists {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO progress_snapshots
+                 (timestamp, session_id, total_errors, resolved_errors, resolution_rate)
+                 VALUES (?, ?, ?, ?, ?)",
+                params![
+                    snapshot.timestamp,
+                    snapshot.session_id.and_then(|id| session_ids.get(&id)),
+                    snapshot.total_errors,
+                    snapshot.resolved_errors,
+                    snapshot.resolution_rate
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(summary)
+    }
+
     /// Check if commands are similar (for resolution detection)
     pub fn is_similar_command(cmd1: &str, cmd2: &str) -> bool {
         // Extract the base command (first word)
@@ -417,6 +993,30 @@ mod tests {
         let last = tracker.get_last_error().unwrap().unwrap();
         assert!(last.resolved);
         assert_eq!(last.resolution_time_ms, Some(30000));
+        assert!(last.resolution_command.is_none());
+    }
+
+    #[test]
+    fn test_mark_resolved_by() {
+        let tracker = LearningTracker::in_memory().unwrap();
+
+        let error_id = tracker
+            .record_error(
+                &ErrorType::CommandNotFound,
+                "command not found: jq",
+                "jq .name package.json",
+                Some(127),
+                None,
+            )
+            .unwrap();
+
+        tracker
+            .mark_resolved_by(error_id, Duration::from_secs(45), "brew install jq")
+            .unwrap();
+
+        let last = tracker.get_last_error().unwrap().unwrap();
+        assert!(last.resolved);
+        assert_eq!(last.resolution_command.as_deref(), Some("brew install jq"));
     }
 
     #[test]
@@ -478,6 +1078,35 @@ mod tests {
         assert!(tracker.session_id.is_none());
     }
 
+    #[test]
+    fn test_progress_history() {
+        let mut tracker = LearningTracker::in_memory().unwrap();
+
+        tracker.start_session().unwrap();
+        let id = tracker
+            .record_error(&ErrorType::CommandNotFound, "error", "cmd", Some(127), None)
+            .unwrap();
+        tracker.mark_resolved(id, Duration::from_secs(5)).unwrap();
+        tracker.end_session().unwrap();
+
+        tracker.start_session().unwrap();
+        tracker
+            .record_error(&ErrorType::PermissionDenied, "error", "cmd", Some(1), None)
+            .unwrap();
+        tracker.end_session().unwrap();
+
+        let history = tracker.get_progress_history(10).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].total_errors, 1);
+        assert_eq!(history[0].resolved_errors, 1);
+        assert_eq!(history[1].total_errors, 2);
+        assert!((history[1].resolution_rate - 0.5).abs() < 0.01);
+
+        let latest = tracker.get_progress_history(1).unwrap();
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].total_errors, 2);
+    }
+
     #[test]
     fn test_is_similar_command() {
         assert!(LearningTracker::is_similar_command("ls -la", "ls /tmp"));
@@ -517,4 +1146,187 @@ mod tests {
         assert_eq!(summaries[1].error_type, "Permission Denied");
         assert_eq!(summaries[1].count, 2);
     }
+
+    #[test]
+    fn test_reset() {
+        let mut tracker = LearningTracker::in_memory().unwrap();
+        tracker.start_session().unwrap();
+        tracker
+            .record_error(
+                &ErrorType::CommandNotFound,
+                "not found",
+                "foo",
+                Some(127),
+                None,
+            )
+            .unwrap();
+        tracker.end_session().unwrap();
+
+        tracker.reset().unwrap();
+        assert_eq!(tracker.get_progress().unwrap().total_errors, 0);
+        assert!(tracker.get_progress().unwrap().concepts.is_empty());
+        assert!(tracker.get_progress_history(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let mut source = LearningTracker::in_memory().unwrap();
+        source.start_session().unwrap();
+        let fixed = source
+            .record_error(
+                &ErrorType::PermissionDenied,
+                "Permission denied",
+                "cat /etc/shadow",
+                Some(1),
+                Some("cat: /etc/shadow: Permission denied"),
+            )
+            .unwrap();
+        source
+            .record_error(
+                &ErrorType::CommandNotFound,
+                "not found",
+                "foo",
+                Some(127),
+                None,
+            )
+            .unwrap();
+        source
+            .mark_resolved_by(fixed, Duration::from_secs(5), "sudo cat /etc/shadow")
+            .unwrap();
+        source.end_session().unwrap();
+        let json = source.export_json().unwrap();
+
+        let target = LearningTracker::in_memory().unwrap();
+        let summary = target.import_json(&json).unwrap();
+        assert_eq!(summary.added, 2);
+
+        let progress = target.get_progress().unwrap();
+        assert_eq!(progress.total_errors, 2);
+        assert_eq!(progress.resolved_errors, 1);
+        assert_eq!(progress.concepts.len(), 2);
+        assert_eq!(target.get_progress_history(10).unwrap().len(), 1);
+
+        // Importing again adds nothing
+        let again = target.import_json(&json).unwrap();
+        assert_eq!(
+            again,
+            ImportSummary {
+                added: 0,
+                resolved: 0,
+                duplicates: 2
+            }
+        );
+        assert_eq!(target.get_progress().unwrap().total_errors, 2);
+        assert_eq!(target.get_progress_history(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_import_preserves_resolution() {
+        let source = LearningTracker::in_memory().unwrap();
+        let id = source
+            .record_error(
+                &ErrorType::FileNotFound,
+                "No such file",
+                "cat x",
+                Some(1),
+                None,
+            )
+            .unwrap();
+        let unresolved = source.export_json().unwrap();
+        source
+            .mark_resolved_by(id, Duration::from_secs(3), "cat y")
+            .unwrap();
+        let resolved = source.export_json().unwrap();
+
+        // The resolved copy fills in resolution data on the existing row
+        let target = LearningTracker::in_memory().unwrap();
+        target.import_json(&unresolved).unwrap();
+        assert_eq!(target.import_json(&resolved).unwrap().resolved, 1);
+        let last = target.get_last_error().unwrap().unwrap();
+        assert!(last.resolved);
+        assert_eq!(last.resolution_command.as_deref(), Some("cat y"));
+
+        // An unresolved copy never undoes a resolution
+        target.import_json(&unresolved).unwrap();
+        assert!(target.get_last_error().unwrap().unwrap().resolved);
+
+        assert!(target.import_json("{\"nope\": true}").is_err());
+    }
+
+    #[test]
+    fn test_concept_due_for_review() {
+        let tracker = LearningTracker::in_memory().unwrap();
+        let record = |error_type: &ErrorType, resolve: bool| {
+            let id = tracker
+                .record_error(error_type, "error", "cmd", Some(1), None)
+                .unwrap();
+            if resolve {
+                tracker.mark_resolved(id, Duration::from_secs(5)).unwrap();
+            }
+        };
+
+        // Two encounters aren't a pattern yet
+        record(&ErrorType::PermissionDenied, false);
+        record(&ErrorType::PermissionDenied, false);
+        assert_eq!(tracker.concept_due_for_review().unwrap(), None);
+        record(&ErrorType::PermissionDenied, true);
+        // Mostly resolved: fine without a refresher
+        for _ in 0..3 {
+            record(&ErrorType::FileNotFound, true);
+        }
+
+        let due = tracker.concept_due_for_review().unwrap().unwrap();
+        assert_eq!(due.concept, "Permission Denied");
+        assert_eq!(due.encounters, 3);
+        assert!((due.recent_resolution_rate - 1.0 / 3.0).abs() < 0.01);
+
+        // Once a day at most
+        tracker.mark_reviewed(&due.concept).unwrap();
+        assert_eq!(tracker.concept_due_for_review().unwrap(), None);
+
+        // A day later the concept itself waits two days after its first review
+        let day_ago = current_timestamp() - REVIEW_COOLDOWN_MS - 1000;
+        let set_reviewed = |at: u64| {
+            let conn = tracker.conn.lock().unwrap();
+            conn.execute("UPDATE concepts_learned SET last_reviewed = ?", params![at])
+                .unwrap();
+        };
+        set_reviewed(day_ago);
+        assert_eq!(tracker.concept_due_for_review().unwrap(), None);
+        set_reviewed(day_ago - REVIEW_COOLDOWN_MS);
+        assert_eq!(
+            tracker.concept_due_for_review().unwrap().unwrap().reviews,
+            1
+        );
+
+        tracker.dismiss_review("Permission Denied").unwrap();
+        assert_eq!(tracker.concept_due_for_review().unwrap(), None);
+    }
+
+    #[test]
+    fn test_hint_shown_count() {
+        let tracker = LearningTracker::in_memory().unwrap();
+        assert_eq!(tracker.hint_shown_count("why").unwrap(), 0);
+        tracker.mark_hint_shown("why").unwrap();
+        tracker.mark_hint_shown("why").unwrap();
+        assert_eq!(tracker.hint_shown_count("why").unwrap(), 2);
+        assert_eq!(tracker.hint_shown_count("fix").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_improving_concept_is_not_reviewed() {
+        let tracker = LearningTracker::in_memory().unwrap();
+        // Five unresolved, then the last five resolved twice: better than before
+        for resolve in [
+            false, false, false, false, false, false, true, false, true, false,
+        ] {
+            let id = tracker
+                .record_error(&ErrorType::GitError, "error", "git push", Some(1), None)
+                .unwrap();
+            if resolve {
+                tracker.mark_resolved(id, Duration::from_secs(5)).unwrap();
+            }
+        }
+        assert_eq!(tracker.concept_due_for_review().unwrap(), None);
+    }
 }
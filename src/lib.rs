@@ -4,14 +4,17 @@
 pub mod agent;
 pub mod ai;
 pub mod audit;
+pub mod bug_report;
 pub mod coach;
 pub mod commands;
 pub mod config;
+pub mod doctor;
 pub mod error;
 pub mod kubectl;
 pub mod learning;
 pub mod mcp;
 pub mod mentor;
+pub mod secrets;
 pub mod shell;
 pub mod target;
 pub mod tools;
@@ -27,6 +30,9 @@ pub use error::PatternMatcher;
 pub use learning::{LearningProgress, LearningTracker};
 pub use mcp::{KaidoTools, McpServer};
 pub use mentor::{ErrorDetector, ErrorInfo, ErrorType, MentorDisplay, Verbosity};
-pub use shell::{CommandParser, KaidoShell, ParseError, PromptBuilder, PtyExecutionResult, PtyExecutor, ShellConfig};
+pub use shell::{
+    CommandParser, KaidoShell, ParseError, PromptBuilder, PtyExecutionResult, PtyExecutor,
+    ShellConfig,
+};
 pub use target::Target;
 pub use tools::{RiskLevel, Tool, ToolRegistry};
@@ -0,0 +1,750 @@
+// MCP HTTP/SSE transport
+//
+// Serves the same tools as the stdio server using the MCP SSE transport: a
+// client opens `GET /sse`, receives an `endpoint` event naming its message
+// URL, and POSTs JSON-RPC requests there. Responses arrive as `message`
+// events on the stream. Every connection runs on its own task, so a slow
+// tool call never blocks other requests.
+//
+// POSTing to /message without a sessionId returns the response in the HTTP
+// body instead, which is handy for curl and simple clients.
+//
+// `kaido_execute` runs commands, so every request must carry the bearer
+// token printed at startup, and requests whose Host or Origin isn't this
+// machine are refused. That keeps web pages out, whether they POST to
+// localhost directly or through DNS rebinding.
+
+use super::server::{McpHandler, McpSession};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinSet;
+
+/// Largest accepted request line + headers
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+
+/// Largest accepted request body
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// How often idle SSE streams get a keep-alive comment
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long in-flight requests may run after shutdown starts
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Open SSE streams by session id, with what's known about their client
+type Sessions = Arc<Mutex<HashMap<String, (mpsc::UnboundedSender<String>, Arc<McpSession>)>>>;
+
+/// Environment variable that fixes the bearer token instead of a random one
+pub const TOKEN_ENV: &str = "KAIDO_MCP_TOKEN";
+
+/// Shortest bearer token accepted in place of the generated one
+pub const MIN_TOKEN_LEN: usize = 16;
+
+/// MCP server over HTTP with the SSE transport
+pub struct McpHttpServer {
+    handler: Arc<McpHandler>,
+    sessions: Sessions,
+    token: String,
+}
+
+impl McpHttpServer {
+    /// Create a server exposing the default Kaido tools
+    pub fn new() -> Self {
+        Self::with_handler(McpHandler::new())
+    }
+
+    /// Create a server around an existing handler, with a random token
+    pub fn with_handler(handler: McpHandler) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+        }
+    }
+
+    /// Require `token` instead of the generated one
+    ///
+    /// Fails for a token shorter than `MIN_TOKEN_LEN` once trimmed, so a
+    /// blank `KAIDO_MCP_TOKEN` can't leave the server open.
+    pub fn with_token(mut self, token: impl Into<String>) -> Result<Self> {
+        let token = token.into().trim().to_string();
+        if token.len() < MIN_TOKEN_LEN {
+            anyhow::bail!(
+                "{TOKEN_ENV} must be at least {MIN_TOKEN_LEN} characters (got {})",
+                token.len()
+            );
+        }
+        self.token = token;
+        Ok(self)
+    }
+
+    /// Bearer token clients must send
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Serve on `addr` until Ctrl-C or a `shutdown` request
+    pub async fn serve(&self, addr: impl ToSocketAddrs) -> Result<()> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .context("Failed to bind MCP HTTP server")?;
+        eprintln!(
+            "[kaido-mcp] Listening on http://{}/sse",
+            listener.local_addr()?
+        );
+        eprintln!("[kaido-mcp] Authorization: Bearer {}", self.token);
+
+        self.serve_listener(listener, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+    }
+
+    /// Serve connections from a bound listener until `shutdown` completes
+    /// or a client sends a JSON-RPC `shutdown`
+    ///
+    /// On shutdown no new connections are accepted, open SSE streams are
+    /// closed once their pending responses are sent, and in-flight requests
+    /// get a grace period to finish before being aborted.
+    pub async fn serve_listener(
+        &self,
+        listener: TcpListener,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<()> {
+        tokio::pin!(shutdown);
+        let mut connections = JoinSet::new();
+        let state = Arc::new(ServerState {
+            handler: Arc::clone(&self.handler),
+            sessions: Arc::clone(&self.sessions),
+            access: Access {
+                token: self.token.clone(),
+                bound_ip: listener
+                    .local_addr()
+                    .ok()
+                    .map(|addr| addr.ip())
+                    .filter(|ip| !ip.is_unspecified()),
+            },
+            shutdown: Notify::new(),
+        });
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = state.shutdown.notified() => break,
+                accepted = listener.accept() => {
+                    let stream = match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            eprintln!("[kaido-mcp] Accept failed: {e}");
+                            continue;
+                        }
+                    };
+                    let state = Arc::clone(&state);
+                    connections.spawn(async move {
+                        if let Err(e) = handle_connection(stream, state).await {
+                            eprintln!("[kaido-mcp] Connection error: {e}");
+                        }
+                    });
+                }
+                // Reap finished connections so the set doesn't grow
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+            }
+        }
+
+        eprintln!("[kaido-mcp] Shutting down...");
+        drop(listener);
+        // Dropping the senders ends each SSE stream after its queued events
+        self.sessions.lock().unwrap().clear();
+
+        let drained = tokio::time::timeout(SHUTDOWN_GRACE, async {
+            while connections.join_next().await.is_some() {}
+        })
+        .await;
+        if drained.is_err() {
+            eprintln!(
+                "[kaido-mcp] Aborting {} unfinished connection(s)",
+                connections.len()
+            );
+            connections.shutdown().await;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for McpHttpServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What every connection task shares
+struct ServerState {
+    handler: Arc<McpHandler>,
+    sessions: Sessions,
+    access: Access,
+    /// Signalled when a client sends `shutdown`
+    shutdown: Notify,
+}
+
+/// Who may talk to the server
+struct Access {
+    token: String,
+    /// Address the listener is bound to, when it's a specific one
+    bound_ip: Option<IpAddr>,
+}
+
+impl Access {
+    /// Why `request` must be refused, as a status line and message
+    fn check(&self, request: &HttpRequest) -> Option<(&'static str, &'static str)> {
+        let host_ok = request
+            .header("host")
+            .is_some_and(|host| self.is_local_host(host));
+        let origin_ok = request.header("origin").is_none_or(|origin| {
+            origin
+                .split_once("://")
+                .map(|(_, rest)| rest.split('/').next().unwrap_or(""))
+                .is_some_and(|host| self.is_local_host(host))
+        });
+        if !host_ok || !origin_ok {
+            return Some(("403 Forbidden", "Host or Origin is not local"));
+        }
+
+        let authorized = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim(), &self.token));
+        if !authorized {
+            return Some(("401 Unauthorized", "Missing or wrong bearer token"));
+        }
+
+        let is_json = request.header("content-type").is_some_and(|value| {
+            value
+                .split(';')
+                .next()
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+        });
+        if request.method == "POST" && !is_json {
+            return Some((
+                "415 Unsupported Media Type",
+                "Content-Type must be application/json",
+            ));
+        }
+        None
+    }
+
+    /// Whether a Host header value (or Origin authority) names this machine
+    fn is_local_host(&self, authority: &str) -> bool {
+        let host = match authority.strip_prefix('[') {
+            Some(rest) => rest.split(']').next().unwrap_or(""),
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+                _ => authority,
+            },
+        };
+        if host.eq_ignore_ascii_case("localhost") {
+            return true;
+        }
+        host.parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback() || Some(ip) == self.bound_ip)
+    }
+}
+
+/// Compare secrets without leaking the length of the matching prefix
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// A parsed HTTP request
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    target: String,
+    /// Header names lowercased
+    headers: Vec<(String, String)>,
+    content_length: usize,
+    body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Value of a header, by lowercase name
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Request path without the query string
+    fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or("")
+    }
+
+    /// Value of a query string parameter
+    fn query_param(&self, name: &str) -> Option<&str> {
+        let (_, query) = self.target.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Parse the request line and headers (body not included)
+fn parse_head(head: &str) -> Result<HttpRequest> {
+    let mut lines = head.lines();
+    let request_line = lines.next().context("Empty request")?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().context("Missing method")?.to_string();
+    let target = parts.next().context("Missing request target")?.to_string();
+
+    let mut content_length = 0;
+    let mut headers = Vec::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            if name == "content-length" {
+                content_length = value.trim().parse().context("Invalid Content-Length")?;
+            }
+            headers.push((name, value.trim().to_string()));
+        }
+    }
+
+    Ok(HttpRequest {
+        method,
+        target,
+        headers,
+        content_length,
+        body: Vec::new(),
+    })
+}
+
+/// Read one request from the connection
+///
+/// Returns None if the client closed the connection before sending anything.
+async fn read_request(reader: &mut BufReader<TcpStream>) -> Result<Option<HttpRequest>> {
+    let mut head = String::new();
+    let mut limited = (&mut *reader).take(MAX_HEADER_BYTES);
+    loop {
+        let read = limited.read_line(&mut head).await?;
+        if read == 0 {
+            if head.is_empty() {
+                return Ok(None);
+            }
+            anyhow::bail!("Request headers incomplete or too large");
+        }
+        if head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+    }
+
+    let mut request = parse_head(&head)?;
+    if request.content_length > MAX_BODY_BYTES {
+        anyhow::bail!("Request body too large ({} bytes)", request.content_length);
+    }
+    request.body = vec![0; request.content_length];
+    reader.read_exact(&mut request.body).await?;
+
+    Ok(Some(request))
+}
+
+async fn handle_connection(stream: TcpStream, state: Arc<ServerState>) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = match read_request(&mut reader).await {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            let mut stream = reader.into_inner();
+            write_response(&mut stream, "400 Bad Request", "text/plain", &e.to_string()).await?;
+            return Ok(());
+        }
+    };
+    let mut stream = reader.into_inner();
+
+    if let Some((status, reason)) = state.access.check(&request) {
+        eprintln!(
+            "[kaido-mcp] Refused {} {}: {reason}",
+            request.method,
+            request.path()
+        );
+        return write_response(&mut stream, status, "text/plain", reason).await;
+    }
+
+    match (request.method.as_str(), request.path()) {
+        ("GET", "/sse") => serve_sse(stream, &state.sessions).await,
+        ("POST", "/message") => handle_post(&mut stream, &request, &state).await,
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not found").await,
+    }
+}
+
+/// Handle a JSON-RPC POST, replying on the session's stream or inline
+async fn handle_post(
+    stream: &mut TcpStream,
+    request: &HttpRequest,
+    state: &ServerState,
+) -> Result<()> {
+    let message = String::from_utf8_lossy(&request.body);
+
+    let Some(session_id) = request.query_param("sessionId") else {
+        // A sessionless POST stands alone, client name included
+        let session = McpSession::default();
+        let result = match state
+            .handler
+            .handle_session_message(&message, &session)
+            .await
+        {
+            Some(response) => {
+                let body = serde_json::to_string(&response)?;
+                write_response(stream, "200 OK", "application/json", &body).await
+            }
+            None => write_response(stream, "202 Accepted", "text/plain", "").await,
+        };
+        stop_if_requested(state);
+        return result;
+    };
+
+    let entry = state.sessions.lock().unwrap().get(session_id).cloned();
+    let Some((sender, session)) = entry else {
+        return write_response(stream, "404 Not Found", "text/plain", "Unknown session").await;
+    };
+
+    // Acknowledge right away; the response goes out on the SSE stream
+    write_response(stream, "202 Accepted", "text/plain", "Accepted").await?;
+    if let Some(response) = state
+        .handler
+        .handle_session_message(&message, &session)
+        .await
+    {
+        // The stream may have closed meanwhile; nothing left to notify
+        let _ = sender.send(serde_json::to_string(&response)?);
+    }
+    stop_if_requested(state);
+
+    Ok(())
+}
+
+/// Start shutting down once a client has sent `shutdown`
+fn stop_if_requested(state: &ServerState) {
+    if state.handler.shutdown_requested() {
+        state.shutdown.notify_one();
+    }
+}
+
+/// Hold an SSE stream open, forwarding responses for its session
+async fn serve_sse(mut stream: TcpStream, sessions: &Sessions) -> Result<()> {
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    sessions
+        .lock()
+        .unwrap()
+        .insert(session_id.clone(), (sender, Arc::default()));
+    eprintln!("[kaido-mcp] SSE session {session_id} opened");
+
+    let result = stream_events(&mut stream, &session_id, &mut receiver).await;
+
+    sessions.lock().unwrap().remove(&session_id);
+    eprintln!("[kaido-mcp] SSE session {session_id} closed");
+    result
+}
+
+async fn stream_events(
+    stream: &mut TcpStream,
+    session_id: &str,
+    receiver: &mut mpsc::UnboundedReceiver<String>,
+) -> Result<()> {
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\n\
+              Content-Type: text/event-stream\r\n\
+              Cache-Control: no-cache\r\n\
+              Connection: keep-alive\r\n\r\n",
+        )
+        .await?;
+    write_event(
+        stream,
+        "endpoint",
+        &format!("/message?sessionId={session_id}"),
+    )
+    .await?;
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    keepalive.tick().await;
+
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(message) => write_event(stream, "message", &message).await?,
+                None => break,
+            },
+            // Writing also detects clients that went away
+            _ = keepalive.tick() => stream.write_all(b": keepalive\n\n").await?,
+        }
+    }
+
+    Ok(())
+}
+
+async fn write_event(stream: &mut TcpStream, event: &str, data: &str) -> Result<()> {
+    stream
+        .write_all(format!("event: {event}\ndata: {data}\n\n").as_bytes())
+        .await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: {content_type}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n\
+         {body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::KaidoTools;
+    use tempfile::TempDir;
+    use tokio::sync::oneshot;
+
+    const TOKEN: &str = "test-token-0123456789";
+
+    /// Start a server on an ephemeral port; returns its address and a stop handle
+    async fn start_server(
+        dir: &TempDir,
+    ) -> (
+        std::net::SocketAddr,
+        oneshot::Sender<()>,
+        tokio::task::JoinHandle<Result<()>>,
+    ) {
+        let tools = KaidoTools::with_audit_db(dir.path().join("audit.db"));
+        let server = McpHttpServer::with_handler(McpHandler::with_tools(tools))
+            .with_token(TOKEN)
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            server
+                .serve_listener(listener, async {
+                    let _ = stopped.await;
+                })
+                .await
+        });
+        (addr, stop, task)
+    }
+
+    async fn post(addr: std::net::SocketAddr, target: &str, body: &str) -> String {
+        let headers = format!(
+            "Host: localhost\r\nAuthorization: Bearer {TOKEN}\r\nContent-Type: application/json\r\n"
+        );
+        post_with(addr, target, &headers, body).await
+    }
+
+    async fn post_with(
+        addr: std::net::SocketAddr,
+        target: &str,
+        headers: &str,
+        body: &str,
+    ) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {target} HTTP/1.1\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// Read SSE lines until one starting with `data: ` follows `event: <name>`
+    async fn next_event(reader: &mut BufReader<TcpStream>, name: &str) -> String {
+        let mut current = String::new();
+        loop {
+            let mut line = String::new();
+            assert!(
+                reader.read_line(&mut line).await.unwrap() > 0,
+                "stream ended"
+            );
+            let line = line.trim_end();
+            if let Some(event) = line.strip_prefix("event: ") {
+                current = event.to_string();
+            } else if let Some(data) = line.strip_prefix("data: ") {
+                if current == name {
+                    return data.to_string();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "POST /message?sessionId=abc&x=1 HTTP/1.1\r\nHost: localhost\r\ncontent-length: 42\r\n\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path(), "/message");
+        assert_eq!(request.query_param("sessionId"), Some("abc"));
+        assert_eq!(request.query_param("missing"), None);
+        assert_eq!(request.content_length, 42);
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert!(parse_head("").is_err());
+    }
+
+    #[test]
+    fn test_rejects_blank_and_short_tokens() {
+        for token in ["", "   ", "short"] {
+            assert!(McpHttpServer::new().with_token(token).is_err(), "{token:?}");
+        }
+        let server = McpHttpServer::new()
+            .with_token(format!(" {TOKEN}\n"))
+            .unwrap();
+        assert_eq!(server.token(), TOKEN);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_foreign_origin_missing_token_and_non_json() {
+        let dir = TempDir::new().unwrap();
+        let (addr, stop, task) = start_server(&dir).await;
+        let body = r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/list"}"#;
+        let auth = format!("Authorization: Bearer {TOKEN}\r\n");
+
+        // A web page posting through DNS rebinding or a cross-site form
+        let rebinding = format!(
+            "Host: evil.example:{}\r\n{auth}Content-Type: application/json\r\n",
+            addr.port()
+        );
+        assert!(post_with(addr, "/message", &rebinding, body)
+            .await
+            .starts_with("HTTP/1.1 403"));
+        let cross_site = format!("Host: localhost\r\nOrigin: https://evil.example\r\n{auth}Content-Type: application/json\r\n");
+        assert!(post_with(addr, "/message", &cross_site, body)
+            .await
+            .starts_with("HTTP/1.1 403"));
+
+        let no_token = "Host: 127.0.0.1\r\nContent-Type: application/json\r\n";
+        assert!(post_with(addr, "/message", no_token, body)
+            .await
+            .starts_with("HTTP/1.1 401"));
+        let form = format!("Host: [::1]:80\r\n{auth}Content-Type: text/plain\r\n");
+        assert!(post_with(addr, "/message", &form, body)
+            .await
+            .starts_with("HTTP/1.1 415"));
+
+        let local_origin = format!("Host: localhost\r\nOrigin: http://localhost:3000\r\n{auth}Content-Type: application/json; charset=utf-8\r\n");
+        assert!(post_with(addr, "/message", &local_origin, body)
+            .await
+            .starts_with("HTTP/1.1 200"));
+
+        stop.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_request_stops_server() {
+        let dir = TempDir::new().unwrap();
+        let (addr, _stop, task) = start_server(&dir).await;
+
+        let response = post(
+            addr,
+            "/message",
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "shutdown"}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .expect("server kept running")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_inline_post_lists_tools() {
+        let dir = TempDir::new().unwrap();
+        let (addr, stop, task) = start_server(&dir).await;
+
+        let response = post(
+            addr,
+            "/message",
+            r#"{"jsonrpc": "2.0", "id": 1, "method": "tools/list"}"#,
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("kaido_list_tools"));
+
+        let missing = post(addr, "/nowhere", "").await;
+        assert!(missing.starts_with("HTTP/1.1 404"));
+
+        stop.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sse_session_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let (addr, stop, task) = start_server(&dir).await;
+
+        let mut sse = TcpStream::connect(addr).await.unwrap();
+        sse.write_all(
+            format!(
+                "GET /sse HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {TOKEN}\r\n\r\n"
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+        let mut sse = BufReader::new(sse);
+
+        let endpoint = next_event(&mut sse, "endpoint").await;
+        assert!(endpoint.starts_with("/message?sessionId="));
+
+        let ack = post(
+            addr,
+            &endpoint,
+            r#"{"jsonrpc": "2.0", "id": 2, "method": "ping"}"#,
+        )
+        .await;
+        assert!(ack.starts_with("HTTP/1.1 202"));
+
+        let message = next_event(&mut sse, "message").await;
+        let response: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(response["id"], 2);
+
+        let unknown = post(addr, "/message?sessionId=nope", "{}").await;
+        assert!(unknown.starts_with("HTTP/1.1 404"));
+
+        // Shutdown closes the open stream and the server returns
+        stop.send(()).unwrap();
+        task.await.unwrap().unwrap();
+        let mut rest = String::new();
+        sse.read_to_string(&mut rest).await.unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 // MCP (Model Context Protocol) Server Module
 //
 // Exposes Kaido tools via MCP for integration with Claude Code
-// and other MCP-compatible clients.
+// and other MCP-compatible clients, over stdio or HTTP/SSE.
 //
 // Tools exposed:
 // - kaido_diagnose: AI-powered problem diagnosis
@@ -10,11 +10,15 @@
 // - kaido_get_context: System context information
 // - kaido_list_tools: Available tools listing
 // - kaido_check_risk: Command risk assessment
+// - kaido_recent_history: Recently executed commands from the audit log
+// - kaido_apply_solution: List the known fixes for an error and run one
 
+pub mod http;
 pub mod server;
 pub mod tools;
 pub mod types;
 
-pub use server::McpServer;
+pub use http::McpHttpServer;
+pub use server::{McpHandler, McpServer, McpSession};
 pub use tools::KaidoTools;
 pub use types::*;
@@ -1,71 +1,86 @@
 // MCP Server Implementation
-// Handles JSON-RPC 2.0 communication over stdio
+// Handles JSON-RPC 2.0 communication over stdio; the request handling is
+// shared with the HTTP/SSE transport in `http.rs`
 
 use super::tools::KaidoTools;
 use super::types::*;
 use serde_json::{json, Value};
 use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use tokio::runtime::Runtime;
 
-/// MCP Server for Kaido
-pub struct McpServer {
+/// What the handler knows about one connected client
+///
+/// The stdio server has a single client; the HTTP transport keeps one per
+/// SSE stream, so concurrent clients are audited under their own names.
+#[derive(Debug, Default)]
+pub struct McpSession {
+    /// Client name from `initialize`, for the audit log
+    client_name: Mutex<Option<String>>,
+}
+
+impl McpSession {
+    /// Name the client gave in `initialize`
+    pub fn client_name(&self) -> Option<String> {
+        self.client_name.lock().unwrap().clone()
+    }
+}
+
+/// Transport-independent JSON-RPC request handling
+///
+/// Shared by the stdio server and the HTTP/SSE transport. Methods take
+/// `&self` so one handler can serve concurrent requests.
+pub struct McpHandler {
     tools: KaidoTools,
-    initialized: bool,
-    runtime: Runtime,
+    initialized: AtomicBool,
+    shutdown_requested: AtomicBool,
+    /// The stdio client, used by [`handle_message`](Self::handle_message)
+    session: McpSession,
 }
 
-impl McpServer {
-    /// Create a new MCP server
+impl McpHandler {
+    /// Create a handler exposing the default Kaido tools
     pub fn new() -> Self {
-        let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+        Self::with_tools(KaidoTools::new())
+    }
+
+    /// Create a handler exposing the given tools
+    pub fn with_tools(tools: KaidoTools) -> Self {
         Self {
-            tools: KaidoTools::new(),
-            initialized: false,
-            runtime,
+            tools,
+            initialized: AtomicBool::new(false),
+            shutdown_requested: AtomicBool::new(false),
+            session: McpSession::default(),
         }
     }
 
-    /// Run the server, processing stdin and writing to stdout
-    pub fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let stdin = std::io::stdin();
-        let mut stdout = std::io::stdout();
-        let reader = BufReader::new(stdin.lock());
-
-        eprintln!("[kaido-mcp] Server started, waiting for requests...");
-
-        for line in reader.lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(e) => {
-                    eprintln!("[kaido-mcp] Error reading input: {e}");
-                    continue;
-                }
-            };
-
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            eprintln!("[kaido-mcp] Received: {}", &line[..line.len().min(100)]);
+    /// Whether a client has sent `initialize`
+    pub fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
 
-            let response = self.handle_message(&line);
+    /// Name the stdio client gave in `initialize`
+    pub fn client_name(&self) -> Option<String> {
+        self.session.client_name()
+    }
 
-            if let Some(resp) = response {
-                let json_str = serde_json::to_string(&resp)?;
-                eprintln!(
-                    "[kaido-mcp] Sending: {}",
-                    &json_str[..json_str.len().min(100)]
-                );
-                writeln!(stdout, "{json_str}")?;
-                stdout.flush()?;
-            }
-        }
+    /// Whether a client has sent `shutdown`
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested.load(Ordering::SeqCst)
+    }
 
-        Ok(())
+    /// Handle a single JSON-RPC message from the stdio client
+    pub async fn handle_message(&self, message: &str) -> Option<JsonRpcResponse> {
+        self.handle_session_message(message, &self.session).await
     }
 
-    /// Handle a single JSON-RPC message
-    fn handle_message(&mut self, message: &str) -> Option<JsonRpcResponse> {
+    /// Handle a single JSON-RPC message from the client of `session`
+    pub async fn handle_session_message(
+        &self,
+        message: &str,
+        session: &McpSession,
+    ) -> Option<JsonRpcResponse> {
         // Parse JSON
         let request: JsonRpcRequest = match serde_json::from_str(message) {
             Ok(req) => req,
@@ -78,7 +93,7 @@ impl McpServer {
         };
 
         // Route to handler
-        let result = self.handle_request(&request);
+        let result = self.handle_request(&request, session).await;
 
         // Build response
         Some(match result {
@@ -88,33 +103,43 @@ impl McpServer {
     }
 
     /// Handle a parsed request
-    fn handle_request(&mut self, request: &JsonRpcRequest) -> Result<Value, JsonRpcError> {
+    async fn handle_request(
+        &self,
+        request: &JsonRpcRequest,
+        session: &McpSession,
+    ) -> Result<Value, JsonRpcError> {
         match request.method.as_str() {
-            "initialize" => self.handle_initialize(&request.params),
+            "initialize" => self.handle_initialize(&request.params, session),
             "initialized" => Ok(json!({})),
             "tools/list" => self.handle_tools_list(),
-            "tools/call" => self.handle_tool_call(&request.params),
+            "tools/call" => self.handle_tool_call(&request.params, session).await,
             "ping" => Ok(json!({})),
             "shutdown" => {
                 eprintln!("[kaido-mcp] Shutdown requested");
-                std::process::exit(0);
+                self.shutdown_requested.store(true, Ordering::SeqCst);
+                Ok(json!({}))
             }
             method => Err(JsonRpcError::method_not_found(method)),
         }
     }
 
     /// Handle initialize request
-    fn handle_initialize(&mut self, params: &Option<Value>) -> Result<Value, JsonRpcError> {
+    fn handle_initialize(
+        &self,
+        params: &Option<Value>,
+        session: &McpSession,
+    ) -> Result<Value, JsonRpcError> {
         if let Some(p) = params {
             if let Ok(init_params) = serde_json::from_value::<InitializeParams>(p.clone()) {
                 eprintln!(
                     "[kaido-mcp] Client: {} v{}",
                     init_params.client_info.name, init_params.client_info.version
                 );
+                *session.client_name.lock().unwrap() = Some(init_params.client_info.name);
             }
         }
 
-        self.initialized = true;
+        self.initialized.store(true, Ordering::SeqCst);
 
         let result = InitializeResult {
             protocol_version: "2024-11-05".to_string(),
@@ -141,7 +166,11 @@ impl McpServer {
     }
 
     /// Handle tools/call request
-    fn handle_tool_call(&self, params: &Option<Value>) -> Result<Value, JsonRpcError> {
+    async fn handle_tool_call(
+        &self,
+        params: &Option<Value>,
+        session: &McpSession,
+    ) -> Result<Value, JsonRpcError> {
         let params = params
             .as_ref()
             .ok_or_else(|| JsonRpcError::invalid_params("Missing params"))?;
@@ -154,17 +183,82 @@ impl McpServer {
             call_params.name, call_params.arguments
         );
 
-        // Execute tool call in async context
-        let result = self.runtime.block_on(async {
-            self.tools
-                .call(&call_params.name, &call_params.arguments)
-                .await
-        });
+        let client = session.client_name();
+        let result = self
+            .tools
+            .call(&call_params.name, &call_params.arguments, client.as_deref())
+            .await;
 
         serde_json::to_value(result).map_err(|e| JsonRpcError::internal_error(&e.to_string()))
     }
 }
 
+impl Default for McpHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// MCP Server for Kaido (stdio transport)
+pub struct McpServer {
+    handler: McpHandler,
+    runtime: Runtime,
+}
+
+impl McpServer {
+    /// Create a new MCP server
+    pub fn new() -> Self {
+        let runtime = Runtime::new().expect("Failed to create Tokio runtime");
+        Self {
+            handler: McpHandler::new(),
+            runtime,
+        }
+    }
+
+    /// Run the server, processing stdin and writing to stdout
+    pub fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let stdin = std::io::stdin();
+        let mut stdout = std::io::stdout();
+        let reader = BufReader::new(stdin.lock());
+
+        eprintln!("[kaido-mcp] Server started, waiting for requests...");
+
+        for line in reader.lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("[kaido-mcp] Error reading input: {e}");
+                    continue;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            eprintln!("[kaido-mcp] Received: {}", &line[..line.len().min(100)]);
+
+            let response = self.runtime.block_on(self.handler.handle_message(&line));
+
+            if let Some(resp) = response {
+                let json_str = serde_json::to_string(&resp)?;
+                eprintln!(
+                    "[kaido-mcp] Sending: {}",
+                    &json_str[..json_str.len().min(100)]
+                );
+                writeln!(stdout, "{json_str}")?;
+                stdout.flush()?;
+            }
+
+            if self.handler.shutdown_requested() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl Default for McpServer {
     fn default() -> Self {
         Self::new()
@@ -178,12 +272,12 @@ mod tests {
     #[test]
     fn test_server_creation() {
         let server = McpServer::new();
-        assert!(!server.initialized);
+        assert!(!server.handler.is_initialized());
     }
 
     #[test]
     fn test_handle_initialize() {
-        let mut server = McpServer::new();
+        let handler = McpHandler::new();
 
         let params = json!({
             "protocolVersion": "2024-11-05",
@@ -194,9 +288,10 @@ mod tests {
             }
         });
 
-        let result = server.handle_initialize(&Some(params));
+        let result = handler.handle_initialize(&Some(params), &handler.session);
         assert!(result.is_ok());
-        assert!(server.initialized);
+        assert!(handler.is_initialized());
+        assert_eq!(handler.client_name().as_deref(), Some("test-client"));
 
         let value = result.unwrap();
         assert_eq!(value["serverInfo"]["name"], "kaido-mcp");
@@ -204,8 +299,8 @@ mod tests {
 
     #[test]
     fn test_handle_tools_list() {
-        let server = McpServer::new();
-        let result = server.handle_tools_list();
+        let handler = McpHandler::new();
+        let result = handler.handle_tools_list();
 
         assert!(result.is_ok());
         let value = result.unwrap();
@@ -213,9 +308,9 @@ mod tests {
         assert!(!value["tools"].as_array().unwrap().is_empty());
     }
 
-    #[test]
-    fn test_handle_unknown_method() {
-        let mut server = McpServer::new();
+    #[tokio::test]
+    async fn test_handle_unknown_method() {
+        let handler = McpHandler::new();
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
             id: Some(json!(1)),
@@ -223,27 +318,72 @@ mod tests {
             params: None,
         };
 
-        let result = server.handle_request(&request);
+        let result = handler
+            .handle_request(&request, &McpSession::default())
+            .await;
         assert!(result.is_err());
 
         let error = result.unwrap_err();
         assert_eq!(error.code, -32601); // Method not found
     }
 
-    #[test]
-    fn test_handle_tool_call() {
-        let server = McpServer::new();
+    #[tokio::test]
+    async fn test_handle_tool_call() {
+        let handler = McpHandler::new();
 
         let params = json!({
             "name": "kaido_list_tools",
             "arguments": {}
         });
 
-        let result = server.handle_tool_call(&Some(params));
+        let result = handler
+            .handle_tool_call(&Some(params), &McpSession::default())
+            .await;
         assert!(result.is_ok());
 
         let value = result.unwrap();
         assert!(value["content"].is_array());
         assert!(!value["isError"].as_bool().unwrap_or(true));
     }
+
+    #[tokio::test]
+    async fn test_sessions_keep_their_own_client_name() {
+        let handler = McpHandler::new();
+        let (first, second) = (McpSession::default(), McpSession::default());
+        let initialize = |name: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": {"name": name, "version": "1.0.0"}
+                }
+            })
+            .to_string()
+        };
+
+        handler
+            .handle_session_message(&initialize("claude-desktop"), &first)
+            .await;
+        handler
+            .handle_session_message(&initialize("cursor"), &second)
+            .await;
+        assert_eq!(first.client_name().as_deref(), Some("claude-desktop"));
+        assert_eq!(second.client_name().as_deref(), Some("cursor"));
+        assert_eq!(handler.client_name(), None);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_is_acknowledged() {
+        let handler = McpHandler::new();
+        let response = handler
+            .handle_message(r#"{"jsonrpc": "2.0", "id": 7, "method": "shutdown"}"#)
+            .await
+            .unwrap();
+
+        assert!(response.error.is_none());
+        assert!(handler.shutdown_requested());
+    }
 }
@@ -2,24 +2,138 @@
 // Exposes Kaido capabilities as MCP tools for Claude Code integration
 
 use super::types::{ToolCallResult, ToolDefinition};
-use crate::ai::CommandExplainer;
-use crate::kubectl::EnvironmentType;
-use crate::tools::{RiskLevel, ToolContext, ToolRegistry};
+use crate::ai::{CommandExplainer, OutputBudget};
+use crate::audit::logger::AuditLogger;
+use crate::audit::query::{parse_since, AuditQuery};
+use crate::audit::{audit_entry_cancelled, audit_entry_from_execution, AuditContext, UserAction};
+use crate::commands::engine::convert_risk_level;
+use crate::config::Config;
+use crate::error::solutions::runnable_command;
+use crate::error::SolutionRunner;
+use crate::kubectl::{EnvironmentType, ExecutionResult, KubectlContext};
+use crate::tools::{
+    command_line, ExecutionResult as CapturedOutput, RiskLevel, SQLTool, ToolContext, ToolRegistry,
+    DEFAULT_MAX_CAPTURE_BYTES,
+};
+use regex::Regex;
 use serde_json::{json, Value};
-use std::process::Command;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Instant;
+use tokio::process::Command;
+
+/// Default number of entries returned by kaido_recent_history
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+
+/// Upper bound on entries returned by kaido_recent_history
+const MAX_HISTORY_LIMIT: usize = 200;
+
+/// Lines of pod/container logs fetched by kaido_diagnose
+const LOG_TAIL_LINES: usize = 50;
+
+/// Log output kept per log command; the newest lines matter most
+const LOG_BUDGET: OutputBudget = OutputBudget::new(400, 1600);
+
+/// A command kaido_diagnose runs, and how much of its output to keep
+struct DiagnosticCommand {
+    title: &'static str,
+    command: String,
+    budget: OutputBudget,
+}
+
+/// Pod, namespace and container named in a problem description
+#[derive(Debug, Default, PartialEq, Eq)]
+struct NamedResources {
+    pod: Option<String>,
+    namespace: Option<String>,
+    container: Option<String>,
+}
+
+impl NamedResources {
+    /// Pull resource names out of text like "pod api-7d9f8 in namespace prod"
+    ///
+    /// Bare pod and container names only count if they contain a digit or
+    /// hyphen (`api-7d9f8`, `redis1`) or are quoted, so "the pod keeps
+    /// crashing" doesn't yield a pod called "keeps". Namespaces are often
+    /// plain words (`prod`), so only obvious filler is rejected there.
+    fn from_problem(problem: &str) -> Self {
+        const NOT_NAMESPACES: [&str; 8] = ["is", "was", "has", "the", "a", "an", "and", "not"];
+
+        static PATTERNS: OnceLock<[Regex; 3]> = OnceLock::new();
+        let [pod, namespace, container] = PATTERNS.get_or_init(|| {
+            let name = r#"(?:["'`]([a-z0-9][a-z0-9_.-]*)["'`]|([a-z0-9][a-z0-9_.-]*))"#;
+            [
+                Regex::new(&format!(r"(?i)\bpods?[\s/]+{name}")).unwrap(),
+                Regex::new(&format!(r"(?i)(?:\b(?:namespace|ns)|(?:^|\s)-n)\s+{name}")).unwrap(),
+                Regex::new(&format!(r"(?i)\bcontainers?\s+{name}")).unwrap(),
+            ]
+        });
+
+        let find = |regex: &Regex, accept_bare: fn(&str) -> bool| {
+            regex.captures_iter(problem).find_map(|caps| {
+                if let Some(quoted) = caps.get(1) {
+                    return Some(quoted.as_str().to_string());
+                }
+                let bare = caps.get(2)?.as_str();
+                accept_bare(bare).then(|| bare.to_string())
+            })
+        };
+        let looks_generated = |name: &str| name.contains(|c: char| c == '-' || c.is_ascii_digit());
+
+        Self {
+            pod: find(pod, looks_generated),
+            namespace: find(namespace, |name| {
+                !NOT_NAMESPACES.contains(&name.to_lowercase().as_str())
+            }),
+            container: find(container, looks_generated),
+        }
+    }
+}
 
 /// Kaido MCP tool handler
 pub struct KaidoTools {
     registry: ToolRegistry,
+    audit_db_path: PathBuf,
+    /// Opened on first audited call; None if the database can't be opened
+    audit_logger: OnceLock<Option<AuditLogger>>,
+    /// How much of each diagnostic command's output kaido_diagnose returns
+    diagnose_budget: OutputBudget,
+    /// Bytes of each output stream kept from a command; the tail is kept
+    capture_bytes: usize,
 }
 
 impl KaidoTools {
     pub fn new() -> Self {
+        let config = Config::load().unwrap_or_default();
+        crate::tools::set_max_capture_bytes(config.truncation.capture_bytes);
+        Self::with_audit_db(config.audit.database_path)
+            .with_diagnose_budget(config.truncation.mcp)
+            .with_capture_bytes(config.truncation.capture_bytes)
+    }
+
+    /// Create a tool handler using a specific audit database
+    pub fn with_audit_db(audit_db_path: impl Into<PathBuf>) -> Self {
         Self {
             registry: ToolRegistry::new(),
+            audit_db_path: audit_db_path.into(),
+            audit_logger: OnceLock::new(),
+            diagnose_budget: OutputBudget::MCP,
+            capture_bytes: DEFAULT_MAX_CAPTURE_BYTES,
         }
     }
 
+    /// Set how much diagnostic output kaido_diagnose returns per command
+    pub fn with_diagnose_budget(mut self, budget: OutputBudget) -> Self {
+        self.diagnose_budget = budget;
+        self
+    }
+
+    /// Set how many bytes of each output stream a command may return
+    pub fn with_capture_bytes(mut self, bytes: usize) -> Self {
+        self.capture_bytes = bytes.max(1);
+        self
+    }
+
     /// Get all tool definitions
     pub fn get_definitions(&self) -> Vec<ToolDefinition> {
         vec![
@@ -33,7 +147,7 @@ impl KaidoTools {
                     "properties": {
                         "problem": {
                             "type": "string",
-                            "description": "Description of the problem to diagnose (e.g., 'nginx is returning 502', 'pod keeps crashing')"
+                            "description": "Description of the problem to diagnose (e.g., 'nginx is returning 502', 'pod api-7d9f8 keeps crashing'). Name the pod or container to include its recent logs"
                         }
                     },
                     "required": ["problem"]
@@ -103,27 +217,82 @@ impl KaidoTools {
                         "command": {
                             "type": "string",
                             "description": "The command to assess"
+                        }
+                    },
+                    "required": ["command"]
+                }),
+            },
+            ToolDefinition {
+                name: "kaido_recent_history".to_string(),
+                description: "Get recently executed commands from the Kaido audit log, newest first. \
+                              Includes timestamps, risk levels, and outcomes so you can reason about \
+                              what has already been tried in this session.".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "limit": {
+                            "type": "integer",
+                            "description": "Maximum number of commands to return (default 20, max 200)"
                         },
-                        "tool": {
+                        "since": {
                             "type": "string",
-                            "description": "Tool name (optional, auto-detected if not provided)"
+                            "description": "Only include commands at or after this point: a relative duration (e.g., '30m', '2h', '7d'), an RFC 3339 timestamp, or a date (YYYY-MM-DD)"
                         }
                     },
-                    "required": ["command"]
+                    "required": []
+                }),
+            },
+            ToolDefinition {
+                name: "kaido_apply_solution".to_string(),
+                description: "Look up Kaido's known fixes for an error and run one. Without `solution`, \
+                              lists the numbered fixes with their risk levels and the recommended one. \
+                              With `solution`, runs that fix after the same risk checks as kaido_execute; \
+                              HIGH risk fixes also need `confirm: true`.".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {
+                        "error": {
+                            "type": "string",
+                            "description": "The error output to fix"
+                        },
+                        "command": {
+                            "type": "string",
+                            "description": "The command that failed (optional, picks the tool that explains the error)"
+                        },
+                        "solution": {
+                            "type": "integer",
+                            "description": "Number of the fix to run, as listed (1-based)"
+                        },
+                        "confirm": {
+                            "type": "boolean",
+                            "description": "Confirm running a HIGH risk fix"
+                        }
+                    },
+                    "required": ["error"]
                 }),
             },
         ]
     }
 
     /// Handle a tool call
-    pub async fn call(&self, name: &str, arguments: &Value) -> ToolCallResult {
+    ///
+    /// `client` is the MCP client name from `initialize`, recorded in the
+    /// audit log for executed and risk-checked commands.
+    pub async fn call(
+        &self,
+        name: &str,
+        arguments: &Value,
+        client: Option<&str>,
+    ) -> ToolCallResult {
         match name {
             "kaido_diagnose" => self.diagnose(arguments).await,
-            "kaido_execute" => self.execute(arguments).await,
+            "kaido_execute" => self.execute(arguments, client).await,
             "kaido_explain" => self.explain(arguments).await,
             "kaido_get_context" => self.get_context().await,
             "kaido_list_tools" => self.list_tools(),
-            "kaido_check_risk" => self.check_risk(arguments),
+            "kaido_check_risk" => self.check_risk(arguments, client),
+            "kaido_recent_history" => self.recent_history(arguments),
+            "kaido_apply_solution" => self.apply_solution(arguments, client).await,
             _ => ToolCallResult::error(format!("Unknown tool: {name}")),
         }
     }
@@ -154,18 +323,13 @@ impl KaidoTools {
         if !diagnostics.is_empty() {
             diagnosis.push_str("## Diagnostic Results\n\n");
 
-            for (cmd_name, cmd) in diagnostics {
-                diagnosis.push_str(&format!("### {cmd_name}\n"));
-                diagnosis.push_str(&format!("```\n$ {cmd}\n"));
+            for diagnostic in diagnostics {
+                diagnosis.push_str(&format!("### {}\n", diagnostic.title));
+                diagnosis.push_str(&format!("```\n$ {}\n", diagnostic.command));
 
-                match self.run_command(&cmd) {
+                match self.run_command(&diagnostic.command).await {
                     Ok(output) => {
-                        let truncated = if output.len() > 2000 {
-                            format!("{}...\n(truncated)", &output[..2000])
-                        } else {
-                            output
-                        };
-                        diagnosis.push_str(&truncated);
+                        diagnosis.push_str(&diagnostic.budget.truncate(&output));
                     }
                     Err(e) => {
                         diagnosis.push_str(&format!("Error: {e}"));
@@ -183,7 +347,7 @@ impl KaidoTools {
     }
 
     /// Execute a command
-    async fn execute(&self, arguments: &Value) -> ToolCallResult {
+    async fn execute(&self, arguments: &Value, client: Option<&str>) -> ToolCallResult {
         let command = arguments
             .get("command")
             .and_then(|v| v.as_str())
@@ -203,9 +367,29 @@ impl KaidoTools {
         }
 
         // Check risk level first
-        let risk = self.assess_risk(command, tool_name);
+        let risk = self.assess_risk(command);
+        self.run_assessed(command, risk, client).await
+    }
+
+    /// Run a command whose risk is known, unless it must not be automated
+    async fn run_assessed(
+        &self,
+        command: &str,
+        risk: RiskLevel,
+        client: Option<&str>,
+    ) -> ToolCallResult {
+        // Whichever tool was detected, a dropped database stays dropped
+        if SQLTool::is_never_automated(command) {
+            self.audit(command, risk, None, UserAction::McpBlocked, client);
+            return ToolCallResult::error(format!(
+                "Command drops or truncates database data and cannot be auto-executed.\n\
+                 Command: {command}\n\n\
+                 Run it manually after checking which database it targets."
+            ));
+        }
 
         if matches!(risk, RiskLevel::Critical) {
+            self.audit(command, risk, None, UserAction::McpBlocked, client);
             return ToolCallResult::error(format!(
                 "Command has CRITICAL risk level and cannot be auto-executed.\n\
                  Command: {command}\n\n\
@@ -215,23 +399,123 @@ impl KaidoTools {
         }
 
         // Execute the command
-        match self.run_command(command) {
+        let started = Instant::now();
+        let output = self.command_output(command).await;
+        let execution = match &output {
+            Ok(output) => ExecutionResult::new(
+                Some(output.exit_code),
+                output.stdout.clone(),
+                output.stderr.clone(),
+                started.elapsed().as_millis() as i64,
+            ),
+            Err(e) => ExecutionResult::new(
+                None,
+                String::new(),
+                e.clone(),
+                started.elapsed().as_millis() as i64,
+            ),
+        };
+        self.audit(
+            command,
+            risk,
+            Some(&execution),
+            UserAction::McpExecuted,
+            client,
+        );
+
+        match output {
             Ok(output) => {
-                let result = format!(
+                let combined = combined_output(&output);
+                let mut result = format!(
                     "$ {}\n\n{}",
                     command,
-                    if output.is_empty() {
+                    if combined.is_empty() {
                         "(no output)"
                     } else {
-                        &output
+                        &combined
                     }
                 );
+                if output.truncated {
+                    result.push_str(&format!(
+                        "\n\n(output truncated: only the last {} bytes of each stream were kept)",
+                        self.capture_bytes
+                    ));
+                }
                 ToolCallResult::success(result)
             }
             Err(e) => ToolCallResult::error(format!("Execution failed: {e}")),
         }
     }
 
+    /// List the known fixes for an error, or run one
+    async fn apply_solution(&self, arguments: &Value, client: Option<&str>) -> ToolCallResult {
+        let error = arguments
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if error.is_empty() {
+            return ToolCallResult::error("Missing required parameter: error");
+        }
+        let command = arguments
+            .get("command")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        let runner = SolutionRunner::new(&self.registry);
+        let Some(explanation) = runner.explain(command, error) else {
+            return ToolCallResult::error(
+                "No known fix for this error. Use kaido_diagnose to investigate it.",
+            );
+        };
+
+        let Some(number) = arguments.get("solution").and_then(|v| v.as_u64()) else {
+            let mut output = format!(
+                "# {}\n\n{}\n\n## Fixes\n\n",
+                explanation.error_type, explanation.reason
+            );
+            for (index, solution) in explanation.solutions.iter().enumerate() {
+                let recommended = if index == explanation.recommended_solution {
+                    " (recommended)"
+                } else {
+                    ""
+                };
+                output.push_str(&format!(
+                    "{}. {}{recommended} [{}]\n",
+                    index + 1,
+                    solution.description,
+                    runner.risk(solution).as_str()
+                ));
+                match runnable_command(solution) {
+                    Some(command) => output.push_str(&format!("   `{command}`\n")),
+                    None => output.push_str("   (manual step, cannot be applied)\n"),
+                }
+            }
+            output.push_str("\nCall again with `solution` set to the number to run.\n");
+            return ToolCallResult::success(output);
+        };
+
+        let solution = match SolutionRunner::choose(&explanation, Some(number as usize)) {
+            Ok(solution) => solution,
+            Err(e) => return ToolCallResult::error(e.to_string()),
+        };
+        let Some(fix) = runnable_command(solution) else {
+            return ToolCallResult::error("Solution has no command to run");
+        };
+        let risk = runner.risk(solution);
+        let confirmed = arguments
+            .get("confirm")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if risk == RiskLevel::High && !confirmed {
+            return ToolCallResult::error(format!(
+                "Fix has HIGH risk level: {fix}\n\n\
+                 Call again with `confirm: true` to run it."
+            ));
+        }
+
+        self.run_assessed(fix, risk, client).await
+    }
+
     /// Explain a command
     async fn explain(&self, arguments: &Value) -> ToolCallResult {
         let command = arguments
@@ -259,15 +543,18 @@ impl KaidoTools {
 
         // Kubernetes context
         context.push_str("## Kubernetes\n");
-        if let Ok(output) = self.run_command("kubectl config current-context") {
+        if let Ok(output) = self.run_command("kubectl config current-context").await {
             context.push_str(&format!("- Current Context: `{}`\n", output.trim()));
         } else {
             context.push_str("- Kubernetes: Not configured or kubectl not found\n");
         }
 
-        if let Ok(output) = self.run_command(
-            "kubectl config view --minify -o jsonpath='{.contexts[0].context.namespace}'",
-        ) {
+        if let Ok(output) = self
+            .run_command(
+                "kubectl config view --minify -o jsonpath='{.contexts[0].context.namespace}'",
+            )
+            .await
+        {
             let ns = output.trim().trim_matches('\'');
             if !ns.is_empty() {
                 context.push_str(&format!("- Default Namespace: `{ns}`\n"));
@@ -276,14 +563,20 @@ impl KaidoTools {
 
         // Docker status
         context.push_str("\n## Docker\n");
-        if let Ok(output) = self.run_command("docker info --format '{{.ServerVersion}}'") {
+        if let Ok(output) = self
+            .run_command("docker info --format '{{.ServerVersion}}'")
+            .await
+        {
             context.push_str(&format!(
                 "- Docker Version: `{}`\n",
                 output.trim().trim_matches('\'')
             ));
 
-            if let Ok(containers) = self.run_command("docker ps -q | wc -l") {
-                context.push_str(&format!("- Running Containers: {}\n", containers.trim()));
+            if let Ok(containers) = self.run_command("docker ps -q").await {
+                context.push_str(&format!(
+                    "- Running Containers: {}\n",
+                    containers.lines().filter(|l| !l.trim().is_empty()).count()
+                ));
             }
         } else {
             context.push_str("- Docker: Not running or not installed\n");
@@ -346,11 +639,19 @@ impl KaidoTools {
             ),
         ];
 
+        let availability = self.registry.availability(true);
         for (name, desc) in tools_info {
-            let available = self.registry.get_tool(name).is_some();
-            let status = if available { "available" } else { "registered" };
+            let installed = availability.iter().find(|a| a.name == name);
+            let status = match installed {
+                Some(a) if a.is_available() => "available",
+                Some(_) => "not installed",
+                None => "not registered",
+            };
             output.push_str(&format!("## {name}\n"));
             output.push_str(&format!("- **Status:** {status}\n"));
+            if let Some(version) = installed.and_then(|a| a.version.as_deref()) {
+                output.push_str(&format!("- **Version:** {version}\n"));
+            }
             output.push_str(&format!("- **Description:** {desc}\n\n"));
         }
 
@@ -358,7 +659,7 @@ impl KaidoTools {
     }
 
     /// Check risk level of a command
-    fn check_risk(&self, arguments: &Value) -> ToolCallResult {
+    fn check_risk(&self, arguments: &Value, client: Option<&str>) -> ToolCallResult {
         let command = arguments
             .get("command")
             .and_then(|v| v.as_str())
@@ -368,10 +669,9 @@ impl KaidoTools {
             return ToolCallResult::error("Missing required parameter: command");
         }
 
-        let tool_name = arguments.get("tool").and_then(|v| v.as_str());
-
-        let risk = self.assess_risk(command, tool_name);
-        let ctx = ToolContext::default();
+        let risk = self.assess_risk(command);
+        self.audit(command, risk, None, UserAction::McpRiskChecked, client);
+        let ctx = ToolContext::detect();
 
         let mut output = String::new();
         output.push_str("# Risk Assessment\n\n");
@@ -421,63 +721,208 @@ impl KaidoTools {
         ToolCallResult::success(output)
     }
 
+    /// Get recent command history from the audit log
+    fn recent_history(&self, arguments: &Value) -> ToolCallResult {
+        let limit = arguments
+            .get("limit")
+            .and_then(|v| v.as_u64())
+            .map(|n| (n as usize).clamp(1, MAX_HISTORY_LIMIT))
+            .unwrap_or(DEFAULT_HISTORY_LIMIT);
+
+        let since = match arguments.get("since").and_then(|v| v.as_str()) {
+            Some(raw) => match parse_since(raw, AuditLogger::current_timestamp()) {
+                Some(ts) => Some(ts),
+                None => {
+                    return ToolCallResult::error(format!(
+                        "Invalid 'since' value: {raw}. Use a duration like '2h' or '7d', \
+                         an RFC 3339 timestamp, or a date (YYYY-MM-DD)."
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        if !self.audit_db_path.exists() {
+            return ToolCallResult::success(
+                "# Recent Command History\n\nNo commands recorded yet.",
+            );
+        }
+
+        let results = AuditQuery::new(&self.audit_db_path.to_string_lossy())
+            .and_then(|query| query.query_recent(limit, since));
+
+        let results = match results {
+            Ok(r) => r,
+            Err(e) => return ToolCallResult::error(format!("Failed to read audit log: {e}")),
+        };
+
+        let mut output = String::new();
+        output.push_str("# Recent Command History\n\n");
+
+        if results.is_empty() {
+            output.push_str("No commands found.\n");
+            return ToolCallResult::success(output);
+        }
+
+        for entry in &results {
+            let outcome = match (entry.user_action.as_str(), entry.exit_code) {
+                ("CANCELLED", _) => "cancelled".to_string(),
+                (_, Some(0)) => "succeeded".to_string(),
+                (_, Some(code)) => format!("failed (exit {code})"),
+                (_, None) => "no exit code".to_string(),
+            };
+            output.push_str(&format!(
+                "- `{}` — `{}`\n  - **Risk:** {} | **Environment:** {} | **Outcome:** {}\n",
+                entry.executed_at,
+                entry.kubectl_command,
+                entry.risk_level,
+                entry.environment,
+                outcome
+            ));
+        }
+
+        output.push_str(&format!("\nTotal: {} commands\n", results.len()));
+
+        ToolCallResult::success(output)
+    }
+
     // Helper methods
 
-    fn run_command(&self, command: &str) -> Result<String, String> {
+    async fn run_command(&self, command: &str) -> Result<String, String> {
+        self.command_output(command)
+            .await
+            .map(|output| combined_output(&output))
+    }
+
+    /// Run a single command directly, without a shell
+    ///
+    /// Pipes, chains and redirects are refused rather than passed to the
+    /// program as literal arguments. Only the tail of each output stream is
+    /// kept, up to the capture limit.
+    async fn command_output(&self, command: &str) -> Result<CapturedOutput, String> {
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
             return Err("Empty command".to_string());
         }
+        if command_line::segments(command).len() > 1
+            || command_line::has_substitution(command)
+            || parts.iter().any(|part| part.contains(['<', '>']))
+        {
+            return Err(
+                "Pipes, command chains and redirects aren't supported; run one command at a time"
+                    .to_string(),
+            );
+        }
 
-        let output = Command::new(parts[0])
-            .args(&parts[1..])
-            .output()
-            .map_err(|e| format!("Failed to execute: {e}"))?;
+        let mut process = Command::new(parts[0]);
+        process.args(&parts[1..]);
+        CapturedOutput::capture_with_limit(&mut process, self.capture_bytes)
+            .await
+            .map_err(|e| format!("Failed to execute: {e}"))
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+    /// Audit log, opened on first use
+    fn audit_logger(&self) -> Option<&AuditLogger> {
+        self.audit_logger
+            .get_or_init(|| {
+                if let Some(parent) = self.audit_db_path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                AuditLogger::new(&self.audit_db_path.to_string_lossy())
+                    .map_err(|e| log::warn!("MCP audit logging disabled: {e}"))
+                    .ok()
+            })
+            .as_ref()
+    }
 
-        if output.status.success() {
-            Ok(stdout.to_string())
-        } else if !stderr.is_empty() {
-            Ok(format!("{stdout}\n{stderr}"))
-        } else {
-            Ok(stdout.to_string())
+    /// Record a command requested by an MCP client in the audit log
+    ///
+    /// `execution` is None when the command was not run. Failures are only
+    /// logged so auditing never breaks a tool call.
+    fn audit(
+        &self,
+        command: &str,
+        risk: RiskLevel,
+        execution: Option<&ExecutionResult>,
+        action: UserAction,
+        client: Option<&str>,
+    ) {
+        let Some(logger) = self.audit_logger() else {
+            return;
+        };
+
+        let is_kubectl = self
+            .registry
+            .tool_for_command(command)
+            .is_some_and(|tool| tool.name() == "kubectl");
+        let kubectl_ctx = is_kubectl.then(|| KubectlContext::current().ok()).flatten();
+        let (environment, cluster, namespace) = match &kubectl_ctx {
+            Some(ctx) => (
+                ctx.name.as_str(),
+                ctx.cluster.as_str(),
+                ctx.namespace.as_deref(),
+            ),
+            None => ("unknown", "unknown", None),
+        };
+        let risk_level = convert_risk_level(risk);
+
+        let mut entry = match execution {
+            Some(execution) => audit_entry_from_execution(
+                AuditContext {
+                    natural_language: "",
+                    kubectl_command: command,
+                    confidence_score: None,
+                    risk_level,
+                    environment,
+                    cluster,
+                    namespace,
+                },
+                execution,
+                action,
+            ),
+            None => audit_entry_cancelled(
+                "",
+                command,
+                None,
+                risk_level,
+                environment,
+                cluster,
+                namespace,
+            ),
+        };
+        entry.user_action = action;
+        entry.mcp_client = client.map(str::to_string);
+
+        if let Err(e) = logger.log_execution(entry) {
+            log::warn!("Failed to audit MCP command: {e}");
         }
     }
 
-    fn assess_risk(&self, command: &str, tool_name: Option<&str>) -> RiskLevel {
-        let ctx = ToolContext::default();
-
-        // Try to get tool from name or detect from command
-        let tool = tool_name
-            .and_then(|name| self.registry.get_tool(name))
-            .or_else(|| self.registry.detect_tool(command));
+    /// Risk of a command, from the programs it runs
+    ///
+    /// A client's `tool` argument never picks the classifier. Commands no
+    /// tool owns are also rated from their wording, so an unknown program
+    /// asked to delete or drop something still rates High.
+    fn assess_risk(&self, command: &str) -> RiskLevel {
+        let (tool, risk) = self.registry.classify(command, &ToolContext::detect());
+        if tool != "shell" {
+            return risk;
+        }
 
-        if let Some(t) = tool {
-            t.classify_risk(command, &ctx)
+        let cmd_lower = command.to_lowercase();
+        let worded = if cmd_lower.contains("rm ")
+            || cmd_lower.contains("delete")
+            || cmd_lower.contains("drop ")
+            || cmd_lower.contains("truncate")
+        {
+            RiskLevel::High
         } else {
-            // Default risk assessment for unknown commands
-            let cmd_lower = command.to_lowercase();
-            if cmd_lower.contains("rm ")
-                || cmd_lower.contains("delete")
-                || cmd_lower.contains("drop ")
-                || cmd_lower.contains("truncate")
-            {
-                RiskLevel::High
-            } else if cmd_lower.contains("update")
-                || cmd_lower.contains("insert")
-                || cmd_lower.contains("create")
-                || cmd_lower.contains("modify")
-            {
-                RiskLevel::Medium
-            } else {
-                RiskLevel::Low
-            }
-        }
+            RiskLevel::Low
+        };
+        risk.max(worded)
     }
 
-    fn get_diagnostic_commands(&self, problem: &str) -> Vec<(&'static str, String)> {
+    fn get_diagnostic_commands(&self, problem: &str) -> Vec<DiagnosticCommand> {
         let problem_lower = problem.to_lowercase();
         let mut commands = Vec::new();
 
@@ -524,13 +969,55 @@ impl KaidoTools {
             commands.push(("Listening Ports", "ss -tlnp".to_string()));
         }
 
+        let mut logs = Vec::new();
+        let resources = NamedResources::from_problem(problem);
+        if let Some(pod) = &resources.pod {
+            let namespace = resources
+                .namespace
+                .as_ref()
+                .map(|ns| format!(" -n {ns}"))
+                .unwrap_or_default();
+            logs.push((
+                "Pod Logs",
+                format!("kubectl logs {pod}{namespace} --tail={LOG_TAIL_LINES}"),
+            ));
+            // A crashed container's own logs are gone; the previous
+            // instance's are usually what explains the crash
+            if ["crash", "restart", "oom", "backoff"]
+                .iter()
+                .any(|word| problem_lower.contains(word))
+            {
+                logs.push((
+                    "Previous Pod Logs",
+                    format!("kubectl logs {pod}{namespace} --tail={LOG_TAIL_LINES} --previous"),
+                ));
+            }
+        }
+        if let Some(container) = &resources.container {
+            logs.push((
+                "Container Logs",
+                format!("docker logs --tail={LOG_TAIL_LINES} {container}"),
+            ));
+        }
+
         // If no specific diagnostics, provide general system info
-        if commands.is_empty() {
+        if commands.is_empty() && logs.is_empty() {
             commands.push(("System Load", "uptime".to_string()));
             commands.push(("Disk Usage", "df -h".to_string()));
         }
 
+        let with_budget = |budget: OutputBudget| {
+            move |(title, command)| DiagnosticCommand {
+                title,
+                command,
+                budget,
+            }
+        };
         commands
+            .into_iter()
+            .map(with_budget(self.diagnose_budget))
+            .chain(logs.into_iter().map(with_budget(LOG_BUDGET)))
+            .collect()
     }
 
     fn get_suggestions(&self, problem: &str) -> String {
@@ -562,6 +1049,15 @@ impl KaidoTools {
     }
 }
 
+/// stdout, followed by stderr when the command failed
+fn combined_output(output: &CapturedOutput) -> String {
+    if output.exit_code != 0 && !output.stderr.is_empty() {
+        format!("{}\n{}", output.stdout, output.stderr)
+    } else {
+        output.stdout.clone()
+    }
+}
+
 impl Default for KaidoTools {
     fn default() -> Self {
         Self::new()
@@ -578,7 +1074,7 @@ mod tests {
         let tools = KaidoTools::new();
         let definitions = tools.get_definitions();
 
-        assert_eq!(definitions.len(), 6);
+        assert_eq!(definitions.len(), 8);
 
         let names: Vec<_> = definitions.iter().map(|d| d.name.as_str()).collect();
         assert!(names.contains(&"kaido_diagnose"));
@@ -587,6 +1083,203 @@ mod tests {
         assert!(names.contains(&"kaido_get_context"));
         assert!(names.contains(&"kaido_list_tools"));
         assert!(names.contains(&"kaido_check_risk"));
+        assert!(names.contains(&"kaido_recent_history"));
+        assert!(names.contains(&"kaido_apply_solution"));
+    }
+
+    #[test]
+    fn test_recent_history() {
+        use crate::audit::logger::AuditLogEntry;
+        use crate::audit::UserAction;
+        use tempfile::NamedTempFile;
+
+        let temp_db = NamedTempFile::new().unwrap();
+        let logger = AuditLogger::new(temp_db.path().to_str().unwrap()).unwrap();
+        logger
+            .log_execution(AuditLogEntry {
+                timestamp: AuditLogger::current_timestamp(),
+                user_id: "testuser".to_string(),
+                natural_language_input: "show pods".to_string(),
+                kubectl_command: "kubectl get pods".to_string(),
+                original_command: None,
+                confidence_score: Some(95),
+                risk_level: crate::kubectl::RiskLevel::Low,
+                environment: "dev".to_string(),
+                cluster: "test-cluster".to_string(),
+                namespace: None,
+                exit_code: Some(1),
+                stdout: None,
+                stderr: None,
+                execution_duration_ms: Some(10),
+                user_action: UserAction::Executed,
+                mcp_client: None,
+            })
+            .unwrap();
+
+        let tools = KaidoTools::with_audit_db(temp_db.path());
+
+        let result = tools.recent_history(&json!({ "limit": 5, "since": "1h" }));
+        assert!(!result.is_error);
+        let ToolContent::Text { text } = &result.content[0];
+        assert!(text.contains("kubectl get pods"));
+        assert!(text.contains("LOW"));
+        assert!(text.contains("failed (exit 1)"));
+
+        let result = tools.recent_history(&json!({ "since": "last tuesday" }));
+        assert!(result.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_execute_and_check_risk_are_audited() {
+        use tempfile::TempDir;
+
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("audit.db");
+        let tools = KaidoTools::with_audit_db(&db_path);
+
+        let result = tools
+            .call(
+                "kaido_execute",
+                &json!({ "command": "echo hello" }),
+                Some("claude-code"),
+            )
+            .await;
+        assert!(!result.is_error);
+        tools
+            .call(
+                "kaido_check_risk",
+                &json!({ "command": "rm -rf /tmp/x" }),
+                None,
+            )
+            .await;
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT kubectl_command, user_action, mcp_client, exit_code FROM audit_log ORDER BY id")
+            .unwrap();
+        let rows: Vec<(String, String, Option<String>, Option<i32>)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "echo hello".to_string(),
+                    "MCP_EXECUTED".to_string(),
+                    Some("claude-code".to_string()),
+                    Some(0)
+                ),
+                (
+                    "rm -rf /tmp/x".to_string(),
+                    "MCP_RISK_CHECKED".to_string(),
+                    None,
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_solution_lists_then_requires_confirmation() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tools = KaidoTools::with_audit_db(dir.path().join("audit.db"));
+        let error = "nginx: [emerg] bind() to 0.0.0.0:80 failed (98: Address already in use)";
+
+        let listed = tools
+            .call(
+                "kaido_apply_solution",
+                &json!({ "error": error, "command": "nginx" }),
+                None,
+            )
+            .await;
+        assert!(!listed.is_error);
+        let text = serde_json::to_string(&listed).unwrap();
+        assert!(
+            text.contains("1. Check what's using the port (recommended)"),
+            "{text}"
+        );
+
+        let unconfirmed = tools
+            .call(
+                "kaido_apply_solution",
+                &json!({ "error": error, "command": "nginx", "solution": 2 }),
+                None,
+            )
+            .await;
+        assert!(unconfirmed.is_error);
+
+        let manual = tools
+            .call(
+                "kaido_apply_solution",
+                &json!({ "error": error, "command": "nginx", "solution": 3 }),
+                None,
+            )
+            .await;
+        assert!(manual.is_error);
+    }
+
+    #[tokio::test]
+    async fn test_execute_blocks_database_wipes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tools = KaidoTools::with_audit_db(dir.path().join("audit.db"));
+
+        for command in [
+            "psql -c 'TRUNCATE orders' shop",
+            "mysql -e 'DROP DATABASE shop'",
+        ] {
+            let result = tools
+                .call("kaido_execute", &json!({ "command": command }), None)
+                .await;
+            assert!(result.is_error, "{command} was executed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_refuses_pipes_and_redirects() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tools = KaidoTools::with_audit_db(dir.path().join("audit.db"));
+
+        for command in ["ps aux | grep nginx", "echo x > /tmp/kaido-none", "ls; ls"] {
+            let result = tools
+                .call("kaido_execute", &json!({ "command": command }), None)
+                .await;
+            let ToolContent::Text { text } = &result.content[0];
+            assert!(
+                result.is_error && text.contains("Pipes"),
+                "{command}: {text}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_keeps_the_tail_of_large_output() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tools = KaidoTools::with_audit_db(dir.path().join("audit.db")).with_capture_bytes(64);
+
+        let result = tools
+            .call("kaido_execute", &json!({ "command": "seq 1 20000" }), None)
+            .await;
+
+        assert!(!result.is_error);
+        let ToolContent::Text { text } = &result.content[0];
+        assert!(text.contains("19999\n20000"), "{text}");
+        assert!(!text.contains("\n1\n"), "{text}");
+        assert!(text.contains("output truncated"), "{text}");
+    }
+
+    #[test]
+    fn test_recent_history_without_audit_db() {
+        let tools = KaidoTools::with_audit_db("/nonexistent/kaido/audit.db");
+        let result = tools.recent_history(&json!({}));
+
+        assert!(!result.is_error);
+        let ToolContent::Text { text } = &result.content[0];
+        assert!(text.contains("No commands recorded yet"));
     }
 
     #[test]
@@ -595,19 +1288,19 @@ mod tests {
 
         // Low risk
         assert!(matches!(
-            tools.assess_risk("kubectl get pods", None),
+            tools.assess_risk("kubectl get pods"),
             RiskLevel::Low
         ));
 
         // High risk
         assert!(matches!(
-            tools.assess_risk("kubectl delete pod nginx", None),
+            tools.assess_risk("kubectl delete pod nginx"),
             RiskLevel::High
         ));
 
         // Unknown command with delete
         assert!(matches!(
-            tools.assess_risk("some-tool delete everything", None),
+            tools.assess_risk("some-tool delete everything"),
             RiskLevel::High
         ));
     }
@@ -623,4 +1316,53 @@ mod tests {
         assert!(text.contains("docker"));
         assert!(text.contains("nginx"));
     }
+
+    #[test]
+    fn test_named_resources_from_problem() {
+        assert_eq!(
+            NamedResources::from_problem("pod api-7d9f8 in namespace prod-eu keeps crashing"),
+            NamedResources {
+                pod: Some("api-7d9f8".to_string()),
+                namespace: Some("prod-eu".to_string()),
+                container: None,
+            }
+        );
+        assert_eq!(
+            NamedResources::from_problem("docker container `web` exits on start"),
+            NamedResources {
+                container: Some("web".to_string()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            NamedResources::from_problem("the pod keeps restarting; pods are pending"),
+            NamedResources::default()
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_commands_include_logs() {
+        let tools = KaidoTools::new();
+        let commands = tools.get_diagnostic_commands("pod/worker-5 -n jobs is in CrashLoopBackOff");
+        let logs: Vec<_> = commands
+            .iter()
+            .filter(|c| c.budget == LOG_BUDGET)
+            .map(|c| c.command.as_str())
+            .collect();
+        assert_eq!(
+            logs,
+            [
+                "kubectl logs worker-5 -n jobs --tail=50",
+                "kubectl logs worker-5 -n jobs --tail=50 --previous",
+            ]
+        );
+
+        let commands = tools.get_diagnostic_commands("container redis1 is unhealthy");
+        assert!(commands
+            .iter()
+            .any(|c| c.command == "docker logs --tail=50 redis1"));
+
+        let commands = tools.get_diagnostic_commands("disk is slow");
+        assert!(commands.iter().all(|c| c.budget != LOG_BUDGET));
+    }
 }
@@ -4,16 +4,174 @@
 // for the same or similar errors.
 
 use anyhow::Result;
+use regex::Regex;
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
 use std::sync::Mutex;
 
 use super::guidance::{GuidanceSource, MentorGuidance};
+use super::i18n::Language;
 use super::types::ErrorInfo;
 
+/// Minimum length for a hex token to be treated as an ID
+const MIN_HEX_ID_LEN: usize = 7;
+
+/// Masks variable parts of error messages (names, paths, ports, IDs)
+///
+/// Lets templated errors such as `pods "foo" not found` and
+/// `pods "bar" not found` share one cache entry.
+pub struct MessageNormalizer {
+    /// Patterns replaced in order, with their placeholder
+    masks: Vec<(Regex, &'static str)>,
+    /// Hex-looking tokens (commit SHAs, container IDs)
+    hex_regex: Regex,
+}
+
+impl MessageNormalizer {
+    pub fn new() -> Self {
+        Self {
+            masks: vec![
+                (
+                    Regex::new(
+                        r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+                    )
+                    .unwrap(),
+                    "<ID>",
+                ),
+                (Regex::new(r#""[^"\n]*""#).unwrap(), r#""<NAME>""#),
+                (Regex::new(r"(^|\W)'[^'\n]*'").unwrap(), "${1}'<NAME>'"),
+                (Regex::new(r"`[^`\n]*`").unwrap(), "`<NAME>`"),
+                (
+                    Regex::new(r#"(?i)\b[a-z][a-z0-9+.-]*://[^\s'"]+"#).unwrap(),
+                    "<URL>",
+                ),
+                (Regex::new(r"\b\d{1,3}(?:\.\d{1,3}){3}\b").unwrap(), "<IP>"),
+                (
+                    Regex::new(r#"(^|[\s=(\[])(?:~|\.{1,2})?/[^\s:,;'"()\[\]]+"#).unwrap(),
+                    "${1}<PATH>",
+                ),
+                (Regex::new(r":\d{2,5}\b").unwrap(), ":<PORT>"),
+                (Regex::new(r"(?i)\b(port)\s+\d+\b").unwrap(), "${1} <PORT>"),
+            ],
+            hex_regex: Regex::new(r"(?i)\b[0-9a-f]+\b").unwrap(),
+        }
+    }
+
+    /// Mask variable parts of a message, keeping its shape
+    ///
+    /// e.g. `pods "foo" not found` becomes `pods "<NAME>" not found`
+    pub fn normalize(&self, message: &str) -> String {
+        let mut normalized = message.to_string();
+
+        for (regex, placeholder) in &self.masks {
+            normalized = regex.replace_all(&normalized, *placeholder).into_owned();
+        }
+
+        self.hex_regex
+            .replace_all(&normalized, |caps: &regex::Captures| {
+                let token = &caps[0];
+                if token.len() >= MIN_HEX_ID_LEN && token.chars().any(|c| c.is_ascii_digit()) {
+                    "<ID>".to_string()
+                } else {
+                    token.to_string()
+                }
+            })
+            .into_owned()
+    }
+
+    /// Normalized, lowercased message without punctuation, for comparing
+    /// errors that differ only in names, paths or IDs
+    pub fn key(&self, message: &str) -> String {
+        self.normalize(message)
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '<' || *c == '>')
+            .collect()
+    }
+}
+
+impl Default for MessageNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Values that differ between two same-shape messages, as (old, new)
+///
+/// Messages are compared token by token, splitting at whitespace, quotes
+/// and punctuation other than the `.`, `/`, `-`, `_` and `~` that names
+/// and paths are made of. `None` when the token counts differ, since the
+/// values can't be paired up then.
+fn value_swaps(old: &str, new: &str) -> Option<Vec<(String, String)>> {
+    let tokens = |message: &str| -> Vec<String> {
+        message
+            .split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '/' | '-' | '_' | '~')))
+            .map(|token| token.trim_end_matches('.'))
+            .filter(|token| !token.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    let (old, new) = (tokens(old), tokens(new));
+    if old.len() != new.len() {
+        return None;
+    }
+
+    let mut swaps: Vec<(String, String)> = old
+        .into_iter()
+        .zip(new)
+        .filter(|(old, new)| old != new)
+        .collect();
+    swaps.sort();
+    swaps.dedup();
+    // Longest first, so a value isn't clobbered by a shorter one inside it
+    swaps.sort_by_key(|(old, _)| std::cmp::Reverse(old.len()));
+    Some(swaps)
+}
+
+/// Replace whole-token occurrences of each old value with the new one
+fn swap_values(text: &str, swaps: &[(String, String)]) -> String {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let mut text = text.to_string();
+    for (old, new) in swaps {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(at) = rest.find(old.as_str()) {
+            let end = at + old.len();
+            let bounded =
+                !is_word(rest[..at].chars().next_back()) && !is_word(rest[end..].chars().next());
+            result.push_str(&rest[..at]);
+            result.push_str(if bounded { new } else { old });
+            rest = &rest[end..];
+        }
+        result.push_str(rest);
+        text = result;
+    }
+    text
+}
+
+/// Guidance with each old value replaced by the current error's
+fn with_values_swapped(mut guidance: MentorGuidance, swaps: &[(String, String)]) -> MentorGuidance {
+    guidance.key_message = swap_values(&guidance.key_message, swaps);
+    guidance.explanation = swap_values(&guidance.explanation, swaps);
+    for keyword in &mut guidance.search_keywords {
+        *keyword = swap_values(keyword, swaps);
+    }
+    for step in &mut guidance.next_steps {
+        step.description = swap_values(&step.description, swaps);
+        step.command = step
+            .command
+            .as_deref()
+            .map(|command| swap_values(command, swaps));
+    }
+    guidance
+}
+
 /// Cache for mentor guidance responses
 pub struct GuidanceCache {
     conn: Mutex<Connection>,
+    normalizer: MessageNormalizer,
+    /// Language of the guidance stored and looked up
+    language: Language,
 }
 
 impl GuidanceCache {
@@ -34,71 +192,110 @@ impl GuidanceCache {
             [],
         )?;
 
+        // Message the guidance was generated for, so hits for a same-shape
+        // error can be rewritten with that error's values. Older databases
+        // lack the column; their rows count as misses.
+        let _ = conn.execute(
+            "ALTER TABLE guidance_cache ADD COLUMN error_message TEXT",
+            [],
+        );
+
         // Create index for faster lookups
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_cache_key ON guidance_cache(cache_key)",
             [],
         )?;
 
+        // Lookup counters (hits, misses), kept across sessions
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cache_counters (
+                name TEXT PRIMARY KEY,
+                count INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         Ok(Self {
             conn: Mutex::new(conn),
+            normalizer: MessageNormalizer::new(),
+            language: Language::default(),
         })
     }
 
+    /// Keep guidance for `language` apart from other languages'
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
     /// Create an in-memory cache (for testing)
     pub fn in_memory() -> Result<Self> {
         Self::new(":memory:")
     }
 
     /// Generate cache key from error info
-    fn cache_key(error: &ErrorInfo) -> String {
-        // Key based on error type and normalized key message
-        let normalized_msg = error
-            .key_message
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
-            .collect::<String>();
-
-        format!("{}:{}", error.error_type.name(), normalized_msg)
+    ///
+    /// Only the key is normalized; the stored guidance keeps the
+    /// original message for display.
+    pub(super) fn cache_key(&self, error: &ErrorInfo) -> String {
+        // Key based on language, error type and normalized key message
+        let normalized_msg = self.normalizer.key(&error.key_message);
+
+        format!(
+            "{}:{}:{}",
+            self.language.code(),
+            error.error_type.name(),
+            normalized_msg
+        )
     }
 
     /// Get cached guidance for an error
+    ///
+    /// The stored guidance names the values of the error it was generated
+    /// for; they are swapped for the current error's values. An entry
+    /// whose values can't be matched up word for word is a miss.
     pub fn get(&self, error: &ErrorInfo) -> Option<MentorGuidance> {
-        let key = Self::cache_key(error);
+        let key = self.cache_key(error);
         let conn = self.conn.lock().ok()?;
 
-        let result: Option<String> = conn
+        let row: Option<(String, Option<String>)> = conn
             .query_row(
-                "SELECT guidance_json FROM guidance_cache WHERE cache_key = ?",
+                "SELECT guidance_json, error_message FROM guidance_cache WHERE cache_key = ?",
                 params![key],
-                |row| row.get(0),
+                |row| Ok((row.get(0)?, row.get(1)?)),
             )
             .optional()
             .ok()?;
 
-        if let Some(json) = result {
-            // Update hit count
-            let _ = conn.execute(
-                "UPDATE guidance_cache SET hit_count = hit_count + 1 WHERE cache_key = ?",
-                params![key],
-            );
-
-            // Parse and return
-            serde_json::from_str::<MentorGuidance>(&json)
-                .ok()
-                .map(|mut g| {
-                    g.source = GuidanceSource::Cached;
-                    g
-                })
-        } else {
-            None
-        }
+        let guidance = row.and_then(|(json, message)| {
+            let guidance = serde_json::from_str::<MentorGuidance>(&json).ok()?;
+            let swaps = value_swaps(&message?, &error.key_message)?;
+            Some(with_values_swapped(guidance, &swaps))
+        });
+
+        Self::bump_counter(&conn, if guidance.is_some() { "hits" } else { "misses" });
+
+        let mut guidance = guidance?;
+        // Update hit count
+        let _ = conn.execute(
+            "UPDATE guidance_cache SET hit_count = hit_count + 1 WHERE cache_key = ?",
+            params![key],
+        );
+        guidance.source = GuidanceSource::Cached;
+        Some(guidance)
+    }
+
+    fn bump_counter(conn: &Connection, name: &str) {
+        let _ = conn.execute(
+            "INSERT INTO cache_counters (name, count) VALUES (?, 1)
+             ON CONFLICT(name) DO UPDATE SET count = count + 1",
+            params![name],
+        );
     }
 
     /// Store guidance in cache
     pub fn set(&self, error: &ErrorInfo, guidance: &MentorGuidance) -> Result<()> {
-        let key = Self::cache_key(error);
+        let key = self.cache_key(error);
         let json = serde_json::to_string(guidance)?;
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -108,9 +305,10 @@ impl GuidanceCache {
         let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
 
         conn.execute(
-            "INSERT OR REPLACE INTO guidance_cache (cache_key, error_type, guidance_json, created_at)
-             VALUES (?, ?, ?, ?)",
-            params![key, error.error_type.name(), json, now],
+            "INSERT OR REPLACE INTO guidance_cache
+                 (cache_key, error_type, guidance_json, created_at, error_message)
+             VALUES (?, ?, ?, ?, ?)",
+            params![key, error.error_type.name(), json, now, error.key_message],
         )?;
 
         Ok(())
@@ -134,22 +332,43 @@ impl GuidanceCache {
         Ok(deleted)
     }
 
+    /// Remove every entry and reset the lookup counters
+    pub fn clear(&self) -> Result<usize> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
+        let deleted = conn.execute("DELETE FROM guidance_cache", [])?;
+        conn.execute("DELETE FROM cache_counters", [])?;
+        Ok(deleted)
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> Result<CacheStats> {
         let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("{e}"))?;
 
-        let total_entries: i64 =
-            conn.query_row("SELECT COUNT(*) FROM guidance_cache", [], |row| row.get(0))?;
-
-        let total_hits: i64 = conn.query_row(
-            "SELECT COALESCE(SUM(hit_count), 0) FROM guidance_cache",
+        let (total_entries, total_hits, bytes): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(hit_count), 0),
+                    COALESCE(SUM(LENGTH(cache_key) + LENGTH(guidance_json)), 0)
+             FROM guidance_cache",
             [],
-            |row| row.get(0),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )?;
 
+        let counter = |name: &str| -> Result<u64> {
+            let count: Option<i64> = conn
+                .query_row(
+                    "SELECT count FROM cache_counters WHERE name = ?",
+                    params![name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(count.unwrap_or(0) as u64)
+        };
+
         Ok(CacheStats {
             total_entries: total_entries as usize,
             total_hits: total_hits as usize,
+            hits: counter("hits")?,
+            misses: counter("misses")?,
+            bytes: bytes as u64,
         })
     }
 }
@@ -158,12 +377,28 @@ impl GuidanceCache {
 #[derive(Debug, Clone)]
 pub struct CacheStats {
     pub total_entries: usize,
+    /// Sum of per-entry hit counts (each entry starts at 1 when stored)
     pub total_hits: usize,
+    /// Lookups that found guidance
+    pub hits: u64,
+    /// Lookups that found nothing
+    pub misses: u64,
+    /// Stored keys and guidance JSON
+    pub bytes: u64,
+}
+
+impl CacheStats {
+    /// Share of lookups answered from the cache, None before any lookup
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mentor::guidance::NextStep;
     use crate::mentor::types::ErrorType;
 
     fn create_test_error() -> ErrorInfo {
@@ -188,13 +423,24 @@ mod tests {
 
     #[test]
     fn test_cache_key_generation() {
+        let cache = GuidanceCache::in_memory().unwrap();
         let error = create_test_error();
-        let key = GuidanceCache::cache_key(&error);
+        let key = cache.cache_key(&error);
 
         assert!(key.contains("Command Not Found"));
         assert!(key.contains("kubectl"));
     }
 
+    #[test]
+    fn test_languages_cached_apart() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let error = create_test_error();
+        cache.set(&error, &create_test_guidance()).unwrap();
+
+        let cache = cache.with_language(Language::TraditionalChinese);
+        assert!(cache.get(&error).is_none());
+    }
+
     #[test]
     fn test_cache_miss() {
         let cache = GuidanceCache::in_memory().unwrap();
@@ -237,6 +483,29 @@ mod tests {
         assert_eq!(stats.total_hits, 3); // Initial + 2 hits
     }
 
+    #[test]
+    fn test_lookup_counters_and_clear() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let error = create_test_error();
+        assert_eq!(cache.stats().unwrap().hit_rate(), None);
+
+        cache.get(&error); // Miss
+        cache.set(&error, &create_test_guidance()).unwrap();
+        cache.get(&error); // Hit
+        cache.get(&error); // Hit
+        cache.get(&error); // Hit
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.hits, stats.misses), (3, 1));
+        assert_eq!(stats.hit_rate(), Some(0.75));
+        assert!(stats.bytes > 0);
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.total_entries, 0);
+        assert_eq!((stats.hits, stats.misses, stats.bytes), (0, 0, 0));
+    }
+
     #[test]
     fn test_similar_errors_same_cache() {
         let cache = GuidanceCache::in_memory().unwrap();
@@ -262,4 +531,161 @@ mod tests {
         let cached = cache.get(&error2);
         assert!(cached.is_some());
     }
+
+    #[test]
+    fn test_normalize_masks_variable_parts() {
+        let normalizer = MessageNormalizer::new();
+
+        assert_eq!(
+            normalizer.normalize(r#"pods "foo" not found"#),
+            r#"pods "<NAME>" not found"#
+        );
+        assert_eq!(
+            normalizer.normalize("cannot open '/etc/nginx/nginx.conf': Permission denied"),
+            "cannot open '<NAME>': Permission denied"
+        );
+        assert_eq!(
+            normalizer.normalize("open /var/log/app.log: no such file or directory"),
+            "open <PATH>: no such file or directory"
+        );
+        assert_eq!(
+            normalizer.normalize("dial tcp 10.0.0.12:5432: connect: connection refused"),
+            "dial tcp <IP>:<PORT>: connect: connection refused"
+        );
+        assert_eq!(
+            normalizer.normalize("bind to port 8080 failed"),
+            "bind to port <PORT> failed"
+        );
+        assert_eq!(
+            normalizer.normalize("No such container: 3f4e1a2b9c7d"),
+            "No such container: <ID>"
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_plain_words() {
+        let normalizer = MessageNormalizer::new();
+
+        // Command names, apostrophes and short numbers are part of the shape
+        assert_eq!(
+            normalizer.normalize("command not found: kubectl"),
+            "command not found: kubectl"
+        );
+        assert_eq!(
+            normalizer.normalize("doesn't exist, exit code 137"),
+            "doesn't exist, exit code 137"
+        );
+        assert_eq!(normalizer.normalize("deadbeef added"), "deadbeef added");
+    }
+
+    #[test]
+    fn test_same_shape_errors_share_cache_entry() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let guidance = MentorGuidance::from_pattern(
+            r#"pods "foo" not found"#,
+            "The pod does not exist in this namespace",
+        );
+
+        let error1 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"Error from server (NotFound): pods "foo" not found"#,
+            "kubectl get pod foo",
+        );
+        let error2 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"Error from server (NotFound): pods "bar" not found"#,
+            "kubectl get pod bar",
+        );
+
+        cache.set(&error1, &guidance).unwrap();
+
+        let cached = cache.get(&error2).expect("same-shape error should hit");
+        // Shown with the current error's values, not the first one's
+        assert_eq!(cached.key_message, r#"pods "bar" not found"#);
+        assert_eq!(cache.stats().unwrap().total_entries, 1);
+    }
+
+    #[test]
+    fn test_hit_rewrites_values_in_steps() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let guidance = MentorGuidance::from_pattern(
+            "Port 8080 is already in use",
+            "Another process on 10.0.0.12 holds port 8080",
+        )
+        .with_steps(vec![NextStep::with_command(
+            "See what holds port 8080",
+            "lsof -i :8080",
+        )]);
+
+        let error1 = ErrorInfo::new(
+            ErrorType::PortInUse,
+            1,
+            "listen tcp 10.0.0.12:8080: bind: address already in use",
+            "./server",
+        );
+        let error2 = ErrorInfo::new(
+            ErrorType::PortInUse,
+            1,
+            "listen tcp 10.0.0.7:3000: bind: address already in use",
+            "./server",
+        );
+        cache.set(&error1, &guidance).unwrap();
+
+        let cached = cache.get(&error2).expect("same-shape error should hit");
+        assert_eq!(cached.key_message, "Port 3000 is already in use");
+        assert_eq!(
+            cached.explanation,
+            "Another process on 10.0.0.7 holds port 3000"
+        );
+        assert_eq!(cached.next_steps[0].description, "See what holds port 3000");
+        assert_eq!(
+            cached.next_steps[0].command.as_deref(),
+            Some("lsof -i :3000")
+        );
+    }
+
+    #[test]
+    fn test_unpaired_values_miss() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let error1 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            "open /srv/app.yml: no such file or directory",
+            "cat /srv/app.yml",
+        );
+        let error2 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            "open /srv/my app.yml: no such file or directory",
+            "cat '/srv/my app.yml'",
+        );
+        cache.set(&error1, &create_test_guidance()).unwrap();
+
+        assert!(cache.get(&error2).is_none());
+        assert!(cache.get(&error1).is_some());
+    }
+
+    #[test]
+    fn test_different_shape_errors_miss() {
+        let cache = GuidanceCache::in_memory().unwrap();
+        let guidance = create_test_guidance();
+
+        let error1 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"pods "foo" not found"#,
+            "kubectl get pod foo",
+        );
+        let error2 = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"services "foo" not found"#,
+            "kubectl get svc foo",
+        );
+
+        cache.set(&error1, &guidance).unwrap();
+        assert!(cache.get(&error2).is_none());
+    }
 }
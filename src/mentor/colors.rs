@@ -2,23 +2,253 @@
 //
 // Provides consistent terminal coloring for the mentor system.
 // Respects NO_COLOR environment variable for accessibility.
+//
+// Colors come from a named theme, optionally with single roles overridden
+// by a custom palette from config.toml. The colorblind-safe theme never
+// relies on telling red from green: errors are orange, success and search
+// hints are blue.
+
+use serde::{Deserialize, Serialize};
+
+/// Named palettes for mentor output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorTheme {
+    /// Bright colors for dark terminals
+    #[default]
+    Default,
+    /// Darker colors that stay readable on light backgrounds
+    Light,
+    /// Bold, bright colors with no dimmed text
+    HighContrast,
+    /// Blue/orange palette that avoids red-green distinctions
+    ColorblindSafe,
+}
+
+impl ColorTheme {
+    /// Every theme, in the order they are listed to users
+    pub const ALL: [ColorTheme; 4] = [
+        ColorTheme::Default,
+        ColorTheme::Light,
+        ColorTheme::HighContrast,
+        ColorTheme::ColorblindSafe,
+    ];
+
+    /// Name used in config.toml and by the `theme` builtin
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorTheme::Default => "default",
+            ColorTheme::Light => "light",
+            ColorTheme::HighContrast => "high-contrast",
+            ColorTheme::ColorblindSafe => "colorblind-safe",
+        }
+    }
+
+    /// Look up a theme by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+
+    /// The theme's colors
+    pub fn palette(&self) -> Palette {
+        match self {
+            ColorTheme::Default => Palette {
+                border: Some("36".to_string()),
+                title: Some("1;36".to_string()),
+                key_message: Some("1;33".to_string()),
+                explanation: Some("0".to_string()),
+                location: Some("34".to_string()),
+                search: Some("32".to_string()),
+                command: Some("1;37".to_string()),
+                concept: Some("35".to_string()),
+                dim: Some("2".to_string()),
+                error_type: Some("1;31".to_string()),
+            },
+            ColorTheme::Light => Palette {
+                border: Some("34".to_string()),
+                title: Some("1;34".to_string()),
+                key_message: Some("1;38;5;130".to_string()),
+                explanation: Some("0".to_string()),
+                location: Some("34".to_string()),
+                search: Some("38;5;28".to_string()),
+                command: Some("1;30".to_string()),
+                concept: Some("35".to_string()),
+                dim: Some("38;5;242".to_string()),
+                error_type: Some("1;31".to_string()),
+            },
+            ColorTheme::HighContrast => Palette {
+                border: Some("1;97".to_string()),
+                title: Some("1;97".to_string()),
+                key_message: Some("1;93".to_string()),
+                explanation: Some("0".to_string()),
+                location: Some("1;96".to_string()),
+                search: Some("1;92".to_string()),
+                command: Some("1;97".to_string()),
+                concept: Some("1;95".to_string()),
+                dim: Some("0".to_string()),
+                error_type: Some("1;91".to_string()),
+            },
+            ColorTheme::ColorblindSafe => Palette {
+                border: Some("38;5;32".to_string()),
+                title: Some("1;38;5;32".to_string()),
+                key_message: Some("1;38;5;220".to_string()),
+                explanation: Some("0".to_string()),
+                location: Some("38;5;39".to_string()),
+                search: Some("38;5;117".to_string()),
+                command: Some("1;37".to_string()),
+                concept: Some("38;5;176".to_string()),
+                dim: Some("2".to_string()),
+                error_type: Some("1;38;5;208".to_string()),
+            },
+        }
+    }
+}
+
+/// SGR parameters for each mentor color role, e.g. `"1;36"` for bold cyan
+/// or `"38;5;208"` for 256-color orange
+///
+/// In config.toml (`[display.palette]`) any role may be left out to keep
+/// the theme's color.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    /// Box borders
+    pub border: Option<String>,
+    /// Title
+    pub title: Option<String>,
+    /// Key message (the main error)
+    pub key_message: Option<String>,
+    /// Explanation text
+    pub explanation: Option<String>,
+    /// Source location
+    pub location: Option<String>,
+    /// Search suggestions
+    pub search: Option<String>,
+    /// Commands
+    pub command: Option<String>,
+    /// Concepts/learning topics
+    pub concept: Option<String>,
+    /// Secondary/muted text
+    pub dim: Option<String>,
+    /// Error type label
+    pub error_type: Option<String>,
+}
+
+impl Palette {
+    /// This palette's colors, falling back to `base` for unset roles
+    pub fn over(&self, base: &Palette) -> Palette {
+        Palette {
+            border: self.border.clone().or_else(|| base.border.clone()),
+            title: self.title.clone().or_else(|| base.title.clone()),
+            key_message: self
+                .key_message
+                .clone()
+                .or_else(|| base.key_message.clone()),
+            explanation: self
+                .explanation
+                .clone()
+                .or_else(|| base.explanation.clone()),
+            location: self.location.clone().or_else(|| base.location.clone()),
+            search: self.search.clone().or_else(|| base.search.clone()),
+            command: self.command.clone().or_else(|| base.command.clone()),
+            concept: self.concept.clone().or_else(|| base.concept.clone()),
+            dim: self.dim.clone().or_else(|| base.dim.clone()),
+            error_type: self.error_type.clone().or_else(|| base.error_type.clone()),
+        }
+    }
+
+    /// Roles whose value isn't a valid SGR parameter list
+    pub fn invalid_roles(&self) -> Vec<&'static str> {
+        [
+            ("border", &self.border),
+            ("title", &self.title),
+            ("key_message", &self.key_message),
+            ("explanation", &self.explanation),
+            ("location", &self.location),
+            ("search", &self.search),
+            ("command", &self.command),
+            ("concept", &self.concept),
+            ("dim", &self.dim),
+            ("error_type", &self.error_type),
+        ]
+        .into_iter()
+        .filter(|(_, code)| code.as_deref().is_some_and(|code| !is_sgr(code)))
+        .map(|(role, _)| role)
+        .collect()
+    }
+}
+
+/// Whether `code` is an SGR parameter list such as `"1;36"`
+pub fn is_sgr(code: &str) -> bool {
+    !code.is_empty()
+        && code
+            .split(';')
+            .all(|part| !part.is_empty() && part.parse::<u8>().is_ok())
+}
+
+/// Full escape sequence for each role
+#[derive(Debug, Clone)]
+struct Codes {
+    border: String,
+    title: String,
+    key_message: String,
+    explanation: String,
+    location: String,
+    search: String,
+    command: String,
+    concept: String,
+    dim: String,
+    error_type: String,
+}
+
+impl Codes {
+    fn from_palette(palette: &Palette) -> Self {
+        let fallback = ColorTheme::Default.palette();
+        let escape = |code: &Option<String>, default: &Option<String>| {
+            let code = code.as_deref().or(default.as_deref()).unwrap_or("0");
+            format!("\x1b[{code}m")
+        };
+        Self {
+            border: escape(&palette.border, &fallback.border),
+            title: escape(&palette.title, &fallback.title),
+            key_message: escape(&palette.key_message, &fallback.key_message),
+            explanation: escape(&palette.explanation, &fallback.explanation),
+            location: escape(&palette.location, &fallback.location),
+            search: escape(&palette.search, &fallback.search),
+            command: escape(&palette.command, &fallback.command),
+            concept: escape(&palette.concept, &fallback.concept),
+            dim: escape(&palette.dim, &fallback.dim),
+            error_type: escape(&palette.error_type, &fallback.error_type),
+        }
+    }
+}
 
 /// ANSI escape codes for terminal colors
 pub struct MentorColors {
     /// Whether colors are enabled
     enabled: bool,
+    codes: Codes,
 }
 
 impl MentorColors {
     /// Create new color provider, respecting NO_COLOR env var
     pub fn new() -> Self {
         let enabled = std::env::var("NO_COLOR").is_err();
-        Self { enabled }
+        Self::with_enabled(enabled)
     }
 
     /// Create with colors explicitly enabled or disabled
     pub fn with_enabled(enabled: bool) -> Self {
-        Self { enabled }
+        Self::with_palette(enabled, &ColorTheme::Default.palette())
+    }
+
+    /// Create with a theme's palette, or a custom one
+    pub fn with_palette(enabled: bool, palette: &Palette) -> Self {
+        Self {
+            enabled,
+            codes: Codes::from_palette(palette),
+        }
     }
 
     /// Check if colors are enabled
@@ -26,98 +256,66 @@ impl MentorColors {
         self.enabled
     }
 
-    // Border and structure colors
-
-    /// Dim cyan for box borders
-    pub fn border(&self) -> &'static str {
+    fn code<'a>(&self, code: &'a str) -> &'a str {
         if self.enabled {
-            "\x1b[36m"
+            code
         } else {
             ""
         }
     }
 
-    /// Bold cyan for title
-    pub fn title(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[1;36m"
-        } else {
-            ""
-        }
+    // Border and structure colors
+
+    /// Box borders (dim cyan by default)
+    pub fn border(&self) -> &str {
+        self.code(&self.codes.border)
+    }
+
+    /// Title (bold cyan by default)
+    pub fn title(&self) -> &str {
+        self.code(&self.codes.title)
     }
 
     // Content colors
 
-    /// Bold yellow for key message (the main error)
-    pub fn key_message(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[1;33m"
-        } else {
-            ""
-        }
+    /// Key message, the main error (bold yellow by default)
+    pub fn key_message(&self) -> &str {
+        self.code(&self.codes.key_message)
     }
 
-    /// White for explanation text
-    pub fn explanation(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[0m"
-        } else {
-            ""
-        }
+    /// Explanation text (plain by default)
+    pub fn explanation(&self) -> &str {
+        self.code(&self.codes.explanation)
     }
 
-    /// Dim blue for source location
-    pub fn location(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[34m"
-        } else {
-            ""
-        }
+    /// Source location (blue by default)
+    pub fn location(&self) -> &str {
+        self.code(&self.codes.location)
     }
 
-    /// Green for search suggestions
-    pub fn search(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[32m"
-        } else {
-            ""
-        }
+    /// Search suggestions (green by default)
+    pub fn search(&self) -> &str {
+        self.code(&self.codes.search)
     }
 
-    /// Bold white for commands
-    pub fn command(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[1;37m"
-        } else {
-            ""
-        }
+    /// Commands (bold white by default)
+    pub fn command(&self) -> &str {
+        self.code(&self.codes.command)
     }
 
-    /// Magenta for concepts/learning topics
-    pub fn concept(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[35m"
-        } else {
-            ""
-        }
+    /// Concepts/learning topics (magenta by default)
+    pub fn concept(&self) -> &str {
+        self.code(&self.codes.concept)
     }
 
-    /// Dim for secondary/muted text
-    pub fn dim(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[2m"
-        } else {
-            ""
-        }
+    /// Secondary/muted text (dim by default)
+    pub fn dim(&self) -> &str {
+        self.code(&self.codes.dim)
     }
 
-    /// Red for error type label
-    pub fn error_type(&self) -> &'static str {
-        if self.enabled {
-            "\x1b[1;31m"
-        } else {
-            ""
-        }
+    /// Error type label (bold red by default)
+    pub fn error_type(&self) -> &str {
+        self.code(&self.codes.error_type)
     }
 
     /// Reset all formatting
@@ -179,4 +377,44 @@ mod tests {
         assert!(!colors.dim().is_empty());
         assert!(!colors.error_type().is_empty());
     }
+
+    #[test]
+    fn test_themes_by_name() {
+        for theme in ColorTheme::ALL {
+            assert_eq!(ColorTheme::from_name(theme.name()), Some(theme));
+            assert!(theme.palette().invalid_roles().is_empty());
+        }
+        assert_eq!(
+            ColorTheme::from_name("Colorblind-Safe"),
+            Some(ColorTheme::ColorblindSafe)
+        );
+        assert_eq!(ColorTheme::from_name("solarized"), None);
+
+        let default = MentorColors::with_enabled(true);
+        assert_eq!(default.error_type(), "\x1b[1;31m");
+        let colorblind = MentorColors::with_palette(true, &ColorTheme::ColorblindSafe.palette());
+        assert_ne!(colorblind.error_type(), default.error_type());
+        assert_ne!(colorblind.search(), default.search());
+    }
+
+    #[test]
+    fn test_custom_palette_overrides_theme() {
+        let custom = Palette {
+            key_message: Some("1;38;5;208".to_string()),
+            ..Palette::default()
+        };
+        let colors = MentorColors::with_palette(true, &custom.over(&ColorTheme::Light.palette()));
+        assert_eq!(colors.key_message(), "\x1b[1;38;5;208m");
+        assert_eq!(colors.border(), "\x1b[34m");
+
+        let disabled = MentorColors::with_palette(false, &custom);
+        assert_eq!(disabled.key_message(), "");
+
+        let bad = Palette {
+            title: Some("bold".to_string()),
+            dim: Some("2;;".to_string()),
+            ..Palette::default()
+        };
+        assert_eq!(bad.invalid_roles(), ["title", "dim"]);
+    }
 }
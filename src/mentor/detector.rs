@@ -3,11 +3,36 @@
 // Analyzes command execution results to detect errors and
 // extract useful information for educational guidance.
 
-use regex::Regex;
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use std::time::{Duration, Instant};
 
-use super::types::{ErrorInfo, ErrorType, SourceLocation};
+use super::types::{ErrorInfo, ErrorType, PatternMatch, SourceLocation};
+use crate::ai::OutputBudget;
 use crate::shell::PtyExecutionResult;
 
+/// Output fed to the regexes: errors sit at the start or the end, so a huge
+/// log keeps 64KB of each and drops the middle
+const SCAN_WINDOW: OutputBudget = OutputBudget::new(64 * 1024, 64 * 1024);
+
+/// Time allowed for the whole pattern pass of one `analyze` call
+const DEFAULT_TIME_BUDGET: Duration = Duration::from_millis(50);
+
+/// Compiled size limit for patterns added with `with_pattern`
+const PATTERN_SIZE_LIMIT: usize = 1024 * 1024;
+
+/// Regex for an HTTP status line with one of `codes` (an alternation)
+///
+/// Matches curl/httpie status lines (`HTTP/1.1 403 Forbidden`), httpie's
+/// `HTTP 403` warning and `curl -f`'s `returned error: 403`, capturing the
+/// status code.
+fn http_status_regex(codes: &str) -> Regex {
+    Regex::new(&format!(
+        r"(?im)(?:^HTTP/[\d.]+|\bHTTP|returned error:)\s+({codes})\b"
+    ))
+    .unwrap()
+}
+
 /// Pattern for detecting specific error types
 #[derive(Debug)]
 struct ErrorPattern {
@@ -25,6 +50,8 @@ pub struct ErrorDetector {
     patterns: Vec<ErrorPattern>,
     /// Regex for extracting file:line:column references
     location_regex: Regex,
+    /// Time allowed for the pattern pass before falling back to exit code
+    time_budget: Duration,
 }
 
 impl ErrorDetector {
@@ -33,9 +60,34 @@ impl ErrorDetector {
         Self {
             patterns: Self::build_patterns(),
             location_regex: Regex::new(r"(?:^|[:\s])(/[^\s:]+):(\d+)(?::(\d+))?").unwrap(),
+            time_budget: DEFAULT_TIME_BUDGET,
         }
     }
 
+    /// Add a pattern, tried after the built-in ones
+    ///
+    /// Patterns whose compiled program exceeds a size limit are rejected so a
+    /// user-supplied pattern can't blow up memory or scan time.
+    pub fn with_pattern(mut self, pattern: &str, error_type: ErrorType) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .size_limit(PATTERN_SIZE_LIMIT)
+            .dfa_size_limit(PATTERN_SIZE_LIMIT)
+            .build()
+            .with_context(|| format!("Invalid error pattern: {pattern}"))?;
+        self.patterns.push(ErrorPattern {
+            regex,
+            error_type,
+            key_group: 0,
+        });
+        Ok(self)
+    }
+
+    /// Set the time allowed for the pattern pass of one `analyze` call
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = budget;
+        self
+    }
+
     /// Build the default error patterns
     fn build_patterns() -> Vec<ErrorPattern> {
         vec![
@@ -50,6 +102,23 @@ impl ErrorDetector {
                 error_type: ErrorType::CommandNotFound,
                 key_group: 0,
             },
+            // HTTP status codes from curl/httpie, before anything the
+            // response body might contain; the key message is the code
+            ErrorPattern {
+                regex: http_status_regex("401|403"),
+                error_type: ErrorType::AuthenticationFailed,
+                key_group: 1,
+            },
+            ErrorPattern {
+                regex: http_status_regex("404"),
+                error_type: ErrorType::ResourceNotFound,
+                key_group: 1,
+            },
+            ErrorPattern {
+                regex: http_status_regex(r"429|5\d\d"),
+                error_type: ErrorType::HttpError,
+                key_group: 1,
+            },
             // Permission denied
             ErrorPattern {
                 regex: Regex::new(r"(?i)permission denied").unwrap(),
@@ -160,6 +229,16 @@ impl ErrorDetector {
                 key_group: 0,
             },
             // Kubernetes specific
+            ErrorPattern {
+                regex: Regex::new(r"(?m)^.*\bOOMKilled\b.*$").unwrap(),
+                error_type: ErrorType::KubernetesError,
+                key_group: 0,
+            },
+            ErrorPattern {
+                regex: Regex::new(r"(?i)command terminated with exit code 137").unwrap(),
+                error_type: ErrorType::KubernetesError,
+                key_group: 0,
+            },
             ErrorPattern {
                 regex: Regex::new(r"(?i)error from server \((\w+)\):\s*(.+)").unwrap(),
                 error_type: ErrorType::KubernetesError,
@@ -176,6 +255,17 @@ impl ErrorDetector {
                 key_group: 0,
             },
             // Git specific
+            ErrorPattern {
+                regex: Regex::new(r"(?i)merge conflict in \S+").unwrap(),
+                error_type: ErrorType::GitError,
+                key_group: 0,
+            },
+            ErrorPattern {
+                regex: Regex::new(r"(?i)\[rejected\].*\((?:non-fast-forward|fetch first)\)")
+                    .unwrap(),
+                error_type: ErrorType::GitError,
+                key_group: 0,
+            },
             ErrorPattern {
                 regex: Regex::new(r"(?i)fatal:\s*(.+)").unwrap(),
                 error_type: ErrorType::GitError,
@@ -278,10 +368,12 @@ impl ErrorDetector {
         }
 
         let exit_code = result.exit_code.unwrap_or(1);
-        let output = &result.output;
+        // Prefer stderr when it was captured separately
+        let output = SCAN_WINDOW.truncate(result.error_text());
+        let output = output.as_ref();
 
         // Detect error type from patterns
-        let (error_type, key_message) = self.detect_error_type(output, exit_code);
+        let (error_type, key_message, matched_pattern) = self.detect_error_type(output, exit_code);
 
         // Extract source location if present
         let source_location = self.extract_source_location(output);
@@ -293,17 +385,31 @@ impl ErrorDetector {
             error_type,
             exit_code,
             key_message,
-            full_output: output.clone(),
+            full_output: result.output.clone(),
             command: result.command.clone(),
             context_lines,
             source_location,
+            matched_pattern,
         })
     }
 
     /// Detect error type and extract key message from output
-    fn detect_error_type(&self, output: &str, exit_code: i32) -> (ErrorType, String) {
-        // Try pattern matching first
+    fn detect_error_type(
+        &self,
+        output: &str,
+        exit_code: i32,
+    ) -> (ErrorType, String, Option<PatternMatch>) {
+        // Try pattern matching first, giving up once the time budget is spent
+        let started = Instant::now();
         for pattern in &self.patterns {
+            if started.elapsed() > self.time_budget {
+                log::warn!(
+                    "Error pattern scan exceeded {:?}, falling back to exit code",
+                    self.time_budget
+                );
+                break;
+            }
+
             if let Some(captures) = pattern.regex.captures(output) {
                 let key_message = if pattern.key_group > 0 {
                     captures
@@ -313,15 +419,25 @@ impl ErrorDetector {
                 } else {
                     captures.get(0).unwrap().as_str().to_string()
                 };
-                return (pattern.error_type.clone(), key_message);
+                let matched = PatternMatch {
+                    regex: pattern.regex.as_str().to_string(),
+                    captures: captures
+                        .iter()
+                        .map(|m| m.map(|m| m.as_str().to_string()))
+                        .collect(),
+                };
+                return (pattern.error_type.clone(), key_message, Some(matched));
             }
         }
 
-        // Fall back to exit code
+        // Fall back to exit code, which also explains a failure with no output
         let error_type = ErrorType::from_exit_code(exit_code);
-        let key_message = self.extract_first_error_line(output);
+        let key_message = match ErrorType::describe_exit_code(exit_code) {
+            Some(meaning) if output.trim().is_empty() => meaning,
+            _ => self.extract_first_error_line(output),
+        };
 
-        (error_type, key_message)
+        (error_type, key_message, None)
     }
 
     /// Extract the first meaningful error line from output
@@ -419,10 +535,13 @@ mod tests {
     fn make_result(output: &str, exit_code: i32) -> PtyExecutionResult {
         PtyExecutionResult {
             output: output.to_string(),
+            stdout: None,
+            stderr: None,
             exit_code: Some(exit_code),
             duration: std::time::Duration::from_secs(0),
             command: "test command".to_string(),
             interrupted: false,
+            env_snapshot: None,
         }
     }
 
@@ -463,6 +582,23 @@ mod tests {
         assert_eq!(error.error_type, ErrorType::ConnectionRefused);
     }
 
+    #[test]
+    fn test_matched_pattern_recorded() {
+        let detector = ErrorDetector::new();
+        let result = make_result("nginx: [emerg] unknown directive \"proxy_passs\"", 1);
+
+        let error = detector.analyze(&result).unwrap();
+        let pattern = error.matched_pattern.unwrap();
+        assert!(pattern.regex.contains("emerg"));
+        assert_eq!(
+            pattern.captures[1].as_deref(),
+            Some("unknown directive \"proxy_passs\"")
+        );
+
+        let result = make_result("something odd happened", 1);
+        assert!(detector.analyze(&result).unwrap().matched_pattern.is_none());
+    }
+
     #[test]
     fn test_detect_nginx_config_error() {
         let detector = ErrorDetector::new();
@@ -493,6 +629,23 @@ mod tests {
         assert_eq!(error.error_type, ErrorType::KubernetesError);
     }
 
+    #[test]
+    fn test_detect_oom_killed_pod() {
+        let detector = ErrorDetector::new();
+        let result = make_result(
+            "error: timed out waiting for the condition\nweb-7d4b9c-x2k    0/1     OOMKilled   3          2m",
+            1,
+        );
+
+        let error = detector.analyze(&result).unwrap();
+        assert_eq!(error.error_type, ErrorType::KubernetesError);
+        assert!(error.key_message.starts_with("web-7d4b9c-x2k"));
+
+        let result = make_result("command terminated with exit code 137", 137);
+        let error = detector.analyze(&result).unwrap();
+        assert_eq!(error.error_type, ErrorType::KubernetesError);
+    }
+
     #[test]
     fn test_detect_port_in_use() {
         let detector = ErrorDetector::new();
@@ -523,10 +676,13 @@ mod tests {
         let detector = ErrorDetector::new();
         let result = PtyExecutionResult {
             output: "success".to_string(),
+            stdout: None,
+            stderr: None,
             exit_code: Some(0),
             duration: std::time::Duration::from_secs(0),
             command: "echo success".to_string(),
             interrupted: false,
+            env_snapshot: None,
         };
 
         assert!(detector.analyze(&result).is_none());
@@ -537,10 +693,13 @@ mod tests {
         let detector = ErrorDetector::new();
         let result = PtyExecutionResult {
             output: "^C".to_string(),
+            stdout: None,
+            stderr: None,
             exit_code: Some(130),
             duration: std::time::Duration::from_secs(0),
             command: "sleep 100".to_string(),
             interrupted: true,
+            env_snapshot: None,
         };
 
         assert!(detector.analyze(&result).is_none());
@@ -555,6 +714,66 @@ mod tests {
         assert_eq!(error.error_type, ErrorType::GitError);
     }
 
+    #[test]
+    fn test_prefers_stderr_when_separated() {
+        let detector = ErrorDetector::new();
+        let stdout = "audit: permission denied for guest (expected)\n";
+        let stderr = "fatal: not a git repository\n";
+        let result = PtyExecutionResult {
+            output: format!("{stdout}{stderr}"),
+            stdout: Some(stdout.to_string()),
+            stderr: Some(stderr.to_string()),
+            exit_code: Some(128),
+            duration: std::time::Duration::from_secs(0),
+            command: "git status".to_string(),
+            interrupted: false,
+            env_snapshot: None,
+        };
+
+        let error = detector.analyze(&result).unwrap();
+        assert_eq!(error.error_type, ErrorType::GitError);
+        // Full interleaved output is kept for display
+        assert!(error.full_output.contains("audit: permission denied"));
+    }
+
+    #[test]
+    fn test_detect_http_status() {
+        let detector = ErrorDetector::new();
+        let detect = |output: &str, exit_code: i32| {
+            let error = detector.analyze(&make_result(output, exit_code)).unwrap();
+            (error.error_type, error.key_message)
+        };
+
+        assert_eq!(
+            detect(
+                "HTTP/1.1 403 Forbidden\r\nContent-Type: text/html\r\n\r\nAccess denied",
+                22
+            ),
+            (ErrorType::AuthenticationFailed, "403".to_string())
+        );
+        assert_eq!(
+            detect("curl: (22) The requested URL returned error: 401", 22),
+            (ErrorType::AuthenticationFailed, "401".to_string())
+        );
+        assert_eq!(
+            detect("HTTP/2 404 \r\nserver: nginx\r\n", 22),
+            (ErrorType::ResourceNotFound, "404".to_string())
+        );
+        assert_eq!(
+            detect("http: warning: HTTP 429 Too Many Requests", 4),
+            (ErrorType::HttpError, "429".to_string())
+        );
+        assert_eq!(
+            detect("HTTP/1.1 502 Bad Gateway\r\n\r\nconnection refused", 22),
+            (ErrorType::HttpError, "502".to_string())
+        );
+        // Codes elsewhere in the output aren't status lines
+        assert_eq!(
+            detect("HTTP/1.1 200 OK\r\n\r\n{\"status\": 503}", 1).0,
+            ErrorType::Unknown
+        );
+    }
+
     #[test]
     fn test_dependency_error() {
         let detector = ErrorDetector::new();
@@ -563,4 +782,60 @@ mod tests {
         let error = detector.analyze(&result).unwrap();
         assert_eq!(error.error_type, ErrorType::DependencyError);
     }
+
+    #[test]
+    fn test_scans_head_and_tail_of_huge_output() {
+        let detector = ErrorDetector::new();
+        let output = format!(
+            "{}\ncat: /etc/shadow: Permission denied\n",
+            "x".repeat(10 * 1024 * 1024)
+        );
+
+        let error = detector.analyze(&make_result(&output, 1)).unwrap();
+        assert_eq!(error.error_type, ErrorType::PermissionDenied);
+        assert_eq!(error.full_output.len(), output.len());
+    }
+
+    #[test]
+    fn test_silent_crash_classified_by_exit_code() {
+        let detector = ErrorDetector::new();
+
+        let error = detector.analyze(&make_result("", 139)).unwrap();
+        assert_eq!(error.error_type, ErrorType::Crashed);
+        assert_eq!(
+            error.key_message,
+            "Crashed with SIGSEGV (exit 139 = 128 + 11)"
+        );
+
+        let error = detector.analyze(&make_result("", 124)).unwrap();
+        assert_eq!(error.error_type, ErrorType::Timeout);
+    }
+
+    #[test]
+    fn test_time_budget_falls_back_to_exit_code() {
+        let detector = ErrorDetector::new().with_time_budget(Duration::ZERO);
+        let result = make_result("bash: foo: command not found", 126);
+
+        let error = detector.analyze(&result).unwrap();
+        assert_eq!(error.error_type, ErrorType::from_exit_code(126));
+        assert!(error.matched_pattern.is_none());
+    }
+
+    #[test]
+    fn test_with_pattern() {
+        let detector = ErrorDetector::new()
+            .with_pattern(r"(?i)disk quota exceeded", ErrorType::DiskFull)
+            .unwrap();
+        let error = detector
+            .analyze(&make_result("cp: error writing: Disk quota exceeded", 1))
+            .unwrap();
+        assert_eq!(error.error_type, ErrorType::DiskFull);
+
+        assert!(ErrorDetector::new()
+            .with_pattern(r"\w{1000}{1000}", ErrorType::Unknown)
+            .is_err());
+        assert!(ErrorDetector::new()
+            .with_pattern(r"(unclosed", ErrorType::Unknown)
+            .is_err());
+    }
 }
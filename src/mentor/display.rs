@@ -3,12 +3,21 @@
 // Renders mentor guidance in a clear, educational format with
 // proper terminal styling and adaptive width.
 
-use super::colors::MentorColors;
+use super::colors::{ColorTheme, MentorColors, Palette};
+use super::engine::MentorEngine;
 use super::guidance::MentorGuidance;
-use super::types::ErrorInfo;
+use super::hyperlink::{hyperlink, LinkTarget};
+use super::i18n::{Label, Language, Phrase};
+use super::types::{ErrorInfo, SourceLocation};
+use crate::shell::TerminalSize;
+use crate::tools::NetworkTool;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Verbosity level for mentor display
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Verbosity {
     /// Full educational explanation with all details
     Verbose,
@@ -17,6 +26,8 @@ pub enum Verbosity {
     Normal,
     /// One-liner for experts
     Compact,
+    /// Verbose display plus the detector pattern that matched (for pattern authors)
+    Debug,
 }
 
 /// Configuration for mentor display
@@ -28,14 +39,34 @@ pub struct DisplayConfig {
     pub terminal_width: u16,
     /// Whether colors are enabled
     pub colors_enabled: bool,
+    /// Whether source locations are clickable OSC 8 links
+    pub hyperlinks: bool,
+    /// Named color theme
+    pub theme: ColorTheme,
+    /// Custom colors overriding single roles of the theme
+    pub palette: Palette,
+    /// Language of labels, explanations and next-step notes
+    pub language: Language,
+    /// Guidance this short (key message plus explanation, in characters)
+    /// with no next steps renders as a one-liner at Normal and Verbose;
+    /// 0 always uses the full box
+    pub compact_threshold: usize,
 }
 
+/// Default `compact_threshold`: what fits on the compact box's one line
+pub const DEFAULT_COMPACT_THRESHOLD: usize = 50;
+
 impl Default for DisplayConfig {
     fn default() -> Self {
         Self {
             verbosity: Verbosity::Normal,
             terminal_width: 0, // Auto-detect
             colors_enabled: std::env::var("NO_COLOR").is_err(),
+            hyperlinks: LinkTarget::detect().is_some(),
+            theme: ColorTheme::default(),
+            palette: Palette::default(),
+            language: Language::default(),
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
         }
     }
 }
@@ -44,6 +75,9 @@ impl Default for DisplayConfig {
 pub struct MentorDisplay {
     config: DisplayConfig,
     colors: MentorColors,
+    link_target: Option<LinkTarget>,
+    /// Width kept current by the shell's SIGWINCH handler
+    terminal_size: Option<TerminalSize>,
 }
 
 impl MentorDisplay {
@@ -54,8 +88,26 @@ impl MentorDisplay {
 
     /// Create display with custom config
     pub fn with_config(config: DisplayConfig) -> Self {
-        let colors = MentorColors::with_enabled(config.colors_enabled);
-        Self { config, colors }
+        let colors = MentorColors::with_palette(
+            config.colors_enabled,
+            &config.palette.over(&config.theme.palette()),
+        );
+        let link_target = config
+            .hyperlinks
+            .then(|| LinkTarget::detect().unwrap_or(LinkTarget::File));
+        Self {
+            config,
+            colors,
+            link_target,
+            terminal_size: None,
+        }
+    }
+
+    /// Take the terminal width from a resize-tracked size instead of
+    /// querying the terminal on every render
+    pub fn with_terminal_size(mut self, terminal_size: TerminalSize) -> Self {
+        self.terminal_size = Some(terminal_size);
+        self
     }
 
     /// Set verbosity level
@@ -66,35 +118,123 @@ impl MentorDisplay {
 
     /// Render error info as formatted string
     pub fn render(&self, error: &ErrorInfo) -> String {
-        match self.config.verbosity {
-            Verbosity::Verbose => self.render_verbose(error),
+        let mut output = match self.config.verbosity {
+            Verbosity::Verbose | Verbosity::Debug => self.render_verbose(error),
             Verbosity::Normal => self.render_normal(error),
             Verbosity::Compact => self.render_compact(error),
+        };
+
+        if self.debug_enabled() {
+            output.push_str(&self.render_debug(error));
         }
+
+        output
+    }
+
+    /// Whether pattern debug output is on
+    ///
+    /// Enabled by `Verbosity::Debug` or `KAIDO_MENTOR_DEBUG=1`.
+    pub fn debug_enabled(&self) -> bool {
+        self.config.verbosity == Verbosity::Debug
+            || std::env::var("KAIDO_MENTOR_DEBUG").is_ok_and(|v| v == "1")
+    }
+
+    /// Render which detector pattern matched and what it captured
+    pub fn render_debug(&self, error: &ErrorInfo) -> String {
+        let c = &self.colors;
+        let mut output = format!(
+            "{}  debug: error_type = {:?}{}\n",
+            c.dim(),
+            error.error_type,
+            c.reset()
+        );
+
+        match &error.matched_pattern {
+            Some(pattern) => {
+                output.push_str(&format!(
+                    "{}  debug: regex = {}{}\n",
+                    c.dim(),
+                    pattern.regex,
+                    c.reset()
+                ));
+                for (i, capture) in pattern.captures.iter().enumerate() {
+                    let value = match capture {
+                        Some(text) => format!("{text:?}"),
+                        None => "(no match)".to_string(),
+                    };
+                    output.push_str(&format!(
+                        "{}  debug: ${i} = {value}{}\n",
+                        c.dim(),
+                        c.reset()
+                    ));
+                }
+            }
+            None => {
+                output.push_str(&format!(
+                    "{}  debug: no pattern matched (classified from exit code {}){}\n",
+                    c.dim(),
+                    error.exit_code,
+                    c.reset()
+                ));
+            }
+        }
+
+        output
     }
 
     /// Render MentorGuidance as formatted string
     pub fn render_guidance(&self, guidance: &MentorGuidance) -> String {
+        if let Some(line) = self.one_line_guidance(guidance) {
+            return self.render_compact_box(&line);
+        }
         match self.config.verbosity {
-            Verbosity::Verbose => self.render_guidance_verbose(guidance),
+            Verbosity::Verbose | Verbosity::Debug => self.render_guidance_verbose(guidance),
             Verbosity::Normal => self.render_guidance_normal(guidance),
             Verbosity::Compact => self.render_guidance_compact(guidance),
         }
     }
 
+    /// Guidance as a single line, if it's short enough to skip the box
+    ///
+    /// Compact already is one line and Debug always gets the full output.
+    fn one_line_guidance(&self, guidance: &MentorGuidance) -> Option<String> {
+        if !matches!(
+            self.config.verbosity,
+            Verbosity::Normal | Verbosity::Verbose
+        ) || self.config.compact_threshold == 0
+            || !guidance.next_steps.is_empty()
+        {
+            return None;
+        }
+
+        let key = guidance.key_message.trim();
+        let explanation = guidance.explanation.trim();
+        let line = if explanation.is_empty() {
+            key.to_string()
+        } else {
+            format!("{key} — {explanation}")
+        };
+        (!line.contains('\n') && line.width() <= self.config.compact_threshold).then_some(line)
+    }
+
     /// Render compact guidance
     fn render_guidance_compact(&self, guidance: &MentorGuidance) -> String {
+        self.render_compact_box(&guidance.key_message)
+    }
+
+    /// One line of text in a small mentor box
+    fn render_compact_box(&self, text: &str) -> String {
         let c = &self.colors;
         let width = self.box_width().min(60);
         let inner_width = width - 4;
 
-        let key_msg = Self::truncate(&guidance.key_message, inner_width - 4);
+        let key_msg = Self::truncate(text, inner_width - 4);
 
         let mut output = String::new();
         output.push_str(&format!(
             "{}┌─ MENTOR {}┐{}\n",
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
         output.push_str(&format!(
@@ -103,7 +243,7 @@ impl MentorDisplay {
             c.key_message(),
             key_msg,
             c.reset(),
-            " ".repeat(inner_width.saturating_sub(key_msg.len())),
+            " ".repeat(inner_width.saturating_sub(Self::visible_length(&key_msg))),
             c.reset()
         ));
         output.push_str(&format!(
@@ -130,7 +270,7 @@ impl MentorDisplay {
             c.border(),
             c.title(),
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
 
@@ -141,12 +281,13 @@ impl MentorDisplay {
         output.push_str(&self.render_line(
             width,
             &format!(
-                "  {}Key:{} {}{}{}",
+                "  {}{label}{} {}{}{}",
                 c.error_type(),
                 c.reset(),
                 c.key_message(),
                 key_display,
-                c.reset()
+                c.reset(),
+                label = self.label(Label::Key)
             ),
         ));
 
@@ -162,9 +303,19 @@ impl MentorDisplay {
         // First next step if available
         if let Some(step) = guidance.next_steps.first() {
             let step_text = if let Some(ref cmd) = step.command {
-                format!("Try: {}{}{}", c.command(), cmd, c.reset())
+                format!(
+                    "{label} {}{}{}",
+                    c.command(),
+                    cmd,
+                    c.reset(),
+                    label = self.label(Label::Try)
+                )
             } else {
-                format!("Try: {}", step.description)
+                format!(
+                    "{label} {}",
+                    step.description,
+                    label = self.label(Label::Try)
+                )
             };
             output.push_str(&self.render_line(
                 width,
@@ -198,7 +349,7 @@ impl MentorDisplay {
             c.border(),
             c.title(),
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
 
@@ -209,16 +360,17 @@ impl MentorDisplay {
         output.push_str(&self.render_line(
             width,
             &format!(
-                "  {}Key:{} \"{}{}{}\"",
+                "  {}{label}{} \"{}{}{}\"",
                 c.error_type(),
                 c.reset(),
                 c.key_message(),
                 key_display,
-                c.reset()
+                c.reset(),
+                label = self.label(Label::Key)
             ),
         ));
 
-        let underline_len = key_display.len().min(inner_width - 12);
+        let underline_len = Self::visible_length(&key_display).min(inner_width - 12);
         output.push_str(&self.render_line(
             width,
             &format!(
@@ -232,8 +384,15 @@ impl MentorDisplay {
         output.push_str(&self.render_empty_line(width));
 
         // Explanation
-        output
-            .push_str(&self.render_line(width, &format!("  {}This means:{}", c.dim(), c.reset())));
+        output.push_str(&self.render_line(
+            width,
+            &format!(
+                "  {}{label}{}",
+                c.dim(),
+                c.reset(),
+                label = self.label(Label::ThisMeans)
+            ),
+        ));
         for line in Self::wrap_text(&guidance.explanation, inner_width - 6) {
             output.push_str(&self.render_line(width, &format!("    {line}")));
         }
@@ -245,16 +404,28 @@ impl MentorDisplay {
             let keywords = guidance.search_keywords.join(", ");
             output.push_str(&self.render_line(
                 width,
-                &format!("  {}Search:{} {}", c.search(), c.reset(), keywords),
+                &format!(
+                    "  {}{label}{} {}",
+                    c.search(),
+                    c.reset(),
+                    keywords,
+                    label = self.label(Label::Search)
+                ),
             ));
             output.push_str(&self.render_empty_line(width));
         }
 
         // Next steps
         if !guidance.next_steps.is_empty() {
-            output.push_str(
-                &self.render_line(width, &format!("  {}Next steps:{}", c.dim(), c.reset())),
-            );
+            output.push_str(&self.render_line(
+                width,
+                &format!(
+                    "  {}{label}{}",
+                    c.dim(),
+                    c.reset(),
+                    label = self.label(Label::NextSteps)
+                ),
+            ));
             for (i, step) in guidance.next_steps.iter().take(4).enumerate() {
                 let step_text = if let Some(ref cmd) = step.command {
                     format!("{}{}{}", c.command(), cmd, c.reset())
@@ -276,12 +447,13 @@ impl MentorDisplay {
             output.push_str(&self.render_line(
                 width,
                 &format!(
-                    "  {}Learn more:{} {}{}{}",
+                    "  {}{label}{} {}{}{}",
                     c.concept(),
                     c.reset(),
                     c.concept(),
                     concepts,
-                    c.reset()
+                    c.reset(),
+                    label = self.label(Label::LearnMore)
                 ),
             ));
             output.push_str(&self.render_empty_line(width));
@@ -298,10 +470,62 @@ impl MentorDisplay {
         output
     }
 
+    /// Render guidance as Markdown, for GitHub issues, chat and docs
+    ///
+    /// Plain text without ANSI codes or box drawing. Every next-step command
+    /// becomes a fenced `bash` block so it survives copy and paste.
+    pub fn render_markdown(&self, guidance: &MentorGuidance) -> String {
+        let mut output = String::from("### Key\n\n");
+        for line in guidance.key_message.lines() {
+            output.push_str(&format!("> {line}\n"));
+        }
+
+        output.push_str("\n### Explanation\n\n");
+        output.push_str(guidance.explanation.trim());
+        output.push('\n');
+
+        if !guidance.next_steps.is_empty() {
+            output.push_str("\n### Steps\n\n");
+            for (i, step) in guidance.next_steps.iter().enumerate() {
+                output.push_str(&format!("{}. {}\n", i + 1, step.description));
+                if let Some(ref cmd) = step.command {
+                    let fence = Self::markdown_fence(cmd);
+                    output.push_str(&format!("\n   {fence}bash\n"));
+                    for line in cmd.lines() {
+                        output.push_str(&format!("   {line}\n"));
+                    }
+                    output.push_str(&format!("   {fence}\n\n"));
+                }
+            }
+        }
+
+        if !guidance.search_keywords.is_empty() {
+            output.push_str("\n### Search\n\n");
+            output.push_str(&guidance.search_keywords.join(", "));
+            output.push('\n');
+        }
+
+        if !guidance.related_concepts.is_empty() {
+            output.push_str("\n### Learn more\n\n");
+            output.push_str(&guidance.related_concepts.join(", "));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// A backtick fence longer than any backtick run inside `code`
+    fn markdown_fence(code: &str) -> String {
+        let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        "`".repeat(longest.max(2) + 1)
+    }
+
     /// Get the box width based on terminal width
     fn box_width(&self) -> usize {
         let term_width = if self.config.terminal_width > 0 {
             self.config.terminal_width as usize
+        } else if let Some(size) = &self.terminal_size {
+            size.cols() as usize
         } else {
             // Try to detect terminal width
             terminal_size::terminal_size()
@@ -333,7 +557,7 @@ impl MentorDisplay {
         output.push_str(&format!(
             "{}┌─ MENTOR {}┐{}\n",
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
 
@@ -344,7 +568,7 @@ impl MentorDisplay {
             c.key_message(),
             key_msg,
             c.reset(),
-            " ".repeat(inner_width.saturating_sub(key_msg.len())),
+            " ".repeat(inner_width.saturating_sub(Self::visible_length(&key_msg))),
             c.reset()
         ));
 
@@ -373,7 +597,7 @@ impl MentorDisplay {
             c.border(),
             c.title(),
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
 
@@ -384,10 +608,11 @@ impl MentorDisplay {
         output.push_str(&self.render_line(
             width,
             &format!(
-                "  {}Type:{} {}",
+                "  {}{label}{} {}",
                 c.dim(),
                 c.reset(),
-                error.error_type.name()
+                error.error_type.name(),
+                label = self.label(Label::Type)
             ),
         ));
 
@@ -396,28 +621,29 @@ impl MentorDisplay {
         output.push_str(&self.render_line(
             width,
             &format!(
-                "  {}Key:{} {}{}{}",
+                "  {}{label}{} {}{}{}",
                 c.error_type(),
                 c.reset(),
                 c.key_message(),
                 key_display,
-                c.reset()
+                c.reset(),
+                label = self.label(Label::Key)
             ),
         ));
 
         // Source location if available
         if let Some(ref loc) = error.source_location {
-            let loc_str = loc.to_string();
-            let loc_display = Self::truncate(&loc_str, inner_width - 14);
+            let loc_display = Self::truncate(&loc.to_string(), inner_width - 14);
             output.push_str(&self.render_line(
                 width,
                 &format!(
-                    "  {}Location:{} {}{}{}",
+                    "  {}{label}{} {}{}{}",
                     c.location(),
                     c.reset(),
                     c.location(),
-                    loc_display,
-                    c.reset()
+                    self.link_location(loc, &loc_display),
+                    c.reset(),
+                    label = self.label(Label::Location)
                 ),
             ));
         }
@@ -429,7 +655,13 @@ impl MentorDisplay {
         if let Some(suggestion) = self.get_quick_suggestion(error) {
             output.push_str(&self.render_line(
                 width,
-                &format!("  {}Try:{} {}", c.search(), c.reset(), suggestion),
+                &format!(
+                    "  {}{label}{} {}",
+                    c.search(),
+                    c.reset(),
+                    suggestion,
+                    label = self.label(Label::Try)
+                ),
             ));
             output.push_str(&self.render_empty_line(width));
         }
@@ -459,7 +691,7 @@ impl MentorDisplay {
             c.border(),
             c.title(),
             c.border(),
-            "─".repeat(width - 12),
+            "─".repeat(width - 11),
             c.reset()
         ));
 
@@ -471,17 +703,18 @@ impl MentorDisplay {
         output.push_str(&self.render_line(
             width,
             &format!(
-                "  {}Key:{} \"{}{}{}\"",
+                "  {}{label}{} \"{}{}{}\"",
                 c.error_type(),
                 c.reset(),
                 c.key_message(),
                 key_display,
-                c.reset()
+                c.reset(),
+                label = self.label(Label::Key)
             ),
         ));
 
         // Underline for emphasis
-        let underline_len = key_display.len().min(inner_width - 12);
+        let underline_len = Self::visible_length(&key_display).min(inner_width - 12);
         output.push_str(&self.render_line(
             width,
             &format!(
@@ -497,9 +730,15 @@ impl MentorDisplay {
 
         // Error explanation
         if let Some(explanation) = self.get_error_explanation(error) {
-            output.push_str(
-                &self.render_line(width, &format!("  {}This means:{}", c.dim(), c.reset())),
-            );
+            output.push_str(&self.render_line(
+                width,
+                &format!(
+                    "  {}{label}{}",
+                    c.dim(),
+                    c.reset(),
+                    label = self.label(Label::ThisMeans)
+                ),
+            ));
             for line in Self::wrap_text(&explanation, inner_width - 6) {
                 output.push_str(&self.render_line(width, &format!("    {line}")));
             }
@@ -508,16 +747,16 @@ impl MentorDisplay {
 
         // Source location if available
         if let Some(ref loc) = error.source_location {
-            let loc_str = loc.to_string();
             output.push_str(&self.render_line(
                 width,
                 &format!(
-                    "  {}Location:{} {}{}{}",
+                    "  {}{label}{} {}{}{}",
                     c.location(),
                     c.reset(),
                     c.location(),
-                    loc_str,
-                    c.reset()
+                    self.link_location(loc, &loc.to_string()),
+                    c.reset(),
+                    label = self.label(Label::Location)
                 ),
             ));
             output.push_str(&self.render_empty_line(width));
@@ -527,7 +766,13 @@ impl MentorDisplay {
         if let Some(search) = self.get_search_suggestion(error) {
             output.push_str(&self.render_line(
                 width,
-                &format!("  {}Search:{} {}", c.search(), c.reset(), search),
+                &format!(
+                    "  {}{label}{} {}",
+                    c.search(),
+                    c.reset(),
+                    search,
+                    label = self.label(Label::Search)
+                ),
             ));
             output.push_str(&self.render_empty_line(width));
         }
@@ -535,9 +780,15 @@ impl MentorDisplay {
         // Next steps
         let steps = self.get_next_steps(error);
         if !steps.is_empty() {
-            output.push_str(
-                &self.render_line(width, &format!("  {}Next steps:{}", c.dim(), c.reset())),
-            );
+            output.push_str(&self.render_line(
+                width,
+                &format!(
+                    "  {}{label}{}",
+                    c.dim(),
+                    c.reset(),
+                    label = self.label(Label::NextSteps)
+                ),
+            ));
             for (i, step) in steps.iter().enumerate() {
                 let step_display = Self::truncate(step, inner_width - 8);
                 output.push_str(&self.render_line(
@@ -560,12 +811,13 @@ impl MentorDisplay {
             output.push_str(&self.render_line(
                 width,
                 &format!(
-                    "  {}Learn more:{} {}{}{}",
+                    "  {}{label}{} {}{}{}",
                     c.concept(),
                     c.reset(),
                     c.concept(),
                     concepts,
-                    c.reset()
+                    c.reset(),
+                    label = self.label(Label::LearnMore)
                 ),
             ));
             output.push_str(&self.render_empty_line(width));
@@ -593,6 +845,14 @@ impl MentorDisplay {
     }
 
     /// Render a content line within the box
+    /// Make `text` a link to `location` when hyperlinks are on
+    fn link_location(&self, location: &SourceLocation, text: &str) -> String {
+        match self.link_target {
+            Some(target) => hyperlink(&target.url(location), text),
+            None => text.to_string(),
+        }
+    }
+
     fn render_line(&self, width: usize, content: &str) -> String {
         // Calculate visible length (without ANSI codes)
         let visible_len = Self::visible_length(content);
@@ -608,38 +868,68 @@ impl MentorDisplay {
         )
     }
 
-    /// Calculate visible length of string (excluding ANSI codes)
+    /// Calculate visible width of string in terminal columns (excluding ANSI codes)
+    ///
+    /// Wide glyphs such as CJK characters and most emoji take two columns.
+    /// OSC sequences (hyperlinks) run to their `ESC \\` or BEL terminator.
     fn visible_length(s: &str) -> usize {
         let mut len = 0;
-        let mut in_escape = false;
-
-        for c in s.chars() {
-            if c == '\x1b' {
-                in_escape = true;
-            } else if in_escape {
-                if c == 'm' {
-                    in_escape = false;
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                len += c.width().unwrap_or(0);
+            } else if chars.next_if_eq(&']').is_some() {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
                 }
             } else {
-                len += 1;
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
             }
         }
 
         len
     }
 
-    /// Truncate string to max length with ellipsis
-    fn truncate(s: &str, max_len: usize) -> String {
-        if s.len() <= max_len {
-            s.to_string()
-        } else if max_len > 3 {
-            format!("{}...", &s[..max_len - 3])
+    /// Truncate string to max display width with ellipsis
+    ///
+    /// Cuts on grapheme boundaries so combined characters are never split.
+    pub(crate) fn truncate(s: &str, max_len: usize) -> String {
+        if s.width() <= max_len {
+            return s.to_string();
+        }
+
+        let (budget, ellipsis) = if max_len > 3 {
+            (max_len - 3, "...")
         } else {
-            s[..max_len].to_string()
+            (max_len, "")
+        };
+
+        let mut result = String::new();
+        let mut used = 0;
+        for grapheme in s.graphemes(true) {
+            let w = grapheme.width();
+            if used + w > budget {
+                break;
+            }
+            result.push_str(grapheme);
+            used += w;
         }
+        result.push_str(ellipsis);
+        result
     }
 
-    /// Wrap text to fit within width
+    /// Wrap text to fit within display width
+    ///
+    /// Widths are visible columns, so embedded ANSI codes don't count.
+    /// Words wider than the line (URLs, long resource names, unspaced CJK
+    /// text) are broken with [`Self::break_line`].
     fn wrap_text(text: &str, width: usize) -> Vec<String> {
         let mut lines = Vec::new();
         let mut current_line = String::new();
@@ -647,13 +937,20 @@ impl MentorDisplay {
         for word in text.split_whitespace() {
             if current_line.is_empty() {
                 current_line = word.to_string();
-            } else if current_line.len() + 1 + word.len() <= width {
+            } else if Self::visible_length(&current_line) + 1 + Self::visible_length(word) <= width
+            {
                 current_line.push(' ');
                 current_line.push_str(word);
             } else {
                 lines.push(current_line);
                 current_line = word.to_string();
             }
+
+            while width > 0 && Self::visible_length(&current_line) > width {
+                let (head, rest) = Self::break_line(&current_line, width);
+                lines.push(head);
+                current_line = rest;
+            }
         }
 
         if !current_line.is_empty() {
@@ -663,10 +960,105 @@ impl MentorDisplay {
         lines
     }
 
+    /// Split a line wider than `width` columns in two
+    ///
+    /// Breaks after the last hyphen or slash in the second half of the
+    /// line if there is one (`api-gateway-7d9f8-`, `https://host/path/`),
+    /// otherwise at the last grapheme that fits. ANSI sequences are never
+    /// split; a color still active at the break is reset on the first part
+    /// and restarted on the rest.
+    fn break_line(line: &str, width: usize) -> (String, String) {
+        let units = Self::display_units(line);
+        let mut used = 0;
+        let mut fit = 0;
+        let mut soft_break = None;
+        for (i, unit) in units.iter().enumerate() {
+            let w = if unit.starts_with('\x1b') {
+                0
+            } else {
+                unit.width()
+            };
+            if used + w > width && used > 0 {
+                break;
+            }
+            used += w;
+            fit = i + 1;
+            if matches!(*unit, "-" | "/") && used * 2 >= width {
+                soft_break = Some(fit);
+            }
+        }
+
+        let split = soft_break.unwrap_or(fit);
+        let mut head = units[..split].concat();
+        let mut rest = units[split..].concat();
+        let active_color = units[..split]
+            .iter()
+            .rev()
+            .find(|unit| unit.starts_with("\x1b[") && unit.ends_with('m'));
+        if let Some(color) = active_color.filter(|c| !matches!(**c, "\x1b[0m" | "\x1b[m")) {
+            head.push_str("\x1b[0m");
+            rest.insert_str(0, color);
+        }
+        (head, rest)
+    }
+
+    /// Graphemes of `text`, with each ANSI escape sequence kept whole
+    fn display_units(text: &str) -> Vec<&str> {
+        let mut units = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            match rest.find('\x1b') {
+                Some(0) => {
+                    let len = Self::escape_len(rest);
+                    units.push(&rest[..len]);
+                    rest = &rest[len..];
+                }
+                Some(start) => {
+                    units.extend(rest[..start].graphemes(true));
+                    rest = &rest[start..];
+                }
+                None => {
+                    units.extend(rest.graphemes(true));
+                    break;
+                }
+            }
+        }
+        units
+    }
+
+    /// Byte length of the escape sequence at the start of `s`
+    ///
+    /// OSC sequences (hyperlinks) run to BEL or `ESC \\`, others to the
+    /// final `m` of an SGR code.
+    fn escape_len(s: &str) -> usize {
+        let bytes = s.as_bytes();
+        if bytes.get(1) == Some(&b']') {
+            let mut i = 2;
+            while i < bytes.len() {
+                if bytes[i] == 0x07 {
+                    return i + 1;
+                }
+                if bytes[i] == 0x1b && bytes.get(i + 1) == Some(&b'\\') {
+                    return i + 2;
+                }
+                i += 1;
+            }
+            bytes.len()
+        } else {
+            s.find('m').map_or(s.len(), |i| i + 1)
+        }
+    }
+
+    /// Label text in the configured language
+    fn label(&self, label: Label) -> &'static str {
+        self.config.language.label(label)
+    }
+
     /// Get a quick suggestion for the error
     fn get_quick_suggestion(&self, error: &ErrorInfo) -> Option<String> {
         use super::types::ErrorType;
 
+        let lang = self.config.language;
         match error.error_type {
             ErrorType::CommandNotFound => {
                 let cmd = error
@@ -674,64 +1066,30 @@ impl MentorDisplay {
                     .split_whitespace()
                     .last()
                     .unwrap_or("command");
-                Some(format!("which {cmd} or brew install {cmd}"))
+                Some(format!(
+                    "which {cmd} {} brew install {cmd}",
+                    lang.phrase(Phrase::Or)
+                ))
             }
             ErrorType::PermissionDenied => Some("sudo !!".to_string()),
-            ErrorType::FileNotFound => Some("ls -la to check path".to_string()),
-            ErrorType::ConnectionRefused => Some("Check if service is running".to_string()),
-            ErrorType::PortInUse => Some("lsof -i :<port> to find process".to_string()),
+            ErrorType::FileNotFound => Some(format!("ls -la {}", lang.phrase(Phrase::ToCheckPath))),
+            ErrorType::ConnectionRefused => {
+                Some(lang.phrase(Phrase::CheckServiceRunning).to_string())
+            }
+            ErrorType::PortInUse => Some(format!(
+                "lsof -i :<port> {}",
+                lang.phrase(Phrase::ToFindProcess)
+            )),
             _ => None,
         }
     }
 
     /// Get detailed explanation for error type
     fn get_error_explanation(&self, error: &ErrorInfo) -> Option<String> {
-        use super::types::ErrorType;
-
-        match error.error_type {
-            ErrorType::CommandNotFound => Some(
-                "The shell cannot find this command. It's either not installed, \
-                 or not in your PATH environment variable."
-                    .to_string(),
-            ),
-            ErrorType::PermissionDenied => Some(
-                "You don't have permission to perform this action. \
-                 This usually means you need elevated privileges (sudo) \
-                 or the file/directory permissions need to be changed."
-                    .to_string(),
-            ),
-            ErrorType::FileNotFound => Some(
-                "The specified file or directory doesn't exist. \
-                 Check the path for typos or verify the file was created."
-                    .to_string(),
-            ),
-            ErrorType::SyntaxError => Some(
-                "There's a syntax error in the command or configuration file. \
-                 Check for typos, missing quotes, or incorrect formatting."
-                    .to_string(),
-            ),
-            ErrorType::ConnectionRefused => Some(
-                "The connection was refused by the target. \
-                 The service might not be running, or a firewall is blocking it."
-                    .to_string(),
-            ),
-            ErrorType::ConfigurationError => Some(
-                "There's an error in a configuration file. \
-                 Check the file for typos or invalid directives."
-                    .to_string(),
-            ),
-            ErrorType::PortInUse => Some(
-                "Another process is already using this port. \
-                 You'll need to stop that process or use a different port."
-                    .to_string(),
-            ),
-            ErrorType::DependencyError => Some(
-                "A required module or dependency is missing. \
-                 You may need to install it or check your import paths."
-                    .to_string(),
-            ),
-            _ => None,
-        }
+        self.config
+            .language
+            .explanation(&error.error_type)
+            .map(str::to_string)
     }
 
     /// Get search suggestion for the error
@@ -762,6 +1120,7 @@ impl MentorDisplay {
     fn get_next_steps(&self, error: &ErrorInfo) -> Vec<String> {
         use super::types::ErrorType;
 
+        let lang = self.config.language;
         match error.error_type {
             ErrorType::CommandNotFound => {
                 let cmd = error
@@ -777,30 +1136,47 @@ impl MentorDisplay {
             }
             ErrorType::PermissionDenied => {
                 vec![
-                    "sudo !! (run last command as root)".to_string(),
-                    "ls -la <file> (check permissions)".to_string(),
-                    "chmod +x <file> (if executable)".to_string(),
+                    format!("sudo !! ({})", lang.phrase(Phrase::RunAsRoot)),
+                    format!("ls -la <file> ({})", lang.phrase(Phrase::CheckPermissions)),
+                    format!("chmod +x <file> ({})", lang.phrase(Phrase::IfExecutable)),
                 ]
             }
             ErrorType::FileNotFound => {
                 vec![
-                    "ls -la (list current directory)".to_string(),
-                    "pwd (print working directory)".to_string(),
+                    format!("ls -la ({})", lang.phrase(Phrase::ListDirectory)),
+                    format!("pwd ({})", lang.phrase(Phrase::PrintDirectory)),
                     "find . -name '<filename>'".to_string(),
                 ]
             }
             ErrorType::PortInUse => {
-                vec![
-                    "lsof -i :<port> (find process)".to_string(),
-                    "kill <pid> (stop process)".to_string(),
-                    "Use a different port".to_string(),
-                ]
+                let port = MentorEngine::extract_port(error);
+                let holders = port.map(NetworkTool::who_uses_port).unwrap_or_default();
+                let mut steps: Vec<String> = holders
+                    .iter()
+                    .map(|holder| {
+                        format!(
+                            "{} ({} {holder})",
+                            holder.kill_command(),
+                            lang.phrase(Phrase::Stop)
+                        )
+                    })
+                    .collect();
+                if holders.is_empty() {
+                    let port = port.map_or("<port>".to_string(), |p| p.to_string());
+                    steps.push(format!(
+                        "lsof -i :{port} ({})",
+                        lang.phrase(Phrase::FindProcess)
+                    ));
+                    steps.push(format!("kill <pid> ({})", lang.phrase(Phrase::StopProcess)));
+                }
+                steps.push(lang.phrase(Phrase::UseDifferentPort).to_string());
+                steps
             }
             ErrorType::ConnectionRefused => {
                 vec![
-                    "Check if service is running".to_string(),
-                    "Verify the host and port".to_string(),
-                    "Check firewall settings".to_string(),
+                    lang.phrase(Phrase::CheckServiceRunning).to_string(),
+                    lang.phrase(Phrase::VerifyHostAndPort).to_string(),
+                    lang.phrase(Phrase::CheckFirewall).to_string(),
                 ]
             }
             _ => {
@@ -819,27 +1195,10 @@ impl MentorDisplay {
 
     /// Get learning concepts related to the error
     fn get_learning_concepts(&self, error: &ErrorInfo) -> Option<String> {
-        use super::types::ErrorType;
-
-        match error.error_type {
-            ErrorType::CommandNotFound => Some("PATH environment, package managers".to_string()),
-            ErrorType::PermissionDenied => {
-                Some("Unix permissions, sudo, file ownership".to_string())
-            }
-            ErrorType::FileNotFound => {
-                Some("file paths, working directory, ls command".to_string())
-            }
-            ErrorType::ConnectionRefused => Some("networking, ports, services".to_string()),
-            ErrorType::ConfigurationError => {
-                Some("configuration files, syntax checking".to_string())
-            }
-            ErrorType::DockerError => Some("Docker containers, images, volumes".to_string()),
-            ErrorType::KubernetesError => {
-                Some("Kubernetes pods, deployments, services".to_string())
-            }
-            ErrorType::GitError => Some("Git workflow, branches, commits".to_string()),
-            _ => None,
-        }
+        self.config
+            .language
+            .concepts(&error.error_type)
+            .map(str::to_string)
     }
 }
 
@@ -852,7 +1211,8 @@ impl Default for MentorDisplay {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mentor::types::{ErrorType, SourceLocation};
+    use crate::mentor::guidance::NextStep;
+    use crate::mentor::types::{ErrorType, PatternMatch, SourceLocation};
 
     fn create_test_error() -> ErrorInfo {
         ErrorInfo::new(
@@ -908,6 +1268,25 @@ mod tests {
         assert!(output.contains("Learn more:"));
     }
 
+    #[test]
+    fn test_verbose_render_traditional_chinese() {
+        let display = MentorDisplay::with_config(DisplayConfig {
+            language: Language::TraditionalChinese,
+            colors_enabled: false,
+            ..Default::default()
+        })
+        .with_verbosity(Verbosity::Verbose);
+        let output = display.render(&create_test_error());
+
+        assert!(output.contains("這表示："));
+        assert!(output.contains("下一步："));
+        assert!(output.contains("PATH 環境變數"));
+        // Commands stay as they are
+        assert!(output.contains("which "));
+        assert!(!output.contains("This means:"));
+        assert_aligned_borders(&output);
+    }
+
     #[test]
     fn test_render_with_location() {
         let display = MentorDisplay::new().with_verbosity(Verbosity::Normal);
@@ -940,6 +1319,209 @@ mod tests {
         assert!(lines[0].len() <= 12);
     }
 
+    #[test]
+    fn test_visible_length_wide_chars() {
+        assert_eq!(MentorDisplay::visible_length("連線失敗"), 8);
+        assert_eq!(MentorDisplay::visible_length("\x1b[31m錯誤\x1b[0m: x"), 7);
+        assert_eq!(MentorDisplay::visible_length("🚀 go"), 5);
+    }
+
+    #[test]
+    fn test_truncate_wide_chars() {
+        // Each CJK char is 2 columns: 2 chars + "..." fits in 7
+        assert_eq!(MentorDisplay::truncate("無法連線到伺服器", 7), "無法...");
+        assert!(MentorDisplay::visible_length(&MentorDisplay::truncate("🚀🚀🚀🚀🚀", 6)) <= 6);
+        // Combining accent stays with its base character
+        assert_eq!(
+            MentorDisplay::truncate("cafe\u{301}s au lait", 7),
+            "cafe\u{301}..."
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_unspaced_cjk() {
+        let lines = MentorDisplay::wrap_text("設定檔中有未知的指令請檢查語法", 10);
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(MentorDisplay::visible_length(line) <= 10);
+        }
+        assert_eq!(lines.concat(), "設定檔中有未知的指令請檢查語法");
+    }
+
+    const LONG_URL: &str = "https://kubernetes.io/docs/concepts/workloads/pods/pod-lifecycle/#container-restarts-and-exp-backoff";
+
+    #[test]
+    fn test_wrap_text_breaks_long_url() {
+        assert_eq!(LONG_URL.len(), 100);
+        let lines = MentorDisplay::wrap_text(&format!("See {LONG_URL} for details"), 40);
+        for line in &lines {
+            assert!(MentorDisplay::visible_length(line) <= 40, "{line}");
+        }
+        // Broken after slashes, and nothing lost
+        assert!(lines[1].ends_with('/'), "{lines:?}");
+        assert_eq!(
+            lines.concat().replace(' ', ""),
+            format!("See{LONG_URL}fordetails")
+        );
+    }
+
+    #[test]
+    fn test_wrap_text_prefers_hyphens() {
+        let lines =
+            MentorDisplay::wrap_text("pod api-gateway-deployment-7d9f8b6c5d-x2x9q is pending", 24);
+        assert_eq!(lines[0], "pod");
+        assert_eq!(lines[1], "api-gateway-deployment-");
+        assert_eq!(lines[2], "7d9f8b6c5d-x2x9q is");
+    }
+
+    #[test]
+    fn test_wrap_text_ignores_and_keeps_ansi() {
+        let colored = format!("\x1b[36m{}\x1b[0m", "x".repeat(30));
+        let lines = MentorDisplay::wrap_text(&format!("run {colored} now"), 20);
+        assert_eq!(lines[0], "run");
+        // The color is closed at the break and restarted on the next line
+        assert_eq!(lines[1], format!("\x1b[36m{}\x1b[0m", "x".repeat(20)));
+        assert_eq!(lines[2], format!("\x1b[36m{}\x1b[0m now", "x".repeat(10)));
+    }
+
+    #[test]
+    fn test_long_url_stays_inside_the_box() {
+        let display = MentorDisplay::with_config(DisplayConfig {
+            colors_enabled: false,
+            verbosity: Verbosity::Verbose,
+            terminal_width: 60,
+            ..Default::default()
+        });
+        let guidance = MentorGuidance::from_pattern(
+            "Back-off restarting failed container",
+            format!("The container keeps crashing; see {LONG_URL}"),
+        );
+        assert_aligned_borders(&display.render_guidance(&guidance));
+    }
+
+    #[test]
+    fn test_short_guidance_auto_compacts() {
+        let config = DisplayConfig {
+            colors_enabled: false,
+            terminal_width: 80,
+            ..Default::default()
+        };
+        let short = MentorGuidance::from_pattern("Permission denied", "");
+        let mut with_step = short.clone();
+        with_step
+            .next_steps
+            .push(NextStep::new("Check the file owner"));
+
+        for verbosity in [Verbosity::Normal, Verbosity::Verbose] {
+            let display = MentorDisplay::with_config(config.clone()).with_verbosity(verbosity);
+            let output = display.render_guidance(&short);
+            assert_eq!(output.lines().count(), 3, "{output}");
+            assert!(output.contains("Permission denied"));
+            assert!(display.render_guidance(&with_step).lines().count() > 3);
+        }
+
+        // Turned off, or too long for one line
+        let off = MentorDisplay::with_config(DisplayConfig {
+            compact_threshold: 0,
+            ..config.clone()
+        });
+        assert!(off.render_guidance(&short).lines().count() > 3);
+        let long = MentorGuidance::fallback("Permission denied");
+        let display = MentorDisplay::with_config(config);
+        assert!(display.render_guidance(&long).lines().count() > 3);
+    }
+
+    fn assert_aligned_borders(output: &str) {
+        let box_lines: Vec<&str> = output
+            .lines()
+            .filter(|l| l.starts_with('│') || l.starts_with('┌') || l.starts_with('└'))
+            .collect();
+        assert!(!box_lines.is_empty());
+
+        let expected = MentorDisplay::visible_length(box_lines[0]);
+        for line in &box_lines {
+            assert_eq!(
+                MentorDisplay::visible_length(line),
+                expected,
+                "misaligned line: {line:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_borders_aligned_with_cjk_and_emoji() {
+        let config = DisplayConfig {
+            colors_enabled: false,
+            ..Default::default()
+        };
+        let error = ErrorInfo::new(
+            ErrorType::ConfigurationError,
+            1,
+            "設定檔錯誤：找不到指令 🚀 proxy_passs 在第四十二行附近，請檢查設定檔的語法是否正確",
+            "nginx -t",
+        )
+        .with_location(SourceLocation::new("/etc/nginx/站台.conf").with_line(42));
+
+        for verbosity in [Verbosity::Compact, Verbosity::Normal, Verbosity::Verbose] {
+            let display = MentorDisplay::with_config(config.clone()).with_verbosity(verbosity);
+            assert_aligned_borders(&display.render(&error));
+        }
+    }
+
+    #[test]
+    fn test_location_hyperlink() {
+        let config = DisplayConfig {
+            colors_enabled: false,
+            hyperlinks: true,
+            ..Default::default()
+        };
+        let error = create_error_with_location();
+
+        for verbosity in [Verbosity::Normal, Verbosity::Verbose] {
+            let display = MentorDisplay::with_config(config.clone()).with_verbosity(verbosity);
+            let output = display.render(&error);
+            assert!(output.contains("\x1b]8;;"));
+            assert!(output.contains("/etc/nginx/nginx.conf:42"));
+            assert_aligned_borders(&output);
+        }
+
+        let plain = MentorDisplay::with_config(DisplayConfig {
+            hyperlinks: false,
+            ..config
+        });
+        assert!(!plain.render(&error).contains("\x1b]8;;"));
+    }
+
+    #[test]
+    fn test_debug_render_shows_pattern() {
+        let config = DisplayConfig {
+            colors_enabled: false,
+            ..Default::default()
+        };
+        let display = MentorDisplay::with_config(config).with_verbosity(Verbosity::Debug);
+        let error = create_error_with_location().with_pattern_match(PatternMatch {
+            regex: r"nginx:\s*\[emerg\]\s*(.+)".to_string(),
+            captures: vec![
+                Some("nginx: [emerg] unknown directive".to_string()),
+                Some("unknown directive".to_string()),
+            ],
+        });
+        let output = display.render(&error);
+
+        assert!(output.contains("MENTOR"));
+        assert!(output.contains("error_type = ConfigurationError"));
+        assert!(output.contains(r"regex = nginx:\s*\[emerg\]\s*(.+)"));
+        assert!(output.contains("$1 = \"unknown directive\""));
+    }
+
+    #[test]
+    fn test_debug_render_without_pattern() {
+        let display = MentorDisplay::new().with_verbosity(Verbosity::Debug);
+        let output = display.render_debug(&create_test_error());
+
+        assert!(output.contains("no pattern matched (classified from exit code 127)"));
+    }
+
     #[test]
     fn test_no_color() {
         let config = DisplayConfig {
@@ -973,4 +1555,45 @@ mod tests {
         let display = MentorDisplay::with_config(config);
         assert_eq!(display.box_width(), 66); // 70 - 4 margin
     }
+
+    #[test]
+    fn test_box_width_follows_resize() {
+        let size = TerminalSize::new();
+        size.set(70, 24);
+        let display = MentorDisplay::new().with_terminal_size(size.clone());
+        assert_eq!(display.box_width(), 66);
+
+        // A split pane shrinks the terminal between renders
+        size.set(50, 24);
+        assert_eq!(display.box_width(), 50);
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let mut guidance = MentorGuidance::from_pattern(
+            "command not found: kubectl",
+            "The shell can't find kubectl on your PATH.",
+        );
+        guidance.next_steps = vec![
+            NextStep::with_command("Check where it is", "which kubectl"),
+            NextStep::new("Reopen your terminal after installing"),
+        ];
+        guidance.related_concepts = vec!["PATH environment".to_string()];
+
+        let output = MentorDisplay::new().render_markdown(&guidance);
+
+        assert!(!output.contains('\x1b'));
+        assert!(output.starts_with("### Key\n\n> command not found: kubectl\n"));
+        assert!(output.contains("### Explanation\n\nThe shell can't find kubectl"));
+        assert!(output.contains("1. Check where it is\n\n   ```bash\n   which kubectl\n   ```\n"));
+        assert!(output.contains("2. Reopen your terminal after installing\n"));
+        assert!(output.contains("### Learn more\n\nPATH environment\n"));
+        assert!(!output.contains("### Search"));
+    }
+
+    #[test]
+    fn test_markdown_fence_outlasts_backticks() {
+        assert_eq!(MentorDisplay::markdown_fence("ls"), "```");
+        assert_eq!(MentorDisplay::markdown_fence("echo ```"), "````");
+    }
 }
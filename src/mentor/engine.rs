@@ -2,15 +2,45 @@
 //
 // Core engine that generates educational guidance for errors.
 // Uses pattern matching first (fast), falls back to LLM for unknown errors.
+//
+// A failing script can produce the same error many times in a second.
+// Identical errors share one in-flight request (single flight) and reuse
+// its result for a short window, and a semaphore caps concurrent LLM calls.
 
+use regex::Regex;
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, Semaphore};
 
 use super::cache::GuidanceCache;
 use super::display::MentorDisplay;
 use super::guidance::{GuidanceSource, MentorGuidance, NextStep};
+use super::i18n::Language;
 use super::llm_fallback::LLMMentor;
+use super::platform::PackageManager;
 use super::types::{ErrorInfo, ErrorType};
-use crate::tools::LLMBackend;
+use crate::shell::builtins::BUILTIN_NAMES;
+use crate::tools::{LLMBackend, NetworkTool, ProcessInfo, ToolRegistry, WebServer};
+
+/// Largest edit distance still treated as a likely typo
+const TYPO_MAX_DISTANCE: usize = 2;
+
+/// Conflicted files that get their own --ours/--theirs steps
+const MAX_CONFLICT_STEPS: usize = 3;
+
+/// Quote a file name for a suggested shell command when it needs it
+fn shell_quote(name: &str) -> String {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "/._-+@:".contains(c))
+    {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', r"'\''"))
+    }
+}
 
 /// Configuration for the mentor engine
 #[derive(Debug, Clone)]
@@ -21,6 +51,16 @@ pub struct MentorConfig {
     pub cache_path: Option<PathBuf>,
     /// Cache retention in days
     pub cache_retention_days: u32,
+    /// Most LLM calls allowed at once
+    pub max_concurrent_llm: usize,
+    /// How long guidance for an error is reused for identical errors
+    pub dedup_window: Duration,
+    /// After an LLM call for an error, how long errors with the same
+    /// fingerprint (differing only in paths, IDs, ...) get pattern or
+    /// cached guidance without another call
+    pub llm_cooldown: Duration,
+    /// Language LLM guidance is asked for in
+    pub language: Language,
 }
 
 impl Default for MentorConfig {
@@ -29,15 +69,31 @@ impl Default for MentorConfig {
             enable_llm: true,
             cache_path: dirs::home_dir().map(|h| h.join(".kaido").join("mentor_cache.db")),
             cache_retention_days: 30,
+            max_concurrent_llm: 2,
+            dedup_window: Duration::from_secs(30),
+            llm_cooldown: Duration::from_secs(10 * 60),
+            language: Language::default(),
         }
     }
 }
 
+/// Guidance generation shared by identical errors
+struct Flight {
+    started: Instant,
+    guidance: OnceCell<MentorGuidance>,
+}
+
 /// The main mentor engine
 pub struct MentorEngine {
     config: MentorConfig,
     cache: Option<GuidanceCache>,
     display: MentorDisplay,
+    /// Recent and in-flight generations by language, error type and message
+    flights: Mutex<HashMap<String, Arc<Flight>>>,
+    /// Permits for concurrent LLM calls
+    llm_permits: Semaphore,
+    /// When each error fingerprint last went to the LLM
+    llm_calls: Mutex<HashMap<u64, Instant>>,
 }
 
 impl MentorEngine {
@@ -59,7 +115,8 @@ impl MentorEngine {
                 }
                 GuidanceCache::new(path).ok()
             })
-            .or_else(|| GuidanceCache::in_memory().ok());
+            .or_else(|| GuidanceCache::in_memory().ok())
+            .map(|cache| cache.with_language(config.language));
 
         // Clean old cache entries
         if let Some(ref cache) = cache {
@@ -67,9 +124,12 @@ impl MentorEngine {
         }
 
         Self {
+            llm_permits: Semaphore::new(config.max_concurrent_llm.max(1)),
             config,
             cache,
             display: MentorDisplay::new(),
+            flights: Mutex::new(HashMap::new()),
+            llm_calls: Mutex::new(HashMap::new()),
         }
     }
 
@@ -88,10 +148,54 @@ impl MentorEngine {
     }
 
     /// Generate guidance for an error (with LLM fallback, async)
+    ///
+    /// Identical errors within the dedup window get the same guidance from
+    /// a single generation.
     pub async fn generate(
         &self,
         error: &ErrorInfo,
         llm: Option<&dyn LLMBackend>,
+    ) -> MentorGuidance {
+        let flight = self.join_flight(error);
+        flight
+            .guidance
+            .get_or_init(|| self.generate_uncoalesced(error, llm))
+            .await
+            .clone()
+    }
+
+    /// Flight for this error, started if there is none
+    ///
+    /// Keyed on the language and the exact message: errors that only
+    /// share a shape get their own guidance, with their own values.
+    fn join_flight(&self, error: &ErrorInfo) -> Arc<Flight> {
+        let key = format!(
+            "{}:{}:{}",
+            self.config.language.code(),
+            error.error_type.name(),
+            error.key_message
+        );
+
+        let mut flights = self.flights.lock().unwrap_or_else(|e| e.into_inner());
+        let window = self.config.dedup_window;
+        flights.retain(|_, flight| {
+            flight.started.elapsed() < window || flight.guidance.get().is_none()
+        });
+
+        let flight = flights.entry(key).or_insert_with(|| {
+            Arc::new(Flight {
+                started: Instant::now(),
+                guidance: OnceCell::new(),
+            })
+        });
+        Arc::clone(flight)
+    }
+
+    /// Generate guidance without coalescing identical errors
+    async fn generate_uncoalesced(
+        &self,
+        error: &ErrorInfo,
+        llm: Option<&dyn LLMBackend>,
     ) -> MentorGuidance {
         // 1. Check cache first
         if let Some(ref cache) = self.cache {
@@ -112,14 +216,18 @@ impl MentorEngine {
             return pattern_guidance;
         }
 
-        // 4. Try LLM fallback if enabled and available
-        if self.config.enable_llm {
-            if let Some(llm) = llm {
+        // 4. Try LLM fallback if enabled and available, and this kind of
+        // error hasn't just been sent
+        if let Some(llm) = llm.filter(|_| self.config.enable_llm) {
+            if self.start_llm_cooldown(error) {
                 log::info!(
                     "Using LLM fallback for unknown error: {}",
                     error.key_message
                 );
-                match LLMMentor::generate(error, llm).await {
+                // Closed only when the engine is dropped, which can't
+                // happen while it is borrowed here
+                let _permit = self.llm_permits.acquire().await;
+                match LLMMentor::generate(error, llm, self.config.language).await {
                     Ok(guidance) => {
                         // Cache the LLM response
                         if let Some(ref cache) = self.cache {
@@ -138,8 +246,33 @@ impl MentorEngine {
         pattern_guidance
     }
 
+    /// Whether an error may go to the LLM, starting its cooldown if so
+    ///
+    /// A failed call counts too, so a broken backend isn't retried on
+    /// every repeat.
+    fn start_llm_cooldown(&self, error: &ErrorInfo) -> bool {
+        let mut calls = self.llm_calls.lock().unwrap_or_else(|e| e.into_inner());
+        let cooldown = self.config.llm_cooldown;
+        calls.retain(|_, at| at.elapsed() < cooldown);
+
+        let fingerprint = error.fingerprint();
+        if calls.contains_key(&fingerprint) {
+            log::debug!(
+                "Skipping LLM guidance for a repeat of: {}",
+                error.key_message
+            );
+            return false;
+        }
+        calls.insert(fingerprint, Instant::now());
+        true
+    }
+
     /// Generate guidance from built-in patterns
     fn generate_from_pattern(&self, error: &ErrorInfo) -> MentorGuidance {
+        if let Some(status) = Self::http_status(error) {
+            return Self::http_status_guidance(self.config.language, error, status);
+        }
+
         match error.error_type {
             ErrorType::CommandNotFound => self.guidance_command_not_found(error),
             ErrorType::PermissionDenied => self.guidance_permission_denied(error),
@@ -152,6 +285,9 @@ impl MentorEngine {
             ErrorType::DockerError => self.guidance_docker_error(error),
             ErrorType::KubernetesError => self.guidance_kubernetes_error(error),
             ErrorType::GitError => self.guidance_git_error(error),
+            ErrorType::OutOfMemory if Self::is_oom_killed(error) => {
+                Self::oom_killed_guidance(self.config.language, error)
+            }
             _ => self.guidance_generic(error),
         }
     }
@@ -159,249 +295,779 @@ impl MentorEngine {
     // Pattern-specific guidance generators
 
     fn guidance_command_not_found(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         let cmd = Self::extract_command_name(&error.key_message);
 
+        let mut steps = Vec::new();
+        if let Some(suggestion) = Self::suggest_typo_fix(&cmd) {
+            // Re-run the original command with just the program name fixed
+            let fixed = match error.command.split_once(char::is_whitespace) {
+                Some((first, rest)) if first == cmd => format!("{suggestion} {rest}"),
+                _ => suggestion.clone(),
+            };
+            steps.push(NextStep::with_command(
+                lang.fill(
+                    "Did you mean `{suggestion}`?",
+                    &[("suggestion", &suggestion)],
+                ),
+                fixed,
+            ));
+        }
+        let package_manager = PackageManager::detect();
+        steps.push(NextStep::with_command(
+            lang.text("Check if it's installed somewhere"),
+            format!("which {cmd}"),
+        ));
+        steps.extend(NextStep::install(&cmd, package_manager, lang));
+        steps.push(NextStep::with_command(
+            lang.text("Check your PATH"),
+            "echo $PATH",
+        ));
+
+        let (search, managers) = match package_manager {
+            Some(pm) => (
+                vec![format!("install {} {}", cmd, pm.platform().to_lowercase())],
+                pm.program().to_string(),
+            ),
+            None => (
+                vec![
+                    format!("install {} macos", cmd),
+                    format!("install {} linux", cmd),
+                ],
+                "brew, apt".to_string(),
+            ),
+        };
+
         MentorGuidance::from_pattern(
             &error.key_message,
-            format!(
-                "The command '{cmd}' is not installed on this system, or it's not in your PATH."
+            lang.fill(
+                "The command '{cmd}' is not installed on this system, or it's not in your PATH.",
+                &[("cmd", &cmd)],
             ),
         )
-        .with_search(vec![
-            format!("install {} macos", cmd),
-            format!("install {} linux", cmd),
-        ])
-        .with_steps(vec![
-            NextStep::with_command("Check if it's installed somewhere", format!("which {cmd}")),
-            NextStep::with_command("Install on macOS", format!("brew install {cmd}")),
-            NextStep::with_command(
-                "Install on Ubuntu/Debian",
-                format!("sudo apt install {cmd}"),
-            ),
-            NextStep::with_command("Check your PATH", "echo $PATH"),
-        ])
+        .with_search(search)
+        .with_steps(steps)
         .with_concepts(vec![
-            "PATH environment variable".to_string(),
-            "Package managers (brew, apt)".to_string(),
+            lang.text("PATH environment variable").to_string(),
+            lang.fill("Package managers ({managers})", &[("managers", &managers)]),
         ])
     }
 
     fn guidance_permission_denied(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "You don't have permission to perform this action. This usually means you need \
-             elevated privileges (sudo) or the file/directory permissions need to be changed.",
+            lang.text(
+                "You don't have permission to perform this action. This usually means you need \
+                 elevated privileges (sudo) or the file/directory permissions need to be changed.",
+            ),
         )
         .with_search(vec![
             "linux file permissions".to_string(),
             "chmod tutorial".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("Run with sudo (if appropriate)", "sudo !!"),
-            NextStep::with_command("Check file permissions", "ls -la <file>"),
-            NextStep::with_command("Make file executable", "chmod +x <file>"),
-            NextStep::new("Check file ownership with 'ls -la'"),
+            NextStep::with_command(lang.text("Run with sudo (if appropriate)"), "sudo !!"),
+            NextStep::with_command(lang.text("Check file permissions"), "ls -la <file>"),
+            NextStep::with_command(lang.text("Make file executable"), "chmod +x <file>"),
+            NextStep::new(lang.text("Check file ownership with 'ls -la'")),
         ])
         .with_concepts(vec![
-            "Unix file permissions".to_string(),
-            "sudo and root access".to_string(),
-            "File ownership".to_string(),
+            lang.text("Unix file permissions").to_string(),
+            lang.text("sudo and root access").to_string(),
+            lang.text("File ownership").to_string(),
         ])
     }
 
     fn guidance_file_not_found(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "The specified file or directory doesn't exist. Check the path for typos \
-             or verify the file was created.",
+            lang.text(
+                "The specified file or directory doesn't exist. Check the path for typos \
+                 or verify the file was created.",
+            ),
         )
         .with_search(vec![
             "find file linux".to_string(),
             "bash tab completion".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("List current directory", "ls -la"),
-            NextStep::with_command("Show working directory", "pwd"),
-            NextStep::with_command("Search for file", "find . -name '<filename>'"),
-            NextStep::new("Use tab completion to verify paths"),
+            NextStep::with_command(lang.text("List current directory"), "ls -la"),
+            NextStep::with_command(lang.text("Show working directory"), "pwd"),
+            NextStep::with_command(lang.text("Search for file"), "find . -name '<filename>'"),
+            NextStep::new(lang.text("Use tab completion to verify paths")),
         ])
         .with_concepts(vec![
-            "File paths (absolute vs relative)".to_string(),
-            "Working directory".to_string(),
+            lang.text("File paths (absolute vs relative)").to_string(),
+            lang.text("Working directory").to_string(),
         ])
     }
 
     fn guidance_connection_refused(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "The connection was refused. The service might not be running, \
-             or a firewall could be blocking the connection.",
+            lang.text(
+                "The connection was refused. The service might not be running, \
+                 or a firewall could be blocking the connection.",
+            ),
         )
         .with_search(vec![
             "check if service is running linux".to_string(),
             "netstat listening ports".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("Check if service is running", "systemctl status <service>"),
-            NextStep::with_command("List listening ports", "netstat -tuln"),
-            NextStep::with_command("Check firewall (Ubuntu)", "sudo ufw status"),
-            NextStep::new("Verify the host and port are correct"),
+            NextStep::with_command(
+                lang.text("Check if service is running"),
+                "systemctl status <service>",
+            ),
+            NextStep::with_command(lang.text("List listening ports"), "netstat -tuln"),
+            NextStep::with_command(lang.text("Check firewall (Ubuntu)"), "sudo ufw status"),
+            NextStep::new(lang.text("Verify the host and port are correct")),
         ])
         .with_concepts(vec![
-            "Network ports and services".to_string(),
-            "Systemd service management".to_string(),
+            lang.text("Network ports and services").to_string(),
+            lang.text("Systemd service management").to_string(),
         ])
     }
 
     fn guidance_port_in_use(&self, error: &ErrorInfo) -> MentorGuidance {
-        MentorGuidance::from_pattern(
-            &error.key_message,
-            "Another process is already using this port. You'll need to stop that process \
-             or use a different port.",
-        )
-        .with_search(vec![
-            "find process using port".to_string(),
-            "kill process linux".to_string(),
-        ])
-        .with_steps(vec![
-            NextStep::with_command("Find process using port", "lsof -i :<port>"),
-            NextStep::with_command("Or use netstat", "netstat -tuln | grep <port>"),
-            NextStep::with_command("Kill process by PID", "kill <pid>"),
-            NextStep::new("Or configure your service to use a different port"),
-        ])
-        .with_concepts(vec![
-            "Network ports".to_string(),
-            "Process management".to_string(),
-        ])
+        let port = Self::extract_port(error);
+        let holders = port.map(NetworkTool::who_uses_port).unwrap_or_default();
+        Self::port_in_use_guidance(self.config.language, error, port, &holders)
+    }
+
+    /// PortInUse guidance given the processes found holding the port
+    fn port_in_use_guidance(
+        lang: Language,
+        error: &ErrorInfo,
+        port: Option<u16>,
+        holders: &[ProcessInfo],
+    ) -> MentorGuidance {
+        let port_label = port.map_or("<port>".to_string(), |p| p.to_string());
+
+        let explanation = match holders {
+            [] => lang
+                .text(
+                    "Another process is already using this port. You'll need to stop that process \
+                     or use a different port.",
+                )
+                .to_string(),
+            [holder] => lang.fill(
+                "Port {port} is already held by {holder}. Stop it or use a different port.",
+                &[("port", &port_label), ("holder", &holder.to_string())],
+            ),
+            _ => lang.fill(
+                "Port {port} is already held by {holders}. Stop them or use a different port.",
+                &[
+                    ("port", &port_label),
+                    (
+                        "holders",
+                        &holders
+                            .iter()
+                            .map(|h| h.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                ],
+            ),
+        };
+
+        let mut steps: Vec<NextStep> = holders
+            .iter()
+            .map(|holder| {
+                NextStep::with_command(
+                    lang.fill("Stop {holder}", &[("holder", &holder.to_string())]),
+                    holder.kill_command(),
+                )
+            })
+            .collect();
+        if holders.is_empty() {
+            steps.extend([
+                NextStep::with_command(
+                    lang.text("Find process using port"),
+                    format!("lsof -i :{port_label}"),
+                ),
+                NextStep::with_command(
+                    lang.text("Or use netstat"),
+                    format!("netstat -tuln | grep {port_label}"),
+                ),
+                NextStep::with_command(lang.text("Kill process by PID"), "kill <pid>"),
+            ]);
+        }
+        steps.push(NextStep::new(
+            lang.text("Or configure your service to use a different port"),
+        ));
+
+        MentorGuidance::from_pattern(&error.key_message, explanation)
+            .with_search(vec![
+                "find process using port".to_string(),
+                "kill process linux".to_string(),
+            ])
+            .with_steps(steps)
+            .with_concepts(vec![
+                lang.text("Network ports").to_string(),
+                lang.text("Process management").to_string(),
+            ])
     }
 
     fn guidance_configuration_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         let location = error
             .source_location
             .as_ref()
             .map(|l| l.to_string())
-            .unwrap_or_else(|| "configuration file".to_string());
+            .unwrap_or_else(|| lang.text("configuration file").to_string());
 
-        MentorGuidance::from_pattern(
-            &error.key_message,
-            format!(
-                "There's an error in {location}. Check the file for typos or invalid directives."
-            ),
-        )
-        .with_search(vec!["configuration syntax".to_string()])
-        .with_steps(if let Some(ref loc) = error.source_location {
+        let mut steps = if let Some(ref loc) = error.source_location {
             let file = loc.file.display().to_string();
             let line = loc.line.unwrap_or(1);
             vec![
-                NextStep::with_command("Open file at error line", format!("vim {file} +{line}")),
-                NextStep::new("Check for typos in the directive name"),
-                NextStep::new("Verify syntax matches documentation"),
+                NextStep::with_command(
+                    lang.text("Open file at error line"),
+                    format!("vim {file} +{line}"),
+                ),
+                NextStep::new(lang.text("Check for typos in the directive name")),
+                NextStep::new(lang.text("Verify syntax matches documentation")),
             ]
         } else {
             vec![
-                NextStep::new("Check the configuration file for syntax errors"),
-                NextStep::new("Compare with documentation examples"),
+                NextStep::new(lang.text("Check the configuration file for syntax errors")),
+                NextStep::new(lang.text("Compare with documentation examples")),
             ]
-        })
-        .with_concepts(vec!["Configuration file syntax".to_string()])
+        };
+
+        // nginx/apache: point at what changed since the last successful reload
+        if let Some(snapshot) = WebServer::from_command(&error.command)
+            .and_then(|server| server.snapshot())
+            .filter(|snapshot| snapshot.exists())
+        {
+            steps.insert(
+                0,
+                NextStep::with_command(
+                    lang.text("See what changed since the last working config"),
+                    format!(
+                        "diff -ru {} {}",
+                        snapshot.snapshot_dir().display(),
+                        snapshot.config_root().display()
+                    ),
+                ),
+            );
+        }
+
+        MentorGuidance::from_pattern(
+            &error.key_message,
+            lang.fill(
+                "There's an error in {location}. Check the file for typos or invalid directives.",
+                &[("location", &location)],
+            ),
+        )
+        .with_search(vec!["configuration syntax".to_string()])
+        .with_steps(steps)
+        .with_concepts(vec![lang.text("Configuration file syntax").to_string()])
     }
 
     fn guidance_syntax_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "There's a syntax error. Check for missing quotes, brackets, or typos.",
+            lang.text("There's a syntax error. Check for missing quotes, brackets, or typos."),
         )
         .with_search(vec!["syntax error".to_string()])
         .with_steps(vec![
-            NextStep::new("Check for missing or mismatched quotes"),
-            NextStep::new("Check for missing brackets or parentheses"),
-            NextStep::new("Look for typos in keywords"),
+            NextStep::new(lang.text("Check for missing or mismatched quotes")),
+            NextStep::new(lang.text("Check for missing brackets or parentheses")),
+            NextStep::new(lang.text("Look for typos in keywords")),
         ])
-        .with_concepts(vec!["Syntax and parsing".to_string()])
+        .with_concepts(vec![lang.text("Syntax and parsing").to_string()])
     }
 
     fn guidance_dependency_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "A required module or dependency is missing. You may need to install it.",
+            lang.text("A required module or dependency is missing. You may need to install it."),
         )
         .with_search(vec!["install dependency".to_string()])
         .with_steps(vec![
-            NextStep::with_command("For Node.js", "npm install"),
-            NextStep::with_command("For Python", "pip install -r requirements.txt"),
-            NextStep::with_command("For Rust", "cargo build"),
-            NextStep::new("Check if the module name is spelled correctly"),
+            NextStep::with_command(lang.text("For Node.js"), "npm install"),
+            NextStep::with_command(lang.text("For Python"), "pip install -r requirements.txt"),
+            NextStep::with_command(lang.text("For Rust"), "cargo build"),
+            NextStep::new(lang.text("Check if the module name is spelled correctly")),
         ])
         .with_concepts(vec![
-            "Package managers".to_string(),
-            "Dependencies".to_string(),
+            lang.text("Package managers").to_string(),
+            lang.text("Dependencies").to_string(),
         ])
     }
 
     fn guidance_docker_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
         MentorGuidance::from_pattern(
             &error.key_message,
-            "A Docker error occurred. Check if Docker is running and the image/container exists.",
+            lang.text("A Docker error occurred. Check if Docker is running and the image/container exists."),
         )
         .with_search(vec![
             "docker troubleshooting".to_string(),
             "docker common errors".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("Check Docker status", "docker info"),
-            NextStep::with_command("List containers", "docker ps -a"),
-            NextStep::with_command("List images", "docker images"),
-            NextStep::with_command("View container logs", "docker logs <container>"),
+            NextStep::with_command(lang.text("Check Docker status"), "docker info"),
+            NextStep::with_command(lang.text("List containers"), "docker ps -a"),
+            NextStep::with_command(lang.text("List images"), "docker images"),
+            NextStep::with_command(lang.text("View container logs"), "docker logs <container>"),
         ])
         .with_concepts(vec![
-            "Docker containers".to_string(),
-            "Docker images".to_string(),
+            lang.text("Docker containers").to_string(),
+            lang.text("Docker images").to_string(),
         ])
     }
 
     fn guidance_kubernetes_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
+        if Self::is_oom_killed(error) {
+            return Self::oom_killed_guidance(lang, error);
+        }
+
         MentorGuidance::from_pattern(
             &error.key_message,
-            "A Kubernetes error occurred. Check the resource name, namespace, and cluster connection.",
+            lang.text("A Kubernetes error occurred. Check the resource name, namespace, and cluster connection."),
         )
         .with_search(vec![
             "kubernetes debugging".to_string(),
             "kubectl troubleshooting".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("Check cluster connection", "kubectl cluster-info"),
-            NextStep::with_command("List resources in namespace", "kubectl get all"),
-            NextStep::with_command("Check all namespaces", "kubectl get all -A"),
-            NextStep::with_command("Describe resource", "kubectl describe <resource> <name>"),
+            NextStep::with_command(lang.text("Check cluster connection"), "kubectl cluster-info"),
+            NextStep::with_command(lang.text("List resources in namespace"), "kubectl get all"),
+            NextStep::with_command(lang.text("Check all namespaces"), "kubectl get all -A"),
+            NextStep::with_command(lang.text("Describe resource"), "kubectl describe <resource> <name>"),
         ])
         .with_concepts(vec![
-            "Kubernetes namespaces".to_string(),
-            "Kubernetes resources".to_string(),
+            lang.text("Kubernetes namespaces").to_string(),
+            lang.text("Kubernetes resources").to_string(),
         ])
     }
 
     fn guidance_git_error(&self, error: &ErrorInfo) -> MentorGuidance {
+        let lang = self.config.language;
+        let conflicts = Self::conflicted_files(&error.full_output);
+        if !conflicts.is_empty() {
+            return Self::merge_conflict_guidance(lang, error, &conflicts);
+        }
+        if Self::is_non_fast_forward(error) {
+            return Self::non_fast_forward_guidance(lang, error);
+        }
+
         MentorGuidance::from_pattern(
             &error.key_message,
-            "A Git error occurred. Check your repository state and remote configuration.",
+            lang.text(
+                "A Git error occurred. Check your repository state and remote configuration.",
+            ),
         )
         .with_search(vec![
             "git common errors".to_string(),
             "git troubleshooting".to_string(),
         ])
         .with_steps(vec![
-            NextStep::with_command("Check repository status", "git status"),
-            NextStep::with_command("View recent commits", "git log --oneline -5"),
-            NextStep::with_command("Check remotes", "git remote -v"),
-            NextStep::with_command("Check branches", "git branch -a"),
+            NextStep::with_command(lang.text("Check repository status"), "git status"),
+            NextStep::with_command(lang.text("View recent commits"), "git log --oneline -5"),
+            NextStep::with_command(lang.text("Check remotes"), "git remote -v"),
+            NextStep::with_command(lang.text("Check branches"), "git branch -a"),
+        ])
+        .with_concepts(vec![
+            lang.text("Git workflow").to_string(),
+            lang.text("Git remotes").to_string(),
+        ])
+    }
+
+    /// Guidance naming the files a merge, rebase or pull left conflicted
+    fn merge_conflict_guidance(
+        lang: Language,
+        error: &ErrorInfo,
+        files: &[String],
+    ) -> MentorGuidance {
+        let rebasing = format!("{} {}", error.command, error.full_output)
+            .to_lowercase()
+            .contains("rebase");
+        let (operation, continue_command) = if rebasing {
+            ("rebase", "git rebase --continue")
+        } else {
+            ("merge", "git commit")
+        };
+
+        let conflicting = if files.len() == 1 {
+            "Git couldn't {operation} automatically: 1 file has conflicting changes ({files}). \
+             Edit the <<<<<<< / >>>>>>> sections, or keep one side, then mark them resolved."
+        } else {
+            "Git couldn't {operation} automatically: {count} files have conflicting changes \
+             ({files}). Edit the <<<<<<< / >>>>>>> sections, or keep one side, then mark them \
+             resolved."
+        };
+        let mut explanation = lang.fill(
+            conflicting,
+            &[
+                ("operation", operation),
+                ("count", &files.len().to_string()),
+                ("files", &files.join(", ")),
+            ],
+        );
+        if rebasing {
+            explanation.push(' ');
+            explanation.push_str(lang.text(
+                "During a rebase \"ours\" is the branch you're rebasing onto and \"theirs\" is your \
+                 commit.",
+            ));
+        }
+
+        let quoted: Vec<String> = files.iter().map(|f| shell_quote(f)).collect();
+        let mut steps = vec![NextStep::with_command(
+            lang.text("Resolve in your merge tool"),
+            format!("git mergetool {}", quoted.join(" ")),
+        )];
+        for file in quoted.iter().take(MAX_CONFLICT_STEPS) {
+            steps.push(NextStep::with_command(
+                lang.fill("Keep your side of {file}", &[("file", file)]),
+                format!("git checkout --ours {file}"),
+            ));
+            steps.push(NextStep::with_command(
+                lang.fill("Take the incoming side of {file}", &[("file", file)]),
+                format!("git checkout --theirs {file}"),
+            ));
+        }
+        steps.push(NextStep::with_command(
+            lang.text("Mark resolved and continue"),
+            format!("git add {} && {continue_command}", quoted.join(" ")),
+        ));
+        steps.push(NextStep::with_command(
+            lang.fill(
+                "Give up and go back to before the {operation}",
+                &[("operation", operation)],
+            ),
+            format!("git {operation} --abort"),
+        ));
+
+        MentorGuidance::from_pattern(&error.key_message, explanation)
+            .with_search(vec!["git resolve merge conflict".to_string()])
+            .with_steps(steps)
+            .with_concepts(vec![
+                lang.text("Merge conflicts").to_string(),
+                lang.text("Git merge").to_string(),
+            ])
+    }
+
+    /// Guidance for a push rejected because the remote has newer commits
+    fn non_fast_forward_guidance(lang: Language, error: &ErrorInfo) -> MentorGuidance {
+        MentorGuidance::from_pattern(
+            &error.key_message,
+            lang.text(
+                "The remote branch has commits you don't have yet, so pushing would overwrite them. \
+                 Bring them in first, then push again.",
+            ),
+        )
+        .with_search(vec!["git push rejected non-fast-forward".to_string()])
+        .with_steps(vec![
+            NextStep::with_command(
+                lang.text("See what's new on the remote"),
+                "git fetch && git log --oneline HEAD..@{u}",
+            ),
+            NextStep::with_command(
+                lang.text("Replay your commits on top of the remote"),
+                "git pull --rebase",
+            ),
+            NextStep::with_command(lang.text("Push again"), "git push"),
         ])
-        .with_concepts(vec!["Git workflow".to_string(), "Git remotes".to_string()])
+        .with_concepts(vec![lang.text("Git remotes").to_string(), lang.text("Rebasing").to_string()])
+    }
+
+    /// A container killed for going over its memory limit
+    ///
+    /// Either the pod status says OOMKilled, or a kubectl command (usually
+    /// `exec`) ended with 137, the exit code of a SIGKILLed process.
+    fn is_oom_killed(error: &ErrorInfo) -> bool {
+        let kubectl = error.command.split_whitespace().next() == Some("kubectl");
+        format!("{}\n{}", error.key_message, error.full_output).contains("OOMKilled")
+            || (kubectl && error.exit_code == 137)
+            || error
+                .full_output
+                .contains("command terminated with exit code 137")
+    }
+
+    /// Guidance for a pod whose container hit its memory limit
+    fn oom_killed_guidance(lang: Language, error: &ErrorInfo) -> MentorGuidance {
+        let pod = Self::oom_killed_pod(error).unwrap_or_else(|| "<pod>".to_string());
+
+        MentorGuidance::from_pattern(
+            &error.key_message,
+            lang.text(
+                "The container used more memory than its limit (resources.limits.memory), so the \
+                 kernel's OOM killer stopped it with SIGKILL (exit code 137). Kubernetes restarts it, \
+                 and it will keep crashing until it uses less memory or gets a higher limit.",
+            ),
+        )
+        .with_search(vec![
+            "kubernetes OOMKilled".to_string(),
+            "kubernetes memory limits".to_string(),
+        ])
+        .with_steps(vec![
+            NextStep::with_command(
+                lang.text("See the last state, exit code and memory limit"),
+                format!("kubectl describe pod {pod}"),
+            ),
+            NextStep::with_command(
+                lang.text("Compare current memory use with the limit"),
+                format!("kubectl top pod {pod} --containers"),
+            ),
+            NextStep::with_command(
+                lang.text("Read the logs from before the kill"),
+                format!("kubectl logs {pod} --previous"),
+            ),
+            NextStep::with_command(
+                lang.text("Raise the limit on the deployment"),
+                "kubectl set resources deployment/<name> --limits=memory=512Mi",
+            ),
+            NextStep::new(lang.text("Or raise resources.limits.memory in the manifest and apply it again")),
+        ])
+        .with_concepts(vec![
+            lang.text("Resource requests and limits").to_string(),
+            "OOM killer".to_string(),
+        ])
+    }
+
+    /// Pod named in an OOMKilled status line or in the failed command
+    fn oom_killed_pod(error: &ErrorInfo) -> Option<String> {
+        let status_line = format!("{}\n{}", error.key_message, error.full_output)
+            .lines()
+            .find(|line| line.contains("OOMKilled"))
+            .and_then(|line| line.split_whitespace().next())
+            .filter(|name| {
+                *name != "OOMKilled"
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "-./".contains(c))
+            })
+            .map(|name| name.trim_start_matches("pod/").to_string());
+        status_line.or_else(|| {
+            let tokens: Vec<&str> = error.command.split_whitespace().collect();
+            let position = tokens
+                .iter()
+                .position(|t| matches!(*t, "exec" | "logs" | "describe" | "attach" | "get"))?;
+            tokens[position + 1..]
+                .iter()
+                .filter(|t| !t.starts_with('-') && !matches!(**t, "pod" | "pods" | "po"))
+                .map(|t| t.trim_start_matches("pod/").to_string())
+                .next()
+        })
+    }
+
+    /// Files listed as conflicted in merge, pull or `git status` output
+    ///
+    /// Understands `CONFLICT (...): Merge conflict in <file>`, modify/delete
+    /// conflicts, the long `both modified: <file>` form and porcelain
+    /// `UU <file>` lines.
+    pub(super) fn conflicted_files(output: &str) -> Vec<String> {
+        static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+        let patterns = PATTERNS.get_or_init(|| {
+            [
+                Regex::new(r"^CONFLICT \([^)]*\): Merge conflict in (.+?)\s*$").unwrap(),
+                Regex::new(r"^CONFLICT \([^)]*\): (.+?) deleted in ").unwrap(),
+                Regex::new(
+                    r"^\s*(?:both modified|both added|both deleted|added by us|added by them|deleted by us|deleted by them):\s+(.+?)\s*$",
+                )
+                .unwrap(),
+                Regex::new(r"^(?:UU|AA|DD|AU|UA|DU|UD) (.+?)\s*$").unwrap(),
+            ]
+        });
+
+        let mut files: Vec<String> = Vec::new();
+        for line in output.lines() {
+            let file = patterns
+                .iter()
+                .find_map(|pattern| pattern.captures(line))
+                .map(|caps| caps[1].to_string());
+            if let Some(file) = file {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+        files
+    }
+
+    /// Whether a push was rejected as non-fast-forward
+    fn is_non_fast_forward(error: &ErrorInfo) -> bool {
+        let output = format!("{}\n{}", error.key_message, error.full_output).to_lowercase();
+        output.contains("non-fast-forward")
+            || output.contains("(fetch first)")
+            || output.contains("updates were rejected because")
+    }
+
+    /// HTTP status the detector extracted from a curl/httpie response
+    fn http_status(error: &ErrorInfo) -> Option<u16> {
+        match error.error_type {
+            ErrorType::AuthenticationFailed
+            | ErrorType::ResourceNotFound
+            | ErrorType::HttpError => error
+                .key_message
+                .parse()
+                .ok()
+                .filter(|status| (400..600).contains(status)),
+            _ => None,
+        }
+    }
+
+    /// Guidance for a 4xx/5xx response, by what the status usually means
+    fn http_status_guidance(lang: Language, error: &ErrorInfo, status: u16) -> MentorGuidance {
+        let url =
+            crate::tools::network::curl_url(&error.command).unwrap_or_else(|| "<url>".to_string());
+        let key_message = format!("HTTP {status}");
+
+        let (explanation, steps, concept) = match status {
+            401 => (
+                "The server wants to know who you are and got no valid credentials: the token or \
+                 password is missing, mistyped or expired.",
+                vec![
+                    NextStep::with_command(
+                        lang.text("See which headers were actually sent"),
+                        format!("curl -v {url} 2>&1 | grep '^>'"),
+                    ),
+                    NextStep::with_command(
+                        lang.text("Send a token"),
+                        format!("curl -H \"Authorization: Bearer $TOKEN\" {url}"),
+                    ),
+                    NextStep::new(
+                        lang.text("Check the token hasn't expired and belongs to this API"),
+                    ),
+                ],
+                "Authentication",
+            ),
+            403 => (
+                "The server knows who you are but won't allow this request: the credentials lack \
+                 the permission, scope or role, or an IP allowlist or firewall blocks you.",
+                vec![
+                    NextStep::new(
+                        lang.text("Check the scopes or role granted to your token or account"),
+                    ),
+                    NextStep::with_command(
+                        lang.text("Read the response body, it often names the missing permission"),
+                        format!("curl -s {url}"),
+                    ),
+                    NextStep::new(
+                        lang.text("Try from a network the API allows, e.g. over the VPN"),
+                    ),
+                ],
+                "Authorization",
+            ),
+            404 => (
+                "The server is up but has nothing at this address: the path, API version or \
+                 resource ID is wrong, or the resource was deleted.",
+                vec![
+                    NextStep::new(
+                        lang.text("Check the path for typos and a missing or extra trailing slash"),
+                    ),
+                    NextStep::new(
+                        lang.text("Check the API base path and version, e.g. /api/v1 vs /v2"),
+                    ),
+                    NextStep::new(lang.text("List the parent collection to find the right ID")),
+                ],
+                "URL paths",
+            ),
+            429 => (
+                "You sent more requests than the API allows in its time window. Wait before \
+                 retrying, and retry with backoff instead of in a tight loop.",
+                vec![
+                    NextStep::with_command(
+                        lang.text("See how long to wait and what the limits are"),
+                        format!("curl -sI {url} | grep -iE 'retry-after|ratelimit'"),
+                    ),
+                    NextStep::with_command(
+                        lang.text("Let curl back off and retry for you"),
+                        format!("curl --retry 5 --retry-delay 2 {url}"),
+                    ),
+                    NextStep::new(
+                        lang.text("Cache responses or batch requests to stay under the quota"),
+                    ),
+                ],
+                "Rate limiting",
+            ),
+            502..=504 => (
+                match status {
+                    502 => {
+                        "A proxy or load balancer answered, but the service behind it sent a bad \
+                            response or none at all: it's down, crashing or on the wrong port."
+                    }
+                    503 => {
+                        "The service is unavailable: overloaded, in maintenance, mid-deploy or \
+                            with no healthy instances behind the load balancer."
+                    }
+                    _ => {
+                        "A proxy or load balancer gave up waiting for the service behind it: the \
+                          backend is too slow or stuck."
+                    }
+                },
+                vec![
+                    NextStep::with_command(
+                        lang.text("Check the backend service"),
+                        "systemctl status <service>",
+                    ),
+                    NextStep::with_command(
+                        lang.text("Read the proxy's error log"),
+                        "sudo tail -n 50 /var/log/nginx/error.log",
+                    ),
+                    NextStep::with_command(
+                        lang.text("In Kubernetes, check the service has ready endpoints"),
+                        "kubectl get endpoints <service>",
+                    ),
+                    NextStep::with_command(
+                        lang.text("See which phase is slow"),
+                        format!("curl -o /dev/null -s -w '%{{time_total}}s\\n' {url}"),
+                    ),
+                ],
+                "Reverse proxies",
+            ),
+            500..=599 => (
+                "The server hit an error while handling the request. The problem is on the server \
+                 side; its logs say what went wrong.",
+                vec![
+                    NextStep::new(
+                        lang.text("Check the server's application logs around this time"),
+                    ),
+                    NextStep::with_command(
+                        lang.text("Retry once, it may be transient"),
+                        format!("curl {url}"),
+                    ),
+                ],
+                "HTTP status codes",
+            ),
+            _ => (
+                "The server rejected the request itself. Check the method, headers and body.",
+                vec![NextStep::with_command(
+                    lang.text("See the full request and response"),
+                    format!("curl -v {url}"),
+                )],
+                "HTTP status codes",
+            ),
+        };
+
+        MentorGuidance::from_pattern(key_message, lang.text(explanation))
+            .with_search(vec![format!("HTTP {status} error")])
+            .with_steps(steps)
+            .with_concepts(vec![
+                lang.text("HTTP status codes").to_string(),
+                lang.text(concept).to_string(),
+            ])
     }
 
     fn guidance_generic(&self, error: &ErrorInfo) -> MentorGuidance {
-        MentorGuidance::fallback(&error.key_message).with_steps(vec![
-            NextStep::new("Check the full error output above"),
-            NextStep::new("Search for the error message online"),
+        let lang = self.config.language;
+        let mut guidance = MentorGuidance::fallback(&error.key_message);
+        guidance.explanation = lang
+            .text("An error occurred. Check the full output for details.")
+            .to_string();
+        guidance.with_steps(vec![
+            NextStep::new(lang.text("Check the full error output above")),
+            NextStep::new(lang.text("Search for the error message online")),
         ])
     }
 
@@ -464,10 +1130,146 @@ impl MentorEngine {
             .to_string()
     }
 
+    /// Port a "port in use" error is about
+    ///
+    /// Tries the lines reporting the error first ("port 8080",
+    /// "0.0.0.0:8080"), then the command line (`-p 8080`, `--port=8080`,
+    /// `http.server 8000`). Other output lines are skipped since timestamps
+    /// look like ports too.
+    pub(super) fn extract_port(error: &ErrorInfo) -> Option<u16> {
+        static IN_OUTPUT: OnceLock<Regex> = OnceLock::new();
+        static IN_COMMAND: OnceLock<Regex> = OnceLock::new();
+        let in_output =
+            IN_OUTPUT.get_or_init(|| Regex::new(r"(?i)\bport\s+(\d{1,5})\b|:(\d{2,5})\b").unwrap());
+        let in_command = IN_COMMAND.get_or_init(|| {
+            Regex::new(r"(?:^|\s)(?:-p\s*|--port[=\s]|:)?(\d{2,5})(?::\d+)?(?:\s|$)").unwrap()
+        });
+
+        let parse = |caps: regex::Captures| {
+            caps.iter()
+                .skip(1)
+                .flatten()
+                .find_map(|m| m.as_str().parse::<u16>().ok())
+                .filter(|&port| port > 0)
+        };
+
+        let in_use = |line: &&str| {
+            let line = line.to_lowercase();
+            line.contains("in use") || line.contains("eaddrinuse")
+        };
+
+        std::iter::once(error.key_message.as_str())
+            .chain(error.full_output.lines().filter(in_use))
+            .flat_map(|line| in_output.captures_iter(line))
+            .find_map(parse)
+            .or_else(|| in_command.captures_iter(&error.command).find_map(parse))
+    }
+
+    /// Suggest a known command or PATH binary the missing command is probably a typo of
+    fn suggest_typo_fix(cmd: &str) -> Option<String> {
+        Self::closest_command(cmd, &Self::known_commands(), &Self::path_binaries())
+    }
+
+    /// Programs the registry's tools run, plus the shell's builtins
+    ///
+    /// Tool names like "network" aren't commands, so they are never offered.
+    fn known_commands() -> Vec<String> {
+        let registry = ToolRegistry::new();
+        registry
+            .list_tools()
+            .into_iter()
+            .filter_map(|name| registry.get_tool(name))
+            .flat_map(|tool| tool.binaries())
+            .chain(BUILTIN_NAMES.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Pick the closest candidate, but only when the match is unambiguous
+    ///
+    /// Known commands win over PATH binaries. Short names need a smaller distance,
+    /// and ties between different candidates yield no suggestion.
+    fn closest_command(cmd: &str, known: &[String], path: &[String]) -> Option<String> {
+        let len = cmd.chars().count();
+        // Allow one edit per three characters, capped at TYPO_MAX_DISTANCE
+        let max_distance = (len / 3).min(TYPO_MAX_DISTANCE);
+        if max_distance == 0 {
+            return None;
+        }
+
+        for candidates in [known, path] {
+            let mut best: Option<(usize, &str)> = None;
+            let mut tied = false;
+
+            for candidate in candidates {
+                if candidate == cmd {
+                    continue;
+                }
+                let distance = levenshtein(cmd, candidate);
+                if distance > max_distance {
+                    continue;
+                }
+                match best {
+                    Some((best_distance, best_name)) if distance == best_distance => {
+                        tied |= best_name != candidate;
+                    }
+                    Some((best_distance, _)) if distance > best_distance => {}
+                    _ => {
+                        best = Some((distance, candidate));
+                        tied = false;
+                    }
+                }
+            }
+
+            if let Some((_, name)) = best {
+                return if tied { None } else { Some(name.to_string()) };
+            }
+        }
+
+        None
+    }
+
+    /// List executable names from every directory on PATH
+    fn path_binaries() -> Vec<String> {
+        let Some(path) = std::env::var_os("PATH") else {
+            return Vec::new();
+        };
+
+        std::env::split_paths(&path)
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect()
+    }
+
     /// Render guidance as formatted output
     pub fn render(&self, guidance: &MentorGuidance) -> String {
         self.display.render_guidance(guidance)
     }
+
+    /// Render guidance as Markdown
+    pub fn render_markdown(&self, guidance: &MentorGuidance) -> String {
+        self.display.render_markdown(guidance)
+    }
+}
+
+/// Levenshtein edit distance between two strings (by chars)
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
 }
 
 impl Default for MentorEngine {
@@ -479,6 +1281,9 @@ impl Default for MentorEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::LLMResponse;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     fn create_test_error(error_type: ErrorType, key_message: &str) -> ErrorInfo {
         ErrorInfo::new(error_type, 1, key_message, "test command")
@@ -542,6 +1347,352 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kubectl", "kubectl"), 0);
+        assert_eq!(levenshtein("kubecl", "kubectl"), 1);
+        assert_eq!(levenshtein("dokcer", "docker"), 2);
+        assert_eq!(levenshtein("", "git"), 3);
+        assert_eq!(levenshtein("日本語", "日本"), 1);
+    }
+
+    #[test]
+    fn test_closest_command() {
+        let known = vec!["kubectl".to_string(), "docker".to_string()];
+        let path = vec!["terraform".to_string(), "git".to_string(), "gh".to_string()];
+
+        assert_eq!(
+            MentorEngine::closest_command("kubecl", &known, &path),
+            Some("kubectl".to_string())
+        );
+        assert_eq!(
+            MentorEngine::closest_command("terrafrom", &known, &path),
+            Some("terraform".to_string())
+        );
+        // Too short to be confident
+        assert_eq!(MentorEngine::closest_command("gt", &known, &path), None);
+        // Nothing close enough
+        assert_eq!(
+            MentorEngine::closest_command("ansible", &known, &path),
+            None
+        );
+    }
+
+    #[test]
+    fn test_known_commands_are_executables() {
+        let known = MentorEngine::known_commands();
+        assert!(known.iter().any(|c| c == "netstat"));
+        assert!(known.iter().any(|c| c == "export"));
+        assert!(!known.iter().any(|c| c == "network"));
+
+        assert_eq!(
+            MentorEngine::closest_command("ntstat", &known, &[]),
+            Some("netstat".to_string())
+        );
+        assert_eq!(
+            MentorEngine::closest_command("exprot", &known, &[]),
+            Some("export".to_string())
+        );
+    }
+
+    #[test]
+    fn test_closest_command_ambiguous() {
+        let path = vec!["vim".to_string(), "vis".to_string()];
+        assert_eq!(MentorEngine::closest_command("vix", &[], &path), None);
+
+        // Duplicate PATH entries are not ambiguous
+        let path = vec!["helm".to_string(), "helm".to_string()];
+        assert_eq!(
+            MentorEngine::closest_command("helmm", &[], &path),
+            Some("helm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_command_not_found_typo_suggestion() {
+        let engine = MentorEngine::new();
+        let error = ErrorInfo::new(
+            ErrorType::CommandNotFound,
+            127,
+            "command not found: kubecl",
+            "kubecl get pods",
+        );
+
+        let guidance = engine.generate_sync(&error);
+
+        let first = &guidance.next_steps[0];
+        assert_eq!(first.description, "Did you mean `kubectl`?");
+        assert_eq!(first.command.as_deref(), Some("kubectl get pods"));
+    }
+
+    #[test]
+    fn test_extract_port() {
+        let mut error = create_test_error(ErrorType::PortInUse, "address already in use");
+        error.full_output = "12:01:33 starting server\n\
+            Error: listen EADDRINUSE: address already in use :::3000\n"
+            .to_string();
+        assert_eq!(MentorEngine::extract_port(&error), Some(3000));
+
+        let error = create_test_error(ErrorType::PortInUse, "port 8080 is already in use");
+        assert_eq!(MentorEngine::extract_port(&error), Some(8080));
+
+        let mut error = create_test_error(ErrorType::PortInUse, "address already in use");
+        error.command = "python3 -m http.server 8000".to_string();
+        assert_eq!(MentorEngine::extract_port(&error), Some(8000));
+
+        error.command = "docker run -p 5432:5432 postgres".to_string();
+        assert_eq!(MentorEngine::extract_port(&error), Some(5432));
+
+        error.command = "nginx".to_string();
+        assert_eq!(MentorEngine::extract_port(&error), None);
+    }
+
+    #[test]
+    fn test_port_in_use_names_holder() {
+        let error = create_test_error(ErrorType::PortInUse, "port 8080 is already in use");
+        let holder = ProcessInfo {
+            pid: 4242,
+            name: "node".to_string(),
+            user: Some("dev".to_string()),
+        };
+
+        let guidance =
+            MentorEngine::port_in_use_guidance(Language::English, &error, Some(8080), &[holder]);
+        assert!(guidance
+            .explanation
+            .contains("Port 8080 is already held by node (pid 4242, user dev)"));
+        assert_eq!(guidance.next_steps[0].command.as_deref(), Some("kill 4242"));
+
+        let guidance =
+            MentorEngine::port_in_use_guidance(Language::English, &error, Some(8080), &[]);
+        assert_eq!(
+            guidance.next_steps[0].command.as_deref(),
+            Some("lsof -i :8080")
+        );
+    }
+
+    #[test]
+    fn test_conflicted_files() {
+        let output = "Auto-merging src/app.rs\n\
+            CONFLICT (content): Merge conflict in src/app.rs\n\
+            CONFLICT (modify/delete): docs/old notes.md deleted in HEAD and modified in feature.\n\
+            Automatic merge failed; fix conflicts and then commit the result.\n\
+            \tboth modified:   src/app.rs\n\
+            \tdeleted by us:   docs/old notes.md\n\
+            UU Cargo.lock\n";
+
+        assert_eq!(
+            MentorEngine::conflicted_files(output),
+            ["src/app.rs", "docs/old notes.md", "Cargo.lock"]
+        );
+        assert!(MentorEngine::conflicted_files("nothing to commit").is_empty());
+    }
+
+    #[test]
+    fn test_git_conflict_and_rejected_push_guidance() {
+        let engine = MentorEngine::new();
+
+        let error = ErrorInfo::new(
+            ErrorType::GitError,
+            1,
+            "CONFLICT (content): Merge conflict in src/app.rs",
+            "git merge feature",
+        )
+        .with_output("CONFLICT (content): Merge conflict in src/app.rs\n");
+        let guidance = engine.generate_from_pattern(&error);
+        let commands: Vec<_> = guidance
+            .next_steps
+            .iter()
+            .filter_map(|s| s.command.as_deref())
+            .collect();
+        assert!(guidance.explanation.contains("src/app.rs"));
+        assert!(commands.contains(&"git checkout --ours src/app.rs"));
+        assert!(commands.contains(&"git checkout --theirs src/app.rs"));
+        assert!(commands.contains(&"git mergetool src/app.rs"));
+        assert!(commands.contains(&"git merge --abort"));
+
+        let error = ErrorInfo::new(
+            ErrorType::GitError,
+            1,
+            "error: failed to push some refs",
+            "git push",
+        )
+        .with_output(" ! [rejected]        main -> main (non-fast-forward)\n");
+        let guidance = engine.generate_from_pattern(&error);
+        assert!(guidance
+            .next_steps
+            .iter()
+            .any(|s| s.command.as_deref() == Some("git pull --rebase")));
+    }
+
+    #[test]
+    fn test_http_status_guidance() {
+        let engine = MentorEngine::new();
+        let guidance = |error_type: ErrorType, status: &str| {
+            let error = ErrorInfo::new(
+                error_type,
+                22,
+                status,
+                "curl -f https://api.example.com/v1/users",
+            );
+            engine.generate_from_pattern(&error)
+        };
+
+        let forbidden = guidance(ErrorType::AuthenticationFailed, "403");
+        assert_eq!(forbidden.key_message, "HTTP 403");
+        assert!(forbidden.explanation.contains("permission"));
+        assert!(forbidden
+            .related_concepts
+            .contains(&"Authorization".to_string()));
+
+        let unauthorized = guidance(ErrorType::AuthenticationFailed, "401");
+        assert!(unauthorized.next_steps.iter().any(|s| s.command.as_deref()
+            == Some("curl -H \"Authorization: Bearer $TOKEN\" https://api.example.com/v1/users")));
+
+        assert!(guidance(ErrorType::ResourceNotFound, "404")
+            .explanation
+            .contains("nothing at this address"));
+        assert!(guidance(ErrorType::HttpError, "429")
+            .explanation
+            .contains("Wait before retrying"));
+        let gateway = guidance(ErrorType::HttpError, "502");
+        assert!(gateway.explanation.contains("service behind it"));
+        assert!(gateway
+            .related_concepts
+            .contains(&"Reverse proxies".to_string()));
+
+        // Non-HTTP errors of the same types keep their usual guidance
+        let error = create_test_error(ErrorType::AuthenticationFailed, "Authentication failed");
+        assert_ne!(engine.generate_from_pattern(&error).key_message, "HTTP 401");
+    }
+
+    #[test]
+    fn test_pattern_guidance_follows_language() {
+        let engine = |language| {
+            MentorEngine::with_config(MentorConfig {
+                cache_path: None,
+                language,
+                ..Default::default()
+            })
+        };
+        let (english, chinese) = (
+            engine(Language::English),
+            engine(Language::TraditionalChinese),
+        );
+        let curl = "curl -f https://api.example.com/v1/users";
+        let mut errors = vec![
+            create_test_error(
+                ErrorType::CommandNotFound,
+                "bash: kubctl: command not found",
+            ),
+            create_test_error(ErrorType::PermissionDenied, "Permission denied"),
+            create_test_error(ErrorType::FileNotFound, "No such file or directory"),
+            create_test_error(ErrorType::ConnectionRefused, "Connection refused"),
+            create_test_error(ErrorType::ConfigurationError, "unknown directive"),
+            create_test_error(ErrorType::SyntaxError, "unexpected token"),
+            create_test_error(ErrorType::DependencyError, "No module named 'flask'"),
+            create_test_error(ErrorType::DockerError, "No such container: api"),
+            create_test_error(ErrorType::KubernetesError, "Error from server (NotFound)"),
+            create_test_error(ErrorType::GitError, "fatal: not a git repository"),
+            create_test_error(ErrorType::Unknown, "segfault in worker"),
+            ErrorInfo::new(
+                ErrorType::KubernetesError,
+                1,
+                "api-0 0/1 OOMKilled",
+                "kubectl get pods",
+            ),
+            ErrorInfo::new(ErrorType::GitError, 1, "error: failed to push", "git push")
+                .with_output(" ! [rejected]  main -> main (non-fast-forward)\n"),
+            ErrorInfo::new(ErrorType::GitError, 1, "CONFLICT", "git rebase main")
+                .with_output("CONFLICT (content): Merge conflict in a.rs\nUU b.rs\n"),
+        ];
+        for status in [
+            "400", "401", "403", "404", "429", "500", "502", "503", "504",
+        ] {
+            errors.push(ErrorInfo::new(ErrorType::HttpError, 22, status, curl));
+        }
+
+        for error in &errors {
+            let (en, zh) = (
+                english.generate_from_pattern(error),
+                chinese.generate_from_pattern(error),
+            );
+            assert_ne!(en.explanation, zh.explanation, "{}", error.key_message);
+            assert_eq!(en.next_steps.len(), zh.next_steps.len());
+            for (en, zh) in en.next_steps.iter().zip(&zh.next_steps) {
+                assert_ne!(en.description, zh.description, "{}", error.key_message);
+                assert_eq!(en.command, zh.command);
+            }
+        }
+
+        let holder = ProcessInfo {
+            pid: 4242,
+            name: "node".to_string(),
+            user: None,
+        };
+        for holders in [vec![], vec![holder.clone()], vec![holder.clone(), holder]] {
+            let error = create_test_error(ErrorType::PortInUse, "port 8080 is already in use");
+            let en =
+                MentorEngine::port_in_use_guidance(Language::English, &error, Some(8080), &holders);
+            let zh = MentorEngine::port_in_use_guidance(
+                Language::TraditionalChinese,
+                &error,
+                Some(8080),
+                &holders,
+            );
+            assert_ne!(en.explanation, zh.explanation);
+            for (en, zh) in en.next_steps.iter().zip(&zh.next_steps) {
+                assert_ne!(en.description, zh.description);
+            }
+        }
+    }
+
+    #[test]
+    fn test_oom_killed_guidance() {
+        let engine = MentorEngine::new();
+        let step_commands = |guidance: &MentorGuidance| -> Vec<String> {
+            guidance
+                .next_steps
+                .iter()
+                .filter_map(|s| s.command.clone())
+                .collect()
+        };
+
+        let error = ErrorInfo::new(
+            ErrorType::KubernetesError,
+            1,
+            "web-7d4b9c-x2k    0/1     OOMKilled   3          2m",
+            "kubectl rollout status deployment/web",
+        );
+        let guidance = engine.generate_from_pattern(&error);
+        assert!(guidance.explanation.contains("limits.memory"));
+        let commands = step_commands(&guidance);
+        assert!(commands.contains(&"kubectl describe pod web-7d4b9c-x2k".to_string()));
+        assert!(commands.contains(&"kubectl top pod web-7d4b9c-x2k --containers".to_string()));
+
+        // Exit 137 from kubectl exec falls back to the exit code
+        let error = ErrorInfo::new(
+            ErrorType::OutOfMemory,
+            137,
+            "Killed (SIGKILL), often the out-of-memory killer",
+            "kubectl exec -it api-0 -- python load.py",
+        );
+        let guidance = engine.generate_from_pattern(&error);
+        assert!(step_commands(&guidance).contains(&"kubectl describe pod api-0".to_string()));
+
+        // Other Kubernetes errors and local OOMs keep their guidance
+        let error = create_test_error(ErrorType::KubernetesError, "Error from server (NotFound)");
+        assert!(!engine
+            .generate_from_pattern(&error)
+            .explanation
+            .contains("OOM"));
+        let error = ErrorInfo::new(ErrorType::OutOfMemory, 137, "Killed", "cargo build");
+        assert!(!engine
+            .generate_from_pattern(&error)
+            .explanation
+            .contains("resources.limits"));
+    }
+
     #[test]
     fn test_cache_integration() {
         let config = MentorConfig {
@@ -564,4 +1715,168 @@ mod tests {
         let guidance2 = engine.generate_sync(&error);
         assert_eq!(guidance2.source, GuidanceSource::Cached);
     }
+
+    /// LLM that counts calls and how many overlap
+    #[derive(Default)]
+    struct SlowLLM {
+        calls: AtomicUsize,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LLMBackend for SlowLLM {
+        async fn infer(&self, _prompt: &str) -> anyhow::Result<LLMResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            // Not JSON, so nothing gets cached and every flight hits the LLM
+            // twice (the reply is retried once)
+            Ok(LLMResponse {
+                command: String::new(),
+                confidence: 0,
+                reasoning: "not json".to_string(),
+                prompt_tokens: None,
+                completion_tokens: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identical_errors_share_one_llm_call() {
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            ..Default::default()
+        });
+        let llm = SlowLLM::default();
+        let error = create_test_error(ErrorType::Unknown, "segfault in worker");
+
+        tokio::join!(
+            engine.generate(&error, Some(&llm)),
+            engine.generate(&error, Some(&llm)),
+        );
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 2);
+
+        // Still within the window: reused without another call
+        engine.generate(&error, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_same_shape_errors_get_their_own_flight() {
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            ..Default::default()
+        });
+        let foo = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"Error from server (NotFound): pods "foo" not found"#,
+            "kubectl get pod foo",
+        );
+        let bar = ErrorInfo::new(
+            ErrorType::FileNotFound,
+            1,
+            r#"Error from server (NotFound): pods "bar" not found"#,
+            "kubectl get pod bar",
+        );
+
+        assert!(!Arc::ptr_eq(
+            &engine.join_flight(&foo),
+            &engine.join_flight(&bar)
+        ));
+        assert!(Arc::ptr_eq(
+            &engine.join_flight(&foo),
+            &engine.join_flight(&foo)
+        ));
+    }
+
+    /// LLM that is down; failures aren't cached
+    #[derive(Default)]
+    struct DownLLM {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl LLMBackend for DownLLM {
+        async fn infer(&self, _prompt: &str) -> anyhow::Result<LLMResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            anyhow::bail!("connection reset")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_cooldown_per_fingerprint() {
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            dedup_window: Duration::ZERO,
+            ..Default::default()
+        });
+        let llm = DownLLM::default();
+        let first = create_test_error(ErrorType::Unknown, "cannot load /srv/app/one.yml");
+        let repeat = create_test_error(ErrorType::Unknown, "cannot load /srv/app/two.yml");
+        let other = create_test_error(ErrorType::Unknown, "scheduler stalled");
+
+        engine.generate(&first, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 1);
+
+        // Same fingerprint: pattern guidance, no call
+        let guidance = engine.generate(&repeat, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 1);
+        assert_ne!(guidance.source, GuidanceSource::LLM);
+
+        engine.generate(&other, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 2);
+
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            dedup_window: Duration::ZERO,
+            llm_cooldown: Duration::ZERO,
+            ..Default::default()
+        });
+        engine.generate(&first, Some(&llm)).await;
+        engine.generate(&repeat, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_no_cooldown_without_llm() {
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            dedup_window: Duration::ZERO,
+            ..Default::default()
+        });
+        let llm = DownLLM::default();
+        let error = create_test_error(ErrorType::Unknown, "scheduler stalled");
+
+        // No backend yet (e.g. still starting): the error may go to the
+        // LLM once one is available
+        engine.generate(&error, None).await;
+        engine.generate(&error, Some(&llm)).await;
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_caps_concurrent_llm_calls() {
+        let engine = MentorEngine::with_config(MentorConfig {
+            cache_path: None,
+            max_concurrent_llm: 1,
+            ..Default::default()
+        });
+        let llm = SlowLLM::default();
+        let errors: Vec<_> = ["alpha failed", "beta failed", "gamma failed"]
+            .into_iter()
+            .map(|msg| create_test_error(ErrorType::Unknown, msg))
+            .collect();
+
+        tokio::join!(
+            engine.generate(&errors[0], Some(&llm)),
+            engine.generate(&errors[1], Some(&llm)),
+            engine.generate(&errors[2], Some(&llm)),
+        );
+        assert_eq!(llm.calls.load(Ordering::SeqCst), 6);
+        assert_eq!(llm.max_running.load(Ordering::SeqCst), 1);
+    }
 }
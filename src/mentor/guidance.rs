@@ -5,6 +5,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::i18n::Language;
+use super::platform::PackageManager;
+
 /// A single actionable next step
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NextStep {
@@ -30,6 +33,31 @@ impl NextStep {
             command: None,
         }
     }
+
+    /// Steps installing a package with the host's package manager
+    ///
+    /// Only the detected manager is suggested; when the platform is unknown
+    /// every supported one is listed.
+    pub fn install(
+        package: &str,
+        detected: Option<PackageManager>,
+        language: Language,
+    ) -> Vec<Self> {
+        let managers = match detected {
+            Some(pm) => vec![pm],
+            None => PackageManager::ALL.to_vec(),
+        };
+
+        managers
+            .into_iter()
+            .map(|pm| {
+                Self::with_command(
+                    language.fill("Install on {platform}", &[("platform", pm.platform())]),
+                    pm.install_command(package),
+                )
+            })
+            .collect()
+    }
 }
 
 /// Source of the guidance
@@ -146,6 +174,20 @@ mod tests {
         assert!(step.command.is_none());
     }
 
+    #[test]
+    fn test_install_steps() {
+        let steps = NextStep::install("htop", Some(PackageManager::Pacman), Language::English);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].description, "Install on Arch Linux");
+        assert_eq!(steps[0].command.as_deref(), Some("sudo pacman -S htop"));
+
+        let steps = NextStep::install("htop", None, Language::English);
+        assert_eq!(steps.len(), PackageManager::ALL.len());
+        assert!(steps
+            .iter()
+            .any(|s| s.command.as_deref() == Some("sudo apt install htop")));
+    }
+
     #[test]
     fn test_guidance_from_pattern() {
         let guidance =
@@ -0,0 +1,142 @@
+// Clickable source locations (OSC 8 hyperlinks)
+//
+// Terminals that understand OSC 8 turn `/etc/nginx/nginx.conf:42` into a
+// link that opens the file. Support can't be queried, so it is guessed from
+// the environment; everything else gets plain text.
+
+use std::path::Path;
+
+use super::types::SourceLocation;
+
+/// Where a source location link points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// `file://` URL (opens the file, no line)
+    File,
+    /// `vscode://file` URL, which opens at the line and column
+    Vscode,
+}
+
+impl LinkTarget {
+    /// Link target for the running terminal, None if it lacks OSC 8
+    pub fn detect() -> Option<Self> {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Link target given an environment lookup
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        match var("TERM_PROGRAM").as_deref() {
+            Some("vscode") => return Some(Self::Vscode),
+            Some("iTerm.app" | "WezTerm" | "ghostty" | "Hyper" | "Tabby") => {
+                return Some(Self::File)
+            }
+            _ => {}
+        }
+
+        // GNOME Terminal and other VTE terminals since 0.50
+        let vte = var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok());
+        if vte.is_some_and(|v| v >= 5000)
+            || var("KITTY_WINDOW_ID").is_some()
+            || var("WT_SESSION").is_some()
+        {
+            return Some(Self::File);
+        }
+
+        None
+    }
+
+    /// URL opening a source location
+    pub fn url(&self, location: &SourceLocation) -> String {
+        let path = percent_encode_path(&location.file);
+        match self {
+            Self::File => format!("file://{path}"),
+            Self::Vscode => {
+                let mut url = format!("vscode://file{path}");
+                if let Some(line) = location.line {
+                    url.push_str(&format!(":{line}"));
+                    if let Some(column) = location.column {
+                        url.push_str(&format!(":{column}"));
+                    }
+                }
+                url
+            }
+        }
+    }
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Percent-encode a path for a URL, keeping `/` separators
+fn percent_encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_detect_from_env() {
+        assert_eq!(
+            LinkTarget::from_env(env(&[("TERM_PROGRAM", "WezTerm")])),
+            Some(LinkTarget::File)
+        );
+        assert_eq!(
+            LinkTarget::from_env(env(&[("TERM_PROGRAM", "vscode")])),
+            Some(LinkTarget::Vscode)
+        );
+        assert_eq!(
+            LinkTarget::from_env(env(&[("VTE_VERSION", "6003")])),
+            Some(LinkTarget::File)
+        );
+        assert_eq!(LinkTarget::from_env(env(&[("VTE_VERSION", "4205")])), None);
+        assert_eq!(
+            LinkTarget::from_env(env(&[("TERM_PROGRAM", "Apple_Terminal")])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_urls() {
+        let location = SourceLocation::new("/etc/nginx/sites enabled/app.conf")
+            .with_line(42)
+            .with_column(7);
+
+        assert_eq!(
+            LinkTarget::File.url(&location),
+            "file:///etc/nginx/sites%20enabled/app.conf"
+        );
+        assert_eq!(
+            LinkTarget::Vscode.url(&location),
+            "vscode://file/etc/nginx/sites%20enabled/app.conf:42:7"
+        );
+    }
+
+    #[test]
+    fn test_hyperlink_escape() {
+        assert_eq!(
+            hyperlink("file:///tmp/a", "/tmp/a:1"),
+            "\x1b]8;;file:///tmp/a\x1b\\/tmp/a:1\x1b]8;;\x1b\\"
+        );
+    }
+}
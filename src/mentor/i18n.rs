@@ -0,0 +1,904 @@
+// Localized mentor text
+//
+// The static strings of the pattern-based mentor block (labels,
+// explanations, next-step notes, concepts) and of the engine's pattern
+// guidance in each supported language.
+// Commands, flags and error messages are never translated. The language
+// comes from `display.language` in config.toml, or from $LC_ALL,
+// $LC_MESSAGES and $LANG when that's unset.
+
+use super::types::ErrorType;
+use serde::{Deserialize, Serialize};
+
+/// Language of mentor output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    /// Traditional Chinese (Taiwan)
+    #[serde(rename = "zh-tw", alias = "zh-TW")]
+    TraditionalChinese,
+}
+
+/// Labels of the mentor block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    Type,
+    Key,
+    Location,
+    Try,
+    ThisMeans,
+    Search,
+    NextSteps,
+    LearnMore,
+    Refresher,
+    Practice,
+}
+
+/// Notes attached to suggested commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phrase {
+    Or,
+    ToCheckPath,
+    ToFindProcess,
+    CheckServiceRunning,
+    VerifyHostAndPort,
+    CheckFirewall,
+    RunAsRoot,
+    CheckPermissions,
+    IfExecutable,
+    ListDirectory,
+    PrintDirectory,
+    FindProcess,
+    StopProcess,
+    Stop,
+    UseDifferentPort,
+    DismissRefresher,
+    RecentlyResolved,
+}
+
+/// Traditional Chinese pattern guidance, by the English text the mentor
+/// engine writes
+///
+/// `{name}` placeholders are filled in by [`Language::fill`]. Search
+/// keywords aren't translated: English searches find far more answers.
+const GUIDANCE_ZH_TW: &[(&str, &str)] = &[
+    // Command not found
+    (
+        "Did you mean `{suggestion}`?",
+        "你是不是要輸入 `{suggestion}`？",
+    ),
+    (
+        "Check if it's installed somewhere",
+        "確認是否已安裝在其他位置",
+    ),
+    ("Install on {platform}", "在 {platform} 上安裝"),
+    ("Check your PATH", "檢查 PATH"),
+    (
+        "The command '{cmd}' is not installed on this system, or it's not in your PATH.",
+        "這台機器沒有安裝 '{cmd}' 指令，或它不在 PATH 裡。",
+    ),
+    ("PATH environment variable", "PATH 環境變數"),
+    (
+        "Package managers ({managers})",
+        "套件管理工具（{managers}）",
+    ),
+    // Permission denied
+    (
+        "You don't have permission to perform this action. This usually means you need \
+         elevated privileges (sudo) or the file/directory permissions need to be changed.",
+        "你沒有執行這個動作的權限。通常表示需要提升權限（sudo），\
+         或需要調整檔案／目錄的權限。",
+    ),
+    ("Run with sudo (if appropriate)", "用 sudo 執行（若適當）"),
+    ("Check file permissions", "檢查檔案權限"),
+    ("Make file executable", "讓檔案可以執行"),
+    (
+        "Check file ownership with 'ls -la'",
+        "用 'ls -la' 檢查檔案擁有者",
+    ),
+    ("Unix file permissions", "Unix 檔案權限"),
+    ("sudo and root access", "sudo 與 root 權限"),
+    ("File ownership", "檔案擁有者"),
+    // File not found
+    (
+        "The specified file or directory doesn't exist. Check the path for typos \
+         or verify the file was created.",
+        "指定的檔案或目錄不存在。請檢查路徑有沒有打錯，或確認檔案已經建立。",
+    ),
+    ("List current directory", "列出目前目錄"),
+    ("Show working directory", "顯示目前所在目錄"),
+    ("Search for file", "搜尋檔案"),
+    (
+        "Use tab completion to verify paths",
+        "用 Tab 自動補全確認路徑",
+    ),
+    (
+        "File paths (absolute vs relative)",
+        "檔案路徑（絕對與相對）",
+    ),
+    ("Working directory", "工作目錄"),
+    // Connection refused
+    (
+        "The connection was refused. The service might not be running, \
+         or a firewall could be blocking the connection.",
+        "連線被拒絕。服務可能沒有在執行，或被防火牆擋住了。",
+    ),
+    ("Check if service is running", "確認服務是否正在執行"),
+    ("List listening ports", "列出正在監聽的連接埠"),
+    ("Check firewall (Ubuntu)", "檢查防火牆（Ubuntu）"),
+    (
+        "Verify the host and port are correct",
+        "確認主機與連接埠是否正確",
+    ),
+    ("Network ports and services", "網路連接埠與服務"),
+    ("Systemd service management", "Systemd 服務管理"),
+    // Port in use
+    (
+        "Another process is already using this port. You'll need to stop that process \
+         or use a different port.",
+        "已經有其他程序在使用這個連接埠。你需要停止那個程序，或改用其他連接埠。",
+    ),
+    (
+        "Port {port} is already held by {holder}. Stop it or use a different port.",
+        "連接埠 {port} 已被 {holder} 佔用。請停止它，或改用其他連接埠。",
+    ),
+    (
+        "Port {port} is already held by {holders}. Stop them or use a different port.",
+        "連接埠 {port} 已被 {holders} 佔用。請停止它們，或改用其他連接埠。",
+    ),
+    ("Stop {holder}", "停止 {holder}"),
+    ("Find process using port", "找出使用該連接埠的程序"),
+    ("Or use netstat", "或使用 netstat"),
+    ("Kill process by PID", "依 PID 終止程序"),
+    (
+        "Or configure your service to use a different port",
+        "或把服務設定成使用其他連接埠",
+    ),
+    ("Network ports", "網路連接埠"),
+    ("Process management", "程序管理"),
+    // Configuration errors
+    ("configuration file", "設定檔"),
+    ("Open file at error line", "在出錯的那一行開啟檔案"),
+    (
+        "Check for typos in the directive name",
+        "檢查設定指令名稱有沒有打錯",
+    ),
+    ("Verify syntax matches documentation", "確認語法與文件相符"),
+    (
+        "Check the configuration file for syntax errors",
+        "檢查設定檔的語法錯誤",
+    ),
+    ("Compare with documentation examples", "對照文件中的範例"),
+    (
+        "See what changed since the last working config",
+        "查看與上次可用設定之間的差異",
+    ),
+    (
+        "There's an error in {location}. Check the file for typos or invalid directives.",
+        "{location} 有錯誤。請檢查檔案裡的錯字或無效的設定指令。",
+    ),
+    ("Configuration file syntax", "設定檔語法"),
+    // Syntax errors
+    (
+        "There's a syntax error. Check for missing quotes, brackets, or typos.",
+        "有語法錯誤。請檢查缺少的引號、括號或錯字。",
+    ),
+    (
+        "Check for missing or mismatched quotes",
+        "檢查引號是否缺少或不成對",
+    ),
+    (
+        "Check for missing brackets or parentheses",
+        "檢查是否缺少方括號或圓括號",
+    ),
+    ("Look for typos in keywords", "檢查關鍵字有沒有打錯"),
+    ("Syntax and parsing", "語法與解析"),
+    // Dependencies
+    (
+        "A required module or dependency is missing. You may need to install it.",
+        "缺少必要的模組或相依套件。可能需要先安裝它。",
+    ),
+    ("For Node.js", "Node.js 專案"),
+    ("For Python", "Python 專案"),
+    ("For Rust", "Rust 專案"),
+    (
+        "Check if the module name is spelled correctly",
+        "確認模組名稱拼寫正確",
+    ),
+    ("Package managers", "套件管理工具"),
+    ("Dependencies", "相依套件"),
+    // Docker
+    (
+        "A Docker error occurred. Check if Docker is running and the image/container exists.",
+        "Docker 發生錯誤。請確認 Docker 正在執行，且映像檔／容器存在。",
+    ),
+    ("Check Docker status", "檢查 Docker 狀態"),
+    ("List containers", "列出容器"),
+    ("List images", "列出映像檔"),
+    ("View container logs", "查看容器日誌"),
+    ("Docker containers", "Docker 容器"),
+    ("Docker images", "Docker 映像檔"),
+    // Kubernetes
+    (
+        "A Kubernetes error occurred. Check the resource name, namespace, and cluster connection.",
+        "Kubernetes 發生錯誤。請檢查資源名稱、namespace 與叢集連線。",
+    ),
+    ("Check cluster connection", "檢查叢集連線"),
+    ("List resources in namespace", "列出 namespace 裡的資源"),
+    ("Check all namespaces", "查看所有 namespace"),
+    ("Describe resource", "查看資源詳情"),
+    ("Kubernetes namespaces", "Kubernetes namespace"),
+    ("Kubernetes resources", "Kubernetes 資源"),
+    (
+        "The container used more memory than its limit (resources.limits.memory), so the \
+         kernel's OOM killer stopped it with SIGKILL (exit code 137). Kubernetes restarts it, \
+         and it will keep crashing until it uses less memory or gets a higher limit.",
+        "容器用掉的記憶體超過了上限（resources.limits.memory），所以被核心的 OOM killer \
+         以 SIGKILL 終止（exit code 137）。Kubernetes 會重新啟動它，但在它用更少的記憶體\
+         或拿到更高的上限之前，會一直崩潰。",
+    ),
+    (
+        "See the last state, exit code and memory limit",
+        "查看上一次的狀態、exit code 與記憶體上限",
+    ),
+    (
+        "Compare current memory use with the limit",
+        "比較目前的記憶體用量與上限",
+    ),
+    ("Read the logs from before the kill", "讀取被終止之前的日誌"),
+    (
+        "Raise the limit on the deployment",
+        "提高 deployment 的記憶體上限",
+    ),
+    (
+        "Or raise resources.limits.memory in the manifest and apply it again",
+        "或在 manifest 裡提高 resources.limits.memory 後重新 apply",
+    ),
+    ("Resource requests and limits", "資源的 requests 與 limits"),
+    // Git
+    (
+        "A Git error occurred. Check your repository state and remote configuration.",
+        "Git 發生錯誤。請檢查儲存庫狀態與遠端設定。",
+    ),
+    ("Check repository status", "檢查儲存庫狀態"),
+    ("View recent commits", "查看最近的 commit"),
+    ("Check remotes", "檢查遠端"),
+    ("Check branches", "檢查分支"),
+    ("Git workflow", "Git 工作流程"),
+    ("Git remotes", "Git 遠端"),
+    (
+        "Git couldn't {operation} automatically: 1 file has conflicting changes ({files}). \
+         Edit the <<<<<<< / >>>>>>> sections, or keep one side, then mark them resolved.",
+        "Git 無法自動完成 {operation}：1 個檔案有衝突（{files}）。\
+         編輯 <<<<<<< / >>>>>>> 區段，或保留其中一方，再標記為已解決。",
+    ),
+    (
+        "Git couldn't {operation} automatically: {count} files have conflicting changes \
+         ({files}). Edit the <<<<<<< / >>>>>>> sections, or keep one side, then mark them \
+         resolved.",
+        "Git 無法自動完成 {operation}：{count} 個檔案有衝突（{files}）。\
+         編輯 <<<<<<< / >>>>>>> 區段，或保留其中一方，再標記為已解決。",
+    ),
+    (
+        "During a rebase \"ours\" is the branch you're rebasing onto and \"theirs\" is your \
+         commit.",
+        "rebase 時，\"ours\" 是你要 rebase 到的分支，\"theirs\" 才是你的 commit。",
+    ),
+    ("Resolve in your merge tool", "用合併工具解決"),
+    ("Keep your side of {file}", "保留你這一方的 {file}"),
+    (
+        "Take the incoming side of {file}",
+        "採用傳入那一方的 {file}",
+    ),
+    ("Mark resolved and continue", "標記為已解決並繼續"),
+    (
+        "Give up and go back to before the {operation}",
+        "放棄並回到 {operation} 之前",
+    ),
+    ("Merge conflicts", "合併衝突"),
+    ("Git merge", "Git 合併"),
+    (
+        "The remote branch has commits you don't have yet, so pushing would overwrite them. \
+         Bring them in first, then push again.",
+        "遠端分支有你還沒有的 commit，直接 push 會覆蓋它們。先把它們拉進來，再 push 一次。",
+    ),
+    ("See what's new on the remote", "查看遠端有哪些新的變更"),
+    (
+        "Replay your commits on top of the remote",
+        "把你的 commit 重新套用在遠端之上",
+    ),
+    ("Push again", "再 push 一次"),
+    ("Rebasing", "Rebase"),
+    // HTTP status codes
+    (
+        "The server wants to know who you are and got no valid credentials: the token or \
+         password is missing, mistyped or expired.",
+        "伺服器需要確認你的身分，但沒有收到有效的憑證：token 或密碼缺少、打錯或已過期。",
+    ),
+    (
+        "See which headers were actually sent",
+        "查看實際送出了哪些標頭",
+    ),
+    ("Send a token", "帶上 token"),
+    (
+        "Check the token hasn't expired and belongs to this API",
+        "確認 token 沒有過期，且屬於這個 API",
+    ),
+    ("Authentication", "身分驗證"),
+    (
+        "The server knows who you are but won't allow this request: the credentials lack \
+         the permission, scope or role, or an IP allowlist or firewall blocks you.",
+        "伺服器知道你是誰，但不允許這個請求：憑證缺少所需的權限、scope 或角色，\
+         或被 IP 允許清單或防火牆擋住了。",
+    ),
+    (
+        "Check the scopes or role granted to your token or account",
+        "檢查你的 token 或帳號被授予的 scope 或角色",
+    ),
+    (
+        "Read the response body, it often names the missing permission",
+        "讀一下回應內容，通常會寫出缺少哪個權限",
+    ),
+    (
+        "Try from a network the API allows, e.g. over the VPN",
+        "從 API 允許的網路再試一次，例如透過 VPN",
+    ),
+    ("Authorization", "授權"),
+    (
+        "The server is up but has nothing at this address: the path, API version or \
+         resource ID is wrong, or the resource was deleted.",
+        "伺服器有回應，但這個位址什麼都沒有：路徑、API 版本或資源 ID 錯了，或資源已被刪除。",
+    ),
+    (
+        "Check the path for typos and a missing or extra trailing slash",
+        "檢查路徑有沒有打錯，以及結尾斜線是否多了或少了",
+    ),
+    (
+        "Check the API base path and version, e.g. /api/v1 vs /v2",
+        "檢查 API 的基本路徑與版本，例如 /api/v1 與 /v2",
+    ),
+    (
+        "List the parent collection to find the right ID",
+        "列出上一層的集合，找出正確的 ID",
+    ),
+    ("URL paths", "URL 路徑"),
+    (
+        "You sent more requests than the API allows in its time window. Wait before \
+         retrying, and retry with backoff instead of in a tight loop.",
+        "你送出的請求超過了 API 在時間區間內允許的數量。重試前先等一下，\
+         並以退避（backoff）的方式重試，不要密集地連續重試。",
+    ),
+    (
+        "See how long to wait and what the limits are",
+        "查看要等多久，以及限制是多少",
+    ),
+    (
+        "Let curl back off and retry for you",
+        "讓 curl 自動退避並重試",
+    ),
+    (
+        "Cache responses or batch requests to stay under the quota",
+        "快取回應或合併請求，以免超過配額",
+    ),
+    ("Rate limiting", "速率限制"),
+    (
+        "A proxy or load balancer answered, but the service behind it sent a bad \
+         response or none at all: it's down, crashing or on the wrong port.",
+        "代理或負載平衡器有回應，但後面的服務回傳了錯誤的回應或完全沒有回應：\
+         服務掛了、一直崩潰，或連接埠設錯了。",
+    ),
+    (
+        "The service is unavailable: overloaded, in maintenance, mid-deploy or \
+         with no healthy instances behind the load balancer.",
+        "服務目前無法使用：負載過高、維護中、正在部署，或負載平衡器後面沒有健康的執行個體。",
+    ),
+    (
+        "A proxy or load balancer gave up waiting for the service behind it: the \
+         backend is too slow or stuck.",
+        "代理或負載平衡器等不到後面服務的回應而放棄了：後端太慢或卡住了。",
+    ),
+    ("Check the backend service", "檢查後端服務"),
+    ("Read the proxy's error log", "讀取代理的錯誤日誌"),
+    (
+        "In Kubernetes, check the service has ready endpoints",
+        "在 Kubernetes 中，確認 service 有就緒的 endpoint",
+    ),
+    ("See which phase is slow", "看看是哪個階段比較慢"),
+    ("Reverse proxies", "反向代理"),
+    (
+        "The server hit an error while handling the request. The problem is on the server \
+         side; its logs say what went wrong.",
+        "伺服器在處理請求時發生錯誤。問題出在伺服器端，它的日誌會說明哪裡出錯。",
+    ),
+    (
+        "Check the server's application logs around this time",
+        "檢查伺服器在這個時間點前後的應用程式日誌",
+    ),
+    (
+        "Retry once, it may be transient",
+        "重試一次，可能只是暫時的問題",
+    ),
+    (
+        "The server rejected the request itself. Check the method, headers and body.",
+        "伺服器拒絕了這個請求本身。請檢查 method、標頭與內容。",
+    ),
+    ("See the full request and response", "查看完整的請求與回應"),
+    ("HTTP status codes", "HTTP 狀態碼"),
+    // Anything else
+    (
+        "Check the full error output above",
+        "查看上方完整的錯誤輸出",
+    ),
+    (
+        "Search for the error message online",
+        "上網搜尋這個錯誤訊息",
+    ),
+    (
+        "An error occurred. Check the full output for details.",
+        "發生錯誤。請查看完整輸出了解細節。",
+    ),
+];
+
+impl Language {
+    /// Code used in config.toml
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::TraditionalChinese => "zh-TW",
+        }
+    }
+
+    /// Language of a locale such as `zh_TW.UTF-8`, `zh-Hant` or `en_US`
+    ///
+    /// None for locales without a translation, and for `C`/`POSIX`.
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let tag = locale
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+        match tag.as_str() {
+            "zh-tw" | "zh-hk" | "zh-mo" | "zh-hant" => Some(Language::TraditionalChinese),
+            _ if tag.starts_with("zh-hant-") => Some(Language::TraditionalChinese),
+            "en" => Some(Language::English),
+            _ if tag.starts_with("en-") => Some(Language::English),
+            _ => None,
+        }
+    }
+
+    /// Language from the first set locale variable, English if none has a
+    /// translation
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// The configured language, or the environment's if none is set
+    pub fn resolve(configured: Option<Language>) -> Self {
+        configured.unwrap_or_else(Self::from_env)
+    }
+
+    /// Sentence appended to LLM prompts so replies come back in this
+    /// language; empty for English
+    pub fn prompt_instruction(&self) -> &'static str {
+        match self {
+            Language::English => "",
+            Language::TraditionalChinese => {
+                "\n\nRespond in Traditional Chinese (繁體中文, Taiwan usage). \
+                 Keep commands, flags, file paths and quoted error messages unchanged."
+            }
+        }
+    }
+
+    /// Label text, with its trailing colon
+    pub fn label(&self, label: Label) -> &'static str {
+        match self {
+            Language::English => match label {
+                Label::Type => "Type:",
+                Label::Key => "Key:",
+                Label::Location => "Location:",
+                Label::Try => "Try:",
+                Label::ThisMeans => "This means:",
+                Label::Search => "Search:",
+                Label::NextSteps => "Next steps:",
+                Label::LearnMore => "Learn more:",
+                Label::Refresher => "Concept refresher:",
+                Label::Practice => "Practice:",
+            },
+            Language::TraditionalChinese => match label {
+                Label::Type => "類型：",
+                Label::Key => "重點：",
+                Label::Location => "位置：",
+                Label::Try => "試試：",
+                Label::ThisMeans => "這表示：",
+                Label::Search => "搜尋：",
+                Label::NextSteps => "下一步：",
+                Label::LearnMore => "延伸學習：",
+                Label::Refresher => "觀念複習：",
+                Label::Practice => "練習：",
+            },
+        }
+    }
+
+    /// Note text
+    pub fn phrase(&self, phrase: Phrase) -> &'static str {
+        match self {
+            Language::English => match phrase {
+                Phrase::Or => "or",
+                Phrase::ToCheckPath => "to check path",
+                Phrase::ToFindProcess => "to find process",
+                Phrase::CheckServiceRunning => "Check if service is running",
+                Phrase::VerifyHostAndPort => "Verify the host and port",
+                Phrase::CheckFirewall => "Check firewall settings",
+                Phrase::RunAsRoot => "run last command as root",
+                Phrase::CheckPermissions => "check permissions",
+                Phrase::IfExecutable => "if executable",
+                Phrase::ListDirectory => "list current directory",
+                Phrase::PrintDirectory => "print working directory",
+                Phrase::FindProcess => "find process",
+                Phrase::StopProcess => "stop process",
+                Phrase::Stop => "stop",
+                Phrase::UseDifferentPort => "Use a different port",
+                Phrase::DismissRefresher => "stop refreshers for this topic",
+                Phrase::RecentlyResolved => "of recent ones resolved",
+            },
+            Language::TraditionalChinese => match phrase {
+                Phrase::Or => "或",
+                Phrase::ToCheckPath => "檢查路徑",
+                Phrase::ToFindProcess => "找出佔用的程序",
+                Phrase::CheckServiceRunning => "確認服務是否正在執行",
+                Phrase::VerifyHostAndPort => "確認主機與連接埠",
+                Phrase::CheckFirewall => "檢查防火牆設定",
+                Phrase::RunAsRoot => "以 root 重新執行上一個指令",
+                Phrase::CheckPermissions => "檢查權限",
+                Phrase::IfExecutable => "若為可執行檔",
+                Phrase::ListDirectory => "列出目前目錄",
+                Phrase::PrintDirectory => "顯示目前所在目錄",
+                Phrase::FindProcess => "找出程序",
+                Phrase::StopProcess => "停止程序",
+                Phrase::Stop => "停止",
+                Phrase::UseDifferentPort => "改用其他連接埠",
+                Phrase::DismissRefresher => "不再提醒複習這個主題",
+                Phrase::RecentlyResolved => "近期已解決",
+            },
+        }
+    }
+
+    /// Pattern guidance text in this language
+    ///
+    /// Takes the English text as the mentor engine writes it; text without
+    /// a translation stays English.
+    pub fn text(&self, english: &'static str) -> &'static str {
+        match self {
+            Language::English => english,
+            Language::TraditionalChinese => GUIDANCE_ZH_TW
+                .iter()
+                .find(|(en, _)| *en == english)
+                .map_or(english, |(_, translated)| translated),
+        }
+    }
+
+    /// Like [`text`](Self::text), with `{name}` placeholders filled in
+    pub fn fill(&self, english: &'static str, values: &[(&str, &str)]) -> String {
+        values
+            .iter()
+            .fold(self.text(english).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+
+    /// What an error type means, for the verbose mentor block
+    pub fn explanation(&self, error_type: &ErrorType) -> Option<&'static str> {
+        let english = match error_type {
+            ErrorType::CommandNotFound => {
+                "The shell cannot find this command. It's either not installed, \
+                 or not in your PATH environment variable."
+            }
+            ErrorType::PermissionDenied => {
+                "You don't have permission to perform this action. \
+                 This usually means you need elevated privileges (sudo) \
+                 or the file/directory permissions need to be changed."
+            }
+            ErrorType::FileNotFound => {
+                "The specified file or directory doesn't exist. \
+                 Check the path for typos or verify the file was created."
+            }
+            ErrorType::SyntaxError => {
+                "There's a syntax error in the command or configuration file. \
+                 Check for typos, missing quotes, or incorrect formatting."
+            }
+            ErrorType::ConnectionRefused => {
+                "The connection was refused by the target. \
+                 The service might not be running, or a firewall is blocking it."
+            }
+            ErrorType::ConfigurationError => {
+                "There's an error in a configuration file. \
+                 Check the file for typos or invalid directives."
+            }
+            ErrorType::PortInUse => {
+                "Another process is already using this port. \
+                 You'll need to stop that process or use a different port."
+            }
+            ErrorType::DependencyError => {
+                "A required module or dependency is missing. \
+                 You may need to install it or check your import paths."
+            }
+            ErrorType::OutOfMemory => {
+                "The process ran out of memory, or the kernel's OOM killer \
+                 stopped it (exit 137 means it got SIGKILL)."
+            }
+            ErrorType::Crashed => {
+                "The program crashed instead of exiting with an error: \
+                 the exit code is 128 plus the signal that killed it. \
+                 This is a bug in the program or a broken build, not in your command."
+            }
+            _ => return None,
+        };
+
+        Some(match self {
+            Language::English => english,
+            Language::TraditionalChinese => match error_type {
+                ErrorType::CommandNotFound => {
+                    "Shell 找不到這個指令。可能是尚未安裝，或不在 PATH 環境變數裡。"
+                }
+                ErrorType::PermissionDenied => {
+                    "你沒有執行這個動作的權限。通常表示需要提升權限（sudo），\
+                     或需要調整檔案／目錄的權限。"
+                }
+                ErrorType::FileNotFound => {
+                    "指定的檔案或目錄不存在。請檢查路徑有沒有打錯，或確認檔案已經建立。"
+                }
+                ErrorType::SyntaxError => {
+                    "指令或設定檔有語法錯誤。請檢查錯字、缺少的引號或格式問題。"
+                }
+                ErrorType::ConnectionRefused => {
+                    "目標拒絕了連線。服務可能沒有在執行，或被防火牆擋住了。"
+                }
+                ErrorType::ConfigurationError => {
+                    "設定檔有錯誤。請檢查檔案裡的錯字或無效的設定指令。"
+                }
+                ErrorType::PortInUse => {
+                    "已經有其他程序在使用這個連接埠。你需要停止那個程序，或改用其他連接埠。"
+                }
+                ErrorType::DependencyError => {
+                    "缺少必要的模組或相依套件。可能需要安裝它，或檢查 import 路徑。"
+                }
+                ErrorType::OutOfMemory => {
+                    "程序用光了記憶體，或被核心的 OOM killer 終止了\
+                     （exit 137 表示收到 SIGKILL）。"
+                }
+                ErrorType::Crashed => {
+                    "程式是崩潰了，而不是正常地回報錯誤：exit code 是 128 加上終止它的訊號編號。\
+                     這是程式本身的 bug 或建置有問題，不是你的指令有錯。"
+                }
+                _ => english,
+            },
+        })
+    }
+
+    /// A small exercise for a concept the user keeps struggling with
+    pub fn practice(&self, error_type: &ErrorType) -> &'static str {
+        match (self, error_type) {
+            (Language::English, ErrorType::CommandNotFound) => {
+                "Run `type <command>` and `echo $PATH` for a command you use daily, \
+                 and find the PATH entry it comes from."
+            }
+            (Language::English, ErrorType::PermissionDenied) => {
+                "Run `ls -l` on a file you own and on one in /etc, \
+                 and read the owner, group and mode columns."
+            }
+            (Language::English, ErrorType::FileNotFound) => {
+                "Before your next `cd` or `cat`, run `pwd` and `ls` \
+                 to confirm where you are and what's there."
+            }
+            (Language::English, ErrorType::ConnectionRefused) => {
+                "Run `ss -tlnp` and match a service you know to the port it listens on."
+            }
+            (Language::English, ErrorType::ConfigurationError) => {
+                "Run the config test (`nginx -t`, `apachectl configtest`) \
+                 before every reload, even when you're sure."
+            }
+            (Language::English, ErrorType::PortInUse) => {
+                "Start `python3 -m http.server 8000` twice, \
+                 then use `ss -tlnp` to find which process holds the port."
+            }
+            (Language::English, ErrorType::DockerError) => {
+                "Run `docker ps -a`, then `docker logs` on a stopped container \
+                 to see why it exited."
+            }
+            (Language::English, ErrorType::KubernetesError) => {
+                "Pick a pod and walk `kubectl get`, `kubectl describe`, \
+                 then `kubectl logs --previous`, in that order."
+            }
+            (Language::English, ErrorType::GitError) => {
+                "Run `git status` and `git log --oneline -5` before each push or pull \
+                 to know where you stand."
+            }
+            (Language::English, ErrorType::OutOfMemory | ErrorType::Crashed) => {
+                "Run `sh -c 'kill -9 $$'; echo $?` and work out why the exit code is 137."
+            }
+            (Language::English, _) => {
+                "Next time, read the first error line in full before retrying or searching."
+            }
+            (Language::TraditionalChinese, ErrorType::CommandNotFound) => {
+                "對一個每天都用的指令執行 `type <指令>` 和 `echo $PATH`，\
+                 找出它來自 PATH 裡的哪個目錄。"
+            }
+            (Language::TraditionalChinese, ErrorType::PermissionDenied) => {
+                "對自己的檔案和 /etc 裡的檔案各執行一次 `ls -l`，\
+                 看懂擁有者、群組和權限欄位。"
+            }
+            (Language::TraditionalChinese, ErrorType::FileNotFound) => {
+                "下次 `cd` 或 `cat` 之前，先執行 `pwd` 和 `ls` 確認自己在哪裡、有哪些檔案。"
+            }
+            (Language::TraditionalChinese, ErrorType::ConnectionRefused) => {
+                "執行 `ss -tlnp`，把一個你熟悉的服務對應到它監聽的連接埠。"
+            }
+            (Language::TraditionalChinese, ErrorType::ConfigurationError) => {
+                "每次 reload 之前都先跑設定檢查（`nginx -t`、`apachectl configtest`），\
+                 即使你很有把握。"
+            }
+            (Language::TraditionalChinese, ErrorType::PortInUse) => {
+                "啟動兩次 `python3 -m http.server 8000`，再用 `ss -tlnp` 找出佔用連接埠的程序。"
+            }
+            (Language::TraditionalChinese, ErrorType::DockerError) => {
+                "執行 `docker ps -a`，再對已停止的容器執行 `docker logs`，看看它為什麼結束。"
+            }
+            (Language::TraditionalChinese, ErrorType::KubernetesError) => {
+                "挑一個 pod，依序執行 `kubectl get`、`kubectl describe`、\
+                 `kubectl logs --previous`。"
+            }
+            (Language::TraditionalChinese, ErrorType::GitError) => {
+                "每次 push 或 pull 之前先執行 `git status` 和 `git log --oneline -5`，\
+                 確認目前的狀態。"
+            }
+            (Language::TraditionalChinese, ErrorType::OutOfMemory | ErrorType::Crashed) => {
+                "執行 `sh -c 'kill -9 $$'; echo $?`，想想為什麼 exit code 是 137。"
+            }
+            (Language::TraditionalChinese, _) => {
+                "下次重試或搜尋之前，先把第一行錯誤訊息完整讀一遍。"
+            }
+        }
+    }
+
+    /// Topics worth reading up on after an error type
+    pub fn concepts(&self, error_type: &ErrorType) -> Option<&'static str> {
+        let concepts = match (self, error_type) {
+            (Language::English, ErrorType::CommandNotFound) => "PATH environment, package managers",
+            (Language::English, ErrorType::PermissionDenied) => {
+                "Unix permissions, sudo, file ownership"
+            }
+            (Language::English, ErrorType::FileNotFound) => {
+                "file paths, working directory, ls command"
+            }
+            (Language::English, ErrorType::ConnectionRefused) => "networking, ports, services",
+            (Language::English, ErrorType::ConfigurationError) => {
+                "configuration files, syntax checking"
+            }
+            (Language::English, ErrorType::DockerError) => "Docker containers, images, volumes",
+            (Language::English, ErrorType::KubernetesError) => {
+                "Kubernetes pods, deployments, services"
+            }
+            (Language::English, ErrorType::GitError) => "Git workflow, branches, commits",
+            (Language::English, ErrorType::OutOfMemory | ErrorType::Crashed) => {
+                "signals, exit codes, dmesg"
+            }
+            (Language::TraditionalChinese, ErrorType::CommandNotFound) => {
+                "PATH 環境變數、套件管理工具"
+            }
+            (Language::TraditionalChinese, ErrorType::PermissionDenied) => {
+                "Unix 權限、sudo、檔案擁有者"
+            }
+            (Language::TraditionalChinese, ErrorType::FileNotFound) => {
+                "檔案路徑、工作目錄、ls 指令"
+            }
+            (Language::TraditionalChinese, ErrorType::ConnectionRefused) => "網路、連接埠、服務",
+            (Language::TraditionalChinese, ErrorType::ConfigurationError) => "設定檔、語法檢查",
+            (Language::TraditionalChinese, ErrorType::DockerError) => "Docker 容器、映像檔、volume",
+            (Language::TraditionalChinese, ErrorType::KubernetesError) => {
+                "Kubernetes 的 pod、deployment、service"
+            }
+            (Language::TraditionalChinese, ErrorType::GitError) => "Git 工作流程、分支、commit",
+            (Language::TraditionalChinese, ErrorType::OutOfMemory | ErrorType::Crashed) => {
+                "訊號、exit code、dmesg"
+            }
+            _ => return None,
+        };
+        Some(concepts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_locale() {
+        assert_eq!(
+            Language::from_locale("zh_TW.UTF-8"),
+            Some(Language::TraditionalChinese)
+        );
+        assert_eq!(
+            Language::from_locale("zh-Hant-TW"),
+            Some(Language::TraditionalChinese)
+        );
+        assert_eq!(
+            Language::from_locale("en_US.UTF-8"),
+            Some(Language::English)
+        );
+        assert_eq!(Language::from_locale("C"), None);
+        assert_eq!(Language::from_locale("fr_FR.UTF-8"), None);
+    }
+
+    #[test]
+    fn test_configured_language_wins() {
+        assert_eq!(
+            Language::resolve(Some(Language::TraditionalChinese)),
+            Language::TraditionalChinese
+        );
+    }
+
+    #[test]
+    fn test_guidance_text_keeps_placeholders() {
+        let placeholders = |text: &str| -> Vec<String> {
+            let mut names: Vec<String> = text
+                .split('{')
+                .skip(1)
+                .filter_map(|rest| rest.split_once('}').map(|(name, _)| name.to_string()))
+                .collect();
+            names.sort();
+            names
+        };
+        for (i, (english, translated)) in GUIDANCE_ZH_TW.iter().enumerate() {
+            assert_eq!(placeholders(english), placeholders(translated), "{english}");
+            assert!(
+                GUIDANCE_ZH_TW[..i].iter().all(|(en, _)| en != english),
+                "duplicate: {english}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_translations_cover_english() {
+        let types = [
+            ErrorType::CommandNotFound,
+            ErrorType::PermissionDenied,
+            ErrorType::FileNotFound,
+            ErrorType::SyntaxError,
+            ErrorType::ConnectionRefused,
+            ErrorType::ConfigurationError,
+            ErrorType::PortInUse,
+            ErrorType::DependencyError,
+            ErrorType::OutOfMemory,
+            ErrorType::Crashed,
+            ErrorType::DockerError,
+            ErrorType::KubernetesError,
+            ErrorType::GitError,
+        ];
+        let zh = Language::TraditionalChinese;
+        for error_type in &types {
+            let english = Language::English.explanation(error_type);
+            assert_eq!(english.is_some(), zh.explanation(error_type).is_some());
+            if english.is_some() {
+                assert_ne!(english, zh.explanation(error_type), "{error_type:?}");
+            }
+            assert_eq!(
+                Language::English.concepts(error_type).is_some(),
+                zh.concepts(error_type).is_some(),
+                "{error_type:?}"
+            );
+        }
+        assert_eq!(zh.label(Label::NextSteps), "下一步：");
+        assert_eq!(zh.fill("Stop {holder}", &[("holder", "node")]), "停止 node");
+        assert_eq!(Language::English.text("Push again"), "Push again");
+        assert!(Language::English.prompt_instruction().is_empty());
+        assert!(zh.prompt_instruction().contains("Traditional Chinese"));
+    }
+}
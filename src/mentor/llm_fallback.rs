@@ -1,28 +1,37 @@
 // LLM Fallback for Mentor System
 //
 // When pattern matching doesn't find a match, use the LLM to generate
-// educational guidance for unknown errors.
+// educational guidance for unknown errors. The model must answer with a
+// JSON object matching `GUIDANCE_SCHEMA`; a reply that doesn't is retried
+// once with the validation error before falling back to the raw text.
 
 use anyhow::Result;
 use serde::Deserialize;
 
 use super::guidance::{GuidanceSource, MentorGuidance, NextStep};
+use super::i18n::Language;
 use super::types::ErrorInfo;
 use crate::tools::LLMBackend;
 
 /// LLM-based mentor guidance generator
 pub struct LLMMentor;
 
-/// Response structure expected from LLM
+/// Shape of the JSON object the model must return
+const GUIDANCE_SCHEMA: &str = r#"{
+  "key_message": string, non-empty (the most important part of the error, 1 sentence),
+  "explanation": string, non-empty (what it means in simple terms, 2-3 sentences),
+  "search_keywords": [string, ...] (1-2 entries),
+  "next_steps": [{"description": string, non-empty, "command": string or null}, ...] (2-3 entries),
+  "related_concepts": [string, ...] (1-2 entries)
+}"#;
+
+/// Response structure expected from LLM; every field is required
 #[derive(Debug, Deserialize)]
 struct LLMResponse {
     key_message: String,
     explanation: String,
-    #[serde(default)]
     search_keywords: Vec<String>,
-    #[serde(default)]
     next_steps: Vec<LLMNextStep>,
-    #[serde(default)]
     related_concepts: Vec<String>,
 }
 
@@ -32,18 +41,77 @@ struct LLMNextStep {
     command: Option<String>,
 }
 
+impl LLMResponse {
+    /// Check the parts serde can't: required text and steps are present
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.key_message.trim().is_empty() {
+            return Err("key_message is empty".to_string());
+        }
+        if self.explanation.trim().is_empty() {
+            return Err("explanation is empty".to_string());
+        }
+        if self.next_steps.is_empty() {
+            return Err("next_steps has no entries".to_string());
+        }
+        if let Some(i) = self
+            .next_steps
+            .iter()
+            .position(|s| s.description.trim().is_empty())
+        {
+            return Err(format!("next_steps[{i}].description is empty"));
+        }
+        Ok(())
+    }
+
+    fn into_guidance(self) -> MentorGuidance {
+        let next_steps = self
+            .next_steps
+            .into_iter()
+            .map(|s| match s.command.filter(|c| !c.trim().is_empty()) {
+                Some(cmd) => NextStep::with_command(s.description, cmd),
+                None => NextStep::new(s.description),
+            })
+            .collect();
+
+        MentorGuidance {
+            key_message: self.key_message,
+            explanation: self.explanation,
+            search_keywords: self.search_keywords,
+            next_steps,
+            related_concepts: self.related_concepts,
+            source: GuidanceSource::LLM,
+        }
+    }
+}
+
 impl LLMMentor {
     /// Generate mentor guidance using LLM
-    pub async fn generate(error: &ErrorInfo, llm: &dyn LLMBackend) -> Result<MentorGuidance> {
-        let prompt = Self::build_prompt(error);
+    ///
+    /// A reply that doesn't match the schema is retried once, telling the
+    /// model what was wrong; if that fails too the raw text is used. The
+    /// guidance text is asked for in `language`.
+    pub async fn generate(
+        error: &ErrorInfo,
+        llm: &dyn LLMBackend,
+        language: Language,
+    ) -> Result<MentorGuidance> {
+        let prompt = Self::build_prompt(error, language);
         let response = llm.infer(&prompt).await?;
 
-        // Try to parse as JSON
-        Self::parse_response(&response.reasoning, error)
+        let problem = match Self::parse_strict(&response.reasoning) {
+            Ok(guidance) => return Ok(guidance),
+            Err(problem) => problem,
+        };
+        log::warn!("LLM guidance didn't match the schema ({problem}), retrying");
+
+        let retry = llm
+            .infer(&Self::build_retry_prompt(&prompt, &problem))
+            .await?;
+        Self::parse_response(&retry.reasoning, error)
     }
 
     /// Build the prompt for the LLM
-    fn build_prompt(error: &ErrorInfo) -> String {
+    fn build_prompt(error: &ErrorInfo, language: Language) -> String {
         // Truncate output if too long
         let output = if error.full_output.len() > 1000 {
             format!("{}...(truncated)", &error.full_output[..1000])
@@ -63,17 +131,9 @@ The user ran a command that failed:
 {output}
 ```
 
-Provide educational guidance in this exact JSON format (no markdown, just raw JSON):
-{{
-  "key_message": "The most important part of the error (1 sentence)",
-  "explanation": "What this error means in simple terms (2-3 sentences)",
-  "search_keywords": ["keyword1", "keyword2"],
-  "next_steps": [
-    {{"description": "What to do first", "command": "actual command or null"}},
-    {{"description": "What to do next", "command": null}}
-  ],
-  "related_concepts": ["concept to learn about"]
-}}
+Provide educational guidance as one JSON object with exactly these fields
+(all required, no markdown, just raw JSON):
+{schema}
 
 Important:
 - Focus on TEACHING, not just fixing
@@ -82,44 +142,39 @@ Important:
 - Keep explanations simple for beginners
 - Include 2-3 next steps
 - Include 1-2 search keywords
-- Return ONLY valid JSON, no other text"#,
+- Return ONLY valid JSON, no other text{language}"#,
             command = error.command,
             exit_code = error.exit_code,
             error_type = error.error_type.name(),
-            output = output
+            output = output,
+            schema = GUIDANCE_SCHEMA,
+            language = language.prompt_instruction()
         )
     }
 
-    /// Parse the LLM response into MentorGuidance
-    fn parse_response(response: &str, error: &ErrorInfo) -> Result<MentorGuidance> {
-        // Try to extract JSON from the response
-        let json_str = Self::extract_json(response);
-
-        match serde_json::from_str::<LLMResponse>(&json_str) {
-            Ok(parsed) => {
-                let next_steps: Vec<NextStep> = parsed
-                    .next_steps
-                    .into_iter()
-                    .map(|s| {
-                        if let Some(cmd) = s.command {
-                            NextStep::with_command(s.description, cmd)
-                        } else {
-                            NextStep::new(s.description)
-                        }
-                    })
-                    .collect();
+    /// Ask again after a reply failed validation
+    fn build_retry_prompt(prompt: &str, problem: &str) -> String {
+        format!(
+            "{prompt}\n\nYour previous reply was rejected: {problem}.\n\
+             Reply again with only the JSON object, with every field filled in."
+        )
+    }
 
-                Ok(MentorGuidance {
-                    key_message: parsed.key_message,
-                    explanation: parsed.explanation,
-                    search_keywords: parsed.search_keywords,
-                    next_steps,
-                    related_concepts: parsed.related_concepts,
-                    source: GuidanceSource::LLM,
-                })
-            }
+    /// Parse and validate a reply against the schema; the error says why not
+    fn parse_strict(response: &str) -> std::result::Result<MentorGuidance, String> {
+        let parsed: LLMResponse =
+            serde_json::from_str(&Self::extract_json(response)).map_err(|e| e.to_string())?;
+        parsed.validate()?;
+        Ok(parsed.into_guidance())
+    }
+
+    /// Parse the LLM response into MentorGuidance, falling back to the raw
+    /// text as the explanation when it doesn't match the schema
+    fn parse_response(response: &str, error: &ErrorInfo) -> Result<MentorGuidance> {
+        match Self::parse_strict(response) {
+            Ok(guidance) => Ok(guidance),
             Err(e) => {
-                log::warn!("Failed to parse LLM response as JSON: {e}");
+                log::warn!("LLM response doesn't match the guidance schema: {e}");
                 log::debug!("Response was: {response}");
 
                 // Return a basic guidance with the raw response as explanation
@@ -136,65 +191,11 @@ Important:
     }
 
     /// Try to extract JSON from a response that might have extra text
+    ///
+    /// Strips code fences and prose around the first object and drops
+    /// trailing commas; falls back to the trimmed response.
     fn extract_json(response: &str) -> String {
-        let response = response.trim();
-
-        // If it starts with {, assume it's JSON
-        if response.starts_with('{') {
-            // Find matching closing brace
-            let mut depth = 0;
-            let mut end = 0;
-            for (i, c) in response.chars().enumerate() {
-                match c {
-                    '{' => depth += 1,
-                    '}' => {
-                        depth -= 1;
-                        if depth == 0 {
-                            end = i + 1;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if end > 0 {
-                return response[..end].to_string();
-            }
-        }
-
-        // Try to find JSON in markdown code block
-        if let Some(start) = response.find("```json") {
-            let json_start = start + 7;
-            if let Some(end) = response[json_start..].find("```") {
-                return response[json_start..json_start + end].trim().to_string();
-            }
-        }
-
-        // Try to find JSON in generic code block
-        if let Some(start) = response.find("```") {
-            let block_start = start + 3;
-            // Skip language identifier if present
-            let content_start = response[block_start..]
-                .find('\n')
-                .map(|i| block_start + i + 1)
-                .unwrap_or(block_start);
-            if let Some(end) = response[content_start..].find("```") {
-                return response[content_start..content_start + end]
-                    .trim()
-                    .to_string();
-            }
-        }
-
-        // Try to find { ... } anywhere
-        if let Some(start) = response.find('{') {
-            if let Some(end) = response.rfind('}') {
-                if end > start {
-                    return response[start..=end].to_string();
-                }
-            }
-        }
-
-        response.to_string()
+        crate::ai::extract_json(response).unwrap_or_else(|| response.trim().to_string())
     }
 
     /// Extract a usable explanation from non-JSON response
@@ -230,12 +231,16 @@ mod tests {
     #[test]
     fn test_build_prompt() {
         let error = create_test_error();
-        let prompt = LLMMentor::build_prompt(&error);
+        let prompt = LLMMentor::build_prompt(&error, Language::English);
 
         assert!(prompt.contains("foo --bar"));
         assert!(prompt.contains("127"));
         assert!(prompt.contains("Command Not Found"));
         assert!(prompt.contains("JSON"));
+        assert!(prompt.ends_with("no other text"));
+
+        let zh = LLMMentor::build_prompt(&error, Language::TraditionalChinese);
+        assert!(zh.contains("Respond in Traditional Chinese"));
     }
 
     #[test]
@@ -266,6 +271,13 @@ Hope this helps!"#;
         assert!(json.starts_with('{'));
     }
 
+    #[test]
+    fn test_extract_json_trailing_comma() {
+        let response = "```json\n{\"key_message\": \"test\", \"explanation\": \"test\",}\n```";
+        let json = LLMMentor::extract_json(response);
+        assert_eq!(json, r#"{"key_message": "test", "explanation": "test"}"#);
+    }
+
     #[test]
     fn test_parse_valid_response() {
         let error = create_test_error();
@@ -296,6 +308,73 @@ Hope this helps!"#;
         assert!(!guidance.explanation.is_empty());
     }
 
+    #[test]
+    fn test_parse_strict_rejects_incomplete() {
+        let missing = r#"{"key_message": "x", "explanation": "y", "next_steps": []}"#;
+        assert!(LLMMentor::parse_strict(missing)
+            .unwrap_err()
+            .contains("search_keywords"));
+
+        let no_steps = r#"{"key_message": "x", "explanation": "y", "search_keywords": [],
+            "next_steps": [], "related_concepts": []}"#;
+        assert_eq!(
+            LLMMentor::parse_strict(no_steps).unwrap_err(),
+            "next_steps has no entries"
+        );
+
+        let blank_command = r#"```json
+{"key_message": "x", "explanation": "y", "search_keywords": ["k"],
+ "next_steps": [{"description": "Check PATH", "command": ""}], "related_concepts": ["PATH"],}
+```"#;
+        let guidance = LLMMentor::parse_strict(blank_command).unwrap();
+        assert_eq!(guidance.next_steps[0].command, None);
+    }
+
+    /// Replies with each canned response in turn, recording the prompts
+    struct ScriptedLLM {
+        replies: Vec<&'static str>,
+        prompts: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMBackend for ScriptedLLM {
+        async fn infer(&self, prompt: &str) -> Result<crate::tools::LLMResponse> {
+            let mut prompts = self.prompts.lock().unwrap();
+            prompts.push(prompt.to_string());
+            Ok(crate::tools::LLMResponse {
+                command: String::new(),
+                confidence: 80,
+                reasoning: self.replies[prompts.len() - 1].to_string(),
+                prompt_tokens: None,
+                completion_tokens: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_retries_once_on_invalid_reply() {
+        let llm = ScriptedLLM {
+            replies: vec![
+                r#"{"key_message": "not found"}"#,
+                r#"{"key_message": "not found", "explanation": "foo isn't installed",
+                    "search_keywords": ["install foo"],
+                    "next_steps": [{"description": "Install it", "command": "brew install foo"}],
+                    "related_concepts": ["PATH"]}"#,
+            ],
+            prompts: std::sync::Mutex::new(Vec::new()),
+        };
+
+        let guidance = LLMMentor::generate(&create_test_error(), &llm, Language::English)
+            .await
+            .unwrap();
+
+        assert_eq!(guidance.explanation, "foo isn't installed");
+        assert_eq!(guidance.related_concepts, vec!["PATH".to_string()]);
+        let prompts = llm.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("previous reply was rejected: missing field"));
+    }
+
     #[test]
     fn test_extract_explanation() {
         let response = "First paragraph with explanation.\n\nSecond paragraph.";
@@ -5,6 +5,7 @@
 // - Key message extraction
 // - Source location identification
 // - Formatted display with verbosity levels
+// - Localized static text (English, Traditional Chinese)
 // - Pattern-based and LLM guidance
 // - Response caching for efficiency
 
@@ -14,14 +15,20 @@ pub mod detector;
 pub mod display;
 pub mod engine;
 pub mod guidance;
+pub mod hyperlink;
+pub mod i18n;
 pub mod llm_fallback;
+pub mod platform;
 pub mod types;
 
-pub use cache::GuidanceCache;
-pub use colors::MentorColors;
+pub use cache::{CacheStats, GuidanceCache};
+pub use colors::{ColorTheme, MentorColors, Palette};
 pub use detector::ErrorDetector;
-pub use display::{DisplayConfig, MentorDisplay, Verbosity};
+pub use display::{DisplayConfig, MentorDisplay, Verbosity, DEFAULT_COMPACT_THRESHOLD};
 pub use engine::{MentorConfig, MentorEngine};
 pub use guidance::{GuidanceSource, MentorGuidance, NextStep};
+pub use hyperlink::LinkTarget;
+pub use i18n::Language;
 pub use llm_fallback::LLMMentor;
-pub use types::{ErrorInfo, ErrorType, SourceLocation};
+pub use platform::PackageManager;
+pub use types::{ErrorInfo, ErrorType, PatternMatch, SourceLocation};
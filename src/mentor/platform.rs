@@ -0,0 +1,144 @@
+// Host platform detection
+//
+// Figures out which package manager the running system uses so install
+// suggestions match the box the user is actually on.
+
+use std::sync::OnceLock;
+
+/// Package managers Kaido can suggest install commands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Brew,
+    Apt,
+    Dnf,
+    Pacman,
+    Apk,
+}
+
+impl PackageManager {
+    /// All supported package managers, in fallback display order
+    pub const ALL: [Self; 5] = [Self::Brew, Self::Apt, Self::Dnf, Self::Pacman, Self::Apk];
+
+    /// Detect the host's package manager
+    ///
+    /// macOS uses Homebrew; Linux is identified from /etc/os-release, then
+    /// by probing PATH. The result is cached for the process lifetime.
+    pub fn detect() -> Option<Self> {
+        static DETECTED: OnceLock<Option<PackageManager>> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            if cfg!(target_os = "macos") {
+                return Some(Self::Brew);
+            }
+
+            std::fs::read_to_string("/etc/os-release")
+                .ok()
+                .and_then(|contents| Self::from_os_release(&contents))
+                .or_else(|| Self::ALL.into_iter().find(|pm| is_in_path(pm.program())))
+        })
+    }
+
+    /// Map /etc/os-release `ID` and `ID_LIKE` to a package manager
+    pub fn from_os_release(contents: &str) -> Option<Self> {
+        let ids: Vec<String> = contents
+            .lines()
+            .filter_map(|line| {
+                line.strip_prefix("ID=")
+                    .or_else(|| line.strip_prefix("ID_LIKE="))
+            })
+            .flat_map(|value| value.trim_matches('"').split_whitespace())
+            .map(str::to_lowercase)
+            .collect();
+
+        ids.iter().find_map(|id| match id.as_str() {
+            "debian" | "ubuntu" | "linuxmint" | "pop" => Some(Self::Apt),
+            "fedora" | "rhel" | "centos" | "rocky" | "almalinux" => Some(Self::Dnf),
+            "arch" | "manjaro" | "endeavouros" => Some(Self::Pacman),
+            "alpine" => Some(Self::Apk),
+            _ => None,
+        })
+    }
+
+    /// Executable name
+    pub fn program(&self) -> &'static str {
+        match self {
+            Self::Brew => "brew",
+            Self::Apt => "apt",
+            Self::Dnf => "dnf",
+            Self::Pacman => "pacman",
+            Self::Apk => "apk",
+        }
+    }
+
+    /// Platforms this package manager is typically found on
+    pub fn platform(&self) -> &'static str {
+        match self {
+            Self::Brew => "macOS",
+            Self::Apt => "Ubuntu/Debian",
+            Self::Dnf => "Fedora/RHEL",
+            Self::Pacman => "Arch Linux",
+            Self::Apk => "Alpine",
+        }
+    }
+
+    /// Command installing a package
+    pub fn install_command(&self, package: &str) -> String {
+        match self {
+            Self::Brew => format!("brew install {package}"),
+            Self::Apt => format!("sudo apt install {package}"),
+            Self::Dnf => format!("sudo dnf install {package}"),
+            Self::Pacman => format!("sudo pacman -S {package}"),
+            Self::Apk => format!("sudo apk add {package}"),
+        }
+    }
+}
+
+/// Whether an executable exists in one of the PATH directories
+fn is_in_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_os_release() {
+        let arch = "NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling\n";
+        assert_eq!(
+            PackageManager::from_os_release(arch),
+            Some(PackageManager::Pacman)
+        );
+
+        let mint = "NAME=\"Linux Mint\"\nID=linuxmint\nID_LIKE=\"ubuntu debian\"\n";
+        assert_eq!(
+            PackageManager::from_os_release(mint),
+            Some(PackageManager::Apt)
+        );
+
+        let rocky = "ID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\n";
+        assert_eq!(
+            PackageManager::from_os_release(rocky),
+            Some(PackageManager::Dnf)
+        );
+
+        assert_eq!(
+            PackageManager::from_os_release("ID=alpine\n"),
+            Some(PackageManager::Apk)
+        );
+        assert_eq!(PackageManager::from_os_release("ID=plan9\n"), None);
+    }
+
+    #[test]
+    fn test_install_command() {
+        assert_eq!(
+            PackageManager::Pacman.install_command("jq"),
+            "sudo pacman -S jq"
+        );
+        assert_eq!(
+            PackageManager::Brew.install_command("jq"),
+            "brew install jq"
+        );
+    }
+}
@@ -3,8 +3,13 @@
 // These types represent detected errors and provide context
 // for generating educational guidance.
 
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use super::cache::MessageNormalizer;
 
 /// Classification of error types
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -33,6 +38,8 @@ pub enum ErrorType {
     Timeout,
     /// Out of memory
     OutOfMemory,
+    /// Process crashed (SIGSEGV, SIGABRT, SIGBUS, SIGFPE, SIGILL)
+    Crashed,
     /// Port already in use
     PortInUse,
     /// Invalid argument or option
@@ -47,11 +54,46 @@ pub enum ErrorType {
     KubernetesError,
     /// Database error
     DatabaseError,
+    /// HTTP rate limit (429) or server error (5xx) from an API
+    HttpError,
     /// Unknown error type
     Unknown,
 }
 
 impl ErrorType {
+    /// Every error type, in declaration order
+    pub const ALL: [ErrorType; 22] = [
+        Self::CommandNotFound,
+        Self::PermissionDenied,
+        Self::FileNotFound,
+        Self::SyntaxError,
+        Self::ConnectionRefused,
+        Self::ConnectionTimeout,
+        Self::ConfigurationError,
+        Self::ResourceNotFound,
+        Self::AuthenticationFailed,
+        Self::DiskFull,
+        Self::Timeout,
+        Self::OutOfMemory,
+        Self::Crashed,
+        Self::PortInUse,
+        Self::InvalidArgument,
+        Self::DependencyError,
+        Self::GitError,
+        Self::DockerError,
+        Self::KubernetesError,
+        Self::DatabaseError,
+        Self::HttpError,
+        Self::Unknown,
+    ];
+
+    /// Error type whose `name()` this is, e.g. from the learning database
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|error_type| error_type.name() == name)
+    }
+
     /// Get a human-readable name for the error type
     pub fn name(&self) -> &'static str {
         match self {
@@ -67,6 +109,7 @@ impl ErrorType {
             Self::DiskFull => "Disk Full",
             Self::Timeout => "Timeout",
             Self::OutOfMemory => "Out of Memory",
+            Self::Crashed => "Crashed",
             Self::PortInUse => "Port Already in Use",
             Self::InvalidArgument => "Invalid Argument",
             Self::DependencyError => "Dependency Error",
@@ -74,25 +117,85 @@ impl ErrorType {
             Self::DockerError => "Docker Error",
             Self::KubernetesError => "Kubernetes Error",
             Self::DatabaseError => "Database Error",
+            Self::HttpError => "HTTP Error",
             Self::Unknown => "Unknown Error",
         }
     }
 
     /// Determine error type from exit code
+    ///
+    /// Follows shell conventions, so it classifies failures whose output
+    /// has nothing recognizable (or no output at all).
     pub fn from_exit_code(code: i32) -> Self {
         match code {
-            1 => Self::Unknown,               // General error
-            2 => Self::InvalidArgument,       // Misuse of command
-            126 => Self::PermissionDenied,    // Permission problem
-            127 => Self::CommandNotFound,     // Command not found
-            128 => Self::Unknown,             // Invalid exit argument
-            130 => Self::Unknown,             // Ctrl+C (not really an error)
-            137 => Self::OutOfMemory,         // Often OOM killer (SIGKILL)
-            139 => Self::Unknown,             // Segfault
-            _ if code > 128 => Self::Unknown, // Killed by signal
-            _ => Self::Unknown,
+            1 => Self::Unknown,            // General error
+            2 => Self::InvalidArgument,    // Misuse of command
+            124 => Self::Timeout,          // `timeout` gave up on the command
+            126 => Self::PermissionDenied, // Found but not executable
+            127 => Self::CommandNotFound,  // Command not found
+            128 => Self::Unknown,          // Invalid exit argument
+            130 => Self::Unknown,          // Ctrl+C (not really an error)
+            137 => Self::OutOfMemory,      // SIGKILL, usually the OOM killer
+            _ => match signal_from_exit_code(code) {
+                Some(SIGILL | SIGABRT | SIGBUS | SIGFPE | SIGSEGV) => Self::Crashed,
+                _ => Self::Unknown,
+            },
         }
     }
+
+    /// One-line meaning of a well-known exit code, e.g. for a failure
+    /// that printed nothing
+    pub fn describe_exit_code(code: i32) -> Option<String> {
+        match code {
+            124 => return Some("Timed out (exit 124 from `timeout`)".to_string()),
+            126 => return Some("Found but not executable (exit 126)".to_string()),
+            127 => return Some("Command not found (exit 127)".to_string()),
+            _ => {}
+        }
+
+        let signal = signal_from_exit_code(code)?;
+        let name = signal_name(signal)
+            .map(|name| format!("SIG{name}"))
+            .unwrap_or_else(|| format!("signal {signal}"));
+        let how = format!("(exit {code} = 128 + {signal})");
+        Some(match signal {
+            SIGKILL => format!("Killed by {name} {how}, often the out-of-memory killer"),
+            SIGILL | SIGABRT | SIGBUS | SIGFPE | SIGSEGV => format!("Crashed with {name} {how}"),
+            _ => format!("Killed by {name} {how}"),
+        })
+    }
+}
+
+const SIGILL: i32 = 4;
+const SIGABRT: i32 = 6;
+const SIGBUS: i32 = 7;
+const SIGFPE: i32 = 8;
+const SIGKILL: i32 = 9;
+const SIGSEGV: i32 = 11;
+
+/// Signal that killed a process, from the shell's 128+N exit code
+pub fn signal_from_exit_code(code: i32) -> Option<i32> {
+    // Linux has 64 signals; anything above is an ordinary exit code
+    (129..=128 + 64).contains(&code).then(|| code - 128)
+}
+
+/// Name of a common signal, without the SIG prefix
+fn signal_name(signal: i32) -> Option<&'static str> {
+    Some(match signal {
+        1 => "HUP",
+        2 => "INT",
+        3 => "QUIT",
+        SIGILL => "ILL",
+        SIGABRT => "ABRT",
+        SIGBUS => "BUS",
+        SIGFPE => "FPE",
+        SIGKILL => "KILL",
+        SIGSEGV => "SEGV",
+        13 => "PIPE",
+        14 => "ALRM",
+        15 => "TERM",
+        _ => return None,
+    })
 }
 
 /// Location in source code where error occurred
@@ -142,6 +245,17 @@ impl fmt::Display for SourceLocation {
     }
 }
 
+/// The detector pattern that classified an error
+///
+/// Kept so pattern authors can see why detection picked a type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// Source of the regex that matched
+    pub regex: String,
+    /// Capture groups (index 0 is the whole match)
+    pub captures: Vec<Option<String>>,
+}
+
 /// Detailed information about a detected error
 #[derive(Debug, Clone)]
 pub struct ErrorInfo {
@@ -159,6 +273,8 @@ pub struct ErrorInfo {
     pub context_lines: Vec<String>,
     /// Source location if detected (file:line)
     pub source_location: Option<SourceLocation>,
+    /// Pattern that matched (None when classified by exit code)
+    pub matched_pattern: Option<PatternMatch>,
 }
 
 impl ErrorInfo {
@@ -177,6 +293,7 @@ impl ErrorInfo {
             command: command.into(),
             context_lines: Vec::new(),
             source_location: None,
+            matched_pattern: None,
         }
     }
 
@@ -198,6 +315,12 @@ impl ErrorInfo {
         self
     }
 
+    /// Set the pattern that matched
+    pub fn with_pattern_match(mut self, pattern: PatternMatch) -> Self {
+        self.matched_pattern = Some(pattern);
+        self
+    }
+
     /// Check if this is a user interruption (Ctrl+C)
     pub fn is_interrupt(&self) -> bool {
         self.exit_code == 130
@@ -207,12 +330,42 @@ impl ErrorInfo {
     pub fn is_real_error(&self) -> bool {
         self.exit_code != 0 && self.exit_code != 130
     }
+
+    /// Identity of this error for spotting repeats: the error type plus the
+    /// key message with names, paths, ports and IDs masked
+    ///
+    /// Stable within a process only; don't persist it.
+    pub fn fingerprint(&self) -> u64 {
+        static NORMALIZER: OnceLock<MessageNormalizer> = OnceLock::new();
+        let key = NORMALIZER
+            .get_or_init(MessageNormalizer::new)
+            .key(&self.key_message);
+
+        let mut hasher = DefaultHasher::new();
+        self.error_type.hash(&mut hasher);
+        key.trim().hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fingerprint_ignores_variable_parts() {
+        let error = |kind, message: &str| ErrorInfo::new(kind, 1, message, "kubectl get pod");
+
+        let first = error(ErrorType::ResourceNotFound, r#"pods "api-7f9c" not found"#);
+        let again = error(ErrorType::ResourceNotFound, r#"pods "worker-1" not found"#);
+        assert_eq!(first.fingerprint(), again.fingerprint());
+
+        let other_message = error(ErrorType::ResourceNotFound, r#"services "api" not found"#);
+        let other_type = error(ErrorType::Unknown, r#"pods "api-7f9c" not found"#);
+        assert_ne!(first.fingerprint(), other_message.fingerprint());
+        assert_ne!(first.fingerprint(), other_type.fingerprint());
+    }
+
     #[test]
     fn test_error_type_from_exit_code() {
         assert_eq!(ErrorType::from_exit_code(127), ErrorType::CommandNotFound);
@@ -220,6 +373,58 @@ mod tests {
         assert_eq!(ErrorType::from_exit_code(2), ErrorType::InvalidArgument);
     }
 
+    #[test]
+    fn test_error_type_from_well_known_exit_codes() {
+        assert_eq!(ErrorType::from_exit_code(124), ErrorType::Timeout);
+        assert_eq!(ErrorType::from_exit_code(137), ErrorType::OutOfMemory);
+        assert_eq!(ErrorType::from_exit_code(139), ErrorType::Crashed);
+        assert_eq!(ErrorType::from_exit_code(134), ErrorType::Crashed);
+        assert_eq!(ErrorType::from_exit_code(143), ErrorType::Unknown);
+        assert_eq!(ErrorType::from_exit_code(255), ErrorType::Unknown);
+    }
+
+    #[test]
+    fn test_signal_from_exit_code() {
+        assert_eq!(signal_from_exit_code(137), Some(9));
+        assert_eq!(signal_from_exit_code(143), Some(15));
+        assert_eq!(signal_from_exit_code(128), None);
+        assert_eq!(signal_from_exit_code(255), None);
+        assert_eq!(signal_from_exit_code(1), None);
+    }
+
+    #[test]
+    fn test_describe_exit_code() {
+        assert_eq!(
+            ErrorType::describe_exit_code(139).unwrap(),
+            "Crashed with SIGSEGV (exit 139 = 128 + 11)"
+        );
+        assert!(ErrorType::describe_exit_code(137)
+            .unwrap()
+            .contains("out-of-memory killer"));
+        assert_eq!(
+            ErrorType::describe_exit_code(143).unwrap(),
+            "Killed by SIGTERM (exit 143 = 128 + 15)"
+        );
+        assert!(ErrorType::describe_exit_code(126)
+            .unwrap()
+            .contains("not executable"));
+        assert!(ErrorType::describe_exit_code(127)
+            .unwrap()
+            .contains("not found"));
+        assert!(ErrorType::describe_exit_code(124)
+            .unwrap()
+            .contains("Timed out"));
+        assert_eq!(ErrorType::describe_exit_code(1), None);
+    }
+
+    #[test]
+    fn test_error_type_from_name() {
+        for error_type in ErrorType::ALL {
+            assert_eq!(ErrorType::from_name(error_type.name()), Some(error_type));
+        }
+        assert_eq!(ErrorType::from_name("Nope"), None);
+    }
+
     #[test]
     fn test_error_type_name() {
         assert_eq!(ErrorType::CommandNotFound.name(), "Command Not Found");
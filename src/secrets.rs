@@ -0,0 +1,295 @@
+// Secrets kept out of config.toml
+//
+// With `key_storage = "keychain"` API keys go to the OS keychain (macOS
+// Keychain, Secret Service on Linux, Windows Credential Manager). Where no
+// keychain is reachable, e.g. a headless box without a Secret Service, or
+// with `key_storage = "encrypted"`, they are age-encrypted into
+// ~/.kaido/secrets/<name>.age with a per-machine identity next to them. That
+// keeps keys out of a config file that gets copied, synced or committed.
+
+use age::secrecy::ExposeSecret;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Keychain service name secrets are stored under
+const KEYCHAIN_SERVICE: &str = "kaido";
+
+/// File holding the age identity that encrypts the fallback blobs
+const IDENTITY_FILE: &str = "identity.txt";
+
+/// Where API keys are kept
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStorage {
+    /// In config.toml as written
+    #[default]
+    Plaintext,
+    /// In the OS keychain, or an encrypted file if there is none
+    Keychain,
+    /// In an age-encrypted file under ~/.kaido/secrets
+    Encrypted,
+}
+
+impl KeyStorage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStorage::Plaintext => "plaintext",
+            KeyStorage::Keychain => "keychain",
+            KeyStorage::Encrypted => "encrypted",
+        }
+    }
+}
+
+/// Reads and writes named secrets in the keychain or encrypted files
+pub struct SecretStore {
+    dir: PathBuf,
+    use_keychain: bool,
+}
+
+impl SecretStore {
+    /// Store under ~/.kaido/secrets
+    pub fn new() -> Result<Self> {
+        let home = dirs::home_dir().context("Cannot determine home directory")?;
+        Ok(Self::with_dir(home.join(".kaido").join("secrets")))
+    }
+
+    /// Store with encrypted files in `dir`
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            use_keychain: true,
+        }
+    }
+
+    /// Never touch the OS keychain, only encrypted files
+    pub fn without_keychain(mut self) -> Self {
+        self.use_keychain = false;
+        self
+    }
+
+    /// Save a secret, returning where it ended up
+    ///
+    /// `Keychain` falls back to an encrypted file when the keychain can't be
+    /// reached; the stale copy in the other place is removed.
+    pub fn save(&self, name: &str, secret: &str, storage: KeyStorage) -> Result<KeyStorage> {
+        if storage == KeyStorage::Plaintext {
+            anyhow::bail!("{name} is kept in config.toml, not the secret store");
+        }
+        if storage == KeyStorage::Keychain && self.use_keychain {
+            match keychain_entry(name).and_then(|entry| Ok(entry.set_password(secret)?)) {
+                Ok(()) => {
+                    self.remove_file(name)?;
+                    return Ok(KeyStorage::Keychain);
+                }
+                Err(e) => log::warn!("Keychain unavailable ({e}), encrypting {name} to a file"),
+            }
+        }
+
+        let identity = self.identity()?;
+        let encrypted = age::encrypt(&identity.to_public(), secret.as_bytes())
+            .with_context(|| format!("Failed to encrypt {name}"))?;
+        write_private(&self.blob_path(name), &encrypted)?;
+        if storage == KeyStorage::Encrypted && self.use_keychain {
+            self.remove_keychain(name);
+        }
+        Ok(KeyStorage::Encrypted)
+    }
+
+    /// Load a secret: the keychain first (for `Keychain`), then the
+    /// encrypted file
+    ///
+    /// None when it was never stored.
+    pub fn load(&self, name: &str, storage: KeyStorage) -> Result<Option<String>> {
+        if storage == KeyStorage::Plaintext {
+            return Ok(None);
+        }
+        if storage == KeyStorage::Keychain && self.use_keychain {
+            match keychain_entry(name).and_then(|entry| Ok(entry.get_password()?)) {
+                Ok(secret) => return Ok(Some(secret)),
+                Err(e) => match e.downcast_ref::<keyring::Error>() {
+                    Some(keyring::Error::NoEntry) => {}
+                    _ => log::warn!("Keychain unavailable ({e}), reading {name} from its file"),
+                },
+            }
+        }
+
+        let path = self.blob_path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let encrypted =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let identity = self.identity()?;
+        let secret = age::decrypt(&identity, &encrypted)
+            .with_context(|| format!("Failed to decrypt {}", path.display()))?;
+        Ok(Some(
+            String::from_utf8(secret).with_context(|| format!("{name} is not valid UTF-8"))?,
+        ))
+    }
+
+    fn blob_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.age"))
+    }
+
+    fn remove_file(&self, name: &str) -> Result<()> {
+        let path = self.blob_path(name);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn remove_keychain(&self, name: &str) {
+        let removed = keychain_entry(name).and_then(|entry| Ok(entry.delete_credential()?));
+        if let Err(e) = removed {
+            if !matches!(e.downcast_ref(), Some(keyring::Error::NoEntry)) {
+                log::debug!("Could not remove {name} from the keychain: {e}");
+            }
+        }
+    }
+
+    /// The age identity for encrypted files, created on first use
+    fn identity(&self) -> Result<age::x25519::Identity> {
+        let path = self.dir.join(IDENTITY_FILE);
+        if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            return contents
+                .lines()
+                .find(|line| line.starts_with("AGE-SECRET-KEY-"))
+                .and_then(|line| line.parse().ok())
+                .with_context(|| format!("No age identity in {}", path.display()));
+        }
+
+        let identity = age::x25519::Identity::generate();
+        let contents = format!(
+            "# Encrypts kaido's API keys; without it they can't be recovered\n{}\n",
+            identity.to_string().expose_secret()
+        );
+        write_private(&path, contents.as_bytes())?;
+        Ok(identity)
+    }
+}
+
+fn keychain_entry(name: &str) -> Result<keyring::Entry> {
+    Ok(keyring::Entry::new(KEYCHAIN_SERVICE, name)?)
+}
+
+/// Write a file only the user can read, creating its directory
+///
+/// Both are created with private modes (0700/0600) from the start, so the
+/// contents are never readable by others, not even briefly.
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut dirs = std::fs::DirBuilder::new();
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+        dirs.mode(0o700);
+        options.mode(0o600);
+    }
+
+    if let Some(parent) = path.parent() {
+        dirs.recursive(true)
+            .create(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // An existing file keeps its mode on open; tighten it before writing
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_modes() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets").join("key");
+        write_private(&path, b"one").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, b"two").unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+        assert_eq!(std::fs::read(&path).unwrap(), b"two");
+    }
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store = SecretStore::with_dir(dir.path()).without_keychain();
+        assert_eq!(
+            store.load("gemini_api_key", KeyStorage::Encrypted).unwrap(),
+            None
+        );
+
+        let stored = store
+            .save(
+                "gemini_api_key",
+                "AIzaSyExampleKey1234",
+                KeyStorage::Encrypted,
+            )
+            .unwrap();
+        assert_eq!(stored, KeyStorage::Encrypted);
+        let blob = std::fs::read(dir.path().join("gemini_api_key.age")).unwrap();
+        assert!(!String::from_utf8_lossy(&blob).contains("AIzaSyExampleKey1234"));
+        assert_eq!(
+            store.load("gemini_api_key", KeyStorage::Encrypted).unwrap(),
+            Some("AIzaSyExampleKey1234".to_string())
+        );
+
+        // The identity is reused, so a new store decrypts the same file
+        let again = SecretStore::with_dir(dir.path()).without_keychain();
+        assert_eq!(
+            again.load("gemini_api_key", KeyStorage::Encrypted).unwrap(),
+            Some("AIzaSyExampleKey1234".to_string())
+        );
+    }
+
+    #[test]
+    fn test_keychain_falls_back_to_file() {
+        let dir = TempDir::new().unwrap();
+        let store = SecretStore::with_dir(dir.path()).without_keychain();
+        let stored = store
+            .save(
+                "gemini_api_key",
+                "AIzaSyExampleKey1234",
+                KeyStorage::Keychain,
+            )
+            .unwrap();
+        assert_eq!(stored, KeyStorage::Encrypted);
+        assert_eq!(
+            store.load("gemini_api_key", KeyStorage::Keychain).unwrap(),
+            Some("AIzaSyExampleKey1234".to_string())
+        );
+        assert_eq!(
+            store.load("gemini_api_key", KeyStorage::Plaintext).unwrap(),
+            None
+        );
+        assert!(store
+            .save("gemini_api_key", "x", KeyStorage::Plaintext)
+            .is_err());
+    }
+}
@@ -0,0 +1,233 @@
+// Aliases imported from bash/zsh
+//
+// `kaido onboard --import-aliases` reads `alias name=value` lines from the
+// user's rc files into ~/.kaido/aliases, which the shell loads on startup.
+// Values are unquoted the way the shell would (single quotes, double
+// quotes, backslashes, the `'\''` idiom), and the file is written back in
+// the same `alias name='value'` form so it stays sourceable.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Header of the kaido-managed aliases file
+const HEADER: &str = "# Aliases loaded by the kaido shell on startup.\n\
+                      # Written by `kaido onboard --import-aliases`; edit freely.\n";
+
+/// Path of the kaido-managed aliases file
+pub fn default_aliases_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".kaido")
+        .join("aliases")
+}
+
+/// Shell rc files aliases are imported from, in order
+pub fn rc_files(home: &Path) -> Vec<PathBuf> {
+    [
+        ".bashrc",
+        ".bash_aliases",
+        ".bash_profile",
+        ".zshrc",
+        ".zsh_aliases",
+    ]
+    .iter()
+    .map(|name| home.join(name))
+    .filter(|path| path.is_file())
+    .collect()
+}
+
+/// Alias definitions on one line: `alias ll='ls -la' la="ls -A"`
+///
+/// Returns nothing for lines that aren't alias definitions, listings
+/// (`alias ll`) or have an unterminated quote. Options like zsh's `-g` are
+/// skipped.
+pub fn parse_alias_line(line: &str) -> Vec<(String, String)> {
+    let Some(rest) = line.trim().strip_prefix("alias ") else {
+        return Vec::new();
+    };
+    let Some(words) = shell_words(rest) else {
+        return Vec::new();
+    };
+
+    words
+        .into_iter()
+        .skip_while(|word| word.starts_with('-'))
+        .filter_map(|word| {
+            let (name, value) = word.split_once('=')?;
+            let valid = !name.is_empty()
+                && !name
+                    .chars()
+                    .any(|c| c.is_whitespace() || "'\"$`=/".contains(c));
+            valid.then(|| (name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Split text into words like a POSIX shell, removing quotes
+///
+/// Stops at an unquoted `#` starting a word. None if a quote is left open.
+fn shell_words(text: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '#' if !in_word => break,
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\' | '$' | '`') => word.push(c),
+                            c => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Some(words)
+}
+
+/// Aliases defined in a file; later definitions win
+pub fn read_aliases(path: &Path) -> Result<BTreeMap<String, String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content.lines().flat_map(parse_alias_line).collect())
+}
+
+/// Aliases from all rc files, later files overriding earlier ones
+pub fn import_from_rc_files(files: &[PathBuf]) -> BTreeMap<String, String> {
+    let mut aliases = BTreeMap::new();
+    for file in files {
+        match read_aliases(file) {
+            Ok(found) => aliases.extend(found),
+            Err(e) => log::warn!("Skipping aliases in {}: {e}", file.display()),
+        }
+    }
+    aliases
+}
+
+/// Write aliases as `alias name='value'` lines, replacing the file
+pub fn save_aliases(path: &Path, aliases: &BTreeMap<String, String>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut content = HEADER.to_string();
+    for (name, value) in aliases {
+        content.push_str(&format!(
+            "alias {name}='{}'\n",
+            value.replace('\'', r"'\''")
+        ));
+    }
+    std::fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn parsed(line: &str) -> Vec<(String, String)> {
+        parse_alias_line(line)
+    }
+
+    fn pair(name: &str, value: &str) -> (String, String) {
+        (name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_parse_quoting() {
+        assert_eq!(parsed("alias ll='ls -la'"), vec![pair("ll", "ls -la")]);
+        assert_eq!(parsed("alias k=kubectl"), vec![pair("k", "kubectl")]);
+        assert_eq!(
+            parsed(r#"alias gl="git log --format=\"%h %s\"""#),
+            vec![pair("gl", r#"git log --format="%h %s""#)]
+        );
+        assert_eq!(
+            parsed(r"alias say='echo '\''hi'\'''"),
+            vec![pair("say", "echo 'hi'")]
+        );
+        assert_eq!(
+            parsed(r"  alias up=cd\ .. # go up"),
+            vec![pair("up", "cd ..")]
+        );
+    }
+
+    #[test]
+    fn test_parse_several_and_options() {
+        assert_eq!(
+            parsed("alias -g G='| grep' L='| less'"),
+            vec![pair("G", "| grep"), pair("L", "| less")]
+        );
+        assert_eq!(
+            parsed("alias la='ls -A' l='ls -CF'"),
+            vec![pair("la", "ls -A"), pair("l", "ls -CF")]
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_non_definitions() {
+        assert!(parsed("alias ll").is_empty());
+        assert!(parsed("alias broken='ls -la").is_empty());
+        assert!(parsed("# alias old='ls'").is_empty());
+        assert!(parsed("export PATH=$HOME/bin:$PATH").is_empty());
+        assert!(parsed("aliases=1").is_empty());
+    }
+
+    #[test]
+    fn test_import_and_save_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let bashrc = dir.path().join(".bashrc");
+        let zshrc = dir.path().join(".zshrc");
+        std::fs::write(&bashrc, "alias ll='ls -l'\nalias k=kubectl\nexport X=1\n").unwrap();
+        std::fs::write(&zshrc, "alias ll='ls -la'\nalias say='echo '\\''hi'\\'''\n").unwrap();
+
+        let files = rc_files(dir.path());
+        assert_eq!(files, vec![bashrc, zshrc]);
+        let aliases = import_from_rc_files(&files);
+        assert_eq!(aliases.len(), 3);
+        assert_eq!(aliases["ll"], "ls -la");
+
+        let saved = dir.path().join("kaido").join("aliases");
+        save_aliases(&saved, &aliases).unwrap();
+        assert_eq!(read_aliases(&saved).unwrap(), aliases);
+    }
+}
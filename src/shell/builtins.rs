@@ -3,8 +3,9 @@
 // Implements shell builtin commands that must be handled by Kaido
 // itself rather than delegated to child processes.
 
+use anyhow::Result;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Environment variable storage for the shell
 #[derive(Debug, Clone, Default)]
@@ -73,6 +74,19 @@ impl ShellEnvironment {
         self.aliases.iter()
     }
 
+    /// Add the aliases defined in a file of `alias name='value'` lines
+    ///
+    /// A missing file adds none. Returns how many were loaded.
+    pub fn load_aliases(&mut self, path: &Path) -> Result<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let aliases = super::aliases::read_aliases(path)?;
+        let count = aliases.len();
+        self.aliases.extend(aliases);
+        Ok(count)
+    }
+
     // === Directory Tracking ===
 
     /// Get the previous directory
@@ -127,12 +141,22 @@ pub enum Builtin {
     Exit(i32),
     /// Display help
     Help,
-    /// Display history
+    /// Display history: history
     History,
+    /// Display this session's history: history --session
+    HistorySession,
+    /// Forget all history: history clear
+    HistoryClear,
     /// Clear screen
     Clear,
 }
 
+/// Names `parse_builtin` recognizes
+pub const BUILTIN_NAMES: &[&str] = &[
+    "alias", "cd", "clear", "exit", "export", "help", "history", "quit", "source", "unalias",
+    "unset",
+];
+
 /// Parse a command line into a builtin if it matches
 pub fn parse_builtin(line: &str) -> Option<Builtin> {
     let line = line.trim();
@@ -155,6 +179,12 @@ pub fn parse_builtin(line: &str) -> Option<Builtin> {
     if line == "history" {
         return Some(Builtin::History);
     }
+    if line == "history --session" {
+        return Some(Builtin::HistorySession);
+    }
+    if line == "history clear" {
+        return Some(Builtin::HistoryClear);
+    }
 
     // Clear
     if line == "clear" {
@@ -275,7 +305,11 @@ pub fn execute_builtin(builtin: &Builtin, env: &mut ShellEnvironment) -> Builtin
         }
         Builtin::Source(path) => execute_source(path),
         Builtin::Exit(code) => BuiltinResult::Exit(*code),
-        Builtin::Help | Builtin::History | Builtin::Clear => {
+        Builtin::Help
+        | Builtin::History
+        | Builtin::HistorySession
+        | Builtin::HistoryClear
+        | Builtin::Clear => {
             // These are handled by the shell directly
             BuiltinResult::Ok(None)
         }
@@ -396,6 +430,21 @@ mod tests {
         assert_eq!(env.get_alias("ll"), Some(&"ls -la".to_string()));
     }
 
+    #[test]
+    fn test_load_aliases() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("aliases");
+        let mut env = ShellEnvironment::new();
+        assert_eq!(env.load_aliases(&path).unwrap(), 0);
+
+        std::fs::write(&path, "alias k=kubectl\nalias gs='git status -sb'\n").unwrap();
+        assert_eq!(env.load_aliases(&path).unwrap(), 2);
+        assert_eq!(
+            env.expand_aliases("gs ."),
+            Some("git status -sb .".to_string())
+        );
+    }
+
     #[test]
     fn test_unset_alias() {
         let mut env = ShellEnvironment::new();
@@ -439,6 +488,19 @@ mod tests {
         assert!(matches!(parse_builtin("exit 1"), Some(Builtin::Exit(1))));
     }
 
+    #[test]
+    fn test_parse_builtin_history() {
+        assert!(matches!(parse_builtin("history"), Some(Builtin::History)));
+        assert!(matches!(
+            parse_builtin("history --session"),
+            Some(Builtin::HistorySession)
+        ));
+        assert!(matches!(
+            parse_builtin("history clear"),
+            Some(Builtin::HistoryClear)
+        ));
+    }
+
     #[test]
     fn test_parse_builtin_cd() {
         assert!(matches!(parse_builtin("cd"), Some(Builtin::Cd(s)) if s == "~"));
@@ -0,0 +1,355 @@
+// Tab completion for known tools
+//
+// Completes subcommands, their arguments and common flags for kubectl,
+// docker and git from a static command tree, so `kubectl g<Tab> po<Tab>`
+// discovers `kubectl get pods`. Anything outside the tree, like paths
+// after `-f` or arguments to other programs, falls back to filenames.
+
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Resources kubectl verbs operate on
+const KUBECTL_RESOURCES: &[&str] = &[
+    "pods",
+    "deployments",
+    "services",
+    "nodes",
+    "namespaces",
+    "configmaps",
+    "secrets",
+    "ingresses",
+    "statefulsets",
+    "daemonsets",
+    "replicasets",
+    "jobs",
+    "cronjobs",
+    "persistentvolumeclaims",
+    "persistentvolumes",
+    "events",
+    "serviceaccounts",
+    "all",
+];
+
+/// Commands, what may follow each, and flags of one tool
+struct ToolTree {
+    program: &'static str,
+    subcommands: &'static [(&'static str, &'static [&'static str])],
+    flags: &'static [&'static str],
+    /// Flags whose next word is a value, not a subcommand
+    value_flags: &'static [&'static str],
+}
+
+const TOOLS: &[ToolTree] = &[
+    ToolTree {
+        program: "kubectl",
+        subcommands: &[
+            ("get", KUBECTL_RESOURCES),
+            ("describe", KUBECTL_RESOURCES),
+            ("delete", KUBECTL_RESOURCES),
+            ("edit", KUBECTL_RESOURCES),
+            ("explain", KUBECTL_RESOURCES),
+            ("label", KUBECTL_RESOURCES),
+            ("annotate", KUBECTL_RESOURCES),
+            ("logs", &[]),
+            ("exec", &[]),
+            ("apply", &[]),
+            (
+                "create",
+                &["deployment", "namespace", "secret", "configmap", "job"],
+            ),
+            ("scale", &["deployment", "statefulset", "replicaset"]),
+            (
+                "rollout",
+                &["status", "history", "undo", "restart", "pause", "resume"],
+            ),
+            ("top", &["pods", "nodes"]),
+            (
+                "config",
+                &["get-contexts", "current-context", "use-context", "view"],
+            ),
+            ("port-forward", &[]),
+            ("cordon", &[]),
+            ("uncordon", &[]),
+            ("drain", &[]),
+            ("patch", &[]),
+            ("diff", &[]),
+            ("auth", &["can-i", "whoami"]),
+            ("api-resources", &[]),
+            ("cluster-info", &[]),
+            ("version", &[]),
+        ],
+        flags: &[
+            "--namespace",
+            "--all-namespaces",
+            "--output",
+            "--selector",
+            "--filename",
+            "--context",
+            "--watch",
+            "--follow",
+            "--previous",
+            "--dry-run=client",
+            "--dry-run=server",
+        ],
+        value_flags: &[
+            "-n",
+            "--namespace",
+            "-o",
+            "--output",
+            "-l",
+            "--selector",
+            "-f",
+            "--filename",
+            "--context",
+            "-c",
+            "--container",
+        ],
+    },
+    ToolTree {
+        program: "docker",
+        subcommands: &[
+            ("ps", &[]),
+            ("images", &[]),
+            ("run", &[]),
+            ("exec", &[]),
+            ("logs", &[]),
+            ("build", &[]),
+            ("pull", &[]),
+            ("push", &[]),
+            ("start", &[]),
+            ("stop", &[]),
+            ("restart", &[]),
+            ("rm", &[]),
+            ("rmi", &[]),
+            ("inspect", &[]),
+            ("tag", &[]),
+            ("login", &[]),
+            (
+                "compose",
+                &["up", "down", "ps", "logs", "build", "pull", "restart"],
+            ),
+            ("network", &["ls", "create", "rm", "inspect", "prune"]),
+            ("volume", &["ls", "create", "rm", "inspect", "prune"]),
+            ("system", &["df", "prune", "info"]),
+            ("container", &["ls", "prune", "inspect"]),
+            ("image", &["ls", "prune", "inspect"]),
+        ],
+        flags: &[
+            "--all",
+            "--detach",
+            "--interactive",
+            "--tty",
+            "--rm",
+            "--name",
+            "--publish",
+            "--volume",
+            "--env",
+            "--follow",
+            "--tail",
+        ],
+        value_flags: &[
+            "--name",
+            "-p",
+            "--publish",
+            "-v",
+            "--volume",
+            "-e",
+            "--env",
+            "--tail",
+            "-f",
+            "--file",
+        ],
+    },
+    ToolTree {
+        program: "git",
+        subcommands: &[
+            ("status", &[]),
+            ("add", &[]),
+            ("commit", &[]),
+            ("push", &[]),
+            ("pull", &[]),
+            ("fetch", &[]),
+            ("clone", &[]),
+            ("init", &[]),
+            ("checkout", &[]),
+            ("switch", &[]),
+            ("branch", &[]),
+            ("merge", &[]),
+            ("rebase", &[]),
+            ("log", &[]),
+            ("diff", &[]),
+            ("show", &[]),
+            ("reset", &[]),
+            ("restore", &[]),
+            ("tag", &[]),
+            ("stash", &["list", "push", "pop", "apply", "drop", "show"]),
+            ("remote", &["add", "remove", "rename", "set-url", "show"]),
+        ],
+        flags: &[
+            "--all",
+            "--amend",
+            "--message",
+            "--oneline",
+            "--graph",
+            "--staged",
+            "--force-with-lease",
+            "--set-upstream",
+            "--hard",
+            "--soft",
+        ],
+        value_flags: &["-m", "--message", "-C", "-b"],
+    },
+];
+
+/// rustyline helper completing tool commands, then filenames
+pub struct CommandCompleter {
+    files: FilenameCompleter,
+}
+
+impl CommandCompleter {
+    pub fn new() -> Self {
+        Self {
+            files: FilenameCompleter::new(),
+        }
+    }
+}
+
+impl Default for CommandCompleter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Candidates for the word being typed, None when the tree has no opinion
+///
+/// `before` is the line up to the start of `word`.
+fn tree_candidates(before: &str, word: &str) -> Option<Vec<&'static str>> {
+    let words: Vec<&str> = before.split_whitespace().collect();
+    let Some((program, args)) = words.split_first() else {
+        return Some(matching(TOOLS.iter().map(|tool| tool.program), word));
+    };
+    let tool = TOOLS.iter().find(|tool| tool.program == *program)?;
+
+    if word.starts_with('-') {
+        return Some(matching(tool.flags.iter().copied(), word));
+    }
+
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if tool.value_flags.contains(arg) {
+            // None when completing a flag's value, e.g. a file after -f
+            args.next()?;
+        } else if !arg.starts_with('-') {
+            positional.push(*arg);
+        }
+    }
+
+    match positional.as_slice() {
+        [] => Some(matching(
+            tool.subcommands.iter().map(|(name, _)| *name),
+            word,
+        )),
+        [subcommand] => {
+            let (_, next) = tool
+                .subcommands
+                .iter()
+                .find(|(name, _)| name == subcommand)?;
+            (!next.is_empty()).then(|| matching(next.iter().copied(), word))
+        }
+        _ => None,
+    }
+}
+
+fn matching<'a>(candidates: impl Iterator<Item = &'a str>, word: &str) -> Vec<&'a str> {
+    candidates
+        .filter(|candidate| candidate.starts_with(word))
+        .collect()
+}
+
+impl Completer for CommandCompleter {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.len()
+            - line
+                .split(char::is_whitespace)
+                .next_back()
+                .unwrap_or("")
+                .len();
+        let (before, word) = line.split_at(start);
+
+        match tree_candidates(before, word) {
+            Some(candidates) => Ok((
+                start,
+                candidates
+                    .into_iter()
+                    .map(|candidate| Pair {
+                        display: candidate.to_string(),
+                        replacement: candidate.to_string(),
+                    })
+                    .collect(),
+            )),
+            None if before.trim().is_empty() => Ok((start, Vec::new())),
+            None => self.files.complete_path(line, pos),
+        }
+    }
+}
+
+impl Hinter for CommandCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for CommandCompleter {}
+
+impl Validator for CommandCompleter {}
+
+impl Helper for CommandCompleter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completes_programs_and_subcommands() {
+        assert_eq!(tree_candidates("", "ku"), Some(vec!["kubectl"]));
+        assert_eq!(tree_candidates("kubectl ", "g"), Some(vec!["get"]));
+        assert_eq!(
+            tree_candidates("docker ", "r"),
+            Some(vec!["run", "restart", "rm", "rmi"])
+        );
+        assert_eq!(
+            tree_candidates("git stash ", "p"),
+            Some(vec!["push", "pop"])
+        );
+        // Not a tool in the tree
+        assert_eq!(tree_candidates("ls ", "-"), None);
+    }
+
+    #[test]
+    fn test_completes_kubectl_resources_and_flags() {
+        assert_eq!(tree_candidates("kubectl get ", "po"), Some(vec!["pods"]));
+        // Flag values aren't mistaken for the subcommand
+        assert_eq!(
+            tree_candidates("kubectl -n prod describe ", "dep"),
+            Some(vec!["deployments"])
+        );
+        assert_eq!(
+            tree_candidates("kubectl get pods ", "--all"),
+            Some(vec!["--all-namespaces"])
+        );
+        // Names after the resource and files after -f are left to the user
+        assert_eq!(tree_candidates("kubectl get pods ", "we"), None);
+        assert_eq!(tree_candidates("kubectl apply -f ", "de"), None);
+        assert_eq!(tree_candidates("kubectl logs ", "we"), None);
+    }
+}
@@ -0,0 +1,366 @@
+// Confirmation before running risky commands
+//
+// The `confirm <mode>` builtin controls whether Medium+ risk commands
+// prompt before they run. Risk comes from the classifier of the tool that
+// owns the command, or from shell patterns when none does; production is
+// detected from the kubectl context. kubectl apply/patch/scale show a
+// preview of the change before the prompt.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+use super::pager;
+use crate::kubectl::preview::Preview;
+use crate::kubectl::EnvironmentType;
+use crate::tools::{RiskLevel, ToolContext, ToolRegistry};
+use crate::ui::confirmation::{extract_resource_name, ConfirmationType};
+
+/// How eagerly the shell asks before running risky commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmMode {
+    /// Weigh risk against environment: Medium only prompts in production
+    #[default]
+    Smart,
+    /// Prompt for anything Medium risk or above
+    Always,
+    /// Never prompt
+    Never,
+}
+
+impl ConfirmMode {
+    /// Parse a mode name as typed in the `confirm` builtin
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.trim().to_lowercase().as_str() {
+            "smart" => Some(Self::Smart),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// Mode name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Smart => "smart",
+            Self::Always => "always",
+            Self::Never => "never",
+        }
+    }
+
+    /// One-line description for the `confirm` builtin
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Smart => "prompts based on risk and environment",
+            Self::Always => "prompts for every Medium+ risk command",
+            Self::Never => "never prompts",
+        }
+    }
+
+    /// Kind of confirmation needed for a command
+    ///
+    /// Typed confirmation is kept for High risk in production and Critical
+    /// commands in both `smart` and `always`.
+    pub fn confirmation_for(&self, risk: RiskLevel, is_production: bool) -> ConfirmationType {
+        let needs_prompt = match self {
+            Self::Never => false,
+            Self::Always => risk.requires_confirmation(),
+            Self::Smart => match risk {
+                RiskLevel::Low => false,
+                RiskLevel::Medium => is_production,
+                RiskLevel::High | RiskLevel::Critical => true,
+            },
+        };
+
+        if !needs_prompt {
+            ConfirmationType::None
+        } else if risk.requires_typed_confirmation(is_production) {
+            ConfirmationType::Typed
+        } else {
+            ConfirmationType::YesNo
+        }
+    }
+}
+
+/// Risk assessment of a shell command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandRisk {
+    /// Tool that classified the command
+    pub tool: &'static str,
+    /// Risk level
+    pub level: RiskLevel,
+    /// Environment of the current kubectl context (kubectl commands only)
+    pub environment: EnvironmentType,
+}
+
+impl CommandRisk {
+    /// Whether the command targets production
+    pub fn is_production(&self) -> bool {
+        self.environment == EnvironmentType::Production
+    }
+}
+
+/// Classify a command by the programs it runs
+///
+/// Commands no tool owns are rated from their shell patterns, never
+/// skipped; see `ToolRegistry::classify`.
+pub fn assess(registry: &ToolRegistry, command: &str) -> CommandRisk {
+    let context = ToolContext::detect();
+    let (tool, level) = registry.classify(command, &context);
+
+    CommandRisk {
+        tool,
+        level,
+        environment: context
+            .kubectl_context
+            .map(|ctx| ctx.environment_type)
+            .unwrap_or(EnvironmentType::Unknown),
+    }
+}
+
+/// Classify a command, rating it no lower than `floor`
+///
+/// Used for suggested fixes, which carry their own risk rating.
+pub fn assess_at_least(registry: &ToolRegistry, command: &str, floor: RiskLevel) -> CommandRisk {
+    let mut risk = assess(registry, command);
+    risk.level = risk.level.max(floor);
+    risk
+}
+
+/// Text the user must type to confirm a command
+pub fn expected_text(command: &str, risk: &CommandRisk) -> String {
+    if risk.tool == "kubectl" {
+        extract_resource_name(command, &risk.environment)
+    } else {
+        "yes".to_string()
+    }
+}
+
+/// Read-only preview of what `command` would change, if it has one
+pub fn preview(command: &str, risk: &CommandRisk) -> Option<Preview> {
+    if risk.tool == "kubectl" {
+        Preview::for_command(command)
+    } else {
+        None
+    }
+}
+
+/// Run a preview and show its diff, paged when it's taller than the screen
+///
+/// A failing preview is reported and the prompt still follows.
+pub fn show_preview(preview: &Preview) {
+    println!(
+        "\x1b[38;5;147m◆\x1b[0m Previewing: \x1b[2m{}\x1b[0m",
+        preview.describe()
+    );
+    match preview.run() {
+        Ok(diff) if diff.trim().is_empty() => {
+            println!("  \x1b[2mNo changes: the cluster already matches.\x1b[0m");
+        }
+        Ok(diff) => {
+            let colored: String = diff
+                .lines()
+                .map(|line| format!("{}{line}\x1b[0m\n", pager::diff_line_color(line)))
+                .collect();
+            if let Err(e) = pager::print_paged(&colored) {
+                log::warn!("Paging the preview failed: {e}");
+            }
+        }
+        Err(e) => println!("  \x1b[33m⚠\x1b[0m Preview failed: {e}"),
+    }
+}
+
+/// Ask the user to confirm a command on stdin
+///
+/// Returns true if the command should run.
+pub fn prompt(command: &str, risk: &CommandRisk, confirmation: ConfirmationType) -> bool {
+    let stdin = io::stdin();
+    prompt_with(command, risk, confirmation, &mut stdin.lock())
+}
+
+/// Ask the user to confirm a command, reading the answer from `input`
+pub fn prompt_with(
+    command: &str,
+    risk: &CommandRisk,
+    confirmation: ConfirmationType,
+    input: &mut impl BufRead,
+) -> bool {
+    if confirmation == ConfirmationType::None {
+        return true;
+    }
+
+    let env = if risk.is_production() {
+        " in \x1b[1;31mPRODUCTION\x1b[0m"
+    } else {
+        ""
+    };
+    println!(
+        "\x1b[33m⚠\x1b[0m {} risk {} command{env}: \x1b[1m{command}\x1b[0m",
+        risk.level, risk.tool
+    );
+
+    let expected = match confirmation {
+        ConfirmationType::Typed => {
+            let expected = expected_text(command, risk);
+            print!("  Type '\x1b[1m{expected}\x1b[0m' to run it: ");
+            Some(expected)
+        }
+        _ => {
+            print!("  Run it? [y/N]: ");
+            None
+        }
+    };
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer).is_err() {
+        return false;
+    }
+    let answer = answer.trim();
+
+    match expected {
+        Some(expected) => answer == expected,
+        None => matches!(answer.to_lowercase().as_str(), "y" | "yes"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn risk(tool: &'static str, level: RiskLevel, environment: EnvironmentType) -> CommandRisk {
+        CommandRisk {
+            tool,
+            level,
+            environment,
+        }
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!(ConfirmMode::parse("always"), Some(ConfirmMode::Always));
+        assert_eq!(ConfirmMode::parse(" Never "), Some(ConfirmMode::Never));
+        assert_eq!(ConfirmMode::parse("smart"), Some(ConfirmMode::Smart));
+        assert_eq!(ConfirmMode::parse("sometimes"), None);
+        assert_eq!(ConfirmMode::default(), ConfirmMode::Smart);
+    }
+
+    #[test]
+    fn test_smart_confirmation() {
+        let smart = ConfirmMode::Smart;
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::Low, true),
+            ConfirmationType::None
+        );
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::Medium, false),
+            ConfirmationType::None
+        );
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::Medium, true),
+            ConfirmationType::YesNo
+        );
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::High, false),
+            ConfirmationType::YesNo
+        );
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::High, true),
+            ConfirmationType::Typed
+        );
+        assert_eq!(
+            smart.confirmation_for(RiskLevel::Critical, false),
+            ConfirmationType::Typed
+        );
+    }
+
+    #[test]
+    fn test_always_and_never_confirmation() {
+        assert_eq!(
+            ConfirmMode::Always.confirmation_for(RiskLevel::Medium, false),
+            ConfirmationType::YesNo
+        );
+        assert_eq!(
+            ConfirmMode::Always.confirmation_for(RiskLevel::Low, true),
+            ConfirmationType::None
+        );
+        assert_eq!(
+            ConfirmMode::Never.confirmation_for(RiskLevel::Critical, true),
+            ConfirmationType::None
+        );
+    }
+
+    #[test]
+    fn test_assess_uses_tool_classifier() {
+        let registry = ToolRegistry::new();
+        let assessed = assess(&registry, "docker rm -f web");
+        assert_eq!(assessed.tool, "docker");
+        assert!(assessed.level.requires_confirmation());
+        assert!(!assessed.is_production());
+    }
+
+    #[test]
+    fn test_unowned_destructive_commands_prompt() {
+        let registry = ToolRegistry::new();
+        for command in ["rm -rf ~", "dd if=/dev/zero of=/dev/sda", "chmod -R 777 /"] {
+            let risk = assess(&registry, command);
+            assert_eq!(risk.tool, "shell");
+            assert_ne!(
+                ConfirmMode::Always.confirmation_for(risk.level, false),
+                ConfirmationType::None,
+                "{command}"
+            );
+        }
+        for command in ["rm -rf ~", "dd if=/dev/zero of=/dev/sda"] {
+            let risk = assess(&registry, command);
+            assert_ne!(
+                ConfirmMode::Smart.confirmation_for(risk.level, false),
+                ConfirmationType::None,
+                "{command}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_assess_at_least_keeps_the_higher_risk() {
+        let registry = ToolRegistry::new();
+        let unknown = assess_at_least(&registry, "open -a Docker", RiskLevel::High);
+        assert_eq!((unknown.tool, unknown.level), ("shell", RiskLevel::High));
+
+        let docker = assess_at_least(&registry, "docker rm -f web", RiskLevel::Low);
+        assert_eq!(docker.tool, "docker");
+        assert!(docker.level > RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_prompt_answers() {
+        let medium = risk("docker", RiskLevel::Medium, EnvironmentType::Unknown);
+        let run = |answer: &str, confirmation| {
+            prompt_with(
+                "docker restart web",
+                &medium,
+                confirmation,
+                &mut answer.as_bytes(),
+            )
+        };
+
+        assert!(run("y\n", ConfirmationType::YesNo));
+        assert!(run("YES\n", ConfirmationType::YesNo));
+        assert!(!run("\n", ConfirmationType::YesNo));
+        assert!(!run("", ConfirmationType::YesNo));
+        assert!(run("", ConfirmationType::None));
+        assert!(run("yes\n", ConfirmationType::Typed));
+        assert!(!run("y\n", ConfirmationType::Typed));
+    }
+
+    #[test]
+    fn test_typed_expects_kubectl_resource_name() {
+        let high = risk("kubectl", RiskLevel::High, EnvironmentType::Production);
+        assert!(prompt_with(
+            "kubectl delete deployment api",
+            &high,
+            ConfirmationType::Typed,
+            &mut "api\n".as_bytes(),
+        ));
+    }
+}
@@ -0,0 +1,157 @@
+// Output highlighting
+//
+// User rules from `[[shell.output_highlights]]` color whole lines of
+// command output, e.g. ERROR lines red and WARN lines yellow. Patterns are
+// matched against the text without escape sequences, and the rule's color
+// is re-applied after every reset the program printed, so lines a tool
+// already colors keep their own colors and stay highlighted around them.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// CSI sequences (colors, cursor moves) and OSC sequences (titles, links)
+fn escape_sequence() -> &'static Regex {
+    static ESCAPE: OnceLock<Regex> = OnceLock::new();
+    ESCAPE.get_or_init(|| {
+        Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\))").unwrap()
+    })
+}
+
+/// SGR resets: `ESC[0m` and `ESC[m`
+fn sgr_reset() -> &'static Regex {
+    static RESET: OnceLock<Regex> = OnceLock::new();
+    RESET.get_or_init(|| Regex::new(r"\x1b\[0*m").unwrap())
+}
+
+/// One highlight rule from config.toml
+///
+/// ```toml
+/// [[shell.output_highlights]]
+/// pattern = "\\bERROR\\b"
+/// color = "1;31"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// Regex; a line with a match is colored
+    pub pattern: String,
+    /// SGR parameters, like the display palette: "31", "1;33", "38;5;208"
+    pub color: String,
+}
+
+impl HighlightRule {
+    pub fn new(pattern: impl Into<String>, color: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            color: color.into(),
+        }
+    }
+}
+
+/// Highlight rules compiled for the print path
+#[derive(Debug, Clone, Default)]
+pub struct OutputHighlighter {
+    rules: Vec<(Regex, String)>,
+}
+
+impl OutputHighlighter {
+    /// Compile `rules`, skipping (and logging) invalid ones
+    pub fn new(rules: &[HighlightRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) if crate::mentor::colors::is_sgr(&rule.color) => {
+                    Some((regex, format!("\x1b[{}m", rule.color)))
+                }
+                Ok(_) => {
+                    log::warn!(
+                        "Ignoring highlight rule '{}': '{}' is not an SGR color",
+                        rule.pattern,
+                        rule.color
+                    );
+                    None
+                }
+                Err(e) => {
+                    log::warn!("Ignoring highlight rule '{}': {e}", rule.pattern);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Output with matching lines colored by the first rule that matches
+    pub fn apply<'a>(&self, output: &'a str) -> Cow<'a, str> {
+        if self.rules.is_empty() {
+            return Cow::Borrowed(output);
+        }
+
+        let mut highlighted = String::with_capacity(output.len());
+        let mut changed = false;
+        for line in output.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\r', '\n']);
+            let ending = &line[content.len()..];
+            let plain = escape_sequence().replace_all(content, "");
+
+            match self.rules.iter().find(|(regex, _)| regex.is_match(&plain)) {
+                Some((_, color)) => {
+                    changed = true;
+                    let reset_then_color = format!("\x1b[0m{color}");
+                    highlighted.push_str(color);
+                    highlighted
+                        .push_str(&sgr_reset().replace_all(content, reset_then_color.as_str()));
+                    highlighted.push_str("\x1b[0m");
+                    highlighted.push_str(ending);
+                }
+                None => highlighted.push_str(line),
+            }
+        }
+
+        if changed {
+            Cow::Owned(highlighted)
+        } else {
+            Cow::Borrowed(output)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighter() -> OutputHighlighter {
+        OutputHighlighter::new(&[
+            HighlightRule::new(r"\bERROR\b", "31"),
+            HighlightRule::new(r"\bWARN", "33"),
+            HighlightRule::new(r"(", "32"),
+            HighlightRule::new("INFO", "green"),
+        ])
+    }
+
+    #[test]
+    fn test_colors_matching_lines() {
+        let output = "INFO start\r\nWARN disk 91%\r\nERROR failed\r\n";
+        assert_eq!(
+            highlighter().apply(output),
+            "INFO start\r\n\x1b[33mWARN disk 91%\x1b[0m\r\n\x1b[31mERROR failed\x1b[0m\r\n"
+        );
+        // Invalid rules are dropped, nothing matching borrows the input
+        assert_eq!(highlighter().rules.len(), 2);
+        assert!(matches!(highlighter().apply("ok\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_keeps_existing_colors() {
+        // The tool colored "ERROR" itself; the pattern still sees plain text
+        let output = "\x1b[1mERROR\x1b[0m: no such host";
+        assert_eq!(
+            highlighter().apply(output),
+            "\x1b[31m\x1b[1mERROR\x1b[0m\x1b[31m: no such host\x1b[0m"
+        );
+    }
+}
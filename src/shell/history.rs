@@ -1,10 +1,14 @@
 // Shell history management for Kaido
 //
-// Handles command history persistence using rustyline's FileHistory.
+// rustyline's FileHistory keeps the in-memory history for arrow-key recall;
+// the file itself is written here so each session can be preceded by a
+// timestamped `#session` comment. The format is otherwise rustyline's V2
+// (newlines and backslashes escaped), so older history files load as is.
 // History is stored in ~/.kaido/history
 
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
 
 /// Get the default history file path
 pub fn default_history_path() -> PathBuf {
@@ -65,6 +69,148 @@ impl HistoryConfig {
     }
 }
 
+/// Header rustyline writes on V2 history files
+const FILE_VERSION_V2: &str = "#V2";
+
+/// Prefix of the comment line that starts a session in the history file
+pub const SESSION_MARKER: &str = "#session ";
+
+/// One line of the history file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistoryLine {
+    /// Start of a session, with its timestamp as written
+    Session(String),
+    /// A command
+    Command(String),
+}
+
+/// Read the history file; a missing file is empty
+///
+/// Comment lines other than session markers (such as the `#V2` header) are
+/// skipped, so they never come back as commands.
+pub fn read_history(path: &Path) -> Result<Vec<HistoryLine>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context("Failed to read history"),
+    };
+
+    Ok(text
+        .lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            if let Some(started) = line.strip_prefix(SESSION_MARKER) {
+                Some(HistoryLine::Session(started.to_string()))
+            } else if line.starts_with('#') {
+                None
+            } else {
+                Some(HistoryLine::Command(unescape(line)))
+            }
+        })
+        .collect())
+}
+
+/// Commands in the history file, oldest first
+pub fn history_commands(lines: &[HistoryLine]) -> impl Iterator<Item = &str> {
+    lines.iter().filter_map(|line| match line {
+        HistoryLine::Command(command) => Some(command.as_str()),
+        HistoryLine::Session(_) => None,
+    })
+}
+
+/// Add a session's commands to the history file under a session marker
+///
+/// With `config.ignore_dups`, consecutive repeats are written once. The
+/// oldest commands are dropped once the file holds more than
+/// `config.max_entries`, along with markers left with no commands.
+pub fn append_session(
+    config: &HistoryConfig,
+    started: DateTime<Local>,
+    commands: &[String],
+) -> Result<()> {
+    let mut lines = read_history(&config.file_path)?;
+
+    let mut session: Vec<&String> = commands.iter().collect();
+    if config.ignore_dups {
+        session.dedup();
+    }
+    if session.is_empty() {
+        return Ok(());
+    }
+    lines.push(HistoryLine::Session(started.to_rfc3339()));
+    lines.extend(session.into_iter().cloned().map(HistoryLine::Command));
+
+    write_history(&config.file_path, &trim_history(lines, config.max_entries))
+}
+
+/// Empty the history file
+pub fn clear_history(path: &Path) -> Result<()> {
+    write_history(path, &[])
+}
+
+/// Keep the newest `max_entries` commands
+fn trim_history(lines: Vec<HistoryLine>, max_entries: usize) -> Vec<HistoryLine> {
+    let total = history_commands(&lines).count();
+    let mut to_drop = total.saturating_sub(max_entries);
+
+    let mut kept: Vec<HistoryLine> = lines
+        .into_iter()
+        .filter(|line| match line {
+            HistoryLine::Command(_) if to_drop > 0 => {
+                to_drop -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    // Drop markers directly followed by another marker or the end
+    let mut i = 0;
+    while i < kept.len() {
+        let empty_session = matches!(kept[i], HistoryLine::Session(_))
+            && !matches!(kept.get(i + 1), Some(HistoryLine::Command(_)));
+        if empty_session {
+            kept.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    kept
+}
+
+fn write_history(path: &Path, lines: &[HistoryLine]) -> Result<()> {
+    let mut text = format!("{FILE_VERSION_V2}\n");
+    for line in lines {
+        match line {
+            HistoryLine::Session(started) => text.push_str(&format!("{SESSION_MARKER}{started}")),
+            HistoryLine::Command(command) => text.push_str(&escape(command)),
+        }
+        text.push('\n');
+    }
+    std::fs::write(path, text).context("Failed to save history")
+}
+
+/// Escape backslashes and newlines as rustyline's V2 format does
+fn escape(command: &str) -> String {
+    command.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(line: &str) -> String {
+    let mut unescaped = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +242,59 @@ mod tests {
         let result = ensure_history_dir();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_sessions_are_marked_and_deduplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HistoryConfig::with_path(dir.path().join("history"));
+        let commands = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        append_session(
+            &config,
+            Local::now(),
+            &commands(&["ls", "ls", "git status"]),
+        )
+        .unwrap();
+        append_session(&config, Local::now(), &commands(&["echo 'a\\nb'", "ls"])).unwrap();
+        append_session(&config, Local::now(), &[]).unwrap();
+
+        let lines = read_history(&config.file_path).unwrap();
+        let sessions = lines
+            .iter()
+            .filter(|line| matches!(line, HistoryLine::Session(_)))
+            .count();
+        assert_eq!(sessions, 2);
+        assert_eq!(
+            history_commands(&lines).collect::<Vec<_>>(),
+            ["ls", "git status", "echo 'a\\nb'", "ls"]
+        );
+
+        clear_history(&config.file_path).unwrap();
+        assert!(read_history(&config.file_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trim_drops_oldest_commands_and_empty_sessions() {
+        let lines = vec![
+            HistoryLine::Session("one".to_string()),
+            HistoryLine::Command("a".to_string()),
+            HistoryLine::Command("b".to_string()),
+            HistoryLine::Session("two".to_string()),
+            HistoryLine::Command("c".to_string()),
+        ];
+        assert_eq!(
+            trim_history(lines, 1),
+            [
+                HistoryLine::Session("two".to_string()),
+                HistoryLine::Command("c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_escaping_round_trips() {
+        let command = "printf 'a\\tb'\necho done";
+        assert!(!escape(command).contains('\n'));
+        assert_eq!(unescape(&escape(command)), command);
+    }
 }
@@ -10,21 +10,53 @@
 use anyhow::{Context, Result};
 use rustyline::error::ReadlineError;
 use rustyline::history::FileHistory;
-use rustyline::{Config, Editor};
+use rustyline::{CompletionType, Config, Editor};
 
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
+use unicode_width::UnicodeWidthStr;
 
 use super::builtins::{execute_builtin, parse_builtin, Builtin, BuiltinResult, ShellEnvironment};
-use super::history::{ensure_history_dir, HistoryConfig};
-use super::prompt::PromptBuilder;
-use super::pty::{PtyExecutionResult, PtyExecutor};
-use crate::ai::AIManager;
-use crate::config::Config as KaidoConfig;
+use super::completion::CommandCompleter;
+use super::confirm::{self, ConfirmMode};
+use super::highlight::{HighlightRule, OutputHighlighter};
+use super::history::{
+    append_session, clear_history, ensure_history_dir, history_commands, read_history,
+    HistoryConfig, HistoryLine,
+};
+use super::pager;
+use super::prefetch::{PendingExplanation, PromptPrinter};
+use super::prompt::{switches_kube_context, PromptBuilder};
+use super::pty::{PtyExecutionResult, PtyExecutor, DEFAULT_ENV_SNAPSHOT, DEFAULT_PASSTHROUGH};
+use super::recording::{SessionEvent, SessionRecorder};
+use super::signals::{self, SignalHandler};
+use crate::ai::{
+    redact_secrets, AIManager, ChatMessage, CommandExplainer, Conversation, OutputBudget,
+};
+use crate::config::{Config as KaidoConfig, VerbosityModeConfig};
+use crate::error::solutions::{render_solutions, runnable_command};
+use crate::error::SolutionRunner;
+use crate::kubectl::switch::{self as kube_switch, NamespaceCheck};
+use crate::kubectl::{EnvironmentType, KubectlContext};
+use crate::learning::hints::MAX_TIMES_SHOWN;
 use crate::learning::{
-    LearningTracker, SessionStats, SkillDetector, SummaryGenerator, VerbosityMode,
+    FeatureHint, HintScheduler, LearningTracker, SessionStats, SkillDetector, SummaryGenerator,
+    VerbosityMode, WeakConcept,
+};
+use crate::mentor::i18n::{Label, Phrase};
+use crate::mentor::{
+    CacheStats, ColorTheme, ErrorDetector, ErrorInfo, ErrorType, GuidanceCache, Language,
+    MentorColors, MentorConfig, MentorDisplay, Palette, Verbosity,
+};
+use crate::tools::command_line;
+use crate::tools::config_snapshot::{self, WebServer};
+use crate::tools::network::plain_get_url;
+use crate::tools::{
+    format_availability, ErrorExplanation, ErrorLogTail, HttpTiming, LLMBackend, LLMResponse,
+    NetworkTool, ToolContext, ToolRegistry,
 };
-use crate::mentor::{ErrorDetector, ErrorInfo, MentorDisplay, Verbosity};
-use crate::tools::LLMBackend;
+use crate::ui::confirmation::ConfirmationType;
 
 /// Kaido shell configuration
 #[derive(Debug, Clone)]
@@ -35,6 +67,8 @@ pub struct ShellConfig {
     pub use_colors: bool,
     /// Whether to show git branch in prompt
     pub show_git_branch: bool,
+    /// Show the kubectl context and environment in the prompt
+    pub show_kube_context: bool,
     /// Shell to use for command execution
     pub shell: Option<String>,
     /// Mentor display verbosity level
@@ -45,6 +79,41 @@ pub struct ShellConfig {
     pub ai_enabled: bool,
     /// Show AI suggestions after commands
     pub show_suggestions: bool,
+    /// Capture stdout and stderr separately (best effort, runs commands
+    /// without a PTY so colors and full-screen programs may not work)
+    pub separate_streams: bool,
+    /// Page output taller than the terminal through `$PAGER`
+    /// (never when stdout is not a TTY or under CI)
+    pub page_long_output: bool,
+    /// When to ask before running Medium+ risk commands
+    pub confirm_mode: ConfirmMode,
+    /// Mask tokens, passwords and keys in prompts sent to cloud AI providers
+    pub redact_secrets: bool,
+    /// Mentor color theme
+    pub color_theme: ColorTheme,
+    /// Custom mentor colors over the theme's
+    pub palette: Palette,
+    /// Programs run on the real terminal (vim, less, top, ...)
+    pub passthrough_commands: Vec<String>,
+    /// Show a one-line note instead of the full mentor block when an error
+    /// repeats within the session
+    pub collapse_repeated_errors: bool,
+    /// Language of mentor text and AI explanations
+    pub language: Language,
+    /// Show the welcome banner on startup
+    pub show_banner: bool,
+    /// Custom banner (MOTD) file shown instead of the built-in one
+    pub banner_path: Option<PathBuf>,
+    /// Print nothing on startup, not even the AI mode line
+    pub quiet: bool,
+    /// Environment variables recorded with each command result
+    pub env_snapshot: Vec<String>,
+    /// Suggest builtins like `why` to beginners when errors repeat
+    pub feature_hints: bool,
+    /// Regex → color rules applied to command output lines
+    pub output_highlights: Vec<HighlightRule>,
+    /// Longest guidance shown as a one-liner (0 = always the full box)
+    pub compact_threshold: usize,
 }
 
 impl Default for ShellConfig {
@@ -53,11 +122,121 @@ impl Default for ShellConfig {
             history: HistoryConfig::default(),
             use_colors: true,
             show_git_branch: true,
+            show_kube_context: true,
             shell: None,
             mentor_verbosity: Verbosity::Normal,
             verbosity_mode: VerbosityMode::Auto,
             ai_enabled: true, // AI-native by default
             show_suggestions: true,
+            separate_streams: false,
+            page_long_output: false,
+            confirm_mode: ConfirmMode::Smart,
+            redact_secrets: true,
+            color_theme: ColorTheme::default(),
+            palette: Palette::default(),
+            passthrough_commands: DEFAULT_PASSTHROUGH.iter().map(|p| p.to_string()).collect(),
+            collapse_repeated_errors: false,
+            language: Language::from_env(),
+            show_banner: true,
+            banner_path: None,
+            quiet: false,
+            env_snapshot: DEFAULT_ENV_SNAPSHOT.iter().map(|v| v.to_string()).collect(),
+            feature_hints: true,
+            output_highlights: Vec::new(),
+            compact_threshold: crate::mentor::DEFAULT_COMPACT_THRESHOLD,
+        }
+    }
+}
+
+impl ShellConfig {
+    /// Shell settings from the `[shell]` section of config.toml
+    pub fn from_config(config: &KaidoConfig) -> Self {
+        let prefs = &config.shell;
+        Self {
+            use_colors: prefs.use_colors,
+            show_git_branch: prefs.show_git_branch,
+            show_kube_context: prefs.show_kube_context,
+            shell: prefs.shell.clone(),
+            mentor_verbosity: prefs.mentor_verbosity,
+            verbosity_mode: match prefs.verbosity_mode {
+                VerbosityModeConfig::Auto => VerbosityMode::Auto,
+                VerbosityModeConfig::Fixed => VerbosityMode::Fixed(prefs.mentor_verbosity),
+            },
+            ai_enabled: prefs.ai_enabled,
+            show_suggestions: prefs.show_suggestions,
+            separate_streams: prefs.separate_streams,
+            page_long_output: prefs.page_long_output,
+            confirm_mode: prefs.confirm_mode,
+            redact_secrets: prefs.redact_secrets,
+            color_theme: config.display.theme,
+            palette: config.display.palette.clone(),
+            passthrough_commands: prefs.passthrough_commands.clone(),
+            collapse_repeated_errors: prefs.collapse_repeated_errors,
+            language: Language::resolve(config.display.language),
+            show_banner: prefs.show_banner,
+            banner_path: prefs.banner_path.clone(),
+            env_snapshot: prefs.env_snapshot.clone(),
+            feature_hints: prefs.feature_hints,
+            output_highlights: prefs.output_highlights.clone(),
+            compact_threshold: config.display.compact_threshold,
+            ..Self::default()
+        }
+    }
+
+    /// Compact threshold for the mentor display; choosing verbose (or
+    /// debug) explicitly always gets the full box
+    pub fn mentor_compact_threshold(&self) -> usize {
+        match self.verbosity_mode {
+            VerbosityMode::Fixed(Verbosity::Verbose | Verbosity::Debug) => 0,
+            _ => self.compact_threshold,
+        }
+    }
+}
+
+/// How many follow-up commands a tracked error survives before we stop
+/// waiting for it to be resolved
+const RESOLUTION_WINDOW: usize = 5;
+
+/// Longest config diff shown after a config error
+const MAX_DISPLAY_DIFF_LINES: usize = 40;
+
+/// Longest config diff sent to the AI
+const MAX_PROMPT_DIFF_CHARS: usize = 2000;
+
+/// Columns of text inside the AI mentor box
+const MENTOR_BOX_TEXT_WIDTH: usize = 58;
+
+/// Error log lines shown after a web server config error or 5xx
+const ERROR_LOG_LINES: usize = 15;
+
+/// Follow-up builtins that dig into the last error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FollowUp {
+    /// Deeper explanation of the root cause
+    Why,
+    /// Propose the top fix and offer to run it
+    Fix,
+    /// Re-render the mentor block in verbose mode
+    More,
+}
+
+impl FollowUp {
+    /// Parse a bare `why`, `fix` or `more` (`more <file>` is the pager)
+    fn parse(line: &str) -> Option<Self> {
+        match line {
+            "why" => Some(Self::Why),
+            "fix" => Some(Self::Fix),
+            "more" => Some(Self::More),
+            _ => None,
+        }
+    }
+
+    /// Feature hint this builtin makes unnecessary
+    fn hint(&self) -> FeatureHint {
+        match self {
+            Self::Why => FeatureHint::Why,
+            Self::Fix => FeatureHint::Fix,
+            Self::More => FeatureHint::Verbose,
         }
     }
 }
@@ -71,6 +250,68 @@ struct TrackedError {
     command: String,
     /// When the error occurred
     timestamp: Instant,
+    /// Commands run since the error
+    followups: usize,
+    /// Most recent successful related command that differs from the
+    /// original (e.g. `brew install jq` before re-running `jq`)
+    last_fix: Option<String>,
+}
+
+/// Outcome of checking a follow-up command against a tracked error
+#[derive(Debug, PartialEq)]
+enum ResolutionCheck {
+    /// The original command now succeeds; carries the command credited with the fix
+    Resolved(String),
+    /// Keep waiting
+    Pending,
+    /// Window exhausted without a resolution
+    Expired,
+}
+
+impl TrackedError {
+    fn new(id: i64, command: &str) -> Self {
+        Self {
+            id,
+            command: command.to_string(),
+            timestamp: Instant::now(),
+            followups: 0,
+            last_fix: None,
+        }
+    }
+
+    /// Observe a command run after the error
+    fn observe(&mut self, command: &str, succeeded: bool) -> ResolutionCheck {
+        let similar = LearningTracker::is_similar_command(command, &self.command);
+
+        if succeeded && similar {
+            let resolved_by = self.last_fix.take().unwrap_or_else(|| command.to_string());
+            return ResolutionCheck::Resolved(resolved_by);
+        }
+
+        if succeeded && self.relates_to(command) {
+            self.last_fix = Some(command.to_string());
+        }
+
+        self.followups += 1;
+        if self.followups >= RESOLUTION_WINDOW {
+            ResolutionCheck::Expired
+        } else {
+            ResolutionCheck::Pending
+        }
+    }
+
+    /// Whether `command` works on the same problem: it runs the failed
+    /// program again, or names it (`sudo systemctl start docker` after
+    /// `docker ps` failed)
+    fn relates_to(&self, command: &str) -> bool {
+        let Some(program) = command_line::program(&self.command) else {
+            return false;
+        };
+        command_line::program(command) == Some(program)
+            || command
+                .split_whitespace()
+                .any(|word| word.eq_ignore_ascii_case(program))
+    }
 }
 
 /// The main Kaido shell - AI-Native
@@ -79,8 +320,8 @@ pub struct KaidoShell {
     config: ShellConfig,
     /// PTY executor for running commands
     pty: PtyExecutor,
-    /// Readline editor with history
-    editor: Editor<(), FileHistory>,
+    /// Readline editor with history and tool completion
+    editor: Editor<CommandCompleter, FileHistory>,
     /// Prompt builder
     prompt_builder: PromptBuilder,
     /// Shell environment (variables, aliases, previous dir)
@@ -90,11 +331,25 @@ pub struct KaidoShell {
     /// Mentor display for formatting guidance (fallback)
     mentor_display: MentorDisplay,
     /// AI Manager for LLM-powered explanations
-    ai_manager: AIManager,
+    ai_manager: Arc<AIManager>,
+    /// Config profile the AI settings came from
+    active_profile: Option<String>,
+    /// Recent AI exchanges, so follow-up questions have context
+    conversation: Conversation,
+    /// Whether `conversation` already holds the explanation of `last_error`
+    last_error_explained: bool,
+    /// AI explanation of `last_error` still being fetched
+    pending_explanation: Option<PendingExplanation>,
+    /// Prints arriving explanations above the input line
+    prompt_printer: PromptPrinter,
     /// Learning tracker for progress
     learning_tracker: Option<LearningTracker>,
     /// Skill detector for adaptive verbosity
     skill_detector: SkillDetector,
+    /// When to suggest a builtin the user hasn't found yet
+    hints: HintScheduler,
+    /// Concept of the refresher card shown at startup, for `dismiss`
+    refresher: Option<String>,
     /// Session statistics for summary
     session_stats: SessionStats,
     /// Whether the shell is running
@@ -103,20 +358,50 @@ pub struct KaidoShell {
     last_result: Option<PtyExecutionResult>,
     /// Last detected error (for mentor system)
     last_error: Option<ErrorInfo>,
+    /// Numbered fixes shown for `last_error`, run with `apply [n]`
+    last_solutions: Option<ErrorExplanation>,
     /// Tracked error for resolution detection
     tracked_error: Option<TrackedError>,
     /// Command history for context (last N commands)
     command_history: Vec<String>,
+    /// Lines added to history this session, saved under a session marker
+    session_commands: Vec<String>,
+    /// When this session started
+    session_started: chrono::DateTime<chrono::Local>,
+    /// Tool classifiers used to rate command risk before execution
+    tool_registry: ToolRegistry,
+    /// Whether the `confirm never` warning was already shown
+    confirm_never_warned: bool,
+    /// How much command output goes into AI explanation prompts
+    output_budget: OutputBudget,
+    /// Colors lines of command output per `output_highlights`
+    highlighter: OutputHighlighter,
+    /// Transcript writer when the session is being recorded
+    recorder: Option<SessionRecorder>,
+    /// Terminal size kept current on SIGWINCH
+    signals: SignalHandler,
 }
 
 impl KaidoShell {
-    /// Create a new Kaido shell with default configuration
+    /// Create a new Kaido shell configured from config.toml
     pub fn new() -> Result<Self> {
-        Self::with_config(ShellConfig::default())
+        let kaido_config = KaidoConfig::load_with_profile(None).unwrap_or_default();
+        Self::with_ai_config(ShellConfig::from_config(&kaido_config), kaido_config)
     }
 
     /// Create a new Kaido shell with custom configuration
     pub fn with_config(config: ShellConfig) -> Result<Self> {
+        Self::with_ai_config(
+            config,
+            KaidoConfig::load_with_profile(None).unwrap_or_default(),
+        )
+    }
+
+    /// Create a new Kaido shell with custom shell and AI configuration
+    ///
+    /// Used to apply per-session overrides (e.g. `--model`) without
+    /// touching the config file.
+    pub fn with_ai_config(mut config: ShellConfig, kaido_config: KaidoConfig) -> Result<Self> {
         // Ensure history directory exists
         ensure_history_dir()?;
 
@@ -125,18 +410,30 @@ impl KaidoShell {
             .history_ignore_dups(config.history.ignore_dups)?
             .history_ignore_space(config.history.ignore_space)
             .max_history_size(config.history.max_entries)?
-            .auto_add_history(true)
+            // Lines are added in `run` so the session's commands are known
+            .auto_add_history(false)
+            // Show every match on Tab, like bash, so commands can be discovered
+            .completion_type(CompletionType::List)
             .build();
 
         // Create editor with file history
-        let mut editor = Editor::<(), FileHistory>::with_history(
+        let mut editor = Editor::<CommandCompleter, FileHistory>::with_history(
             rl_config,
             FileHistory::with_config(rl_config),
         )?;
+        editor.set_helper(Some(CommandCompleter::new()));
+
+        // Load history, leaving out session markers
+        let saved = read_history(&config.history.file_path).unwrap_or_default();
+        for command in history_commands(&saved) {
+            let _ = editor.add_history_entry(command);
+        }
 
-        // Load history if file exists
-        if config.history.file_path.exists() {
-            let _ = editor.load_history(&config.history.file_path);
+        // Aliases imported from bash/zsh by `kaido onboard --import-aliases`
+        let mut shell_env = ShellEnvironment::new();
+        let aliases_path = super::aliases::default_aliases_path();
+        if let Err(e) = shell_env.load_aliases(&aliases_path) {
+            log::warn!("Failed to load aliases: {e}");
         }
 
         // Create PTY executor
@@ -144,7 +441,10 @@ impl KaidoShell {
             PtyExecutor::with_shell(shell)
         } else {
             PtyExecutor::new()
-        };
+        }
+        .with_separate_streams(config.separate_streams)
+        .with_passthrough(config.passthrough_commands.clone())
+        .with_env_snapshot(config.env_snapshot.clone());
 
         // Create prompt builder
         let mut prompt_builder = PromptBuilder::new();
@@ -154,21 +454,41 @@ impl KaidoShell {
         if !config.show_git_branch {
             prompt_builder = prompt_builder.no_git_branch();
         }
+        if !config.show_kube_context {
+            prompt_builder = prompt_builder.no_kube_context();
+        }
 
         // Create mentor display with config (fallback for when AI is unavailable)
         let mentor_display_config = crate::mentor::DisplayConfig {
             verbosity: config.mentor_verbosity,
             terminal_width: 0, // Auto-detect
             colors_enabled: config.use_colors,
+            hyperlinks: config.use_colors && crate::mentor::LinkTarget::detect().is_some(),
+            theme: config.color_theme,
+            palette: config.palette.clone(),
+            language: config.language,
+            compact_threshold: config.mentor_compact_threshold(),
         };
-        let mentor_display = MentorDisplay::with_config(mentor_display_config);
+        let signals = SignalHandler::new();
+        let highlighter = if config.use_colors {
+            OutputHighlighter::new(&config.output_highlights)
+        } else {
+            OutputHighlighter::default()
+        };
+        let mentor_display = MentorDisplay::with_config(mentor_display_config)
+            .with_terminal_size(signals.terminal_size().clone());
 
         // Create AI Manager for LLM-powered explanations
-        let kaido_config = KaidoConfig::load().unwrap_or_default();
+        let output_budget = kaido_config.truncation.prompt;
+        crate::tools::set_max_capture_bytes(kaido_config.truncation.capture_bytes);
+        let active_profile = kaido_config.active_profile.clone();
         let ai_manager = AIManager::new(kaido_config);
+        if ai_manager.is_offline() {
+            config.ai_enabled = false;
+        }
 
         // Try to create learning tracker (non-fatal if it fails)
-        let learning_tracker = match LearningTracker::with_default_path() {
+        let learning_tracker = match LearningTracker::with_default_path_or_memory() {
             Ok(tracker) => Some(tracker),
             Err(e) => {
                 log::warn!("Failed to create learning tracker: {e}");
@@ -181,44 +501,132 @@ impl KaidoShell {
             pty,
             editor,
             prompt_builder,
-            shell_env: ShellEnvironment::new(),
+            shell_env,
             error_detector: ErrorDetector::new(),
             mentor_display,
-            ai_manager,
+            ai_manager: Arc::new(ai_manager),
+            active_profile,
+            conversation: Conversation::new(),
+            last_error_explained: false,
+            pending_explanation: None,
+            prompt_printer: PromptPrinter::default(),
             learning_tracker,
             skill_detector: SkillDetector::new(),
+            hints: HintScheduler::new(),
+            refresher: None,
             session_stats: SessionStats::new(),
             running: false,
             last_result: None,
             last_error: None,
+            last_solutions: None,
             tracked_error: None,
             command_history: Vec::with_capacity(10),
+            session_commands: Vec::new(),
+            session_started: chrono::Local::now(),
+            tool_registry: ToolRegistry::new(),
+            confirm_never_warned: false,
+            output_budget,
+            highlighter,
+            recorder: None,
+            signals,
         })
     }
 
+    /// Record commands, output and mentor guidance to a JSONL transcript
+    pub fn record_to(&mut self, path: &std::path::Path) -> Result<()> {
+        self.recorder = Some(SessionRecorder::create(path)?);
+        Ok(())
+    }
+
+    /// Add an event to the transcript, if recording
+    ///
+    /// A failing recorder is dropped with a warning rather than
+    /// interrupting the session.
+    fn record(&mut self, elapsed: Option<std::time::Duration>, event: SessionEvent) {
+        let Some(recorder) = self.recorder.as_mut() else {
+            return;
+        };
+        let elapsed = elapsed.unwrap_or_else(|| recorder.elapsed());
+        if let Err(e) = recorder.record_at(elapsed, event) {
+            println!("\x1b[33m⚠ Recording stopped: {e}\x1b[0m");
+            self.recorder = None;
+        }
+    }
+
+    /// Print mentor guidance and add it to the transcript
+    fn show_mentor(&mut self, text: String) {
+        print!("{text}");
+        self.record(None, SessionEvent::Mentor { text });
+    }
+
     /// Display welcome message
     fn display_welcome(&self) {
-        println!();
-        println!("\x1b[1;36m  _  __     _     _       \x1b[0m");
-        println!("\x1b[1;36m | |/ /__ _(_) __| | ___  \x1b[0m");
-        println!("\x1b[1;36m | ' // _` | |/ _` |/ _ \\ \x1b[0m");
-        println!("\x1b[1;36m | . \\ (_| | | (_| | (_) |\x1b[0m");
-        println!("\x1b[1;36m |_|\\_\\__,_|_|\\__,_|\\___/ \x1b[0m");
-        println!();
-        println!("\x1b[1mAI-Native Shell\x1b[0m - Your intelligent ops companion.");
-        println!();
-        let ai_status = if self.config.ai_enabled {
+        if self.config.quiet {
+            return;
+        }
+        if let Some(banner) = banner(&self.config) {
+            print!("{banner}");
+        }
+        let ai_status = if self.ai_manager.is_offline() {
+            "\x1b[2m◆ Offline mode\x1b[0m - AI disabled, no network calls (pattern-based guidance)"
+        } else if self.config.ai_enabled {
             "\x1b[38;5;147m◆ AI Mode: ON\x1b[0m - LLM-powered explanations enabled"
         } else {
             "\x1b[2m◆ AI Mode: OFF\x1b[0m - Using pattern-based fallback"
         };
         println!("{ai_status}");
+        if self.config.show_banner && self.config.banner_path.is_none() {
+            println!();
+            println!(
+                "\x1b[2mType commands normally. AI will explain errors and suggest next steps.\x1b[0m"
+            );
+            println!(
+                "\x1b[2mType 'help' for commands, 'ai' for AI settings, 'exit' to quit.\x1b[0m"
+            );
+        }
         println!();
-        println!(
-            "\x1b[2mType commands normally. AI will explain errors and suggest next steps.\x1b[0m"
-        );
-        println!("\x1b[2mType 'help' for commands, 'ai' for AI settings, 'exit' to quit.\x1b[0m");
-        println!();
+    }
+
+    /// Show a refresher card for a concept that keeps tripping the user up
+    ///
+    /// At most once a day; the tracker decides which concept is due.
+    fn display_refresher(&mut self) {
+        if self.config.quiet {
+            return;
+        }
+        let Some(ref tracker) = self.learning_tracker else {
+            return;
+        };
+        let weak = match tracker.concept_due_for_review() {
+            Ok(Some(weak)) => weak,
+            Ok(None) => return,
+            Err(e) => {
+                log::debug!("No concept refresher: {e}");
+                return;
+            }
+        };
+        if let Err(e) = tracker.mark_reviewed(&weak.concept) {
+            log::warn!("Failed to record concept refresher: {e}");
+        }
+        print!("{}", render_refresher(&weak, self.config.language));
+        self.refresher = Some(weak.concept);
+    }
+
+    /// Handle `dismiss`: no more refreshers for the card's concept
+    fn dismiss_refresher(&mut self) {
+        let Some(concept) = self.refresher.take() else {
+            return;
+        };
+        let dismissed = match self.learning_tracker {
+            Some(ref tracker) => tracker.dismiss_review(&concept),
+            None => Ok(()),
+        };
+        match dismissed {
+            Ok(()) => {
+                println!("\x1b[38;5;147m◆\x1b[0m No more refreshers for \x1b[1m{concept}\x1b[0m")
+            }
+            Err(e) => println!("\x1b[33mCould not dismiss the refresher: {e}\x1b[0m"),
+        }
     }
 
     /// Run the shell main loop
@@ -230,13 +638,39 @@ impl KaidoShell {
             let _ = tracker.start_session();
         }
 
+        // Installed after the editor so rustyline's own SIGWINCH handler,
+        // which redraws the prompt while reading a line, still runs too
+        if let Err(e) = self.signals.setup() {
+            log::warn!("Failed to watch terminal resizes: {e}");
+        }
+        match self.editor.create_external_printer() {
+            Ok(printer) => self.prompt_printer = PromptPrinter::new(printer),
+            Err(e) => log::debug!("No external printer, AI explanations wait for the prompt: {e}"),
+        }
+
         self.display_welcome();
+        self.display_refresher();
 
         while self.running {
+            self.collect_explanation(false).await;
+            self.prompt_builder.refresh_kube_context();
             let prompt = self.prompt_builder.build();
 
-            match self.editor.readline(&prompt) {
+            self.prompt_printer.set_at_prompt(true);
+            let line = self.editor.readline(&prompt);
+            self.prompt_printer.set_at_prompt(false);
+            self.sync_terminal_size();
+            // Bookkeeping for an explanation printed while the line was edited
+            self.collect_explanation(false).await;
+            match line {
                 Ok(line) => {
+                    if self
+                        .editor
+                        .add_history_entry(line.as_str())
+                        .unwrap_or(false)
+                    {
+                        self.session_commands.push(line.clone());
+                    }
                     let line = line.trim();
 
                     // Skip empty lines
@@ -244,6 +678,39 @@ impl KaidoShell {
                         continue;
                     }
 
+                    // `ai status` also checks the model, which needs async
+                    if matches!(line, "ai" | "ai status") {
+                        self.display_ai_status().await;
+                        continue;
+                    }
+
+                    // `? restart nginx` asks the AI for a command
+                    if let Some(request) = line.strip_prefix('?') {
+                        self.translate_request(request.trim()).await?;
+                        continue;
+                    }
+
+                    // `explain --deep` may ask the AI
+                    if let Some(command) = line.strip_prefix("explain --deep ") {
+                        self.explain_deep(command.trim()).await;
+                        continue;
+                    }
+
+                    // `apply [n]` runs one of the fixes listed after an error
+                    if let Some(number) = line.strip_prefix("apply") {
+                        if number.is_empty() || number.starts_with(' ') {
+                            self.apply_solution(number.trim()).await?;
+                            continue;
+                        }
+                    }
+
+                    // `why`/`fix`/`more` may call the AI and run commands
+                    if let Some(follow_up) = FollowUp::parse(line) {
+                        self.hints.note_used(follow_up.hint());
+                        self.handle_follow_up(follow_up).await?;
+                        continue;
+                    }
+
                     // Handle built-in commands
                     if self.handle_builtin(line) {
                         continue;
@@ -289,9 +756,54 @@ impl KaidoShell {
         Ok(())
     }
 
+    /// Display AI mode settings
+    fn display_ai_mode(&self) {
+        let status = if self.ai_manager.is_offline() {
+            "OFF (offline mode)"
+        } else if self.config.ai_enabled {
+            "ON"
+        } else {
+            "OFF"
+        };
+        let suggestions = if self.config.show_suggestions {
+            "ON"
+        } else {
+            "OFF"
+        };
+        println!("\x1b[38;5;147m◆\x1b[0m AI Mode: \x1b[1m{status}\x1b[0m");
+        if let Some(ref profile) = self.active_profile {
+            println!("  Profile: \x1b[1m{profile}\x1b[0m");
+        }
+        println!("  Suggestions: \x1b[1m{suggestions}\x1b[0m");
+        println!("  Use 'ai on/off' or 'ai suggestions on/off' to change.");
+    }
+
+    /// Display AI mode settings and the Ollama model's load state
+    async fn display_ai_status(&self) {
+        self.display_ai_mode();
+        if let Some((left, limit)) = self.ai_manager.gemini_quota() {
+            println!("  Gemini: {left} of {limit} requests left this minute");
+        }
+        if let Some(health) = self.ai_manager.ollama_health().await {
+            println!("  Ollama: {}", health.summary());
+        }
+    }
+
     /// Display session summary
     fn display_session_summary(&self) {
-        let summary = SummaryGenerator::generate(&self.session_stats);
+        let mut summary = SummaryGenerator::generate(&self.session_stats);
+
+        // Compare this session against recent ones
+        if let Some(ref tracker) = self.learning_tracker {
+            let window = self.skill_detector.trend_window();
+            if let (Ok(mut history), Ok(current)) =
+                (tracker.get_progress_history(window), tracker.get_progress())
+            {
+                history.push(current);
+                summary.trend = Some(self.skill_detector.trend(&history));
+            }
+        }
+
         let output = SummaryGenerator::render(&summary);
         print!("{output}");
     }
@@ -299,9 +811,31 @@ impl KaidoShell {
     /// Handle built-in shell commands
     /// Returns true if the command was handled
     fn handle_builtin(&mut self, line: &str) -> bool {
+        if line == "confirm" || line.starts_with("confirm ") {
+            self.handle_confirm_builtin(line["confirm".len()..].trim());
+            return true;
+        }
+        if line == "mentor cache" || line.starts_with("mentor cache ") {
+            self.handle_mentor_cache_builtin(line["mentor cache".len()..].trim());
+            return true;
+        }
+        if line == "profile" || line.starts_with("profile ") {
+            self.handle_profile_builtin(line["profile".len()..].trim());
+            return true;
+        }
+        if line == "kctx" || line.starts_with("kctx ") {
+            self.handle_kctx_builtin(line["kctx".len()..].trim());
+            return true;
+        }
+        if line == "kns" || line.starts_with("kns ") {
+            self.handle_kns_builtin(line["kns".len()..].trim());
+            return true;
+        }
+
         // First check mentor-specific commands (not in builtins module)
         match line {
             "verbose" | "mentor verbose" => {
+                self.hints.note_used(FeatureHint::Verbose);
                 self.config.verbosity_mode = VerbosityMode::Fixed(Verbosity::Verbose);
                 self.set_verbosity(Verbosity::Verbose);
                 println!(
@@ -321,17 +855,27 @@ impl KaidoShell {
                 println!("\x1b[36m◆\x1b[0m Mentor verbosity: \x1b[1mCompact\x1b[0m (one-liner)");
                 return true;
             }
+            "mentor debug" => {
+                self.config.verbosity_mode = VerbosityMode::Fixed(Verbosity::Debug);
+                self.set_verbosity(Verbosity::Debug);
+                println!(
+                    "\x1b[36m◆\x1b[0m Mentor verbosity: \x1b[1mDebug\x1b[0m (shows matched pattern)"
+                );
+                return true;
+            }
             "mentor" => {
                 let level = match self.config.mentor_verbosity {
                     Verbosity::Verbose => "Verbose",
                     Verbosity::Normal => "Normal",
                     Verbosity::Compact => "Compact",
+                    Verbosity::Debug => "Debug",
                 };
                 println!("\x1b[36m◆\x1b[0m Mentor verbosity: \x1b[1m{level}\x1b[0m");
                 println!("  Use 'verbose', 'normal', or 'compact' to change.");
                 return true;
             }
             "progress" | "/progress" => {
+                self.hints.note_used(FeatureHint::Progress);
                 self.display_progress();
                 return true;
             }
@@ -339,6 +883,10 @@ impl KaidoShell {
                 self.display_skill_assessment();
                 return true;
             }
+            "dismiss" if self.refresher.is_some() => {
+                self.dismiss_refresher();
+                return true;
+            }
             "mentor auto" => {
                 self.config.verbosity_mode = VerbosityMode::Auto;
                 println!(
@@ -348,15 +896,13 @@ impl KaidoShell {
                 return true;
             }
             "ai" | "ai status" => {
-                let status = if self.config.ai_enabled { "ON" } else { "OFF" };
-                let suggestions = if self.config.show_suggestions {
-                    "ON"
-                } else {
-                    "OFF"
-                };
-                println!("\x1b[38;5;147m◆\x1b[0m AI Mode: \x1b[1m{status}\x1b[0m");
-                println!("  Suggestions: \x1b[1m{suggestions}\x1b[0m");
-                println!("  Use 'ai on/off' or 'ai suggestions on/off' to change.");
+                self.display_ai_mode();
+                return true;
+            }
+            "ai on" if self.ai_manager.is_offline() => {
+                println!(
+                    "\x1b[33mAI stays off in offline mode. Restart without --no-ai or KAIDO_OFFLINE.\x1b[0m"
+                );
                 return true;
             }
             "ai on" => {
@@ -378,6 +924,19 @@ impl KaidoShell {
                 println!("\x1b[38;5;147m◆\x1b[0m AI Suggestions: \x1b[1mON\x1b[0m");
                 return true;
             }
+            "theme" => {
+                self.display_themes();
+                return true;
+            }
+            "tools" => {
+                println!();
+                print!(
+                    "{}",
+                    format_availability(&ToolRegistry::new().availability(true), true)
+                );
+                println!();
+                return true;
+            }
             "ai suggestions off" => {
                 self.config.show_suggestions = false;
                 println!("\x1b[38;5;147m◆\x1b[0m AI Suggestions: \x1b[1mOFF\x1b[0m");
@@ -386,6 +945,11 @@ impl KaidoShell {
             _ => {}
         }
 
+        if let Some(name) = line.strip_prefix("theme ") {
+            self.set_theme(name);
+            return true;
+        }
+
         // Try to parse as a builtin
         if let Some(builtin) = parse_builtin(line) {
             match &builtin {
@@ -394,7 +958,15 @@ impl KaidoShell {
                     return true;
                 }
                 Builtin::History => {
-                    self.display_history();
+                    self.display_history(false);
+                    return true;
+                }
+                Builtin::HistorySession => {
+                    self.display_history(true);
+                    return true;
+                }
+                Builtin::HistoryClear => {
+                    self.clear_history();
                     return true;
                 }
                 Builtin::Clear => {
@@ -438,108 +1010,591 @@ impl KaidoShell {
         false
     }
 
-    /// Set mentor verbosity level
-    fn set_verbosity(&mut self, verbosity: Verbosity) {
-        self.config.mentor_verbosity = verbosity;
-        let display_config = crate::mentor::DisplayConfig {
-            verbosity,
-            terminal_width: 0,
-            colors_enabled: self.config.use_colors,
+    /// Show or change the confirmation mode (`confirm [always|never|smart]`)
+    fn handle_confirm_builtin(&mut self, arg: &str) {
+        if arg.is_empty() {
+            let mode = self.config.confirm_mode;
+            println!(
+                "\x1b[36m◆\x1b[0m Confirm mode: \x1b[1m{}\x1b[0m ({})",
+                mode.as_str(),
+                mode.description()
+            );
+            println!("  Use 'confirm always', 'confirm smart' or 'confirm never' to change.");
+            return;
+        }
+
+        let Some(mode) = ConfirmMode::parse(arg) else {
+            println!("\x1b[31mUnknown confirm mode '{arg}'. Use always, never or smart.\x1b[0m");
+            return;
         };
-        self.mentor_display = MentorDisplay::with_config(display_config);
-    }
 
-    /// Display help message
-    fn display_help(&self) {
-        println!();
-        println!("\x1b[1;36mKaido Shell - Built-in Commands\x1b[0m");
-        println!();
-        println!("  \x1b[1mhelp\x1b[0m              Show this help message");
-        println!("  \x1b[1mhistory\x1b[0m           Show command history");
-        println!("  \x1b[1mclear\x1b[0m             Clear the screen");
-        println!("  \x1b[1mexit\x1b[0m              Exit the shell");
-        println!();
-        println!("\x1b[1;36mDirectory & Environment\x1b[0m");
-        println!();
-        println!("  \x1b[1mcd <dir>\x1b[0m          Change directory");
-        println!("  \x1b[1mcd -\x1b[0m              Go to previous directory");
-        println!("  \x1b[1mexport VAR=val\x1b[0m    Set environment variable");
-        println!("  \x1b[1munset VAR\x1b[0m         Remove environment variable");
-        println!();
-        println!("\x1b[1;36mAliases\x1b[0m");
-        println!();
-        println!("  \x1b[1malias\x1b[0m             List all aliases");
-        println!("  \x1b[1malias k=kubectl\x1b[0m   Create an alias");
-        println!("  \x1b[1munalias k\x1b[0m         Remove an alias");
-        println!();
-        println!("\x1b[1;36mScripting\x1b[0m");
-        println!();
-        println!("  \x1b[1msource <file>\x1b[0m     Execute commands from file");
-        println!();
-        println!("\x1b[1;36mMentor Verbosity\x1b[0m");
-        println!();
-        println!("  \x1b[1mmentor\x1b[0m            Show current verbosity level");
-        println!("  \x1b[1mmentor auto\x1b[0m       Adapt to your skill level");
-        println!("  \x1b[1mverbose\x1b[0m           Full explanations with next steps");
-        println!("  \x1b[1mnormal\x1b[0m            Key points only (default)");
-        println!("  \x1b[1mcompact\x1b[0m           One-liner for experts");
-        println!();
-        println!("\x1b[1;36mLearning Progress\x1b[0m");
-        println!();
-        println!("  \x1b[1mprogress\x1b[0m          Show your learning progress");
-        println!("  \x1b[1mskill\x1b[0m             Show your skill assessment");
-        println!();
-        println!("\x1b[1;38;5;147mAI Mode\x1b[0m");
-        println!();
-        println!("  \x1b[1mai\x1b[0m                Show AI status");
-        println!("  \x1b[1mai on\x1b[0m             Enable AI-powered explanations");
-        println!("  \x1b[1mai off\x1b[0m            Use pattern-based fallback");
-        println!("  \x1b[1mai suggestions on\x1b[0m Enable next-step suggestions");
-        println!("  \x1b[1mai suggestions off\x1b[0m Disable suggestions");
-        println!();
-        println!("\x1b[2mAll other commands are executed in the system shell.\x1b[0m");
-        println!("\x1b[2mWhen errors occur, AI will help you understand them.\x1b[0m");
-        println!();
-    }
+        self.config.confirm_mode = mode;
+        println!(
+            "\x1b[36m◆\x1b[0m Confirm mode: \x1b[1m{}\x1b[0m ({})",
+            mode.as_str(),
+            mode.description()
+        );
 
-    /// Display command history
-    fn display_history(&self) {
-        println!();
-        for (i, entry) in self.editor.history().iter().enumerate() {
-            println!("  {:4}  {}", i + 1, entry);
+        if mode == ConfirmMode::Never && !self.confirm_never_warned {
+            self.confirm_never_warned = true;
+            println!();
+            println!(
+                "\x1b[1;31m⚠ WARNING: destructive commands will now run without asking.\x1b[0m"
+            );
+            println!(
+                "\x1b[31m  That includes deleting production resources and dropping databases.\x1b[0m"
+            );
+            println!("\x1b[31m  Run 'confirm smart' to turn the safety net back on.\x1b[0m");
+            println!();
         }
-        println!();
     }
 
-    /// Display learning progress
-    fn display_progress(&self) {
-        println!();
+    /// Inspect or prune the mentor guidance cache
+    /// (`mentor cache [stats|clear|gc]`)
+    fn handle_mentor_cache_builtin(&self, action: &str) {
+        if !matches!(action, "" | "stats" | "clear" | "gc") {
+            println!("\x1b[31mUnknown cache action '{action}'. Use stats, clear or gc.\x1b[0m");
+            return;
+        }
 
-        let progress = match &self.learning_tracker {
-            Some(tracker) => match tracker.get_progress() {
-                Ok(p) => p,
-                Err(_) => {
-                    println!("\x1b[33mUnable to load learning progress.\x1b[0m");
-                    println!();
-                    return;
-                }
-            },
-            None => {
-                println!("\x1b[33mLearning tracker not available.\x1b[0m");
-                println!();
+        let config = MentorConfig::default();
+        let Some(path) = config.cache_path.filter(|path| path.exists()) else {
+            println!("\x1b[36m◆\x1b[0m Mentor cache: empty (nothing cached yet)");
+            return;
+        };
+        let cache = match GuidanceCache::new(&path) {
+            Ok(cache) => cache,
+            Err(e) => {
+                println!("\x1b[31mCannot open mentor cache: {e}\x1b[0m");
                 return;
             }
         };
 
-        let resolution_pct = (progress.resolution_rate * 100.0) as u32;
+        let result = match action {
+            "clear" => cache.clear().map(|removed| {
+                println!("\x1b[36m◆\x1b[0m Mentor cache cleared ({removed} entries removed)");
+            }),
+            "gc" => cache
+                .clean_old_entries(config.cache_retention_days)
+                .map(|removed| {
+                    println!(
+                        "\x1b[36m◆\x1b[0m Removed {removed} entries older than {} days",
+                        config.cache_retention_days
+                    );
+                }),
+            _ => cache.stats().map(|stats| {
+                println!("\x1b[36m◆\x1b[0m Mentor cache: {}", path.display());
+                print!(
+                    "{}",
+                    format_cache_stats(&stats, config.cache_retention_days)
+                );
+            }),
+        };
+        if let Err(e) = result {
+            println!("\x1b[31mMentor cache {action}: {e}\x1b[0m");
+        }
+    }
 
-        println!(
-            "\x1b[1;36m┌─ Your Learning Progress ─────────────────────────────────────┐\x1b[0m"
-        );
-        println!("\x1b[36m│\x1b[0m                                                               \x1b[36m│\x1b[0m");
-        println!(
-            "\x1b[36m│\x1b[0m  Total errors encountered: \x1b[1m{:<5}\x1b[0m                              \x1b[36m│\x1b[0m",
-            progress.total_errors
+    /// Show or switch the config profile (`profile [name]`)
+    ///
+    /// Switching rebuilds the AI manager from config.toml with the profile
+    /// applied, so `--model`/`--provider` overrides don't carry over.
+    fn handle_profile_builtin(&mut self, name: &str) {
+        if name.is_empty() {
+            let current = self.active_profile.as_deref().unwrap_or("none");
+            println!("\x1b[38;5;147m◆\x1b[0m Profile: \x1b[1m{current}\x1b[0m");
+            let config = KaidoConfig::load().unwrap_or_default();
+            let names = config.profile_names();
+            if names.is_empty() {
+                println!("  No profiles defined. Add a [profiles.<name>] table to config.toml.");
+            } else {
+                println!("  Available: {}", names.join(", "));
+                println!("  Use 'profile <name>' to switch.");
+            }
+            return;
+        }
+
+        let config = match KaidoConfig::load_with_profile(Some(name)) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("\x1b[31m{e:#}\x1b[0m");
+                return;
+            }
+        };
+
+        let provider = config.provider.clone();
+        self.output_budget = config.truncation.prompt;
+        self.active_profile = config.active_profile.clone();
+        self.ai_manager = Arc::new(AIManager::new(config));
+        // Earlier answers came from a different model
+        self.conversation.clear();
+        self.last_error_explained = false;
+        self.pending_explanation = None;
+        println!("\x1b[38;5;147m◆\x1b[0m Profile: \x1b[1m{name}\x1b[0m (provider: {provider:?})");
+    }
+
+    /// `kctx` lists kubectl contexts, `kctx <name>` switches to one
+    fn handle_kctx_builtin(&mut self, name: &str) {
+        if name.is_empty() {
+            let names = match kube_switch::context_names() {
+                Ok(names) => names,
+                Err(e) => {
+                    println!("\x1b[31m{e}\x1b[0m");
+                    return;
+                }
+            };
+            let current = KubectlContext::current().ok().map(|c| c.name);
+            println!("\x1b[36m◆\x1b[0m Contexts:");
+            for name in names {
+                if current.as_deref() == Some(name.as_str()) {
+                    println!("  \x1b[1m* {name}\x1b[0m");
+                } else {
+                    println!("    {name}");
+                }
+            }
+            println!("  Use 'kctx <name>' to switch.");
+            return;
+        }
+
+        if let Err(e) = kube_switch::use_context(name) {
+            println!("\x1b[31m{e}\x1b[0m");
+            return;
+        }
+        self.refresh_kube_banner();
+    }
+
+    /// `kns` shows the current namespace, `kns <namespace>` switches to one
+    fn handle_kns_builtin(&mut self, namespace: &str) {
+        let context = match KubectlContext::current() {
+            Ok(context) => context,
+            Err(e) => {
+                println!("\x1b[31m{e}\x1b[0m");
+                return;
+            }
+        };
+        if namespace.is_empty() {
+            println!(
+                "\x1b[36m◆\x1b[0m Namespace: \x1b[1m{}\x1b[0m (context {})",
+                context.effective_namespace(),
+                context.name
+            );
+            println!("  Use 'kns <namespace>' to switch.");
+            return;
+        }
+
+        match kube_switch::check_namespace(namespace) {
+            Ok(NamespaceCheck::Exists) => {}
+            Ok(NamespaceCheck::Missing) => {
+                println!(
+                    "\x1b[31mNamespace '{namespace}' not found in context '{}'. List them with: kubectl get namespaces\x1b[0m",
+                    context.name
+                );
+                return;
+            }
+            Ok(NamespaceCheck::Unverified(reason)) => {
+                println!(
+                    "\x1b[33m⚠ Couldn't verify namespace '{namespace}' exists: {reason}\x1b[0m"
+                );
+            }
+            Err(e) => {
+                println!("\x1b[31m{e}\x1b[0m");
+                return;
+            }
+        }
+
+        if let Err(e) = kube_switch::set_namespace(namespace) {
+            println!("\x1b[31m{e}\x1b[0m");
+            return;
+        }
+        self.refresh_kube_banner();
+    }
+
+    /// Reload the prompt's kubectl context and announce where commands now go
+    fn refresh_kube_banner(&mut self) {
+        self.prompt_builder.invalidate_kube_context();
+        self.prompt_builder.refresh_kube_context();
+
+        let context = match KubectlContext::current() {
+            Ok(context) => context,
+            Err(e) => {
+                println!("\x1b[31m{e}\x1b[0m");
+                return;
+            }
+        };
+        let (color, environment) = match context.environment_type {
+            EnvironmentType::Production => ("\x1b[1;31m", "production"),
+            EnvironmentType::Staging => ("\x1b[33m", "staging"),
+            EnvironmentType::Development => ("\x1b[32m", "development"),
+            EnvironmentType::Unknown => ("\x1b[2m", "unknown environment"),
+        };
+        println!(
+            "\x1b[36m◆\x1b[0m Context: \x1b[1m{}\x1b[0m  namespace: \x1b[1m{}\x1b[0m  {color}{environment}\x1b[0m",
+            context.name,
+            context.effective_namespace()
+        );
+        if context.environment_type == EnvironmentType::Production {
+            println!("\x1b[1;31m⚠ Commands now run against production\x1b[0m");
+        }
+    }
+
+    /// Ask before running a risky command, per the confirm mode
+    ///
+    /// Returns true if the command should run.
+    fn confirm_execution(&self, command: &str) -> bool {
+        if self.config.confirm_mode == ConfirmMode::Never {
+            return true;
+        }
+
+        let risk = confirm::assess(&self.tool_registry, command);
+
+        let confirmation = self
+            .config
+            .confirm_mode
+            .confirmation_for(risk.level, risk.is_production());
+        if confirmation != ConfirmationType::None {
+            if let Some(preview) = confirm::preview(command, &risk) {
+                confirm::show_preview(&preview);
+            }
+        }
+        confirm::prompt(command, &risk, confirmation)
+    }
+
+    /// Set mentor verbosity level
+    fn set_verbosity(&mut self, verbosity: Verbosity) {
+        self.config.mentor_verbosity = verbosity;
+        self.mentor_display = self.mentor_display_for(verbosity);
+    }
+
+    /// List the color themes, each with a sample in its colors
+    fn display_themes(&self) {
+        println!();
+        for theme in ColorTheme::ALL {
+            let colors = MentorColors::with_palette(
+                self.config.use_colors,
+                &self.config.palette.over(&theme.palette()),
+            );
+            let marker = if theme == self.config.color_theme {
+                "*"
+            } else {
+                " "
+            };
+            println!(
+                "  {marker} {:<16} {}error{} {}key message{} {}command{} {}search{} {}hint{}",
+                theme.name(),
+                colors.error_type(),
+                colors.reset(),
+                colors.key_message(),
+                colors.reset(),
+                colors.command(),
+                colors.reset(),
+                colors.search(),
+                colors.reset(),
+                colors.dim(),
+                colors.reset(),
+            );
+        }
+        println!();
+        println!(
+            "\x1b[2mSwitch with 'theme <name>'; keep it with 'kaido config set display.theme <name>'.\x1b[0m"
+        );
+        println!();
+    }
+
+    /// Switch the mentor color theme for this session
+    fn set_theme(&mut self, name: &str) {
+        let Some(theme) = ColorTheme::from_name(name) else {
+            let names: Vec<&str> = ColorTheme::ALL.iter().map(|t| t.name()).collect();
+            println!(
+                "\x1b[31mUnknown theme '{}'. Available: {}\x1b[0m",
+                name.trim(),
+                names.join(", ")
+            );
+            return;
+        };
+        self.config.color_theme = theme;
+        self.mentor_display = self.mentor_display_for(self.config.mentor_verbosity);
+        println!(
+            "\x1b[38;5;147m◆\x1b[0m Theme: \x1b[1m{}\x1b[0m",
+            theme.name()
+        );
+    }
+
+    /// Mentor display using the shell's color settings at a given verbosity
+    fn mentor_display_for(&self, verbosity: Verbosity) -> MentorDisplay {
+        MentorDisplay::with_config(crate::mentor::DisplayConfig {
+            verbosity,
+            terminal_width: 0,
+            colors_enabled: self.config.use_colors,
+            hyperlinks: self.config.use_colors && crate::mentor::LinkTarget::detect().is_some(),
+            theme: self.config.color_theme,
+            palette: self.config.palette.clone(),
+            language: self.config.language,
+            compact_threshold: self.config.mentor_compact_threshold(),
+        })
+        .with_terminal_size(self.signals.terminal_size().clone())
+    }
+
+    /// Pick up a resize that happened while the prompt was showing and size
+    /// the next command's PTY to match
+    fn sync_terminal_size(&mut self) {
+        let terminal_size = self.signals.terminal_size();
+        terminal_size.update();
+        if self.pty.update_size_from(terminal_size) {
+            log::debug!("PTY resized to {:?}", self.pty.get_size());
+        }
+    }
+
+    /// Display help message
+    fn display_help(&self) {
+        println!();
+        println!("\x1b[1;36mKaido Shell - Built-in Commands\x1b[0m");
+        println!();
+        println!("  \x1b[1mhelp\x1b[0m              Show this help message");
+        println!("  \x1b[1mhistory\x1b[0m           Show command history by session");
+        println!("  \x1b[1mhistory --session\x1b[0m Show this session's commands");
+        println!("  \x1b[1mhistory clear\x1b[0m     Forget all history");
+        println!("  \x1b[1mexplain --deep <cmd>\x1b[0m Annotate each flag and argument");
+        println!("  \x1b[1m? <request>\x1b[0m       Turn plain English into a command");
+        println!("  \x1b[1mtheme [name]\x1b[0m      List or switch mentor color themes");
+        println!("  \x1b[1mtools\x1b[0m             Show which tools are installed, with versions");
+        println!("  \x1b[1mclear\x1b[0m             Clear the screen");
+        println!("  \x1b[1mexit\x1b[0m              Exit the shell");
+        println!();
+        println!("\x1b[1;36mDirectory & Environment\x1b[0m");
+        println!();
+        println!("  \x1b[1mcd <dir>\x1b[0m          Change directory");
+        println!("  \x1b[1mcd -\x1b[0m              Go to previous directory");
+        println!("  \x1b[1mexport VAR=val\x1b[0m    Set environment variable");
+        println!("  \x1b[1munset VAR\x1b[0m         Remove environment variable");
+        println!("  \x1b[1mkctx [name]\x1b[0m       List or switch kubectl contexts");
+        println!("  \x1b[1mkns [namespace]\x1b[0m   Show or switch the kubectl namespace");
+        println!();
+        println!("\x1b[1;36mAliases\x1b[0m");
+        println!();
+        println!("  \x1b[1malias\x1b[0m             List all aliases");
+        println!("  \x1b[1malias k=kubectl\x1b[0m   Create an alias");
+        println!("  \x1b[1munalias k\x1b[0m         Remove an alias");
+        println!();
+        println!("\x1b[1;36mScripting\x1b[0m");
+        println!();
+        println!("  \x1b[1msource <file>\x1b[0m     Execute commands from file");
+        println!();
+        println!("\x1b[1;36mMentor Verbosity\x1b[0m");
+        println!();
+        println!("  \x1b[1mmentor\x1b[0m            Show current verbosity level");
+        println!("  \x1b[1mmentor auto\x1b[0m       Adapt to your skill level");
+        println!("  \x1b[1mverbose\x1b[0m           Full explanations with next steps");
+        println!("  \x1b[1mnormal\x1b[0m            Key points only (default)");
+        println!("  \x1b[1mcompact\x1b[0m           One-liner for experts");
+        println!("  \x1b[1mmentor debug\x1b[0m      Show which error pattern matched");
+        println!("  \x1b[1mmentor cache\x1b[0m      Show cache hit rate and size");
+        println!("  \x1b[1mmentor cache clear\x1b[0m Remove all cached guidance");
+        println!("  \x1b[1mmentor cache gc\x1b[0m   Remove entries past the retention period");
+        println!();
+        println!("\x1b[1;36mAfter an Error\x1b[0m");
+        println!();
+        println!("  \x1b[1mwhy\x1b[0m               Explain the root cause in more depth");
+        println!("  \x1b[1mfix\x1b[0m               Propose a fix and offer to run it");
+        println!("  \x1b[1mapply [n]\x1b[0m         Run listed fix n (default: the starred one)");
+        println!("  \x1b[1mmore\x1b[0m              Show the full mentor explanation");
+        println!();
+        println!("\x1b[1;36mSafety\x1b[0m");
+        println!();
+        println!("  \x1b[1mconfirm\x1b[0m           Show when risky commands ask first");
+        println!("  \x1b[1mconfirm smart\x1b[0m     Ask based on risk and environment (default)");
+        println!("  \x1b[1mconfirm always\x1b[0m    Ask for every Medium+ risk command");
+        println!("  \x1b[1mconfirm never\x1b[0m     Never ask (not recommended)");
+        println!();
+        println!("\x1b[1;36mLearning Progress\x1b[0m");
+        println!();
+        println!("  \x1b[1mprogress\x1b[0m          Show your learning progress");
+        println!("  \x1b[1mskill\x1b[0m             Show your skill assessment");
+        println!(
+            "  \x1b[1mdismiss\x1b[0m           Stop refreshers for the topic shown at startup"
+        );
+        println!();
+        println!("\x1b[1;38;5;147mAI Mode\x1b[0m");
+        println!();
+        println!("  \x1b[1mai\x1b[0m                Show AI status");
+        println!("  \x1b[1mprofile [name]\x1b[0m    Show or switch the config profile");
+        println!("  \x1b[1mai on\x1b[0m             Enable AI-powered explanations");
+        println!("  \x1b[1mai off\x1b[0m            Use pattern-based fallback");
+        println!("  \x1b[1mai suggestions on\x1b[0m Enable next-step suggestions");
+        println!("  \x1b[1mai suggestions off\x1b[0m Disable suggestions");
+        println!();
+        println!("\x1b[2mAll other commands are executed in the system shell.\x1b[0m");
+        println!("\x1b[2mWhen errors occur, AI will help you understand them.\x1b[0m");
+        println!();
+    }
+
+    /// Turn a plain-English request into a command and offer to run it
+    ///
+    /// The proposal can be edited first; whatever runs goes through the
+    /// same risk confirmation as a typed command.
+    async fn translate_request(&mut self, request: &str) -> Result<()> {
+        if request.is_empty() {
+            println!("Usage: ? <what you want to do>, e.g. ? restart nginx");
+            return Ok(());
+        }
+        if !self.config.ai_enabled || self.ai_manager.is_offline() {
+            println!("\x1b[31m?: AI is disabled, so requests can't be translated\x1b[0m");
+            return Ok(());
+        }
+
+        let context = ToolContext::detect();
+        let translation = match self
+            .ai_manager
+            .translate_input(request, &self.tool_registry, &context)
+            .await
+        {
+            Ok(translation) => translation,
+            Err(e) => {
+                println!("\x1b[33m?: {e}\x1b[0m");
+                return Ok(());
+            }
+        };
+        // The model's tool name is only a hint; the command decides
+        let (tool_name, risk) = self.tool_registry.classify(&translation.command, &context);
+
+        println!();
+        println!("  \x1b[1m{}\x1b[0m", translation.command);
+        println!(
+            "  \x1b[2m{tool_name} · risk {risk} · confidence {}%\x1b[0m",
+            translation.confidence
+        );
+        if !translation.reasoning.trim().is_empty() {
+            self.show_mentor(format!(
+                "\x1b[38;5;147m◆\x1b[0m {}\n",
+                translation.reasoning.trim()
+            ));
+        }
+        print!("  Run it? [y/e(dit)/N]: ");
+        use std::io::Write;
+        std::io::stdout().flush().ok();
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err() {
+            answer.clear();
+        }
+        let command = match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => translation.command,
+            "e" | "edit" => match self
+                .editor
+                .readline_with_initial("  edit> ", (&translation.command, ""))
+            {
+                Ok(edited) if !edited.trim().is_empty() => edited.trim().to_string(),
+                _ => {
+                    println!("\x1b[2mNot run.\x1b[0m");
+                    return Ok(());
+                }
+            },
+            _ => {
+                println!("\x1b[2mNot run.\x1b[0m");
+                return Ok(());
+            }
+        };
+
+        // History gets the command that ran, after the `?` line
+        if self
+            .editor
+            .add_history_entry(command.as_str())
+            .unwrap_or(false)
+        {
+            self.session_commands.push(command.clone());
+        }
+        self.execute_command(&command).await
+    }
+
+    /// Print a token-by-token breakdown of a command
+    async fn explain_deep(&self, command: &str) {
+        let llm: Option<&dyn LLMBackend> = (self.config.ai_enabled
+            && !self.ai_manager.is_offline())
+        .then_some(self.ai_manager.as_ref());
+        match CommandExplainer::explain_deep(command, llm).await {
+            Ok(deep) => {
+                println!();
+                print!("{}", deep.render());
+                println!();
+            }
+            Err(e) => println!("\x1b[31mexplain: {e}\x1b[0m"),
+        }
+    }
+
+    /// Display command history, one block per session
+    ///
+    /// With `session_only`, just the commands typed since the shell started.
+    fn display_history(&self, session_only: bool) {
+        let mut number = 0;
+        println!();
+        if !session_only {
+            let saved = read_history(&self.config.history.file_path).unwrap_or_default();
+            for line in saved {
+                match line {
+                    HistoryLine::Session(started) => {
+                        println!("\x1b[2m── session {} ──\x1b[0m", session_time(&started));
+                    }
+                    HistoryLine::Command(command) => {
+                        number += 1;
+                        println!("  {number:4}  {command}");
+                    }
+                }
+            }
+        }
+
+        let mut current: Vec<&String> = self.session_commands.iter().collect();
+        if self.config.history.ignore_dups {
+            current.dedup();
+        }
+        println!(
+            "\x1b[2m── this session, {} ──\x1b[0m",
+            self.session_started.format("%Y-%m-%d %H:%M")
+        );
+        for command in current {
+            number += 1;
+            println!("  {number:4}  {command}");
+        }
+        println!();
+    }
+
+    /// Forget all history, in memory and on disk
+    fn clear_history(&mut self) {
+        let _ = self.editor.clear_history();
+        self.session_commands.clear();
+        match clear_history(&self.config.history.file_path) {
+            Ok(()) => println!("\x1b[38;5;147m◆\x1b[0m History cleared"),
+            Err(e) => println!("\x1b[31mCould not clear history: {e}\x1b[0m"),
+        }
+    }
+
+    /// Display learning progress
+    fn display_progress(&self) {
+        println!();
+
+        let progress = match &self.learning_tracker {
+            Some(tracker) => match tracker.get_progress() {
+                Ok(p) => p,
+                Err(_) => {
+                    println!("\x1b[33mUnable to load learning progress.\x1b[0m");
+                    println!();
+                    return;
+                }
+            },
+            None => {
+                println!("\x1b[33mLearning tracker not available.\x1b[0m");
+                println!();
+                return;
+            }
+        };
+
+        let resolution_pct = (progress.resolution_rate * 100.0) as u32;
+
+        println!(
+            "\x1b[1;36m┌─ Your Learning Progress ─────────────────────────────────────┐\x1b[0m"
+        );
+        println!("\x1b[36m│\x1b[0m                                                               \x1b[36m│\x1b[0m");
+        println!(
+            "\x1b[36m│\x1b[0m  Total errors encountered: \x1b[1m{:<5}\x1b[0m                              \x1b[36m│\x1b[0m",
+            progress.total_errors
         );
         println!(
             "\x1b[36m│\x1b[0m  Resolution rate: \x1b[1m{resolution_pct}%\x1b[0m                                         \x1b[36m│\x1b[0m"
@@ -658,45 +1713,124 @@ impl KaidoShell {
 
     /// Execute a command via PTY (AI-native)
     async fn execute_command(&mut self, command: &str) -> Result<()> {
+        if !self.confirm_execution(command) {
+            println!("\x1b[2mCancelled.\x1b[0m");
+            return Ok(());
+        }
+        self.run_command(command).await
+    }
+
+    /// Follow the tracked error with a command that just ran
+    ///
+    /// Returns true when it resolved the error, which is then recorded
+    /// with the command credited for the fix.
+    fn check_resolution(&mut self, command: &str, succeeded: bool) -> bool {
+        let Some(mut tracked) = self.tracked_error.take() else {
+            return false;
+        };
+        match tracked.observe(command, succeeded) {
+            ResolutionCheck::Resolved(resolved_by) => {
+                let resolution_time = tracked.timestamp.elapsed();
+                if let Some(ref tracker) = self.learning_tracker {
+                    let _ = tracker.mark_resolved_by(tracked.id, resolution_time, &resolved_by);
+                }
+                self.session_stats.record_resolution();
+                true
+            }
+            ResolutionCheck::Pending => {
+                self.tracked_error = Some(tracked);
+                false
+            }
+            ResolutionCheck::Expired => false,
+        }
+    }
+
+    /// Track a newly recorded error for resolution detection
+    ///
+    /// A failed retry or fix attempt for the error already tracked keeps
+    /// the original, so its window isn't restarted by every miss.
+    fn track_error(&mut self, error_id: i64, command: &str) {
+        if self
+            .tracked_error
+            .as_ref()
+            .is_some_and(|tracked| tracked.relates_to(command))
+        {
+            return;
+        }
+        self.tracked_error = Some(TrackedError::new(error_id, command));
+    }
+
+    /// Run a command that already passed risk confirmation
+    async fn run_command(&mut self, command: &str) -> Result<()> {
         // Track command in session stats and history
         self.session_stats.record_command(command);
         self.add_to_command_history(command);
+        let started = self.recorder.as_ref().map(SessionRecorder::elapsed);
 
         let result = self
             .pty
             .execute(command)
             .await
             .context("Failed to execute command")?;
+        if started.is_some() {
+            self.record(
+                started,
+                SessionEvent::Command {
+                    result: result.clone(),
+                },
+            );
+        }
 
-        // Print the output
-        if !result.output.is_empty() {
-            print!("{}", result.output);
-            // Ensure output ends with newline
-            if !result.output.ends_with('\n') {
-                println!();
+        // Ctrl+C: show what it printed and move on, it isn't an error to explain
+        if result.interrupted {
+            let mut stdout = std::io::stdout();
+            if let Err(e) = signals::print_interrupted_output(&mut stdout, &result.output) {
+                log::debug!("Failed to print interrupted output: {e}");
             }
+            println!("\x1b[2m✗ interrupted\x1b[0m");
+            self.last_error = None;
+            self.last_solutions = None;
+            self.last_result = None;
+            return Ok(());
         }
 
-        // Check if previous error was resolved (successful similar command)
-        if result.exit_code == Some(0) {
-            if let Some(tracked) = self.tracked_error.take() {
-                if LearningTracker::is_similar_command(command, &tracked.command) {
-                    // Error was resolved!
-                    let resolution_time = tracked.timestamp.elapsed();
-                    if let Some(ref tracker) = self.learning_tracker {
-                        let _ = tracker.mark_resolved(tracked.id, resolution_time);
-                    }
-                    // Track resolution in session stats
-                    self.session_stats.record_resolution();
-
-                    // Celebrate with AI suggestion for next steps
-                    if self.config.ai_enabled && self.config.show_suggestions {
-                        self.display_success_suggestion(command).await;
-                    }
+        // Print the output (paged if enabled and too tall for the terminal)
+        if !result.output.is_empty() {
+            let output = self.highlighter.apply(&result.output);
+            if self.config.page_long_output {
+                // Keep going on pager failure so mentor guidance still renders
+                if let Err(e) = pager::print_paged(&output) {
+                    log::warn!("Paging failed: {e}");
+                }
+            } else {
+                print!("{output}");
+                // Ensure output ends with newline
+                if !output.ends_with('\n') {
+                    println!();
                 }
             }
         }
 
+        // Check if a recent error was resolved: the original command succeeds
+        // within a few commands, possibly after a different fixing command
+        if self.check_resolution(command, result.exit_code == Some(0))
+            && self.config.ai_enabled
+            && self.config.show_suggestions
+        {
+            // Celebrate with AI suggestion for next steps
+            self.display_success_suggestion(command).await;
+        }
+
+        // A successful reload or config test marks the web server config as known-good
+        if result.success() {
+            self.snapshot_web_server_config(command);
+        }
+
+        // Pick up a new kubectl context in the next prompt
+        if switches_kube_context(command) {
+            self.prompt_builder.invalidate_kube_context();
+        }
+
         // Analyze for errors using pattern matching (fast-path)
         if let Some(error_info) = self.error_detector.analyze(&result) {
             // Record error in learning tracker
@@ -708,37 +1842,164 @@ impl KaidoShell {
                     result.exit_code,
                     Some(&result.output),
                 ) {
-                    // Track this error for resolution detection
-                    self.tracked_error = Some(TrackedError {
-                        id: error_id,
-                        command: command.to_string(),
-                        timestamp: Instant::now(),
-                    });
+                    self.track_error(error_id, command);
                 }
             }
 
             // Track error in session stats
             self.session_stats
                 .record_error(error_info.error_type.name());
-
-            // Display AI-powered guidance (or fallback to pattern-based)
-            if self.config.ai_enabled {
-                self.display_ai_guidance(command, &result, &error_info)
-                    .await;
+            let occurrence = self.session_stats.record_occurrence(
+                error_info.fingerprint(),
+                &format!(
+                    "{}: {}",
+                    error_info.error_type.name(),
+                    error_info.key_message
+                ),
+            );
+
+            self.last_error_explained = false;
+            if occurrence > 1 && self.config.collapse_repeated_errors {
+                // Seen this session already; `more` still shows the full block
+                self.show_mentor(format!(
+                    "\x1b[38;5;147m◆\x1b[0m Same error again ({} time): {} \x1b[2m(`more` for details)\x1b[0m\n",
+                    ordinal(occurrence),
+                    error_info.key_message
+                ));
             } else {
+                // For nginx/apache config errors, show what changed since the last good reload
+                let config_diff = self.web_server_config_diff(command, &error_info);
+                // ...and for those and 5xx responses, what the server itself logged
+                let error_log = web_server_error_log(command, &error_info);
+                // Pattern guidance now, the AI explanation when it arrives
                 self.display_mentor_block(&error_info);
+                if occurrence > 1 {
+                    self.show_mentor(format!(
+                        "\x1b[2m({} time this session)\x1b[0m\n",
+                        ordinal(occurrence)
+                    ));
+                }
+                if let Some(ref diff) = config_diff {
+                    self.display_config_diff(diff);
+                }
+                if let Some(ref tail) = error_log {
+                    self.display_error_log(tail);
+                }
+                self.last_solutions =
+                    SolutionRunner::new(&self.tool_registry).explain(command, &result.output);
+                if let Some(ref explanation) = self.last_solutions {
+                    self.show_mentor(render_solutions(explanation));
+                }
+                if self.config.ai_enabled {
+                    self.start_ai_guidance(
+                        command,
+                        &result,
+                        &error_info,
+                        config_diff.as_deref(),
+                        error_log.as_ref(),
+                        plain_get_url(command),
+                    );
+                }
+            }
+
+            if self.config.feature_hints {
+                self.show_feature_hint(occurrence);
             }
 
             self.last_error = Some(error_info);
             self.last_result = Some(result);
         } else {
             self.last_error = None;
+            self.last_solutions = None;
             self.last_result = None;
         }
 
         Ok(())
     }
 
+    /// Suggest one builtin after a repeated error, if one is due
+    fn show_feature_hint(&mut self, occurrence: u32) {
+        let Some(ref tracker) = self.learning_tracker else {
+            return;
+        };
+        let Ok(progress) = tracker.get_progress() else {
+            return;
+        };
+        let level = self.skill_detector.assess(&progress).level;
+        if self.config.mentor_verbosity == Verbosity::Verbose {
+            self.hints.note_used(FeatureHint::Verbose);
+        }
+
+        let commands_run = self.session_stats.commands_executed;
+        let Some(hint) = self
+            .hints
+            .next_hint(level, occurrence, commands_run, |hint| {
+                // Unknown counts retire the hint rather than risk nagging
+                tracker
+                    .hint_shown_count(hint.key())
+                    .unwrap_or(MAX_TIMES_SHOWN)
+            })
+        else {
+            return;
+        };
+        if let Err(e) = tracker.mark_hint_shown(hint.key()) {
+            log::debug!("Could not record hint: {e}");
+        }
+        self.hints.mark_shown(commands_run);
+        self.show_mentor(format!(
+            "\x1b[38;5;147m◆\x1b[0m \x1b[2mTip: {}\x1b[0m\n",
+            hint.text()
+        ));
+    }
+
+    /// Run fix `number` (1-based) from the list shown after the last error,
+    /// or the recommended one
+    ///
+    /// The fix is rated at least at its own risk level, so a fix no tool
+    /// classifies still asks first when it's rated risky.
+    async fn apply_solution(&mut self, number: &str) -> Result<()> {
+        let Some(explanation) = self.last_solutions.clone() else {
+            println!(
+                "\x1b[2mNo fixes to apply. They're listed after errors Kaido recognizes.\x1b[0m"
+            );
+            return Ok(());
+        };
+        let number = if number.is_empty() {
+            None
+        } else {
+            match number.parse::<usize>() {
+                Ok(n) => Some(n),
+                Err(_) => {
+                    println!("\x1b[31mUsage: apply [number]\x1b[0m");
+                    return Ok(());
+                }
+            }
+        };
+        let solution = match SolutionRunner::choose(&explanation, number) {
+            Ok(solution) => solution,
+            Err(e) => {
+                println!("\x1b[31m{e}\x1b[0m");
+                return Ok(());
+            }
+        };
+        let Some(command) = runnable_command(solution).map(str::to_string) else {
+            return Ok(());
+        };
+
+        println!("\x1b[38;5;150m◆ Applying:\x1b[0m {}", solution.description);
+        let risk = confirm::assess_at_least(&self.tool_registry, &command, solution.risk_level);
+        let confirmation = self
+            .config
+            .confirm_mode
+            .confirmation_for(risk.level, risk.is_production());
+        if !confirm::prompt(&command, &risk, confirmation) {
+            println!("\x1b[2mCancelled.\x1b[0m");
+            return Ok(());
+        }
+        println!("\x1b[2m$ {command}\x1b[0m");
+        self.run_command(&command).await
+    }
+
     /// Add command to history for AI context
     fn add_to_command_history(&mut self, command: &str) {
         self.command_history.push(command.to_string());
@@ -748,52 +2009,249 @@ impl KaidoShell {
         }
     }
 
-    /// Display AI-powered guidance for errors
-    async fn display_ai_guidance(
-        &self,
+    /// Request an AI explanation of an error in the background
+    ///
+    /// Replaces (and cancels) any explanation still pending for an earlier
+    /// error. A failed bare GET to `retime_url` is timed phase by phase
+    /// first, in the same background task, and the breakdown heads the
+    /// answer.
+    fn start_ai_guidance(
+        &mut self,
         command: &str,
         result: &PtyExecutionResult,
         error_info: &ErrorInfo,
+        config_diff: Option<&str>,
+        error_log: Option<&ErrorLogTail>,
+        retime_url: Option<String>,
     ) {
         // Build context for AI
-        let prompt = self.build_error_explanation_prompt(command, result, error_info);
+        let mut prompt = self.build_error_explanation_prompt(command, result, error_info);
+        if let Some(diff) = config_diff {
+            let diff_preview: String = diff.chars().take(MAX_PROMPT_DIFF_CHARS).collect();
+            prompt.push_str(&format!(
+                "\n\nCONFIG CHANGES SINCE THE LAST SUCCESSFUL RELOAD (likely cause):\n{diff_preview}"
+            ));
+        }
+        if let Some(ErrorLogTail::Entries { path, lines }) = error_log {
+            prompt.push_str(&format!(
+                "\n\nLAST ENTRIES OF {} (the real cause is often here):\n{lines}",
+                path.display()
+            ));
+        }
+        let prompt = self.redact_prompt(prompt);
+
+        // Earlier explanations go along as context
+        let mut messages = self.conversation.messages().to_vec();
+        messages.push(ChatMessage::user(prompt.clone()));
+        let ai_manager = Arc::clone(&self.ai_manager);
+        self.pending_explanation = Some(PendingExplanation::spawn(
+            prompt,
+            async move {
+                let Some(timing) = failed_request_timing(retime_url).await else {
+                    return ai_manager.infer_with_context(&messages).await;
+                };
+                if let Some(question) = messages.last_mut() {
+                    question.content.push_str(&format!(
+                        "\n\nHTTP TIMING OF THE SAME URL (re-measured):\n{}",
+                        timing.report()
+                    ));
+                }
+                let mut response = ai_manager.infer_with_context(&messages).await?;
+                response.reasoning = format!("{}\n{}", timing.report(), response.reasoning);
+                Ok(response)
+            },
+            self.prompt_printer.clone(),
+            render_mentor_box,
+        ));
+        println!("\x1b[2m◆ AI explanation on its way, keep typing\x1b[0m");
+    }
 
-        // Show thinking indicator
+    /// Show a finished background explanation and add it to the conversation
+    ///
+    /// With `wait`, waits for one still in flight (a follow-up about the
+    /// same error needs it); otherwise only a completed one is taken.
+    async fn collect_explanation(&mut self, wait: bool) {
+        if !self
+            .pending_explanation
+            .as_ref()
+            .is_some_and(|pending| wait || pending.is_finished())
+        {
+            return;
+        }
+        let Some(pending) = self.pending_explanation.take() else {
+            return;
+        };
+
+        let waiting = !pending.is_finished();
+        if waiting {
+            print!("\x1b[38;5;147m◆ AI analyzing...\x1b[0m ");
+            use std::io::Write;
+            std::io::stdout().flush().ok();
+        }
+        let finished = pending.finish().await;
+        if waiting {
+            print!("\r\x1b[K");
+        }
+
+        let response = match finished.response {
+            Ok(response) => response,
+            Err(e) => {
+                // The pattern guidance is already on screen
+                log::debug!("AI explanation failed: {e}");
+                return;
+            }
+        };
+        self.session_stats
+            .record_tokens(response.prompt_tokens, response.completion_tokens);
+        self.conversation.push_user(finished.question);
+        self.conversation.push_assistant(response.reasoning.clone());
+        self.last_error_explained = true;
+
+        let text = render_mentor_box(&response.reasoning);
+        if finished.shown {
+            self.record(None, SessionEvent::Mentor { text });
+        } else {
+            self.show_mentor(text);
+        }
+        if self.mentor_display.debug_enabled() {
+            if let Some(ref error_info) = self.last_error {
+                println!("{}", self.mentor_display.render_debug(error_info));
+            }
+        }
+    }
+
+    /// Send a question along with the conversation so far
+    ///
+    /// Shows a thinking indicator while waiting. The question and reply are
+    /// kept in the conversation only if the request succeeds.
+    async fn ask_with_context(&mut self, question: String) -> Result<LLMResponse> {
         print!("\x1b[38;5;147m◆ AI analyzing...\x1b[0m ");
         use std::io::Write;
         std::io::stdout().flush().ok();
 
-        // Call AI for explanation
-        match self.ai_manager.infer(&prompt).await {
+        self.conversation.push_user(question);
+        let result = self
+            .ai_manager
+            .infer_with_context(self.conversation.messages())
+            .await;
+
+        // Clear the "analyzing" line
+        print!("\r\x1b[K");
+
+        match result {
             Ok(response) => {
-                // Clear the "analyzing" line
-                print!("\r\x1b[K");
+                self.session_stats
+                    .record_tokens(response.prompt_tokens, response.completion_tokens);
+                self.conversation.push_assistant(response.reasoning.clone());
+                Ok(response)
+            }
+            Err(e) => {
+                self.conversation.pop();
+                Err(e)
+            }
+        }
+    }
 
-                // Display AI explanation
-                println!();
-                println!("\x1b[38;5;147m┌─ AI MENTOR ────────────────────────────────────────────────┐\x1b[0m");
-                println!("\x1b[38;5;147m│\x1b[0m                                                              \x1b[38;5;147m│\x1b[0m");
+    /// Run `why`, `fix` or `more` against the last error
+    async fn handle_follow_up(&mut self, follow_up: FollowUp) -> Result<()> {
+        let (Some(error_info), Some(result)) = (self.last_error.clone(), self.last_result.clone())
+        else {
+            println!("\x1b[2mNo recent error to follow up on.\x1b[0m");
+            return Ok(());
+        };
 
-                // Format and display the explanation (wrap lines)
-                for line in response.reasoning.lines().take(12) {
-                    let truncated = if line.len() > 58 {
-                        format!("{}...", &line[..55])
-                    } else {
-                        line.to_string()
-                    };
-                    println!("\x1b[38;5;147m│\x1b[0m  {truncated:<56}  \x1b[38;5;147m│\x1b[0m");
-                }
+        if follow_up == FollowUp::More {
+            let output = self
+                .mentor_display_for(Verbosity::Verbose)
+                .render(&error_info);
+            self.show_mentor(output);
+            return Ok(());
+        }
 
-                println!("\x1b[38;5;147m│\x1b[0m                                                              \x1b[38;5;147m│\x1b[0m");
-                println!("\x1b[38;5;147m└──────────────────────────────────────────────────────────────┘\x1b[0m");
-                println!();
-            }
+        if !self.config.ai_enabled {
+            println!("\x1b[2mAI is off. Use 'more' for the full explanation or 'ai on'.\x1b[0m");
+            return Ok(());
+        }
+        // The follow-up builds on the explanation of the same error
+        self.collect_explanation(true).await;
+
+        let mut question = match follow_up {
+            FollowUp::Why => "Why did this happen? Go one level deeper into the root cause: \
+                what the tool was trying to do, which assumption failed, and how to \
+                confirm it. Keep it under 12 lines. Plain text only, no markdown."
+                .to_string(),
+            _ => "Propose the single most likely fix as ONE shell command in a ```bash \
+                code block, then one sentence on what it does. If no command can fix it, \
+                say so and do not include a code block."
+                .to_string(),
+        };
+        // The error isn't in the conversation if AI guidance failed or was off
+        if !self.last_error_explained {
+            let context =
+                self.build_error_explanation_prompt(&error_info.command, &result, &error_info);
+            question = format!("{context}\n\n{question}");
+        }
+        let question = self.redact_prompt(question);
+
+        let response = match self.ask_with_context(question).await {
+            Ok(response) => response,
             Err(e) => {
-                // Clear the "analyzing" line and fallback to pattern-based
-                print!("\r\x1b[K");
-                log::debug!("AI explanation failed, using fallback: {e}");
-                self.display_mentor_block(error_info);
+                println!("\x1b[31mAI request failed: {e}\x1b[0m");
+                return Ok(());
             }
+        };
+        self.last_error_explained = true;
+
+        if follow_up == FollowUp::Why {
+            self.show_mentor(render_mentor_box(&response.reasoning));
+            return Ok(());
+        }
+
+        let fix = response
+            .command
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty());
+        let Some(fix) = fix.map(str::to_string) else {
+            self.show_mentor(render_mentor_box(&response.reasoning));
+            return Ok(());
+        };
+
+        let explanation: String = response
+            .reasoning
+            .lines()
+            .skip_while(|l| !l.trim_start().starts_with("```"))
+            .skip(1)
+            .skip_while(|l| !l.trim_start().starts_with("```"))
+            .skip(1)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !explanation.trim().is_empty() {
+            self.show_mentor(render_mentor_box(explanation.trim()));
+        }
+        println!("\x1b[38;5;150m◆ Suggested fix:\x1b[0m \x1b[1m{fix}\x1b[0m");
+        print!("  Run it? [y/N]: ");
+        use std::io::Write;
+        std::io::stdout().flush().ok();
+
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err()
+            || !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        {
+            println!("\x1b[2mNot run.\x1b[0m");
+            return Ok(());
+        }
+
+        // Goes through the same risk confirmation as a typed command
+        self.execute_command(&fix).await
+    }
+
+    /// Mask secrets in a prompt that may go to a cloud provider
+    fn redact_prompt(&self, prompt: String) -> String {
+        if self.config.redact_secrets && self.ai_manager.may_use_cloud() {
+            redact_secrets(&prompt).into_owned()
+        } else {
+            prompt
         }
     }
 
@@ -814,11 +2272,7 @@ impl KaidoShell {
             .collect::<Vec<_>>()
             .join("\n  ");
 
-        let output_preview = if result.output.len() > 500 {
-            format!("{}...(truncated)", &result.output[..500])
-        } else {
-            result.output.clone()
-        };
+        let output_preview = self.output_budget.truncate(&result.output);
 
         format!(
             r#"You are an AI ops mentor helping a user understand a command error.
@@ -840,7 +2294,7 @@ Explain this error in a helpful, educational way:
 4. Pro tip (something to remember for next time)
 
 Keep your response concise (under 10 lines). Be friendly and encouraging.
-Do NOT use markdown formatting. Use plain text only."#,
+Do NOT use markdown formatting. Use plain text only.{language}"#,
             command = command,
             exit_code = result
                 .exit_code
@@ -849,11 +2303,12 @@ Do NOT use markdown formatting. Use plain text only."#,
             error_type = error_info.error_type.name(),
             output = output_preview,
             recent_commands = recent_commands,
+            language = self.config.language.prompt_instruction(),
         )
     }
 
     /// Display success suggestion after resolving an error
-    async fn display_success_suggestion(&self, command: &str) {
+    async fn display_success_suggestion(&mut self, command: &str) {
         let prompt = format!(
             r#"The user just successfully ran: {command}
 
@@ -861,8 +2316,11 @@ This resolved a previous error. Suggest ONE helpful next step they might want to
 Keep it to a single short sentence. Be encouraging.
 Do NOT use markdown. Plain text only."#
         );
+        let prompt = self.redact_prompt(prompt);
 
         if let Ok(response) = self.ai_manager.infer(&prompt).await {
+            self.session_stats
+                .record_tokens(response.prompt_tokens, response.completion_tokens);
             let suggestion = response.reasoning.lines().next().unwrap_or("");
             if !suggestion.is_empty() {
                 println!("\x1b[38;5;150m✓ Nice! {}\x1b[0m", suggestion.trim());
@@ -872,17 +2330,86 @@ Do NOT use markdown. Plain text only."#
     }
 
     /// Display mentor guidance for detected errors (fallback, pattern-based)
-    fn display_mentor_block(&self, error: &ErrorInfo) {
+    fn display_mentor_block(&mut self, error: &ErrorInfo) {
         let output = self.mentor_display.render(error);
-        print!("{output}");
+        self.show_mentor(output);
+    }
+
+    /// Snapshot nginx/apache config after a successful reload or config test
+    fn snapshot_web_server_config(&self, command: &str) {
+        if !config_snapshot::is_reload_command(command) {
+            return;
+        }
+
+        if let Some(snapshot) = WebServer::from_command(command).and_then(|s| s.snapshot()) {
+            match snapshot.take() {
+                Ok(files) => log::debug!(
+                    "Snapshotted {files} config files to {}",
+                    snapshot.snapshot_dir().display()
+                ),
+                Err(e) => log::warn!("Failed to snapshot web server config: {e}"),
+            }
+        }
     }
 
-    /// Save history to file
+    /// Diff of the nginx/apache config since the last good reload, for config errors
+    fn web_server_config_diff(&self, command: &str, error: &ErrorInfo) -> Option<String> {
+        if error.error_type != ErrorType::ConfigurationError {
+            return None;
+        }
+
+        let diff = WebServer::from_command(command)?.snapshot()?.diff().ok()?;
+        (!diff.is_empty()).then_some(diff)
+    }
+
+    /// Show the error log's last entries, or how to read it when it needs root
+    fn display_error_log(&mut self, tail: &ErrorLogTail) {
+        match tail {
+            ErrorLogTail::Entries { path, lines } => {
+                let mut text = format!(
+                    "\x1b[38;5;147m◆ Last entries in {}:\x1b[0m\n",
+                    path.display()
+                );
+                for line in lines.lines() {
+                    text.push_str(&format!("  \x1b[2m{line}\x1b[0m\n"));
+                }
+                text.push('\n');
+                self.show_mentor(text);
+            }
+            ErrorLogTail::PermissionDenied { path } => {
+                let path = path.display();
+                self.show_mentor(format!(
+                    "\x1b[38;5;147m◆\x1b[0m {path} needs root to read; the cause is usually there: \x1b[1msudo tail -n {ERROR_LOG_LINES} {path}\x1b[0m\n\n"
+                ));
+            }
+        }
+    }
+
+    /// Display a config diff, colored like `git diff`
+    fn display_config_diff(&self, diff: &str) {
+        println!("\x1b[38;5;147m◆ Changed since the last successful reload:\x1b[0m");
+
+        let lines: Vec<&str> = diff.lines().collect();
+        for line in lines.iter().take(MAX_DISPLAY_DIFF_LINES) {
+            println!("  {}{line}\x1b[0m", pager::diff_line_color(line));
+        }
+
+        if lines.len() > MAX_DISPLAY_DIFF_LINES {
+            println!(
+                "  \x1b[2m... {} more lines\x1b[0m",
+                lines.len() - MAX_DISPLAY_DIFF_LINES
+            );
+        }
+        println!();
+    }
+
+    /// Add this session's commands to the history file
     fn save_history(&mut self) -> Result<()> {
-        self.editor
-            .save_history(&self.config.history.file_path)
-            .context("Failed to save history")?;
-        Ok(())
+        append_session(
+            &self.config.history,
+            self.session_started,
+            &self.session_commands,
+        )
     }
 
     /// Get the last execution result
@@ -912,10 +2439,249 @@ impl Default for KaidoShell {
     }
 }
 
+/// Startup banner: the `banner_path` file, or the built-in ASCII art
+///
+/// None when banners are off. An unreadable file falls back to the
+/// built-in banner with a note.
+fn banner(config: &ShellConfig) -> Option<String> {
+    if !config.show_banner {
+        return None;
+    }
+    let mut output = String::new();
+    if let Some(ref path) = config.banner_path {
+        match std::fs::read_to_string(path) {
+            Ok(motd) => {
+                output.push_str(&motd);
+                if !motd.ends_with('\n') {
+                    output.push('\n');
+                }
+                return Some(output);
+            }
+            Err(e) => output.push_str(&format!(
+                "\x1b[2mCannot read banner {}: {e}\x1b[0m\n",
+                path.display()
+            )),
+        }
+    }
+    output.push_str(concat!(
+        "\n",
+        "\x1b[1;36m  _  __     _     _       \x1b[0m\n",
+        "\x1b[1;36m | |/ /__ _(_) __| | ___  \x1b[0m\n",
+        "\x1b[1;36m | ' // _` | |/ _` |/ _ \\ \x1b[0m\n",
+        "\x1b[1;36m | . \\ (_| | | (_| | (_) |\x1b[0m\n",
+        "\x1b[1;36m |_|\\_\\__,_|_|\\__,_|\\___/ \x1b[0m\n",
+        "\n",
+        "\x1b[1mAI-Native Shell\x1b[0m - Your intelligent ops companion.\n",
+        "\n",
+    ));
+    Some(output)
+}
+
+/// Refresher card: what the concept means and something to practice
+fn render_refresher(weak: &WeakConcept, language: Language) -> String {
+    let error_type = ErrorType::from_name(&weak.concept).unwrap_or(ErrorType::Unknown);
+    let mut card = format!(
+        "\x1b[38;5;147m◆ {} {}\x1b[0m \x1b[2m({}× · {:.0}% {})\x1b[0m\n",
+        language.label(Label::Refresher),
+        weak.concept,
+        weak.encounters,
+        weak.recent_resolution_rate * 100.0,
+        language.phrase(Phrase::RecentlyResolved)
+    );
+    let meaning = language
+        .explanation(&error_type)
+        .or_else(|| language.concepts(&error_type));
+    if let Some(meaning) = meaning {
+        card.push_str(&format!(
+            "  {} {meaning}\n",
+            language.label(Label::ThisMeans)
+        ));
+    }
+    card.push_str(&format!(
+        "  {} {}\n",
+        language.label(Label::Practice),
+        language.practice(&error_type)
+    ));
+    card.push_str(&format!(
+        "  \x1b[2mdismiss ({})\x1b[0m\n\n",
+        language.phrase(Phrase::DismissRefresher)
+    ));
+    card
+}
+
+/// Render an AI reply in the mentor box (first 12 lines)
+fn render_mentor_box(text: &str) -> String {
+    let mut output = String::from("\n");
+    output.push_str(
+        "\x1b[38;5;147m┌─ AI MENTOR ──────────────────────────────────────────────────┐\x1b[0m\n",
+    );
+    output.push_str("\x1b[38;5;147m│\x1b[0m                                                              \x1b[38;5;147m│\x1b[0m\n");
+
+    // Format and display the explanation, cut and padded by display width
+    // so CJK replies keep the border aligned
+    for line in text.lines().take(12) {
+        let truncated = MentorDisplay::truncate(line, MENTOR_BOX_TEXT_WIDTH);
+        let padding = " ".repeat(MENTOR_BOX_TEXT_WIDTH.saturating_sub(truncated.width()));
+        output.push_str(&format!(
+            "\x1b[38;5;147m│\x1b[0m  {truncated}{padding}  \x1b[38;5;147m│\x1b[0m\n"
+        ));
+    }
+
+    output.push_str("\x1b[38;5;147m│\x1b[0m                                                              \x1b[38;5;147m│\x1b[0m\n");
+    output.push_str(
+        "\x1b[38;5;147m└──────────────────────────────────────────────────────────────┘\x1b[0m\n\n",
+    );
+    output
+}
+
+/// Body of `mentor cache stats`
+fn format_cache_stats(stats: &CacheStats, retention_days: u32) -> String {
+    let size = if stats.bytes >= 1024 * 1024 {
+        format!("{:.1} MB", stats.bytes as f64 / (1024.0 * 1024.0))
+    } else if stats.bytes >= 1024 {
+        format!("{:.1} KB", stats.bytes as f64 / 1024.0)
+    } else {
+        format!("{} bytes", stats.bytes)
+    };
+    let hit_rate = match stats.hit_rate() {
+        Some(rate) => format!("{:.0}% hit rate", rate * 100.0),
+        None => "no lookups yet".to_string(),
+    };
+
+    format!(
+        "  Entries:   {} ({size})\n  \
+         Lookups:   {} hits, {} misses ({hit_rate})\n  \
+         Retention: {retention_days} days ('mentor cache gc' prunes older entries)\n",
+        stats.total_entries, stats.hits, stats.misses
+    )
+}
+
+/// Render a session marker's RFC 3339 timestamp as local date and time
+fn session_time(started: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(started)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| started.to_string())
+}
+
+/// Web servers whose error log may explain this error, most likely first
+///
+/// Config errors point at the server the command named. A 5xx from curl
+/// doesn't say which server answered, so both are tried.
+fn error_log_servers(command: &str, error: &ErrorInfo) -> Vec<WebServer> {
+    let server = WebServer::from_command(command);
+    match error.error_type {
+        ErrorType::ConfigurationError => server.into_iter().collect(),
+        ErrorType::HttpError if error.key_message.starts_with('5') => match server {
+            Some(server) => vec![server],
+            None => vec![WebServer::Nginx, WebServer::Apache],
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// Tail of the first error log found among the servers behind this error
+fn web_server_error_log(command: &str, error: &ErrorInfo) -> Option<ErrorLogTail> {
+    error_log_servers(command, error)
+        .iter()
+        .find_map(|server| server.tail_error_log(ERROR_LOG_LINES).ok())
+}
+
+/// Phase timing of a failed bare GET, re-requested with nothing but the URL
+///
+/// None without a URL and when the URL answers fine on a second try.
+async fn failed_request_timing(url: Option<String>) -> Option<HttpTiming> {
+    let url = url?;
+    match NetworkTool::http_timing(&url).await {
+        Ok(timing) if timing.diagnosis().is_some() => Some(timing),
+        Ok(_) => None,
+        Err(e) => {
+            log::debug!("Could not time {url}: {e}");
+            None
+        }
+    }
+}
+
+/// "2nd", "3rd", "11th", ... for repeat counts
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_log_servers() {
+        let error = |error_type, key: &str| ErrorInfo::new(error_type, 1, key, "");
+
+        assert_eq!(
+            error_log_servers(
+                "sudo nginx -t",
+                &error(ErrorType::ConfigurationError, "unknown directive")
+            ),
+            vec![WebServer::Nginx]
+        );
+        assert_eq!(
+            error_log_servers(
+                "curl -f http://localhost/",
+                &error(ErrorType::HttpError, "502")
+            ),
+            vec![WebServer::Nginx, WebServer::Apache]
+        );
+        // 429 is the client's problem, not the server's
+        assert!(error_log_servers(
+            "curl -f http://localhost/",
+            &error(ErrorType::HttpError, "429")
+        )
+        .is_empty());
+        assert!(error_log_servers(
+            "vim app.yaml",
+            &error(ErrorType::ConfigurationError, "bad key")
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_mentor_box_fits_long_cjk_lines() {
+        let text = format!(
+            "{}\nshort line\n{}",
+            "容器一直崩潰重新啟動".repeat(8),
+            "x".repeat(80)
+        );
+        let rendered = render_mentor_box(&text);
+        let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+
+        let widths: Vec<usize> = rendered
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| ansi.replace_all(line, "").width())
+            .collect();
+        assert_eq!(widths.len(), 7);
+        assert!(widths.iter().all(|&w| w == widths[0]), "{widths:?}");
+        assert!(rendered.contains("容器一直崩潰"));
+        assert!(rendered.contains("..."));
+    }
+
+    #[test]
+    fn test_ordinal() {
+        let words: Vec<_> = [2, 3, 4, 11, 12, 13, 21, 22, 101].map(ordinal).into();
+        assert_eq!(
+            words,
+            ["2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "101st"]
+        );
+    }
+
     #[test]
     fn test_shell_config_default() {
         let config = ShellConfig::default();
@@ -924,6 +2690,58 @@ mod tests {
         assert!(config.shell.is_none());
     }
 
+    #[test]
+    fn test_banner() {
+        let default = banner(&ShellConfig::default()).unwrap();
+        assert!(default.contains("AI-Native Shell"));
+
+        let off = ShellConfig {
+            show_banner: false,
+            ..ShellConfig::default()
+        };
+        assert_eq!(banner(&off), None);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let motd = dir.path().join("motd");
+        std::fs::write(&motd, "prod-db-1: be careful").unwrap();
+        let custom = ShellConfig {
+            banner_path: Some(motd),
+            ..ShellConfig::default()
+        };
+        assert_eq!(banner(&custom).unwrap(), "prod-db-1: be careful\n");
+
+        let missing = ShellConfig {
+            banner_path: Some(dir.path().join("nope")),
+            ..ShellConfig::default()
+        };
+        let fallback = banner(&missing).unwrap();
+        assert!(fallback.starts_with("\x1b[2mCannot read banner"));
+        assert!(fallback.contains("AI-Native Shell"));
+    }
+
+    #[test]
+    fn test_render_refresher() {
+        let weak = WeakConcept {
+            concept: "Permission Denied".to_string(),
+            encounters: 6,
+            recent_resolution_rate: 0.2,
+            reviews: 0,
+        };
+        let card = render_refresher(&weak, Language::English);
+        assert!(
+            card.contains("Concept refresher: Permission Denied"),
+            "{card}"
+        );
+        assert!(card.contains("(6× · 20% of recent ones resolved)"));
+        assert!(card.contains("This means: You don't have permission"));
+        assert!(card.contains("Practice: Run `ls -l`"));
+        assert!(card.contains("dismiss (stop refreshers for this topic)"));
+
+        let card = render_refresher(&weak, Language::TraditionalChinese);
+        assert!(card.contains("觀念複習： Permission Denied"), "{card}");
+        assert!(card.contains("20% 近期已解決"));
+    }
+
     #[test]
     fn test_shell_creation() {
         let shell = KaidoShell::new();
@@ -947,6 +2765,145 @@ mod tests {
         assert!(shell.handle_builtin("help"));
     }
 
+    #[test]
+    fn test_handle_builtin_confirm() {
+        let mut shell = KaidoShell::new().unwrap();
+        assert_eq!(shell.config.confirm_mode, ConfirmMode::Smart);
+
+        assert!(shell.handle_builtin("confirm always"));
+        assert_eq!(shell.config.confirm_mode, ConfirmMode::Always);
+
+        assert!(shell.handle_builtin("confirm never"));
+        assert_eq!(shell.config.confirm_mode, ConfirmMode::Never);
+        assert!(shell.confirm_never_warned);
+
+        // Unknown modes leave the setting alone
+        assert!(shell.handle_builtin("confirm bogus"));
+        assert_eq!(shell.config.confirm_mode, ConfirmMode::Never);
+        assert!(shell.handle_builtin("confirm"));
+        assert!(!shell.handle_builtin("confirmation-tool --check"));
+    }
+
+    #[test]
+    fn test_format_cache_stats() {
+        let stats = CacheStats {
+            total_entries: 12,
+            total_hits: 42,
+            hits: 30,
+            misses: 10,
+            bytes: 35_328,
+        };
+        let rendered = format_cache_stats(&stats, 30);
+        assert!(rendered.contains("Entries:   12 (34.5 KB)"));
+        assert!(rendered.contains("30 hits, 10 misses (75% hit rate)"));
+        assert!(rendered.contains("Retention: 30 days"));
+
+        let mut shell = KaidoShell::new().unwrap();
+        assert!(shell.handle_builtin("mentor cache bogus"));
+    }
+
+    #[test]
+    fn test_handle_builtin_profile() {
+        let mut shell = KaidoShell::new().unwrap();
+        let profile = shell.active_profile.clone();
+
+        assert!(shell.handle_builtin("profile"));
+        // Unknown profiles leave the AI settings alone
+        assert!(shell.handle_builtin("profile no-such-profile"));
+        assert_eq!(shell.active_profile, profile);
+        assert!(!shell.handle_builtin("profiler --top"));
+    }
+
+    #[test]
+    fn test_tracked_error_resolved_by_retry() {
+        let mut tracked = TrackedError::new(1, "kubectl get pods");
+        assert_eq!(
+            tracked.observe("kubectl get pods -n prod", true),
+            ResolutionCheck::Resolved("kubectl get pods -n prod".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tracked_error_resolved_by_different_command() {
+        let mut tracked = TrackedError::new(1, "jq .name package.json");
+        assert_eq!(
+            tracked.observe("brew install jq", true),
+            ResolutionCheck::Pending
+        );
+        assert_eq!(tracked.observe("ls", false), ResolutionCheck::Pending);
+        assert_eq!(tracked.observe("ls", true), ResolutionCheck::Pending);
+        assert_eq!(
+            tracked.observe("jq .name package.json", true),
+            ResolutionCheck::Resolved("brew install jq".to_string())
+        );
+    }
+
+    #[test]
+    fn test_failed_retries_keep_the_tracked_error() {
+        let mut shell = KaidoShell::new().unwrap();
+        shell.learning_tracker = Some(LearningTracker::in_memory().unwrap());
+        let command = "jq .name package.json";
+        let error_id = shell
+            .learning_tracker
+            .as_ref()
+            .unwrap()
+            .record_error(
+                &ErrorType::CommandNotFound,
+                "jq: not found",
+                command,
+                Some(127),
+                None,
+            )
+            .unwrap();
+        shell.track_error(error_id, command);
+
+        // Failed fix attempts and retries don't replace the original error
+        assert!(!shell.check_resolution("brew install jq", false));
+        shell.track_error(error_id + 1, "brew install jq");
+        assert!(!shell.check_resolution(command, false));
+        shell.track_error(error_id + 2, command);
+        assert_eq!(shell.tracked_error.as_ref().unwrap().id, error_id);
+
+        // An unrelated success isn't credited with the fix
+        assert!(!shell.check_resolution("ls", true));
+        assert!(shell.check_resolution(command, true));
+        assert!(shell.tracked_error.is_none());
+
+        let encounter = shell
+            .learning_tracker
+            .as_ref()
+            .unwrap()
+            .get_last_error()
+            .unwrap()
+            .unwrap();
+        assert!(encounter.resolved);
+        assert_eq!(encounter.resolution_command.as_deref(), Some(command));
+
+        // An unrelated new error starts tracking afresh
+        shell.track_error(error_id, command);
+        shell.track_error(error_id + 3, "terraform apply");
+        assert_eq!(shell.tracked_error.as_ref().unwrap().id, error_id + 3);
+    }
+
+    #[test]
+    fn test_tracked_error_expires_after_window() {
+        let mut tracked = TrackedError::new(1, "terraform apply");
+        for _ in 0..RESOLUTION_WINDOW - 1 {
+            assert_eq!(tracked.observe("ls", true), ResolutionCheck::Pending);
+        }
+        assert_eq!(tracked.observe("ls", true), ResolutionCheck::Expired);
+    }
+
+    #[test]
+    fn test_follow_up_parse() {
+        assert_eq!(FollowUp::parse("why"), Some(FollowUp::Why));
+        assert_eq!(FollowUp::parse("fix"), Some(FollowUp::Fix));
+        assert_eq!(FollowUp::parse("more"), Some(FollowUp::More));
+        // `more <file>` is still the pager
+        assert_eq!(FollowUp::parse("more README.md"), None);
+        assert_eq!(FollowUp::parse("whyz"), None);
+    }
+
     #[test]
     fn test_handle_builtin_not_builtin() {
         let mut shell = KaidoShell::new().unwrap();
@@ -1,30 +1,41 @@
 pub mod ai;
+pub mod aliases;
 pub mod builtins;
+pub mod completion;
+pub mod confirm;
 pub mod core;
 pub mod executor;
+pub mod highlight;
 pub mod history;
 pub mod kaido_shell;
 pub mod learning;
+pub mod oneshot;
+pub mod pager;
+pub mod palette;
 pub mod parser;
 pub mod plugin;
 pub mod plugins;
+pub mod prefetch;
 pub mod prompt;
 pub mod pty;
+pub mod recording;
 pub mod repl;
 pub mod signals;
 pub mod skills;
 pub mod theme;
-pub mod palette;
 
 pub use builtins::{parse_builtin, Builtin, BuiltinResult, ShellEnvironment};
+pub use completion::CommandCompleter;
 pub use core::Shell;
 pub use executor::CommandExecutor;
+pub use highlight::{HighlightRule, OutputHighlighter};
 pub use history::{default_history_path, ensure_history_dir, HistoryConfig};
 pub use kaido_shell::{KaidoShell, ShellConfig};
 pub use learning::{LearningTracker, SkillCategory};
 pub use parser::{CommandParser, ParseError, ParsedCommand};
 pub use prompt::PromptBuilder;
 pub use pty::{PtyExecutionResult, PtyExecutor};
-pub use repl::run_agent_repl;
+pub use recording::{SessionEvent, SessionRecorder};
+pub use repl::{run_agent_repl, KaidoREPL};
 pub use signals::{SignalHandler, TerminalSize};
 pub use theme::Theme;
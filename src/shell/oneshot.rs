@@ -0,0 +1,163 @@
+// One-shot mode: `kaido shell -c "<command>"`
+//
+// Runs a single command through the same executor and error detection as
+// the interactive shell, then exits with the command's own code, so kaido
+// can wrap steps in Makefiles and CI. Output goes to stdout; the mentor
+// block goes to stderr so piped output stays clean. `--format json` prints
+// one object with the output, the detected error and its guidance instead.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::kaido_shell::ShellConfig;
+use super::pty::{PtyExecutionResult, PtyExecutor};
+use crate::ai::AIManager;
+use crate::config::Config as KaidoConfig;
+use crate::mentor::{
+    DisplayConfig, ErrorDetector, ErrorInfo, MentorConfig, MentorDisplay, MentorEngine,
+    MentorGuidance,
+};
+use crate::tools::LLMBackend;
+
+/// Exit code when the command was killed by a signal
+const SIGNALED_EXIT_CODE: i32 = 1;
+
+/// Detected error in `--format json` output
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub error_type: String,
+    pub key_message: String,
+    pub location: Option<String>,
+    pub context_lines: Vec<String>,
+}
+
+impl From<&ErrorInfo> for ErrorReport {
+    fn from(error: &ErrorInfo) -> Self {
+        Self {
+            error_type: error.error_type.name().to_string(),
+            key_message: error.key_message.clone(),
+            location: error.source_location.as_ref().map(ToString::to_string),
+            context_lines: error.context_lines.clone(),
+        }
+    }
+}
+
+/// Everything `--format json` prints
+#[derive(Debug, Serialize)]
+pub struct OneShotReport {
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub output: String,
+    pub error: Option<ErrorReport>,
+    pub guidance: Option<MentorGuidance>,
+}
+
+impl OneShotReport {
+    fn new(result: &PtyExecutionResult, error: Option<&ErrorInfo>) -> Self {
+        Self {
+            command: result.command.clone(),
+            exit_code: result.exit_code,
+            duration_ms: result.duration.as_millis() as u64,
+            output: result.output.clone(),
+            error: error.map(ErrorReport::from),
+            guidance: None,
+        }
+    }
+}
+
+/// Run `command` once and return the exit code kaido should exit with
+pub async fn run_once(
+    command: &str,
+    config: &ShellConfig,
+    kaido_config: KaidoConfig,
+    json: bool,
+) -> Result<i32> {
+    let executor = match &config.shell {
+        Some(shell) => PtyExecutor::with_shell(shell),
+        None => PtyExecutor::new(),
+    }
+    .with_separate_streams(config.separate_streams)
+    .with_passthrough(config.passthrough_commands.clone());
+
+    let result = executor
+        .execute(command)
+        .await
+        .context("Failed to execute command")?;
+    let error = ErrorDetector::new().analyze(&result);
+    let exit_code = result.exit_code.unwrap_or(SIGNALED_EXIT_CODE);
+
+    if json {
+        let mut report = OneShotReport::new(&result, error.as_ref());
+        if let Some(error) = &error {
+            let ai = (config.ai_enabled && !crate::ai::offline_mode())
+                .then(|| AIManager::new(kaido_config));
+            let llm = ai.as_ref().map(|ai| ai as &dyn LLMBackend);
+            let engine = MentorEngine::with_config(MentorConfig {
+                language: config.language,
+                ..Default::default()
+            });
+            report.guidance = Some(engine.generate(error, llm).await);
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(exit_code);
+    }
+
+    print!("{}", result.output);
+    if !result.output.is_empty() && !result.output.ends_with('\n') {
+        println!();
+    }
+    if let Some(error) = &error {
+        let display = MentorDisplay::with_config(DisplayConfig {
+            verbosity: config.mentor_verbosity,
+            terminal_width: 0,
+            colors_enabled: config.use_colors,
+            hyperlinks: false,
+            theme: config.color_theme,
+            palette: config.palette.clone(),
+            language: config.language,
+            compact_threshold: config.mentor_compact_threshold(),
+        });
+        eprint!("{}", display.render(error));
+    }
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_once_returns_command_exit_code() {
+        let config = ShellConfig {
+            separate_streams: true,
+            ai_enabled: false,
+            ..ShellConfig::default()
+        };
+
+        let code = run_once("exit 3", &config, KaidoConfig::default(), false)
+            .await
+            .unwrap();
+        assert_eq!(code, 3);
+        let code = run_once("true", &config, KaidoConfig::default(), true)
+            .await
+            .unwrap();
+        assert_eq!(code, 0);
+    }
+
+    #[tokio::test]
+    async fn test_report_carries_detected_error() {
+        let result = PtyExecutor::new()
+            .with_separate_streams(true)
+            .execute("kaido-no-such-command-xyz")
+            .await
+            .unwrap();
+        let error = ErrorDetector::new().analyze(&result);
+        let report = OneShotReport::new(&result, error.as_ref());
+
+        assert_eq!(report.exit_code, Some(127));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["error"]["error_type"], "Command Not Found");
+        assert!(json["guidance"].is_null());
+    }
+}
@@ -0,0 +1,173 @@
+// Output paging for long command output
+//
+// Pipes output through `$PAGER` (default `less -R`) when it would not fit
+// on screen, falling back to a simple built-in pager. Never pages when
+// stdout is not a terminal or when running under CI.
+
+use anyhow::{Context, Result};
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use super::signals::TerminalSize;
+
+/// Lines kept free below the output (prompt line)
+const RESERVED_ROWS: usize = 2;
+
+/// Pager used when `$PAGER` is not set
+const DEFAULT_PAGER: &str = "less -R";
+
+/// Color for a line of unified diff output
+pub fn diff_line_color(line: &str) -> &'static str {
+    if line.starts_with("+++") || line.starts_with("---") {
+        "\x1b[1m"
+    } else if line.starts_with('+') {
+        "\x1b[32m"
+    } else if line.starts_with('-') {
+        "\x1b[31m"
+    } else if line.starts_with("@@") {
+        "\x1b[36m"
+    } else {
+        ""
+    }
+}
+
+/// Whether output with this many lines should be paged
+///
+/// Only pages when stdout is a terminal and the output is taller than it.
+pub fn should_page(output: &str, rows: u16, is_tty: bool) -> bool {
+    if !is_tty || rows == 0 {
+        return false;
+    }
+
+    let available = (rows as usize).saturating_sub(RESERVED_ROWS).max(1);
+    output.lines().count() > available
+}
+
+/// Whether paging is possible in this environment at all
+pub fn paging_available() -> bool {
+    io::stdout().is_terminal() && io::stdin().is_terminal() && std::env::var_os("CI").is_none()
+}
+
+/// Parse a pager command line such as `less -R` into program and args
+fn pager_command(pager: Option<&str>) -> (String, Vec<String>) {
+    let pager = pager
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(DEFAULT_PAGER);
+
+    let mut parts = pager.split_whitespace().map(str::to_string);
+    let program = parts.next().unwrap_or_else(|| "less".to_string());
+    (program, parts.collect())
+}
+
+/// Page output if it is too long for the terminal, otherwise print it
+///
+/// Returns once the pager exits, so callers can render follow-up output
+/// (e.g. mentor guidance) afterwards.
+pub fn print_paged(output: &str) -> Result<()> {
+    let (_, rows) = TerminalSize::get_current_size();
+
+    if !paging_available() || !should_page(output, rows, true) {
+        print!("{output}");
+        if !output.ends_with('\n') {
+            println!();
+        }
+        return Ok(());
+    }
+
+    let pager = std::env::var("PAGER").ok();
+    if let Err(e) = run_external_pager(pager.as_deref(), output) {
+        log::debug!("External pager failed ({e}), using built-in pager");
+        run_builtin_pager(output, rows as usize)?;
+    }
+
+    Ok(())
+}
+
+/// Pipe output through an external pager
+fn run_external_pager(pager: Option<&str>, output: &str) -> Result<()> {
+    let (program, args) = pager_command(pager);
+
+    let mut child = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start pager '{program}'"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything
+        let _ = stdin.write_all(output.as_bytes());
+    }
+
+    child.wait().context("Pager did not exit cleanly")?;
+    Ok(())
+}
+
+/// Minimal built-in pager: a screenful at a time, Enter for more, q to quit
+fn run_builtin_pager(output: &str, rows: usize) -> Result<()> {
+    let page_size = rows.saturating_sub(RESERVED_ROWS).max(1);
+    let lines: Vec<&str> = output.lines().collect();
+    let stdin = io::stdin();
+
+    for (index, chunk) in lines.chunks(page_size).enumerate() {
+        for line in chunk {
+            println!("{line}");
+        }
+
+        let shown = (index + 1) * page_size;
+        if shown >= lines.len() {
+            break;
+        }
+
+        print!(
+            "\x1b[7m-- More ({}/{} lines) -- Enter to continue, q to quit\x1b[0m",
+            shown,
+            lines.len()
+        );
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        stdin.lock().read_line(&mut answer)?;
+        // Clear the prompt line
+        print!("\x1b[1A\r\x1b[K");
+        if answer.trim().eq_ignore_ascii_case("q") {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_page() {
+        let long = "line\n".repeat(50);
+        let short = "line\n".repeat(5);
+
+        assert!(should_page(&long, 24, true));
+        assert!(!should_page(&short, 24, true));
+        // Never page when not attached to a terminal
+        assert!(!should_page(&long, 24, false));
+        // Unknown height
+        assert!(!should_page(&long, 0, true));
+    }
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(
+            pager_command(None),
+            ("less".to_string(), vec!["-R".to_string()])
+        );
+        assert_eq!(
+            pager_command(Some("  ")),
+            ("less".to_string(), vec!["-R".to_string()])
+        );
+        assert_eq!(
+            pager_command(Some("most -s")),
+            ("most".to_string(), vec!["-s".to_string()])
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// Background AI explanations
+//
+// Pattern guidance renders the moment an error is detected; the LLM
+// explanation is requested in the background and shown when it arrives.
+// If the shell is sitting at the prompt by then, the answer is printed above
+// the line being edited through rustyline's external printer, so whatever
+// the user has started typing stays intact. Otherwise it waits for the next
+// prompt.
+
+use anyhow::Result;
+use rustyline::ExternalPrinter;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::tools::LLMResponse;
+
+/// Prints above the input line while the shell is reading one
+#[derive(Clone, Default)]
+pub struct PromptPrinter {
+    printer: Option<Arc<Mutex<Box<dyn ExternalPrinter + Send>>>>,
+    at_prompt: Arc<AtomicBool>,
+}
+
+impl PromptPrinter {
+    pub fn new(printer: impl ExternalPrinter + Send + 'static) -> Self {
+        Self {
+            printer: Some(Arc::new(Mutex::new(Box::new(printer)))),
+            at_prompt: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Mark whether the shell is currently waiting for input
+    pub fn set_at_prompt(&self, at_prompt: bool) {
+        self.at_prompt.store(at_prompt, Ordering::SeqCst);
+    }
+
+    /// Print `text` if the shell is at the prompt; returns whether it did
+    ///
+    /// While a command runs the printer would write straight into its
+    /// output, so nothing is printed then.
+    fn print_at_prompt(&self, text: String) -> bool {
+        let Some(ref printer) = self.printer else {
+            return false;
+        };
+        if !self.at_prompt.load(Ordering::SeqCst) {
+            return false;
+        }
+        let Ok(mut printer) = printer.lock() else {
+            return false;
+        };
+        printer.print(text).is_ok()
+    }
+}
+
+/// An AI explanation being fetched in the background
+///
+/// Dropping it cancels the request, e.g. when a newer error supersedes it.
+pub struct PendingExplanation {
+    /// The question, added to the conversation once it is answered
+    question: String,
+    task: JoinHandle<Result<LLMResponse>>,
+    /// Set when the answer was already printed at the prompt
+    shown: Arc<AtomicBool>,
+}
+
+/// A background explanation that has completed
+pub struct FinishedExplanation {
+    pub question: String,
+    pub response: Result<LLMResponse>,
+    /// Whether the answer was already printed at the prompt
+    pub shown: bool,
+}
+
+impl PendingExplanation {
+    /// Start `request`, printing `render(answer)` at the prompt if it lands there
+    pub fn spawn<F>(
+        question: String,
+        request: F,
+        printer: PromptPrinter,
+        render: fn(&str) -> String,
+    ) -> Self
+    where
+        F: Future<Output = Result<LLMResponse>> + Send + 'static,
+    {
+        let shown = Arc::new(AtomicBool::new(false));
+        let task_shown = shown.clone();
+        let task = tokio::spawn(async move {
+            let response = request.await;
+            if let Ok(ref response) = response {
+                if printer.print_at_prompt(render(&response.reasoning)) {
+                    task_shown.store(true, Ordering::SeqCst);
+                }
+            }
+            response
+        });
+
+        Self {
+            question,
+            task,
+            shown,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the request to complete
+    pub async fn finish(mut self) -> FinishedExplanation {
+        let response = match (&mut self.task).await {
+            Ok(response) => response,
+            Err(e) => Err(anyhow::anyhow!("AI explanation task failed: {e}")),
+        };
+        FinishedExplanation {
+            question: std::mem::take(&mut self.question),
+            response,
+            shown: self.shown.load(Ordering::SeqCst),
+        }
+    }
+}
+
+impl Drop for PendingExplanation {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CapturePrinter(Arc<Mutex<Vec<String>>>);
+
+    impl ExternalPrinter for CapturePrinter {
+        fn print(&mut self, msg: String) -> rustyline::Result<()> {
+            self.0.lock().unwrap().push(msg);
+            Ok(())
+        }
+    }
+
+    fn answer(text: &str) -> LLMResponse {
+        LLMResponse {
+            command: String::new(),
+            confidence: 90,
+            reasoning: text.to_string(),
+            prompt_tokens: None,
+            completion_tokens: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prints_at_prompt_only() {
+        let printed = Arc::new(Mutex::new(Vec::new()));
+        let printer = PromptPrinter::new(CapturePrinter(printed.clone()));
+
+        // Still running a command: left for the next prompt
+        let pending = PendingExplanation::spawn(
+            "why?".to_string(),
+            async { Ok(answer("disk full")) },
+            printer.clone(),
+            |text| format!("[{text}]"),
+        );
+        let finished = pending.finish().await;
+        assert!(!finished.shown);
+        assert_eq!(finished.question, "why?");
+        assert_eq!(finished.response.unwrap().reasoning, "disk full");
+        assert!(printed.lock().unwrap().is_empty());
+
+        printer.set_at_prompt(true);
+        let pending = PendingExplanation::spawn(
+            "why?".to_string(),
+            async { Ok(answer("disk full")) },
+            printer,
+            |text| format!("[{text}]"),
+        );
+        assert!(pending.finish().await.shown);
+        assert_eq!(*printed.lock().unwrap(), ["[disk full]"]);
+    }
+}
@@ -4,14 +4,19 @@
 // - kaido branding
 // - current directory (shortened)
 // - git branch (if in a git repo)
+// - kubectl context, colored by environment (red for production)
 
 use std::env;
 use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::kubectl::{EnvironmentType, KubectlContext};
 
 /// ANSI color codes for prompt
 pub mod colors {
     pub const RESET: &str = "\x1b[0m";
     pub const BOLD: &str = "\x1b[1m";
+    pub const RED: &str = "\x1b[31m";
     pub const DIM: &str = "\x1b[2m";
 
     pub const CYAN: &str = "\x1b[36m";
@@ -29,6 +34,28 @@ pub struct PromptBuilder {
     show_git_branch: bool,
     /// Custom prompt prefix (default: "kaido")
     prefix: String,
+    /// Whether to show the kubectl context
+    show_kube_context: bool,
+    /// Cached kubectl context; reading kubeconfig on every prompt is slow
+    kube_context: Option<KubectlContext>,
+    /// Kubeconfig path and modification time the cache was read from
+    kubeconfig_stamp: Option<(PathBuf, SystemTime)>,
+    /// Whether the cache must be re-read before the next prompt
+    kube_context_stale: bool,
+}
+
+/// Whether a command switches the kubectl context
+pub fn switches_kube_context(command: &str) -> bool {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    matches!(
+        words.as_slice(),
+        [
+            "kubectl",
+            "config",
+            "use-context" | "use" | "set-context" | "delete-context",
+            ..
+        ] | ["kubectx" | "kubens", ..]
+    )
 }
 
 impl PromptBuilder {
@@ -38,6 +65,10 @@ impl PromptBuilder {
             use_colors: true,
             show_git_branch: true,
             prefix: "kaido".to_string(),
+            show_kube_context: true,
+            kube_context: None,
+            kubeconfig_stamp: None,
+            kube_context_stale: true,
         }
     }
 
@@ -53,6 +84,46 @@ impl PromptBuilder {
         self
     }
 
+    /// Disable kubectl context display
+    pub fn no_kube_context(mut self) -> Self {
+        self.show_kube_context = false;
+        self
+    }
+
+    /// Use a fixed kubectl context instead of reading kubeconfig
+    pub fn with_kube_context(mut self, context: Option<KubectlContext>) -> Self {
+        self.kube_context = context;
+        self.kube_context_stale = false;
+        self
+    }
+
+    /// Re-read the kubectl context on the next `refresh_kube_context`
+    ///
+    /// Call after `kubectl config use-context` and similar commands.
+    pub fn invalidate_kube_context(&mut self) {
+        self.kube_context_stale = true;
+    }
+
+    /// Reload the cached kubectl context if it was invalidated or the
+    /// kubeconfig file changed (e.g. a context switch in another terminal)
+    pub fn refresh_kube_context(&mut self) {
+        if !self.show_kube_context {
+            return;
+        }
+
+        let stamp = KubectlContext::kubeconfig_path().and_then(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        });
+        if !self.kube_context_stale && stamp == self.kubeconfig_stamp {
+            return;
+        }
+
+        self.kube_context = stamp.as_ref().and_then(|_| KubectlContext::current().ok());
+        self.kubeconfig_stamp = stamp;
+        self.kube_context_stale = false;
+    }
+
     /// Set custom prefix
     pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
         self.prefix = prefix.into();
@@ -75,10 +146,32 @@ impl PromptBuilder {
         }
     }
 
+    /// Cached kubectl context, if it should be shown
+    fn kube_context(&self) -> Option<&KubectlContext> {
+        self.kube_context
+            .as_ref()
+            .filter(|_| self.show_kube_context)
+    }
+
     /// Build colored prompt
     fn build_colored_prompt(&self, cwd: &str, git_branch: Option<&str>) -> String {
         let mut prompt = String::new();
 
+        // Kubectl context, colored by environment
+        if let Some(context) = self.kube_context() {
+            let color = match context.environment_type {
+                EnvironmentType::Production => colors::RED,
+                EnvironmentType::Staging => colors::YELLOW,
+                EnvironmentType::Development => colors::GREEN,
+                EnvironmentType::Unknown => colors::DIM,
+            };
+            prompt.push_str(colors::BOLD);
+            prompt.push_str(color);
+            prompt.push_str(&kube_segment(context));
+            prompt.push_str(colors::RESET);
+            prompt.push(' ');
+        }
+
         // Prefix (cyan, bold)
         prompt.push_str(colors::BOLD);
         prompt.push_str(colors::CYAN);
@@ -119,6 +212,11 @@ impl PromptBuilder {
     fn build_plain_prompt(&self, cwd: &str, git_branch: Option<&str>) -> String {
         let mut prompt = String::new();
 
+        if let Some(context) = self.kube_context() {
+            prompt.push_str(&kube_segment(context));
+            prompt.push(' ');
+        }
+
         prompt.push_str(&self.prefix);
         prompt.push(' ');
         prompt.push_str(cwd);
@@ -183,6 +281,17 @@ impl PromptBuilder {
     }
 }
 
+/// Kubectl context segment, e.g. `[prod-eu PROD]`
+fn kube_segment(context: &KubectlContext) -> String {
+    let label = match context.environment_type {
+        EnvironmentType::Production => " PROD",
+        EnvironmentType::Staging => " STAGING",
+        EnvironmentType::Development => " dev",
+        EnvironmentType::Unknown => "",
+    };
+    format!("[{}{label}]", context.name)
+}
+
 impl Default for PromptBuilder {
     fn default() -> Self {
         Self::new()
@@ -222,6 +331,45 @@ mod tests {
         assert!(prompt.starts_with("myshell "));
     }
 
+    #[test]
+    fn test_kube_context_segment() {
+        let context = |name: &str| {
+            KubectlContext::new(
+                name.to_string(),
+                "cluster".to_string(),
+                None,
+                "admin".to_string(),
+            )
+        };
+
+        let prompt = PromptBuilder::new()
+            .no_colors()
+            .no_git_branch()
+            .with_kube_context(Some(context("prod-eu")))
+            .build();
+        assert!(prompt.starts_with("[prod-eu PROD] kaido "));
+
+        let prompt = PromptBuilder::new()
+            .with_kube_context(Some(context("prod-eu")))
+            .build();
+        assert!(prompt.starts_with("\x1b[1m\x1b[31m[prod-eu PROD]"));
+
+        let prompt = PromptBuilder::new()
+            .no_colors()
+            .no_kube_context()
+            .with_kube_context(Some(context("prod-eu")))
+            .build();
+        assert!(prompt.starts_with("kaido "));
+    }
+
+    #[test]
+    fn test_switches_kube_context() {
+        assert!(switches_kube_context("kubectl config use-context prod-eu"));
+        assert!(switches_kube_context("kubectx staging"));
+        assert!(!switches_kube_context("kubectl config get-contexts"));
+        assert!(!switches_kube_context("kubectl get pods"));
+    }
+
     #[test]
     fn test_shortened_cwd() {
         let builder = PromptBuilder::new();
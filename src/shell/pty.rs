@@ -2,18 +2,28 @@
 //
 // Uses pty-process crate to execute commands in a pseudo-terminal,
 // preserving colors, supporting interactive programs, and capturing output.
+// Optionally falls back to a piped subprocess to keep stdout and stderr apart.
+// Full-screen programs (vim, less, top) get the real terminal instead.
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::process::Stdio;
 use std::time::{Duration, Instant};
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-use super::signals::TerminalSize;
+use super::signals::{Interrupts, TerminalSize};
 
 /// Result of executing a command in the PTY
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PtyExecutionResult {
     /// Combined output (stdout + stderr merged, as in real terminal)
     pub output: String,
+    /// Standard output alone (only when streams were captured separately)
+    pub stdout: Option<String>,
+    /// Standard error alone (only when streams were captured separately)
+    pub stderr: Option<String>,
     /// Exit code of the command (None if killed by signal)
     pub exit_code: Option<i32>,
     /// How long the command took to execute
@@ -22,6 +32,11 @@ pub struct PtyExecutionResult {
     pub command: String,
     /// Whether the command was interrupted (Ctrl+C)
     pub interrupted: bool,
+    /// Where and how the command ran: the working directory (`cwd`), the
+    /// kubectl context (`kube_context`) and allowlisted environment
+    /// variables, secrets masked. None when snapshots are off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_snapshot: Option<HashMap<String, String>>,
 }
 
 impl PtyExecutionResult {
@@ -34,6 +49,15 @@ impl PtyExecutionResult {
     pub fn failed(&self) -> bool {
         matches!(self.exit_code, Some(code) if code != 0)
     }
+
+    /// Text to inspect for errors: stderr when captured and non-empty,
+    /// otherwise the combined output
+    pub fn error_text(&self) -> &str {
+        match &self.stderr {
+            Some(stderr) if !stderr.trim().is_empty() => stderr,
+            _ => &self.output,
+        }
+    }
 }
 
 /// PTY executor for running shell commands
@@ -42,6 +66,91 @@ pub struct PtyExecutor {
     shell: String,
     /// Terminal size (rows, cols)
     size: (u16, u16),
+    /// Capture stdout and stderr separately instead of using a PTY
+    separate_streams: bool,
+    /// Programs that take over the terminal instead of being captured
+    passthrough: Vec<String>,
+    /// Environment variables recorded with each result
+    env_snapshot: Vec<String>,
+}
+
+/// Programs that need the real terminal (full-screen or interactive)
+pub const DEFAULT_PASSTHROUGH: &[&str] = &[
+    "vim", "vi", "nvim", "nano", "emacs", "less", "more", "man", "top", "htop", "btop", "watch",
+    "tmux", "screen", "ssh", "k9s",
+];
+
+/// Environment variables recorded with each result by default: the ones
+/// that decide which binary, cluster, cloud account or interpreter a
+/// command talks to
+pub const DEFAULT_ENV_SNAPSHOT: &[&str] = &[
+    "PATH",
+    "SHELL",
+    "LANG",
+    "KUBECONFIG",
+    "DOCKER_HOST",
+    "AWS_PROFILE",
+    "AWS_REGION",
+    "GOOGLE_CLOUD_PROJECT",
+    "VIRTUAL_ENV",
+    "NODE_ENV",
+];
+
+/// Container CLIs whose `-it` / `--tty` flag means an interactive session
+const TTY_FLAG_PROGRAMS: &[&str] = &["kubectl", "docker", "podman"];
+
+/// Words that run the next word as the command
+const COMMAND_PREFIXES: &[&str] = &["sudo", "env", "exec", "command", "nohup", "time"];
+
+/// Whether any stage of `command` runs one of `programs`, or a container
+/// CLI with a TTY flag, and so needs the real terminal
+pub fn needs_terminal(command: &str, programs: &[String]) -> bool {
+    let Ok(stages) = crate::ai::explainer::tokenize_command(command) else {
+        return false;
+    };
+    stages.iter().any(|tokens| {
+        let mut words = tokens
+            .iter()
+            .map(String::as_str)
+            .skip_while(|t| t.contains('=') && !t.starts_with('-'))
+            .skip_while(|t| COMMAND_PREFIXES.contains(t));
+        let Some(program) = words.next() else {
+            return false;
+        };
+        let program = program.rsplit('/').next().unwrap_or(program);
+        programs.iter().any(|p| p == program)
+            || (TTY_FLAG_PROGRAMS.contains(&program)
+                && words.any(|w| matches!(w, "-it" | "-ti" | "-t" | "--tty")))
+    })
+}
+
+/// Terminal settings (`stty -g`) saved before a program takes over,
+/// restored on drop so a crashed full-screen program can't leave it raw
+struct TerminalGuard(String);
+
+impl TerminalGuard {
+    fn save() -> Option<Self> {
+        let output = std::process::Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| Self(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = std::process::Command::new("stty")
+            .arg(&self.0)
+            .stdin(Stdio::inherit())
+            .status();
+        // Show the cursor again in case the program hid it
+        print!("\x1b[?25h");
+    }
 }
 
 impl PtyExecutor {
@@ -50,6 +159,9 @@ impl PtyExecutor {
         Self {
             shell: std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string()),
             size: (24, 80),
+            separate_streams: false,
+            passthrough: Vec::new(),
+            env_snapshot: Vec::new(),
         }
     }
 
@@ -58,7 +170,72 @@ impl PtyExecutor {
         Self {
             shell: shell.into(),
             size: (24, 80),
+            separate_streams: false,
+            passthrough: Vec::new(),
+            env_snapshot: Vec::new(),
+        }
+    }
+
+    /// Capture stdout and stderr separately (best effort)
+    ///
+    /// A PTY merges both streams, so in this mode commands run as a piped
+    /// subprocess instead. Output is still interleaved in `output` in the
+    /// order it arrived, but programs see no terminal: colors may be
+    /// dropped and full-screen programs won't work.
+    pub fn with_separate_streams(mut self, enabled: bool) -> Self {
+        self.separate_streams = enabled;
+        self
+    }
+
+    /// Hand the terminal to these programs instead of capturing them
+    ///
+    /// Their output isn't captured, so mentor guidance only sees the exit
+    /// code. Only applies when stdin is a terminal.
+    pub fn with_passthrough(mut self, programs: Vec<String>) -> Self {
+        self.passthrough = programs;
+        self
+    }
+
+    /// Record these environment variables, plus the working directory and
+    /// kubectl context, with every result
+    ///
+    /// Values are passed through secret redaction. An empty list turns
+    /// snapshots off.
+    pub fn with_env_snapshot(mut self, vars: Vec<String>) -> Self {
+        self.env_snapshot = vars;
+        self
+    }
+
+    /// The environment a command run now would see, if snapshots are on
+    pub fn env_snapshot(&self) -> Option<HashMap<String, String>> {
+        if self.env_snapshot.is_empty() {
+            return None;
+        }
+        let mut snapshot: HashMap<String, String> = self
+            .env_snapshot
+            .iter()
+            .filter_map(|name| {
+                let value = std::env::var(name).ok()?;
+                // Redacted as `NAME=value` so secret-looking names mask too
+                let line = format!("{name}={value}");
+                let redacted = crate::ai::redact_secrets(&line);
+                Some((name.clone(), redacted[name.len() + 1..].to_string()))
+            })
+            .collect();
+        if let Ok(cwd) = std::env::current_dir() {
+            snapshot.insert("cwd".to_string(), cwd.display().to_string());
         }
+        if let Some(context) = crate::tools::ToolContext::detect().kubectl_context {
+            snapshot.insert("kube_context".to_string(), context.name);
+        }
+        Some(snapshot)
+    }
+
+    /// Whether `command` will get the real terminal
+    pub fn is_passthrough(&self, command: &str) -> bool {
+        !self.passthrough.is_empty()
+            && std::io::stdin().is_terminal()
+            && needs_terminal(command, &self.passthrough)
     }
 
     /// Set terminal size
@@ -103,7 +280,41 @@ impl PtyExecutor {
     /// - Colors and ANSI escape codes are preserved
     /// - stdout and stderr are merged (as in a real terminal)
     /// - Interactive programs can work (though we don't forward input here)
+    ///
+    /// Ctrl+C interrupts the command, not kaido.
     pub async fn execute(&self, command: &str) -> Result<PtyExecutionResult> {
+        let mut interrupts = Interrupts::for_terminal();
+        self.execute_interruptible(command, &mut interrupts).await
+    }
+
+    /// Execute a command, interrupting it on each of `interrupts`
+    ///
+    /// The first interrupt sends SIGINT to the command's process group
+    /// only; a second one kills the command. The result is then marked
+    /// `interrupted`, with the output captured so far.
+    pub async fn execute_interruptible(
+        &self,
+        command: &str,
+        interrupts: &mut Interrupts,
+    ) -> Result<PtyExecutionResult> {
+        let env_snapshot = self.env_snapshot();
+        let mut result = if self.is_passthrough(command) {
+            self.execute_passthrough(command).await
+        } else if self.separate_streams {
+            self.execute_separated(command, interrupts).await
+        } else {
+            self.execute_pty(command, interrupts).await
+        }?;
+        result.env_snapshot = env_snapshot;
+        Ok(result)
+    }
+
+    /// Execute a command in a new PTY
+    async fn execute_pty(
+        &self,
+        command: &str,
+        interrupts: &mut Interrupts,
+    ) -> Result<PtyExecutionResult> {
         let start = Instant::now();
 
         // Open a new PTY pair
@@ -125,9 +336,20 @@ impl PtyExecutor {
         // Read output from PTY
         let mut output = Vec::new();
         let mut buffer = [0u8; 4096];
+        let mut interrupted = false;
 
         loop {
             tokio::select! {
+                // Ctrl+C: the PTY turns ^C into SIGINT for its foreground
+                // process group, which is the command and nothing else
+                () = interrupts.recv() => {
+                    if interrupted {
+                        child.start_kill()?;
+                    } else {
+                        interrupted = true;
+                        pty.write_all(&[0x03]).await.context("Failed to interrupt command")?;
+                    }
+                }
                 // Read from PTY
                 result = pty.read(&mut buffer) => {
                     match result {
@@ -167,10 +389,13 @@ impl PtyExecutor {
 
                     return Ok(PtyExecutionResult {
                         output: output_str,
-                        exit_code: status.code(),
+                        stdout: None,
+                        stderr: None,
+                        exit_code: interrupted_exit_code(status.code(), interrupted),
                         duration,
                         command: command.to_string(),
-                        interrupted: false,
+                        interrupted,
+                        env_snapshot: None,
                     });
                 }
             }
@@ -183,10 +408,133 @@ impl PtyExecutor {
 
         Ok(PtyExecutionResult {
             output: output_str,
-            exit_code: status.code(),
+            stdout: None,
+            stderr: None,
+            exit_code: interrupted_exit_code(status.code(), interrupted),
             duration,
             command: command.to_string(),
+            interrupted,
+            env_snapshot: None,
+        })
+    }
+
+    /// Run a command attached to the real terminal, restoring its settings after
+    async fn execute_passthrough(&self, command: &str) -> Result<PtyExecutionResult> {
+        let start = Instant::now();
+        let _guard = TerminalGuard::save();
+        // Ctrl+C belongs to the program; keep it from killing kaido meanwhile
+        let _sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt());
+
+        let status = tokio::process::Command::new(&self.shell)
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .status()
+            .await
+            .context("Failed to spawn command")?;
+
+        Ok(PtyExecutionResult {
+            output: String::new(),
+            stdout: None,
+            stderr: None,
+            exit_code: status.code(),
+            duration: start.elapsed(),
+            command: command.to_string(),
             interrupted: false,
+            env_snapshot: None,
+        })
+    }
+
+    /// Execute a command as a piped subprocess, keeping stdout and stderr apart
+    ///
+    /// On a terminal the command shares kaido's process group so it can
+    /// read from it, and gets Ctrl+C from the terminal directly. Otherwise
+    /// it runs in its own group, which interrupts are forwarded to.
+    async fn execute_separated(
+        &self,
+        command: &str,
+        interrupts: &mut Interrupts,
+    ) -> Result<PtyExecutionResult> {
+        let start = Instant::now();
+        let on_terminal = std::io::stdin().is_terminal();
+        // The command may change terminal settings (`read -s`) and be cut
+        // off before it restores them
+        let _guard = on_terminal.then(TerminalGuard::save).flatten();
+
+        let mut process = tokio::process::Command::new(&self.shell);
+        process
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if !on_terminal {
+            process.process_group(0);
+        }
+        let mut child = process.spawn().context("Failed to spawn command")?;
+
+        let mut child_stdout = child.stdout.take().context("Failed to capture stdout")?;
+        let mut child_stderr = child.stderr.take().context("Failed to capture stderr")?;
+
+        let mut output = Vec::new();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut out_buffer = [0u8; 4096];
+        let mut err_buffer = [0u8; 4096];
+        let mut out_done = false;
+        let mut err_done = false;
+        let mut interrupted = false;
+
+        // Read both pipes as data arrives so `output` keeps a best-effort order
+        while !(out_done && err_done) {
+            tokio::select! {
+                () = interrupts.recv() => {
+                    if interrupted {
+                        child.start_kill()?;
+                        break;
+                    }
+                    interrupted = true;
+                    if let (false, Some(pid)) = (on_terminal, child.id()) {
+                        if let Err(e) = super::signals::interrupt_process_group(pid) {
+                            log::debug!("Failed to interrupt command: {e}");
+                        }
+                    }
+                }
+                result = child_stdout.read(&mut out_buffer), if !out_done => {
+                    match result? {
+                        0 => out_done = true,
+                        n => {
+                            stdout.extend_from_slice(&out_buffer[..n]);
+                            output.extend_from_slice(&out_buffer[..n]);
+                        }
+                    }
+                }
+                result = child_stderr.read(&mut err_buffer), if !err_done => {
+                    match result? {
+                        0 => err_done = true,
+                        n => {
+                            stderr.extend_from_slice(&err_buffer[..n]);
+                            output.extend_from_slice(&err_buffer[..n]);
+                        }
+                    }
+                }
+            }
+        }
+
+        let status = child.wait().await?;
+
+        Ok(PtyExecutionResult {
+            output: String::from_utf8_lossy(&output).to_string(),
+            stdout: Some(String::from_utf8_lossy(&stdout).to_string()),
+            stderr: Some(String::from_utf8_lossy(&stderr).to_string()),
+            exit_code: interrupted_exit_code(status.code(), interrupted),
+            duration: start.elapsed(),
+            command: command.to_string(),
+            interrupted,
+            env_snapshot: None,
         })
     }
 
@@ -201,16 +549,25 @@ impl PtyExecutor {
             Err(_) => {
                 Ok(PtyExecutionResult {
                     output: format!("Command timed out after {timeout:?}"),
+                    stdout: None,
+                    stderr: None,
                     exit_code: Some(124), // Standard timeout exit code
                     duration: timeout,
                     command: command.to_string(),
                     interrupted: true,
+                    env_snapshot: self.env_snapshot(),
                 })
             }
         }
     }
 }
 
+/// Exit code of a command, reporting the shell's 130 (128 + SIGINT) when
+/// an interrupted command died of the signal
+fn interrupted_exit_code(code: Option<i32>, interrupted: bool) -> Option<i32> {
+    code.or(interrupted.then_some(130))
+}
+
 impl Default for PtyExecutor {
     fn default() -> Self {
         Self::new()
@@ -299,6 +656,136 @@ mod tests {
         assert!(result.output.contains("line3"));
     }
 
+    #[tokio::test]
+    async fn test_execute_separate_streams() {
+        let executor = PtyExecutor::with_shell("/bin/sh").with_separate_streams(true);
+        let result = executor
+            .execute("echo out; echo err >&2; exit 3")
+            .await
+            .unwrap();
+
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.stdout.as_deref(), Some("out\n"));
+        assert_eq!(result.stderr.as_deref(), Some("err\n"));
+        // Interleaved view still has both
+        assert!(result.output.contains("out"));
+        assert!(result.output.contains("err"));
+        assert_eq!(result.error_text(), "err\n");
+    }
+
+    #[tokio::test]
+    async fn test_separate_streams_timeout() {
+        let executor = PtyExecutor::with_shell("/bin/sh").with_separate_streams(true);
+        let result = executor
+            .execute_with_timeout("sleep 10", Duration::from_millis(100))
+            .await
+            .unwrap();
+
+        assert!(result.interrupted);
+        assert_eq!(result.exit_code, Some(124));
+    }
+
+    #[tokio::test]
+    async fn test_interrupt_mid_command() {
+        // On a terminal the command would get Ctrl+C from the terminal itself
+        if std::io::stdin().is_terminal() {
+            return;
+        }
+        let executor = PtyExecutor::with_shell("/bin/sh").with_separate_streams(true);
+        let (tx, mut interrupts) = Interrupts::channel();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let _ = tx.send(());
+        });
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            executor.execute_interruptible("echo started; sleep 100", &mut interrupts),
+        )
+        .await
+        .expect("SIGINT stops the command")
+        .unwrap();
+
+        assert!(result.interrupted);
+        assert_eq!(result.exit_code, Some(130));
+        assert_eq!(result.stdout.as_deref(), Some("started\n"));
+        assert!(result.duration < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_env_snapshot() {
+        assert_eq!(PtyExecutor::new().env_snapshot(), None);
+
+        std::env::set_var("KAIDO_TEST_SNAPSHOT_REGION", "eu-west-1");
+        std::env::set_var("KAIDO_TEST_SNAPSHOT_TOKEN", "s3cr3t-value");
+        let snapshot = PtyExecutor::new()
+            .with_env_snapshot(vec![
+                "KAIDO_TEST_SNAPSHOT_REGION".to_string(),
+                "KAIDO_TEST_SNAPSHOT_TOKEN".to_string(),
+                "KAIDO_TEST_SNAPSHOT_UNSET".to_string(),
+            ])
+            .env_snapshot()
+            .unwrap();
+        assert_eq!(snapshot["KAIDO_TEST_SNAPSHOT_REGION"], "eu-west-1");
+        assert_eq!(snapshot["KAIDO_TEST_SNAPSHOT_TOKEN"], "[REDACTED]");
+        assert!(!snapshot.contains_key("KAIDO_TEST_SNAPSHOT_UNSET"));
+        assert_eq!(
+            snapshot["cwd"],
+            std::env::current_dir().unwrap().display().to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_text_falls_back_to_output() {
+        let mut result = PtyExecutionResult {
+            output: "merged output".to_string(),
+            stdout: None,
+            stderr: None,
+            exit_code: Some(1),
+            duration: Duration::from_secs(0),
+            command: "false".to_string(),
+            interrupted: false,
+            env_snapshot: None,
+        };
+        assert_eq!(result.error_text(), "merged output");
+
+        // Empty stderr doesn't hide the combined output
+        result.stdout = Some("merged output".to_string());
+        result.stderr = Some("\n".to_string());
+        assert_eq!(result.error_text(), "merged output");
+    }
+
+    #[test]
+    fn test_needs_terminal() {
+        let programs: Vec<String> = DEFAULT_PASSTHROUGH.iter().map(|p| p.to_string()).collect();
+
+        assert!(needs_terminal("vim src/main.rs", &programs));
+        assert!(needs_terminal("sudo /usr/bin/vim /etc/hosts", &programs));
+        assert!(needs_terminal("EDITOR=x TERM=xterm top", &programs));
+        assert!(needs_terminal("journalctl -u nginx | less", &programs));
+        assert!(needs_terminal("kubectl exec -it api-1 -- sh", &programs));
+        assert!(needs_terminal("docker run --rm -ti alpine", &programs));
+
+        assert!(!needs_terminal("ls -la", &programs));
+        assert!(!needs_terminal("echo vim", &programs));
+        assert!(!needs_terminal("kubectl get pods", &programs));
+        assert!(!needs_terminal("vim file", &[]));
+    }
+
+    #[tokio::test]
+    async fn test_passthrough_needs_terminal_stdin() {
+        // Test stdin is not a terminal, so output is still captured
+        let executor = PtyExecutor::new()
+            .with_separate_streams(true)
+            .with_passthrough(vec!["echo".to_string()]);
+        if std::io::stdin().is_terminal() {
+            return;
+        }
+        assert!(!executor.is_passthrough("echo hi"));
+        let result = executor.execute("echo hi").await.unwrap();
+        assert!(result.output.contains("hi"));
+    }
+
     #[test]
     fn test_pty_executor_default() {
         let executor = PtyExecutor::default();
@@ -0,0 +1,265 @@
+// Session recording and replay
+//
+// `kaido shell --record <file>` writes every command, its output and the
+// mentor guidance shown for it to a JSONL transcript; `--replay <file>`
+// plays it back with the original timing. Transcripts end up in bug reports
+// and teaching material, so everything passes through secret redaction
+// before it is written.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::pty::PtyExecutionResult;
+use crate::ai::redact_secrets;
+
+/// Transcript format version, bumped on incompatible changes
+const TRANSCRIPT_VERSION: u32 = 1;
+
+/// Something that happened in a recorded session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// First line of every transcript
+    Start {
+        version: u32,
+        started_at: DateTime<Utc>,
+    },
+    /// A command ran, stamped with the time it started
+    Command { result: PtyExecutionResult },
+    /// Mentor guidance as it was printed (ANSI colors included)
+    Mentor { text: String },
+}
+
+/// One transcript line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// Time since the session started
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// Writes a session transcript as JSON lines
+pub struct SessionRecorder {
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+impl SessionRecorder {
+    /// Create (or truncate) a transcript file
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create transcript {}", path.display()))?;
+        let mut recorder = Self {
+            writer: BufWriter::new(file),
+            started: Instant::now(),
+        };
+        recorder.record(SessionEvent::Start {
+            version: TRANSCRIPT_VERSION,
+            started_at: Utc::now(),
+        })?;
+        Ok(recorder)
+    }
+
+    /// Time since recording started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Record an event at the current time
+    pub fn record(&mut self, event: SessionEvent) -> Result<()> {
+        self.record_at(self.elapsed(), event)
+    }
+
+    /// Record an event that happened at `elapsed` (e.g. a command's start)
+    pub fn record_at(&mut self, elapsed: Duration, event: SessionEvent) -> Result<()> {
+        let line = RecordedEvent {
+            elapsed_ms: elapsed.as_millis() as u64,
+            event: redact_event(event),
+        };
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")?;
+        // Flush per event so a crash still leaves a usable transcript
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Mask secrets in everything a transcript would store
+fn redact_event(event: SessionEvent) -> SessionEvent {
+    let redact = |text: &str| redact_secrets(text).into_owned();
+    match event {
+        SessionEvent::Command { mut result } => {
+            result.command = redact(&result.command);
+            result.output = redact(&result.output);
+            result.stdout = result.stdout.as_deref().map(redact);
+            result.stderr = result.stderr.as_deref().map(redact);
+            result.env_snapshot = result.env_snapshot.map(|snapshot| {
+                snapshot
+                    .into_iter()
+                    .map(|(name, value)| (name, redact(&value)))
+                    .collect()
+            });
+            SessionEvent::Command { result }
+        }
+        SessionEvent::Mentor { text } => SessionEvent::Mentor {
+            text: redact(&text),
+        },
+        start @ SessionEvent::Start { .. } => start,
+    }
+}
+
+/// Read a transcript, skipping lines this version doesn't understand
+pub fn read_transcript(path: &Path) -> Result<Vec<RecordedEvent>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open transcript {}", path.display()))?;
+
+    let mut events = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(event) => events.push(event),
+            Err(e) => log::warn!("Skipping transcript line {}: {e}", number + 1),
+        }
+    }
+    Ok(events)
+}
+
+/// Play a transcript back with its original timing, or at once if `fast`
+pub async fn replay(path: &Path, fast: bool) -> Result<()> {
+    let events = read_transcript(path)?;
+
+    let started = Instant::now();
+    let wait_until = |elapsed_ms: u64| {
+        let target = Duration::from_millis(elapsed_ms);
+        target.saturating_sub(started.elapsed())
+    };
+
+    for recorded in events {
+        if !fast {
+            tokio::time::sleep(wait_until(recorded.elapsed_ms)).await;
+        }
+
+        match recorded.event {
+            SessionEvent::Start { started_at, .. } => {
+                println!(
+                    "\x1b[2m▶ Replaying session recorded {}\x1b[0m",
+                    started_at.format("%Y-%m-%d %H:%M UTC")
+                );
+                println!();
+            }
+            SessionEvent::Command { result } => {
+                println!("\x1b[1;36mkaido\x1b[0m \x1b[33m$\x1b[0m {}", result.command);
+                if !fast {
+                    tokio::time::sleep(result.duration).await;
+                }
+                print!("{}", result.output);
+                if !result.output.is_empty() && !result.output.ends_with('\n') {
+                    println!();
+                }
+            }
+            SessionEvent::Mentor { text } => print!("{text}"),
+        }
+        std::io::stdout().flush().ok();
+    }
+
+    println!();
+    println!("\x1b[2m■ End of recording\x1b[0m");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::NamedTempFile;
+
+    fn result(command: &str, output: &str) -> PtyExecutionResult {
+        PtyExecutionResult {
+            output: output.to_string(),
+            stdout: None,
+            stderr: None,
+            exit_code: Some(1),
+            duration: Duration::from_millis(250),
+            command: command.to_string(),
+            interrupted: false,
+            env_snapshot: None,
+        }
+    }
+
+    #[test]
+    fn test_record_and_read_back() {
+        let file = NamedTempFile::new().unwrap();
+        let mut recorder = SessionRecorder::create(file.path()).unwrap();
+        recorder
+            .record_at(
+                Duration::from_millis(1500),
+                SessionEvent::Command {
+                    result: PtyExecutionResult {
+                        env_snapshot: Some(HashMap::from([
+                            ("cwd".to_string(), "/srv/app".to_string()),
+                            (
+                                "DATABASE_URL".to_string(),
+                                "postgres://app:hunter2@db:5432/app".to_string(),
+                            ),
+                        ])),
+                        ..result("cat app.env", "DB_PASSWORD=hunter2\n")
+                    },
+                },
+            )
+            .unwrap();
+        recorder
+            .record(SessionEvent::Mentor {
+                text: "Check the file name\n".to_string(),
+            })
+            .unwrap();
+        drop(recorder);
+
+        let events = read_transcript(file.path()).unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            events[0].event,
+            SessionEvent::Start { version: 1, .. }
+        ));
+
+        assert_eq!(events[1].elapsed_ms, 1500);
+        let SessionEvent::Command { result } = &events[1].event else {
+            panic!("expected a command event");
+        };
+        assert_eq!(result.output, "DB_PASSWORD=[REDACTED]\n");
+        assert_eq!(result.duration, Duration::from_millis(250));
+        let snapshot = result.env_snapshot.as_ref().unwrap();
+        assert_eq!(snapshot["cwd"], "/srv/app");
+        assert_eq!(
+            snapshot["DATABASE_URL"],
+            "postgres://app:[REDACTED]@db:5432/app"
+        );
+
+        assert!(
+            matches!(&events[2].event, SessionEvent::Mentor { text } if text.contains("file name"))
+        );
+    }
+
+    #[test]
+    fn test_read_skips_unknown_lines() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            file.path(),
+            "{\"elapsed_ms\":0,\"event\":\"resize\",\"cols\":80}\n\
+             {\"elapsed_ms\":5,\"event\":\"mentor\",\"text\":\"hi\"}\n",
+        )
+        .unwrap();
+
+        let events = read_transcript(file.path()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].elapsed_ms, 5);
+    }
+}
@@ -1,36 +1,39 @@
 use anyhow::Result;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 
-use crate::agent::{AgentLoop, AgentStep, StepType};
+use crate::agent::{AgentLoop, AgentStep, CheckpointStore, CommandPolicy, StepType};
 use crate::ai::AIManager;
-use crate::audit::AgentAuditLogger;
+use crate::audit::{AgentAuditLogger, ToolMetrics};
 use crate::config::Config;
 use crate::target::Target;
 use crate::tools::ToolContext;
+use crate::ui::agent_panel::LivePanel;
 
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Kaido AI Agent REPL
 pub struct KaidoREPL {
     ai_manager: AIManager,
-    tool_context: ToolContext,
     audit_logger: Option<AgentAuditLogger>,
     config: Config,
     json_mode: bool,
     target: Target,
+    /// Where runs are checkpointed for `kaido diagnose --resume`
+    checkpoints: Option<CheckpointStore>,
+    /// Per-tool outcomes and latency, for `kaido tools metrics`
+    metrics: Option<ToolMetrics>,
 }
 
 impl KaidoREPL {
     /// Create new agent REPL
     pub fn new() -> Result<Self> {
-        let config = Config::load().unwrap_or_else(|_| {
+        let config = Config::load_with_profile(None).unwrap_or_else(|_| {
             log::warn!("Failed to load config, using defaults");
             Config::default()
         });
 
         let ai_manager = AIManager::new(config.clone());
-        let tool_context = ToolContext::default();
 
         // Initialize audit logger
         let audit_logger = match Self::init_audit_logger() {
@@ -44,13 +47,22 @@ impl KaidoREPL {
             }
         };
 
+        let checkpoints = CheckpointStore::new()
+            .map_err(|e| log::warn!("Agent checkpoints disabled: {e}"))
+            .ok();
+
+        let metrics = ToolMetrics::new(&config.audit.database_path.to_string_lossy())
+            .map_err(|e| log::warn!("Tool metrics disabled: {e}"))
+            .ok();
+
         Ok(Self {
             ai_manager,
-            tool_context,
             audit_logger,
             config,
             json_mode: false,
             target: Target::Local,
+            checkpoints,
+            metrics,
         })
     }
 
@@ -149,42 +161,88 @@ impl KaidoREPL {
     }
 
     /// Run agent loop for a problem
-    async fn run_agent(&mut self, problem: &str) -> Result<()> {
+    pub async fn run_agent(&mut self, problem: &str) -> Result<()> {
+        let agent = AgentLoop::new(problem.to_string(), ToolContext::detect());
+        self.run_session(agent).await
+    }
+
+    /// Continue a checkpointed run where it stopped
+    pub async fn resume(&mut self, id: &str) -> Result<()> {
+        let store = self
+            .checkpoints
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Agent checkpoints are unavailable"))?;
+        let state = store.load(id)?;
+        if !self.json_mode {
+            println!(
+                "\x1b[38;5;147m◆\x1b[0m Resuming {id}: {} \x1b[2m({} steps so far)\x1b[0m",
+                state.task,
+                state.history.len()
+            );
+        }
+        let agent = AgentLoop::resume(state, ToolContext::detect());
+        self.run_session(agent).await
+    }
+
+    async fn run_session(&mut self, agent: AgentLoop) -> Result<()> {
+        let problem = agent.state().task.clone();
+        let problem = problem.as_str();
+
         // Generate session ID
         let session_id = uuid::Uuid::new_v4().to_string();
 
-        // Log session start
+        // Log session start (failures are reported by the logger, once)
         if let Some(logger) = &self.audit_logger {
-            logger.log_session_start(&session_id, problem)?;
+            let _ = logger.log_session_start(&session_id, problem);
         }
 
-        let mut agent = AgentLoop::new(problem.to_string(), self.tool_context.clone())
-            .with_explain_mode(self.config.display.explain_mode);
+        let mut agent = agent
+            .with_explain_mode(self.config.display.explain_mode)
+            .with_policy(CommandPolicy::from_config(&self.config.agent)?);
+        if let Some(store) = &self.checkpoints {
+            agent = agent.with_checkpoints(store.clone());
+        }
+        if let Some(metrics) = &self.metrics {
+            agent = agent.with_metrics(metrics.clone());
+        }
 
-        // Set up progress callback with audit logging
-        let session_id_clone = session_id.clone();
-        let logger_clone = self.audit_logger.clone();
-        let callback = move |step: &AgentStep| {
-            Self::display_step_static(step);
+        // Ctrl+C stops the running command instead of killing kaido
+        let cancel = agent.cancel_token();
+        let interrupt = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancel.cancel();
+            }
+        });
 
-            // Log step to audit
-            if let Some(logger) = &logger_clone {
-                let _ = logger.log_step(&session_id_clone, step);
+        // Run until complete, stopping to ask whenever the agent has a
+        // question; in JSON mode the question ends the run
+        let interactive = io::stdin().is_terminal() && !self.json_mode;
+        let final_state = loop {
+            let panel = self.start_panel(problem);
+            agent = agent.with_progress_callback(self.step_callback(&panel, &session_id));
+            let state = agent.run_until_complete(&self.ai_manager).await;
+            if let Some(panel) = panel {
+                panel.finish();
+            }
+            let state = match state {
+                Ok(state) => state,
+                Err(e) => {
+                    interrupt.abort();
+                    return Err(e);
+                }
+            };
+            if state.pending_question().is_none() || !interactive || !agent.answer_from_stdin() {
+                break agent.state().clone();
             }
         };
-
-        agent = agent.with_progress_callback(callback);
-
-        // Run until complete
-        let final_state = agent.run_until_complete(&self.ai_manager).await?;
-
-        // Log session end
-        if let Some(logger) = &self.audit_logger {
-            logger.log_session_end(&session_id, &final_state)?;
-        }
+        interrupt.abort();
 
         // Output JSON if in json mode
         if self.json_mode {
+            if let Some(logger) = &self.audit_logger {
+                let _ = logger.log_session_end(&session_id, &final_state);
+            }
+
             let result = serde_json::json!({
                 "task": final_state.task,
                 "status": format!("{:?}", final_state.status),
@@ -244,9 +302,67 @@ impl KaidoREPL {
 
         println!("\x1b[38;5;250m╰─\x1b[0m");
 
+        if final_state.is_resumable() && self.checkpoints.is_some() {
+            println!(
+                "\x1b[2mResume with `kaido diagnose --resume {}`\x1b[0m",
+                final_state.id
+            );
+        }
+
+        // Let the user walk the fix plan one command at a time
+        if final_state
+            .solution_plan
+            .as_ref()
+            .is_some_and(|plan| !plan.is_empty())
+        {
+            let outcome = agent.execute_plan_interactive().await?;
+            println!(
+                "\n\x1b[38;5;245mplan: {} succeeded, {} failed, {} skipped\x1b[0m",
+                outcome.succeeded, outcome.failed, outcome.skipped
+            );
+        }
+
+        // Log session end
+        if let Some(logger) = &self.audit_logger {
+            let _ = logger.log_session_end(&session_id, agent.state());
+        }
+
         Ok(())
     }
 
+    /// Live panel for the agent's steps on a terminal; None means steps
+    /// are printed as plain blocks
+    fn start_panel(&self, problem: &str) -> Option<LivePanel> {
+        if !io::stdout().is_terminal() || self.json_mode {
+            return None;
+        }
+        LivePanel::start(problem)
+            .map_err(|e| log::warn!("Live agent panel unavailable: {e}"))
+            .ok()
+    }
+
+    /// Progress callback showing each step and logging it to the audit log
+    fn step_callback(
+        &self,
+        panel: &Option<LivePanel>,
+        session_id: &str,
+    ) -> impl Fn(&AgentStep) + Send + 'static {
+        let show: Box<dyn Fn(&AgentStep) + Send> = match panel {
+            Some(panel) => Box::new(panel.callback()),
+            None => Box::new(Self::display_step_static),
+        };
+        let session_id = session_id.to_string();
+        let logger = self.audit_logger.clone();
+        move |step: &AgentStep| {
+            show(step);
+
+            // Log step to audit
+            if let Some(logger) = &logger {
+                let _ = logger.log_step(&session_id, step);
+            }
+        }
+    }
+
     /// Display a single agent step (static version for callback)
     fn display_step_static(step: &AgentStep) {
         match step.step_type {
@@ -322,6 +438,20 @@ impl KaidoREPL {
                 }
                 println!("\x1b[38;5;245m╰─\x1b[0m");
             }
+            StepType::Skipped => {
+                println!(
+                    "\x1b[38;5;242m↷ skipped #{}: {}\x1b[0m",
+                    step.step_number, step.content
+                );
+            }
+            StepType::Interrupted => {
+                println!("\x1b[38;5;203m✗ interrupted\x1b[0m");
+            }
+            // The question is printed by the prompt that reads the answer
+            StepType::Question => {}
+            StepType::Answer => {
+                println!("\x1b[38;5;245m↳ noted: {}\x1b[0m", step.content);
+            }
         }
     }
 
@@ -2,14 +2,18 @@
 //
 // Provides proper signal handling for shell behavior:
 // - SIGWINCH: Terminal resize
-// - SIGINT: Interrupt (Ctrl+C) - handled by rustyline
+// - SIGINT: Interrupt (Ctrl+C) - rustyline at the prompt, `Interrupts`
+//   while a command runs
 // - SIGTSTP: Suspend (Ctrl+Z) - not yet implemented
 //
-// Note: Most signal handling is done through rustyline's ReadlineError
-// for Ctrl+C and Ctrl+D. This module focuses on SIGWINCH for terminal resize.
+// Note: At the prompt Ctrl+C and Ctrl+D arrive through rustyline's
+// ReadlineError. While a command runs, `Interrupts` catches SIGINT so kaido
+// survives it and the executor passes it on to the command alone.
 
+use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 /// Terminal size tracking with atomic updates
 #[derive(Debug, Clone)]
@@ -59,6 +63,12 @@ impl TerminalSize {
     /// Returns true if the size changed
     pub fn update(&self) -> bool {
         let (new_cols, new_rows) = Self::get_current_size();
+        self.set(new_cols, new_rows)
+    }
+
+    /// Record a new size, shared with every clone of this tracker
+    /// Returns true if the size changed
+    pub fn set(&self, new_cols: u16, new_rows: u16) -> bool {
         let old_cols = self.cols.swap(new_cols, Ordering::Relaxed);
         let old_rows = self.rows.swap(new_rows, Ordering::Relaxed);
 
@@ -91,7 +101,8 @@ impl Default for TerminalSize {
 /// Currently handles:
 /// - Terminal resize (SIGWINCH)
 ///
-/// Note: SIGINT and EOF are handled by rustyline directly.
+/// Note: SIGINT and EOF at the prompt are handled by rustyline directly,
+/// SIGINT during a command by [`Interrupts`].
 pub struct SignalHandler {
     terminal_size: TerminalSize,
     #[cfg(unix)]
@@ -186,6 +197,102 @@ impl Default for SignalHandler {
     }
 }
 
+/// Ctrl+C presses while a command runs
+///
+/// Dropping it stops listening. Once SIGINT has been listened for, tokio
+/// keeps its handler for the rest of the process, so a Ctrl+C that arrives
+/// between commands no longer kills kaido either.
+pub struct Interrupts {
+    rx: mpsc::UnboundedReceiver<()>,
+    listener: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl Interrupts {
+    /// Listen for SIGINT, i.e. Ctrl+C on the terminal
+    ///
+    /// Must be called within a tokio runtime.
+    #[cfg(unix)]
+    pub fn from_sigint() -> io::Result<Self> {
+        let mut sigint = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let listener = tokio::spawn(async move {
+            while sigint.recv().await.is_some() {
+                if tx.send(()).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            rx,
+            listener: Some(listener),
+        })
+    }
+
+    /// Listen for SIGINT (never fires on non-Unix)
+    #[cfg(not(unix))]
+    pub fn from_sigint() -> io::Result<Self> {
+        Ok(Self::channel().1)
+    }
+
+    /// SIGINT if it can be caught, otherwise interrupts that never come
+    pub fn for_terminal() -> Self {
+        Self::from_sigint().unwrap_or_else(|e| {
+            log::warn!("Ctrl+C can't be caught while commands run: {e}");
+            Self::channel().1
+        })
+    }
+
+    /// Interrupts raised through the returned sender instead of a signal
+    pub fn channel() -> (mpsc::UnboundedSender<()>, Self) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (tx, Self { rx, listener: None })
+    }
+
+    /// Wait for the next interrupt; never returns once no sender is left
+    pub async fn recv(&mut self) {
+        if self.rx.recv().await.is_none() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+impl Drop for Interrupts {
+    fn drop(&mut self) {
+        if let Some(listener) = self.listener.take() {
+            listener.abort();
+        }
+    }
+}
+
+/// Send SIGINT to every process in process group `pgid`, and nothing else
+#[cfg(unix)]
+pub fn interrupt_process_group(pgid: u32) -> io::Result<()> {
+    let status = std::process::Command::new("kill")
+        .args(["-INT", "--", &format!("-{pgid}")])
+        .stderr(std::process::Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("kill -INT -{pgid} failed")))
+    }
+}
+
+/// Print what an interrupted command wrote, leaving the terminal clean
+///
+/// Erases the `^C` the terminal echoed while the command ran, ends the
+/// output on a fresh line, and undoes colors or a hidden cursor the command
+/// left behind when it was cut off.
+pub fn print_interrupted_output(out: &mut impl Write, output: &str) -> io::Result<()> {
+    out.write_all(b"\r\x1b[K")?;
+    out.write_all(output.as_bytes())?;
+    if !output.is_empty() && !output.ends_with('\n') {
+        out.write_all(b"\n")?;
+    }
+    out.write_all(b"\x1b[0m\x1b[?25h")?;
+    out.flush()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +334,35 @@ mod tests {
         assert!(size.1 > 0);
     }
 
+    #[tokio::test]
+    async fn test_interrupts_channel() {
+        let (tx, mut interrupts) = Interrupts::channel();
+        tx.send(()).unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(1), interrupts.recv())
+            .await
+            .expect("interrupt delivered");
+
+        // No sender left: waits forever instead of firing
+        drop(tx);
+        let waited =
+            tokio::time::timeout(std::time::Duration::from_millis(50), interrupts.recv()).await;
+        assert!(waited.is_err());
+    }
+
+    #[test]
+    fn test_print_interrupted_output() {
+        let mut out = Vec::new();
+        print_interrupted_output(&mut out, "\x1b[?25l\x1b[32mcopying 40%").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\r\x1b[K\x1b[?25l\x1b[32mcopying 40%\n\x1b[0m\x1b[?25h"
+        );
+
+        let mut out = Vec::new();
+        print_interrupted_output(&mut out, "").unwrap();
+        assert_eq!(out, b"\r\x1b[K\x1b[0m\x1b[?25h");
+    }
+
     #[test]
     fn test_signal_handler_check_resize() {
         let handler = SignalHandler::new();
@@ -1,12 +1,33 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
 
+use super::config_snapshot::{default_snapshot_dir, ConfigSnapshot};
+use super::error_log::{config_files, directive_paths, tail_first, ErrorLogTail};
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolContext,
-    Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolCapabilities,
+    ToolContext, Translation,
 };
 
+/// Where Apache keeps its config on Debian, RHEL and Homebrew installs
+const APACHE_CONFIG_DIRS: &[&str] = &[
+    "/etc/apache2",
+    "/etc/httpd",
+    "/usr/local/etc/httpd",
+    "/opt/homebrew/etc/httpd",
+];
+
+/// Where Apache logs errors unless told otherwise
+const APACHE_ERROR_LOGS: &[&str] = &[
+    "/var/log/apache2/error.log",
+    "/var/log/httpd/error_log",
+    "/usr/local/var/log/httpd/error_log",
+    "/opt/homebrew/var/log/httpd/error_log",
+];
+
+/// Debian's envvars default for ${APACHE_LOG_DIR}
+const APACHE_LOG_DIR: &str = "/var/log/apache2";
+
 /// Apache2/httpd web server tool
 pub struct Apache2Tool;
 
@@ -71,6 +92,26 @@ impl Apache2Tool {
         ))
     }
 
+    /// Snapshot store for the Apache config (~/.kaido/snapshots/apache2/)
+    pub fn config_snapshot() -> Option<ConfigSnapshot> {
+        let config_root = APACHE_CONFIG_DIRS
+            .iter()
+            .map(Path::new)
+            .find(|p| p.is_dir())?;
+        Some(ConfigSnapshot::new(
+            config_root,
+            default_snapshot_dir("apache2")?,
+        ))
+    }
+
+    /// Unified diff of the current config against the snapshot taken on the
+    /// last successful reload
+    pub fn config_diff() -> Result<String> {
+        Self::config_snapshot()
+            .ok_or_else(|| anyhow::anyhow!("Could not find Apache config directory"))?
+            .diff()
+    }
+
     /// Get apache version
     pub async fn get_version() -> Result<String> {
         let cmd = Self::get_apache_cmd().await;
@@ -115,6 +156,57 @@ impl Apache2Tool {
             String::from_utf8_lossy(&output.stderr)
         ))
     }
+
+    /// Error logs to try, custom `ErrorLog` directives before the defaults
+    pub fn error_log_paths() -> Vec<PathBuf> {
+        let configured = APACHE_CONFIG_DIRS
+            .iter()
+            .map(Path::new)
+            .find(|p| p.is_dir())
+            .map(|root| {
+                config_files(
+                    root,
+                    &["apache2.conf", "httpd.conf", "conf/httpd.conf"],
+                    &["sites-enabled", "conf.d", "conf-enabled"],
+                )
+                .iter()
+                .filter_map(|file| std::fs::read_to_string(file).ok())
+                .flat_map(|config| directive_paths(&config, "ErrorLog"))
+                .filter_map(|path| resolve_log_path(root, &path))
+                .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut paths = configured;
+        for default in APACHE_ERROR_LOGS.iter().map(PathBuf::from) {
+            if !paths.contains(&default) {
+                paths.push(default);
+            }
+        }
+        paths
+    }
+
+    /// Last `lines` lines of the Apache error log
+    pub fn tail_error_log(lines: usize) -> Result<ErrorLogTail> {
+        tail_first(&Self::error_log_paths(), lines)
+            .map_err(|_| anyhow::anyhow!("Could not find Apache error log"))
+    }
+}
+
+/// File an `ErrorLog` value points at, relative to the ServerRoot
+///
+/// None for piped loggers (`|/usr/bin/rotatelogs ...`) and syslog.
+fn resolve_log_path(server_root: &Path, value: &str) -> Option<PathBuf> {
+    if value.starts_with('|') || value.starts_with("syslog") {
+        return None;
+    }
+    let value = value.replace("${APACHE_LOG_DIR}", APACHE_LOG_DIR);
+    let path = Path::new(&value);
+    Some(if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        server_root.join(path)
+    })
 }
 
 impl Default for Apache2Tool {
@@ -172,7 +264,7 @@ impl Tool for Apache2Tool {
         }
 
         let parsed: ApacheResponse =
-            serde_json::from_str(&llm_response.reasoning).unwrap_or(ApacheResponse {
+            crate::ai::parse_json(&llm_response.reasoning).unwrap_or(ApacheResponse {
                 command: llm_response.command.clone(),
                 confidence: llm_response.confidence,
                 reasoning: llm_response.reasoning.clone(),
@@ -227,22 +319,30 @@ impl Tool for Apache2Tool {
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        let start = Instant::now();
+        super::ensure_owned(self, command)?;
+        // Execute command via shell
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c").arg(command);
+        Ok(ExecutionResult::capture(&mut shell).await?)
+    }
 
-        let output = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()
-            .await?;
+    fn binaries(&self) -> Vec<&'static str> {
+        // Debian/Ubuntu name first, then RHEL/CentOS and the control scripts
+        vec!["apache2", "httpd", "apache2ctl", "apachectl"]
+    }
 
-        let duration = start.elapsed();
+    fn version_args(&self) -> &'static [&'static str] {
+        &["-v"]
+    }
 
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration,
-        })
+    fn runs_in_shell(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default()
+            .with_explain_error()
+            .with_dry_run()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
@@ -309,6 +409,23 @@ mod tests {
         assert_eq!(tool.detect_intent("kubectl get pods"), 0.0);
     }
 
+    #[test]
+    fn test_resolve_log_path() {
+        let root = Path::new("/etc/httpd");
+        assert_eq!(
+            resolve_log_path(root, "${APACHE_LOG_DIR}/error.log"),
+            Some(PathBuf::from("/var/log/apache2/error.log"))
+        );
+        assert_eq!(
+            resolve_log_path(root, "logs/error_log"),
+            Some(PathBuf::from("/etc/httpd/logs/error_log"))
+        );
+        assert_eq!(
+            resolve_log_path(root, "|/usr/sbin/rotatelogs x 86400"),
+            None
+        );
+    }
+
     #[test]
     fn test_classify_risk() {
         let tool = Apache2Tool::new();
@@ -0,0 +1,174 @@
+// Whether each tool's program is actually installed
+//
+// Registry presence only means kaido knows the tool; this looks the binary
+// up on PATH and asks it for its version, so a missing `drush` shows up in
+// `kaido tools list` rather than as a command-not-found mid-task.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::{Tool, ToolRegistry};
+
+/// Longest version string shown
+const MAX_VERSION_LEN: usize = 60;
+
+/// Installation status of one tool
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolAvailability {
+    /// Tool name as registered
+    pub name: String,
+    /// Binary found on PATH, if any
+    pub binary: Option<PathBuf>,
+    /// First line of the binary's version output (only when probed)
+    pub version: Option<String>,
+}
+
+impl ToolAvailability {
+    /// Whether the tool's program is installed
+    pub fn is_available(&self) -> bool {
+        self.binary.is_some()
+    }
+}
+
+impl ToolRegistry {
+    /// Look up every tool's binary on PATH; `probe_versions` also runs it
+    /// to read its version
+    pub fn availability(&self, probe_versions: bool) -> Vec<ToolAvailability> {
+        let path_var = std::env::var_os("PATH").unwrap_or_default();
+        self.list_tools()
+            .into_iter()
+            .filter_map(|name| self.get_tool(name))
+            .map(|tool| check_tool(tool, &path_var, probe_versions))
+            .collect()
+    }
+}
+
+/// Availability of one tool, trying its binaries in order
+fn check_tool(tool: &dyn Tool, path_var: &OsStr, probe_version: bool) -> ToolAvailability {
+    let binary = tool
+        .binaries()
+        .iter()
+        .find_map(|name| find_in_path(name, path_var));
+    let version = binary
+        .as_deref()
+        .filter(|_| probe_version)
+        .and_then(|path| probe_version_of(path, tool.version_args()));
+    ToolAvailability {
+        name: tool.name().to_string(),
+        binary,
+        version,
+    }
+}
+
+/// First non-empty line the binary prints for its version flag
+fn probe_version_of(binary: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new(binary).args(args).output().ok()?;
+    // nginx and apache print their version to stderr
+    let text = [output.stdout, output.stderr]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).to_string())
+        .find(|text| !text.trim().is_empty())?;
+    first_version_line(&text)
+}
+
+fn first_version_line(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    Some(if line.chars().count() > MAX_VERSION_LEN {
+        let cut: String = line.chars().take(MAX_VERSION_LEN).collect();
+        format!("{cut}...")
+    } else {
+        line.to_string()
+    })
+}
+
+/// First `PATH` entry holding an executable file called `name`
+pub fn find_in_path(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Table of tools with a ✓/✗ mark; `detailed` adds path and version
+pub fn format_availability(entries: &[ToolAvailability], detailed: bool) -> String {
+    let width = entries.iter().map(|e| e.name.len()).max().unwrap_or(4);
+    let mut out = String::new();
+    for entry in entries {
+        let (mark, status) = match &entry.binary {
+            Some(_) => ("\x1b[32m✓\x1b[0m", "installed"),
+            None => ("\x1b[31m✗\x1b[0m", "not installed"),
+        };
+        out.push_str(&format!("  {mark} {:<width$}  {status}", entry.name));
+        if detailed {
+            if let Some(binary) = &entry.binary {
+                out.push_str(&format!("  \x1b[2m{}\x1b[0m", binary.display()));
+            }
+            if let Some(version) = &entry.version {
+                out.push_str(&format!("\n    {:<width$}  {version}", ""));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_availability_finds_and_probes_binaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let docker = dir.path().join("docker");
+        std::fs::write(
+            &docker,
+            "#!/bin/sh\necho 'Docker version 27.1.1, build 6312585'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&docker, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let path_var = dir.path().as_os_str().to_owned();
+
+        let registry = ToolRegistry::new();
+        let docker_tool = registry.get_tool("docker").unwrap();
+        let found = check_tool(docker_tool, &path_var, true);
+        assert_eq!(found.binary, Some(docker));
+        assert_eq!(
+            found.version.as_deref(),
+            Some("Docker version 27.1.1, build 6312585")
+        );
+
+        let missing = check_tool(registry.get_tool("drush").unwrap(), &path_var, true);
+        assert!(!missing.is_available());
+        assert_eq!(missing.version, None);
+
+        let table = format_availability(&[found, missing], true);
+        assert!(table.contains("docker  installed"));
+        assert!(table.contains("drush   not installed"));
+        assert!(table.contains("Docker version 27.1.1"));
+    }
+
+    #[test]
+    fn test_first_version_line() {
+        assert_eq!(
+            first_version_line("\n  nginx version: nginx/1.25.3\nbuilt by gcc\n").as_deref(),
+            Some("nginx version: nginx/1.25.3")
+        );
+        assert_eq!(first_version_line("   \n"), None);
+        assert!(first_version_line(&"x".repeat(100))
+            .unwrap()
+            .ends_with("..."));
+    }
+}
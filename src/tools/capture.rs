@@ -0,0 +1,143 @@
+// Bounded output capture for tool commands
+//
+// `kubectl logs` or `journalctl` can print gigabytes. Reading everything and
+// truncating afterwards still holds all of it in memory, so tools capture at
+// most `max_capture_bytes()` of each stream while the command runs, keeping
+// the tail where errors usually are.
+
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+
+use super::ExecutionResult;
+
+/// Bytes of stdout and of stderr kept per command by default
+pub const DEFAULT_MAX_CAPTURE_BYTES: usize = 1024 * 1024;
+
+static MAX_CAPTURE_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_CAPTURE_BYTES);
+
+/// Current per-stream capture limit
+pub fn max_capture_bytes() -> usize {
+    MAX_CAPTURE_BYTES.load(Ordering::Relaxed)
+}
+
+/// Set the per-stream capture limit (from `truncation.capture_bytes`)
+pub fn set_max_capture_bytes(bytes: usize) {
+    MAX_CAPTURE_BYTES.store(bytes.max(1), Ordering::Relaxed);
+}
+
+impl ExecutionResult {
+    /// Run `command` capturing at most `max_capture_bytes()` of each stream
+    ///
+    /// Stdin is closed, as with `Command::output`. `truncated` is set when
+    /// either stream went over the limit. The command is killed if the
+    /// returned future is dropped before it exits.
+    pub async fn capture(command: &mut Command) -> std::io::Result<Self> {
+        Self::capture_with_limit(command, max_capture_bytes()).await
+    }
+
+    /// Like [`ExecutionResult::capture`] with an explicit limit
+    pub async fn capture_with_limit(command: &mut Command, limit: usize) -> std::io::Result<Self> {
+        let start = Instant::now();
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let (stdout, stderr, status) = tokio::try_join!(
+            read_tail(stdout, limit),
+            read_tail(stderr, limit),
+            child.wait()
+        )?;
+
+        Ok(Self {
+            exit_code: status.code().unwrap_or(-1),
+            truncated: stdout.1 || stderr.1,
+            stdout: stdout.0,
+            stderr: stderr.0,
+            duration: start.elapsed(),
+        })
+    }
+}
+
+/// Read a stream to the end, keeping its last `limit` bytes
+async fn read_tail(
+    mut reader: impl AsyncRead + Unpin,
+    limit: usize,
+) -> std::io::Result<(String, bool)> {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut chunk = [0u8; 8192];
+
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        kept.extend_from_slice(&chunk[..read]);
+        // Drop the front in batches rather than on every read
+        if kept.len() > limit * 2 {
+            kept.drain(..kept.len() - limit);
+            truncated = true;
+        }
+    }
+    if kept.len() > limit {
+        kept.drain(..kept.len() - limit);
+        truncated = true;
+    }
+
+    // The cut may land inside a UTF-8 sequence
+    let start = if truncated {
+        kept.iter()
+            .take(3)
+            .take_while(|byte| (**byte & 0xC0) == 0x80)
+            .count()
+    } else {
+        0
+    };
+    Ok((
+        String::from_utf8_lossy(&kept[start..]).into_owned(),
+        truncated,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_keeps_tail() {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("seq 1 20000; echo 'error: disk full' >&2");
+        let result = ExecutionResult::capture_with_limit(&mut command, 64)
+            .await
+            .unwrap();
+
+        assert!(result.truncated);
+        assert!(result.stdout.len() <= 64);
+        assert!(result.stdout.ends_with("19999\n20000\n"));
+        assert_eq!(result.stderr, "error: disk full\n");
+        assert_eq!(result.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn test_capture_small_output_untouched() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("echo ok; exit 3");
+        let result = ExecutionResult::capture_with_limit(&mut command, 64)
+            .await
+            .unwrap();
+
+        assert!(!result.truncated);
+        assert_eq!(result.stdout, "ok\n");
+        assert_eq!(result.exit_code, 3);
+    }
+}
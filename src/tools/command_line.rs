@@ -0,0 +1,217 @@
+// Command line structure
+//
+// Just enough shell parsing to see which programs a command line runs. It
+// splits at `|`, `;`, `&&`, `||`, `&` and newlines outside quotes, and finds
+// each segment's program past `sudo`, `env` and `VAR=value` prefixes. Tools
+// use it to refuse commands that would run someone else's program, and the
+// agent policy and risk checks use it to look at every part of a chain.
+
+/// Programs a tool's output may be piped through
+const OUTPUT_FILTERS: [&str; 8] = ["grep", "egrep", "head", "tail", "wc", "sort", "uniq", "cut"];
+
+/// Service managers that may act on a tool's own service
+const SERVICE_MANAGERS: [&str; 3] = ["systemctl", "service", "journalctl"];
+
+/// One simple command of a command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub text: &'a str,
+    /// Reads the previous segment's output through `|`
+    pub piped: bool,
+}
+
+/// Split a command line into its simple commands
+pub fn segments(command: &str) -> Vec<Segment<'_>> {
+    let bytes = command.as_bytes();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut piped = false;
+    let mut quote = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match quote {
+            Some(q) if byte == q => quote = None,
+            Some(_) => {}
+            None if byte == b'\'' || byte == b'"' => quote = Some(byte),
+            None if byte == b'\\' => i += 1,
+            None if matches!(byte, b'|' | b';' | b'&' | b'\n') => {
+                let doubled = bytes.get(i + 1) == Some(&byte) && byte != b'\n';
+                // `2>&1` and `&>` are redirects, not separators
+                let redirect = byte == b'&'
+                    && (i > 0 && bytes[i - 1] == b'>' || bytes.get(i + 1) == Some(&b'>'));
+                if !redirect {
+                    push_segment(&mut segments, &command[start..i], piped);
+                    piped = byte == b'|' && !doubled;
+                    i += usize::from(doubled);
+                    start = i + 1;
+                }
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    push_segment(&mut segments, &command[start.min(command.len())..], piped);
+    segments
+}
+
+fn push_segment<'a>(segments: &mut Vec<Segment<'a>>, text: &'a str, piped: bool) {
+    let text = text.trim();
+    if !text.is_empty() {
+        segments.push(Segment { text, piped });
+    }
+}
+
+/// Program a simple command runs, without its directory
+pub fn program(segment: &str) -> Option<&str> {
+    let mut words = segment.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        match word {
+            "sudo" | "env" | "exec" | "nohup" | "time" => {
+                // Their own options come before the program
+                while words.peek().is_some_and(|w| w.starts_with('-')) {
+                    words.next();
+                }
+            }
+            _ if is_assignment(word) => {}
+            _ => return Some(word.rsplit('/').next().unwrap_or(word)),
+        }
+    }
+    None
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Whether the command runs a command substitution (`$(...)` or backticks)
+pub fn has_substitution(command: &str) -> bool {
+    command.contains("$(") || command.contains('`')
+}
+
+/// Whether the command redirects output into a file (`>`, `>>`, `&>`)
+///
+/// Duplicating a descriptor (`2>&1`) and discarding into `/dev/null`
+/// don't count.
+pub fn writes_to_file(command: &str) -> bool {
+    let bytes = command.as_bytes();
+    let mut quote = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match quote {
+            Some(q) if byte == q => quote = None,
+            Some(_) => {}
+            None if byte == b'\'' || byte == b'"' => quote = Some(byte),
+            None if byte == b'\\' => i += 1,
+            None if byte == b'>' => {
+                let target = command[i + 1..].trim_start_matches('>').trim_start();
+                let duplicate = target.starts_with('&') && !target.starts_with("&>");
+                let discarded = target.starts_with("/dev/null");
+                if !duplicate && !discarded {
+                    return true;
+                }
+                while bytes.get(i + 1) == Some(&b'>') {
+                    i += 1;
+                }
+            }
+            None => {}
+        }
+        i += 1;
+    }
+    false
+}
+
+/// Whether every program `command` runs belongs to a tool
+///
+/// Each segment must start with one of `binaries` or `helpers`, or be a
+/// service manager acting on one of the binaries (`systemctl reload
+/// nginx`); piped segments may also be plain output filters. With
+/// `pipelines` false the command must be a single segment, for tools that
+/// run it without a shell.
+pub fn runs_only(command: &str, binaries: &[&str], helpers: &[&str], pipelines: bool) -> bool {
+    if has_substitution(command) {
+        return false;
+    }
+    let segments = segments(command);
+    if segments.is_empty() || (!pipelines && segments.len() > 1) {
+        return false;
+    }
+
+    segments.iter().all(|segment| {
+        let Some(program) = program(segment.text) else {
+            return false;
+        };
+        binaries.contains(&program)
+            || helpers.contains(&program)
+            || (segment.piped && OUTPUT_FILTERS.contains(&program))
+            || (SERVICE_MANAGERS.contains(&program)
+                && segment
+                    .text
+                    .split_whitespace()
+                    .any(|word| binaries.contains(&word.trim_end_matches(".service"))))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(command: &str) -> Vec<(&str, bool)> {
+        segments(command)
+            .into_iter()
+            .map(|s| (s.text, s.piped))
+            .collect()
+    }
+
+    #[test]
+    fn test_segments_split_outside_quotes() {
+        assert_eq!(
+            texts("ls; rm -rf / && echo 'a|b' || true | wc -l 2>&1 &"),
+            vec![
+                ("ls", false),
+                ("rm -rf /", false),
+                ("echo 'a|b'", false),
+                ("true", false),
+                ("wc -l 2>&1", true),
+            ]
+        );
+        assert_eq!(program("sudo -E FOO=1 /usr/bin/docker ps"), Some("docker"));
+        assert_eq!(program("X=1"), None);
+    }
+
+    #[test]
+    fn test_writes_to_file() {
+        assert!(writes_to_file("echo hi > out.txt"));
+        assert!(writes_to_file("cat a >> b"));
+        assert!(writes_to_file("make &> build.log"));
+        assert!(!writes_to_file("ls 2>&1 | grep x"));
+        assert!(!writes_to_file("find / -name x 2>/dev/null"));
+        assert!(!writes_to_file("grep '>' notes.md"));
+    }
+
+    #[test]
+    fn test_runs_only_own_programs() {
+        let nginx = ["nginx"];
+        assert!(runs_only("nginx -t", &nginx, &[], true));
+        assert!(runs_only(
+            "sudo systemctl reload nginx.service",
+            &nginx,
+            &[],
+            true
+        ));
+        assert!(runs_only("nginx -T | grep server_name", &nginx, &[], true));
+        assert!(!runs_only("rm -rf /", &nginx, &[], true));
+        assert!(!runs_only("nginx -t && rm -rf /", &nginx, &[], true));
+        assert!(!runs_only("systemctl stop postgresql", &nginx, &[], true));
+        assert!(!runs_only("nginx -t $(rm -rf /)", &nginx, &[], true));
+        assert!(!runs_only("grep x | nginx", &nginx, &[], true));
+
+        assert!(runs_only("docker ps -a", &["docker"], &[], false));
+        assert!(!runs_only("docker ps | grep api", &["docker"], &[], false));
+    }
+}
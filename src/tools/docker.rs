@@ -1,10 +1,45 @@
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Tool, ToolContext, Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Tool, ToolCapabilities, ToolContext,
+    Translation,
 };
 use anyhow::Result;
 use async_trait::async_trait;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+
+/// Compose file names, in the order `docker compose` looks for them
+const COMPOSE_FILES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Phrases that talk about a whole compose stack
+const STACK_PHRASES: [&str; 8] = [
+    "the stack",
+    "bring up",
+    "bring down",
+    "spin up",
+    "tear down",
+    "all services",
+    "all the services",
+    "compose",
+];
+
+/// Verbs that act on one compose service
+const SERVICE_VERBS: [&str; 8] = [
+    "restart", "start", "stop", "logs", "rebuild", "build", "exec", "shell",
+];
+
+/// Global `docker compose` options that take a value
+const COMPOSE_VALUE_FLAGS: [&str; 6] = [
+    "-f",
+    "--file",
+    "-p",
+    "--project-name",
+    "--env-file",
+    "--profile",
+];
 
 /// Docker tool implementation
 pub struct DockerTool {
@@ -109,6 +144,94 @@ impl DockerTool {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Compose file in `dir`, if there is one
+    pub fn find_compose_file(dir: &Path) -> Option<PathBuf> {
+        COMPOSE_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    /// Service names declared under the top-level `services:` key
+    pub fn compose_services(content: &str) -> Vec<String> {
+        let mut services = Vec::new();
+        let mut in_services = false;
+        let mut indent = None;
+
+        for line in content.lines() {
+            let trimmed = line.trim_end();
+            if trimmed.trim_start().is_empty() || trimmed.trim_start().starts_with('#') {
+                continue;
+            }
+            let depth = trimmed.len() - trimmed.trim_start().len();
+            if depth == 0 {
+                in_services = trimmed == "services:";
+                indent = None;
+                continue;
+            }
+            if !in_services || *indent.get_or_insert(depth) != depth {
+                continue;
+            }
+            if let Some(name) = trimmed.trim_start().strip_suffix(':') {
+                services.push(name.trim_matches(|c| c == '"' || c == '\'').to_string());
+            }
+        }
+
+        services
+    }
+
+    /// How likely `input` asks for a compose operation on a stack with
+    /// these services
+    fn compose_intent(input: &str, services: &[String]) -> f32 {
+        let lower = input.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+            .collect();
+
+        let names_service = services
+            .iter()
+            .any(|service| words.contains(&service.to_lowercase().as_str()));
+        if names_service && SERVICE_VERBS.iter().any(|verb| words.contains(verb)) {
+            return 0.9;
+        }
+        if STACK_PHRASES.iter().any(|phrase| lower.contains(phrase)) {
+            return 0.85;
+        }
+        0.0
+    }
+
+    /// Point a `docker compose` command at `file` unless it names one
+    pub fn scope_to_compose_file(command: &str, file: &str) -> String {
+        let rest = command
+            .strip_prefix("docker compose ")
+            .or_else(|| command.strip_prefix("docker-compose "));
+        match rest {
+            Some(rest) if !rest.split_whitespace().any(|w| w == "-f" || w == "--file") => {
+                format!("docker compose -f {file} {rest}")
+            }
+            _ => command.to_string(),
+        }
+    }
+
+    /// Subcommand and its arguments, for `docker compose` commands
+    fn compose_subcommand(command: &str) -> Option<(&str, Vec<&str>)> {
+        let mut words = command.split_whitespace();
+        match words.next()? {
+            "docker-compose" => {}
+            "docker" if words.next()? == "compose" => {}
+            _ => return None,
+        }
+
+        while let Some(word) = words.next() {
+            if COMPOSE_VALUE_FLAGS.contains(&word) {
+                words.next();
+            } else if !word.starts_with('-') {
+                return Some((word, words.collect()));
+            }
+        }
+        None
+    }
+
     /// Get docker network information
     pub async fn inspect_network(network_name: &str) -> Result<String> {
         let output = tokio::process::Command::new("docker")
@@ -155,6 +278,20 @@ impl Tool for DockerTool {
             return 1.0;
         }
 
+        // Stack phrasing, when the working directory has a compose file
+        let compose_file = std::env::current_dir()
+            .ok()
+            .and_then(|dir| Self::find_compose_file(&dir));
+        if let Some(file) = compose_file {
+            let services = std::fs::read_to_string(&file)
+                .map(|content| Self::compose_services(&content))
+                .unwrap_or_default();
+            let score = Self::compose_intent(input, &services);
+            if score > 0.0 {
+                return score;
+            }
+        }
+
         // Docker keywords
         let docker_keywords = [
             "container",
@@ -184,6 +321,22 @@ impl Tool for DockerTool {
         context: &ToolContext,
         llm: &dyn LLMBackend,
     ) -> Result<Translation> {
+        let compose_file = Self::find_compose_file(&context.working_directory);
+        let compose_context = match &compose_file {
+            Some(file) => {
+                let services = std::fs::read_to_string(file)
+                    .map(|content| Self::compose_services(&content))
+                    .unwrap_or_default();
+                format!(
+                    "- Compose file: {} (services: {})\n\
+                     Use `docker compose -f <file> ...` for anything about the stack or its services.",
+                    file.display(),
+                    services.join(", ")
+                )
+            }
+            None => "- Compose file: none".to_string(),
+        };
+
         let prompt = format!(
             r#"
 Translate the following natural language to a Docker command.
@@ -193,6 +346,7 @@ User Input: {input}
 Context:
 - Working Directory: {pwd}
 - Docker Host: {docker_host}
+{compose_context}
 
 Common Docker operations:
 - ps: list containers
@@ -204,6 +358,7 @@ Common Docker operations:
 - rm/rmi: remove containers/images
 - build: build image from Dockerfile
 - pull/push: registry operations
+- compose up -d / down / restart <service> / logs <service>: compose stacks
 
 Output JSON format:
 {{
@@ -218,9 +373,13 @@ Output JSON format:
         );
 
         let result = llm.infer(&prompt).await?;
+        let command = match compose_file.as_deref().and_then(Path::file_name) {
+            Some(name) => Self::scope_to_compose_file(&result.command, &name.to_string_lossy()),
+            None => result.command,
+        };
 
         Ok(Translation {
-            command: result.command,
+            command,
             confidence: result.confidence,
             reasoning: result.reasoning,
             tool_name: "docker".to_string(),
@@ -236,6 +395,23 @@ Output JSON format:
             log::debug!("Docker command targeting host: {docker_host}");
         }
 
+        if let Some((subcommand, args)) = Self::compose_subcommand(&cmd) {
+            return match subcommand {
+                // Removes named volumes, and the data in them
+                "down"
+                    if args
+                        .iter()
+                        .any(|a| *a == "-v" || a.starts_with("--volumes")) =>
+                {
+                    RiskLevel::Critical
+                }
+                "down" | "rm" | "kill" => RiskLevel::High,
+                "up" | "start" | "stop" | "restart" | "build" | "pull" | "push" | "create"
+                | "run" | "exec" | "pause" | "unpause" | "scale" | "cp" => RiskLevel::Medium,
+                _ => RiskLevel::Low,
+            };
+        }
+
         // CRITICAL: Batch deletion with command substitution
         if cmd.contains("rm") && (cmd.contains("$(") || cmd.contains("`")) {
             return RiskLevel::Critical;
@@ -268,13 +444,8 @@ Output JSON format:
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        let start = Instant::now();
-
-        // Parse command into parts
+        super::ensure_owned(self, command)?;
         let parts: Vec<&str> = command.split_whitespace().collect();
-        if parts.is_empty() {
-            return Err(anyhow::anyhow!("Empty command"));
-        }
 
         // Use docker_cli_path for execution
         let docker_cmd = if parts[0] == "docker" {
@@ -284,19 +455,17 @@ Output JSON format:
         };
 
         // Execute command
-        let output = tokio::process::Command::new(docker_cmd)
-            .args(&parts[1..])
-            .output()
-            .await?;
+        let mut process = tokio::process::Command::new(docker_cmd);
+        process.args(&parts[1..]);
+        Ok(ExecutionResult::capture(&mut process).await?)
+    }
 
-        let duration = start.elapsed();
+    fn helper_programs(&self) -> &'static [&'static str] {
+        &["docker-compose"]
+    }
 
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration,
-        })
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default().with_explain_error()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
@@ -339,4 +508,78 @@ mod tests {
             RiskLevel::Medium
         );
     }
+
+    #[test]
+    fn test_compose_risk_classification() {
+        let tool = DockerTool::new();
+        let ctx = ToolContext::default();
+        let risk = |command| tool.classify_risk(command, &ctx);
+
+        assert_eq!(risk("docker compose ps"), RiskLevel::Low);
+        assert_eq!(
+            risk("docker compose -f compose.yaml logs web"),
+            RiskLevel::Low
+        );
+        assert_eq!(risk("docker compose up -d"), RiskLevel::Medium);
+        assert_eq!(risk("docker-compose restart web"), RiskLevel::Medium);
+        assert_eq!(risk("docker compose down"), RiskLevel::High);
+        assert_eq!(
+            risk("docker compose -f compose.yaml down -v"),
+            RiskLevel::Critical
+        );
+        assert_eq!(risk("docker-compose down --volumes"), RiskLevel::Critical);
+    }
+
+    #[test]
+    fn test_compose_file_and_services() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(DockerTool::find_compose_file(dir.path()), None);
+
+        let file = dir.path().join("docker-compose.yml");
+        std::fs::write(
+            &file,
+            "version: '3'\nservices:\n  web:\n    image: nginx\n    ports:\n      - 8080:80\n\n  # cache\n  redis:\n    image: redis\nvolumes:\n  data:\n",
+        )
+        .unwrap();
+        assert_eq!(
+            DockerTool::find_compose_file(dir.path()),
+            Some(file.clone())
+        );
+
+        let services = DockerTool::compose_services(&std::fs::read_to_string(&file).unwrap());
+        assert_eq!(services, vec!["web", "redis"]);
+    }
+
+    #[test]
+    fn test_compose_intent() {
+        let services = vec!["web".to_string(), "db".to_string()];
+        let intent = |input| DockerTool::compose_intent(input, &services);
+
+        assert_eq!(intent("restart the web service"), 0.9);
+        assert_eq!(intent("show logs for db"), 0.9);
+        assert_eq!(intent("bring up the stack"), 0.85);
+        assert_eq!(intent("tear down everything"), 0.85);
+        // Not a compose service
+        assert_eq!(intent("restart the nginx service"), 0.0);
+    }
+
+    #[test]
+    fn test_scope_to_compose_file() {
+        assert_eq!(
+            DockerTool::scope_to_compose_file("docker compose restart web", "compose.yaml"),
+            "docker compose -f compose.yaml restart web"
+        );
+        assert_eq!(
+            DockerTool::scope_to_compose_file("docker-compose up -d", "docker-compose.yml"),
+            "docker compose -f docker-compose.yml up -d"
+        );
+        assert_eq!(
+            DockerTool::scope_to_compose_file("docker compose -f other.yml ps", "compose.yaml"),
+            "docker compose -f other.yml ps"
+        );
+        assert_eq!(
+            DockerTool::scope_to_compose_file("docker ps", "compose.yaml"),
+            "docker ps"
+        );
+    }
 }
@@ -1,28 +1,107 @@
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolContext,
-    Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolCapabilities,
+    ToolContext, Translation,
 };
 use anyhow::Result;
 use async_trait::async_trait;
+use regex::Regex;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::sync::OnceLock;
 
 /// Drush tool implementation (Drupal CLI)
 pub struct DrushTool {
     drush_path: PathBuf,
+    /// Site alias patterns treated as production
+    production_aliases: Vec<Regex>,
 }
 
 impl DrushTool {
+    /// Create a Drush tool using the production aliases from config.toml
     pub fn new() -> Self {
+        let patterns = crate::config::Config::load()
+            .map(|config| config.drush.production_aliases)
+            .unwrap_or_else(|_| crate::config::DrushConfig::default().production_aliases);
+
         Self {
             drush_path: which::which("drush").unwrap_or_else(|_| PathBuf::from("vendor/bin/drush")),
+            production_aliases: vec![],
         }
+        .with_production_aliases(&patterns)
+    }
+
+    /// Treat aliases matching these `*` wildcard patterns as production
+    pub fn with_production_aliases(mut self, patterns: &[String]) -> Self {
+        self.production_aliases = patterns
+            .iter()
+            .filter_map(|pattern| {
+                let regex = format!("(?i)^{}$", regex::escape(pattern).replace("\\*", ".*"));
+                Regex::new(&regex).ok()
+            })
+            .collect();
+        self
     }
 
     /// Get drush CLI path
     pub fn cli_path(&self) -> &PathBuf {
         &self.drush_path
     }
+
+    /// Whether a site alias (`@prod.mysite`, `@mysite.live`) is production
+    ///
+    /// Each dot-separated part is checked, covering both the Drush 8
+    /// `@env.site` and Drush 9+ `@site.env` conventions.
+    pub fn is_production_alias(&self, alias: &str) -> bool {
+        alias
+            .trim_start_matches('@')
+            .split('.')
+            .any(|part| self.production_aliases.iter().any(|re| re.is_match(part)))
+    }
+}
+
+/// Drush command line split into site alias and subcommand
+#[derive(Debug, PartialEq, Eq)]
+struct DrushInvocation<'a> {
+    /// Target site alias; for `sql:sync @a @b` the destination
+    alias: Option<&'a str>,
+    /// Subcommand name (`sql:drop`, `cr`, ...)
+    subcommand: Option<&'a str>,
+}
+
+impl<'a> DrushInvocation<'a> {
+    fn parse(command: &'a str) -> Self {
+        let mut words = command
+            .split_whitespace()
+            .skip_while(|word| !word.ends_with("drush"))
+            .skip(1);
+
+        let mut alias = None;
+        let mut subcommand = None;
+        for word in words.by_ref() {
+            if word.starts_with('@') {
+                alias = Some(word);
+            } else if !word.starts_with('-') && subcommand.is_none() {
+                subcommand = Some(word);
+                break;
+            }
+        }
+        // Sync commands take source and target aliases after the subcommand
+        if let Some(target) = words.filter(|word| word.starts_with('@')).last() {
+            alias = Some(target);
+        }
+
+        Self { alias, subcommand }
+    }
+}
+
+/// SQL that deletes or rewrites data
+fn is_destructive_sql(command: &str) -> bool {
+    static DESTRUCTIVE: OnceLock<Regex> = OnceLock::new();
+    DESTRUCTIVE
+        .get_or_init(|| {
+            Regex::new(r"(?i)\b(drop|truncate|delete\s+from|alter\s+table|update\s+\w+\s+set)\b")
+                .unwrap()
+        })
+        .is_match(command)
 }
 
 impl Default for DrushTool {
@@ -98,40 +177,67 @@ Output JSON format:
     }
 
     fn classify_risk(&self, command: &str, context: &ToolContext) -> RiskLevel {
-        let cmd = command.to_lowercase();
-
         // Log working directory for Drush context
         log::debug!(
             "Drush command in directory: {}",
             context.working_directory.display()
         );
 
-        // HIGH: Database operations, cache clear
-        if cmd.contains("sql:drop") || cmd.contains("sql-drop") {
-            return RiskLevel::High;
+        let invocation = DrushInvocation::parse(command);
+        let production = invocation
+            .alias
+            .is_some_and(|alias| self.is_production_alias(alias));
+        let subcommand = invocation.subcommand.unwrap_or("").to_lowercase();
+
+        match subcommand.as_str() {
+            // Drops every table
+            "sql:drop" | "sql-drop" | "site:install" | "site-install" | "si" => {
+                if production {
+                    RiskLevel::Critical
+                } else {
+                    RiskLevel::High
+                }
+            }
+            // Raw SQL: risk depends on what it runs
+            "sql:cli" | "sql-cli" | "sqlc" | "sql:query" | "sql-query" | "sqlq" => {
+                match (is_destructive_sql(command), production) {
+                    (true, true) => RiskLevel::Critical,
+                    (true, false) | (false, true) => RiskLevel::High,
+                    (false, false) => RiskLevel::Medium,
+                }
+            }
+            // Overwrites the target database or files
+            "sql:sync" | "sql-sync" | "core:rsync" | "rsync" => {
+                if production {
+                    RiskLevel::Critical
+                } else {
+                    RiskLevel::Medium
+                }
+            }
+            // A cold cache on a busy production site can take it down
+            "cache:rebuild" | "cache-rebuild" | "cr" | "rebuild" | "cache:clear" | "cc" => {
+                if production {
+                    RiskLevel::Critical
+                } else {
+                    RiskLevel::Medium
+                }
+            }
+            "config:import" | "config-import" | "cim" | "updatedb" | "updb" => {
+                if production {
+                    RiskLevel::High
+                } else {
+                    RiskLevel::Medium
+                }
+            }
+            // Anything else against production deserves a second look
+            _ if production => RiskLevel::Medium,
+            _ => RiskLevel::Low,
         }
-
-        // MEDIUM: Import/export, cache operations
-        if cmd.contains("cim")
-            || cmd.contains("sql:cli")
-            || cmd.contains("sqlq")
-            || cmd.contains("cr")
-        {
-            return RiskLevel::Medium;
-        }
-
-        // LOW: Read-only operations
-        RiskLevel::Low
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        let start = Instant::now();
-
-        // Parse command
+        super::ensure_owned(self, command)?;
         let parts: Vec<&str> = command.split_whitespace().collect();
-        if parts.is_empty() {
-            return Err(anyhow::anyhow!("Empty command"));
-        }
 
         // Use drush_path for execution if this is a drush command
         let cmd_path = if parts[0].contains("drush") {
@@ -141,19 +247,15 @@ Output JSON format:
         };
 
         // Execute
-        let output = tokio::process::Command::new(cmd_path)
-            .args(&parts[1..])
-            .output()
-            .await?;
-
-        let duration = start.elapsed();
-
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration,
-        })
+        let mut process = tokio::process::Command::new(cmd_path);
+        process.args(&parts[1..]);
+        Ok(ExecutionResult::capture(&mut process).await?)
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default()
+            .with_explain_error()
+            .with_dry_run()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
@@ -225,6 +327,60 @@ mod tests {
         assert_eq!(tool.detect_intent("kubectl get pods"), 0.0);
     }
 
+    #[test]
+    fn test_parse_invocation() {
+        assert_eq!(
+            DrushInvocation::parse("vendor/bin/drush @prod.mysite sql:drop -y"),
+            DrushInvocation {
+                alias: Some("@prod.mysite"),
+                subcommand: Some("sql:drop"),
+            }
+        );
+        assert_eq!(
+            DrushInvocation::parse("drush -y sql:sync @mysite.prod @mysite.local"),
+            DrushInvocation {
+                alias: Some("@mysite.local"),
+                subcommand: Some("sql:sync"),
+            }
+        );
+        assert_eq!(
+            DrushInvocation::parse("drush status"),
+            DrushInvocation {
+                alias: None,
+                subcommand: Some("status"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_production_alias_risk() {
+        let tool =
+            DrushTool::new().with_production_aliases(&["prod*".to_string(), "live".to_string()]);
+        let context = ToolContext::default();
+        let risk = |command| tool.classify_risk(command, &context);
+
+        assert!(tool.is_production_alias("@prod.mysite"));
+        assert!(tool.is_production_alias("@mysite.live"));
+        assert!(!tool.is_production_alias("@mysite.liverpool"));
+        assert!(!tool.is_production_alias("@mysite.dev"));
+
+        assert_eq!(risk("drush @prod.mysite sql-drop"), RiskLevel::Critical);
+        assert_eq!(risk("drush @mysite.dev sql-drop"), RiskLevel::High);
+        assert_eq!(risk("drush @mysite.live cr"), RiskLevel::Critical);
+        assert_eq!(risk("drush cr"), RiskLevel::Medium);
+        assert_eq!(
+            risk("drush @prod sqlq \"DELETE FROM users\""),
+            RiskLevel::Critical
+        );
+        assert_eq!(risk("drush @prod sqlq \"SELECT 1\""), RiskLevel::High);
+        assert_eq!(risk("drush sqlq \"SELECT 1\""), RiskLevel::Medium);
+        assert_eq!(risk("drush @prod status"), RiskLevel::Medium);
+        assert_eq!(risk("drush status --format=script"), RiskLevel::Low);
+        // Pulling prod down is fine; pushing over it is not
+        assert_eq!(risk("drush sql:sync @prod @local"), RiskLevel::Medium);
+        assert_eq!(risk("drush sql:sync @local @prod"), RiskLevel::Critical);
+    }
+
     #[test]
     fn test_extract_filename() {
         assert_eq!(
@@ -0,0 +1,159 @@
+// Web server error logs
+//
+// When nginx or Apache refuses a config or answers 5xx, the reason is
+// usually one line in its error log. This finds the log (custom
+// `error_log`/`ErrorLog` directives first, then the distro defaults) and
+// reads its last lines without loading the whole file.
+
+use anyhow::Result;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes read from the end of a log; plenty for a few dozen lines
+const TAIL_WINDOW_BYTES: u64 = 64 * 1024;
+
+/// What reading a web server error log turned up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorLogTail {
+    /// The last lines of the log
+    Entries { path: PathBuf, lines: String },
+    /// The log exists but only root can read it
+    PermissionDenied { path: PathBuf },
+}
+
+impl ErrorLogTail {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Entries { path, .. } | Self::PermissionDenied { path } => path,
+        }
+    }
+}
+
+/// Tail the first readable log among `candidates`
+///
+/// A log that exists but can't be read is reported only if no other
+/// candidate is readable. Errors when none of them exist.
+pub fn tail_first(candidates: &[PathBuf], lines: usize) -> Result<ErrorLogTail> {
+    let mut denied = None;
+    for path in candidates {
+        match tail_lines(path, lines) {
+            Ok(tail) => {
+                return Ok(ErrorLogTail::Entries {
+                    path: path.clone(),
+                    lines: tail,
+                })
+            }
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                denied.get_or_insert_with(|| path.clone());
+            }
+            Err(_) => {}
+        }
+    }
+
+    denied
+        .map(|path| ErrorLogTail::PermissionDenied { path })
+        .ok_or_else(|| anyhow::anyhow!("No error log found"))
+}
+
+/// Last `lines` lines of a file, reading at most the final 64 KiB
+pub fn tail_lines(path: &Path, lines: usize) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(TAIL_WINDOW_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let text = String::from_utf8_lossy(&bytes);
+    let mut all: Vec<&str> = text.lines().collect();
+    if start > 0 && !all.is_empty() {
+        // The window most likely starts mid-line
+        all.remove(0);
+    }
+    Ok(all[all.len().saturating_sub(lines)..].join("\n"))
+}
+
+/// Files a server's config is spread over: the main file, then one level
+/// of include directories
+pub fn config_files(root: &Path, main_files: &[&str], include_dirs: &[&str]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = main_files
+        .iter()
+        .map(|name| root.join(name))
+        .filter(|path| path.is_file())
+        .collect();
+
+    for dir in include_dirs {
+        let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        let mut included: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        included.sort();
+        files.extend(included);
+    }
+    files
+}
+
+/// Log paths named by `directive` in a config file, in order
+///
+/// Matches the directive case-insensitively and drops quotes and a
+/// trailing `;`, so it reads both `error_log /x.log warn;` and
+/// `ErrorLog "/x.log"`.
+pub fn directive_paths(config: &str, directive: &str) -> Vec<String> {
+    config
+        .lines()
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut words = line.split_whitespace();
+            if !words.next()?.eq_ignore_ascii_case(directive) {
+                return None;
+            }
+            let path = words
+                .next()?
+                .trim_end_matches(';')
+                .trim_matches(|c| c == '"' || c == '\'');
+            (!path.is_empty()).then(|| path.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_directive_paths() {
+        let nginx = "user www-data;\n\
+            error_log /srv/logs/nginx-error.log warn;\n\
+            # error_log /commented/out.log;\n\
+            http {\n    error_log  /srv/logs/site.log;\n}\n";
+        assert_eq!(
+            directive_paths(nginx, "error_log"),
+            vec!["/srv/logs/nginx-error.log", "/srv/logs/site.log"]
+        );
+
+        let apache = "ServerRoot \"/etc/httpd\"\nErrorLog \"logs/error_log\"\n";
+        assert_eq!(directive_paths(apache, "ErrorLog"), vec!["logs/error_log"]);
+    }
+
+    #[test]
+    fn test_tail_first_skips_missing_logs() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("error.log");
+        let content: String = (1..=100).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(&log, content).unwrap();
+
+        let tail = tail_first(&[dir.path().join("missing.log"), log.clone()], 3).unwrap();
+        assert_eq!(
+            tail,
+            ErrorLogTail::Entries {
+                path: log,
+                lines: "line 98\nline 99\nline 100".to_string(),
+            }
+        );
+        assert!(tail_first(&[dir.path().join("missing.log")], 3).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Tool, ToolContext, Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Tool, ToolCapabilities, ToolContext,
+    Translation,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -101,7 +102,7 @@ Output JSON format:
         let result = llm.infer(&prompt).await?;
 
         Ok(Translation {
-            command: result.command,
+            command: crate::kubectl::translator::enforce_output_format(input, &result.command)?,
             confidence: result.confidence,
             reasoning: result.reasoning,
             tool_name: "kubectl".to_string(),
@@ -162,16 +163,33 @@ Output JSON format:
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        // Reuse existing kubectl executor (sync function)
-        let kubectl_result = crate::kubectl::execute_kubectl(command)?;
-
-        // Convert kubectl::ExecutionResult to tools::ExecutionResult
-        Ok(ExecutionResult {
-            exit_code: kubectl_result.exit_code.unwrap_or(-1),
-            stdout: kubectl_result.stdout,
-            stderr: kubectl_result.stderr,
-            duration: std::time::Duration::from_millis(kubectl_result.execution_duration_ms as u64),
-        })
+        let parts: Vec<&str> = command.split_whitespace().collect();
+        if parts.first() != Some(&"kubectl") || !self.owns_command(command) {
+            return Err(anyhow::anyhow!(
+                "Command must be a single 'kubectl' invocation"
+            ));
+        }
+
+        // Capture bounded output; `kubectl logs` can be arbitrarily long
+        let mut process = tokio::process::Command::new("kubectl");
+        process.args(&parts[1..]);
+        match ExecutionResult::capture(&mut process).await {
+            Ok(result) => Ok(result),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(anyhow::anyhow!(
+                "kubectl command not found. Please install kubectl: https://kubernetes.io/docs/tasks/tools/"
+            )),
+            Err(e) => Err(anyhow::anyhow!("Failed to execute kubectl: {e}")),
+        }
+    }
+
+    fn version_args(&self) -> &'static [&'static str] {
+        &["version", "--client"]
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default()
+            .with_explain_error()
+            .with_dry_run()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
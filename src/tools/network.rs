@@ -1,12 +1,258 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use std::time::Instant;
+use std::fmt;
+use std::process::Command;
+use std::time::Duration;
 
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolContext,
-    Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolCapabilities,
+    ToolContext, Translation,
 };
 
+/// A process holding a port open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Command name (as truncated by lsof/ss)
+    pub name: String,
+    /// Owner login, when the lookup tool could see it
+    pub user: Option<String>,
+}
+
+impl ProcessInfo {
+    /// Command that stops this process
+    pub fn kill_command(&self) -> String {
+        format!("kill {}", self.pid)
+    }
+}
+
+impl fmt::Display for ProcessInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (pid {}", self.name, self.pid)?;
+        if let Some(ref user) = self.user {
+            write!(f, ", user {user}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Marks the `-w` line curl prints after the response body
+const TIMING_MARKER: &str = "__kaido_timing__";
+
+/// curl `-w` format: phase times in seconds since the start, then the status
+const TIMING_FORMAT: &str = "\\n__kaido_timing__ %{time_namelookup} %{time_connect} \
+                             %{time_appconnect} %{time_starttransfer} %{time_total} %{http_code}\\n";
+
+/// How long `http_timing` waits for a response
+const HTTP_TIMING_TIMEOUT_SECS: u32 = 10;
+
+/// Where the time of an HTTP request went, from curl's `-w` timers
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpTiming {
+    /// Name lookup done
+    pub dns: Duration,
+    /// TCP connection established
+    pub connect: Duration,
+    /// TLS handshake done; zero for plain HTTP or when it never finished
+    pub tls: Duration,
+    /// First response byte received
+    pub ttfb: Duration,
+    pub total: Duration,
+    /// HTTP status, 0 when no response came back
+    pub status: u16,
+    /// curl's exit code
+    pub exit_code: i32,
+}
+
+impl HttpTiming {
+    /// Parse a `-w` line: `__kaido_timing__ <dns> <connect> <tls> <ttfb> <total> <status>`
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.trim().strip_prefix(TIMING_MARKER)?.split_whitespace();
+        let mut time = || -> Option<Duration> {
+            Duration::try_from_secs_f64(fields.next()?.replace(',', ".").parse().ok()?).ok()
+        };
+        let (dns, connect, tls, ttfb, total) = (time()?, time()?, time()?, time()?, time()?);
+        Some(Self {
+            dns,
+            connect,
+            tls,
+            ttfb,
+            total,
+            status: fields.next()?.parse().ok()?,
+            exit_code: 0,
+        })
+    }
+
+    /// One line per phase, as durations of each phase rather than curl's
+    /// running totals
+    pub fn summary(&self) -> String {
+        let ms = |d: Duration| format!("{}ms", d.as_millis());
+        let handshake_done = if self.tls.is_zero() {
+            self.connect
+        } else {
+            self.tls
+        };
+        let mut phases = vec![format!("DNS {}", ms(self.dns))];
+        if !self.connect.is_zero() {
+            phases.push(format!(
+                "connect {}",
+                ms(self.connect.saturating_sub(self.dns))
+            ));
+        }
+        if !self.tls.is_zero() {
+            phases.push(format!("TLS {}", ms(self.tls.saturating_sub(self.connect))));
+        }
+        if !self.ttfb.is_zero() {
+            phases.push(format!(
+                "first byte {}",
+                ms(self.ttfb.saturating_sub(handshake_done))
+            ));
+        }
+        phases.push(format!("total {}", ms(self.total)));
+        let status = match self.status {
+            0 => "no response".to_string(),
+            status => format!("HTTP {status}"),
+        };
+        format!("{} · {status}", phases.join(" → "))
+    }
+
+    /// Which phase failed, when the request did
+    pub fn diagnosis(&self) -> Option<String> {
+        let diagnosis = match self.exit_code {
+            0 if self.status >= 500 => format!(
+                "Server error {}: DNS, connect and TLS worked; the application behind the \
+                 server failed after {}ms",
+                self.status,
+                self.ttfb.as_millis()
+            ),
+            0 => return None,
+            6 => "DNS lookup failed: the host name doesn't resolve".to_string(),
+            7 => "Connect failed: nothing accepted the TCP connection (refused or unreachable)"
+                .to_string(),
+            28 => format!("Timed out {}", self.stalled_phase()),
+            35 => "TLS handshake failed: the connection opened but client and server couldn't \
+                   agree on TLS"
+                .to_string(),
+            51 | 60 => "TLS certificate rejected: the server's certificate isn't trusted or \
+                        doesn't match the host"
+                .to_string(),
+            52 => "The server closed the connection without sending a response".to_string(),
+            56 => "The connection was reset while receiving the response".to_string(),
+            code => format!("curl failed (exit code {code})"),
+        };
+        Some(diagnosis)
+    }
+
+    /// The phase a timed-out request was stuck in
+    fn stalled_phase(&self) -> &'static str {
+        if self.dns.is_zero() {
+            "during DNS lookup"
+        } else if self.connect.is_zero() {
+            "while connecting; a firewall may be dropping packets"
+        } else if self.ttfb.is_zero() && self.tls.is_zero() {
+            "during the TLS handshake or waiting for the first byte"
+        } else if self.ttfb.is_zero() {
+            "waiting for the server's first byte"
+        } else {
+            "while downloading the response"
+        }
+    }
+
+    /// Summary plus diagnosis, as shown under a curl command
+    pub fn report(&self) -> String {
+        let mut report = format!("timing: {}\n", self.summary());
+        if let Some(diagnosis) = self.diagnosis() {
+            report.push_str(&format!("diagnosis: {diagnosis}\n"));
+        }
+        report
+    }
+}
+
+/// URL of a plain `curl` command, None for anything else or pipelines
+pub fn curl_url(command: &str) -> Option<String> {
+    let stages = crate::ai::explainer::tokenize_command(command).ok()?;
+    let [tokens] = stages.as_slice() else {
+        return None;
+    };
+    if tokens.first().map(String::as_str) != Some("curl") {
+        return None;
+    }
+    tokens
+        .iter()
+        .find(|t| t.starts_with("http://") || t.starts_with("https://"))
+        .cloned()
+}
+
+/// curl options that change what a request sends or how it connects
+const REQUEST_SHAPING_FLAGS: &[&str] = &[
+    "-X",
+    "--request",
+    "-H",
+    "--header",
+    "-d",
+    "--data",
+    "--json",
+    "-F",
+    "--form",
+    "-u",
+    "--user",
+    "-b",
+    "--cookie",
+    "-T",
+    "--upload-file",
+    "-I",
+    "--head",
+    "-k",
+    "--insecure",
+    "-E",
+    "--cert",
+    "-x",
+    "--proxy",
+    "--resolve",
+    "--connect-to",
+    "--oauth2-bearer",
+];
+
+/// URL of a curl command that sends a bare GET, which is safe to request
+/// again as is
+///
+/// None when the command sets a method, headers, a body, credentials or
+/// connection options: a bare re-request would time something else.
+pub fn plain_get_url(command: &str) -> Option<String> {
+    let url = curl_url(command)?;
+    let shaped = command.split_whitespace().any(|token| {
+        REQUEST_SHAPING_FLAGS.iter().any(|flag| {
+            token == *flag
+                || (flag.starts_with("--") && token.starts_with(flag))
+                || (!flag.starts_with("--") && !token.starts_with("--") && token.starts_with(flag))
+        })
+    });
+    (!shaped).then_some(url)
+}
+
+/// A plain curl command with the timing `-w` appended, unless it has its own
+fn with_timing(command: &str) -> Option<String> {
+    curl_url(command)?;
+    let own_format = command
+        .split_whitespace()
+        .any(|t| t == "-w" || t.starts_with("--write-out"));
+    (!own_format).then(|| format!("{command} -w '{TIMING_FORMAT}'"))
+}
+
+/// Output of a timed curl with the timing line taken out
+fn split_timing(stdout: &str) -> (String, Option<HttpTiming>) {
+    let Some(start) = stdout.rfind(&format!("\n{TIMING_MARKER}")) else {
+        return (stdout.to_string(), None);
+    };
+    let line = stdout[start + 1..].lines().next().unwrap_or_default();
+    let end = start + 1 + line.len();
+    let rest = stdout[end..].strip_prefix('\n').unwrap_or(&stdout[end..]);
+    (
+        format!("{}{rest}", &stdout[..start]),
+        HttpTiming::parse(line),
+    )
+}
+
 /// Network diagnostic tool
 /// Provides network troubleshooting commands: netstat, ss, lsof, iptables, ufw, etc.
 pub struct NetworkTool;
@@ -52,6 +298,41 @@ impl NetworkTool {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Processes listening on `port`
+    ///
+    /// Uses `ss` on Linux (falling back to `lsof`) and `lsof` elsewhere.
+    /// Without root, processes owned by other users are usually invisible,
+    /// so an empty list means "nothing we can see", not "port is free".
+    pub fn who_uses_port(port: u16) -> Vec<ProcessInfo> {
+        if cfg!(target_os = "linux") {
+            let processes = Command::new("ss")
+                .args(["-H", "-ltnup", &format!("sport = :{port}")])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| parse_ss_output(&String::from_utf8_lossy(&output.stdout)))
+                .unwrap_or_default();
+            if !processes.is_empty() {
+                return processes
+                    .into_iter()
+                    .map(|mut process| {
+                        process.user = proc_owner(process.pid);
+                        process
+                    })
+                    .collect();
+            }
+        }
+
+        // -F prints one field per line, which stays stable across lsof
+        // versions and platforms unlike the column layout
+        Command::new("lsof")
+            .args(["-nP", &format!("-i:{port}"), "-sTCP:LISTEN", "-FpcL"])
+            .output()
+            .ok()
+            .map(|output| parse_lsof_output(&String::from_utf8_lossy(&output.stdout)))
+            .unwrap_or_default()
+    }
+
     /// Get firewall status (iptables or ufw)
     pub async fn get_firewall_status() -> Result<String> {
         // Try ufw first (Ubuntu/Debian)
@@ -102,6 +383,32 @@ impl NetworkTool {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Request `url` once and time each phase
+    ///
+    /// The body is discarded. A failed request still returns its timing,
+    /// with curl's exit code set; only a missing curl is an error.
+    pub async fn http_timing(url: &str) -> Result<HttpTiming> {
+        let output = tokio::process::Command::new("curl")
+            .args([
+                "-sS",
+                "-o",
+                "/dev/null",
+                "--max-time",
+                &HTTP_TIMING_TIMEOUT_SECS.to_string(),
+                "-w",
+                TIMING_FORMAT,
+                url,
+            ])
+            .output()
+            .await
+            .context("Failed to run curl")?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (_, timing) = split_timing(&stdout);
+        let mut timing = timing.context("curl printed no timing")?;
+        timing.exit_code = output.status.code().unwrap_or(-1);
+        Ok(timing)
+    }
+
     /// DNS lookup
     pub async fn dns_lookup(domain: &str) -> Result<String> {
         // Try dig first, fallback to nslookup
@@ -125,6 +432,94 @@ impl NetworkTool {
     }
 }
 
+/// Parse `lsof -F pcL` field output: a `p<pid>` line starts each process,
+/// followed by `c<command>` and `L<login>`, then its open files
+fn parse_lsof_output(output: &str) -> Vec<ProcessInfo> {
+    let mut processes: Vec<ProcessInfo> = Vec::new();
+    for line in output.lines() {
+        let Some(field) = line.chars().next() else {
+            continue;
+        };
+        let value = &line[field.len_utf8()..];
+        match field {
+            'p' => {
+                if let Ok(pid) = value.parse() {
+                    processes.push(ProcessInfo {
+                        pid,
+                        name: String::new(),
+                        user: None,
+                    });
+                }
+            }
+            'c' => {
+                if let Some(process) = processes.last_mut() {
+                    process.name = value.to_string();
+                }
+            }
+            'L' if !value.is_empty() => {
+                if let Some(process) = processes.last_mut() {
+                    process.user = Some(value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    processes
+}
+
+/// Parse `ss -p` output, where each socket ends with
+/// `users:(("nginx",pid=1234,fd=6),("nginx",pid=1235,fd=6))`
+///
+/// ss doesn't report owners; those come from /proc afterwards.
+fn parse_ss_output(output: &str) -> Vec<ProcessInfo> {
+    let mut processes: Vec<ProcessInfo> = Vec::new();
+    for line in output.lines() {
+        let Some((_, users)) = line.split_once("users:(") else {
+            continue;
+        };
+        for entry in users.split("),(") {
+            let mut fields = entry.trim_matches(|c| c == '(' || c == ')').split(',');
+            let name = fields.next().unwrap_or_default().trim_matches('"');
+            let pid = fields
+                .find_map(|field| field.strip_prefix("pid="))
+                .and_then(|pid| pid.parse().ok());
+            if let Some(pid) = pid {
+                // Forked workers share the socket; list each process once
+                if !processes.iter().any(|p| p.pid == pid) {
+                    processes.push(ProcessInfo {
+                        pid,
+                        name: name.to_string(),
+                        user: None,
+                    });
+                }
+            }
+        }
+    }
+    processes
+}
+
+/// Login name owning a process, from /proc/<pid>/status and /etc/passwd
+fn proc_owner(pid: u32) -> Option<String> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    let uid = status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .to_string();
+
+    let login = std::fs::read_to_string("/etc/passwd")
+        .ok()
+        .and_then(|passwd| {
+            passwd.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                (fields.nth(1)? == uid).then(|| name.to_string())
+            })
+        });
+    Some(login.unwrap_or(uid))
+}
+
 impl Default for NetworkTool {
     fn default() -> Self {
         Self::new()
@@ -159,6 +554,10 @@ impl Tool for NetworkTool {
             "dig",
             "route",
             "ip addr",
+            "curl",
+            "latency",
+            "response time",
+            "ttfb",
         ];
 
         for keyword in &network_keywords {
@@ -192,7 +591,8 @@ impl Tool for NetworkTool {
             - ufw status (check UFW firewall)\n\
             - ping HOST (test connectivity)\n\
             - dig DOMAIN (DNS lookup)\n\
-            - ip addr show (show network interfaces)\n\n\
+            - ip addr show (show network interfaces)\n\
+            - curl -sS -o /dev/null URL (time an HTTP request; kaido adds the timing breakdown)\n\n\
             Respond ONLY with JSON:\n\
             {{\"command\": \"ss -tlnp\", \"confidence\": 90, \"reasoning\": \"Check listening ports\"}}\n\n\
             Your response:"
@@ -208,7 +608,7 @@ impl Tool for NetworkTool {
         }
 
         let parsed: NetworkResponse =
-            serde_json::from_str(&llm_response.reasoning).unwrap_or(NetworkResponse {
+            crate::ai::parse_json(&llm_response.reasoning).unwrap_or(NetworkResponse {
                 command: llm_response.command.clone(),
                 confidence: llm_response.confidence,
                 reasoning: llm_response.reasoning.clone(),
@@ -273,22 +673,59 @@ impl Tool for NetworkTool {
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        let start = Instant::now();
+        super::ensure_owned(self, command)?;
+        // Plain curl requests get a timing breakdown appended; it goes to
+        // stderr on failure, where the agent looks for the cause
+        let timed = with_timing(command);
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c").arg(timed.as_deref().unwrap_or(command));
+        let mut result = ExecutionResult::capture(&mut shell).await?;
 
-        let output = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()
-            .await?;
+        if timed.is_some() {
+            let (stdout, timing) = split_timing(&result.stdout);
+            result.stdout = stdout;
+            if let Some(mut timing) = timing {
+                timing.exit_code = result.exit_code;
+                let report = timing.report();
+                if result.exit_code == 0 && timing.status < 500 {
+                    result.stdout.push_str(&report);
+                } else {
+                    result.stderr.push_str(&report);
+                }
+            }
+        }
+        Ok(result)
+    }
 
-        let duration = start.elapsed();
+    fn binaries(&self) -> Vec<&'static str> {
+        vec!["ss", "netstat", "curl", "dig"]
+    }
 
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration,
-        })
+    fn helper_programs(&self) -> &'static [&'static str] {
+        &[
+            "ping",
+            "nc",
+            "netcat",
+            "telnet",
+            "nslookup",
+            "host",
+            "traceroute",
+            "tracepath",
+            "mtr",
+            "ip",
+            "lsof",
+            "iptables",
+            "ufw",
+            "wget",
+        ]
+    }
+
+    fn runs_in_shell(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default().with_explain_error()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
@@ -320,6 +757,121 @@ impl Tool for NetworkTool {
             });
         }
 
+        if error_lower.contains("could not resolve host") {
+            return Some(ErrorExplanation {
+                error_type: "DNS Lookup Failed".to_string(),
+                reason: "The host name in the URL doesn't resolve to an address".to_string(),
+                possible_causes: vec![
+                    "Typo in the host name".to_string(),
+                    "The DNS record is missing or hasn't propagated".to_string(),
+                    "The resolver in /etc/resolv.conf is unreachable".to_string(),
+                ],
+                solutions: vec![
+                    Solution {
+                        description: "Look the name up directly".to_string(),
+                        command: Some("dig <host>".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                    Solution {
+                        description: "Check which resolver is configured".to_string(),
+                        command: Some("cat /etc/resolv.conf".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                ],
+                recommended_solution: 0,
+                documentation_links: vec![],
+            });
+        }
+
+        if error_lower.contains("ssl certificate problem")
+            || error_lower.contains("ssl_connect")
+            || error_lower.contains("tls handshake")
+            || error_lower.contains("ssl: no alternative certificate subject name")
+        {
+            return Some(ErrorExplanation {
+                error_type: "TLS Handshake Failed".to_string(),
+                reason: "The TCP connection worked but TLS negotiation or certificate \
+                         verification failed"
+                    .to_string(),
+                possible_causes: vec![
+                    "The certificate expired or doesn't cover this host name".to_string(),
+                    "The certificate chain is incomplete or self-signed".to_string(),
+                    "The port speaks plain HTTP, not HTTPS".to_string(),
+                ],
+                solutions: vec![
+                    Solution {
+                        description: "Inspect the certificate the server presents".to_string(),
+                        command: Some(
+                            "openssl s_client -connect <host>:443 -servername <host> </dev/null"
+                                .to_string(),
+                        ),
+                        risk_level: RiskLevel::Low,
+                    },
+                    Solution {
+                        description: "Show the handshake step by step".to_string(),
+                        command: Some("curl -v <url>".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                ],
+                recommended_solution: 0,
+                documentation_links: vec![],
+            });
+        }
+
+        if error_lower.contains("operation timed out") || error_lower.contains("timed out after") {
+            return Some(ErrorExplanation {
+                error_type: "Request Timed Out".to_string(),
+                reason: "No answer arrived in time; the timing breakdown shows which phase \
+                         stalled"
+                    .to_string(),
+                possible_causes: vec![
+                    "A firewall drops packets instead of refusing them".to_string(),
+                    "The server is overloaded and slow to respond".to_string(),
+                    "A slow upstream (database, API) holds the response".to_string(),
+                ],
+                solutions: vec![
+                    Solution {
+                        description: "Check whether the port is reachable at all".to_string(),
+                        command: Some("nc -zv -w 5 <host> <port>".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                    Solution {
+                        description: "Check firewall rules".to_string(),
+                        command: Some("iptables -L -n".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                ],
+                recommended_solution: 0,
+                documentation_links: vec![],
+            });
+        }
+
+        if error_lower.contains("the requested url returned error: 5") {
+            return Some(ErrorExplanation {
+                error_type: "HTTP Server Error".to_string(),
+                reason: "The server was reached but the application behind it failed".to_string(),
+                possible_causes: vec![
+                    "The backend crashed or isn't running (502/503)".to_string(),
+                    "The backend is too slow for the proxy's timeout (504)".to_string(),
+                    "An unhandled error in the application (500)".to_string(),
+                ],
+                solutions: vec![
+                    Solution {
+                        description: "Read the web server's error log".to_string(),
+                        command: Some("tail -n 50 /var/log/nginx/error.log".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                    Solution {
+                        description: "Check the backend's listening ports".to_string(),
+                        command: Some("ss -tlnp".to_string()),
+                        risk_level: RiskLevel::Low,
+                    },
+                ],
+                recommended_solution: 0,
+                documentation_links: vec![],
+            });
+        }
+
         if error_lower.contains("network is unreachable")
             || error_lower.contains("no route to host")
         {
@@ -364,6 +916,52 @@ mod tests {
         assert_eq!(tool.detect_intent("kubectl get pods"), 0.0);
     }
 
+    #[test]
+    fn test_parse_lsof_output() {
+        let output = "p412\ncnginx\nLroot\nf6\nf7\np413\ncnginx\nLwww-data\nf6\n";
+        assert_eq!(
+            parse_lsof_output(output),
+            [
+                ProcessInfo {
+                    pid: 412,
+                    name: "nginx".to_string(),
+                    user: Some("root".to_string()),
+                },
+                ProcessInfo {
+                    pid: 413,
+                    name: "nginx".to_string(),
+                    user: Some("www-data".to_string()),
+                },
+            ]
+        );
+        assert!(parse_lsof_output("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_ss_output() {
+        let output = "\
+LISTEN 0      511          0.0.0.0:80         0.0.0.0:*    users:((\"nginx\",pid=1235,fd=6),(\"nginx\",pid=1234,fd=6))
+LISTEN 0      511             [::]:80            [::]:*    users:((\"nginx\",pid=1234,fd=7))
+LISTEN 0      128        127.0.0.1:5432       0.0.0.0:*
+";
+        let processes = parse_ss_output(output);
+        let pids: Vec<_> = processes.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [1235, 1234]);
+        assert_eq!(processes[0].name, "nginx");
+        assert_eq!(processes[0].user, None);
+    }
+
+    #[test]
+    fn test_process_info_display() {
+        let process = ProcessInfo {
+            pid: 88,
+            name: "node".to_string(),
+            user: Some("dev".to_string()),
+        };
+        assert_eq!(process.to_string(), "node (pid 88, user dev)");
+        assert_eq!(process.kill_command(), "kill 88");
+    }
+
     #[test]
     fn test_classify_risk() {
         let tool = NetworkTool::new();
@@ -378,4 +976,112 @@ mod tests {
         assert_eq!(tool.classify_risk("iptables -F", &ctx), RiskLevel::Critical);
         assert_eq!(tool.classify_risk("ufw disable", &ctx), RiskLevel::Critical);
     }
+
+    fn timing(line: &str) -> HttpTiming {
+        HttpTiming::parse(line).unwrap()
+    }
+
+    #[test]
+    fn test_timing_summary() {
+        let https = timing("__kaido_timing__ 0.012 0.031 0.084 0.210 0.340 502");
+        assert_eq!(
+            https.summary(),
+            "DNS 12ms → connect 19ms → TLS 53ms → first byte 126ms → total 340ms · HTTP 502"
+        );
+        let diagnosis = https.diagnosis().unwrap();
+        assert!(diagnosis.starts_with("Server error 502"), "{diagnosis}");
+
+        let http = timing("__kaido_timing__ 0,001 0,002 0,000 0,005 0,006 200");
+        assert_eq!(
+            http.summary(),
+            "DNS 1ms → connect 1ms → first byte 3ms → total 6ms · HTTP 200"
+        );
+        assert_eq!(http.diagnosis(), None);
+        assert!(HttpTiming::parse("__kaido_timing__ 0.1 oops").is_none());
+    }
+
+    #[test]
+    fn test_timing_diagnosis_by_exit_code() {
+        let failed = |line: &str, exit_code| HttpTiming {
+            exit_code,
+            ..timing(line)
+        };
+        let dns = failed("__kaido_timing__ 0 0 0 0 0.004 0", 6);
+        assert!(dns.diagnosis().unwrap().starts_with("DNS lookup failed"));
+        assert!(dns.summary().ends_with("total 4ms · no response"));
+
+        let refused = failed("__kaido_timing__ 0.001 0 0 0 0.002 0", 7);
+        assert!(refused.diagnosis().unwrap().starts_with("Connect failed"));
+
+        let dropped = failed("__kaido_timing__ 0.001 0 0 0 5.0 0", 28);
+        assert!(dropped.diagnosis().unwrap().contains("while connecting"));
+        let slow = failed("__kaido_timing__ 0.001 0.002 0.010 0 5.0 0", 28);
+        assert!(slow.diagnosis().unwrap().contains("first byte"));
+
+        let tls = failed("__kaido_timing__ 0.001 0.002 0 0 0.05 0", 35);
+        assert!(tls.diagnosis().unwrap().starts_with("TLS handshake failed"));
+        let cert = failed("__kaido_timing__ 0.001 0.002 0 0 0.05 0", 60);
+        assert!(cert.diagnosis().unwrap().contains("certificate"));
+    }
+
+    #[test]
+    fn test_curl_commands_get_timing() {
+        assert_eq!(
+            curl_url("curl -sI https://example.com/health"),
+            Some("https://example.com/health".to_string())
+        );
+        assert_eq!(curl_url("curl localhost:8080"), None);
+        assert_eq!(curl_url("curl https://x.io | jq ."), None);
+        assert_eq!(curl_url("wget https://x.io"), None);
+
+        assert_eq!(
+            plain_get_url("curl -sS https://x.io/health"),
+            Some("https://x.io/health".to_string())
+        );
+        for shaped in [
+            "curl -X POST https://x.io/api",
+            "curl -H 'Authorization: Bearer t' https://x.io",
+            "curl --data-raw '{}' https://x.io",
+            "curl -u admin:secret https://x.io",
+            "curl -k https://x.io",
+        ] {
+            assert_eq!(plain_get_url(shaped), None, "{shaped}");
+        }
+
+        let timed = with_timing("curl -s https://x.io").unwrap();
+        assert!(timed.starts_with("curl -s https://x.io -w '\\n__kaido_timing__ "));
+        assert_eq!(with_timing("curl -w '%{http_code}' https://x.io"), None);
+
+        let (body, timing) = split_timing("ok\n\n__kaido_timing__ 0.1 0.2 0 0.3 0.4 200\n");
+        assert_eq!(body, "ok\n");
+        assert_eq!(timing.unwrap().status, 200);
+        let (body, timing) = split_timing("no marker\n");
+        assert_eq!((body.as_str(), timing), ("no marker\n", None));
+    }
+
+    #[test]
+    fn test_explain_curl_errors() {
+        let tool = NetworkTool::new();
+        let explain = |error: &str| tool.explain_error(error).unwrap().error_type;
+        assert_eq!(
+            explain("curl: (6) Could not resolve host: api.example.internal"),
+            "DNS Lookup Failed"
+        );
+        assert_eq!(
+            explain("curl: (60) SSL certificate problem: certificate has expired"),
+            "TLS Handshake Failed"
+        );
+        assert_eq!(
+            explain("curl: (28) Connection timed out after 5001 milliseconds"),
+            "Request Timed Out"
+        );
+        assert_eq!(
+            explain("curl: (22) The requested URL returned error: 502"),
+            "HTTP Server Error"
+        );
+        assert_eq!(
+            tool.detect_intent("why is the api response time so high"),
+            0.9
+        );
+    }
 }
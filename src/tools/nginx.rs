@@ -1,12 +1,28 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use std::time::Instant;
+use std::path::{Path, PathBuf};
 
+use super::config_snapshot::{default_snapshot_dir, ConfigSnapshot};
+use super::error_log::{config_files, directive_paths, tail_first, ErrorLogTail};
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolContext,
-    Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolCapabilities,
+    ToolContext, Translation,
 };
 
+/// Where nginx keeps its config on Linux and Homebrew installs
+const NGINX_CONFIG_DIRS: &[&str] = &[
+    "/etc/nginx",
+    "/usr/local/etc/nginx",
+    "/opt/homebrew/etc/nginx",
+];
+
+/// Where nginx logs errors unless told otherwise
+const NGINX_ERROR_LOGS: &[&str] = &[
+    "/var/log/nginx/error.log",
+    "/usr/local/var/log/nginx/error.log",
+    "/opt/homebrew/var/log/nginx/error.log",
+];
+
 /// Nginx web server tool
 pub struct NginxTool;
 
@@ -39,6 +55,26 @@ impl NginxTool {
         ))
     }
 
+    /// Snapshot store for the nginx config (~/.kaido/snapshots/nginx/)
+    pub fn config_snapshot() -> Option<ConfigSnapshot> {
+        let config_root = NGINX_CONFIG_DIRS
+            .iter()
+            .map(Path::new)
+            .find(|p| p.is_dir())?;
+        Some(ConfigSnapshot::new(
+            config_root,
+            default_snapshot_dir("nginx")?,
+        ))
+    }
+
+    /// Unified diff of the current config against the snapshot taken on the
+    /// last successful reload
+    pub fn config_diff() -> Result<String> {
+        Self::config_snapshot()
+            .ok_or_else(|| anyhow::anyhow!("Could not find nginx config directory"))?
+            .diff()
+    }
+
     /// Get nginx version
     pub async fn get_version() -> Result<String> {
         let output = tokio::process::Command::new("nginx")
@@ -102,28 +138,37 @@ impl NginxTool {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    /// Read nginx error log (last N lines)
-    pub async fn read_error_log(lines: usize) -> Result<String> {
-        let log_paths = vec![
-            "/var/log/nginx/error.log",
-            "/usr/local/var/log/nginx/error.log",
-            "/opt/homebrew/var/log/nginx/error.log",
-        ];
-
-        for path in log_paths {
-            let output = tokio::process::Command::new("tail")
-                .args(["-n", &lines.to_string(), path])
-                .output()
-                .await;
-
-            if let Ok(out) = output {
-                if out.status.success() {
-                    return Ok(String::from_utf8_lossy(&out.stdout).to_string());
-                }
+    /// Error logs to try, custom `error_log` directives before the defaults
+    pub fn error_log_paths() -> Vec<PathBuf> {
+        let configured = NGINX_CONFIG_DIRS
+            .iter()
+            .map(Path::new)
+            .find(|p| p.is_dir())
+            .map(|root| {
+                config_files(root, &["nginx.conf"], &["conf.d", "sites-enabled"])
+                    .iter()
+                    .filter_map(|file| std::fs::read_to_string(file).ok())
+                    .flat_map(|config| directive_paths(&config, "error_log"))
+                    // Skips stderr, syslog:..., and paths relative to the build prefix
+                    .filter(|path| path.starts_with('/') && path != "/dev/null")
+                    .map(PathBuf::from)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut paths = configured;
+        for default in NGINX_ERROR_LOGS.iter().map(PathBuf::from) {
+            if !paths.contains(&default) {
+                paths.push(default);
             }
         }
+        paths
+    }
 
-        Err(anyhow::anyhow!("Could not find nginx error log"))
+    /// Last `lines` lines of the nginx error log
+    pub fn tail_error_log(lines: usize) -> Result<ErrorLogTail> {
+        tail_first(&Self::error_log_paths(), lines)
+            .map_err(|_| anyhow::anyhow!("Could not find nginx error log"))
     }
 }
 
@@ -198,7 +243,7 @@ impl Tool for NginxTool {
         }
 
         let parsed: NginxResponse =
-            serde_json::from_str(&llm_response.reasoning).unwrap_or(NginxResponse {
+            crate::ai::parse_json(&llm_response.reasoning).unwrap_or(NginxResponse {
                 command: llm_response.command.clone(),
                 confidence: llm_response.confidence,
                 reasoning: llm_response.reasoning.clone(),
@@ -257,23 +302,25 @@ impl Tool for NginxTool {
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
-        let start = Instant::now();
-
+        super::ensure_owned(self, command)?;
         // Execute command via shell
-        let output = tokio::process::Command::new("sh")
-            .arg("-c")
-            .arg(command)
-            .output()
-            .await?;
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c").arg(command);
+        Ok(ExecutionResult::capture(&mut shell).await?)
+    }
 
-        let duration = start.elapsed();
+    fn version_args(&self) -> &'static [&'static str] {
+        &["-v"]
+    }
 
-        Ok(ExecutionResult {
-            exit_code: output.status.code().unwrap_or(-1),
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            duration,
-        })
+    fn runs_in_shell(&self) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default()
+            .with_explain_error()
+            .with_dry_run()
     }
 
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
@@ -1,7 +1,12 @@
 use super::{
-    Apache2Tool, DockerTool, DrushTool, KubectlTool, NetworkTool, NginxTool, SQLDialect, SQLTool,
-    Tool,
+    command_line, Apache2Tool, Capability, DockerTool, DrushTool, KubectlTool, NetworkTool,
+    NginxTool, RiskLevel, SQLDialect, SQLTool, Tool, ToolContext,
 };
+use crate::error::PatternMatcher;
+use std::sync::OnceLock;
+
+/// Lowest `detect_intent` score that routes input to a tool
+pub const MIN_INTENT_CONFIDENCE: f32 = 0.5;
 
 /// Tool registry for managing and detecting tools
 pub struct ToolRegistry {
@@ -32,6 +37,41 @@ impl ToolRegistry {
         self.tools.push(tool);
     }
 
+    /// Tool whose own programs `command` runs, from the command itself
+    ///
+    /// None means it needs the plain shell (other programs, or a pipeline
+    /// through a tool that doesn't use one).
+    pub fn tool_for_command(&self, command: &str) -> Option<&dyn Tool> {
+        self.tools
+            .iter()
+            .map(|tool| tool.as_ref())
+            .find(|tool| tool.owns_command(command))
+    }
+
+    /// Tool that runs `command` and the risk of running it
+    ///
+    /// The tool comes from `tool_for_command`, or is "shell" when none owns
+    /// the whole line. Then each part of a chain is rated by the tool that
+    /// owns it, or by `PatternMatcher::shell_risk` when none does, and the
+    /// highest rating wins.
+    pub fn classify(&self, command: &str, context: &ToolContext) -> (&'static str, RiskLevel) {
+        if let Some(tool) = self.tool_for_command(command) {
+            return (tool.name(), tool.classify_risk(command, context));
+        }
+
+        static SHELL_PATTERNS: OnceLock<PatternMatcher> = OnceLock::new();
+        let patterns = SHELL_PATTERNS.get_or_init(PatternMatcher::new);
+        let risk = command_line::segments(command)
+            .iter()
+            .map(|segment| match self.tool_for_command(segment.text) {
+                Some(tool) => tool.classify_risk(segment.text, context),
+                None => patterns.shell_risk(segment.text),
+            })
+            .max()
+            .unwrap_or(RiskLevel::Low);
+        ("shell", risk)
+    }
+
     /// Automatically detect which tool to use based on input
     /// Returns the tool with highest confidence score (>= 0.5)
     pub fn detect_tool(&self, input: &str) -> Option<&dyn Tool> {
@@ -39,7 +79,7 @@ impl ToolRegistry {
 
         for tool in &self.tools {
             let score = tool.detect_intent(input);
-            if score >= 0.5 {
+            if score >= MIN_INTENT_CONFIDENCE {
                 // At least 50% confidence
                 if let Some((_, best_score)) = best_match {
                     if score > best_score {
@@ -54,6 +94,54 @@ impl ToolRegistry {
         best_match.map(|(tool, _)| tool)
     }
 
+    /// Every tool's `detect_intent` score for `input`, highest first
+    ///
+    /// Ties keep registration order, so the first entry is the tool
+    /// `detect_tool` picks when its score reaches `MIN_INTENT_CONFIDENCE`.
+    pub fn intent_scores(&self, input: &str) -> Vec<(&str, f32)> {
+        let mut scores: Vec<(&str, f32)> = self
+            .tools
+            .iter()
+            .map(|tool| (tool.name(), tool.detect_intent(input)))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores
+    }
+
+    /// Check that each `(input, tool)` pair routes to the expected tool
+    ///
+    /// `tool` is a tool name, or `"none"` for input no tool should claim.
+    /// All mismatches are reported together, each with its top scores, so a
+    /// corpus of phrases shows every regression in one run.
+    pub fn expect_routes(&self, cases: &[(&str, &str)]) -> anyhow::Result<()> {
+        let mismatches: Vec<String> = cases
+            .iter()
+            .filter_map(|(input, expected)| {
+                let routed = self.detect_tool(input).map(|t| t.name()).unwrap_or("none");
+                (routed != *expected).then(|| {
+                    let scores = self
+                        .intent_scores(input)
+                        .into_iter()
+                        .take(3)
+                        .map(|(name, score)| format!("{name} {score:.2}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!("  '{input}' → {routed}, expected {expected} ({scores})")
+                })
+            })
+            .collect();
+
+        if !mismatches.is_empty() {
+            anyhow::bail!(
+                "{} of {} inputs misrouted:\n{}",
+                mismatches.len(),
+                cases.len(),
+                mismatches.join("\n")
+            );
+        }
+        Ok(())
+    }
+
     /// Get tool by name
     pub fn get_tool(&self, name: &str) -> Option<&dyn Tool> {
         self.tools
@@ -66,6 +154,15 @@ impl ToolRegistry {
     pub fn list_tools(&self) -> Vec<&str> {
         self.tools.iter().map(|t| t.name()).collect()
     }
+
+    /// Tools offering a capability, in registration order
+    pub fn tools_supporting(&self, capability: Capability) -> Vec<&dyn Tool> {
+        self.tools
+            .iter()
+            .filter(|t| t.supports(capability))
+            .map(|t| t.as_ref())
+            .collect()
+    }
 }
 
 impl Default for ToolRegistry {
@@ -89,6 +186,30 @@ mod tests {
         assert!(tools.contains(&"drush"));
     }
 
+    #[test]
+    fn test_classify_by_the_programs_that_run() {
+        let registry = ToolRegistry::new();
+        let context = ToolContext::default();
+
+        let (tool, risk) = registry.classify("docker rm -f web", &context);
+        assert_eq!(tool, "docker");
+        assert!(risk.requires_confirmation());
+
+        // No tool owns these, however they read
+        assert_eq!(
+            registry.classify("rm -rf ~/docker-data", &context),
+            ("shell", RiskLevel::High)
+        );
+        assert_eq!(
+            registry.classify("make deploy", &context),
+            ("shell", RiskLevel::Medium)
+        );
+
+        // A chain is as risky as its riskiest part
+        let (tool, risk) = registry.classify("docker ps && rm -rf /srv/app", &context);
+        assert_eq!((tool, risk), ("shell", RiskLevel::High));
+    }
+
     #[test]
     fn test_get_tool_by_name() {
         let registry = ToolRegistry::new();
@@ -100,4 +221,56 @@ mod tests {
         let nonexistent = registry.get_tool("nonexistent");
         assert!(nonexistent.is_none());
     }
+
+    #[test]
+    fn test_tools_supporting_dry_run() {
+        let registry = ToolRegistry::new();
+        let names: Vec<_> = registry
+            .tools_supporting(Capability::DryRun)
+            .iter()
+            .map(|t| t.name())
+            .collect();
+
+        assert!(names.contains(&"kubectl"));
+        assert!(names.contains(&"nginx"));
+        assert!(!names.contains(&"docker"));
+        assert_eq!(
+            registry.tools_supporting(Capability::Translate).len(),
+            registry.list_tools().len()
+        );
+    }
+
+    #[test]
+    fn test_intent_scores_match_detect_tool() {
+        let registry = ToolRegistry::new();
+        let input = "kubectl get pods -n prod";
+
+        let scores = registry.intent_scores(input);
+        assert_eq!(scores.len(), registry.list_tools().len());
+        assert!(scores.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(
+            Some(scores[0].0),
+            registry.detect_tool(input).map(|tool| tool.name())
+        );
+    }
+
+    #[test]
+    fn test_expect_routes_reports_every_mismatch() {
+        let registry = ToolRegistry::new();
+        assert!(registry
+            .expect_routes(&[("docker ps", "docker"), ("ls -la", "none")])
+            .is_ok());
+
+        let err = registry
+            .expect_routes(&[
+                ("docker ps", "kubectl"),
+                ("nginx -t", "nginx"),
+                ("drush cr", "none"),
+            ])
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("2 of 3 inputs misrouted"));
+        assert!(err.contains("'docker ps' → docker, expected kubectl (docker 1.00"));
+        assert!(err.contains("'drush cr' → drush, expected none"));
+    }
 }
@@ -1,6 +1,6 @@
 use super::{
-    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolContext,
-    Translation,
+    ErrorExplanation, ExecutionResult, LLMBackend, RiskLevel, Solution, Tool, ToolCapabilities,
+    ToolContext, Translation,
 };
 use anyhow::Result;
 use async_trait::async_trait;
@@ -45,6 +45,53 @@ impl SQLTool {
     pub fn dialect(&self) -> &SQLDialect {
         &self.dialect
     }
+
+    /// Whether a command wipes a whole database or table
+    ///
+    /// These are never run on an automated caller's behalf (MCP), whatever
+    /// the detected risk, since a DSN in the command can point anywhere.
+    pub fn is_never_automated(command: &str) -> bool {
+        let cmd = command.to_lowercase();
+        cmd.contains("drop database") || cmd.contains("drop schema") || cmd.contains("truncate")
+    }
+
+    /// Risk of a command regardless of which database it runs against
+    fn base_risk(cmd: &str) -> RiskLevel {
+        // CRITICAL: DROP DATABASE, DELETE FROM without WHERE
+        if cmd.contains("drop database") || cmd.contains("drop schema") {
+            return RiskLevel::Critical;
+        }
+
+        if cmd.contains("delete from") && !cmd.contains("where") {
+            return RiskLevel::Critical;
+        }
+
+        if cmd.contains("truncate") && !cmd.contains("where") {
+            return RiskLevel::Critical;
+        }
+
+        // HIGH: DROP TABLE, TRUNCATE with WHERE
+        if cmd.contains("drop table") {
+            return RiskLevel::High;
+        }
+
+        if cmd.contains("truncate") && cmd.contains("where") {
+            return RiskLevel::High;
+        }
+
+        // MEDIUM: INSERT, UPDATE, DELETE (with WHERE), ALTER
+        if cmd.contains("insert")
+            || cmd.contains("update")
+            || (cmd.contains("delete") && cmd.contains("where"))
+            || cmd.contains("alter")
+            || cmd.contains("create")
+        {
+            return RiskLevel::Medium;
+        }
+
+        // LOW: SELECT, SHOW, DESCRIBE
+        RiskLevel::Low
+    }
 }
 
 #[async_trait]
@@ -145,44 +192,18 @@ Output JSON format:
             .map(|conn| conn.is_production)
             .unwrap_or(false);
 
-        if is_production {
-            log::warn!("Production database detected for SQL command");
+        let risk = Self::base_risk(&cmd);
+        if !is_production {
+            return risk;
         }
 
-        // CRITICAL: DROP DATABASE, DELETE FROM without WHERE
-        if cmd.contains("drop database") || cmd.contains("drop schema") {
-            return RiskLevel::Critical;
+        log::warn!("Production database detected for SQL command");
+        // Any write to production needs typed confirmation, which
+        // `requires_typed_confirmation` grants from High up
+        match risk {
+            RiskLevel::Medium => RiskLevel::High,
+            risk => risk,
         }
-
-        if cmd.contains("delete from") && !cmd.contains("where") {
-            return RiskLevel::Critical;
-        }
-
-        if cmd.contains("truncate") && !cmd.contains("where") {
-            return RiskLevel::Critical;
-        }
-
-        // HIGH: DROP TABLE, TRUNCATE with WHERE
-        if cmd.contains("drop table") {
-            return RiskLevel::High;
-        }
-
-        if cmd.contains("truncate") && cmd.contains("where") {
-            return RiskLevel::High;
-        }
-
-        // MEDIUM: INSERT, UPDATE, DELETE (with WHERE), ALTER
-        if cmd.contains("insert")
-            || cmd.contains("update")
-            || (cmd.contains("delete") && cmd.contains("where"))
-            || cmd.contains("alter")
-            || cmd.contains("create")
-        {
-            return RiskLevel::Medium;
-        }
-
-        // LOW: SELECT, SHOW, DESCRIBE
-        RiskLevel::Low
     }
 
     async fn execute(&self, command: &str) -> Result<ExecutionResult> {
@@ -200,6 +221,14 @@ Output JSON format:
         ))
     }
 
+    fn binaries(&self) -> Vec<&'static str> {
+        vec![self.dialect.cli_command()]
+    }
+
+    fn capabilities(&self) -> ToolCapabilities {
+        ToolCapabilities::default().with_explain_error()
+    }
+
     fn explain_error(&self, error: &str) -> Option<ErrorExplanation> {
         // MySQL ERROR 1064: Syntax error
         if error.contains("ERROR 1064") {
@@ -264,6 +293,7 @@ Output JSON format:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tools::DatabaseConnection;
 
     #[test]
     fn test_sql_detection() {
@@ -299,4 +329,43 @@ mod tests {
             RiskLevel::Critical
         );
     }
+
+    #[test]
+    fn test_production_connection_raises_risk() {
+        let tool = SQLTool::new(SQLDialect::PostgreSQL);
+        let ctx = ToolContext {
+            db_connection: Some(DatabaseConnection {
+                host: "db.prod.internal".to_string(),
+                port: 5432,
+                database: "shop".to_string(),
+                username: "app".to_string(),
+                password: None,
+                dialect: SQLDialect::PostgreSQL,
+                is_production: true,
+            }),
+            ..ToolContext::default()
+        };
+
+        let risk = tool.classify_risk("DELETE FROM orders", &ctx);
+        assert_eq!(risk, RiskLevel::Critical);
+        assert!(risk.requires_typed_confirmation(true));
+
+        let risk = tool.classify_risk("UPDATE orders SET status = 'paid' WHERE id = 7", &ctx);
+        assert_eq!(risk, RiskLevel::High);
+        assert!(risk.requires_typed_confirmation(true));
+
+        assert_eq!(
+            tool.classify_risk("SELECT * FROM orders", &ctx),
+            RiskLevel::Low
+        );
+    }
+
+    #[test]
+    fn test_never_automated() {
+        assert!(SQLTool::is_never_automated("DROP DATABASE shop"));
+        assert!(SQLTool::is_never_automated(
+            "psql -c 'TRUNCATE orders' shop"
+        ));
+        assert!(!SQLTool::is_never_automated("DROP TABLE tmp_import"));
+    }
 }
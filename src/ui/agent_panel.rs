@@ -0,0 +1,423 @@
+// Live panel for agent sessions
+//
+// Agent steps arrive through `AgentLoop::with_progress_callback`. Instead of
+// printing each one as a block, `LivePanel` keeps them in an inline ratatui
+// viewport that is redrawn as steps stream in, with a spinner on the command
+// being run (or on the LLM call being waited for). A ticker task redraws the
+// panel so spinners and timers move between steps.
+
+use std::io::{self, Stdout};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame, Terminal, TerminalOptions, Viewport,
+};
+
+use crate::agent::{AgentStep, StepType};
+use crate::ui::spinner::get_spinner_frame;
+
+/// Rows taken by the live panel, borders included
+const PANEL_HEIGHT: u16 = 14;
+
+/// How often spinners and timers are redrawn
+const TICK: Duration = Duration::from_millis(100);
+
+/// One row of the panel
+#[derive(Debug, Clone)]
+struct PanelEntry {
+    step_number: usize,
+    step_type: StepType,
+    text: String,
+    tool: Option<String>,
+    explanation: Option<String>,
+    started: Instant,
+    /// None while an action runs, then whether it succeeded
+    finished: Option<(bool, Duration)>,
+}
+
+/// Agent steps as shown in the live panel
+#[derive(Debug)]
+pub struct AgentPanel {
+    problem: String,
+    entries: Vec<PanelEntry>,
+    tick: usize,
+    done: bool,
+}
+
+impl AgentPanel {
+    pub fn new(problem: impl Into<String>) -> Self {
+        Self {
+            problem: problem.into(),
+            entries: Vec::new(),
+            tick: 0,
+            done: false,
+        }
+    }
+
+    /// Add a step, or update it when the agent re-notifies the same step
+    ///
+    /// An observation or interruption finishes the action that is running.
+    pub fn push(&mut self, step: &AgentStep) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.step_number == step.step_number && e.step_type == step.step_type)
+        {
+            entry.explanation = step.explanation.as_deref().map(first_line);
+            return;
+        }
+
+        if matches!(
+            step.step_type,
+            StepType::Observation | StepType::Interrupted
+        ) {
+            if let Some(action) = self.running_action_mut() {
+                let elapsed = step
+                    .duration_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or_else(|| action.started.elapsed());
+                action.finished = Some((step.success.unwrap_or(false), elapsed));
+            }
+        }
+
+        self.entries.push(PanelEntry {
+            step_number: step.step_number,
+            step_type: step.step_type.clone(),
+            text: first_line(&step.content),
+            tool: step.tool_used.clone(),
+            explanation: step.explanation.as_deref().map(first_line),
+            started: Instant::now(),
+            finished: None,
+        });
+    }
+
+    /// Advance the spinners by one frame
+    pub fn tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+    }
+
+    /// Stop the spinners; the panel stays on screen as a record
+    pub fn finish(&mut self) {
+        self.done = true;
+    }
+
+    /// Whether a command is running right now
+    pub fn is_running(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|e| e.step_type == StepType::Action && e.finished.is_none())
+    }
+
+    fn running_action_mut(&mut self) -> Option<&mut PanelEntry> {
+        self.entries
+            .iter_mut()
+            .rev()
+            .find(|e| e.step_type == StepType::Action && e.finished.is_none())
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let rows = area.height.saturating_sub(2) as usize;
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title(Span::styled(
+                format!(" kaido · {} ", first_line(&self.problem)),
+                Style::default().fg(Color::LightBlue),
+            ));
+        frame.render_widget(Paragraph::new(self.lines(rows)).block(block), area);
+    }
+
+    /// The last `rows` lines of the panel
+    fn lines(&self, rows: usize) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line> = self
+            .entries
+            .iter()
+            .flat_map(|e| self.entry_lines(e))
+            .collect();
+
+        // Between steps the agent is waiting on the LLM
+        if !self.done && !self.is_running() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("{} ", self.spinner()),
+                    Style::default().fg(Color::LightBlue),
+                ),
+                Span::styled("thinking…", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+
+        let skip = lines.len().saturating_sub(rows);
+        lines.split_off(skip)
+    }
+
+    fn entry_lines(&self, entry: &PanelEntry) -> Vec<Line<'static>> {
+        let dim = Style::default().fg(Color::DarkGray);
+        let label = |text: &str, color: Color| {
+            Span::styled(
+                format!("{text:<11}"),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )
+        };
+
+        let mut lines = Vec::new();
+        match entry.step_type {
+            StepType::Thought => lines.push(Line::from(vec![
+                Span::styled("◆ ", Style::default().fg(Color::Blue)),
+                label("thought", Color::Blue),
+                Span::raw(entry.text.clone()),
+            ])),
+            StepType::Action => {
+                let (marker, color, elapsed) = match entry.finished {
+                    None => (self.spinner(), Color::Yellow, entry.started.elapsed()),
+                    Some((true, elapsed)) => ("✓", Color::Green, elapsed),
+                    Some((false, elapsed)) => ("✗", Color::Red, elapsed),
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{marker} "), Style::default().fg(color)),
+                    label("action", Color::Yellow),
+                ];
+                if let Some(tool) = &entry.tool {
+                    spans.push(Span::styled(
+                        format!("[{tool}] "),
+                        Style::default().fg(Color::LightBlue),
+                    ));
+                }
+                spans.push(Span::raw(entry.text.clone()));
+                spans.push(Span::styled(
+                    format!("  {:.1}s", elapsed.as_secs_f64()),
+                    dim,
+                ));
+                lines.push(Line::from(spans));
+                if let Some(explanation) = &entry.explanation {
+                    lines.push(Line::from(Span::styled(
+                        format!("  ↳ {explanation}"),
+                        Style::default().fg(Color::Green),
+                    )));
+                }
+            }
+            StepType::Observation => lines.push(Line::from(vec![
+                Span::styled("  └ ", dim),
+                Span::styled(entry.text.clone(), dim),
+            ])),
+            StepType::Reflection => lines.push(Line::from(vec![
+                Span::styled("↻ ", Style::default().fg(Color::Magenta)),
+                label("reflection", Color::Magenta),
+                Span::raw(entry.text.clone()),
+            ])),
+            StepType::Solution => lines.push(Line::from(vec![
+                Span::styled("★ ", Style::default().fg(Color::LightBlue)),
+                label("solution", Color::LightBlue),
+                Span::raw(entry.text.clone()),
+            ])),
+            StepType::Skipped => lines.push(Line::from(Span::styled(
+                format!("↷ skipped    {}", entry.text),
+                dim,
+            ))),
+            StepType::Interrupted => lines.push(Line::from(Span::styled(
+                format!("✗ interrupted {}", entry.text),
+                Style::default().fg(Color::Red),
+            ))),
+            StepType::Question => lines.push(Line::from(vec![
+                Span::styled("? ", Style::default().fg(Color::LightBlue)),
+                label("question", Color::LightBlue),
+                Span::raw(entry.text.clone()),
+            ])),
+            StepType::Answer => lines.push(Line::from(vec![
+                Span::styled("  └ ", dim),
+                label("you", Color::Green),
+                Span::raw(entry.text.clone()),
+            ])),
+        }
+        lines
+    }
+
+    fn spinner(&self) -> &'static str {
+        get_spinner_frame(self.tick)
+    }
+}
+
+/// First non-empty line of `text`, trimmed
+fn first_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+struct LiveState {
+    panel: AgentPanel,
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl LiveState {
+    fn draw(&mut self) {
+        let panel = &self.panel;
+        if let Err(e) = self
+            .terminal
+            .draw(|frame| panel.render(frame, frame.size()))
+        {
+            log::debug!("Agent panel redraw failed: {e}");
+        }
+    }
+}
+
+/// An [`AgentPanel`] drawn inline below the prompt while the agent runs
+pub struct LivePanel {
+    state: Arc<Mutex<LiveState>>,
+    ticker: tokio::task::JoinHandle<()>,
+}
+
+impl LivePanel {
+    /// Open the panel under the cursor and start the spinner ticker
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn start(problem: &str) -> io::Result<Self> {
+        let terminal = Terminal::with_options(
+            CrosstermBackend::new(io::stdout()),
+            TerminalOptions {
+                viewport: Viewport::Inline(PANEL_HEIGHT),
+            },
+        )?;
+        let state = Arc::new(Mutex::new(LiveState {
+            panel: AgentPanel::new(problem),
+            terminal,
+        }));
+        state.lock().unwrap_or_else(|e| e.into_inner()).draw();
+
+        let ticking = Arc::clone(&state);
+        let ticker = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK);
+            loop {
+                interval.tick().await;
+                let mut state = ticking.lock().unwrap_or_else(|e| e.into_inner());
+                state.panel.tick();
+                state.draw();
+            }
+        });
+
+        Ok(Self { state, ticker })
+    }
+
+    /// Progress callback that feeds steps into the panel
+    pub fn callback(&self) -> impl Fn(&AgentStep) + Send + 'static {
+        let state = Arc::clone(&self.state);
+        move |step| {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            state.panel.push(step);
+            state.draw();
+        }
+    }
+
+    /// Stop redrawing and move the cursor below the panel
+    pub fn finish(self) {
+        self.ticker.abort();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.panel.finish();
+        state.draw();
+        let bottom = state.terminal.get_frame().size().bottom();
+        let _ = state.terminal.set_cursor(0, bottom.saturating_sub(1));
+        let _ = state.terminal.show_cursor();
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn step(number: usize, step_type: StepType, content: &str) -> AgentStep {
+        AgentStep {
+            step_number: number,
+            step_type,
+            content: content.to_string(),
+            tool_used: None,
+            success: None,
+            timestamp: std::time::SystemTime::now(),
+            explanation: None,
+            duration_ms: None,
+            tokens: None,
+        }
+    }
+
+    fn rendered(panel: &AgentPanel, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(60, height)).unwrap();
+        terminal.draw(|f| panel.render(f, f.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| {
+                (0..60)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_action_spins_until_observed() {
+        let mut panel = AgentPanel::new("nginx returns 502");
+        panel.push(&step(
+            1,
+            StepType::Thought,
+            "Check the upstream\nACTION: ...",
+        ));
+        let mut action = step(2, StepType::Action, "systemctl status php-fpm");
+        action.tool_used = Some("shell".to_string());
+        panel.push(&action);
+        assert!(panel.is_running());
+
+        let screen = rendered(&panel, 8);
+        assert!(screen[0].contains("kaido · nginx returns 502"));
+        assert!(screen[1].contains("thought") && screen[1].contains("Check the upstream"));
+        assert!(screen[2].starts_with("│⠋ action"), "{screen:?}");
+        assert!(screen[2].contains("[shell] systemctl status php-fpm"));
+        assert!(!screen.iter().any(|l| l.contains("thinking")));
+
+        let mut observation = step(3, StepType::Observation, "inactive (dead)");
+        observation.success = Some(false);
+        observation.duration_ms = Some(1500);
+        panel.push(&observation);
+        assert!(!panel.is_running());
+
+        let screen = rendered(&panel, 8);
+        assert!(screen[2].starts_with("│✗ action") && screen[2].contains("1.5s"));
+        assert!(screen[3].contains("└ inactive (dead)"));
+        // Waiting on the LLM for the next thought
+        assert!(screen[4].contains("thinking…"));
+    }
+
+    #[test]
+    fn test_renotified_step_updates_in_place() {
+        let mut panel = AgentPanel::new("disk full");
+        let mut action = step(1, StepType::Action, "df -h");
+        panel.push(&action);
+        action.explanation = Some("Shows free space per filesystem".to_string());
+        panel.push(&action);
+
+        assert_eq!(panel.entries.len(), 1);
+        let screen = rendered(&panel, 6);
+        assert!(screen[2].contains("↳ Shows free space per filesystem"));
+    }
+
+    #[test]
+    fn test_keeps_the_latest_steps_in_view() {
+        let mut panel = AgentPanel::new("slow site");
+        for n in 1..=10 {
+            panel.push(&step(n, StepType::Reflection, &format!("note {n}")));
+        }
+        panel.finish();
+
+        let screen = rendered(&panel, 6);
+        assert!(screen[1].contains("note 7"), "{screen:?}");
+        assert!(screen[4].contains("note 10"));
+    }
+}
@@ -370,7 +370,7 @@ impl ConfirmationModal {
 /// - "kubectl drain node-01" → "node-01"
 ///
 /// For production environment, fallback to "production" if resource name not found
-fn extract_resource_name(command: &str, environment: &EnvironmentType) -> String {
+pub fn extract_resource_name(command: &str, environment: &EnvironmentType) -> String {
     let parts: Vec<&str> = command.split_whitespace().collect();
 
     // Try to find resource name after verb
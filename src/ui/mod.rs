@@ -1,4 +1,6 @@
+pub mod agent_panel;
 pub mod confirmation;
+pub mod spinner;
 
-// Note: app, layout, modal, progress, reasoning, spinner modules removed
-// kubectl MVP only needs confirmation modal for risk-based safety controls
+// Note: app, layout, modal, progress and reasoning modules removed
+// kubectl MVP only needs the confirmation modal, plus the live agent panel
@@ -1,4 +1,4 @@
-use std::fmt;
+pub mod sqlite;
 
 pub type KaidoResult<T> = Result<T, KaidoError>;
 
@@ -63,36 +63,98 @@ impl PerformanceMetrics {
 */
 
 /// Kaido error types
-#[derive(Debug)]
+///
+/// Variants keep the error that caused them as their `source()`, so
+/// callers can match on the kind and still walk the chain down to the
+/// io/serde/reqwest error underneath.
+#[derive(Debug, thiserror::Error)]
 pub enum KaidoError {
+    #[error("Application error: {message}{}", context.as_ref().map(|c| format!(" ({c})")).unwrap_or_default())]
     ApplicationError {
         message: String,
         context: Option<String>,
     },
+    #[error("Model '{model_name}' error: {message}")]
     ModelError {
         message: String,
         model_name: String,
+        #[source]
+        source: Option<anyhow::Error>,
     },
+    /// Missing or invalid configuration
+    #[error("Configuration error: {message}")]
+    ConfigError {
+        message: String,
+        #[source]
+        source: Option<anyhow::Error>,
+    },
+    /// Reading or writing files, spawning processes
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    /// Talking to an AI provider or another HTTP service
+    #[error("Network error: {0}")]
+    NetworkError(#[from] reqwest::Error),
+    /// Malformed JSON, usually an AI reply
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// Anything else, with its full context chain
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
 }
 
-impl fmt::Display for KaidoError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            KaidoError::ApplicationError { message, context } => {
-                write!(f, "Application error: {message}")?;
-                if let Some(ctx) = context {
-                    write!(f, " ({ctx})")?;
-                }
-                Ok(())
-            }
-            KaidoError::ModelError {
-                message,
-                model_name,
-            } => {
-                write!(f, "Model '{model_name}' error: {message}")
-            }
+impl KaidoError {
+    /// Model error caused by `source`
+    pub fn model(model_name: &str, message: &str, source: anyhow::Error) -> Self {
+        Self::ModelError {
+            message: message.to_string(),
+            model_name: model_name.to_string(),
+            source: Some(source),
+        }
+    }
+
+    /// Configuration error, optionally caused by `source`
+    pub fn config(message: &str, source: Option<anyhow::Error>) -> Self {
+        Self::ConfigError {
+            message: message.to_string(),
+            source,
         }
     }
 }
 
-impl std::error::Error for KaidoError {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn test_source_chain() {
+        let io = std::fs::read("/nonexistent/kaido").unwrap_err();
+        let cause = anyhow::Error::new(io).context("Failed to read the model list");
+        let error = KaidoError::model("llama3", "inference failed", cause);
+        assert_eq!(error.to_string(), "Model 'llama3' error: inference failed");
+
+        let chain: Vec<String> = std::iter::successors(error.source(), |&e| e.source())
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(chain[0], "Failed to read the model list");
+        assert!(chain[1].contains("No such file"), "{chain:?}");
+
+        let json = serde_json::from_str::<u8>("{").unwrap_err();
+        assert!(matches!(KaidoError::from(json), KaidoError::JsonError(_)));
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        let error = KaidoError::from(io);
+        assert!(
+            matches!(&error, KaidoError::IoError(e) if e.kind() == std::io::ErrorKind::NotFound)
+        );
+
+        let application = KaidoError::ApplicationError {
+            message: "no shell".to_string(),
+            context: Some("startup".to_string()),
+        };
+        assert_eq!(
+            application.to_string(),
+            "Application error: no shell (startup)"
+        );
+        assert!(application.source().is_none());
+    }
+}
@@ -0,0 +1,211 @@
+// SQLite databases shared between kaido processes
+//
+// Several shells (tmux panes) and the MCP server write the same learning
+// and audit databases, and on NFS locks are slow. Connections use WAL and a
+// busy timeout, lock errors are retried with backoff (off the async
+// executor's worker), and a store that still can't write warns once and
+// stops writing rather than failing or stalling the shell.
+
+use anyhow::Result;
+use rusqlite::{Connection, ErrorCode, TransactionBehavior};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::runtime::RuntimeFlavor;
+
+/// How long SQLite itself waits on a lock before reporting it busy
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pauses between attempts after SQLite gave up on a lock
+const RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_millis(50),
+    Duration::from_millis(200),
+    Duration::from_millis(500),
+];
+
+/// Whether an error is SQLite reporting a busy or locked database
+pub fn is_locked(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<rusqlite::Error>(),
+        Some(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Run `op`, retrying with backoff while the database is locked
+///
+/// On a multi-threaded tokio runtime the waiting happens in
+/// `block_in_place`, so the worker's other tasks move elsewhere meanwhile.
+/// A current-thread runtime has nowhere to move them: there `op` runs once,
+/// with only SQLite's own busy timeout.
+pub fn retry_locked<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    match tokio::runtime::Handle::try_current().map(|handle| handle.runtime_flavor()) {
+        Ok(RuntimeFlavor::MultiThread) => tokio::task::block_in_place(|| with_backoff(op)),
+        Ok(_) => op(),
+        Err(_) => with_backoff(op),
+    }
+}
+
+fn with_backoff<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delays = RETRY_DELAYS.iter();
+    loop {
+        match op() {
+            Err(e) if is_locked(&e) => match delays.next() {
+                Some(delay) => {
+                    log::debug!("Database locked, retrying in {delay:?}");
+                    std::thread::sleep(*delay);
+                }
+                None => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Open a database in WAL mode with a busy timeout, then run `init` on it
+/// (schema setup); both are retried while the database is locked
+pub fn open(
+    path: impl AsRef<Path>,
+    init: impl Fn(&Connection) -> Result<()>,
+) -> Result<Connection> {
+    let path = path.as_ref();
+    retry_locked(|| {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch(
+            "PRAGMA journal_mode=WAL;
+             PRAGMA synchronous=NORMAL;",
+        )?;
+        init(&conn)?;
+        Ok(conn)
+    })
+}
+
+/// Run `op` in an immediate transaction, retried as a whole while locked,
+/// so a retry never repeats half of an earlier attempt
+pub fn write<T>(conn: &mut Connection, op: impl Fn(&Connection) -> Result<T>) -> Result<T> {
+    retry_locked(|| {
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let value = op(&tx)?;
+        tx.commit()?;
+        Ok(value)
+    })
+}
+
+/// Stops writing to a store after its first failed write
+///
+/// The failure is warned about once; later writes fail straight away
+/// instead of each waiting out the lock again.
+#[derive(Debug, Default)]
+pub struct WriteFailures {
+    disabled: AtomicBool,
+}
+
+impl WriteFailures {
+    /// [`write`] to the store, unless an earlier write to it failed
+    pub fn write<T>(
+        &self,
+        store: &str,
+        conn: &mut Connection,
+        op: impl Fn(&Connection) -> Result<T>,
+    ) -> Result<T> {
+        if self.disabled.load(Ordering::Relaxed) {
+            anyhow::bail!("{store} isn't written after an earlier failure");
+        }
+        let result = write(conn, op);
+        if let Err(e) = &result {
+            if !self.disabled.swap(true, Ordering::Relaxed) {
+                log::warn!("{store} can't be written ({e}); continuing without saving to it");
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_waits_out_a_held_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.db");
+        let init = |conn: &Connection| -> Result<()> {
+            conn.execute("CREATE TABLE IF NOT EXISTS t (v INTEGER)", [])?;
+            Ok(())
+        };
+        open(&path, init).unwrap();
+        let mut second = open(&path, init).unwrap();
+        second.busy_timeout(Duration::from_millis(10)).unwrap();
+
+        // Another process holds the write lock for a moment
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder_path = path.clone();
+        let holder = std::thread::spawn(move || {
+            let conn = Connection::open(holder_path).unwrap();
+            conn.execute_batch("BEGIN IMMEDIATE").unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(100));
+            conn.execute_batch("COMMIT").unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        write(&mut second, |conn| {
+            conn.execute("INSERT INTO t (v) VALUES (1)", [])?;
+            Ok(())
+        })
+        .unwrap();
+        holder.join().unwrap();
+
+        let count: i64 = second
+            .query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_store_stops_writing_after_a_failure() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let failures = WriteFailures::default();
+        let attempts = std::cell::Cell::new(0);
+        let op = |conn: &Connection| -> Result<()> {
+            attempts.set(attempts.get() + 1);
+            conn.execute("INSERT INTO missing (v) VALUES (1)", [])?;
+            Ok(())
+        };
+
+        assert!(failures.write("Test store", &mut conn, op).is_err());
+        assert!(failures.write("Test store", &mut conn, op).is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_retry_in_place_on_multi_thread_runtime() {
+        let mut attempts = 0;
+        let result: Result<()> = retry_locked(|| {
+            attempts += 1;
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            )
+            .into())
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, RETRY_DELAYS.len() + 1);
+    }
+
+    #[test]
+    fn test_retry_locked_gives_up() {
+        let mut attempts = 0;
+        let result: Result<()> = retry_locked(|| {
+            attempts += 1;
+            Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+                None,
+            )
+            .into())
+        });
+        assert!(is_locked(&result.unwrap_err()));
+        assert_eq!(attempts, RETRY_DELAYS.len() + 1);
+    }
+}
@@ -137,3 +137,29 @@ fn test_explicit_tool_command_priority() {
         println!("✓ Explicit: '{}' → {}", input, tool.name());
     }
 }
+
+#[test]
+fn test_routing_fixtures() {
+    let registry = ToolRegistry::new();
+
+    let cases: Vec<(&str, &str)> = include_str!("../fixtures/tool_routes.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (input, tool) = line
+                .rsplit_once(" => ")
+                .unwrap_or_else(|| panic!("Fixture line without ' => ': {line}"));
+            (input.trim(), tool.trim())
+        })
+        .collect();
+    assert!(
+        cases.len() >= 50,
+        "Routing corpus shrank to {}",
+        cases.len()
+    );
+
+    if let Err(e) = registry.expect_routes(&cases) {
+        panic!("{e}");
+    }
+}